@@ -1,7 +1,8 @@
 //! High-level types and functions related to CSS parsing
 use std::{
     num::ParseIntError,
-    fmt,
+    path::{Path, PathBuf},
+    fmt, fs,
 };
 pub use simplecss::Error as CssSyntaxError;
 use simplecss::Tokenizer;
@@ -9,10 +10,13 @@ use simplecss::Tokenizer;
 use crate::css_parser;
 pub use crate::css_parser::CssParsingError;
 use azul_css::{
-    Css, CssDeclaration, Stylesheet,
+    Css, CssDeclaration, Stylesheet, CssFontFace,
     DynamicCssProperty, DynamicCssPropertyDefault,
     CssPropertyType, CssRuleBlock, CssPath, CssPathSelector,
-    CssNthChildSelector, CssPathPseudoSelector, CssNthChildSelector::*,
+    CssNthChildSelector, CssPathPseudoSelector, CssNthChildSelector::*, CssPseudoElement,
+    CssAttributeSelector, CssAttributeMatcher,
+    CssMediaQuery, CssMediaType, CssMediaFeature,
+    CssVariable, UnresolvedCssProperty,
     NodeTypePath, NodeTypePathParseError,
 };
 
@@ -36,10 +40,20 @@ pub enum CssParseErrorInner<'a> {
     DynamicCssParseError(DynamicCssParseError<'a>),
     /// Error while parsing a pseudo selector (like `:aldkfja`)
     PseudoSelectorParseError(CssPseudoSelectorParseError<'a>),
+    /// Error while parsing an attribute selector (like `[data-state=]`)
+    AttributeSelectorParseError(CssAttributeSelectorParseError<'a>),
+    /// Error while parsing the query of an `@media` block (like `@media (min-width: wide)`)
+    MediaQueryParseError(CssMediaQueryParseError<'a>),
+    /// Error while parsing the body of an `@font-face` block (like `@font-face { src: url(a.ttf); }`)
+    FontFaceParseError(CssFontFaceParseError<'a>),
     /// The path has to be either `*`, `div`, `p` or something like that
     NodeTypePath(NodeTypePathParseError<'a>),
     /// A certain property has an unknown key, for example: `alsdfkj: 500px` = `unknown CSS key "alsdfkj: 500px"`
     UnknownPropertyKey(&'a str, &'a str),
+    /// An `@import` statement without a file path, like `@import screen;`
+    InvalidImport(&'a str),
+    /// A file that was `@import`ed could not be loaded or parsed
+    ImportError(CssImportError),
 }
 
 impl_display!{ CssParseErrorInner<'a>, {
@@ -48,8 +62,13 @@ impl_display!{ CssParseErrorInner<'a>, {
     MalformedCss => "Malformed Css",
     DynamicCssParseError(e) => format!("Error parsing dynamic CSS property: {}", e),
     PseudoSelectorParseError(e) => format!("Failed to parse pseudo-selector: {}", e),
+    AttributeSelectorParseError(e) => format!("Failed to parse attribute selector: {}", e),
+    MediaQueryParseError(e) => format!("Failed to parse @media query: {}", e),
+    FontFaceParseError(e) => format!("Failed to parse @font-face rule: {}", e),
     NodeTypePath(e) => format!("Failed to parse CSS selector path: {}", e),
     UnknownPropertyKey(k, v) => format!("Unknown CSS key: \"{}: {}\"", k, v),
+    InvalidImport(e) => format!("Invalid @import statement: \"{}\"", e),
+    ImportError(e) => format!("{}", e),
 }}
 
 impl<'a> From<CssSyntaxError> for CssParseErrorInner<'a> {
@@ -61,6 +80,26 @@ impl<'a> From<CssSyntaxError> for CssParseErrorInner<'a> {
 impl_from! { DynamicCssParseError<'a>, CssParseErrorInner::DynamicCssParseError }
 impl_from! { NodeTypePathParseError<'a>, CssParseErrorInner::NodeTypePath }
 impl_from! { CssPseudoSelectorParseError<'a>, CssParseErrorInner::PseudoSelectorParseError }
+impl_from! { CssAttributeSelectorParseError<'a>, CssParseErrorInner::AttributeSelectorParseError }
+impl_from! { CssMediaQueryParseError<'a>, CssParseErrorInner::MediaQueryParseError }
+
+/// Error while loading a file from an `@import` statement. Since the imported files are
+/// loaded and dropped during parsing, errors in them are stored as formatted strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CssImportError {
+    /// The file could not be read
+    Io { path: PathBuf, error: String },
+    /// The file contains invalid CSS (the error includes the line / column in the imported file)
+    InvalidCss { path: PathBuf, error: String },
+    /// The file imports itself, either directly or through other files
+    CyclicImport(PathBuf),
+}
+
+impl_display!{ CssImportError, {
+    Io { path, error } => format!("Could not load imported file \"{}\": {}", path.display(), error),
+    InvalidCss { path, error } => format!("Error in imported file \"{}\": {}", path.display(), error),
+    CyclicImport(path) => format!("File \"{}\" imports itself", path.display()),
+}}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CssPseudoSelectorParseError<'a> {
@@ -104,6 +143,9 @@ fn pseudo_selector_from_str<'a>(selector: &'a str, value: Option<&'a str>)
         "hover" => Ok(CssPathPseudoSelector::Hover),
         "active" => Ok(CssPathPseudoSelector::Active),
         "focus" => Ok(CssPathPseudoSelector::Focus),
+        "disabled" => Ok(CssPathPseudoSelector::Disabled),
+        "checked" => Ok(CssPathPseudoSelector::Checked),
+        "root" => Ok(CssPathPseudoSelector::Root),
         "nth-child" => {
             let value = value.ok_or(CssPseudoSelectorParseError::EmptyNthChild)?;
             let parsed = parse_nth_child_selector(value)?;
@@ -115,6 +157,22 @@ fn pseudo_selector_from_str<'a>(selector: &'a str, value: Option<&'a str>)
     }
 }
 
+/// Same as `pseudo_selector_from_str`, but also parses the `::before` and `::after`
+/// pseudo-elements (as well as their legacy single-colon syntax `:before` and `:after`)
+/// and the `::scrollbar`, `::track` and `::thumb` pseudo-elements of scrollable nodes
+fn parse_pseudo_selector<'a>(selector: &'a str, value: Option<&'a str>)
+-> Result<CssPathSelector, CssPseudoSelectorParseError<'a>>
+{
+    match selector.trim_start_matches(':') {
+        "before" => Ok(CssPathSelector::PseudoElement(CssPseudoElement::Before)),
+        "after" => Ok(CssPathSelector::PseudoElement(CssPseudoElement::After)),
+        "scrollbar" => Ok(CssPathSelector::PseudoElement(CssPseudoElement::Scrollbar)),
+        "track" => Ok(CssPathSelector::PseudoElement(CssPseudoElement::Track)),
+        "thumb" => Ok(CssPathSelector::PseudoElement(CssPseudoElement::Thumb)),
+        _ => pseudo_selector_from_str(selector, value).map(CssPathSelector::PseudoSelector),
+    }
+}
+
 /// Parses the inner value of the `:nth-child` selector, including numbers and patterns.
 ///
 /// I.e.: `"2n+3"` -> `Pattern { repeat: 2, offset: 3 }`
@@ -134,11 +192,11 @@ fn parse_nth_child_selector<'a>(value: &'a str) -> Result<CssNthChildSelector, C
     match value.as_ref() {
         "even" => Ok(Even),
         "odd" => Ok(Odd),
-        other => parse_nth_child_pattern(value),
+        _ => parse_nth_child_pattern(value),
     }
 }
 
-/// Parses the pattern between the braces of a "nth-child" (such as "2n+3").
+/// Parses the pattern between the braces of a "nth-child" (such as "2n+3", "-n + 3" or "2n-1").
 fn parse_nth_child_pattern<'a>(value: &'a str) -> Result<CssNthChildSelector, CssPseudoSelectorParseError<'a>> {
 
     let value = value.trim();
@@ -147,28 +205,36 @@ fn parse_nth_child_pattern<'a>(value: &'a str) -> Result<CssNthChildSelector, Cs
         return Err(CssPseudoSelectorParseError::EmptyNthChild);
     }
 
-    // TODO: Test for "+"
-    let repeat = value.split("n").next()
-        .ok_or(CssPseudoSelectorParseError::InvalidNthChildPattern(value))?
-        .trim()
-        .parse::<usize>()?;
+    let (repeat_string, offset_string) = match value.find('n') {
+        Some(n_pos) => (Some(value[..n_pos].trim()), value[(n_pos + 1)..].trim()),
+        // No "n" in the pattern, such as "+3" or "-1"
+        None => (None, value),
+    };
+
+    let repeat = match repeat_string {
+        None => 0,
+        Some("") | Some("+") => 1,
+        Some("-") => -1,
+        Some(other) => other.parse::<isize>()?,
+    };
 
-    // In a "2n+3" form, the first .next() yields the "2n", the second .next() yields the "3"
-    let mut offset_iterator = value.split("+");
+    // The offset may contain whitespace between the sign and the number, i.e. "2n + 3"
+    let offset = if offset_string.is_empty() {
+        0
+    } else {
+        let (is_negative, number) = match offset_string.chars().next() {
+            Some('+') => (false, offset_string[1..].trim()),
+            Some('-') => (true, offset_string[1..].trim()),
+            _ if repeat_string.is_none() => (false, offset_string),
+            _ => return Err(CssPseudoSelectorParseError::InvalidNthChildPattern(value)),
+        };
 
-    // has to succeed, since the string is verified to not be empty
-    offset_iterator.next().unwrap();
+        if number.is_empty() || number.starts_with('+') || number.starts_with('-') {
+            return Err(CssPseudoSelectorParseError::InvalidNthChildPattern(value));
+        }
 
-    let offset = match offset_iterator.next() {
-        Some(offset_string) => {
-            let offset_string = offset_string.trim();
-            if offset_string.is_empty() {
-                return Err(CssPseudoSelectorParseError::InvalidNthChildPattern(value));
-            } else {
-                offset_string.parse::<usize>()?
-            }
-        },
-        None => 0,
+        let number = number.parse::<isize>()?;
+        if is_negative { -number } else { number }
     };
 
     Ok(Pattern { repeat, offset })
@@ -186,17 +252,29 @@ fn test_css_pseudo_selector_parse() {
         (("hover", None), Hover),
         (("active", None), Active),
         (("focus", None), Focus),
+        (("disabled", None), Disabled),
+        (("checked", None), Checked),
+        (("root", None), Root),
         (("nth-child", Some("4")), NthChild(Number(4))),
         (("nth-child", Some("even")), NthChild(Even)),
         (("nth-child", Some("odd")), NthChild(Odd)),
         (("nth-child", Some("5n")), NthChild(Pattern { repeat: 5, offset: 0 })),
         (("nth-child", Some("2n+3")), NthChild(Pattern { repeat: 2, offset: 3 })),
+        (("nth-child", Some("2n + 3")), NthChild(Pattern { repeat: 2, offset: 3 })),
+        (("nth-child", Some("2n-1")), NthChild(Pattern { repeat: 2, offset: -1 })),
+        (("nth-child", Some("n")), NthChild(Pattern { repeat: 1, offset: 0 })),
+        (("nth-child", Some("-n+3")), NthChild(Pattern { repeat: -1, offset: 3 })),
+        (("nth-child", Some("+n - 2")), NthChild(Pattern { repeat: 1, offset: -2 })),
+        (("nth-child", Some("0n+5")), NthChild(Pattern { repeat: 0, offset: 5 })),
+        (("nth-child", Some("-1")), NthChild(Pattern { repeat: 0, offset: -1 })),
     ];
 
     let err = [
         (("asdf", None), UnknownSelector("asdf", None)),
         (("", None), UnknownSelector("", None)),
         (("nth-child", Some("2n+")), InvalidNthChildPattern("2n+")),
+        (("nth-child", Some("2n 3")), InvalidNthChildPattern("2n 3")),
+        (("nth-child", Some("2n+-3")), InvalidNthChildPattern("2n+-3")),
         // Can't test for ParseIntError because the fields are private.
         // This is an example on why you shouldn't use std::error::Error!
     ];
@@ -208,6 +286,540 @@ fn test_css_pseudo_selector_parse() {
     for ((selector, val), e) in &err {
         assert_eq!(pseudo_selector_from_str(selector, *val), Err(e.clone()));
     }
+
+    assert_eq!(parse_pseudo_selector("before", None), Ok(CssPathSelector::PseudoElement(CssPseudoElement::Before)));
+    assert_eq!(parse_pseudo_selector(":after", None), Ok(CssPathSelector::PseudoElement(CssPseudoElement::After)));
+    assert_eq!(parse_pseudo_selector(":thumb", None), Ok(CssPathSelector::PseudoElement(CssPseudoElement::Thumb)));
+    assert_eq!(parse_pseudo_selector("hover", None), Ok(CssPathSelector::PseudoSelector(Hover)));
+    assert_eq!(parse_pseudo_selector(":hover", None), Err(UnknownSelector(":hover", None)));
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CssAttributeSelectorParseError<'a> {
+    /// No attribute name given, i.e. `[]` or `[="error"]`
+    EmptyAttributeName(&'a str),
+    /// Unknown operator between the name and the value, i.e. `[data-state|="error"]`
+    UnknownOperator(&'a str),
+    /// The value has an opening quote, but no closing quote
+    UnclosedQuote(&'a str),
+}
+
+impl_display! { CssAttributeSelectorParseError<'a>, {
+    EmptyAttributeName(selector) => format!("Attribute selector has no attribute name: \"[{}]\"", selector),
+    UnknownOperator(selector) => format!(
+        "Unknown operator in attribute selector \"[{}]\" - supported are \"=\", \"~=\", \"^=\", \"$=\" and \"*=\"", selector
+    ),
+    UnclosedQuote(selector) => format!("Unclosed quote in attribute selector \"[{}]\"", selector),
+}}
+
+/// Parses the inner value of an attribute selector, i.e. `data-state="error"`
+fn parse_attribute_selector<'a>(selector: &'a str) -> Result<CssAttributeSelector, CssAttributeSelectorParseError<'a>> {
+
+    let input = selector.trim();
+    let input = if input.starts_with('[') && input.ends_with(']') { &input[1..input.len() - 1] } else { input };
+
+    let (name, matcher) = match input.find('=') {
+        None => (input, CssAttributeMatcher::Exists),
+        Some(equals_pos) => {
+            let (name, operator) = match input[..equals_pos].chars().last() {
+                Some(c @ '~') | Some(c @ '^') | Some(c @ '$') | Some(c @ '*') => (&input[..equals_pos - 1], Some(c)),
+                Some(c) if !(c.is_alphanumeric() || c == '-' || c == '_' || c.is_whitespace()) => {
+                    return Err(CssAttributeSelectorParseError::UnknownOperator(selector));
+                },
+                _ => (&input[..equals_pos], None),
+            };
+
+            let value = input[(equals_pos + 1)..].trim();
+            let value = match value.chars().next() {
+                Some(quote @ '"') | Some(quote @ '\'') => {
+                    if value.len() < 2 || !value.ends_with(quote) {
+                        return Err(CssAttributeSelectorParseError::UnclosedQuote(selector));
+                    }
+                    &value[1..value.len() - 1]
+                },
+                _ => value,
+            }.to_string();
+
+            let matcher = match operator {
+                None => CssAttributeMatcher::Equals(value),
+                Some('~') => CssAttributeMatcher::ContainsWord(value),
+                Some('^') => CssAttributeMatcher::StartsWith(value),
+                Some('$') => CssAttributeMatcher::EndsWith(value),
+                Some(_) => CssAttributeMatcher::Contains(value),
+            };
+
+            (name, matcher)
+        }
+    };
+
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(CssAttributeSelectorParseError::EmptyAttributeName(selector));
+    }
+
+    Ok(CssAttributeSelector { name: name.to_string(), matcher })
+}
+
+#[test]
+fn test_css_attribute_selector_parse() {
+
+    use self::CssAttributeMatcher::*;
+    use self::CssAttributeSelectorParseError::*;
+
+    let selector = |name: &str, matcher| CssAttributeSelector { name: name.to_string(), matcher };
+
+    let ok_res = [
+        ("data-state", selector("data-state", Exists)),
+        ("data-state=\"error\"", selector("data-state", Equals("error".into()))),
+        ("data-state = 'error'", selector("data-state", Equals("error".into()))),
+        ("data-state=error", selector("data-state", Equals("error".into()))),
+        ("data-state~=\"error\"", selector("data-state", ContainsWord("error".into()))),
+        ("data-state^=\"err\"", selector("data-state", StartsWith("err".into()))),
+        ("data-state$=\"or\"", selector("data-state", EndsWith("or".into()))),
+        ("data-state*=\"rro\"", selector("data-state", Contains("rro".into()))),
+        ("[data-state=\"\"]", selector("data-state", Equals("".into()))),
+    ];
+
+    let err = [
+        ("", EmptyAttributeName("")),
+        ("=\"error\"", EmptyAttributeName("=\"error\"")),
+        ("data-state|=\"error\"", UnknownOperator("data-state|=\"error\"")),
+        ("data-state=\"error", UnclosedQuote("data-state=\"error")),
+    ];
+
+    for (input, expected) in &ok_res {
+        assert_eq!(parse_attribute_selector(input), Ok(expected.clone()));
+    }
+
+    for (input, e) in &err {
+        assert_eq!(parse_attribute_selector(input), Err(e.clone()));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CssMediaQueryParseError<'a> {
+    /// No query given, i.e. `@media { ... }` or `@media screen, { ... }`
+    EmptyQuery,
+    /// Media type other than `all`, `screen` or `print` (`not` is not supported)
+    UnknownMediaType(&'a str),
+    /// Unsupported media feature, i.e. `(orientation: landscape)`
+    UnknownFeature(&'a str),
+    /// The value of a media feature is invalid, i.e. `(min-width: wide)`
+    InvalidFeatureValue(&'a str, &'a str),
+    /// Unclosed parentheses or features that aren't joined with `and`
+    MalformedQuery(&'a str),
+}
+
+impl_display! { CssMediaQueryParseError<'a>, {
+    EmptyQuery => "Empty @media query",
+    UnknownMediaType(media_type) => format!(
+        "Unknown media type \"{}\" - supported are \"all\", \"screen\" and \"print\"", media_type
+    ),
+    UnknownFeature(feature) => format!(
+        "Unknown media feature \"{}\" - supported are min- / max-width, min- / max-height and min- / max-resolution", feature
+    ),
+    InvalidFeatureValue(feature, value) => format!("Invalid value for media feature \"{}\": \"{}\"", feature, value),
+    MalformedQuery(query) => format!("Malformed @media query: \"{}\"", query),
+}}
+
+/// Parses the comma-separated queries of an `@media` block, i.e. `screen and (min-width: 600px), print`
+fn parse_media_query_list<'a>(input: &'a str) -> Result<Vec<CssMediaQuery>, CssMediaQueryParseError<'a>> {
+    input.split(',').map(parse_media_query).collect()
+}
+
+/// Parses a single media query, i.e. `screen and (min-width: 600px)`
+fn parse_media_query<'a>(query: &'a str) -> Result<CssMediaQuery, CssMediaQueryParseError<'a>> {
+
+    use self::CssMediaQueryParseError::*;
+
+    let mut media_type = None;
+    let mut features = Vec::new();
+    // Set after an `and`, the next token then has to be a media feature
+    let mut expects_feature = false;
+    let mut rest = query.trim();
+
+    if rest.is_empty() {
+        return Err(EmptyQuery);
+    }
+
+    while !rest.is_empty() {
+
+        let is_first_token = media_type.is_none() && features.is_empty();
+
+        if rest.starts_with('(') {
+            if !(is_first_token || expects_feature) {
+                return Err(MalformedQuery(query));
+            }
+            let closing_brace = rest.find(')').ok_or(MalformedQuery(query))?;
+            features.push(parse_media_feature(&rest[1..closing_brace])?);
+            expects_feature = false;
+            rest = rest[(closing_brace + 1)..].trim_start();
+            continue;
+        }
+
+        let word_end = rest.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(rest.len());
+        let word = &rest[..word_end];
+        rest = rest[word_end..].trim_start();
+
+        match word {
+            "and" if !is_first_token && !expects_feature => { expects_feature = true; },
+            "only" if is_first_token => { },
+            _ if is_first_token => {
+                media_type = Some(match word {
+                    "all" => CssMediaType::All,
+                    "screen" => CssMediaType::Screen,
+                    "print" => CssMediaType::Print,
+                    _ => return Err(UnknownMediaType(word)),
+                });
+            },
+            _ => return Err(MalformedQuery(query)),
+        }
+    }
+
+    if expects_feature {
+        return Err(MalformedQuery(query));
+    }
+
+    Ok(CssMediaQuery {
+        media_type: media_type.unwrap_or(CssMediaType::All),
+        features,
+    })
+}
+
+/// Parses the inside of a media feature, i.e. `min-width: 600px`
+fn parse_media_feature<'a>(feature: &'a str) -> Result<CssMediaFeature, CssMediaQueryParseError<'a>> {
+
+    use self::CssMediaQueryParseError::*;
+
+    let colon = feature.find(':').ok_or(UnknownFeature(feature.trim()))?;
+    let name = feature[..colon].trim();
+    let value = feature[(colon + 1)..].trim();
+
+    let length = || crate::css_parser::parse_pixel_value(value)
+        .map(|pixels| pixels.to_pixels())
+        .map_err(|_| InvalidFeatureValue(name, value));
+
+    let resolution = || {
+        let (number, dots_per_pixel) =
+            if value.ends_with("dppx") { (&value[..value.len() - 4], 1.0) }
+            else if value.ends_with("dpi") { (&value[..value.len() - 3], 96.0) }
+            else if value.ends_with('x') { (&value[..value.len() - 1], 1.0) }
+            else { return Err(InvalidFeatureValue(name, value)); };
+        number.trim().parse::<f32>().map(|n| n / dots_per_pixel).map_err(|_| InvalidFeatureValue(name, value))
+    };
+
+    let pixel_ratio = || value.parse::<f32>().map_err(|_| InvalidFeatureValue(name, value));
+
+    match name {
+        "min-width" => Ok(CssMediaFeature::MinWidth(length()?)),
+        "max-width" => Ok(CssMediaFeature::MaxWidth(length()?)),
+        "min-height" => Ok(CssMediaFeature::MinHeight(length()?)),
+        "max-height" => Ok(CssMediaFeature::MaxHeight(length()?)),
+        "min-resolution" => Ok(CssMediaFeature::MinResolution(resolution()?)),
+        "max-resolution" => Ok(CssMediaFeature::MaxResolution(resolution()?)),
+        "min-device-pixel-ratio" | "-webkit-min-device-pixel-ratio" => Ok(CssMediaFeature::MinResolution(pixel_ratio()?)),
+        "max-device-pixel-ratio" | "-webkit-max-device-pixel-ratio" => Ok(CssMediaFeature::MaxResolution(pixel_ratio()?)),
+        _ => Err(UnknownFeature(name)),
+    }
+}
+
+#[test]
+fn test_css_media_query_parse() {
+
+    use self::CssMediaFeature::*;
+    use self::CssMediaQueryParseError::*;
+
+    let query = |media_type, features| CssMediaQuery { media_type, features };
+
+    let ok = [
+        ("(min-width: 600px)", vec![query(CssMediaType::All, vec![MinWidth(600.0)])]),
+        ("screen and (max-width: 40em) and (min-height: 300px)", vec![query(CssMediaType::Screen, vec![MaxWidth(640.0), MinHeight(300.0)])]),
+        ("only screen and (min-resolution: 192dpi)", vec![query(CssMediaType::Screen, vec![MinResolution(2.0)])]),
+        ("(max-resolution: 1.5dppx), print", vec![query(CssMediaType::All, vec![MaxResolution(1.5)]), query(CssMediaType::Print, Vec::new())]),
+        ("(-webkit-min-device-pixel-ratio: 2)", vec![query(CssMediaType::All, vec![MinResolution(2.0)])]),
+    ];
+
+    let err = [
+        ("", EmptyQuery),
+        ("screen,", EmptyQuery),
+        ("tv", UnknownMediaType("tv")),
+        ("not screen", UnknownMediaType("not")),
+        ("(orientation: landscape)", UnknownFeature("orientation")),
+        ("(min-width: wide)", InvalidFeatureValue("min-width", "wide")),
+        ("screen (min-width: 600px)", MalformedQuery("screen (min-width: 600px)")),
+        ("screen and", MalformedQuery("screen and")),
+        ("(min-width: 600px", MalformedQuery("(min-width: 600px")),
+    ];
+
+    for (input, expected) in &ok {
+        assert_eq!(parse_media_query_list(input), Ok(expected.clone()));
+    }
+
+    for (input, e) in &err {
+        assert_eq!(parse_media_query_list(input), Err(e.clone()));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CssFontFaceParseError<'a> {
+    /// The `font-family` descriptor is missing or empty
+    MissingFontFamily,
+    /// The `src` descriptor is missing
+    MissingSource,
+    /// The `src` descriptor doesn't contain a `url()`, i.e. `src: local("Arial")`
+    InvalidSource(&'a str),
+    /// Declaration without a value, i.e. `font-family "MyFont";`
+    MalformedDeclaration(&'a str),
+}
+
+impl_display! { CssFontFaceParseError<'a>, {
+    MissingFontFamily => "Missing \"font-family\" in @font-face rule",
+    MissingSource => "Missing \"src\" in @font-face rule",
+    InvalidSource(src) => format!("Invalid font source \"{}\" - only url(\"path/to/font.ttf\") sources are supported", src),
+    MalformedDeclaration(declaration) => format!("Malformed declaration: \"{}\"", declaration),
+}}
+
+/// Parses the body of an `@font-face` block, i.e. `font-family: "MyFont"; src: url("fonts/my.ttf");`.
+/// Descriptors other than `font-family` and `src` (such as `font-weight`) are ignored.
+/// If `src` contains a list of fonts, the first `url()` is used.
+fn parse_font_face<'a>(input: &'a str, base_dir: &Path) -> Result<CssFontFace, CssFontFaceParseError<'a>> {
+
+    let mut font_family = None;
+    let mut source = None;
+
+    for declaration in input.split(';').map(str::trim).filter(|d| !d.is_empty()) {
+        let colon = declaration.find(':').ok_or(CssFontFaceParseError::MalformedDeclaration(declaration))?;
+        let value = declaration[(colon + 1)..].trim();
+        match declaration[..colon].trim() {
+            "font-family" => font_family = Some(strip_quotes(value).unwrap_or(value)),
+            "src" => source = Some(
+                value.split(',')
+                .map(str::trim)
+                .filter(|s| s.starts_with("url("))
+                .find_map(|s| parse_import_path(s.find(')').map(|end| &s[..=end]).unwrap_or(s)))
+                .ok_or(CssFontFaceParseError::InvalidSource(value))?
+            ),
+            _ => { },
+        }
+    }
+
+    let font_family = font_family.filter(|f| !f.is_empty()).ok_or(CssFontFaceParseError::MissingFontFamily)?;
+    let source = source.ok_or(CssFontFaceParseError::MissingSource)?;
+
+    Ok(CssFontFace {
+        font_family: font_family.to_string(),
+        source: base_dir.join(source),
+    })
+}
+
+/// Type of the content of a `CssSection`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum CssSectionType<'a> {
+    /// Rules outside of any block
+    Rules,
+    /// Body of an `@media` block with the given query (i.e. `(min-width: 600px)`)
+    Media(&'a str),
+    /// Body of an `@font-face` block
+    FontFace,
+}
+
+/// Part of a stylesheet: either the rules outside of `@media` blocks
+/// or the body of a single `@media` or `@font-face` block
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct CssSection<'a> {
+    /// Byte offset of `text` in the whole stylesheet, used for error locations
+    offset: usize,
+    text: &'a str,
+    section_type: CssSectionType<'a>,
+}
+
+/// The tokenizer doesn't understand nested blocks or at-rules, so `@media` and `@font-face`
+/// blocks are cut out of the stylesheet before tokenizing. Returns the error and its byte offset on failure.
+fn split_at_rule_blocks<'a>(css_string: &'a str, rules_start: usize) -> Result<Vec<CssSection<'a>>, (CssParseErrorInner<'a>, usize)> {
+
+    const MEDIA_KEYWORD: &[u8] = b"@media";
+    const FONT_FACE_KEYWORD: &[u8] = b"@font-face";
+
+    let bytes = css_string.as_bytes();
+    let mut sections = Vec::new();
+    let mut section_start = rules_start;
+    let mut pos = rules_start;
+
+    while pos < bytes.len() {
+
+        if let Some(next) = skip_comment_or_string(bytes, pos) {
+            pos = next;
+            continue;
+        }
+
+        let (is_media_block, query_start) = if starts_with_keyword(bytes, pos, MEDIA_KEYWORD) {
+            (true, pos + MEDIA_KEYWORD.len())
+        } else if starts_with_keyword(bytes, pos, FONT_FACE_KEYWORD) {
+            (false, pos + FONT_FACE_KEYWORD.len())
+        } else {
+            pos += 1;
+            continue;
+        };
+
+        let block_start = match bytes[query_start..].iter().position(|c| *c == b'{' || *c == b'}' || *c == b';') {
+            Some(p) if bytes[query_start + p] == b'{' => query_start + p,
+            _ => return Err((CssParseErrorInner::MalformedCss, pos)),
+        };
+
+        // Find the matching closing brace, `block_end` points behind it
+        let mut block_nesting = 1_usize;
+        let mut block_end = block_start + 1;
+        while block_nesting > 0 {
+            if block_end >= bytes.len() {
+                return Err((CssParseErrorInner::UnclosedBlock, block_start));
+            }
+            if let Some(next) = skip_comment_or_string(bytes, block_end) {
+                block_end = next;
+                continue;
+            }
+            match bytes[block_end] {
+                b'{' => block_nesting += 1,
+                b'}' => block_nesting -= 1,
+                _ => { },
+            }
+            block_end += 1;
+        }
+
+        let section_type = if is_media_block {
+            CssSectionType::Media(&css_string[query_start..block_start])
+        } else if css_string[query_start..block_start].trim().is_empty() {
+            CssSectionType::FontFace
+        } else {
+            return Err((CssParseErrorInner::MalformedCss, pos));
+        };
+
+        sections.push(CssSection { offset: section_start, text: &css_string[section_start..pos], section_type: CssSectionType::Rules });
+        sections.push(CssSection {
+            offset: block_start + 1,
+            text: &css_string[(block_start + 1)..(block_end - 1)],
+            section_type,
+        });
+
+        pos = block_end;
+        section_start = block_end;
+    }
+
+    sections.push(CssSection { offset: section_start, text: &css_string[section_start..], section_type: CssSectionType::Rules });
+
+    Ok(sections)
+}
+
+/// Returns whether `keyword` starts at `pos` and is followed by a whitespace, `(` or `{`
+fn starts_with_keyword(bytes: &[u8], pos: usize, keyword: &[u8]) -> bool {
+    bytes[pos..].starts_with(keyword) &&
+    bytes.get(pos + keyword.len()).map_or(false, |c| c.is_ascii_whitespace() || *c == b'(' || *c == b'{')
+}
+
+/// `@import "file.css";` statement at the start of a stylesheet
+struct CssImport<'a> {
+    /// Byte offset of the statement in the stylesheet, used for error locations
+    offset: usize,
+    path: &'a str,
+}
+
+/// Parses the `@import` statements at the start of the stylesheet (`@import` is
+/// only valid before any other rules), returns them and the offset of the first rule
+fn split_imports<'a>(css_string: &'a str) -> Result<(Vec<CssImport<'a>>, usize), (CssParseErrorInner<'a>, usize)> {
+
+    const IMPORT_KEYWORD: &str = "@import";
+
+    let bytes = css_string.as_bytes();
+    let mut imports = Vec::new();
+    let mut pos = 0;
+
+    loop {
+        // Skip the whitespace and comments between the statements
+        while pos < bytes.len() {
+            if bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            } else if bytes[pos..].starts_with(b"/*") {
+                pos = skip_comment_or_string(bytes, pos).unwrap_or(bytes.len());
+            } else {
+                break;
+            }
+        }
+
+        if !css_string[pos..].starts_with(IMPORT_KEYWORD) {
+            return Ok((imports, pos));
+        }
+
+        let statement_end = match css_string[pos..].find(';') {
+            Some(end) => pos + end,
+            None => return Err((CssParseErrorInner::MalformedCss, pos)),
+        };
+
+        let statement = &css_string[pos..statement_end];
+        let path = parse_import_path(statement[IMPORT_KEYWORD.len()..].trim())
+            .ok_or((CssParseErrorInner::InvalidImport(statement), pos))?;
+
+        imports.push(CssImport { offset: pos, path });
+        pos = statement_end + 1;
+    }
+}
+
+/// Parses the path of an `@import`: `"file.css"`, `'file.css'`, `url("file.css")` or `url(file.css)`
+fn parse_import_path<'a>(input: &'a str) -> Option<&'a str> {
+    let path = if input.starts_with("url(") && input.ends_with(')') {
+        let inner = input[4..(input.len() - 1)].trim();
+        strip_quotes(inner).unwrap_or(inner)
+    } else {
+        strip_quotes(input)?
+    };
+
+    if path.is_empty() { None } else { Some(path) }
+}
+
+/// Strips the quotes of a `"quoted"` or `'quoted'` string, returns `None` if the string isn't quoted
+fn strip_quotes<'a>(input: &'a str) -> Option<&'a str> {
+    let quote = input.chars().next()?;
+    if input.len() >= 2 && (quote == '"' || quote == '\'') && input.ends_with(quote) {
+        Some(&input[1..(input.len() - 1)])
+    } else {
+        None
+    }
+}
+
+/// Loads and parses an `@import`ed file. `import_stack` contains the files
+/// that are currently being imported, in order to detect cyclic imports.
+fn load_import(path: &Path, import_stack: &mut Vec<PathBuf>) -> Result<Css, CssImportError> {
+
+    let io_error = |e: ::std::io::Error| CssImportError::Io { path: path.to_path_buf(), error: e.to_string() };
+
+    let canonical_path = path.canonicalize().map_err(io_error)?;
+    if import_stack.contains(&canonical_path) {
+        return Err(CssImportError::CyclicImport(path.to_path_buf()));
+    }
+
+    let css_string = fs::read_to_string(&canonical_path).map_err(io_error)?;
+    let base_dir = canonical_path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    import_stack.push(canonical_path);
+    let css = new_from_str_inner_with_imports(&css_string, &base_dir, import_stack)
+        .map_err(|e| CssImportError::InvalidCss { path: path.to_path_buf(), error: e.to_string() });
+    import_stack.pop();
+
+    css
+}
+
+/// If a comment or a quoted string starts at `pos`, returns the position right after it
+fn skip_comment_or_string(bytes: &[u8], pos: usize) -> Option<usize> {
+    match bytes[pos] {
+        b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+            let comment_end = bytes[(pos + 2)..].windows(2).position(|w| w == b"*/");
+            Some(comment_end.map(|end| pos + 2 + end + 2).unwrap_or(bytes.len()))
+        },
+        quote @ b'"' | quote @ b'\'' => {
+            let string_end = bytes[(pos + 1)..].iter().position(|c| *c == quote);
+            Some(string_end.map(|end| pos + 1 + end + 1).unwrap_or(bytes.len()))
+        },
+        _ => None,
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -222,36 +834,109 @@ impl<'a> fmt::Display for CssParseError<'a> {
     }
 }
 
+/// Parses a CSS string into a `Css`. Each `@media` block gets its own stylesheet, the rules
+/// between the `@media` blocks end up in the stylesheets between them, so the stylesheets
+/// are in the order of the CSS string. Since the rules are only sorted by specificity within
+/// a stylesheet, the rules of a later block override the rules of the blocks before them.
+///
+/// Files from `@import` statements and `@font-face` sources are loaded relative to the current
+/// working directory, use `new_from_str_with_base_dir` when the CSS string was loaded from a file.
 pub fn new_from_str<'a>(css_string: &'a str) -> Result<Css, CssParseError<'a>> {
-    let mut tokenizer = Tokenizer::new(css_string);
-    match new_from_str_inner(css_string, &mut tokenizer) {
-        Ok(stylesheet) => Ok(Css {
-            stylesheets: vec![
-                stylesheet
-            ],
-        }),
-        Err(e) => {
-            let error_location = tokenizer.pos().saturating_sub(1);
-            let line_number: usize = css_string[0..error_location].lines().count();
-
-            // Rust doesn't count "\n" as a character, so we have to add the line number count on top
-            let total_characters: usize = css_string[0..error_location].lines().take(line_number.saturating_sub(1)).map(|line| line.chars().count()).sum();
-            let total_characters = total_characters + line_number;
-            /*println!("line_number: {} error location: {}, total characters: {}", line_number,
-                     error_location, total_characters);*/
-            let characters_in_line = (error_location + 2) - total_characters;
-
-            let error_location = ErrorLocation {
-                line: line_number,
-                column: characters_in_line,
-            };
+    new_from_str_with_base_dir(css_string, Path::new(""))
+}
+
+/// Same as `new_from_str`, but loads the files from `@import` statements (and resolves the
+/// font paths of `@font-face` rules) relative to `base_dir`.
+/// The stylesheets of the imported files are inserted before the stylesheets of the importing
+/// file, so that the rules of the importing file override the imported rules.
+pub fn new_from_str_with_base_dir<'a>(css_string: &'a str, base_dir: &Path) -> Result<Css, CssParseError<'a>> {
+    new_from_str_inner_with_imports(css_string, base_dir, &mut Vec::new())
+}
 
-            Err(CssParseError {
-                error: e,
-                location: error_location,
-            })
+fn new_from_str_inner_with_imports<'a>(css_string: &'a str, base_dir: &Path, import_stack: &mut Vec<PathBuf>)
+-> Result<Css, CssParseError<'a>>
+{
+    let to_parse_error = |(error, error_location)| CssParseError {
+        error,
+        location: get_error_location(css_string, error_location),
+    };
+
+    let (imports, rules_start) = split_imports(css_string).map_err(to_parse_error)?;
+
+    let mut imported_stylesheets = Vec::new();
+    let mut font_faces = Vec::new();
+    for import in imports {
+        let imported_css = load_import(&base_dir.join(import.path), import_stack)
+            .map_err(|e| to_parse_error((CssParseErrorInner::ImportError(e), import.offset)))?;
+        imported_stylesheets.extend(imported_css.stylesheets);
+        font_faces.extend(imported_css.font_faces);
+    }
+
+    let sections = split_at_rule_blocks(css_string, rules_start).map_err(to_parse_error)?;
+
+    let mut stylesheets = imported_stylesheets;
+    stylesheets.push(Stylesheet::new());
+
+    for section in sections {
+
+        let media_query = match section.section_type {
+            CssSectionType::Rules => None,
+            CssSectionType::Media(query) => Some(query),
+            CssSectionType::FontFace => {
+                let font_face = parse_font_face(section.text, base_dir).map_err(|e| CssParseError {
+                    error: CssParseErrorInner::FontFaceParseError(e),
+                    location: get_error_location(css_string, section.offset),
+                })?;
+                font_faces.push(font_face);
+                continue;
+            },
+        };
+
+        let mut tokenizer = Tokenizer::new(section.text);
+        let stylesheet = new_from_str_inner(section.text, &mut tokenizer).map_err(|e| CssParseError {
+            error: e,
+            location: get_error_location(css_string, (section.offset + tokenizer.pos()).saturating_sub(1)),
+        })?;
+
+        match media_query {
+            None => {
+                // The rules before and after an `@font-face` block stay in the same stylesheet
+                match stylesheets.last_mut() {
+                    Some(last) if last.media_queries.is_empty() => last.rules.extend(stylesheet.rules),
+                    _ if stylesheet.rules.is_empty() => { },
+                    _ => stylesheets.push(stylesheet),
+                }
+            },
+            Some(query) => {
+                let media_queries = parse_media_query_list(query).map_err(|e| CssParseError {
+                    error: e.into(),
+                    // the query ends right before the opening brace of the block
+                    location: get_error_location(css_string, section.offset - 1 - query.len()),
+                })?;
+                stylesheets.push(Stylesheet { media_queries, .. stylesheet });
+            },
         }
     }
+
+    Ok(Css { stylesheets, font_faces })
+}
+
+/// Converts a byte offset into the stylesheet into a line / column pair
+fn get_error_location(css_string: &str, error_location: usize) -> ErrorLocation {
+
+    let line_number: usize = css_string[0..error_location].lines().count();
+
+    // Rust doesn't count "\n" as a character, so we have to add the line number count on top
+    let total_characters: usize = css_string[0..error_location].lines().take(line_number.saturating_sub(1)).map(|line| line.chars().count()).sum();
+    let total_characters = total_characters + line_number;
+    /*println!("line_number: {} error location: {}, total characters: {}", line_number,
+             error_location, total_characters);*/
+    let characters_in_line = (error_location + 2) - total_characters;
+
+    ErrorLocation {
+        line: line_number,
+        column: characters_in_line,
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -265,10 +950,13 @@ pub enum CssPathParseError<'a> {
     NodeTypePath(NodeTypePathParseError<'a>),
     /// Error while parsing a pseudo selector (like `:aldkfja`)
     PseudoSelectorParseError(CssPseudoSelectorParseError<'a>),
+    /// Error while parsing an attribute selector (like `[data-state=]`)
+    AttributeSelectorParseError(CssAttributeSelectorParseError<'a>),
 }
 
 impl_from! { NodeTypePathParseError<'a>, CssPathParseError::NodeTypePath }
 impl_from! { CssPseudoSelectorParseError<'a>, CssPathParseError::PseudoSelectorParseError }
+impl_from! { CssAttributeSelectorParseError<'a>, CssPathParseError::AttributeSelectorParseError }
 
 impl<'a> From<CssSyntaxError> for CssPathParseError<'a> {
     fn from(e: CssSyntaxError) -> Self {
@@ -331,7 +1019,10 @@ pub fn parse_css_path<'a>(input: &'a str) -> Result<CssPath, CssPathParseError<'
                 selectors.push(CssPathSelector::Children);
             },
             Token::PseudoClass { selector, value } => {
-                selectors.push(CssPathSelector::PseudoSelector(pseudo_selector_from_str(selector, value)?));
+                selectors.push(parse_pseudo_selector(selector, value)?);
+            },
+            Token::AttributeSelector(attribute) => {
+                selectors.push(CssPathSelector::Attribute(parse_attribute_selector(attribute)?));
             },
             Token::EndOfStream => {
                 break;
@@ -365,6 +1056,10 @@ fn new_from_str_inner<'a>(css_string: &'a str, tokenizer: &mut Tokenizer<'a>) ->
     let mut current_paths = Vec::new();
     // Current CSS declarations
     let mut current_rules = Vec::new();
+    // Current CSS declarations that are marked with `!important`
+    let mut current_important_rules = Vec::new();
+    // Current custom properties (`--my-color: #f00`)
+    let mut current_variables = Vec::new();
     // Keep track of the current path during parsing
     let mut last_path = Vec::new();
 
@@ -393,12 +1088,24 @@ fn new_from_str_inner<'a>(css_string: &'a str, tokenizer: &mut Tokenizer<'a>) ->
                 }
                 parser_in_block = false;
                 for path in current_paths.drain(..) {
+                    if !current_important_rules.is_empty() {
+                        css_blocks.push(CssRuleBlock {
+                            path: CssPath { selectors: path.clone() },
+                            declarations: current_important_rules.clone(),
+                            variables: Vec::new(),
+                            important: true,
+                        })
+                    }
                     css_blocks.push(CssRuleBlock {
                         path: CssPath { selectors: path },
                         declarations: current_rules.clone(),
+                        variables: current_variables.clone(),
+                        important: false,
                     })
                 }
                 current_rules.clear();
+                current_important_rules.clear();
+                current_variables.clear();
                 last_path.clear(); // technically unnecessary, but just to be sure
             },
 
@@ -443,23 +1150,51 @@ fn new_from_str_inner<'a>(css_string: &'a str, tokenizer: &mut Tokenizer<'a>) ->
                 if parser_in_block {
                     return Err(CssParseErrorInner::MalformedCss);
                 }
-                last_path.push(CssPathSelector::PseudoSelector(pseudo_selector_from_str(selector, value)?));
+                last_path.push(parse_pseudo_selector(selector, value)?);
+            },
+            Token::AttributeSelector(attribute) => {
+                if parser_in_block {
+                    return Err(CssParseErrorInner::MalformedCss);
+                }
+                last_path.push(CssPathSelector::Attribute(parse_attribute_selector(attribute)?));
             },
             Token::Declaration(key, val) => {
                 if !parser_in_block {
                     return Err(CssParseErrorInner::MalformedCss);
                 }
 
+                // `!important` on custom properties is ignored
+                let (val, important) = split_important(val);
+
+                if key.starts_with("--") {
+                    current_variables.push(CssVariable { name: key[2..].to_string(), value: val.trim().to_string() });
+                    continue;
+                }
+
                 let parsed_key = CssPropertyType::from_str(key, &css_property_map)
                     .ok_or(CssParseErrorInner::UnknownPropertyKey(key, val))?;
 
-                current_rules.push(determine_static_or_dynamic_css_property(parsed_key, val)?);
+                let declaration = if val.contains("var(") {
+                    // Can only be parsed once the values of the variables are known
+                    CssDeclaration::Unresolved(UnresolvedCssProperty {
+                        property_type: parsed_key,
+                        value: val.trim().to_string(),
+                    })
+                } else {
+                    determine_static_or_dynamic_css_property(parsed_key, val)?
+                };
+
+                if important {
+                    current_important_rules.push(declaration);
+                } else {
+                    current_rules.push(declaration);
+                }
             },
             Token::EndOfStream => {
                 break;
             },
             _ => {
-                // attributes, lang-attributes and @keyframes are not supported
+                // lang-attributes and @keyframes are not supported
             }
         }
     }
@@ -472,6 +1207,26 @@ fn new_from_str_inner<'a>(css_string: &'a str, tokenizer: &mut Tokenizer<'a>) ->
     Ok(css_blocks.into())
 }
 
+/// Splits the `!important` off the end of a declaration value,
+/// i.e. `"red ! important"` -> `("red", true)`
+fn split_important<'a>(value: &'a str) -> (&'a str, bool) {
+    let trimmed = value.trim_end();
+    let len = trimmed.len();
+    if len < "important".len() || !trimmed.is_char_boundary(len - "important".len()) {
+        return (value, false);
+    }
+    let (rest, keyword) = trimmed.split_at(len - "important".len());
+    if !keyword.eq_ignore_ascii_case("important") {
+        return (value, false);
+    }
+    let rest = rest.trim_end();
+    if rest.ends_with('!') {
+        (&rest[..rest.len() - 1], true)
+    } else {
+        (value, false)
+    }
+}
+
 /// Error that can happen during `css_parser::parse_key_value_pair`
 #[derive(Debug, Clone, PartialEq)]
 pub enum DynamicCssParseError<'a> {
@@ -685,10 +1440,12 @@ fn test_css_parse_1() {
                 ],
             },
             declarations: vec![CssDeclaration::Static(CssProperty::Background(StyleBackground::Color(ColorU { r: 255, g: 0, b: 0, a: 255 })))],
+            variables: Vec::new(),
+            important: false,
         }
     ];
 
-    assert_eq!(parsed_css, Css { stylesheets: vec![expected_css_rules.into()] });
+    assert_eq!(parsed_css, Css { stylesheets: vec![expected_css_rules.into()], font_faces: Vec::new() });
 }
 
 #[test]
@@ -710,8 +1467,12 @@ fn test_css_simple_selector_parse() {
             rules: vec![CssRuleBlock {
                 path: CssPath { selectors: parsed },
                 declarations: Vec::new(),
+                variables: Vec::new(),
+                important: false,
             }],
+            media_queries: Vec::new(),
         }],
+        font_faces: Vec::new(),
     });
 }
 
@@ -723,7 +1484,7 @@ mod stylesheet_parse {
 
     fn test_css(css: &str, expected: Vec<CssRuleBlock>) {
         let css = new_from_str(css).unwrap();
-        assert_eq!(css, Css { stylesheets: vec![expected.into()] });
+        assert_eq!(css, Css { stylesheets: vec![expected.into()], font_faces: Vec::new() });
     }
 
     // Tests that an element with a single class always gets the CSS element applied properly
@@ -743,6 +1504,8 @@ mod stylesheet_parse {
                     declarations: vec![
                         CssDeclaration::Static(red.clone())
                     ],
+                    variables: Vec::new(),
+                    important: false,
                 },
             ];
             test_css(css_1, expected_rules);
@@ -754,11 +1517,15 @@ mod stylesheet_parse {
             let expected_rules = vec![
                 CssRuleBlock {
                     path: CssPath { selectors: vec![CssPathSelector::Id("my_id".into())] },
-                    declarations: vec![CssDeclaration::Static(red.clone())]
+                    declarations: vec![CssDeclaration::Static(red.clone())],
+                    variables: Vec::new(),
+                    important: false,
                 },
                 CssRuleBlock {
                     path: CssPath { selectors: vec![CssPathSelector::Class("my_class".into())] },
-                    declarations: vec![CssDeclaration::Static(blue.clone())]
+                    declarations: vec![CssDeclaration::Static(blue.clone())],
+                    variables: Vec::new(),
+                    important: false,
                 },
             ];
             test_css(css_2, expected_rules);
@@ -770,15 +1537,21 @@ mod stylesheet_parse {
             let expected_rules = vec![
                 CssRuleBlock {
                     path: CssPath { selectors: vec![CssPathSelector::Global] },
-                    declarations: vec![CssDeclaration::Static(black.clone())]
+                    declarations: vec![CssDeclaration::Static(black.clone())],
+                    variables: Vec::new(),
+                    important: false,
                 },
                 CssRuleBlock {
                     path: CssPath { selectors: vec![CssPathSelector::Class("my_class".into()), CssPathSelector::Id("my_id".into())] },
-                    declarations: vec![CssDeclaration::Static(red.clone())]
+                    declarations: vec![CssDeclaration::Static(red.clone())],
+                    variables: Vec::new(),
+                    important: false,
                 },
                 CssRuleBlock {
                     path: CssPath { selectors: vec![CssPathSelector::Class("my_class".into())] },
-                    declarations: vec![CssDeclaration::Static(blue.clone())]
+                    declarations: vec![CssDeclaration::Static(blue.clone())],
+                    variables: Vec::new(),
+                    important: false,
                 },
             ];
             test_css(css_3, expected_rules);
@@ -802,14 +1575,14 @@ fn test_multiple_rules() {
 
     let expected_rules = vec![
         // Rules are sorted by order of appearance in source string
-        CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new() },
-        CssRuleBlock { path: CssPath { selectors: vec![Global, Type(NodeTypePath::Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new() },
-        CssRuleBlock { path: CssPath { selectors: vec![Global, Type(NodeTypePath::Div), Id("my_id".into())] }, declarations: Vec::new() },
-        CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new() },
-        CssRuleBlock { path: CssPath { selectors: vec![Type(NodeTypePath::Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new() },
+        CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+        CssRuleBlock { path: CssPath { selectors: vec![Global, Type(NodeTypePath::Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+        CssRuleBlock { path: CssPath { selectors: vec![Global, Type(NodeTypePath::Div), Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+        CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+        CssRuleBlock { path: CssPath { selectors: vec![Type(NodeTypePath::Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
     ];
 
-    assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()] });
+    assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()], font_faces: Vec::new() });
 }
 
 #[test]
@@ -838,6 +1611,8 @@ fn test_case_issue_93() {
             declarations: vec![
                 CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(color))),
             ],
+            variables: Vec::new(),
+            important: false,
         }
     }
 
@@ -847,5 +1622,209 @@ fn test_case_issue_93() {
         declaration(&[Class("tabwidget-tab".into()), Class("active".into()), Children, Class("tabwidget-tab-close".into())], ColorU { r: 255, g: 0, b: 0, a: 255 }),
     ];
 
-    assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()] });
+    assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()], font_faces: Vec::new() });
+}
+
+#[test]
+fn test_media_block_parse() {
+
+    use azul_css::{CssProperty, StyleTextColor, ColorU};
+
+    let parsed_css = new_from_str("
+        .label { color: red; }
+        /* @media in comments is ignored { */
+        @media screen and (min-width: 600px), (min-resolution: 2dppx) {
+            .label { color: blue; }
+        }
+        #title { color: red; }
+        .label { color: green; }
+    ").unwrap();
+
+    let color_rule = |selector: CssPathSelector, color: ColorU| CssRuleBlock {
+        path: CssPath { selectors: vec![selector] },
+        declarations: vec![CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(color)))],
+        variables: Vec::new(),
+        important: false,
+    };
+
+    let red = ColorU { r: 255, g: 0, b: 0, a: 255 };
+    let green = ColorU { r: 0, g: 128, b: 0, a: 255 };
+    let blue = ColorU { r: 0, g: 0, b: 255, a: 255 };
+
+    // The rules after the @media block override it, so they end up in a stylesheet after it
+    let expected_css = Css {
+        stylesheets: vec![
+            vec![color_rule(CssPathSelector::Class("label".into()), red)].into(),
+            Stylesheet {
+                rules: vec![color_rule(CssPathSelector::Class("label".into()), blue)],
+                media_queries: vec![
+                    CssMediaQuery { media_type: CssMediaType::Screen, features: vec![CssMediaFeature::MinWidth(600.0)] },
+                    CssMediaQuery { media_type: CssMediaType::All, features: vec![CssMediaFeature::MinResolution(2.0)] },
+                ],
+            },
+            vec![
+                color_rule(CssPathSelector::Id("title".into()), red),
+                color_rule(CssPathSelector::Class("label".into()), green),
+            ].into(),
+        ],
+        font_faces: Vec::new(),
+    };
+
+    assert_eq!(parsed_css, expected_css);
+
+    assert_eq!(
+        new_from_str("@media (min-width: 600px) { .label { color: red; }").unwrap_err().error,
+        CssParseErrorInner::UnclosedBlock
+    );
+    assert_eq!(
+        new_from_str("\n@media (min-width: wide) { }").unwrap_err(),
+        CssParseError {
+            error: CssParseErrorInner::MediaQueryParseError(CssMediaQueryParseError::InvalidFeatureValue("min-width", "wide")),
+            location: ErrorLocation { line: 2, column: 7 },
+        }
+    );
+}
+
+#[test]
+fn test_css_variables_parse() {
+
+    use azul_css::{CssProperty, StyleBackground, ColorU};
+
+    let parsed_css = new_from_str("
+        * { --accent: #f00; --border: 1px solid var(--accent); }
+        .button { color: var(--accent); --accent: blue; background-color: red; }
+    ").unwrap();
+
+    let variable = |name: &str, value: &str| CssVariable { name: name.into(), value: value.into() };
+
+    let expected_rules = vec![
+        CssRuleBlock {
+            path: CssPath { selectors: vec![CssPathSelector::Global] },
+            declarations: Vec::new(),
+            variables: vec![variable("accent", "#f00"), variable("border", "1px solid var(--accent)")],
+            important: false,
+        },
+        CssRuleBlock {
+            path: CssPath { selectors: vec![CssPathSelector::Class("button".into())] },
+            declarations: vec![
+                CssDeclaration::Unresolved(UnresolvedCssProperty { property_type: CssPropertyType::TextColor, value: "var(--accent)".into() }),
+                CssDeclaration::Static(CssProperty::Background(StyleBackground::Color(ColorU { r: 255, g: 0, b: 0, a: 255 }))),
+            ],
+            variables: vec![variable("accent", "blue")],
+            important: false,
+        },
+    ];
+
+    assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()], font_faces: Vec::new() });
+}
+
+#[test]
+fn test_css_important_parse() {
+
+    use azul_css::{CssProperty, StyleTextColor, StyleFontSize, PixelValue, ColorU};
+
+    let parsed_css = new_from_str("
+        .button { color: red !important; font-size: 12px; --accent: blue ! IMPORTANT; }
+    ").unwrap();
+
+    let path = CssPath { selectors: vec![CssPathSelector::Class("button".into())] };
+    let expected_rules = vec![
+        CssRuleBlock {
+            path: path.clone(),
+            declarations: vec![CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(ColorU { r: 255, g: 0, b: 0, a: 255 })))],
+            variables: Vec::new(),
+            important: true,
+        },
+        CssRuleBlock {
+            path,
+            declarations: vec![CssDeclaration::Static(CssProperty::FontSize(StyleFontSize(PixelValue::px(12.0))))],
+            variables: vec![CssVariable { name: "accent".into(), value: "blue".into() }],
+            important: false,
+        },
+    ];
+
+    assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()], font_faces: Vec::new() });
+    assert_eq!(split_important("red"), ("red", false));
+    assert_eq!(split_important("url(important)"), ("url(important)", false));
+}
+
+#[test]
+fn test_css_import() {
+
+    use std::{env, fs};
+    use azul_css::{CssProperty, StyleTextColor, ColorU};
+
+    let dir = env::temp_dir().join("azul_css_parser_test_css_import");
+    fs::create_dir_all(dir.join("widgets")).unwrap();
+    fs::write(dir.join("base.css"), ".label { color: red; }").unwrap();
+    fs::write(dir.join("widgets").join("button.css"), "@import \"../base.css\";\n.button { color: blue; }").unwrap();
+    fs::write(dir.join("cycle.css"), "@import 'cycle.css';").unwrap();
+
+    let color_rule = |class: &str, color: ColorU| CssRuleBlock {
+        path: CssPath { selectors: vec![CssPathSelector::Class(class.into())] },
+        declarations: vec![CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(color)))],
+        variables: Vec::new(),
+        important: false,
+    };
+
+    let red = ColorU { r: 255, g: 0, b: 0, a: 255 };
+    let blue = ColorU { r: 0, g: 0, b: 255, a: 255 };
+
+    // Imported rules come first, so that the importing file can override them
+    let parsed_css = new_from_str_with_base_dir("/* widgets */\n@import url(widgets/button.css);\n.label { color: blue; }", &dir).unwrap();
+    assert_eq!(parsed_css, Css {
+        stylesheets: vec![
+            vec![color_rule("label", red)].into(),
+            vec![color_rule("button", blue)].into(),
+            vec![color_rule("label", blue)].into(),
+        ],
+        font_faces: Vec::new(),
+    });
+
+    match new_from_str_with_base_dir("\n@import 'missing.css';", &dir).unwrap_err() {
+        CssParseError { error: CssParseErrorInner::ImportError(CssImportError::Io { .. }), location } => {
+            assert_eq!(location.line, 2);
+        },
+        e => panic!("expected io error, got: {}", e),
+    }
+
+    match new_from_str_with_base_dir("@import 'cycle.css';", &dir).unwrap_err().error {
+        CssParseErrorInner::ImportError(CssImportError::InvalidCss { error, .. }) => assert!(error.contains("imports itself")),
+        e => panic!("expected cyclic import error, got: {}", e),
+    }
+
+    assert_eq!(
+        new_from_str("@import screen;").unwrap_err().error,
+        CssParseErrorInner::InvalidImport("@import screen")
+    );
+}
+
+#[test]
+fn test_font_face_parse() {
+
+    let css = r#"
+        @font-face {
+            font-family: "My Font";
+            font-weight: bold;
+            src: local("My Font"), url("fonts/my.ttf") format("truetype");
+        }
+        .label { font-family: "My Font"; }
+        @font-face { font-family: Icons; src: url(/usr/share/fonts/icons.otf); }
+    "#;
+
+    let parsed_css = new_from_str_with_base_dir(css, Path::new("styles")).unwrap();
+    assert_eq!(parsed_css.font_faces, vec![
+        CssFontFace { font_family: "My Font".into(), source: Path::new("styles").join("fonts/my.ttf") },
+        CssFontFace { font_family: "Icons".into(), source: PathBuf::from("/usr/share/fonts/icons.otf") },
+    ]);
+    assert_eq!(parsed_css.stylesheets[0].rules.len(), 1);
+
+    assert_eq!(
+        new_from_str("@font-face { src: url(a.ttf); }").unwrap_err().error,
+        CssParseErrorInner::FontFaceParseError(CssFontFaceParseError::MissingFontFamily)
+    );
+    assert_eq!(
+        new_from_str("@font-face { font-family: A; src: local(A); }").unwrap_err().error,
+        CssParseErrorInner::FontFaceParseError(CssFontFaceParseError::InvalidSource("local(A)"))
+    );
 }
\ No newline at end of file
@@ -7,16 +7,24 @@ use azul_css::{
     LayoutAlignItems, LayoutAlignContent, LayoutJustifyContent, Shape,
     LayoutWrap, LayoutDirection, LayoutPosition, CssProperty, LayoutOverflow,
     StyleFontFamily, StyleFontSize, StyleLineHeight, LayoutFlexShrink, LayoutFlexGrow,
-    LayoutLeft, LayoutRight, LayoutTop, LayoutBottom, StyleCursor, StyleWordSpacing, StyleTabWidth,
+    LayoutFlexBasis, LayoutOrder,
+    StyleOpacity, StyleVisibility, StyleMixBlendMode, StyleObjectFit, StyleOutline, StyleOutlineOffset, StyleTransform, TransformFunction, StyleTransformOrigin, TransformOriginValue,
+    StyleTransformStyle, StylePerspective,
+    LayoutDisplay, LayoutGridTemplateColumns, LayoutGridTemplateRows, LayoutGridGap, LayoutGap, LayoutRowGap, LayoutColumnGap,
+    LayoutGridColumn, LayoutGridRow, GridTrackSize, GridLine, GridPlacement, LayoutFloat,
+    LayoutScrollSnapType, ScrollSnapAxis, ScrollSnapStrictness, LayoutScrollSnapAlign, ScrollSnapAlignment,
+    LayoutLeft, LayoutRight, LayoutTop, LayoutBottom, StyleCursor, StylePointerEvents, StyleUserSelect, StyleContent, StyleWordSpacing, StyleTabWidth,
+    StyleTabStops, StyleTabStop, StyleTabLeader,
+    StyleWhiteSpace, StyleTextOverflow, StyleTextDecoration, StyleTextDecorationStyle,
     LayoutMaxHeight, LayoutMinHeight, LayoutHeight, LayoutMaxWidth, LayoutMinWidth, LayoutWidth,
     StyleBorderRadius, PixelValue, PercentageValue, FloatValue,
     ColorU, LayoutMargin, StyleLetterSpacing, StyleTextColor, StyleBackground, StyleBoxShadow,
-    GradientStopPre, RadialGradient, StyleBackgroundSize, StyleBackgroundRepeat,
+    GradientStopPre, RadialGradient, ConicGradient, StyleBackgroundSize, StyleBackgroundRepeat,
     DirectionCorner, StyleBorder, Direction, CssImageId, LinearGradient,
     BoxShadowPreDisplayItem, BorderStyle, LayoutPadding, StyleBorderSide, BorderRadius, PixelSize,
-    BackgroundType,
+    BackgroundType, LayoutPaddingInline, LayoutMarginInline, StyleBorderInline, StyleTextDirection,
 
-    SizeMetric, BoxShadowClipMode, ExtendMode, FontId,
+    SizeMetric, BoxShadowClipMode, ExtendMode, FontId, CalcExpression, LayoutCalc,
 };
 
 /// A parser that can accept a list of items and mappings
@@ -121,14 +129,52 @@ pub fn parse_key_value_pair<'a>(key: CssPropertyType, value: &'a str) -> Result<
         LetterSpacing    => Ok(parse_style_letter_spacing(value)?.into()),
         WordSpacing      => Ok(parse_style_word_spacing(value)?.into()),
         TabWidth         => Ok(parse_style_tab_width(value)?.into()),
+        TabStops         => Ok(parse_style_tab_stops(value)?.into()),
+        WhiteSpace       => Ok(parse_style_white_space(value)?.into()),
+        TextOverflow     => Ok(parse_style_text_overflow(value)?.into()),
+        TextDecoration   => Ok(parse_style_text_decoration(value)?.into()),
         LineHeight       => Ok(parse_style_line_height(value)?.into()),
         Cursor           => Ok(parse_style_cursor(value)?.into()),
+        PointerEvents    => Ok(parse_style_pointer_events(value)?.into()),
+        UserSelect       => Ok(parse_style_user_select(value)?.into()),
+        Content          => Ok(parse_style_content(value)?.into()),
+        TextDirection    => Ok(parse_style_text_direction(value)?.into()),
+        Opacity          => Ok(parse_style_opacity(value)?.into()),
+        Visibility       => Ok(parse_style_visibility(value)?.into()),
+        MixBlendMode     => Ok(parse_style_mix_blend_mode(value)?.into()),
+        ObjectFit        => Ok(parse_style_object_fit(value)?.into()),
+        Transform        => Ok(parse_style_transform(value)?.into()),
+        TransformOrigin  => Ok(parse_style_transform_origin(value)?.into()),
+        TransformStyle   => Ok(parse_style_transform_style(value)?.into()),
+        Perspective      => Ok(parse_style_perspective(value)?.into()),
 
         Border           => Ok(StyleBorder::all(parse_css_border(value)?).into()),
         BorderTop        => Ok(border_parser::parse_top(value)?.into()),
         BorderBottom     => Ok(border_parser::parse_bottom(value)?.into()),
         BorderLeft       => Ok(border_parser::parse_left(value)?.into()),
         BorderRight      => Ok(border_parser::parse_right(value)?.into()),
+        BorderInline     => {
+            let border = parse_css_border(value)?;
+            Ok(StyleBorderInline { start: Some(border), end: Some(border) }.into())
+        },
+        BorderInlineStart => Ok(border_inline_parser::parse_start(value)?.into()),
+        BorderInlineEnd  => Ok(border_inline_parser::parse_end(value)?.into()),
+        BorderBlock      => {
+            let border = parse_css_border(value)?;
+            Ok(StyleBorder { top: Some(border), bottom: Some(border), .. Default::default() }.into())
+        },
+        BorderBlockStart => Ok(border_parser::parse_top(value)?.into()),
+        BorderBlockEnd   => Ok(border_parser::parse_bottom(value)?.into()),
+
+        Outline          => Ok(parse_style_outline(value)?.into()),
+        OutlineOffset    => Ok(parse_style_outline_offset(value)?.into()),
+
+        Width | Height |
+        MinWidth | MinHeight |
+        MaxWidth | MaxHeight if value.starts_with("calc(") => {
+            let expression = parse_calc_expression(value)?;
+            Ok(LayoutCalc { property_type: key, expression }.into())
+        },
 
         Width            => Ok(parse_layout_width(value)?.into()),
         Height           => Ok(parse_layout_height(value)?.into()),
@@ -144,7 +190,7 @@ pub fn parse_key_value_pair<'a>(key: CssPropertyType, value: &'a str) -> Result<
         Bottom           => Ok(parse_layout_bottom(value)?.into()),
         TextAlign        => Ok(parse_layout_text_align(value)?.into()),
 
-        BoxShadow        => Ok(StyleBoxShadow::all(parse_css_box_shadow(value)?).into()),
+        BoxShadow        => Ok(StyleBoxShadow::from_list(parse_css_box_shadow_list(value)?).into()),
         BoxShadowTop     => Ok(box_shadow_parser::parse_top(value)?.into()),
         BoxShadowBottom  => Ok(box_shadow_parser::parse_bottom(value)?.into()),
         BoxShadowLeft    => Ok(box_shadow_parser::parse_left(value)?.into()),
@@ -155,22 +201,47 @@ pub fn parse_key_value_pair<'a>(key: CssPropertyType, value: &'a str) -> Result<
         PaddingBottom    => Ok(layout_padding_parser::parse_bottom(value)?.into()),
         PaddingLeft      => Ok(layout_padding_parser::parse_left(value)?.into()),
         PaddingRight     => Ok(layout_padding_parser::parse_right(value)?.into()),
+        PaddingInline    => Ok(parse_layout_padding_inline(value)?.into()),
+        PaddingInlineStart => Ok(layout_padding_inline_parser::parse_start(value)?.into()),
+        PaddingInlineEnd => Ok(layout_padding_inline_parser::parse_end(value)?.into()),
+        PaddingBlock     => Ok(parse_layout_padding_block(value)?.into()),
+        PaddingBlockStart => Ok(layout_padding_parser::parse_top(value)?.into()),
+        PaddingBlockEnd  => Ok(layout_padding_parser::parse_bottom(value)?.into()),
 
         Margin           => Ok(parse_layout_margin(value)?.into()),
         MarginTop        => Ok(layout_margin_parser::parse_top(value)?.into()),
         MarginBottom     => Ok(layout_margin_parser::parse_bottom(value)?.into()),
         MarginLeft       => Ok(layout_margin_parser::parse_left(value)?.into()),
         MarginRight      => Ok(layout_margin_parser::parse_right(value)?.into()),
+        MarginInline     => Ok(parse_layout_margin_inline(value)?.into()),
+        MarginInlineStart => Ok(layout_margin_inline_parser::parse_start(value)?.into()),
+        MarginInlineEnd  => Ok(layout_margin_inline_parser::parse_end(value)?.into()),
+        MarginBlock      => Ok(parse_layout_margin_block(value)?.into()),
+        MarginBlockStart => Ok(layout_margin_parser::parse_top(value)?.into()),
+        MarginBlockEnd   => Ok(layout_margin_parser::parse_bottom(value)?.into()),
 
         FlexWrap         => Ok(parse_layout_wrap(value)?.into()),
         FlexDirection    => Ok(parse_layout_direction(value)?.into()),
         FlexGrow         => Ok(parse_layout_flex_grow(value)?.into()),
         FlexShrink       => Ok(parse_layout_flex_shrink(value)?.into()),
+        FlexBasis        => Ok(parse_layout_flex_basis(value)?.into()),
+        Order            => Ok(parse_layout_order(value)?.into()),
 
         JustifyContent   => Ok(parse_layout_justify_content(value)?.into()),
         AlignItems       => Ok(parse_layout_align_items(value)?.into()),
         AlignContent     => Ok(parse_layout_align_content(value)?.into()),
 
+        Display          => Ok(parse_layout_display(value)?.into()),
+        GridTemplateColumns => Ok(LayoutGridTemplateColumns(parse_grid_track_list(value)?).into()),
+        GridTemplateRows => Ok(LayoutGridTemplateRows(parse_grid_track_list(value)?).into()),
+        GridGap          => Ok(parse_layout_grid_gap(value)?.into()),
+        Gap              => Ok(parse_layout_gap(value)?.into()),
+        RowGap           => Ok(parse_layout_row_gap(value)?.into()),
+        ColumnGap        => Ok(parse_layout_column_gap(value)?.into()),
+        GridColumn       => Ok(LayoutGridColumn(parse_grid_placement(value)?).into()),
+        GridRow          => Ok(LayoutGridRow(parse_grid_placement(value)?).into()),
+        Float            => Ok(parse_layout_float(value)?.into()),
+
         Overflow         => {
             let overflow_both_directions = parse_layout_text_overflow(value)?;
             Ok(LayoutOverflow {
@@ -192,6 +263,8 @@ pub fn parse_key_value_pair<'a>(key: CssPropertyType, value: &'a str) -> Result<
                 vertical: Some(overflow_y),
             }.into())
         },
+        ScrollSnapType   => Ok(parse_layout_scroll_snap_type(value)?.into()),
+        ScrollSnapAlign  => Ok(parse_layout_scroll_snap_align(value)?.into()),
     }
 }
 
@@ -207,18 +280,26 @@ pub enum CssParsingError<'a> {
     PercentageParseError(PercentageParseError),
     CssImageParseError(CssImageParseError<'a>),
     CssStyleFontFamilyParseError(CssStyleFontFamilyParseError<'a>),
+    CssStyleContentParseError(CssStyleContentParseError<'a>),
     CssBackgroundParseError(CssBackgroundParseError<'a>),
     CssColorParseError(CssColorParseError<'a>),
     CssStyleBorderRadiusParseError(CssStyleBorderRadiusParseError<'a>),
+    CssStyleTransformParseError(CssStyleTransformParseError<'a>),
     PaddingParseError(LayoutPaddingParseError<'a>),
     MarginParseError(LayoutMarginParseError<'a>),
     FlexShrinkParseError(FlexShrinkParseError<'a>),
     FlexGrowParseError(FlexGrowParseError<'a>),
+    OrderParseError(OrderParseError<'a>),
+    CalcParseError(CssCalcParseError<'a>),
+    GridParseError(CssGridParseError<'a>),
+    TextDecorationParseError(CssTextDecorationParseError<'a>),
+    TabStopsParseError(CssTabStopsParseError<'a>),
 }
 
 impl_debug_as_display!(CssParsingError<'a>);
 impl_display!{ CssParsingError<'a>, {
     CssStyleBorderRadiusParseError(e) => format!("Invalid border-radius: {}", e),
+    CssStyleTransformParseError(e) => format!("Invalid transform: {}", e),
     CssBorderParseError(e) => format!("Invalid border property: {}", e),
     CssShadowParseError(e) => format!("Invalid shadow: \"{}\"", e),
     InvalidValueErr(e) => format!("\"{}\"", e.0),
@@ -226,12 +307,18 @@ impl_display!{ CssParsingError<'a>, {
     PercentageParseError(e) => format!("{}", e),
     CssImageParseError(e) => format!("{}", e),
     CssStyleFontFamilyParseError(e) => format!("{}", e),
+    CssStyleContentParseError(e) => format!("{}", e),
     CssBackgroundParseError(e) => format!("{}", e),
     CssColorParseError(e) => format!("{}", e),
     PaddingParseError(e) => format!("{}", e),
     MarginParseError(e) => format!("{}", e),
     FlexShrinkParseError(e) => format!("{}", e),
     FlexGrowParseError(e) => format!("{}", e),
+    OrderParseError(e) => format!("{}", e),
+    CalcParseError(e) => format!("Invalid calc() expression: {}", e),
+    GridParseError(e) => format!("{}", e),
+    TextDecorationParseError(e) => format!("Invalid text-decoration: {}", e),
+    TabStopsParseError(e) => format!("Invalid tab-stops: {}", e),
 }}
 
 impl_from!(CssBorderParseError<'a>, CssParsingError::CssBorderParseError);
@@ -241,12 +328,19 @@ impl_from!(InvalidValueErr<'a>, CssParsingError::InvalidValueErr);
 impl_from!(PixelParseError<'a>, CssParsingError::PixelParseError);
 impl_from!(CssImageParseError<'a>, CssParsingError::CssImageParseError);
 impl_from!(CssStyleFontFamilyParseError<'a>, CssParsingError::CssStyleFontFamilyParseError);
+impl_from!(CssStyleContentParseError<'a>, CssParsingError::CssStyleContentParseError);
 impl_from!(CssBackgroundParseError<'a>, CssParsingError::CssBackgroundParseError);
 impl_from!(CssStyleBorderRadiusParseError<'a>, CssParsingError::CssStyleBorderRadiusParseError);
+impl_from!(CssStyleTransformParseError<'a>, CssParsingError::CssStyleTransformParseError);
 impl_from!(LayoutPaddingParseError<'a>, CssParsingError::PaddingParseError);
 impl_from!(LayoutMarginParseError<'a>, CssParsingError::MarginParseError);
 impl_from!(FlexShrinkParseError<'a>, CssParsingError::FlexShrinkParseError);
 impl_from!(FlexGrowParseError<'a>, CssParsingError::FlexGrowParseError);
+impl_from!(OrderParseError<'a>, CssParsingError::OrderParseError);
+impl_from!(CssCalcParseError<'a>, CssParsingError::CalcParseError);
+impl_from!(CssGridParseError<'a>, CssParsingError::GridParseError);
+impl_from!(CssTextDecorationParseError<'a>, CssParsingError::TextDecorationParseError);
+impl_from!(CssTabStopsParseError<'a>, CssParsingError::TabStopsParseError);
 
 impl<'a> From<PercentageParseError> for CssParsingError<'a> {
     fn from(e: PercentageParseError) -> Self {
@@ -386,9 +480,42 @@ impl_display!{ CssShadowParseError<'a>, {
 impl_from!(PixelParseError<'a>, CssShadowParseError::ValueParseErr);
 impl_from!(CssColorParseError<'a>, CssShadowParseError::ColorParseError);
 
-/// parse the border-radius like "5px 10px" or "5px 10px 6px 10px"
+/// parse the border-radius like "5px 10px", "5px 10px 6px 10px" or the
+/// elliptical form "10px 0 10px 0 / 20px", where the values after the slash
+/// are the vertical radii of the corners
 pub fn parse_style_border_radius<'a>(input: &'a str)
 -> Result<StyleBorderRadius, CssStyleBorderRadiusParseError<'a>>
+{
+    let mut parts = input.split('/');
+
+    let horizontal_str = parts.next().unwrap();
+    let vertical_str = parts.next();
+
+    if parts.next().is_some() {
+        return Err(CssStyleBorderRadiusParseError::TooManyValues(input));
+    }
+
+    let horizontal = parse_border_radius_corners(horizontal_str.trim())?;
+    let vertical = match vertical_str {
+        Some(v) => parse_border_radius_corners(v.trim())?,
+        None => horizontal,
+    };
+
+    let [h_top_left, h_top_right, h_bottom_right, h_bottom_left] = horizontal;
+    let [v_top_left, v_top_right, v_bottom_right, v_bottom_left] = vertical;
+
+    Ok(StyleBorderRadius(BorderRadius {
+        top_left: PixelSize::new(h_top_left, v_top_left),
+        top_right: PixelSize::new(h_top_right, v_top_right),
+        bottom_right: PixelSize::new(h_bottom_right, v_bottom_right),
+        bottom_left: PixelSize::new(h_bottom_left, v_bottom_left),
+    }))
+}
+
+/// Parses one side of the slash of a `border-radius`, i.e. "15px 50px 30px",
+/// returns the radii in the order `[top_left, top_right, bottom_right, bottom_left]`
+fn parse_border_radius_corners<'a>(input: &'a str)
+-> Result<[PixelValue;4], CssStyleBorderRadiusParseError<'a>>
 {
     let mut components = input.split_whitespace();
     let len = components.clone().count();
@@ -399,7 +526,7 @@ pub fn parse_style_border_radius<'a>(input: &'a str)
             // (the value applies to all four corners, which are rounded equally:
 
             let uniform_radius = parse_pixel_value(components.next().unwrap())?;
-            Ok(StyleBorderRadius(BorderRadius::uniform(PixelSize::new(uniform_radius, uniform_radius))))
+            Ok([uniform_radius, uniform_radius, uniform_radius, uniform_radius])
         },
         2 => {
             // Two values - border-radius: 15px 50px;
@@ -409,12 +536,7 @@ pub fn parse_style_border_radius<'a>(input: &'a str)
             let top_left_bottom_right = parse_pixel_value(components.next().unwrap())?;
             let top_right_bottom_left = parse_pixel_value(components.next().unwrap())?;
 
-            Ok(StyleBorderRadius(BorderRadius {
-                top_left: PixelSize::new(top_left_bottom_right, top_left_bottom_right),
-                bottom_right:  PixelSize::new(top_left_bottom_right, top_left_bottom_right),
-                top_right:  PixelSize::new(top_right_bottom_left, top_right_bottom_left),
-                bottom_left:  PixelSize::new(top_right_bottom_left, top_right_bottom_left),
-            }))
+            Ok([top_left_bottom_right, top_right_bottom_left, top_left_bottom_right, top_right_bottom_left])
         },
         3 => {
             // Three values - border-radius: 15px 50px 30px;
@@ -425,12 +547,7 @@ pub fn parse_style_border_radius<'a>(input: &'a str)
             let top_right_bottom_left = parse_pixel_value(components.next().unwrap())?;
             let bottom_right = parse_pixel_value(components.next().unwrap())?;
 
-            Ok(StyleBorderRadius(BorderRadius {
-                top_left: PixelSize::new(top_left, top_left),
-                bottom_right:  PixelSize::new(bottom_right, bottom_right),
-                top_right:  PixelSize::new(top_right_bottom_left, top_right_bottom_left),
-                bottom_left: PixelSize::new(top_right_bottom_left, top_right_bottom_left),
-            }))
+            Ok([top_left, top_right_bottom_left, bottom_right, top_right_bottom_left])
         }
         4 => {
             // Four values - border-radius: 15px 50px 30px 5px;
@@ -443,12 +560,10 @@ pub fn parse_style_border_radius<'a>(input: &'a str)
             let bottom_right = parse_pixel_value(components.next().unwrap())?;
             let bottom_left = parse_pixel_value(components.next().unwrap())?;
 
-            Ok(StyleBorderRadius(BorderRadius {
-                top_left: PixelSize::new(top_left, top_left),
-                bottom_right: PixelSize::new(bottom_right, bottom_right),
-                top_right: PixelSize::new(top_right, top_right),
-                bottom_left: PixelSize::new(bottom_left, bottom_left),
-            }))
+            Ok([top_left, top_right, bottom_right, bottom_left])
+        },
+        0 => {
+            Err(PixelParseError::EmptyString.into())
         },
         _ => {
             Err(CssStyleBorderRadiusParseError::TooManyValues(input))
@@ -496,6 +611,8 @@ pub fn parse_pixel_value<'a>(input: &'a str)
     let number = number_str.parse::<f32>().map_err(|e| PixelParseError::ValueParseErr(e, number_str))?;
 
     let unit = match unit_str.as_str() {
+        // a unitless zero is valid for every length
+        "" if number == 0.0 => SizeMetric::Px,
         "px" => SizeMetric::Px,
         "em" => SizeMetric::Em,
         "pt" => SizeMetric::Pt,
@@ -542,6 +659,201 @@ pub fn parse_percentage_value(input: &str)
     Ok(PercentageValue::new(number))
 }
 
+#[derive(Copy, Clone, PartialEq)]
+pub enum CssCalcParseError<'a> {
+    /// The value doesn't start with `calc(` or doesn't end with `)`
+    NotACalcExpression(&'a str),
+    /// The expression ended while a value or a closing brace was expected
+    UnexpectedEndOfInput,
+    UnexpectedToken(&'a str),
+    InvalidOperand(&'a str),
+    /// A unitless number was added to a length, i.e. `calc(100% - 5)`
+    MixedUnits,
+    /// Two lengths were multiplied or a value was divided by a length, i.e. `calc(5px * 5px)`
+    InvalidUnitsInProduct,
+    /// The whole expression is a unitless number, i.e. `calc(5 * 2)`
+    UnitlessResult,
+}
+
+impl_debug_as_display!(CssCalcParseError<'a>);
+impl_display!{ CssCalcParseError<'a>, {
+    NotACalcExpression(e) => format!("Expected \"calc(...)\", got: \"{}\"", e),
+    UnexpectedEndOfInput => format!("Unexpected end of expression"),
+    UnexpectedToken(e) => format!("Unexpected token: \"{}\"", e),
+    InvalidOperand(e) => format!("Invalid value: \"{}\"", e),
+    MixedUnits => format!("Can't add or subtract a unitless number and a length"),
+    InvalidUnitsInProduct => format!("Can only multiply by or divide by unitless numbers"),
+    UnitlessResult => format!("Expression has to result in a length, not a unitless number"),
+}}
+
+/// Parses a `calc()` expression, such as `calc(100% - 20px)` or `calc((100% - 10px) / 3)`.
+///
+/// Following the CSS spec, `+` and `-` have to be surrounded by whitespace
+/// (since `-20px` is a negative length), `*` and `/` don't.
+pub fn parse_calc_expression<'a>(input: &'a str)
+-> Result<CalcExpression, CssCalcParseError<'a>>
+{
+    let input = input.trim();
+
+    if !input.starts_with("calc(") || !input.ends_with(')') {
+        return Err(CssCalcParseError::NotACalcExpression(input));
+    }
+
+    let mut parser = CalcParser { tokens: tokenize_calc_expression(input), position: 0 };
+    let expression = parser.parse_value()?;
+
+    if let Some(token) = parser.peek() {
+        return Err(CssCalcParseError::UnexpectedToken(token));
+    }
+
+    if calc_expression_is_unitless(&expression) {
+        return Err(CssCalcParseError::UnitlessResult);
+    }
+
+    Ok(expression)
+}
+
+/// Splits `calc(100% - 20px)` into `["calc", "(", "100%", "-", "20px", ")"]`
+fn tokenize_calc_expression<'a>(input: &'a str) -> Vec<&'a str> {
+
+    let mut tokens = Vec::new();
+    let mut word_start = None;
+
+    for (idx, ch) in input.char_indices() {
+        let is_single_char_token = ch == '(' || ch == ')' || ch == '*' || ch == '/';
+        if ch.is_whitespace() || is_single_char_token {
+            if let Some(start) = word_start.take() {
+                tokens.push(&input[start..idx]);
+            }
+            if is_single_char_token {
+                tokens.push(&input[idx..idx + 1]);
+            }
+        } else if word_start.is_none() {
+            word_start = Some(idx);
+        }
+    }
+
+    if let Some(start) = word_start {
+        tokens.push(&input[start..]);
+    }
+
+    tokens
+}
+
+/// Recursive-descent parser, `*` and `/` bind stronger than `+` and `-`
+struct CalcParser<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+}
+
+impl<'a> CalcParser<'a> {
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).cloned()
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    // sum := product (("+" | "-") product)*
+    fn parse_sum(&mut self) -> Result<CalcExpression, CssCalcParseError<'a>> {
+        let mut lhs = self.parse_product()?;
+        loop {
+            let is_add = match self.peek() {
+                Some("+") => true,
+                Some("-") => false,
+                _ => return Ok(lhs),
+            };
+            self.position += 1;
+            let rhs = self.parse_product()?;
+            if calc_expression_is_unitless(&lhs) != calc_expression_is_unitless(&rhs) {
+                return Err(CssCalcParseError::MixedUnits);
+            }
+            lhs = if is_add {
+                CalcExpression::Add(Box::new(lhs), Box::new(rhs))
+            } else {
+                CalcExpression::Sub(Box::new(lhs), Box::new(rhs))
+            };
+        }
+    }
+
+    // product := value (("*" | "/") value)*
+    fn parse_product(&mut self) -> Result<CalcExpression, CssCalcParseError<'a>> {
+        let mut lhs = self.parse_value()?;
+        loop {
+            let is_mul = match self.peek() {
+                Some("*") => true,
+                Some("/") => false,
+                _ => return Ok(lhs),
+            };
+            self.position += 1;
+            let rhs = self.parse_value()?;
+            lhs = if is_mul {
+                if !calc_expression_is_unitless(&lhs) && !calc_expression_is_unitless(&rhs) {
+                    return Err(CssCalcParseError::InvalidUnitsInProduct);
+                }
+                CalcExpression::Mul(Box::new(lhs), Box::new(rhs))
+            } else {
+                if !calc_expression_is_unitless(&rhs) {
+                    return Err(CssCalcParseError::InvalidUnitsInProduct);
+                }
+                CalcExpression::Div(Box::new(lhs), Box::new(rhs))
+            };
+        }
+    }
+
+    // value := "(" sum ")" | "calc" "(" sum ")" | operand
+    fn parse_value(&mut self) -> Result<CalcExpression, CssCalcParseError<'a>> {
+        match self.next() {
+            None => Err(CssCalcParseError::UnexpectedEndOfInput),
+            Some("calc") if self.peek() == Some("(") => self.parse_value(),
+            Some("(") => {
+                let expression = self.parse_sum()?;
+                match self.next() {
+                    Some(")") => Ok(expression),
+                    Some(token) => Err(CssCalcParseError::UnexpectedToken(token)),
+                    None => Err(CssCalcParseError::UnexpectedEndOfInput),
+                }
+            },
+            Some(token @ ")") | Some(token @ "*") | Some(token @ "/") |
+            Some(token @ "+") | Some(token @ "-") => Err(CssCalcParseError::UnexpectedToken(token)),
+            Some(operand) => parse_calc_operand(operand),
+        }
+    }
+}
+
+/// Parses `20px`, `50%` or a unitless `2`
+fn parse_calc_operand<'a>(input: &'a str) -> Result<CalcExpression, CssCalcParseError<'a>> {
+
+    if input.ends_with('%') {
+        return match input[..input.len() - 1].parse::<f32>() {
+            Ok(number) if number.is_finite() => Ok(CalcExpression::Percentage(PercentageValue::new(number))),
+            _ => Err(CssCalcParseError::InvalidOperand(input)),
+        };
+    }
+
+    match input.parse::<f32>() {
+        Ok(number) if number.is_finite() => Ok(CalcExpression::Number(FloatValue::new(number))),
+        _ => parse_pixel_value(input)
+            .map(CalcExpression::Length)
+            .map_err(|_| CssCalcParseError::InvalidOperand(input)),
+    }
+}
+
+/// Returns whether the expression results in a unitless number instead of a length
+fn calc_expression_is_unitless(expression: &CalcExpression) -> bool {
+    use azul_css::CalcExpression::*;
+    match expression {
+        Length(_) | Percentage(_) => false,
+        Number(_) => true,
+        Add(a, _) | Sub(a, _) | Div(a, _) => calc_expression_is_unitless(a),
+        Mul(a, b) => calc_expression_is_unitless(a) && calc_expression_is_unitless(b),
+    }
+}
+
 /// Parse any valid CSS color, INCLUDING THE HASH
 ///
 /// "blue" -> "00FF00" -> ColorF { r: 0, g: 255, b: 0 })
@@ -988,8 +1300,46 @@ mod $mod_name {
     parse_x!($struct_name, $error_name, parse_top, top, $parse_fn);
 })}
 
-parse_tblr!(layout_padding_parser, LayoutPadding, LayoutPaddingParseError, parse_pixel_value);
-parse_tblr!(layout_margin_parser, LayoutMargin, LayoutMarginParseError, parse_pixel_value);
+// Logical properties: the `-block-` sides are always top / bottom (only horizontal writing
+// modes are supported), the `-inline-` sides are resolved against the `direction` later on
+macro_rules! parse_start_end {($mod_name:ident, $struct_name:ident, $error_name:ident, $parse_fn:ident) => (
+mod $mod_name {
+    use super::*;
+    parse_x!($struct_name, $error_name, parse_start, start, $parse_fn);
+    parse_x!($struct_name, $error_name, parse_end, end, $parse_fn);
+})}
+
+parse_tblr!(layout_padding_parser, LayoutPadding, LayoutPaddingParseError, parse_padding_value);
+parse_tblr!(layout_margin_parser, LayoutMargin, LayoutMarginParseError, parse_margin_value);
+parse_start_end!(layout_padding_inline_parser, LayoutPaddingInline, LayoutPaddingParseError, parse_padding_value);
+parse_start_end!(layout_margin_inline_parser, LayoutMarginInline, LayoutMarginParseError, parse_margin_value);
+
+/// Parses the value of one side of the `padding`: a pixel value or a
+/// percentage of the width of the containing block, i.e. `10%`
+pub fn parse_padding_value<'a>(input: &'a str)
+-> Result<PixelValue, PixelParseError<'a>>
+{
+    let input = input.trim();
+
+    if input.ends_with('%') {
+        let number_str = input[..input.len() - 1].trim();
+        let number = number_str.parse::<f32>().map_err(|e| PixelParseError::ValueParseErr(e, number_str.to_string()))?;
+        Ok(PixelValue::percent(number))
+    } else {
+        parse_pixel_value(input)
+    }
+}
+
+/// Parses the value of one side of the `margin`: the same values as for
+/// the `padding` (see `parse_padding_value`), or `auto`
+pub fn parse_margin_value<'a>(input: &'a str)
+-> Result<PixelValue, PixelParseError<'a>>
+{
+    match input.trim() {
+        "auto" => Ok(PixelValue::auto()),
+        other => parse_padding_value(other),
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum LayoutPaddingParseError<'a> {
@@ -1011,10 +1361,18 @@ impl_from!(PixelParseError<'a>, LayoutPaddingParseError::PixelParseError);
 /// "10px 10px"
 pub fn parse_layout_padding<'a>(input: &'a str)
 -> Result<LayoutPadding, LayoutPaddingParseError>
+{
+    parse_four_pixel_values(input, parse_padding_value)
+}
+
+/// Parses the one to four values of the `padding` and `margin` shorthands,
+/// `parse_value` parses the value of a single side
+fn parse_four_pixel_values<'a>(input: &'a str, parse_value: fn(&'a str) -> Result<PixelValue, PixelParseError<'a>>)
+-> Result<LayoutPadding, LayoutPaddingParseError<'a>>
 {
     let mut input_iter = input.split_whitespace();
-    let first = parse_pixel_value(input_iter.next().ok_or(LayoutPaddingParseError::TooFewValues)?)?;
-    let second = parse_pixel_value(match input_iter.next() {
+    let first = parse_value(input_iter.next().ok_or(LayoutPaddingParseError::TooFewValues)?)?;
+    let second = parse_value(match input_iter.next() {
         Some(s) => s,
         None => return Ok(LayoutPadding {
             top: Some(first),
@@ -1023,7 +1381,7 @@ pub fn parse_layout_padding<'a>(input: &'a str)
             right: Some(first),
         }),
     })?;
-    let third = parse_pixel_value(match input_iter.next() {
+    let third = parse_value(match input_iter.next() {
         Some(s) => s,
         None => return Ok(LayoutPadding {
             top: Some(first),
@@ -1032,7 +1390,7 @@ pub fn parse_layout_padding<'a>(input: &'a str)
             right: Some(second),
         }),
     })?;
-    let fourth = parse_pixel_value(match input_iter.next() {
+    let fourth = parse_value(match input_iter.next() {
         Some(s) => s,
         None => return Ok(LayoutPadding {
             top: Some(first),
@@ -1069,10 +1427,20 @@ impl_display!{ LayoutMarginParseError<'a>, {
 
 impl_from!(PixelParseError<'a>, LayoutMarginParseError::PixelParseError);
 
+impl<'a> From<LayoutPaddingParseError<'a>> for LayoutMarginParseError<'a> {
+    fn from(e: LayoutPaddingParseError<'a>) -> Self {
+        match e {
+            LayoutPaddingParseError::PixelParseError(e) => LayoutMarginParseError::PixelParseError(e),
+            LayoutPaddingParseError::TooManyValues => LayoutMarginParseError::TooManyValues,
+            LayoutPaddingParseError::TooFewValues => LayoutMarginParseError::TooFewValues,
+        }
+    }
+}
+
 pub fn parse_layout_margin<'a>(input: &'a str)
 -> Result<LayoutMargin, LayoutMarginParseError>
 {
-    match parse_layout_padding(input) {
+    match parse_four_pixel_values(input, parse_margin_value) {
         Ok(padding) => {
             Ok(LayoutMargin {
                 top: padding.top,
@@ -1081,13 +1449,59 @@ pub fn parse_layout_margin<'a>(input: &'a str)
                 bottom: padding.bottom,
             })
         },
-        Err(LayoutPaddingParseError::PixelParseError(e)) => Err(e.into()),
-        Err(LayoutPaddingParseError::TooManyValues) => Err(LayoutMarginParseError::TooManyValues),
-        Err(LayoutPaddingParseError::TooFewValues) => Err(LayoutMarginParseError::TooFewValues),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Parses the values of the `{padding,margin}-{inline,block}` shorthands: either
+/// one value for both sides or two values for the start and the end side
+fn parse_start_end_pixel_values<'a>(input: &'a str, parse_value: fn(&'a str) -> Result<PixelValue, PixelParseError<'a>>)
+-> Result<(PixelValue, PixelValue), LayoutPaddingParseError<'a>>
+{
+    let mut input_iter = input.split_whitespace();
+    let start = parse_value(input_iter.next().ok_or(LayoutPaddingParseError::TooFewValues)?)?;
+    let end = match input_iter.next() {
+        Some(s) => parse_value(s)?,
+        None => start,
+    };
+
+    if input_iter.next().is_some() {
+        return Err(LayoutPaddingParseError::TooManyValues);
     }
+
+    Ok((start, end))
+}
+
+pub fn parse_layout_padding_inline<'a>(input: &'a str)
+-> Result<LayoutPaddingInline, LayoutPaddingParseError>
+{
+    let (start, end) = parse_start_end_pixel_values(input, parse_padding_value)?;
+    Ok(LayoutPaddingInline { start: Some(start), end: Some(end) })
+}
+
+pub fn parse_layout_padding_block<'a>(input: &'a str)
+-> Result<LayoutPadding, LayoutPaddingParseError>
+{
+    let (start, end) = parse_start_end_pixel_values(input, parse_padding_value)?;
+    Ok(LayoutPadding { top: Some(start), bottom: Some(end), .. Default::default() })
+}
+
+pub fn parse_layout_margin_inline<'a>(input: &'a str)
+-> Result<LayoutMarginInline, LayoutMarginParseError>
+{
+    let (start, end) = parse_start_end_pixel_values(input, parse_margin_value)?;
+    Ok(LayoutMarginInline { start: Some(start), end: Some(end) })
+}
+
+pub fn parse_layout_margin_block<'a>(input: &'a str)
+-> Result<LayoutMargin, LayoutMarginParseError>
+{
+    let (start, end) = parse_start_end_pixel_values(input, parse_margin_value)?;
+    Ok(LayoutMargin { top: Some(start), bottom: Some(end), .. Default::default() })
 }
 
 parse_tblr!(border_parser, StyleBorder, CssBorderParseError, parse_css_border);
+parse_start_end!(border_inline_parser, StyleBorderInline, CssBorderParseError, parse_css_border);
 
 const DEFAULT_BORDER_COLOR: ColorU = ColorU { r: 0, g: 0, b: 0, a: 255 };
 // Default border thickness on the web seems to be 3px
@@ -1106,6 +1520,15 @@ fn advance_until_next_char(iter: &mut CharIndices) -> Option<usize> {
     Some(next_char.0)
 }
 
+/// Parses an `outline`, which has the same syntax as a border, i.e. `"2px solid blue"` or `"none"`
+pub fn parse_style_outline<'a>(input: &'a str)
+-> Result<StyleOutline, CssBorderParseError<'a>>
+{
+    parse_css_border(input).map(StyleOutline)
+}
+
+typed_pixel_value_parser!(parse_style_outline_offset, StyleOutlineOffset);
+
 /// Advances a CharIndices iterator until the next space is encountered
 fn take_until_next_whitespace(iter: &mut CharIndices) -> Option<usize> {
     let mut next_char = iter.next()?;
@@ -1184,11 +1607,34 @@ multi_type_parser!(parse_border_style, BorderStyle,
 
 parse_tblr!(box_shadow_parser, StyleBoxShadow, CssShadowParseError, parse_css_box_shadow);
 
+/// Parses a comma-separated list of box shadows, such as
+/// `0px 1px 3px rgba(0, 0, 0, 0.12), 0px 1px 2px rgba(0, 0, 0, 0.24)`.
+/// `none` returns an empty list.
+pub fn parse_css_box_shadow_list<'a>(input: &'a str)
+-> Result<Vec<BoxShadowPreDisplayItem>, CssShadowParseError<'a>>
+{
+    let comma_separated_items = split_string_respect_comma(input.trim());
+    let mut box_shadows = Vec::with_capacity(comma_separated_items.len());
+
+    for item in comma_separated_items.iter().map(|item| item.trim()) {
+        match parse_css_box_shadow(item)? {
+            Some(box_shadow) => box_shadows.push(box_shadow),
+            // "none" can't be combined with other shadows
+            None if comma_separated_items.len() == 1 => { },
+            None => return Err(CssShadowParseError::InvalidSingleStatement(item)),
+        }
+    }
+
+    Ok(box_shadows)
+}
+
 /// Parses a CSS box-shadow
 pub fn parse_css_box_shadow<'a>(input: &'a str)
 -> Result<Option<BoxShadowPreDisplayItem>, CssShadowParseError<'a>>
 {
-    let mut input_iter = input.split_whitespace();
+    // Splitting the input by whitespace doesn't work since rgba() might contain spaces
+    let components = split_whitespace_respect_braces(input);
+    let mut input_iter = components.iter().cloned();
     let count = input_iter.clone().count();
 
     let mut box_shadow = BoxShadowPreDisplayItem {
@@ -1303,6 +1749,7 @@ pub enum CssBackgroundParseError<'a> {
     UnclosedGradient(&'a str),
     NoDirection(&'a str),
     TooFewGradientStops(&'a str),
+    InvalidColorHint(&'a str),
     DirectionParseError(CssDirectionParseError<'a>),
     GradientParseError(CssGradientStopParseError<'a>),
     ShapeParseError(CssShapeParseError<'a>),
@@ -1317,6 +1764,7 @@ impl_display!{ CssBackgroundParseError<'a>, {
     UnclosedGradient(val) => format!("Unclosed gradient: \"{}\"", val),
     NoDirection(val) => format!("Gradient has no direction: \"{}\"", val),
     TooFewGradientStops(val) => format!("Failed to parse gradient due to too few gradient steps: \"{}\"", val),
+    InvalidColorHint(val) => format!("Color hint has to be placed between two gradient stops: \"{}\"", val),
     DirectionParseError(e) => format!("Failed to parse gradient direction: \"{}\"", e),
     GradientParseError(e) => format!("Failed to parse gradient: {}", e),
     ShapeParseError(e) => format!("Failed to parse shape of radial gradient: {}", e),
@@ -1337,7 +1785,8 @@ pub fn parse_style_background<'a>(input: &'a str)
 {
     match parse_parentheses(input, &[
         "none", "linear-gradient", "repeating-linear-gradient",
-        "radial-gradient", "repeating-radial-gradient", "image",
+        "radial-gradient", "repeating-radial-gradient",
+        "conic-gradient", "repeating-conic-gradient", "image",
     ]) {
         Ok((background_type, brace_contents)) => {
             let background_type = match background_type {
@@ -1346,6 +1795,8 @@ pub fn parse_style_background<'a>(input: &'a str)
                 "repeating-linear-gradient" => BackgroundType::RepeatingLinearGradient,
                 "radial-gradient" => BackgroundType::RadialGradient,
                 "repeating-radial-gradient" => BackgroundType::RepeatingRadialGradient,
+                "conic-gradient" => BackgroundType::ConicGradient,
+                "repeating-conic-gradient" => BackgroundType::RepeatingConicGradient,
                 "image" => BackgroundType::Image,
                 _ => { return Ok(StyleBackground::NoBackground); /* unreachable */ },
             };
@@ -1391,19 +1842,9 @@ fn skip_next_braces(input: &str, target_char: char) -> Option<(usize, bool)> {
     }
 }
 
-// parses a single gradient such as "to right, 50px"
-pub fn parse_gradient<'a>(input: &'a str, background_type: BackgroundType)
--> Result<StyleBackground, CssBackgroundParseError<'a>>
-{
-    let input = input.trim();
-
-    match background_type {
-        BackgroundType::Image => { return Ok(StyleBackground::Image(parse_image(input)?)); }
-        BackgroundType::Color => { return Ok(StyleBackground::Color(parse_css_color(input)?)); }
-        _ => { },
-    }
+/// Splits the input by ",", but ignores commas inside of braces, i.e. in `rgba(0, 0, 0, 0)`
+fn split_string_respect_comma(input: &str) -> Vec<&str> {
 
-    // Splitting the input by "," doesn't work since rgba() might contain commas
     let mut comma_separated_items = Vec::<&str>::new();
     let mut current_input = &input[..];
 
@@ -1426,6 +1867,54 @@ pub fn parse_gradient<'a>(input: &'a str, background_type: BackgroundType)
         }
     }
 
+    comma_separated_items
+}
+
+/// Splits the input by whitespace, but ignores whitespace inside of braces
+fn split_whitespace_respect_braces(input: &str) -> Vec<&str> {
+
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut item_start = None;
+
+    for (idx, ch) in input.char_indices() {
+        match ch {
+            '(' => { depth += 1; },
+            ')' => { depth -= 1; },
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(start) = item_start.take() {
+                    items.push(&input[start..idx]);
+                }
+                continue;
+            },
+            _ => { },
+        }
+        if item_start.is_none() {
+            item_start = Some(idx);
+        }
+    }
+
+    if let Some(start) = item_start {
+        items.push(&input[start..]);
+    }
+
+    items
+}
+
+// parses a single gradient such as "to right, 50px"
+pub fn parse_gradient<'a>(input: &'a str, background_type: BackgroundType)
+-> Result<StyleBackground, CssBackgroundParseError<'a>>
+{
+    let input = input.trim();
+
+    match background_type {
+        BackgroundType::Image => { return Ok(StyleBackground::Image(parse_image(input)?)); }
+        BackgroundType::Color => { return Ok(StyleBackground::Color(parse_css_color(input)?)); }
+        _ => { },
+    }
+
+    let comma_separated_items = split_string_respect_comma(input);
+
     let mut brace_iterator = comma_separated_items.iter();
     let mut gradient_stop_count = brace_iterator.clone().count();
 
@@ -1439,9 +1928,13 @@ pub fn parse_gradient<'a>(input: &'a str, background_type: BackgroundType)
     let mut shape = Shape::Ellipse;
     // default gradient: from top to bottom
     let mut direction = Direction::FromTo(DirectionCorner::Top, DirectionCorner::Bottom);
+    // default conic gradient: starting at the top, around the center
+    let mut conic_angle = FloatValue::new(0.0);
+    let mut conic_center = (PercentageValue::new(50.0), PercentageValue::new(50.0));
 
     let mut first_is_direction = false;
     let mut first_is_shape = false;
+    let mut first_is_conic_origin = false;
 
     let is_linear_gradient = background_type == BackgroundType::LinearGradient ||
                              background_type == BackgroundType::RepeatingLinearGradient;
@@ -1449,6 +1942,9 @@ pub fn parse_gradient<'a>(input: &'a str, background_type: BackgroundType)
     let is_radial_gradient = background_type == BackgroundType::RadialGradient ||
                              background_type == BackgroundType::RepeatingRadialGradient;
 
+    let is_conic_gradient = background_type == BackgroundType::ConicGradient ||
+                            background_type == BackgroundType::RepeatingConicGradient;
+
     if is_linear_gradient {
         if let Ok(dir) = parse_direction(first_brace_item) {
             direction = dir;
@@ -1463,8 +1959,18 @@ pub fn parse_gradient<'a>(input: &'a str, background_type: BackgroundType)
         }
     }
 
+    if is_conic_gradient {
+        if let Some((angle, center)) = parse_conic_gradient_origin(first_brace_item) {
+            conic_angle = angle;
+            conic_center = center;
+            first_is_conic_origin = true;
+        }
+    }
+
     let mut first_item_doesnt_count = false;
-    if (is_linear_gradient && first_is_direction) || (is_radial_gradient && first_is_shape) {
+    if (is_linear_gradient && first_is_direction) ||
+       (is_radial_gradient && first_is_shape) ||
+       (is_conic_gradient && first_is_conic_origin) {
         gradient_stop_count -= 1; // first item is not a gradient stop
         first_item_doesnt_count = true;
     }
@@ -1473,16 +1979,35 @@ pub fn parse_gradient<'a>(input: &'a str, background_type: BackgroundType)
         return Err(CssBackgroundParseError::TooFewGradientStops(input));
     }
 
+    let first_stop = if first_item_doesnt_count { None } else { Some(first_brace_item) };
+
     let mut color_stops = Vec::<GradientStopPre>::with_capacity(gradient_stop_count);
-    if !first_item_doesnt_count {
-        color_stops.push(parse_gradient_stop(first_brace_item)?);
+    // (index of the stop after the hint, position of the hint)
+    let mut color_hints = Vec::<(usize, PercentageValue)>::new();
+
+    for stop in first_stop.into_iter().chain(brace_iterator) {
+        // A position without a color is a color hint, i.e. the "20%" in "red, 20%, blue"
+        if let Ok(hint) = parse_gradient_stop_position(stop.trim(), is_conic_gradient) {
+            let hint_follows_hint = color_hints.last().map(|(index, _)| *index) == Some(color_stops.len());
+            if color_stops.is_empty() || hint_follows_hint {
+                return Err(CssBackgroundParseError::InvalidColorHint(stop));
+            }
+            color_hints.push((color_stops.len(), hint));
+        } else {
+            color_stops.extend(parse_gradient_stops(stop, is_conic_gradient)?);
+        }
     }
 
-    for stop in brace_iterator {
-        color_stops.push(parse_gradient_stop(stop)?);
+    if color_stops.len() < 2 {
+        return Err(CssBackgroundParseError::TooFewGradientStops(input));
+    }
+
+    if color_hints.last().map(|(index, _)| *index) == Some(color_stops.len()) {
+        return Err(CssBackgroundParseError::InvalidColorHint(input));
     }
 
     normalize_color_stops(&mut color_stops);
+    apply_color_hints(&mut color_stops, &color_hints);
 
     match background_type {
         BackgroundType::LinearGradient => {
@@ -1513,10 +2038,138 @@ pub fn parse_gradient<'a>(input: &'a str, background_type: BackgroundType)
                 stops: color_stops,
             }))
         },
+        BackgroundType::ConicGradient => {
+            Ok(StyleBackground::ConicGradient(ConicGradient {
+                angle: conic_angle,
+                center: conic_center,
+                extend_mode: ExtendMode::Clamp,
+                stops: color_stops,
+            }))
+        },
+        BackgroundType::RepeatingConicGradient => {
+            Ok(StyleBackground::ConicGradient(ConicGradient {
+                angle: conic_angle,
+                center: conic_center,
+                extend_mode: ExtendMode::Repeat,
+                stops: color_stops,
+            }))
+        },
         BackgroundType::Image | BackgroundType::Color => unreachable!(),
     }
 }
 
+/// Parses the `from 90deg at 25% 50%` part of a conic gradient (both parts are optional),
+/// returns `None` if the input isn't a conic gradient origin (i.e. if it is a gradient stop)
+fn parse_conic_gradient_origin(input: &str) -> Option<(FloatValue, (PercentageValue, PercentageValue))> {
+
+    let mut angle = 0.0;
+    let mut center = (PercentageValue::new(50.0), PercentageValue::new(50.0));
+
+    let mut tokens = input.split_whitespace().peekable();
+    let mut is_origin = false;
+
+    if tokens.peek() == Some(&"from") {
+        tokens.next();
+        angle = parse_angle(tokens.next()?)?;
+        is_origin = true;
+    }
+
+    if tokens.peek() == Some(&"at") {
+        tokens.next();
+        let positions = tokens.by_ref().collect::<Vec<&str>>();
+        center = match positions.as_slice() {
+            // "at top" / "at bottom" only set the vertical position
+            [y @ "top"] | [y @ "bottom"] => (PercentageValue::new(50.0), parse_conic_gradient_position(y)?),
+            [x] => (parse_conic_gradient_position(x)?, PercentageValue::new(50.0)),
+            [x, y] => (parse_conic_gradient_position(x)?, parse_conic_gradient_position(y)?),
+            _ => return None,
+        };
+        is_origin = true;
+    }
+
+    if !is_origin || tokens.next().is_some() {
+        return None;
+    }
+
+    // clamp the degree to 360 (so 410deg = 50deg)
+    let angle = ((angle % 360.0) + 360.0) % 360.0;
+    Some((FloatValue::new(angle), center))
+}
+
+/// Parses one coordinate of the center of a conic gradient, i.e. `"25%"` or `"left"`
+fn parse_conic_gradient_position(input: &str) -> Option<PercentageValue> {
+    match input {
+        "left" | "top" => Some(PercentageValue::new(0.0)),
+        "center" => Some(PercentageValue::new(50.0)),
+        "right" | "bottom" => Some(PercentageValue::new(100.0)),
+        other => parse_percentage(other).ok(),
+    }
+}
+
+/// Parses an angle such as `"90deg"`, `"100grad"`, `"1.57rad"` or `"0.25turn"` into degrees
+fn parse_angle(input: &str) -> Option<f32> {
+    use std::f32::consts::PI;
+    if input.ends_with("deg") {
+        input[..input.len() - 3].parse::<f32>().ok()
+    } else if input.ends_with("grad") {
+        input[..input.len() - 4].parse::<f32>().ok().map(|grad| grad / 400.0 * 360.0)
+    } else if input.ends_with("rad") {
+        input[..input.len() - 3].parse::<f32>().ok().map(|rad| rad * 180.0 / PI)
+    } else if input.ends_with("turn") {
+        input[..input.len() - 4].parse::<f32>().ok().map(|turn| turn * 360.0)
+    } else {
+        None
+    }
+}
+
+/// Inserts the stops that approximate the non-linear transitions of color hints,
+/// (WebRender can only interpolate linearly between two stops). Has to be called
+/// after the color stops have been normalized.
+///
+/// The hints are given as `(index of the stop after the hint, position of the hint)`.
+fn apply_color_hints(color_stops: &mut Vec<GradientStopPre>, color_hints: &[(usize, PercentageValue)]) {
+
+    // Number of stops that are inserted to approximate the transition curve
+    const HINT_STOPS: usize = 9;
+
+    // Iterate in reverse, so that inserting stops doesn't invalidate the indices of the other hints
+    for (index, hint) in color_hints.iter().rev() {
+
+        let (start, end) = (color_stops[*index - 1], color_stops[*index]);
+        let start_offset = start.offset.map(|o| o.get()).unwrap_or(0.0);
+        let end_offset = end.offset.map(|o| o.get()).unwrap_or(100.0);
+
+        if end_offset <= start_offset {
+            continue;
+        }
+
+        // Position of the hint relative to the two stops, a hint in the middle is a linear transition
+        let hint_position = ((hint.get() - start_offset) / (end_offset - start_offset)).max(0.0).min(1.0);
+
+        let inserted_stops = if hint_position <= 0.0 {
+            vec![GradientStopPre { offset: start.offset, color: end.color }]
+        } else if hint_position >= 1.0 {
+            vec![GradientStopPre { offset: end.offset, color: start.color }]
+        } else if (hint_position - 0.5).abs() < 0.001 {
+            continue;
+        } else {
+            // The color at the hint is a 50/50 mix of both colors: color(x) = x ^ (ln(0.5) / ln(hint))
+            let exponent = 0.5_f32.ln() / hint_position.ln();
+            (1..=HINT_STOPS).map(|i| {
+                let x = i as f32 / (HINT_STOPS + 1) as f32;
+                GradientStopPre {
+                    offset: Some(PercentageValue::new(start_offset + x * (end_offset - start_offset))),
+                    color: start.color.interpolate(&end.color, x.powf(exponent)),
+                }
+            }).collect()
+        };
+
+        for (i, stop) in inserted_stops.into_iter().enumerate() {
+            color_stops.insert(*index + i, stop);
+        }
+    }
+}
+
 // Normalize the percentages of the parsed color stops
 pub fn normalize_color_stops(color_stops: &mut Vec<GradientStopPre>) {
 
@@ -1540,6 +2193,12 @@ pub fn normalize_color_stops(color_stops: &mut Vec<GradientStopPre>) {
                     continue 'outer;
                 }
 
+                // The last stop is placed at 100% if it has no position, i.e. "red, blue 50%, lime"
+                if i != 0 && color_stop_len - i == 1 {
+                    next[0].offset = Some(PercentageValue::new(100.0));
+                    continue 'outer;
+                }
+
                 let mut next_count: u32 = 0;
                 let mut next_value = None;
 
@@ -1682,12 +2341,55 @@ pub fn parse_gradient_stop<'a>(input: &'a str)
     Ok(GradientStopPre { offset, color: color })
 }
 
-// parses "5%" -> 5
-pub fn parse_percentage(input: &str)
--> Result<PercentageValue, PercentageParseError>
+/// Parses a gradient stop with zero, one or two positions, i.e. `"red"`, `"red 5%"` or
+/// `"red 5% 20%"` (which is the same as `"red 5%, red 20%"`). If `allow_angles` is set
+/// (for conic gradients), the positions can also be angles such as `"90deg"`.
+pub fn parse_gradient_stops<'a>(input: &'a str, allow_angles: bool)
+-> Result<Vec<GradientStopPre>, CssGradientStopParseError<'a>>
 {
-    let percent_location = input.rfind('%').ok_or(PercentageParseError::NoPercentSign)?;
-    let input = &input[..percent_location];
+    let input = input.trim();
+
+    // Color functions such as "rgba(...)" can contain spaces, so the positions start after the closing brace
+    let (color_str, positions_str) = match input.rfind(')') {
+        Some(closing_brace) => input.split_at(closing_brace + 1),
+        None => match input.find(char::is_whitespace) {
+            Some(first_ws) => input.split_at(first_ws),
+            None => (input, ""),
+        },
+    };
+
+    let color = parse_css_color(color_str)?;
+    let positions = positions_str
+        .split_whitespace()
+        .map(|position| parse_gradient_stop_position(position, allow_angles))
+        .collect::<Result<Vec<PercentageValue>, _>>()?;
+
+    match positions.len() {
+        0 => Ok(vec![GradientStopPre { offset: None, color }]),
+        1 | 2 => Ok(positions.into_iter().map(|offset| GradientStopPre { offset: Some(offset), color }).collect()),
+        _ => Err(CssGradientStopParseError::Error(input)),
+    }
+}
+
+/// Parses the position of a gradient stop or color hint, i.e. `"5%"`
+/// or - if `allow_angles` is set - `"90deg"` (= `"25%"`)
+fn parse_gradient_stop_position<'a>(input: &'a str, allow_angles: bool)
+-> Result<PercentageValue, CssGradientStopParseError<'a>>
+{
+    if allow_angles {
+        if let Some(angle) = parse_angle(input) {
+            return Ok(PercentageValue::new(angle / 360.0 * 100.0));
+        }
+    }
+    parse_percentage(input).map_err(|e| CssGradientStopParseError::Percentage(e))
+}
+
+// parses "5%" -> 5
+pub fn parse_percentage(input: &str)
+-> Result<PercentageValue, PercentageParseError>
+{
+    let percent_location = input.rfind('%').ok_or(PercentageParseError::NoPercentSign)?;
+    let input = &input[..percent_location];
     Ok(PercentageValue::new(input.parse::<f32>()?))
 }
 
@@ -1936,6 +2638,33 @@ pub fn parse_layout_flex_shrink<'a>(input: &'a str) -> Result<LayoutFlexShrink,
     }
 }
 
+/// Parses a `flex-basis` such as `"200px"`, `"auto"` or `"content"`
+pub fn parse_layout_flex_basis<'a>(input: &'a str)
+-> Result<LayoutFlexBasis, PixelParseError<'a>>
+{
+    match input.trim() {
+        "auto" => Ok(LayoutFlexBasis::Auto),
+        "content" => Ok(LayoutFlexBasis::Content),
+        other => parse_pixel_value(other).map(LayoutFlexBasis::Exact),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderParseError<'a> {
+    ParseInt(ParseIntError, &'a str),
+}
+
+impl_display!{OrderParseError<'a>, {
+    ParseInt(e, orig_str) => format!("order: Could not parse integer value: \"{}\" - Error: \"{}\"", orig_str, e),
+}}
+
+pub fn parse_layout_order<'a>(input: &'a str) -> Result<LayoutOrder, OrderParseError<'a>> {
+    match input.trim().parse::<i32>() {
+        Ok(o) => Ok(LayoutOrder(o)),
+        Err(e) => Err(OrderParseError::ParseInt(e, input)),
+    }
+}
+
 pub fn parse_style_tab_width(input: &str)
 -> Result<StyleTabWidth, PercentageParseError>
 {
@@ -1948,6 +2677,303 @@ pub fn parse_style_line_height(input: &str)
     parse_percentage_value(input).and_then(|e| Ok(StyleLineHeight(e)))
 }
 
+#[derive(Clone, PartialEq)]
+pub enum CssStyleTransformParseError<'a> {
+    InvalidFunction(ParenthesisParseError<'a>),
+    WrongNumberOfArguments(&'a str),
+    InvalidNumber(ParseFloatError, &'a str),
+    InvalidAngle(&'a str),
+    PixelParseError(PixelParseError<'a>),
+    InvalidOrigin(&'a str),
+}
+
+impl_debug_as_display!(CssStyleTransformParseError<'a>);
+impl_display!{ CssStyleTransformParseError<'a>, {
+    InvalidFunction(e) => format!("Invalid transform function: {}", e),
+    WrongNumberOfArguments(input) => format!("Wrong number of arguments: \"{}\"", input),
+    InvalidNumber(e, input) => format!("Could not parse \"{}\" as floating-point value: \"{}\"", input, e),
+    InvalidAngle(input) => format!("Expected angle such as \"45deg\", got: \"{}\"", input),
+    PixelParseError(e) => format!("{}", e),
+    InvalidOrigin(input) => format!("Invalid transform-origin: \"{}\"", input),
+}}
+
+impl_from!(ParenthesisParseError<'a>, CssStyleTransformParseError::InvalidFunction);
+impl_from!(PixelParseError<'a>, CssStyleTransformParseError::PixelParseError);
+
+/// Parses a `transform` such as `"translate(10px, 20px) rotate(45deg)"` or `"none"`
+pub fn parse_style_transform<'a>(input: &'a str)
+-> Result<StyleTransform, CssStyleTransformParseError<'a>>
+{
+    let input = input.trim();
+
+    if input == "none" {
+        return Ok(StyleTransform::default());
+    }
+
+    split_whitespace_respect_braces(input).into_iter()
+        .map(parse_transform_function)
+        .collect::<Result<Vec<_>, _>>()
+        .map(StyleTransform)
+}
+
+/// Parses a single function of a `transform`, i.e. `"rotate(45deg)"`
+fn parse_transform_function<'a>(input: &'a str)
+-> Result<TransformFunction, CssStyleTransformParseError<'a>>
+{
+    use azul_css::TransformFunction::*;
+
+    let (function, arguments) = parse_parentheses(input, &[
+        "matrix", "translate", "translateX", "translateY", "translateZ", "scale", "scaleX",
+        "scaleY", "rotate", "rotateX", "rotateY", "rotateZ", "skew", "skewX", "skewY", "perspective",
+    ])?;
+
+    let arguments = arguments.split(',').map(|argument| argument.trim()).collect::<Vec<&str>>();
+
+    let number = |input: &'a str| input.parse::<f32>()
+        .map(FloatValue::new)
+        .map_err(|e| CssStyleTransformParseError::InvalidNumber(e, input));
+
+    // A unitless zero is a valid angle
+    let angle = |input: &'a str| if input == "0" {
+        Ok(FloatValue::new(0.0))
+    } else {
+        parse_angle(input).map(FloatValue::new).ok_or(CssStyleTransformParseError::InvalidAngle(input))
+    };
+
+    let zero_px = PixelValue::px(0.0);
+    let zero = FloatValue::const_new(0);
+    let one = FloatValue::const_new(1);
+
+    match (function, arguments.as_slice()) {
+        ("matrix", [a, b, c, d, tx, ty]) => Ok(Matrix([
+            number(a)?, number(b)?, number(c)?, number(d)?, number(tx)?, number(ty)?,
+        ])),
+        ("translate", [x]) => Ok(Translate(parse_pixel_value(x)?, zero_px)),
+        ("translate", [x, y]) => Ok(Translate(parse_pixel_value(x)?, parse_pixel_value(y)?)),
+        ("translateX", [x]) => Ok(Translate(parse_pixel_value(x)?, zero_px)),
+        ("translateY", [y]) => Ok(Translate(zero_px, parse_pixel_value(y)?)),
+        ("translateZ", [z]) => Ok(TranslateZ(parse_pixel_value(z)?)),
+        ("scale", [s]) => { let s = number(s)?; Ok(Scale(s, s)) },
+        ("scale", [x, y]) => Ok(Scale(number(x)?, number(y)?)),
+        ("scaleX", [x]) => Ok(Scale(number(x)?, one)),
+        ("scaleY", [y]) => Ok(Scale(one, number(y)?)),
+        ("rotate", [a]) | ("rotateZ", [a]) => Ok(Rotate(angle(a)?)),
+        ("rotateX", [a]) => Ok(RotateX(angle(a)?)),
+        ("rotateY", [a]) => Ok(RotateY(angle(a)?)),
+        ("skew", [x]) => Ok(Skew(angle(x)?, zero)),
+        ("skew", [x, y]) => Ok(Skew(angle(x)?, angle(y)?)),
+        ("skewX", [x]) => Ok(Skew(angle(x)?, zero)),
+        ("skewY", [y]) => Ok(Skew(zero, angle(y)?)),
+        ("perspective", [d]) => Ok(Perspective(parse_pixel_value(d)?)),
+        _ => Err(CssStyleTransformParseError::WrongNumberOfArguments(input)),
+    }
+}
+
+/// Parses a `transform-origin` such as `"left top"`, `"50% 100%"` or `"10px 20px"`
+pub fn parse_style_transform_origin<'a>(input: &'a str)
+-> Result<StyleTransformOrigin, CssStyleTransformParseError<'a>>
+{
+    let center = TransformOriginValue::Percentage(PercentageValue::new(50.0));
+    let is_vertical_keyword = |input: &str| input == "top" || input == "bottom";
+    let is_horizontal_keyword = |input: &str| input == "left" || input == "right";
+
+    let values = input.split_whitespace().collect::<Vec<&str>>();
+
+    let (x, y) = match values.as_slice() {
+        // "top" / "bottom" only set the vertical position
+        [y] if is_vertical_keyword(y) => (center, parse_transform_origin_value(y)?),
+        [x] => (parse_transform_origin_value(x)?, center),
+        // "top left" is the same as "left top"
+        [y, x] if is_vertical_keyword(y) || is_horizontal_keyword(x) => {
+            (parse_transform_origin_value(x)?, parse_transform_origin_value(y)?)
+        },
+        [x, y] => (parse_transform_origin_value(x)?, parse_transform_origin_value(y)?),
+        _ => return Err(CssStyleTransformParseError::InvalidOrigin(input)),
+    };
+
+    Ok(StyleTransformOrigin { x, y })
+}
+
+/// Parses one coordinate of the `transform-origin`, i.e. `"25%"`, `"10px"` or `"left"`
+fn parse_transform_origin_value<'a>(input: &'a str)
+-> Result<TransformOriginValue, CssStyleTransformParseError<'a>>
+{
+    match input {
+        "left" | "top" => Ok(TransformOriginValue::Percentage(PercentageValue::new(0.0))),
+        "center" => Ok(TransformOriginValue::Percentage(PercentageValue::new(50.0))),
+        "right" | "bottom" => Ok(TransformOriginValue::Percentage(PercentageValue::new(100.0))),
+        other if other.ends_with('%') => parse_percentage(other)
+            .map(TransformOriginValue::Percentage)
+            .map_err(|_| CssStyleTransformParseError::InvalidOrigin(input)),
+        other => Ok(TransformOriginValue::Pixels(parse_pixel_value(other)?)),
+    }
+}
+
+/// Parses a `perspective` such as `"500px"` or `"none"`
+pub fn parse_style_perspective<'a>(input: &'a str)
+-> Result<StylePerspective, PixelParseError<'a>>
+{
+    match input.trim() {
+        "none" => Ok(StylePerspective(None)),
+        other => parse_pixel_value(other).map(|distance| StylePerspective(Some(distance))),
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum CssGridParseError<'a> {
+    /// Not a valid track size, i.e. `"1fx"`
+    InvalidTrackSize(&'a str),
+    /// Invalid `repeat()` function, i.e. `"repeat(0, 1fr)"`
+    InvalidRepeat(&'a str),
+    /// Not a valid `grid-column` / `grid-row` value, i.e. `"1 / 2 / 3"`
+    InvalidPlacement(&'a str),
+    /// More than two values for `grid-gap`
+    InvalidGap(&'a str),
+    PixelParseError(PixelParseError<'a>),
+}
+
+impl_debug_as_display!(CssGridParseError<'a>);
+impl_display!{ CssGridParseError<'a>, {
+    InvalidTrackSize(e) => format!("Invalid grid track size: \"{}\"", e),
+    InvalidRepeat(e) => format!("Invalid repeat(): \"{}\"", e),
+    InvalidPlacement(e) => format!("Invalid grid placement: \"{}\"", e),
+    InvalidGap(e) => format!("Invalid gap, expected one or two values: \"{}\"", e),
+    PixelParseError(e) => format!("{}", e),
+}}
+
+impl_from!(PixelParseError<'a>, CssGridParseError::PixelParseError);
+
+/// Parses a `grid-template-columns` or `grid-template-rows` value,
+/// such as `"200px 1fr 1fr"`, `"auto 25%"` or `"100px repeat(3, 1fr)"`
+pub fn parse_grid_track_list<'a>(input: &'a str)
+-> Result<Vec<GridTrackSize>, CssGridParseError<'a>>
+{
+    let mut tracks = Vec::new();
+
+    for item in split_whitespace_respect_braces(input) {
+        if item.starts_with("repeat(") {
+            let arguments = item["repeat(".len()..].trim_end_matches(')');
+            let comma = arguments.find(',').ok_or(CssGridParseError::InvalidRepeat(item))?;
+            let count = arguments[..comma].trim().parse::<usize>()
+                .ok()
+                .filter(|count| *count > 0)
+                .ok_or(CssGridParseError::InvalidRepeat(item))?;
+            let repeated = parse_grid_track_list(&arguments[(comma + 1)..])?;
+            for _ in 0..count {
+                tracks.extend_from_slice(&repeated);
+            }
+        } else {
+            tracks.push(parse_grid_track_size(item)?);
+        }
+    }
+
+    if tracks.is_empty() {
+        return Err(CssGridParseError::InvalidTrackSize(input));
+    }
+
+    Ok(tracks)
+}
+
+/// Parses a single grid track size, such as `"200px"`, `"25%"`, `"1fr"` or `"auto"`
+pub fn parse_grid_track_size<'a>(input: &'a str)
+-> Result<GridTrackSize, CssGridParseError<'a>>
+{
+    let input = input.trim();
+    if input == "auto" {
+        Ok(GridTrackSize::Auto)
+    } else if input.ends_with("fr") {
+        input[..(input.len() - 2)].parse::<f32>()
+            .ok()
+            .filter(|fraction| *fraction >= 0.0)
+            .map(|fraction| GridTrackSize::Fraction(FloatValue::new(fraction)))
+            .ok_or(CssGridParseError::InvalidTrackSize(input))
+    } else if input.ends_with('%') {
+        parse_percentage(input)
+            .map(GridTrackSize::Percentage)
+            .map_err(|_| CssGridParseError::InvalidTrackSize(input))
+    } else {
+        Ok(GridTrackSize::Fixed(parse_pixel_value(input)?))
+    }
+}
+
+/// Parses a `grid-gap` such as `"10px"` (rows and columns) or `"10px 20px"` (rows, columns)
+pub fn parse_layout_grid_gap<'a>(input: &'a str)
+-> Result<LayoutGridGap, CssGridParseError<'a>>
+{
+    parse_layout_gap(input).map(|LayoutGap { row, column }| LayoutGridGap { row, column })
+}
+
+/// Parses a `gap` such as `"10px"` (rows and columns) or `"10px 20px"` (rows, columns),
+/// same syntax as `grid-gap`
+pub fn parse_layout_gap<'a>(input: &'a str)
+-> Result<LayoutGap, CssGridParseError<'a>>
+{
+    let values = input.split_whitespace().collect::<Vec<&str>>();
+    match values.as_slice() {
+        [gap] => {
+            let gap = parse_pixel_value(gap)?;
+            Ok(LayoutGap { row: gap, column: gap })
+        },
+        [row, column] => Ok(LayoutGap { row: parse_pixel_value(row)?, column: parse_pixel_value(column)? }),
+        _ => Err(CssGridParseError::InvalidGap(input)),
+    }
+}
+
+typed_pixel_value_parser!(parse_layout_row_gap, LayoutRowGap);
+typed_pixel_value_parser!(parse_layout_column_gap, LayoutColumnGap);
+
+/// Parses a `grid-column` or `grid-row` value, such as `"2"`, `"1 / 3"`, `"span 2"` or `"1 / -1"`
+pub fn parse_grid_placement<'a>(input: &'a str)
+-> Result<GridPlacement, CssGridParseError<'a>>
+{
+    let mut lines = input.split('/');
+    let start = parse_grid_line(lines.next().unwrap_or(""), input)?;
+    let end = match lines.next() {
+        Some(end) => parse_grid_line(end, input)?,
+        None => GridLine::Auto,
+    };
+
+    if lines.next().is_some() {
+        return Err(CssGridParseError::InvalidPlacement(input));
+    }
+
+    Ok(GridPlacement { start, end })
+}
+
+fn parse_grid_line<'a>(line: &str, input: &'a str)
+-> Result<GridLine, CssGridParseError<'a>>
+{
+    let line = line.trim();
+    let invalid = || CssGridParseError::InvalidPlacement(input);
+
+    if line == "auto" {
+        Ok(GridLine::Auto)
+    } else if line.starts_with("span ") {
+        line["span ".len()..].trim().parse::<usize>()
+            .ok()
+            .filter(|span| *span > 0)
+            .map(GridLine::Span)
+            .ok_or_else(invalid)
+    } else {
+        line.parse::<isize>()
+            .ok()
+            .filter(|line| *line != 0)
+            .map(GridLine::Line)
+            .ok_or_else(invalid)
+    }
+}
+
+multi_type_parser!(parse_style_transform_style, StyleTransformStyle,
+                    ["flat", Flat],
+                    ["preserve-3d", Preserve3D]);
+
+/// Parses an opacity such as "0.5" or "50%"
+pub fn parse_style_opacity(input: &str)
+-> Result<StyleOpacity, PercentageParseError>
+{
+    parse_percentage_value(input).and_then(|e| Ok(StyleOpacity(e)))
+}
+
 typed_pixel_value_parser!(parse_style_font_size, StyleFontSize);
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -2002,6 +3028,51 @@ pub fn parse_style_font_family<'a>(input: &'a str) -> Result<StyleFontFamily, Cs
     })
 }
 
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum CssStyleContentParseError<'a> {
+    InvalidStyleContent(&'a str),
+    UnclosedQuotes(&'a str),
+}
+
+impl_display!{CssStyleContentParseError<'a>, {
+    InvalidStyleContent(val) => format!("Invalid content: \"{}\" - expected a quoted string or \"none\"", val),
+    UnclosedQuotes(val) => format!("Unclosed quotes: \"{}\"", val),
+}}
+
+/// Parses a `StyleContent` declaration from a `&str`. `none` and `normal`
+/// parse to an empty string, which doesn't generate a pseudo-element.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_style_content;
+/// # use azul_css::StyleContent;
+/// assert_eq!(parse_style_content("\" *\""), Ok(StyleContent(" *".into())));
+/// assert_eq!(parse_style_content("none"), Ok(StyleContent(String::new())));
+/// ```
+pub fn parse_style_content<'a>(input: &'a str) -> Result<StyleContent, CssStyleContentParseError<'a>> {
+    match input {
+        "none" | "normal" => Ok(StyleContent(String::new())),
+        _ => {
+            let quote = match input.chars().next() {
+                Some(c) if c == '"' || c == '\'' => c,
+                _ => return Err(CssStyleContentParseError::InvalidStyleContent(input)),
+            };
+            if input.len() < 2 || !input.ends_with(quote) {
+                return Err(CssStyleContentParseError::UnclosedQuotes(input));
+            }
+            let content = &input[1..(input.len() - 1)];
+            // Only a single string is supported, i.e. no `"a" "b"`
+            if content.contains(quote) {
+                return Err(CssStyleContentParseError::InvalidStyleContent(input));
+            }
+            Ok(StyleContent(content.into()))
+        },
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
 pub enum ParenthesisParseError<'a> {
     UnclosedBraces,
@@ -2064,6 +3135,170 @@ pub fn parse_parentheses<'a>(
     Ok((validated_stopword, &input[(first_open_brace + 1)..last_closing_brace]))
 }
 
+multi_type_parser!(parse_style_text_direction, StyleTextDirection,
+                    ["ltr", Ltr],
+                    ["rtl", Rtl]);
+
+multi_type_parser!(parse_style_white_space, StyleWhiteSpace,
+                    ["normal", Normal],
+                    ["nowrap", Nowrap]);
+
+multi_type_parser!(parse_style_text_overflow, StyleTextOverflow,
+                    ["clip", Clip],
+                    ["ellipsis", Ellipsis]);
+
+#[derive(Clone, PartialEq)]
+pub enum CssTextDecorationParseError<'a> {
+    /// Neither `none` nor one of `underline`, `overline` or `line-through` was given
+    MissingLine(&'a str),
+    ColorParseError(CssColorParseError<'a>),
+}
+impl_debug_as_display!(CssTextDecorationParseError<'a>);
+impl_display!{ CssTextDecorationParseError<'a>, {
+    MissingLine(e) => format!("Missing underline, overline or line-through: \"{}\"", e),
+    ColorParseError(e) => format!("Invalid color: {}", e),
+}}
+
+impl_from!(CssColorParseError<'a>, CssTextDecorationParseError::ColorParseError);
+
+/// Parses a `text-decoration` such as `underline`, `underline overline` or `line-through wavy red`:
+/// one or more lines, followed by an optional style and an optional color (which has to come last)
+pub fn parse_style_text_decoration<'a>(input: &'a str)
+-> Result<StyleTextDecoration, CssTextDecorationParseError<'a>>
+{
+    use self::CssTextDecorationParseError::*;
+
+    let input = input.trim();
+    let mut decoration = StyleTextDecoration::default();
+
+    if input == "none" {
+        return Ok(decoration);
+    }
+
+    let mut rest = input;
+
+    loop {
+        let rest_trimmed = rest.trim_start();
+        let word_end = rest_trimmed.find(char::is_whitespace).unwrap_or(rest_trimmed.len());
+        let word = &rest_trimmed[..word_end];
+        match word {
+            "underline" => decoration.underline = true,
+            "overline" => decoration.overline = true,
+            "line-through" => decoration.line_through = true,
+            _ => match parse_text_decoration_style(word) {
+                Ok(style) => decoration.style = style,
+                Err(_) => break,
+            },
+        }
+        rest = &rest_trimmed[word_end..];
+    }
+
+    if !decoration.has_lines() {
+        return Err(MissingLine(input));
+    }
+
+    // Everything after the keywords is the color, i.e. "rgb(255, 0, 0)" can contain spaces
+    let rest = rest.trim();
+    if !rest.is_empty() {
+        decoration.color = Some(parse_css_color(rest)?);
+    }
+
+    Ok(decoration)
+}
+
+#[derive(Clone, PartialEq)]
+pub enum CssTabStopsParseError<'a> {
+    /// A tab stop has more than a position and a leader
+    InvalidTabStop(&'a str),
+    PixelParseError(PixelParseError<'a>),
+    LeaderParseError(InvalidValueErr<'a>),
+}
+impl_debug_as_display!(CssTabStopsParseError<'a>);
+impl_display!{ CssTabStopsParseError<'a>, {
+    InvalidTabStop(e) => format!("Expected a position and an optional leader: \"{}\"", e),
+    PixelParseError(e) => format!("{}", e),
+    LeaderParseError(e) => format!("Invalid leader: \"{}\" - expected dots, dashes or underline", e.0),
+}}
+
+impl_from!(PixelParseError<'a>, CssTabStopsParseError::PixelParseError);
+impl_from!(InvalidValueErr<'a>, CssTabStopsParseError::LeaderParseError);
+
+/// Parses a `tab-stops` list such as `200px dots, 300px`: comma-separated positions,
+/// each followed by an optional leader (`dots`, `dashes` or `underline`)
+pub fn parse_style_tab_stops<'a>(input: &'a str)
+-> Result<StyleTabStops, CssTabStopsParseError<'a>>
+{
+    let input = input.trim();
+
+    if input == "none" {
+        return Ok(StyleTabStops::default());
+    }
+
+    input.split(',').map(|tab_stop| {
+        let mut words = tab_stop.split_whitespace();
+        let position = parse_pixel_value(words.next().ok_or(CssTabStopsParseError::InvalidTabStop(tab_stop))?)?;
+        let leader = match words.next() {
+            Some(leader) => Some(parse_style_tab_leader(leader)?),
+            None => None,
+        };
+        if words.next().is_some() {
+            return Err(CssTabStopsParseError::InvalidTabStop(tab_stop));
+        }
+        Ok(StyleTabStop { position, leader })
+    }).collect::<Result<Vec<_>, _>>().map(StyleTabStops)
+}
+
+multi_type_parser!(parse_style_tab_leader, StyleTabLeader,
+                    ["dots", Dots],
+                    ["dashes", Dashes],
+                    ["underline", Underline]);
+
+multi_type_parser!(parse_text_decoration_style, StyleTextDecorationStyle,
+                    ["solid", Solid],
+                    ["double", Double],
+                    ["dotted", Dotted],
+                    ["dashed", Dashed],
+                    ["wavy", Wavy]);
+
+multi_type_parser!(parse_style_visibility, StyleVisibility,
+                    ["visible", Visible],
+                    ["hidden", Hidden]);
+
+multi_type_parser!(parse_style_mix_blend_mode, StyleMixBlendMode,
+                    ["normal", Normal],
+                    ["multiply", Multiply],
+                    ["screen", Screen],
+                    ["overlay", Overlay],
+                    ["darken", Darken],
+                    ["lighten", Lighten],
+                    ["color-dodge", ColorDodge],
+                    ["color-burn", ColorBurn],
+                    ["hard-light", HardLight],
+                    ["soft-light", SoftLight],
+                    ["difference", Difference],
+                    ["exclusion", Exclusion],
+                    ["hue", Hue],
+                    ["saturation", Saturation],
+                    ["color", Color],
+                    ["luminosity", Luminosity]);
+
+multi_type_parser!(parse_style_object_fit, StyleObjectFit,
+                    ["fill", Fill],
+                    ["contain", Contain],
+                    ["cover", Cover],
+                    ["none", None],
+                    ["scale-down", ScaleDown]);
+
+multi_type_parser!(parse_style_pointer_events, StylePointerEvents,
+                    ["auto", Auto],
+                    ["none", None]);
+
+multi_type_parser!(parse_style_user_select, StyleUserSelect,
+                    ["auto", Auto],
+                    ["none", None],
+                    ["text", Text],
+                    ["all", All]);
+
 multi_type_parser!(parse_style_cursor, StyleCursor,
                     ["alias", Alias],
                     ["all-scroll", AllScroll],
@@ -2127,7 +3362,8 @@ multi_type_parser!(parse_layout_align_items, LayoutAlignItems,
                     ["flex-start", Start],
                     ["flex-end", End],
                     ["stretch", Stretch],
-                    ["center", Center]);
+                    ["center", Center],
+                    ["baseline", Baseline]);
 
 multi_type_parser!(parse_layout_align_content, LayoutAlignContent,
                     ["flex-start", Start],
@@ -2141,10 +3377,80 @@ multi_type_parser!(parse_shape, Shape,
                     ["circle", Circle],
                     ["ellipse", Ellipse]);
 
+multi_type_parser!(parse_layout_display, LayoutDisplay,
+                    ["flex", Flex],
+                    ["grid", Grid],
+                    ["inline", Inline],
+                    ["inline-block", InlineBlock]);
+
+multi_type_parser!(parse_layout_float, LayoutFloat,
+                    ["left", Left],
+                    ["right", Right],
+                    ["none", None]);
+
+/// Parses a `scroll-snap-type`, i.e. `none`, `x mandatory` or `both` (the strictness
+/// defaults to `proximity`). `block` and `inline` are treated as `y` and `x`.
+pub fn parse_layout_scroll_snap_type<'a>(input: &'a str)
+-> Result<LayoutScrollSnapType, InvalidValueErr<'a>>
+{
+    let input = input.trim();
+    let mut words = input.split_whitespace();
+
+    let axis = match words.next() {
+        Some(axis) => parse_scroll_snap_axis(axis)?,
+        None => return Err(InvalidValueErr(input)),
+    };
+
+    let strictness = match words.next() {
+        Some(_) if axis == ScrollSnapAxis::None => return Err(InvalidValueErr(input)),
+        Some(strictness) => parse_scroll_snap_strictness(strictness)?,
+        None => ScrollSnapStrictness::Proximity,
+    };
+
+    if words.next().is_some() {
+        return Err(InvalidValueErr(input));
+    }
+
+    Ok(LayoutScrollSnapType { axis, strictness })
+}
+
+multi_type_parser!(parse_scroll_snap_axis, ScrollSnapAxis,
+                    ["none", None],
+                    ["x", X],
+                    ["inline", X],
+                    ["y", Y],
+                    ["block", Y],
+                    ["both", Both]);
+
+multi_type_parser!(parse_scroll_snap_strictness, ScrollSnapStrictness,
+                    ["mandatory", Mandatory],
+                    ["proximity", Proximity]);
+
+/// Parses a `scroll-snap-align`, i.e. `start` or `none center` (vertical, horizontal)
+pub fn parse_layout_scroll_snap_align<'a>(input: &'a str)
+-> Result<LayoutScrollSnapAlign, InvalidValueErr<'a>>
+{
+    let input = input.trim();
+    let alignments = input.split_whitespace().map(parse_scroll_snap_alignment).collect::<Result<Vec<_>, _>>()?;
+
+    match alignments.as_slice() {
+        [both] => Ok(LayoutScrollSnapAlign { vertical: *both, horizontal: *both }),
+        [vertical, horizontal] => Ok(LayoutScrollSnapAlign { vertical: *vertical, horizontal: *horizontal }),
+        _ => Err(InvalidValueErr(input)),
+    }
+}
+
+multi_type_parser!(parse_scroll_snap_alignment, ScrollSnapAlignment,
+                    ["none", None],
+                    ["start", Start],
+                    ["center", Center],
+                    ["end", End]);
+
 multi_type_parser!(parse_layout_position, LayoutPosition,
                     ["static", Static],
                     ["absolute", Absolute],
-                    ["relative", Relative]);
+                    ["relative", Relative],
+                    ["sticky", Sticky]);
 
 multi_type_parser!(parse_layout_text_overflow, Overflow,
                     ["auto", Auto],
@@ -2155,7 +3461,10 @@ multi_type_parser!(parse_layout_text_overflow, Overflow,
 multi_type_parser!(parse_layout_text_align, StyleTextAlignmentHorz,
                     ["center", Center],
                     ["left", Left],
-                    ["right", Right]);
+                    ["right", Right],
+                    ["justify", Justify],
+                    ["start", Start],
+                    ["end", End]);
 
 #[cfg(test)]
 mod css_tests {
@@ -2265,6 +3574,31 @@ mod css_tests {
         })));
     }
 
+    #[test]
+    fn test_parse_box_shadow_list() {
+        assert_eq!(parse_css_box_shadow_list("none"), Ok(Vec::new()));
+        assert_eq!(parse_css_box_shadow_list("0px 1px 3px rgba(0, 0, 0, 0.5), 2px 2px 5px 1px #888888 inset"), Ok(vec![
+            BoxShadowPreDisplayItem {
+                offset: [PixelValue::px(0.0), PixelValue::px(1.0)],
+                color: ColorU { r: 0, g: 0, b: 0, a: 128 },
+                blur_radius: PixelValue::px(3.0),
+                spread_radius: PixelValue::px(0.0),
+                clip_mode: BoxShadowClipMode::Outset,
+            },
+            BoxShadowPreDisplayItem {
+                offset: [PixelValue::px(2.0), PixelValue::px(2.0)],
+                color: ColorU { r: 136, g: 136, b: 136, a: 255 },
+                blur_radius: PixelValue::px(5.0),
+                spread_radius: PixelValue::px(1.0),
+                clip_mode: BoxShadowClipMode::Inset,
+            },
+        ]));
+        assert_eq!(
+            parse_css_box_shadow_list("5px 10px, none"),
+            Err(CssShadowParseError::InvalidSingleStatement("none"))
+        );
+    }
+
     #[test]
     fn test_parse_css_border_1() {
         assert_eq!(
@@ -2301,6 +3635,20 @@ mod css_tests {
         );
     }
 
+    #[test]
+    fn test_parse_style_outline() {
+        assert_eq!(
+            parse_style_outline("2px dashed blue"),
+            Ok(StyleOutline(StyleBorderSide {
+                border_width: PixelValue::px(2.0),
+                border_style: BorderStyle::Dashed,
+                border_color: ColorU { r: 0, g: 0, b: 255, a: 255 },
+            }))
+        );
+        assert_eq!(parse_style_outline("none").map(|o| o.0.border_style), Ok(BorderStyle::None));
+        assert_eq!(parse_style_outline_offset("-1px"), Ok(StyleOutlineOffset::px(-1.0)));
+    }
+
     #[test]
     fn test_parse_linear_gradient_1() {
         assert_eq!(parse_style_background("linear-gradient(red, yellow)"),
@@ -2532,6 +3880,79 @@ mod css_tests {
         })));
     }
 
+    #[test]
+    fn test_parse_conic_gradient_1() {
+        assert_eq!(parse_style_background("conic-gradient(from 90deg at 25% top, red, blue 0.5turn, lime)"),
+            Ok(StyleBackground::ConicGradient(ConicGradient {
+                angle: FloatValue::new(90.0),
+                center: (PercentageValue::new(25.0), PercentageValue::new(0.0)),
+                extend_mode: ExtendMode::Clamp,
+                stops: vec![
+                GradientStopPre {
+                    offset: Some(PercentageValue::new(0.0)),
+                    color: ColorU { r: 255, g: 0, b: 0, a: 255 },
+                },
+                GradientStopPre {
+                    offset: Some(PercentageValue::new(50.0)),
+                    color: ColorU { r: 0, g: 0, b: 255, a: 255 },
+                },
+                GradientStopPre {
+                    offset: Some(PercentageValue::new(100.0)),
+                    color: ColorU { r: 0, g: 255, b: 0, a: 255 },
+                }],
+        })));
+    }
+
+    #[test]
+    fn test_parse_conic_gradient_2() {
+        assert_eq!(parse_style_background("repeating-conic-gradient(red 0deg 90deg, blue 90deg 180deg)"),
+            Ok(StyleBackground::ConicGradient(ConicGradient {
+                angle: FloatValue::new(0.0),
+                center: (PercentageValue::new(50.0), PercentageValue::new(50.0)),
+                extend_mode: ExtendMode::Repeat,
+                stops: vec![
+                GradientStopPre {
+                    offset: Some(PercentageValue::new(0.0)),
+                    color: ColorU { r: 255, g: 0, b: 0, a: 255 },
+                },
+                GradientStopPre {
+                    offset: Some(PercentageValue::new(25.0)),
+                    color: ColorU { r: 255, g: 0, b: 0, a: 255 },
+                },
+                GradientStopPre {
+                    offset: Some(PercentageValue::new(25.0)),
+                    color: ColorU { r: 0, g: 0, b: 255, a: 255 },
+                },
+                GradientStopPre {
+                    offset: Some(PercentageValue::new(50.0)),
+                    color: ColorU { r: 0, g: 0, b: 255, a: 255 },
+                }],
+        })));
+    }
+
+    #[test]
+    fn test_parse_gradient_color_hints() {
+        let stops = match parse_style_background("linear-gradient(red, 25%, blue)") {
+            Ok(StyleBackground::LinearGradient(gradient)) => gradient.stops,
+            other => panic!("expected linear gradient, got {:?}", other),
+        };
+
+        // the hint is approximated by 9 stops between red and blue
+        assert_eq!(stops.len(), 11);
+        assert_eq!(stops[0].color, ColorU { r: 255, g: 0, b: 0, a: 255 });
+        assert_eq!(stops[10].color, ColorU { r: 0, g: 0, b: 255, a: 255 });
+        assert_eq!(stops[5].offset, Some(PercentageValue::new(50.0)));
+        // at 50%, the color is already mostly blue
+        assert!(stops[5].color.b > 170);
+
+        // a hint in the middle is a linear transition
+        assert_eq!(parse_style_background("linear-gradient(red, 50%, blue)"), parse_style_background("linear-gradient(red, blue)"));
+
+        assert_eq!(parse_style_background("linear-gradient(10%, red, blue)"), Err(CssBackgroundParseError::InvalidColorHint("10%")));
+        assert_eq!(parse_style_background("linear-gradient(red, 10%, 20%, blue)"), Err(CssBackgroundParseError::InvalidColorHint(" 20%")));
+        assert_eq!(parse_style_background("linear-gradient(red, blue, 10%)"), Err(CssBackgroundParseError::InvalidColorHint("red, blue, 10%")));
+    }
+
     // This test currently fails, but it's not that important to fix right now
     /*
     #[test]
@@ -2811,6 +4232,159 @@ mod css_tests {
         })));
     }
 
+    #[test]
+    fn test_parse_style_border_radius_elliptical() {
+        assert_eq!(parse_style_border_radius("10px 0 10px 0 / 20px"), Ok(StyleBorderRadius(BorderRadius {
+            top_left: PixelSize::new(PixelValue::px(10.0), PixelValue::px(20.0)),
+            top_right: PixelSize::new(PixelValue::px(0.0), PixelValue::px(20.0)),
+            bottom_right: PixelSize::new(PixelValue::px(10.0), PixelValue::px(20.0)),
+            bottom_left: PixelSize::new(PixelValue::px(0.0), PixelValue::px(20.0)),
+        })));
+        assert_eq!(parse_style_border_radius("15px / 5px 10px"), Ok(StyleBorderRadius(BorderRadius {
+            top_left: PixelSize::new(PixelValue::px(15.0), PixelValue::px(5.0)),
+            top_right: PixelSize::new(PixelValue::px(15.0), PixelValue::px(10.0)),
+            bottom_right: PixelSize::new(PixelValue::px(15.0), PixelValue::px(5.0)),
+            bottom_left: PixelSize::new(PixelValue::px(15.0), PixelValue::px(10.0)),
+        })));
+        assert!(parse_style_border_radius("15px / 5px / 3px").is_err());
+        assert!(parse_style_border_radius("15px /").is_err());
+    }
+
+    #[test]
+    fn test_parse_style_opacity() {
+        assert_eq!(parse_style_opacity("0.5"), Ok(StyleOpacity(PercentageValue::new(0.5))));
+        assert_eq!(parse_style_opacity("25%"), Ok(StyleOpacity(PercentageValue::new(0.25))));
+    }
+
+    #[test]
+    fn test_parse_layout_text_align() {
+        assert_eq!(parse_layout_text_align("justify"), Ok(StyleTextAlignmentHorz::Justify));
+        assert_eq!(parse_layout_text_align("start"), Ok(StyleTextAlignmentHorz::Start));
+        assert_eq!(parse_layout_text_align("end"), Ok(StyleTextAlignmentHorz::End));
+    }
+
+    #[test]
+    fn test_parse_style_transform() {
+        use azul_css::TransformFunction::*;
+        assert_eq!(parse_style_transform("none"), Ok(StyleTransform(Vec::new())));
+        assert_eq!(parse_style_transform("translate(10px, 0) rotate(45deg) scaleY(2)"), Ok(StyleTransform(vec![
+            Translate(PixelValue::px(10.0), PixelValue::px(0.0)),
+            Rotate(FloatValue::new(45.0)),
+            Scale(FloatValue::new(1.0), FloatValue::new(2.0)),
+        ])));
+        assert_eq!(parse_style_transform("skewX(0.25turn)"), Ok(StyleTransform(vec![
+            Skew(FloatValue::new(90.0), FloatValue::new(0.0)),
+        ])));
+        assert!(parse_style_transform("rotate(45)").is_err());
+        assert!(parse_style_transform("translate(1px, 2px, 3px)").is_err());
+        assert!(parse_style_transform("rotateX(45px)").is_err());
+    }
+
+    #[test]
+    fn test_parse_style_transform_3d() {
+        use azul_css::TransformFunction::*;
+        assert_eq!(parse_style_transform("perspective(500px) rotateY(180deg) translateZ(-20px) rotateZ(0)"), Ok(StyleTransform(vec![
+            Perspective(PixelValue::px(500.0)),
+            RotateY(FloatValue::new(180.0)),
+            TranslateZ(PixelValue::px(-20.0)),
+            Rotate(FloatValue::new(0.0)),
+        ])));
+        assert_eq!(parse_style_perspective("none"), Ok(StylePerspective(None)));
+        assert_eq!(parse_style_perspective("800px"), Ok(StylePerspective(Some(PixelValue::px(800.0)))));
+        assert_eq!(parse_style_transform_style("preserve-3d"), Ok(StyleTransformStyle::Preserve3D));
+    }
+
+    #[test]
+    fn test_parse_style_transform_origin() {
+        let percent = |p| TransformOriginValue::Percentage(PercentageValue::new(p));
+        assert_eq!(parse_style_transform_origin("top left"), Ok(StyleTransformOrigin { x: percent(0.0), y: percent(0.0) }));
+        assert_eq!(parse_style_transform_origin("bottom"), Ok(StyleTransformOrigin { x: percent(50.0), y: percent(100.0) }));
+        assert_eq!(parse_style_transform_origin("10px 25%"), Ok(StyleTransformOrigin {
+            x: TransformOriginValue::Pixels(PixelValue::px(10.0)),
+            y: percent(25.0),
+        }));
+        assert!(parse_style_transform_origin("left top 10px").is_err());
+    }
+
+    #[test]
+    fn test_parse_grid_track_list() {
+        use azul_css::GridTrackSize::*;
+        assert_eq!(parse_grid_track_list("200px 1fr auto 25%"), Ok(vec![
+            Fixed(PixelValue::px(200.0)),
+            Fraction(FloatValue::new(1.0)),
+            Auto,
+            Percentage(PercentageValue::new(25.0)),
+        ]));
+        assert_eq!(parse_grid_track_list("100px repeat(2, 1fr 2fr)"), Ok(vec![
+            Fixed(PixelValue::px(100.0)),
+            Fraction(FloatValue::new(1.0)),
+            Fraction(FloatValue::new(2.0)),
+            Fraction(FloatValue::new(1.0)),
+            Fraction(FloatValue::new(2.0)),
+        ]));
+        assert!(parse_grid_track_list("repeat(0, 1fr)").is_err());
+        assert!(parse_grid_track_list("1fx").is_err());
+    }
+
+    #[test]
+    fn test_parse_grid_placement() {
+        assert_eq!(parse_grid_placement("2"), Ok(GridPlacement { start: GridLine::Line(2), end: GridLine::Auto }));
+        assert_eq!(parse_grid_placement("1 / -1"), Ok(GridPlacement { start: GridLine::Line(1), end: GridLine::Line(-1) }));
+        assert_eq!(parse_grid_placement("2 / span 3"), Ok(GridPlacement { start: GridLine::Line(2), end: GridLine::Span(3) }));
+        assert_eq!(parse_grid_placement("span 2"), Ok(GridPlacement { start: GridLine::Span(2), end: GridLine::Auto }));
+        assert!(parse_grid_placement("0").is_err());
+        assert!(parse_grid_placement("1 / 2 / 3").is_err());
+        assert_eq!(parse_layout_grid_gap("10px 20px"), Ok(LayoutGridGap { row: PixelValue::px(10.0), column: PixelValue::px(20.0) }));
+    }
+
+    #[test]
+    fn test_parse_logical_properties() {
+        fn parse<'a>(key: &str, value: &'a str) -> Result<CssProperty, CssParsingError<'a>> {
+            let key_map = azul_css::get_css_key_map();
+            parse_key_value_pair(CssPropertyType::from_str(key, &key_map).unwrap(), value)
+        }
+
+        assert_eq!(parse("direction", "rtl"), Ok(CssProperty::TextDirection(StyleTextDirection::Rtl)));
+        assert_eq!(parse("margin-inline-start", "5px"), Ok(CssProperty::MarginInline(LayoutMarginInline { start: Some(PixelValue::px(5.0)), end: None })));
+        assert_eq!(parse("padding-inline", "1px 2px"), Ok(CssProperty::PaddingInline(LayoutPaddingInline { start: Some(PixelValue::px(1.0)), end: Some(PixelValue::px(2.0)) })));
+        assert_eq!(parse("margin-block", "3px"), Ok(CssProperty::Margin(LayoutMargin { top: Some(PixelValue::px(3.0)), bottom: Some(PixelValue::px(3.0)), .. Default::default() })));
+        assert_eq!(parse("padding-block-end", "4px"), Ok(CssProperty::Padding(LayoutPadding { bottom: Some(PixelValue::px(4.0)), .. Default::default() })));
+        assert!(parse("margin-inline", "1px 2px 3px").is_err());
+
+        let border = parse_css_border("1px solid red").unwrap();
+        assert_eq!(parse("border-inline-end", "1px solid red"), Ok(CssProperty::BorderInline(StyleBorderInline { start: None, end: Some(border) })));
+
+        // `start` is the right side in right-to-left text
+        let margin = LayoutMarginInline { start: Some(PixelValue::px(5.0)), end: None };
+        assert_eq!(margin.to_physical(StyleTextDirection::Rtl), LayoutMargin { right: Some(PixelValue::px(5.0)), .. Default::default() });
+        assert_eq!(margin.to_physical(StyleTextDirection::Ltr), LayoutMargin { left: Some(PixelValue::px(5.0)), .. Default::default() });
+
+        // ... and rows start at the right edge
+        assert_eq!(LayoutDirection::Row.to_physical(StyleTextDirection::Rtl), LayoutDirection::RowReverse);
+        assert_eq!(LayoutDirection::RowReverse.to_physical(StyleTextDirection::Rtl), LayoutDirection::Row);
+        assert_eq!(LayoutDirection::Column.to_physical(StyleTextDirection::Rtl), LayoutDirection::Column);
+        assert_eq!(LayoutDirection::Row.to_physical(StyleTextDirection::Ltr), LayoutDirection::Row);
+    }
+
+    #[test]
+    fn test_parse_gap() {
+        assert_eq!(parse_layout_gap("8px"), Ok(LayoutGap { row: PixelValue::px(8.0), column: PixelValue::px(8.0) }));
+        assert_eq!(parse_layout_gap("4px 1em"), Ok(LayoutGap { row: PixelValue::px(4.0), column: PixelValue::em(1.0) }));
+        assert!(parse_layout_gap("1px 2px 3px").is_err());
+        assert_eq!(parse_layout_row_gap("5px"), Ok(LayoutRowGap::px(5.0)));
+        assert_eq!(parse_layout_column_gap("6px"), Ok(LayoutColumnGap::px(6.0)));
+    }
+
+    #[test]
+    fn test_parse_flex_basis_and_order() {
+        assert_eq!(parse_layout_flex_basis("auto"), Ok(LayoutFlexBasis::Auto));
+        assert_eq!(parse_layout_flex_basis("content"), Ok(LayoutFlexBasis::Content));
+        assert_eq!(parse_layout_flex_basis("200px"), Ok(LayoutFlexBasis::Exact(PixelValue::px(200.0))));
+        assert!(parse_layout_flex_basis("fill").is_err());
+        assert_eq!(parse_layout_order("-1"), Ok(LayoutOrder(-1)));
+        assert!(parse_layout_order("1.5").is_err());
+    }
+
     #[test]
     fn test_parse_style_font_family_1() {
         assert_eq!(parse_style_font_family("\"Webly Sleeky UI\", monospace"), Ok(StyleFontFamily {
@@ -2876,4 +4450,134 @@ mod css_tests {
             left: Some(PixelValue::px(100.0)),
         }));
     }
+
+    #[test]
+    fn test_parse_padding_and_margin_percentages() {
+        assert_eq!(parse_layout_padding("10% 5px"), Ok(LayoutPadding {
+            top: Some(PixelValue::percent(10.0)),
+            right: Some(PixelValue::px(5.0)),
+            bottom: Some(PixelValue::percent(10.0)),
+            left: Some(PixelValue::px(5.0)),
+        }));
+        assert_eq!(parse_key_value_pair(CssPropertyType::PaddingLeft, "2.5%"), Ok(CssProperty::Padding(LayoutPadding {
+            left: Some(PixelValue::percent(2.5)),
+            .. Default::default()
+        })));
+        assert_eq!(parse_key_value_pair(CssPropertyType::MarginInline, "0 5%"), Ok(CssProperty::MarginInline(LayoutMarginInline {
+            start: Some(PixelValue::px(0.0)),
+            end: Some(PixelValue::percent(5.0)),
+        })));
+        assert!(parse_layout_padding("auto").is_err());
+        assert!(parse_layout_padding("x%").is_err());
+    }
+
+    #[test]
+    fn test_parse_margin_auto() {
+        assert_eq!(parse_layout_margin("0 auto"), Ok(LayoutMargin {
+            top: Some(PixelValue::px(0.0)),
+            right: Some(PixelValue::auto()),
+            bottom: Some(PixelValue::px(0.0)),
+            left: Some(PixelValue::auto()),
+        }));
+        assert_eq!(parse_key_value_pair(CssPropertyType::MarginTop, "auto"), Ok(CssProperty::Margin(LayoutMargin {
+            top: Some(PixelValue::auto()),
+            .. Default::default()
+        })));
+        assert!(PixelValue::auto().is_auto());
+    }
+
+    #[test]
+    fn test_parse_calc_expression() {
+        use azul_css::CalcExpression::*;
+
+        let px = |v| Box::new(Length(PixelValue::px(v)));
+        let percent = |v| Box::new(Percentage(PercentageValue::new(v)));
+        let number = |v| Box::new(Number(FloatValue::new(v)));
+
+        assert_eq!(parse_calc_expression("calc(100% - 20px)"), Ok(Sub(percent(100.0), px(20.0))));
+        assert_eq!(parse_calc_expression("calc(10px + 50% / 2)"), Ok(Add(px(10.0), Box::new(Div(percent(50.0), number(2.0))))));
+        assert_eq!(parse_calc_expression("calc((100% - 10px)/3)"), Ok(Div(Box::new(Sub(percent(100.0), px(10.0))), number(3.0))));
+        assert_eq!(parse_calc_expression("calc(2 * calc(-5px))"), Ok(Mul(number(2.0), px(-5.0))));
+
+        assert_eq!(parse_calc_expression("100% - 20px"), Err(CssCalcParseError::NotACalcExpression("100% - 20px")));
+        assert_eq!(parse_calc_expression("calc(100% -)"), Err(CssCalcParseError::UnexpectedToken(")")));
+        assert_eq!(parse_calc_expression("calc((100%)"), Err(CssCalcParseError::UnexpectedEndOfInput));
+        assert_eq!(parse_calc_expression("calc(100% 20px)"), Err(CssCalcParseError::UnexpectedToken("20px")));
+        assert_eq!(parse_calc_expression("calc(100% - 5)"), Err(CssCalcParseError::MixedUnits));
+        assert_eq!(parse_calc_expression("calc(5px * 5px)"), Err(CssCalcParseError::InvalidUnitsInProduct));
+        assert_eq!(parse_calc_expression("calc(5 / 5px)"), Err(CssCalcParseError::InvalidUnitsInProduct));
+        assert_eq!(parse_calc_expression("calc(5 * 2)"), Err(CssCalcParseError::UnitlessResult));
+        assert_eq!(parse_calc_expression("calc(5xyz)"), Err(CssCalcParseError::InvalidOperand("5xyz")));
+    }
+
+    #[test]
+    fn test_parse_calc_key_value_pair() {
+        use azul_css::CalcExpression::*;
+        assert_eq!(parse_key_value_pair(CssPropertyType::MinWidth, "calc(50% + 1em)"), Ok(CssProperty::Calc(LayoutCalc {
+            property_type: CssPropertyType::MinWidth,
+            expression: Add(Box::new(Percentage(PercentageValue::new(50.0))), Box::new(Length(PixelValue::em(1.0)))),
+        })));
+        assert_eq!(parse_key_value_pair(CssPropertyType::Width, "calc(100%)").map(|p| p.get_type()), Ok(CssPropertyType::Width));
+    }
+
+    #[test]
+    fn test_parse_style_content() {
+        assert_eq!(parse_style_content("'New'"), Ok(StyleContent("New".into())));
+        assert_eq!(parse_style_content("\"\""), Ok(StyleContent(String::new())));
+        assert_eq!(parse_style_content("normal"), Ok(StyleContent(String::new())));
+        assert_eq!(parse_style_content("New"), Err(CssStyleContentParseError::InvalidStyleContent("New")));
+        assert_eq!(parse_style_content("\"a\" \"b\""), Err(CssStyleContentParseError::InvalidStyleContent("\"a\" \"b\"")));
+        assert_eq!(parse_style_content("\"New"), Err(CssStyleContentParseError::UnclosedQuotes("\"New")));
+    }
+
+    #[test]
+    fn test_parse_style_text_decoration() {
+        assert_eq!(parse_style_text_decoration("none"), Ok(StyleTextDecoration::default()));
+        assert_eq!(parse_style_text_decoration("underline overline"), Ok(StyleTextDecoration {
+            underline: true,
+            overline: true,
+            .. Default::default()
+        }));
+        assert_eq!(parse_style_text_decoration("line-through wavy rgb(255, 0, 0)"), Ok(StyleTextDecoration {
+            line_through: true,
+            style: StyleTextDecorationStyle::Wavy,
+            color: Some(ColorU { r: 255, g: 0, b: 0, a: 255 }),
+            .. Default::default()
+        }));
+        assert_eq!(parse_style_text_decoration("wavy red"), Err(CssTextDecorationParseError::MissingLine("wavy red")));
+        assert!(parse_style_text_decoration("underline notacolor").is_err());
+    }
+
+    #[test]
+    fn test_parse_style_tab_stops() {
+        assert_eq!(parse_style_tab_stops("none"), Ok(StyleTabStops::default()));
+        assert_eq!(parse_style_tab_stops("200px dots, 300px"), Ok(StyleTabStops(vec![
+            StyleTabStop { position: PixelValue::px(200.0), leader: Some(StyleTabLeader::Dots) },
+            StyleTabStop { position: PixelValue::px(300.0), leader: None },
+        ])));
+        assert_eq!(parse_style_tab_stops("200px dots dashes"), Err(CssTabStopsParseError::InvalidTabStop("200px dots dashes")));
+        assert_eq!(parse_style_tab_stops("200px,"), Err(CssTabStopsParseError::InvalidTabStop("")));
+        assert!(parse_style_tab_stops("200px stars").is_err());
+    }
+
+    #[test]
+    fn test_parse_scroll_snap() {
+        assert_eq!(parse_layout_scroll_snap_type("x mandatory"), Ok(LayoutScrollSnapType { axis: ScrollSnapAxis::X, strictness: ScrollSnapStrictness::Mandatory }));
+        assert_eq!(parse_layout_scroll_snap_type("block"), Ok(LayoutScrollSnapType { axis: ScrollSnapAxis::Y, strictness: ScrollSnapStrictness::Proximity }));
+        assert!(parse_layout_scroll_snap_type("none mandatory").is_err());
+        assert!(parse_layout_scroll_snap_type("x mandatory proximity").is_err());
+        assert_eq!(parse_layout_scroll_snap_align("center"), Ok(LayoutScrollSnapAlign { vertical: ScrollSnapAlignment::Center, horizontal: ScrollSnapAlignment::Center }));
+        assert_eq!(parse_layout_scroll_snap_align("none start"), Ok(LayoutScrollSnapAlign { vertical: ScrollSnapAlignment::None, horizontal: ScrollSnapAlignment::Start }));
+        assert!(parse_layout_scroll_snap_align("").is_err());
+        assert!(parse_layout_scroll_snap_align("start end center").is_err());
+    }
+
+    #[test]
+    fn test_parse_style_user_select() {
+        assert_eq!(parse_style_user_select("none"), Ok(StyleUserSelect::None));
+        assert_eq!(parse_style_user_select("text"), Ok(StyleUserSelect::Text));
+        assert_eq!(parse_style_user_select("all"), Ok(StyleUserSelect::All));
+        assert_eq!(parse_style_user_select("auto"), Ok(StyleUserSelect::Auto));
+        assert!(parse_style_user_select("contain").is_err());
+    }
 }
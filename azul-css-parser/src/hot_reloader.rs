@@ -2,28 +2,41 @@
 //! files to be dynamically reloaded at runtime.
 
 use azul_css::{HotReloadHandler, Css};
-use std::time::Duration;
-use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use std::path::{Path, PathBuf};
 
 pub const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_millis(500);
 
 /// Allows dynamic reloading of a CSS file at application runtime.
+///
+/// The file is checked every `reload_interval` and only re-parsed if its modification
+/// time has changed. Note that changes to `@import`ed files are only picked up
+/// once the file itself is modified (or touched).
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct HotReloader {
     file_path: PathBuf,
     reload_interval: Duration,
+    /// Whether the file has been loaded (or has failed to load) at least once
+    has_loaded: bool,
+    /// Modification time of the file when it was last loaded, `None` if the file was missing
+    last_modified: Option<SystemTime>,
 }
 
 impl HotReloader {
     /// Creates a HotReloader that will load a style directly from the CSS file
     /// at the given path.
     pub fn new<P: Into<PathBuf>>(file_path: P) -> Self {
-        Self { file_path: file_path.into(), reload_interval: DEFAULT_RELOAD_INTERVAL }
+        Self { file_path: file_path.into(), reload_interval: DEFAULT_RELOAD_INTERVAL, has_loaded: false, last_modified: None }
     }
 
     pub fn with_reload_interval(self, reload_interval: Duration) -> Self {
         Self { reload_interval, .. self }
     }
+
+    /// Returns the modification time of the file or `None` if the file doesn't exist (anymore)
+    fn get_modified_time(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.file_path).and_then(|metadata| metadata.modified()).ok()
+    }
 }
 
 impl HotReloadHandler for HotReloader {
@@ -31,15 +44,55 @@ impl HotReloadHandler for HotReloader {
         use std::fs;
         use crate::css;
 
+        // Also set if the file can't be loaded or parsed, so that the same error isn't reported again
+        self.has_loaded = true;
+        self.last_modified = self.get_modified_time();
+
         let file_name = self.file_path.file_name().map(|os_str| os_str.to_string_lossy()).unwrap_or_default();
 
         let reloaded_css = fs::read_to_string(&self.file_path)
             .map_err(|e| format!("Io error: Could not load \"{}\" when loading file: \"{}\"", file_name, e))?;
 
-        css::new_from_str(&reloaded_css).map_err(|e| format!("{}: {}", file_name, e))
+        // @import statements are relative to the directory of the reloaded file
+        let base_dir = self.file_path.parent().unwrap_or_else(|| Path::new(""));
+
+        css::new_from_str_with_base_dir(&reloaded_css, base_dir).map_err(|e| format!("{}: {}", file_name, e))
     }
 
     fn get_reload_interval(&self) -> Duration {
         self.reload_interval
     }
+
+    fn has_changed(&mut self) -> bool {
+        // Always load the file once, so that a missing file is reported. After that, a
+        // missing file is only reported again once it was deleted or renamed in between
+        !self.has_loaded || self.get_modified_time() != self.last_modified
+    }
+}
+
+#[test]
+fn test_hot_reloader_only_reloads_on_changes() {
+
+    use std::fs;
+
+    let path = std::env::temp_dir().join(format!("azul_test_hot_reloader_{}.css", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    // A missing file is reported once, not on every interval
+    let mut hot_reloader = HotReloader::new(&path);
+    assert!(hot_reloader.has_changed());
+    assert!(hot_reloader.reload_style().is_err());
+    assert!(!hot_reloader.has_changed());
+
+    // The file appears
+    fs::write(&path, "div { color: red; }").unwrap();
+    assert!(hot_reloader.has_changed());
+    assert!(hot_reloader.reload_style().is_ok());
+    assert!(!hot_reloader.has_changed());
+
+    // The file is deleted (i.e. renamed by an editor while saving)
+    fs::remove_file(&path).unwrap();
+    assert!(hot_reloader.has_changed());
+    assert!(hot_reloader.reload_style().is_err());
+    assert!(!hot_reloader.has_changed());
 }
@@ -25,9 +25,11 @@ mod hot_reloader;
 
 pub use crate::css::{
     new_from_str,
+    new_from_str_with_base_dir,
     parse_css_path,
     CssParseError,
     CssPathParseError,
+    CssImportError,
 };
 
 pub use crate::css_parser::*;
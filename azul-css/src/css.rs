@@ -1,6 +1,6 @@
 //! Types and methods used to describe the style of an application
-use crate::css_properties::{CssProperty, CssPropertyType};
-use std::fmt;
+use crate::css_properties::{CssProperty, CssPropertyType, ColorU};
+use std::{fmt, path::PathBuf};
 
 /// Css stylesheet - contains a parsed CSS stylesheet in "rule blocks",
 /// i.e. blocks of key-value pairs associated with a selector path.
@@ -10,17 +10,140 @@ pub struct Css {
     /// For example, when overriding native styles, the `.sort_by_specificy()` function
     /// should not mix the two stylesheets during sorting.
     pub stylesheets: Vec<Stylesheet>,
+    /// Fonts declared via `@font-face` rules, registered by their `font-family` name
+    pub font_faces: Vec<CssFontFace>,
+}
+
+/// Font declared in an `@font-face` rule, i.e.
+/// `@font-face { font-family: "MyFont"; src: url("fonts/my.ttf"); }`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CssFontFace {
+    /// Name under which the font can be used in `font-family` properties
+    pub font_family: String,
+    /// Path to the font file (relative paths are resolved relative to the stylesheet by the parser)
+    pub source: PathBuf,
 }
 
 #[derive(Debug, Default, PartialEq, Clone)]
 pub struct Stylesheet {
     /// The style rules making up the document - for example, de-duplicated CSS rules
     pub rules: Vec<CssRuleBlock>,
+    /// Media queries (from an `@media` block) that decide whether the rules of this
+    /// stylesheet are active. The stylesheet is active if any of the queries match
+    /// or if there are no media queries at all.
+    pub media_queries: Vec<CssMediaQuery>,
 }
 
 impl From<Vec<CssRuleBlock>> for Stylesheet {
     fn from(rules: Vec<CssRuleBlock>) -> Self {
-        Self { rules }
+        Self { rules, media_queries: Vec::new() }
+    }
+}
+
+/// Size and DPI of the window that `@media` queries are evaluated against
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CssViewport {
+    /// Logical width of the window, in CSS pixels
+    pub width: f32,
+    /// Logical height of the window, in CSS pixels
+    pub height: f32,
+    /// Number of physical pixels per CSS pixel (the `dppx` unit)
+    pub hidpi_factor: f32,
+}
+
+impl Default for CssViewport {
+    fn default() -> Self {
+        Self { width: 0.0, height: 0.0, hidpi_factor: 1.0 }
+    }
+}
+
+/// A single query of an `@media` rule, such as `screen and (min-width: 600px)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssMediaQuery {
+    pub media_type: CssMediaType,
+    /// All features have to match for the query to match
+    pub features: Vec<CssMediaFeature>,
+}
+
+impl CssMediaQuery {
+    /// Returns whether the query matches the given window size / DPI
+    pub fn matches(&self, viewport: &CssViewport) -> bool {
+        self.media_type != CssMediaType::Print &&
+        self.features.iter().all(|feature| feature.matches(viewport))
+    }
+}
+
+impl fmt::Display for CssMediaQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.media_type)?;
+        for feature in &self.features {
+            write!(f, " and {}", feature)?;
+        }
+        Ok(())
+    }
+}
+
+/// Media type of an `@media` query - since azul only renders to screens,
+/// queries for `print` never match.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CssMediaType {
+    /// `all` (or no media type at all)
+    All,
+    /// `screen`
+    Screen,
+    /// `print`
+    Print,
+}
+
+impl fmt::Display for CssMediaType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CssMediaType::*;
+        match self {
+            All => write!(f, "all"),
+            Screen => write!(f, "screen"),
+            Print => write!(f, "print"),
+        }
+    }
+}
+
+/// Condition in parentheses of a media query, such as `(min-width: 600px)`.
+/// Widths and heights are in CSS pixels, resolutions in `dppx`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CssMediaFeature {
+    MinWidth(f32),
+    MaxWidth(f32),
+    MinHeight(f32),
+    MaxHeight(f32),
+    MinResolution(f32),
+    MaxResolution(f32),
+}
+
+impl CssMediaFeature {
+    /// Returns whether the condition holds for the given window size / DPI
+    pub fn matches(&self, viewport: &CssViewport) -> bool {
+        use self::CssMediaFeature::*;
+        match *self {
+            MinWidth(w) => viewport.width >= w,
+            MaxWidth(w) => viewport.width <= w,
+            MinHeight(h) => viewport.height >= h,
+            MaxHeight(h) => viewport.height <= h,
+            MinResolution(r) => viewport.hidpi_factor >= r,
+            MaxResolution(r) => viewport.hidpi_factor <= r,
+        }
+    }
+}
+
+impl fmt::Display for CssMediaFeature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CssMediaFeature::*;
+        match self {
+            MinWidth(w) => write!(f, "(min-width: {}px)", w),
+            MaxWidth(w) => write!(f, "(max-width: {}px)", w),
+            MinHeight(h) => write!(f, "(min-height: {}px)", h),
+            MaxHeight(h) => write!(f, "(max-height: {}px)", h),
+            MinResolution(r) => write!(f, "(min-resolution: {}dppx)", r),
+            MaxResolution(r) => write!(f, "(max-resolution: {}dppx)", r),
+        }
     }
 }
 
@@ -31,6 +154,9 @@ pub enum CssDeclaration {
     Static(CssProperty),
     /// Dynamic key-value pair with default value, such as `width: [[ my_id | 500px ]]`
     Dynamic(DynamicCssProperty),
+    /// Key-value pair that references CSS variables, such as `color: var(--my-color)`,
+    /// parsed once the variables are known during the cascade
+    Unresolved(UnresolvedCssProperty),
 }
 
 impl CssDeclaration {
@@ -41,6 +167,7 @@ impl CssDeclaration {
         match self {
             Static(s) => s.get_type(),
             Dynamic(d) => d.property_type,
+            Unresolved(u) => u.property_type,
         }
     }
 
@@ -51,6 +178,7 @@ impl CssDeclaration {
         match self {
             Static(s) => s.get_type().is_inheritable(),
             Dynamic(d) => d.is_inheritable(),
+            Unresolved(u) => u.property_type.is_inheritable(),
         }
     }
 
@@ -61,10 +189,136 @@ impl CssDeclaration {
         match self {
             Static(s) => s.get_type().can_trigger_relayout(),
             Dynamic(d) => d.can_trigger_relayout(),
+            Unresolved(u) => u.property_type.can_trigger_relayout(),
         }
     }
 }
 
+/// Custom property (CSS variable) declaration, such as `--my-color: #f00`.
+/// Custom properties are always inherited by the children of the node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CssVariable {
+    /// Name of the variable, without the leading `--`
+    pub name: String,
+    /// Unparsed value of the variable, may reference other variables
+    pub value: String,
+}
+
+/// Colors that the user selected in the settings of the operating system, every color
+/// that can't be determined is `None`. Azul declares them as built-in variables that are
+/// visible to every stylesheet (see `get_variables`), so that a style can follow the OS:
+///
+/// ```css
+/// .selected { background: var(--azul-system-highlight-color, #3399ff); }
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SystemColors {
+    /// `--azul-system-accent-color`: Color of focused, checked and default controls
+    pub accent: Option<ColorU>,
+    /// `--azul-system-highlight-color`: Background of selected text and items
+    pub highlight: Option<ColorU>,
+    /// `--azul-system-window-background-color`: Background of windows
+    pub window_background: Option<ColorU>,
+    /// `--azul-system-text-color`: Color of text on the window background
+    pub text: Option<ColorU>,
+}
+
+impl SystemColors {
+    /// Returns the colors as CSS variables (without the leading `--`),
+    /// colors that can't be determined are left undefined
+    pub fn get_variables(&self) -> Vec<CssVariable> {
+        let colors = [
+            ("azul-system-accent-color", self.accent),
+            ("azul-system-highlight-color", self.highlight),
+            ("azul-system-window-background-color", self.window_background),
+            ("azul-system-text-color", self.text),
+        ];
+
+        colors.iter().filter_map(|(name, color)| Some(CssVariable {
+            name: name.to_string(),
+            value: color.as_ref()?.to_hex_string(),
+        })).collect()
+    }
+}
+
+/// Property whose value contains one or more `var()` references, such as
+/// `border: 1px solid var(--border-color, black)`. The value can only be
+/// parsed once the variables of the node it applies to are known.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UnresolvedCssProperty {
+    /// Key for this property
+    pub property_type: CssPropertyType,
+    /// The unparsed value, i.e. `1px solid var(--border-color, black)`
+    pub value: String,
+}
+
+/// Maximum nesting of variables referencing other variables - protects
+/// against cycles such as `--a: var(--b); --b: var(--a);`
+const MAX_VARIABLE_DEPTH: usize = 16;
+
+impl UnresolvedCssProperty {
+
+    /// Substitutes all `var(--name)` and `var(--name, fallback)` references in the value
+    /// with the values returned by `lookup`. Returns `None` if a variable isn't defined
+    /// and has no fallback or if the variables reference each other in a cycle.
+    pub fn substitute_variables<'a, F>(&self, lookup: F) -> Option<String>
+        where F: Fn(&str) -> Option<&'a str>
+    {
+        substitute_variables_inner(&self.value, &lookup, 0)
+    }
+}
+
+fn substitute_variables_inner<'a, F>(value: &str, lookup: &F, depth: usize) -> Option<String>
+    where F: Fn(&str) -> Option<&'a str>
+{
+    if depth > MAX_VARIABLE_DEPTH {
+        return None;
+    }
+
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(var_start) = rest.find("var(") {
+        result.push_str(&rest[..var_start]);
+        let arguments_start = var_start + "var(".len();
+
+        // Find the closing brace, the fallback value may contain braces itself
+        let mut brace_nesting = 1;
+        let mut arguments_end = None;
+        for (idx, c) in rest[arguments_start..].char_indices() {
+            match c {
+                '(' => brace_nesting += 1,
+                ')' => {
+                    brace_nesting -= 1;
+                    if brace_nesting == 0 {
+                        arguments_end = Some(arguments_start + idx);
+                        break;
+                    }
+                },
+                _ => { },
+            }
+        }
+        let arguments_end = arguments_end?;
+        let arguments = &rest[arguments_start..arguments_end];
+
+        let (name, fallback) = match arguments.find(',') {
+            Some(comma) => (arguments[..comma].trim(), Some(arguments[(comma + 1)..].trim())),
+            None => (arguments.trim(), None),
+        };
+
+        if !name.starts_with("--") {
+            return None;
+        }
+
+        let substituted = lookup(&name[2..]).or(fallback)?;
+        result.push_str(&substitute_variables_inner(substituted, lookup, depth + 1)?);
+        rest = &rest[(arguments_end + 1)..];
+    }
+
+    result.push_str(rest);
+    Some(result)
+}
+
 /// A `DynamicCssProperty` is a type of css property that can be changed on possibly
 /// every frame by the Rust code - for example to implement an `On::Hover` behaviour.
 ///
@@ -134,6 +388,13 @@ pub struct CssRuleBlock {
     /// `"justify-content: center"` =>
     /// `CssDeclaration::Static(CssProperty::JustifyContent(LayoutJustifyContent::Center))`
     pub declarations: Vec<CssDeclaration>,
+    /// Custom properties declared in this block, i.e. `--my-color: #f00`
+    pub variables: Vec<CssVariable>,
+    /// Whether the declarations are marked with `!important`. The parser puts the
+    /// `!important` declarations of a block into a separate block with the same path.
+    /// During the cascade, important declarations override all normal declarations,
+    /// regardless of the specificity of their path.
+    pub important: bool,
 }
 
 pub type CssContentGroup<'a> = Vec<&'a CssPathSelector>;
@@ -235,6 +496,10 @@ pub enum CssPathSelector {
     Id(String),
     /// `:something`
     PseudoSelector(CssPathPseudoSelector),
+    /// `::before` or `::after`, only valid at the end of a path
+    PseudoElement(CssPseudoElement),
+    /// `[something="value"]`
+    Attribute(CssAttributeSelector),
     /// Represents the `>` selector
     DirectChildren,
     /// Represents the ` ` selector
@@ -256,12 +521,70 @@ impl fmt::Display for CssPathSelector {
             Class(c) => write!(f, ".{}", c),
             Id(i) => write!(f, "#{}", i),
             PseudoSelector(p) => write!(f, ":{}", p),
+            PseudoElement(p) => write!(f, "::{}", p),
+            Attribute(a) => write!(f, "{}", a),
             DirectChildren => write!(f, ">"),
             Children => write!(f, " "),
         }
     }
 }
 
+/// Attribute selector, i.e. `[data-state="error"]`, matches against
+/// the attributes set via `Dom::with_attribute`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CssAttributeSelector {
+    /// Name of the attribute, i.e. `data-state`
+    pub name: String,
+    /// How the value of the attribute has to match
+    pub matcher: CssAttributeMatcher,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CssAttributeMatcher {
+    /// `[name]` - attribute is set, regardless of its value
+    Exists,
+    /// `[name="value"]` - attribute value is exactly `value`
+    Equals(String),
+    /// `[name~="value"]` - attribute value is a whitespace-separated list containing `value`
+    ContainsWord(String),
+    /// `[name^="value"]` - attribute value starts with `value`
+    StartsWith(String),
+    /// `[name$="value"]` - attribute value ends with `value`
+    EndsWith(String),
+    /// `[name*="value"]` - attribute value contains `value`
+    Contains(String),
+}
+
+impl CssAttributeSelector {
+    /// Returns whether an attribute with the value `value` satisfies the selector
+    /// (the attribute name has to be checked by the caller)
+    pub fn matches(&self, value: &str) -> bool {
+        use self::CssAttributeMatcher::*;
+        match &self.matcher {
+            Exists => true,
+            Equals(v) => value == v,
+            ContainsWord(v) => value.split_whitespace().any(|word| word == v),
+            StartsWith(v) => !v.is_empty() && value.starts_with(v.as_str()),
+            EndsWith(v) => !v.is_empty() && value.ends_with(v.as_str()),
+            Contains(v) => !v.is_empty() && value.contains(v.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for CssAttributeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CssAttributeMatcher::*;
+        match &self.matcher {
+            Exists => write!(f, "[{}]", self.name),
+            Equals(v) => write!(f, "[{}=\"{}\"]", self.name, v),
+            ContainsWord(v) => write!(f, "[{}~=\"{}\"]", self.name, v),
+            StartsWith(v) => write!(f, "[{}^=\"{}\"]", self.name, v),
+            EndsWith(v) => write!(f, "[{}$=\"{}\"]", self.name, v),
+            Contains(v) => write!(f, "[{}*=\"{}\"]", self.name, v),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum CssPathPseudoSelector {
     /// `:first`
@@ -276,14 +599,43 @@ pub enum CssPathPseudoSelector {
     Active,
     /// `:focus` - element has received focus
     Focus,
+    /// `:disabled` - element is disabled (see `NodeData::is_disabled`)
+    Disabled,
+    /// `:checked` - element is checked (see `NodeData::is_checked`)
+    Checked,
+    /// `:root` - element is the root of the DOM, i.e. for declaring global CSS variables
+    Root,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum CssNthChildSelector {
+    /// `:nth-child(3)`
     Number(usize),
+    /// `:nth-child(even)`, same as `2n`
     Even,
+    /// `:nth-child(odd)`, same as `2n+1`
     Odd,
-    Pattern { repeat: usize, offset: usize },
+    /// `:nth-child(An+B)`, for example `3n+1` or `-n+3` (first three children)
+    Pattern { repeat: isize, offset: isize },
+}
+
+impl CssNthChildSelector {
+    /// Returns whether a node at the given (1-based) index in its parent is
+    /// selected, i.e. whether there is an `n >= 0` so that `An + B == index`
+    pub fn matches(&self, index: usize) -> bool {
+        use self::CssNthChildSelector::*;
+        let index = index as isize;
+        match *self {
+            Number(value) => index == value as isize,
+            Even => index % 2 == 0,
+            Odd => index % 2 == 1,
+            Pattern { repeat: 0, offset } => index == offset,
+            Pattern { repeat, offset } => {
+                let diff = index - offset;
+                diff % repeat == 0 && diff / repeat >= 0
+            },
+        }
+    }
 }
 
 impl fmt::Display for CssNthChildSelector {
@@ -293,11 +645,52 @@ impl fmt::Display for CssNthChildSelector {
             Number(u) => write!(f, "{}", u),
             Even => write!(f, "even"),
             Odd => write!(f, "odd"),
+            Pattern { repeat, offset } if *offset < 0 => write!(f, "{}n - {}", repeat, -offset),
             Pattern { repeat, offset } => write!(f, "{}n + {}", repeat, offset),
         }
     }
 }
 
+/// Pseudo-element of the selected node: either an extra child node with the text of
+/// the `content` property before or after the children of the node, or one of the
+/// parts of the scrollbars of a scrollable (`overflow: auto / scroll`) node
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CssPseudoElement {
+    /// `::before` - inserted as the first child
+    Before,
+    /// `::after` - inserted as the last child
+    After,
+    /// `::scrollbar` - the whole scrollbar, only the `width` and the `padding` (the space
+    /// between the track and the thumb) are used
+    Scrollbar,
+    /// `::track` - the background of the scrollbar
+    Track,
+    /// `::thumb` - the draggable part of the scrollbar, which moves while scrolling
+    Thumb,
+}
+
+impl CssPseudoElement {
+    /// Returns whether the pseudo-element generates a node in the DOM (`::before` and `::after`)
+    pub fn generates_node(&self) -> bool {
+        match self {
+            CssPseudoElement::Before | CssPseudoElement::After => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for CssPseudoElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CssPseudoElement::Before => write!(f, "before"),
+            CssPseudoElement::After => write!(f, "after"),
+            CssPseudoElement::Scrollbar => write!(f, "scrollbar"),
+            CssPseudoElement::Track => write!(f, "track"),
+            CssPseudoElement::Thumb => write!(f, "thumb"),
+        }
+    }
+}
+
 impl fmt::Display for CssPathPseudoSelector {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::CssPathPseudoSelector::*;
@@ -308,6 +701,9 @@ impl fmt::Display for CssPathPseudoSelector {
             Hover => write!(f, "hover"),
             Active => write!(f, "active"),
             Focus => write!(f, "focus"),
+            Disabled => write!(f, "disabled"),
+            Checked => write!(f, "checked"),
+            Root => write!(f, "root"),
         }
     }
 }
@@ -323,6 +719,7 @@ impl Css {
         for stylesheet in css.stylesheets {
             self.append_stylesheet(stylesheet);
         }
+        self.font_faces.extend(css.font_faces);
     }
 
     pub fn append_stylesheet(&mut self, styles: Stylesheet) {
@@ -342,6 +739,14 @@ impl Css {
             css: self,
         }
     }
+
+    /// Same as `rules()`, but skips all stylesheets whose `@media` queries
+    /// don't match the given window size / DPI.
+    pub fn rules_for_viewport<'a>(&'a self, viewport: CssViewport) -> impl Iterator<Item = &'a CssRuleBlock> {
+        self.stylesheets.iter()
+            .filter(move |stylesheet| stylesheet.is_active(&viewport))
+            .flat_map(|stylesheet| stylesheet.rules.iter())
+    }
 }
 
 pub struct RuleIterator<'a> {
@@ -381,24 +786,35 @@ impl Stylesheet {
     pub fn sort_by_specificity(&mut self) {
         self.rules.sort_by(|a, b| get_specificity(&a.path).cmp(&get_specificity(&b.path)));
     }
+
+    /// Returns whether the rules of this stylesheet apply to the given viewport
+    pub fn is_active(&self, viewport: &CssViewport) -> bool {
+        self.media_queries.is_empty() || self.media_queries.iter().any(|query| query.matches(viewport))
+    }
 }
 
-/// Returns specificity of the given css path. Further information can be found on
+/// Returns specificity of the given css path as (ids, classes + attributes + pseudo-classes,
+/// types + pseudo-elements). `*` and combinators don't count. Rules with the same specificity
+/// are applied in the order they were declared in. Further information can be found on
 /// [the w3 website](http://www.w3.org/TR/selectors/#specificity).
-fn get_specificity(path: &CssPath) -> (usize, usize, usize, usize) {
-    let id_count = path.selectors.iter().filter(|x|     if let CssPathSelector::Id(_) = x {     true } else { false }).count();
-    let class_count = path.selectors.iter().filter(|x|  if let CssPathSelector::Class(_) = x {  true } else { false }).count();
-    let div_count = path.selectors.iter().filter(|x|    if let CssPathSelector::Type(_) = x {   true } else { false }).count();
-    (id_count, class_count, div_count, path.selectors.len())
+pub fn get_specificity(path: &CssPath) -> (usize, usize, usize) {
+    use self::CssPathSelector::*;
+    let id_count = path.selectors.iter().filter(|x|     match x { Id(_) => true, _ => false }).count();
+    let class_count = path.selectors.iter().filter(|x|  match x { Class(_) | Attribute(_) | PseudoSelector(_) => true, _ => false }).count();
+    let div_count = path.selectors.iter().filter(|x|    match x { Type(_) | PseudoElement(_) => true, _ => false }).count();
+    (id_count, class_count, div_count)
 }
 
 #[test]
 fn test_specificity() {
     use self::CssPathSelector::*;
-    assert_eq!(get_specificity(&CssPath { selectors: vec![Id("hello".into())] }), (1, 0, 0, 1));
-    assert_eq!(get_specificity(&CssPath { selectors: vec![Class("hello".into())] }), (0, 1, 0, 1));
-    assert_eq!(get_specificity(&CssPath { selectors: vec![Type(NodeTypePath::Div)] }), (0, 0, 1, 1));
-    assert_eq!(get_specificity(&CssPath { selectors: vec![Id("hello".into()), Type(NodeTypePath::Div)] }), (1, 0, 1, 2));
+    assert_eq!(get_specificity(&CssPath { selectors: vec![Id("hello".into())] }), (1, 0, 0));
+    assert_eq!(get_specificity(&CssPath { selectors: vec![Class("hello".into())] }), (0, 1, 0));
+    assert_eq!(get_specificity(&CssPath { selectors: vec![Type(NodeTypePath::Div)] }), (0, 0, 1));
+    assert_eq!(get_specificity(&CssPath { selectors: vec![Id("hello".into()), Type(NodeTypePath::Div)] }), (1, 0, 1));
+    // ".button:hover" has to override ".button", "*" and combinators don't count
+    assert_eq!(get_specificity(&CssPath { selectors: vec![Class("button".into()), PseudoSelector(CssPathPseudoSelector::Hover)] }), (0, 2, 0));
+    assert_eq!(get_specificity(&CssPath { selectors: vec![Global, Children, Type(NodeTypePath::Div), PseudoElement(CssPseudoElement::Before)] }), (0, 0, 2));
 }
 
 // Assert that order of the style items is correct (in order of CSS path specificity, lowest-to-highest)
@@ -410,12 +826,13 @@ fn test_specificity_sort() {
     let mut input_style = Stylesheet {
         rules: vec![
             // Rules are sorted from lowest-specificity to highest specificity
-            CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Type(Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+            CssRuleBlock { path: CssPath { selectors: vec![Type(Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
         ],
+        media_queries: Vec::new(),
     };
 
     input_style.sort_by_specificity();
@@ -423,13 +840,88 @@ fn test_specificity_sort() {
     let expected_style = Stylesheet {
         rules: vec![
             // Rules are sorted from lowest-specificity to highest specificity
-            CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Type(Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
+            CssRuleBlock { path: CssPath { selectors: vec![Type(Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new(), variables: Vec::new(), important: false },
         ],
+        media_queries: Vec::new(),
     };
 
     assert_eq!(input_style, expected_style);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_css_variable_substitution() {
+
+    let variables = [("accent", "#f00"), ("border", "1px solid var(--accent)"), ("a", "var(--b)"), ("b", "var(--a)")];
+    let lookup = |name: &str| variables.iter().find(|(n, _)| *n == name).map(|(_, value)| *value);
+    let substitute = |value: &str| UnresolvedCssProperty { property_type: CssPropertyType::TextColor, value: value.into() }.substitute_variables(lookup);
+
+    assert_eq!(substitute("var(--accent)"), Some("#f00".into()));
+    assert_eq!(substitute("var(--border)"), Some("1px solid #f00".into()));
+    assert_eq!(substitute("var( --accent ) var(--accent)"), Some("#f00 #f00".into()));
+    assert_eq!(substitute("var(--missing, rgb(0, 0, 0))"), Some("rgb(0, 0, 0)".into()));
+    assert_eq!(substitute("var(--missing, var(--accent))"), Some("#f00".into()));
+    assert_eq!(substitute("var(--missing)"), None);
+    assert_eq!(substitute("var(--a)"), None);
+    assert_eq!(substitute("var(accent)"), None);
+    assert_eq!(substitute("var(--accent"), None);
+}
+
+#[test]
+fn test_media_query_matches() {
+    use self::CssMediaFeature::*;
+
+    let small = CssViewport { width: 400.0, height: 300.0, hidpi_factor: 1.0 };
+    let large_hidpi = CssViewport { width: 1200.0, height: 800.0, hidpi_factor: 2.0 };
+
+    let wide = CssMediaQuery { media_type: CssMediaType::All, features: vec![MinWidth(600.0)] };
+    assert!(!wide.matches(&small));
+    assert!(wide.matches(&large_hidpi));
+
+    let hidpi = CssMediaQuery { media_type: CssMediaType::Screen, features: vec![MinResolution(2.0), MaxHeight(800.0)] };
+    assert!(!hidpi.matches(&small));
+    assert!(hidpi.matches(&large_hidpi));
+
+    let print = CssMediaQuery { media_type: CssMediaType::Print, features: Vec::new() };
+    assert!(!print.matches(&large_hidpi));
+
+    let stylesheet = Stylesheet { rules: Vec::new(), media_queries: vec![print, wide] };
+    assert!(!stylesheet.is_active(&small));
+    assert!(stylesheet.is_active(&large_hidpi));
+    assert!(Stylesheet::new().is_active(&small));
+}
+#[test]
+fn test_nth_child_selector_matches() {
+    use self::CssNthChildSelector::*;
+
+    let matching = |selector: CssNthChildSelector| (1..=10).filter(|i| selector.matches(*i)).collect::<Vec<usize>>();
+
+    assert_eq!(matching(Number(3)), vec![3]);
+    assert_eq!(matching(Even), vec![2, 4, 6, 8, 10]);
+    assert_eq!(matching(Odd), vec![1, 3, 5, 7, 9]);
+    assert_eq!(matching(Pattern { repeat: 3, offset: 1 }), vec![1, 4, 7, 10]);
+    assert_eq!(matching(Pattern { repeat: 2, offset: -1 }), vec![1, 3, 5, 7, 9]);
+    assert_eq!(matching(Pattern { repeat: -1, offset: 3 }), vec![1, 2, 3]);
+    assert_eq!(matching(Pattern { repeat: 0, offset: 5 }), vec![5]);
+    assert_eq!(matching(Pattern { repeat: 4, offset: 0 }), vec![4, 8]);
+}
+
+#[test]
+fn test_attribute_selector_matches() {
+    use self::CssAttributeMatcher::*;
+
+    let selector = |matcher| CssAttributeSelector { name: "data-state".into(), matcher };
+
+    assert!(selector(Exists).matches(""));
+    assert!(selector(Equals("error".into())).matches("error"));
+    assert!(!selector(Equals("error".into())).matches("error warning"));
+    assert!(selector(ContainsWord("warning".into())).matches("error warning"));
+    assert!(!selector(ContainsWord("warn".into())).matches("error warning"));
+    assert!(selector(StartsWith("err".into())).matches("error"));
+    assert!(selector(EndsWith("or".into())).matches("error"));
+    assert!(selector(Contains("rro".into())).matches("error"));
+    assert!(!selector(Contains("".into())).matches("error"));
+}
@@ -64,6 +64,28 @@ pub struct LayoutSideOffsets {
 #[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
 pub struct ColorU { pub r: u8, pub g: u8, pub b: u8, pub a: u8 }
 
+impl ColorU {
+
+    pub const TRANSPARENT: ColorU = ColorU { r: 0, g: 0, b: 0, a: 0 };
+
+    /// Linearly interpolates between `self` (`t = 0.0`) and `other` (`t = 1.0`)
+    pub fn interpolate(&self, other: &Self, t: f32) -> Self {
+        let t = t.max(0.0).min(1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        ColorU {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+            a: mix(self.a, other.a),
+        }
+    }
+
+    /// Formats the color as `#rrggbbaa`, i.e. for setting it as the value of a CSS variable
+    pub fn to_hex_string(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
+}
+
 /// f32-based color, range 0.0 to 1.0 (similar to webrenders ColorF)
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct ColorF { pub r: f32, pub g: f32, pub b: f32, pub a: f32 }
@@ -220,7 +242,7 @@ macro_rules! impl_float_value{($struct:ident) => (
 )}
 
 /// Map between CSS keys and a statically typed enum
-const CSS_PROPERTY_KEY_MAP: [(CssPropertyType, &'static str);56] = [
+const CSS_PROPERTY_KEY_MAP: [(CssPropertyType, &'static str);106] = [
     (CssPropertyType::Background,       "background"),
     (CssPropertyType::BackgroundSize,   "background-size"),
     (CssPropertyType::BackgroundRepeat, "background-repeat"),
@@ -236,7 +258,23 @@ const CSS_PROPERTY_KEY_MAP: [(CssPropertyType, &'static str);56] = [
     (CssPropertyType::LineHeight,       "line-height"),
     (CssPropertyType::WordSpacing,      "word-spacing"),
     (CssPropertyType::TabWidth,         "tab-width"),
+    (CssPropertyType::TabStops,         "tab-stops"),
+    (CssPropertyType::WhiteSpace,       "white-space"),
+    (CssPropertyType::TextOverflow,     "text-overflow"),
+    (CssPropertyType::TextDecoration,   "text-decoration"),
     (CssPropertyType::Cursor,           "cursor"),
+    (CssPropertyType::PointerEvents,    "pointer-events"),
+    (CssPropertyType::UserSelect,       "user-select"),
+    (CssPropertyType::Content,          "content"),
+    (CssPropertyType::TextDirection,    "direction"),
+    (CssPropertyType::Opacity,          "opacity"),
+    (CssPropertyType::Visibility,       "visibility"),
+    (CssPropertyType::MixBlendMode,     "mix-blend-mode"),
+    (CssPropertyType::ObjectFit,        "object-fit"),
+    (CssPropertyType::Transform,        "transform"),
+    (CssPropertyType::TransformOrigin,  "transform-origin"),
+    (CssPropertyType::TransformStyle,   "transform-style"),
+    (CssPropertyType::Perspective,      "perspective"),
     (CssPropertyType::Width,            "width"),
     (CssPropertyType::Height,           "height"),
     (CssPropertyType::MinWidth,         "min-width"),
@@ -252,27 +290,61 @@ const CSS_PROPERTY_KEY_MAP: [(CssPropertyType, &'static str);56] = [
     (CssPropertyType::FlexDirection,    "flex-direction"),
     (CssPropertyType::FlexGrow,         "flex-grow"),
     (CssPropertyType::FlexShrink,       "flex-shrink"),
+    (CssPropertyType::FlexBasis,        "flex-basis"),
+    (CssPropertyType::Order,            "order"),
     (CssPropertyType::JustifyContent,   "justify-content"),
     (CssPropertyType::AlignItems,       "align-items"),
     (CssPropertyType::AlignContent,     "align-content"),
+    (CssPropertyType::Display,          "display"),
+    (CssPropertyType::GridTemplateColumns, "grid-template-columns"),
+    (CssPropertyType::GridTemplateRows, "grid-template-rows"),
+    (CssPropertyType::GridGap,          "grid-gap"),
+    (CssPropertyType::Gap,              "gap"),
+    (CssPropertyType::RowGap,           "row-gap"),
+    (CssPropertyType::ColumnGap,        "column-gap"),
+    (CssPropertyType::GridColumn,       "grid-column"),
+    (CssPropertyType::GridRow,          "grid-row"),
+    (CssPropertyType::Float,            "float"),
     (CssPropertyType::Overflow,         "overflow"),
     (CssPropertyType::OverflowX,        "overflow-x"),
     (CssPropertyType::OverflowY,        "overflow-y"),
+    (CssPropertyType::ScrollSnapType,   "scroll-snap-type"),
+    (CssPropertyType::ScrollSnapAlign,  "scroll-snap-align"),
     (CssPropertyType::Padding,          "padding"),
     (CssPropertyType::PaddingTop,       "padding-top"),
     (CssPropertyType::PaddingLeft,      "padding-left"),
     (CssPropertyType::PaddingRight,     "padding-right"),
     (CssPropertyType::PaddingBottom,    "padding-bottom"),
+    (CssPropertyType::PaddingInline,    "padding-inline"),
+    (CssPropertyType::PaddingInlineStart, "padding-inline-start"),
+    (CssPropertyType::PaddingInlineEnd, "padding-inline-end"),
+    (CssPropertyType::PaddingBlock,     "padding-block"),
+    (CssPropertyType::PaddingBlockStart, "padding-block-start"),
+    (CssPropertyType::PaddingBlockEnd,  "padding-block-end"),
     (CssPropertyType::Margin,           "margin"),
     (CssPropertyType::MarginTop,        "margin-top"),
     (CssPropertyType::MarginLeft,       "margin-left"),
     (CssPropertyType::MarginRight,      "margin-right"),
     (CssPropertyType::MarginBottom,     "margin-bottom"),
+    (CssPropertyType::MarginInline,     "margin-inline"),
+    (CssPropertyType::MarginInlineStart, "margin-inline-start"),
+    (CssPropertyType::MarginInlineEnd,  "margin-inline-end"),
+    (CssPropertyType::MarginBlock,      "margin-block"),
+    (CssPropertyType::MarginBlockStart, "margin-block-start"),
+    (CssPropertyType::MarginBlockEnd,   "margin-block-end"),
     (CssPropertyType::Border,           "border"),
     (CssPropertyType::BorderTop,        "border-top"),
     (CssPropertyType::BorderLeft,       "border-left"),
     (CssPropertyType::BorderRight,      "border-right"),
     (CssPropertyType::BorderBottom,     "border-bottom"),
+    (CssPropertyType::BorderInline,     "border-inline"),
+    (CssPropertyType::BorderInlineStart, "border-inline-start"),
+    (CssPropertyType::BorderInlineEnd,  "border-inline-end"),
+    (CssPropertyType::BorderBlock,      "border-block"),
+    (CssPropertyType::BorderBlockStart, "border-block-start"),
+    (CssPropertyType::BorderBlockEnd,   "border-block-end"),
+    (CssPropertyType::Outline,          "outline"),
+    (CssPropertyType::OutlineOffset,    "outline-offset"),
     (CssPropertyType::BoxShadow,        "box-shadow"),
     (CssPropertyType::BoxShadowTop,     "box-shadow-top"),
     (CssPropertyType::BoxShadowLeft,    "box-shadow-left"),
@@ -303,8 +375,24 @@ pub enum CssPropertyType {
     LetterSpacing,
     WordSpacing,
     TabWidth,
+    TabStops,
+    WhiteSpace,
+    TextOverflow,
+    TextDecoration,
     LineHeight,
     Cursor,
+    PointerEvents,
+    UserSelect,
+    Content,
+    TextDirection,
+    Opacity,
+    Visibility,
+    MixBlendMode,
+    ObjectFit,
+    Transform,
+    TransformOrigin,
+    TransformStyle,
+    Perspective,
     Width,
     Height,
     MinWidth,
@@ -320,31 +408,67 @@ pub enum CssPropertyType {
     FlexDirection,
     FlexGrow,
     FlexShrink,
+    FlexBasis,
+    Order,
     JustifyContent,
     AlignItems,
     AlignContent,
 
+    Display,
+    GridTemplateColumns,
+    GridTemplateRows,
+    GridGap,
+    Gap,
+    RowGap,
+    ColumnGap,
+    GridColumn,
+    GridRow,
+    Float,
+
     Overflow,
     OverflowX,
     OverflowY,
+    ScrollSnapType,
+    ScrollSnapAlign,
 
     Padding,
     PaddingTop,
     PaddingLeft,
     PaddingRight,
     PaddingBottom,
+    PaddingInline,
+    PaddingInlineStart,
+    PaddingInlineEnd,
+    PaddingBlock,
+    PaddingBlockStart,
+    PaddingBlockEnd,
 
     Margin,
     MarginTop,
     MarginLeft,
     MarginRight,
     MarginBottom,
+    MarginInline,
+    MarginInlineStart,
+    MarginInlineEnd,
+    MarginBlock,
+    MarginBlockStart,
+    MarginBlockEnd,
 
     Border,
     BorderTop,
     BorderLeft,
     BorderRight,
     BorderBottom,
+    BorderInline,
+    BorderInlineStart,
+    BorderInlineEnd,
+    BorderBlock,
+    BorderBlockStart,
+    BorderBlockEnd,
+
+    Outline,
+    OutlineOffset,
 
     BoxShadow,
     BoxShadowTop,
@@ -384,7 +508,15 @@ impl CssPropertyType {
             | FontFamily
             | FontSize
             | LineHeight
-            | TextAlign => true,
+            | LetterSpacing
+            | WordSpacing
+            | TextAlign
+            | WhiteSpace
+            | TextDecoration
+            | PointerEvents
+            | UserSelect
+            | TextDirection
+            | Visibility => true,
             _ => false,
         }
     }
@@ -408,12 +540,27 @@ impl CssPropertyType {
             | TextColor
             | Background
             | TextAlign
+            | TextDecoration
             | BoxShadow
             | BoxShadowTop
             | BoxShadowLeft
             | BoxShadowBottom
             | BoxShadowRight
-            | Cursor => false,
+            | Outline
+            | OutlineOffset
+            | Opacity
+            | Visibility
+            | MixBlendMode
+            | ObjectFit
+            | Transform
+            | TransformOrigin
+            | TransformStyle
+            | Perspective
+            | Cursor
+            | PointerEvents
+            | UserSelect
+            | ScrollSnapType
+            | ScrollSnapAlign => false,
             _ => true,
         }
     }
@@ -440,10 +587,27 @@ pub enum CssProperty {
     TextAlign(StyleTextAlignmentHorz),
     LetterSpacing(StyleLetterSpacing),
     BoxShadow(StyleBoxShadow),
+    Outline(StyleOutline),
+    OutlineOffset(StyleOutlineOffset),
     LineHeight(StyleLineHeight),
     WordSpacing(StyleWordSpacing),
     TabWidth(StyleTabWidth),
+    TabStops(StyleTabStops),
+    WhiteSpace(StyleWhiteSpace),
+    TextOverflow(StyleTextOverflow),
+    TextDecoration(StyleTextDecoration),
     Cursor(StyleCursor),
+    PointerEvents(StylePointerEvents),
+    UserSelect(StyleUserSelect),
+    Content(StyleContent),
+    Opacity(StyleOpacity),
+    Visibility(StyleVisibility),
+    MixBlendMode(StyleMixBlendMode),
+    ObjectFit(StyleObjectFit),
+    Transform(StyleTransform),
+    TransformOrigin(StyleTransformOrigin),
+    TransformStyle(StyleTransformStyle),
+    Perspective(StylePerspective),
     Width(LayoutWidth),
     Height(LayoutHeight),
     MinWidth(LayoutMinWidth),
@@ -457,14 +621,33 @@ pub enum CssProperty {
     Bottom(LayoutBottom),
     Padding(LayoutPadding),
     Margin(LayoutMargin),
+    PaddingInline(LayoutPaddingInline),
+    MarginInline(LayoutMarginInline),
+    BorderInline(StyleBorderInline),
+    TextDirection(StyleTextDirection),
     FlexWrap(LayoutWrap),
     FlexDirection(LayoutDirection),
     FlexGrow(LayoutFlexGrow),
     FlexShrink(LayoutFlexShrink),
+    FlexBasis(LayoutFlexBasis),
+    Order(LayoutOrder),
     JustifyContent(LayoutJustifyContent),
     AlignItems(LayoutAlignItems),
     AlignContent(LayoutAlignContent),
+    Display(LayoutDisplay),
+    GridTemplateColumns(LayoutGridTemplateColumns),
+    GridTemplateRows(LayoutGridTemplateRows),
+    GridGap(LayoutGridGap),
+    Gap(LayoutGap),
+    RowGap(LayoutRowGap),
+    ColumnGap(LayoutColumnGap),
+    GridColumn(LayoutGridColumn),
+    GridRow(LayoutGridRow),
+    Float(LayoutFloat),
     Overflow(LayoutOverflow),
+    ScrollSnapType(LayoutScrollSnapType),
+    ScrollSnapAlign(LayoutScrollSnapAlign),
+    Calc(LayoutCalc),
 }
 
 impl CssProperty {
@@ -484,9 +667,26 @@ impl CssProperty {
             CssProperty::LetterSpacing(_) => CssPropertyType::LetterSpacing,
             CssProperty::WordSpacing(_) => CssPropertyType::WordSpacing,
             CssProperty::TabWidth(_) => CssPropertyType::TabWidth,
+            CssProperty::TabStops(_) => CssPropertyType::TabStops,
+            CssProperty::WhiteSpace(_) => CssPropertyType::WhiteSpace,
+            CssProperty::TextOverflow(_) => CssPropertyType::TextOverflow,
+            CssProperty::TextDecoration(_) => CssPropertyType::TextDecoration,
             CssProperty::BoxShadow(_) => CssPropertyType::BoxShadow,
+            CssProperty::Outline(_) => CssPropertyType::Outline,
+            CssProperty::OutlineOffset(_) => CssPropertyType::OutlineOffset,
             CssProperty::LineHeight(_) => CssPropertyType::LineHeight,
             CssProperty::Cursor(_) => CssPropertyType::Cursor,
+            CssProperty::PointerEvents(_) => CssPropertyType::PointerEvents,
+            CssProperty::UserSelect(_) => CssPropertyType::UserSelect,
+            CssProperty::Content(_) => CssPropertyType::Content,
+            CssProperty::Opacity(_) => CssPropertyType::Opacity,
+            CssProperty::Visibility(_) => CssPropertyType::Visibility,
+            CssProperty::MixBlendMode(_) => CssPropertyType::MixBlendMode,
+            CssProperty::ObjectFit(_) => CssPropertyType::ObjectFit,
+            CssProperty::Transform(_) => CssPropertyType::Transform,
+            CssProperty::TransformOrigin(_) => CssPropertyType::TransformOrigin,
+            CssProperty::TransformStyle(_) => CssPropertyType::TransformStyle,
+            CssProperty::Perspective(_) => CssPropertyType::Perspective,
             CssProperty::Width(_) => CssPropertyType::Width,
             CssProperty::Height(_) => CssPropertyType::Height,
             CssProperty::MinWidth(_) => CssPropertyType::MinWidth,
@@ -500,14 +700,33 @@ impl CssProperty {
             CssProperty::Bottom(_) => CssPropertyType::Bottom,
             CssProperty::Padding(_) => CssPropertyType::Padding,
             CssProperty::Margin(_) => CssPropertyType::Margin,
+            CssProperty::PaddingInline(_) => CssPropertyType::PaddingInline,
+            CssProperty::MarginInline(_) => CssPropertyType::MarginInline,
+            CssProperty::BorderInline(_) => CssPropertyType::BorderInline,
+            CssProperty::TextDirection(_) => CssPropertyType::TextDirection,
             CssProperty::FlexWrap(_) => CssPropertyType::FlexWrap,
             CssProperty::FlexDirection(_) => CssPropertyType::FlexDirection,
             CssProperty::FlexGrow(_) => CssPropertyType::FlexGrow,
             CssProperty::FlexShrink(_) => CssPropertyType::FlexShrink,
+            CssProperty::FlexBasis(_) => CssPropertyType::FlexBasis,
+            CssProperty::Order(_) => CssPropertyType::Order,
             CssProperty::JustifyContent(_) => CssPropertyType::JustifyContent,
             CssProperty::AlignItems(_) => CssPropertyType::AlignItems,
             CssProperty::AlignContent(_) => CssPropertyType::AlignContent,
+            CssProperty::Display(_) => CssPropertyType::Display,
+            CssProperty::GridTemplateColumns(_) => CssPropertyType::GridTemplateColumns,
+            CssProperty::GridTemplateRows(_) => CssPropertyType::GridTemplateRows,
+            CssProperty::GridGap(_) => CssPropertyType::GridGap,
+            CssProperty::Gap(_) => CssPropertyType::Gap,
+            CssProperty::RowGap(_) => CssPropertyType::RowGap,
+            CssProperty::ColumnGap(_) => CssPropertyType::ColumnGap,
+            CssProperty::GridColumn(_) => CssPropertyType::GridColumn,
+            CssProperty::GridRow(_) => CssPropertyType::GridRow,
+            CssProperty::Float(_) => CssPropertyType::Float,
             CssProperty::Overflow(_) => CssPropertyType::Overflow,
+            CssProperty::ScrollSnapType(_) => CssPropertyType::ScrollSnapType,
+            CssProperty::ScrollSnapAlign(_) => CssPropertyType::ScrollSnapAlign,
+            CssProperty::Calc(c) => c.property_type,
         }
     }
 }
@@ -521,12 +740,31 @@ impl_from!(StyleFontFamily, CssProperty::FontFamily);
 impl_from!(StyleTextAlignmentHorz, CssProperty::TextAlign);
 impl_from!(StyleLineHeight, CssProperty::LineHeight);
 impl_from!(StyleTabWidth, CssProperty::TabWidth);
+impl_from!(StyleTabStops, CssProperty::TabStops);
+impl_from!(StyleWhiteSpace, CssProperty::WhiteSpace);
+impl_from!(StyleTextOverflow, CssProperty::TextOverflow);
+impl_from!(StyleTextDecoration, CssProperty::TextDecoration);
 impl_from!(StyleWordSpacing, CssProperty::WordSpacing);
 impl_from!(StyleLetterSpacing, CssProperty::LetterSpacing);
 impl_from!(StyleBackgroundSize, CssProperty::BackgroundSize);
 impl_from!(StyleBackgroundRepeat, CssProperty::BackgroundRepeat);
 impl_from!(StyleTextColor, CssProperty::TextColor);
 impl_from!(StyleCursor, CssProperty::Cursor);
+impl_from!(StylePointerEvents, CssProperty::PointerEvents);
+impl_from!(StyleUserSelect, CssProperty::UserSelect);
+impl_from!(StyleContent, CssProperty::Content);
+impl_from!(StyleTextDirection, CssProperty::TextDirection);
+impl_from!(StyleBorderInline, CssProperty::BorderInline);
+impl_from!(StyleOpacity, CssProperty::Opacity);
+impl_from!(StyleVisibility, CssProperty::Visibility);
+impl_from!(StyleMixBlendMode, CssProperty::MixBlendMode);
+impl_from!(StyleObjectFit, CssProperty::ObjectFit);
+impl_from!(StyleOutline, CssProperty::Outline);
+impl_from!(StyleOutlineOffset, CssProperty::OutlineOffset);
+impl_from!(StyleTransform, CssProperty::Transform);
+impl_from!(StyleTransformOrigin, CssProperty::TransformOrigin);
+impl_from!(StyleTransformStyle, CssProperty::TransformStyle);
+impl_from!(StylePerspective, CssProperty::Perspective);
 
 impl_from!(LayoutOverflow, CssProperty::Overflow);
 impl_from!(LayoutWidth, CssProperty::Width);
@@ -535,6 +773,7 @@ impl_from!(LayoutMinWidth, CssProperty::MinWidth);
 impl_from!(LayoutMinHeight, CssProperty::MinHeight);
 impl_from!(LayoutMaxWidth, CssProperty::MaxWidth);
 impl_from!(LayoutMaxHeight, CssProperty::MaxHeight);
+impl_from!(LayoutCalc, CssProperty::Calc);
 
 impl_from!(LayoutPosition, CssProperty::Position);
 impl_from!(LayoutTop, CssProperty::Top);
@@ -544,15 +783,32 @@ impl_from!(LayoutLeft, CssProperty::Left);
 
 impl_from!(LayoutPadding, CssProperty::Padding);
 impl_from!(LayoutMargin, CssProperty::Margin);
+impl_from!(LayoutPaddingInline, CssProperty::PaddingInline);
+impl_from!(LayoutMarginInline, CssProperty::MarginInline);
 
 impl_from!(LayoutWrap, CssProperty::FlexWrap);
 impl_from!(LayoutDirection, CssProperty::FlexDirection);
 impl_from!(LayoutFlexGrow, CssProperty::FlexGrow);
 impl_from!(LayoutFlexShrink, CssProperty::FlexShrink);
+impl_from!(LayoutFlexBasis, CssProperty::FlexBasis);
+impl_from!(LayoutOrder, CssProperty::Order);
 impl_from!(LayoutJustifyContent, CssProperty::JustifyContent);
 impl_from!(LayoutAlignItems, CssProperty::AlignItems);
 impl_from!(LayoutAlignContent, CssProperty::AlignContent);
 
+impl_from!(LayoutDisplay, CssProperty::Display);
+impl_from!(LayoutGridTemplateColumns, CssProperty::GridTemplateColumns);
+impl_from!(LayoutGridTemplateRows, CssProperty::GridTemplateRows);
+impl_from!(LayoutGridGap, CssProperty::GridGap);
+impl_from!(LayoutGap, CssProperty::Gap);
+impl_from!(LayoutRowGap, CssProperty::RowGap);
+impl_from!(LayoutColumnGap, CssProperty::ColumnGap);
+impl_from!(LayoutGridColumn, CssProperty::GridColumn);
+impl_from!(LayoutGridRow, CssProperty::GridRow);
+impl_from!(LayoutFloat, CssProperty::Float);
+impl_from!(LayoutScrollSnapType, CssProperty::ScrollSnapType);
+impl_from!(LayoutScrollSnapAlign, CssProperty::ScrollSnapAlign);
+
 /// Multiplier for floating point accuracy. Elements such as px or %
 /// are only accurate until a certain number of decimal points, therefore
 /// they have to be casted to isizes in order to make the f32 values
@@ -571,7 +827,10 @@ pub struct PixelValue {
 // Manual Debug implementation, because the auto-generated one is nearly unreadable
 impl fmt::Debug for PixelValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}{:?}", self.number, self.metric)
+        match self.metric {
+            SizeMetric::Auto => write!(f, "auto"),
+            _ => write!(f, "{:?}{:?}", self.number, self.metric),
+        }
     }
 }
 
@@ -582,6 +841,8 @@ impl fmt::Debug for SizeMetric {
             Px => write!(f, "px"),
             Pt => write!(f, "pt"),
             Em => write!(f, "pt"),
+            Percent => write!(f, "%"),
+            Auto => write!(f, "auto"),
         }
     }
 }
@@ -638,6 +899,18 @@ impl PixelValue {
         Self::from_metric(SizeMetric::Pt, value)
     }
 
+    /// Percentage of the width of the containing block, see `SizeMetric::Percent`
+    #[inline]
+    pub fn percent(value: f32) -> Self {
+        Self::from_metric(SizeMetric::Percent, value)
+    }
+
+    /// `auto` margin, see `SizeMetric::Auto`
+    #[inline]
+    pub const fn auto() -> Self {
+        Self::const_from_metric(SizeMetric::Auto, 0)
+    }
+
     #[inline]
     pub fn from_metric(metric: SizeMetric, value: f32) -> Self {
         Self {
@@ -646,13 +919,30 @@ impl PixelValue {
         }
     }
 
-    /// Returns the value of the SizeMetric in pixels
+    #[inline]
+    pub fn is_auto(&self) -> bool {
+        self.metric == SizeMetric::Auto
+    }
+
+    /// Returns the value of the SizeMetric in pixels. Percentages and `auto`
+    /// can only be resolved by the layout, so they are `0.0` here.
     #[inline]
     pub fn to_pixels(&self) -> f32 {
         match self.metric {
             SizeMetric::Px => { self.number.get() },
             SizeMetric::Pt => { (self.number.get()) * PT_TO_PX },
             SizeMetric::Em => { (self.number.get()) * EM_HEIGHT },
+            SizeMetric::Percent | SizeMetric::Auto => 0.0,
+        }
+    }
+
+    /// Converts a percentage into pixels, `percentage_basis` is the size (in pixels)
+    /// that `100%` refers to. All other values are returned unchanged.
+    #[inline]
+    pub fn resolve_percentage(&self, percentage_basis: f32) -> Self {
+        match self.metric {
+            SizeMetric::Percent => Self::px(percentage_basis * self.number.get() / 100.0),
+            _ => *self,
         }
     }
 }
@@ -687,6 +977,70 @@ impl PercentageValue {
     }
 }
 
+/// Parsed `calc()` expression, i.e. `calc(100% - 20px)`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CalcExpression {
+    /// Fixed length, i.e. `20px`
+    Length(PixelValue),
+    /// Percentage of the size of the parent, i.e. `100%`
+    Percentage(PercentageValue),
+    /// Unitless number, used as a factor or divisor, i.e. the `2` in `calc(100% / 2)`
+    Number(FloatValue),
+    Add(Box<CalcExpression>, Box<CalcExpression>),
+    Sub(Box<CalcExpression>, Box<CalcExpression>),
+    Mul(Box<CalcExpression>, Box<CalcExpression>),
+    Div(Box<CalcExpression>, Box<CalcExpression>),
+}
+
+impl CalcExpression {
+    /// Evaluates the expression to a value in pixels, `percentage_basis` is the size
+    /// (in pixels) that `100%` refers to. Divisions by zero evaluate to `0.0`.
+    pub fn evaluate(&self, percentage_basis: f32) -> f32 {
+        use self::CalcExpression::*;
+        match self {
+            Length(px) => px.to_pixels(),
+            Percentage(p) => percentage_basis * p.get() / 100.0,
+            Number(n) => n.get(),
+            Add(a, b) => a.evaluate(percentage_basis) + b.evaluate(percentage_basis),
+            Sub(a, b) => a.evaluate(percentage_basis) - b.evaluate(percentage_basis),
+            Mul(a, b) => a.evaluate(percentage_basis) * b.evaluate(percentage_basis),
+            Div(a, b) => {
+                let divisor = b.evaluate(percentage_basis);
+                if divisor == 0.0 { 0.0 } else { a.evaluate(percentage_basis) / divisor }
+            },
+        }
+    }
+}
+
+/// `calc()` expression of a dimension property (`width`, `min-height`, etc.), i.e.
+/// `width: calc(100% - 20px)`. Since percentages can only be resolved once the size
+/// of the parent is known, the expression is evaluated during the layout.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutCalc {
+    /// Which property the expression is the value for
+    pub property_type: CssPropertyType,
+    pub expression: CalcExpression,
+}
+
+impl LayoutCalc {
+    /// Evaluates the expression against the size of the parent and writes the
+    /// result into the matching field of the `layout`, as a value in pixels
+    pub fn apply(&self, layout: &mut RectLayout, parent_width: f32, parent_height: f32) {
+        use self::CssPropertyType::*;
+        let width = || self.expression.evaluate(parent_width).max(0.0);
+        let height = || self.expression.evaluate(parent_height).max(0.0);
+        match self.property_type {
+            Width => layout.width = Some(LayoutWidth::px(width())),
+            MinWidth => layout.min_width = Some(LayoutMinWidth::px(width())),
+            MaxWidth => layout.max_width = Some(LayoutMaxWidth::px(width())),
+            Height => layout.height = Some(LayoutHeight::px(height())),
+            MinHeight => layout.min_height = Some(LayoutMinHeight::px(height())),
+            MaxHeight => layout.max_height = Some(LayoutMaxHeight::px(height())),
+            _ => { },
+        }
+    }
+}
+
 /// Wrapper around an f32 value that is internally casted to an isize,
 /// in order to provide hash-ability (to avoid numerical instability).
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -723,6 +1077,12 @@ pub enum SizeMetric {
     Px,
     Pt,
     Em,
+    /// Percentage of the width of the containing block - only
+    /// supported by the `padding` and `margin` properties
+    Percent,
+    /// `auto`, the number is ignored - only supported by the `margin` properties,
+    /// where the auto margins take up the free space of the flex line
+    Auto,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -811,6 +1171,48 @@ pub struct LayoutMargin {
     pub right: Option<PixelValue>,
 }
 
+// $struct_name has to have start and end properties, $physical_struct has to
+// have top, left, right, bottom properties
+macro_rules! logical_inline_struct {($struct_name:ident, $physical_struct:ident) => (
+impl $struct_name {
+    pub fn merge(a: &mut Option<$struct_name>, b: &$struct_name) {
+       if let Some(ref mut existing) = a {
+           if b.start.is_some() { existing.start = b.start; }
+           if b.end.is_some() { existing.end = b.end; }
+       } else {
+           *a = Some(*b);
+       }
+    }
+
+    /// Resolves the start and end sides against the writing `direction`:
+    /// `start` is the left side for left-to-right text, the right side for right-to-left text
+    pub fn to_physical(&self, direction: StyleTextDirection) -> $physical_struct {
+        let (left, right) = match direction {
+            StyleTextDirection::Ltr => (self.start, self.end),
+            StyleTextDirection::Rtl => (self.end, self.start),
+        };
+        $physical_struct { left, right, .. Default::default() }
+    }
+})}
+
+/// Represents a `padding-inline{-start,-end}` attribute. Since only horizontal writing modes are
+/// supported, the `padding-block` properties are parsed directly into a `LayoutPadding` instead.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutPaddingInline {
+    pub start: Option<PixelValue>,
+    pub end: Option<PixelValue>,
+}
+
+/// Represents a `margin-inline{-start,-end}` attribute
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutMarginInline {
+    pub start: Option<PixelValue>,
+    pub end: Option<PixelValue>,
+}
+
+logical_inline_struct!(LayoutPaddingInline, LayoutPadding);
+logical_inline_struct!(LayoutMarginInline, LayoutMargin);
+
 /// Wrapper for the `overflow-{x,y}` + `overflow` property
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LayoutOverflow {
@@ -866,6 +1268,15 @@ pub struct StyleBorder {
 merge_struct!(StyleBorder);
 struct_all!(StyleBorder, StyleBorderSide);
 
+/// Represents a `border-inline{-start,-end}` attribute
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBorderInline {
+    pub start: Option<StyleBorderSide>,
+    pub end: Option<StyleBorderSide>,
+}
+
+logical_inline_struct!(StyleBorderInline, StyleBorder);
+
 impl StyleBorder {
 
     /// Returns the merged offsets and details for the top, left,
@@ -924,17 +1335,68 @@ pub struct StyleBorderSide {
     pub border_color: ColorU,
 }
 
+/// Represents an `outline` attribute, i.e. `"2px solid blue"`
+///
+/// Drawn outside of the border box, like a border, but doesn't take up any
+/// space, so it can be toggled (for example on `:focus`) without a relayout.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleOutline(pub StyleBorderSide);
+
+/// Represents an `outline-offset` attribute: the distance between
+/// the border box and the outline (can be negative)
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleOutlineOffset(pub PixelValue);
+
 /// Represents a `box-shadow` attribute.
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StyleBoxShadow {
     pub top: Option<Option<BoxShadowPreDisplayItem>>,
     pub left: Option<Option<BoxShadowPreDisplayItem>>,
     pub bottom: Option<Option<BoxShadowPreDisplayItem>>,
     pub right: Option<Option<BoxShadowPreDisplayItem>>,
+    /// In a comma-separated `box-shadow` list, the first shadow is stored in the
+    /// four edges, the remaining shadows are stored here (in the order of the CSS,
+    /// so each shadow is drawn below the previous one). `None` if the
+    /// `box-shadow` property wasn't set.
+    pub additional_shadows: Option<Vec<BoxShadowPreDisplayItem>>,
 }
 
-merge_struct!(StyleBoxShadow);
-struct_all!(StyleBoxShadow, Option<BoxShadowPreDisplayItem>);
+impl StyleBoxShadow {
+
+    /// Sets all of the fields (top, left, right, bottom) to `Some(field)`
+    pub fn all(field: Option<BoxShadowPreDisplayItem>) -> Self {
+        Self {
+            top: Some(field),
+            right: Some(field),
+            left: Some(field),
+            bottom: Some(field),
+            additional_shadows: None,
+        }
+    }
+
+    /// Creates the shadow from a comma-separated `box-shadow` list,
+    /// an empty list is the same as `box-shadow: none`
+    pub fn from_list(shadows: Vec<BoxShadowPreDisplayItem>) -> Self {
+        let mut shadows = shadows.into_iter();
+        let first_shadow = shadows.next();
+        Self {
+            additional_shadows: Some(shadows.collect()),
+            .. Self::all(first_shadow)
+        }
+    }
+
+    pub fn merge(a: &mut Option<StyleBoxShadow>, b: &StyleBoxShadow) {
+       if let Some(ref mut existing) = a {
+           if b.top.is_some() { existing.top = b.top; }
+           if b.bottom.is_some() { existing.bottom = b.bottom; }
+           if b.left.is_some() { existing.left = b.left; }
+           if b.right.is_some() { existing.right = b.right; }
+           if b.additional_shadows.is_some() { existing.additional_shadows = b.additional_shadows.clone(); }
+       } else {
+           *a = Some(b.clone());
+       }
+    }
+}
 
 // missing StyleBorderRadius & LayoutRect
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -950,6 +1412,7 @@ pub struct BoxShadowPreDisplayItem {
 pub enum StyleBackground {
     LinearGradient(LinearGradient),
     RadialGradient(RadialGradient),
+    ConicGradient(ConicGradient),
     Image(CssImageId),
     Color(ColorU),
     NoBackground,
@@ -985,6 +1448,56 @@ pub struct RadialGradient {
     pub stops: Vec<GradientStopPre>,
 }
 
+/// Gradient that goes around a center point, i.e. `conic-gradient(from 90deg at 50% 50%, red, blue)`.
+/// The offsets of the stops are percentages of a full turn.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConicGradient {
+    /// Start angle in degrees, clockwise, starting at the top (`from 90deg`)
+    pub angle: FloatValue,
+    /// Center of the gradient, relative to the size of the rectangle (`at 25% 50%`)
+    pub center: (PercentageValue, PercentageValue),
+    pub extend_mode: ExtendMode,
+    pub stops: Vec<GradientStopPre>,
+}
+
+impl ConicGradient {
+    /// Returns the color at the `position` (in percent of a full turn, after the start angle)
+    pub fn get_color_at(&self, position: f32) -> ColorU {
+
+        let first = match self.stops.first() { Some(s) => s, None => return ColorU::TRANSPARENT };
+        let last = match self.stops.last() { Some(s) => s, None => return ColorU::TRANSPARENT };
+        let first_offset = first.offset.map(|o| o.get()).unwrap_or(0.0);
+        let last_offset = last.offset.map(|o| o.get()).unwrap_or(100.0);
+
+        // Repeating gradients repeat the range between the first and the last stop
+        let position = match self.extend_mode {
+            ExtendMode::Repeat if last_offset > first_offset => {
+                let range = last_offset - first_offset;
+                first_offset + (((position - first_offset) % range) + range) % range
+            },
+            _ => position,
+        };
+
+        if position <= first_offset {
+            return first.color;
+        }
+
+        for stops in self.stops.windows(2) {
+            let (start, end) = (&stops[0], &stops[1]);
+            let start_offset = start.offset.map(|o| o.get()).unwrap_or(0.0);
+            let end_offset = end.offset.map(|o| o.get()).unwrap_or(100.0);
+            if position <= end_offset {
+                if end_offset <= start_offset {
+                    return end.color;
+                }
+                return start.color.interpolate(&end.color, (position - start_offset) / (end_offset - start_offset));
+            }
+        }
+
+        last.color
+    }
+}
+
 /// CSS direction (necessary for gradients). Can either be a fixed angle or
 /// a direction ("to right" / "to left", etc.).
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -1137,6 +1650,41 @@ impl Default for StyleCursor {
     }
 }
 
+/// Represents a `pointer-events` attribute - default: `Auto`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StylePointerEvents {
+    /// The node receives mouse events (if it has any callbacks)
+    Auto,
+    /// The node is ignored during hit-testing, so it never receives mouse events
+    /// or gets a `:hover` state. The nodes below it are still hit as usual.
+    None,
+}
+
+impl Default for StylePointerEvents {
+    fn default() -> Self {
+        StylePointerEvents::Auto
+    }
+}
+
+/// Represents a `user-select` attribute, whether the text of a node can be selected - default: `Auto`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleUserSelect {
+    /// Same as `Text`
+    Auto,
+    /// The text can't be selected, i.e. for the labels of buttons
+    None,
+    /// The text can be selected
+    Text,
+    /// The whole text of the node is selected at once, i.e. for IDs that are copied as a whole
+    All,
+}
+
+impl Default for StyleUserSelect {
+    fn default() -> Self {
+        StyleUserSelect::Auto
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum DirectionCorner {
     Right,
@@ -1199,6 +1747,8 @@ pub enum BackgroundType {
     RepeatingLinearGradient,
     RadialGradient,
     RepeatingRadialGradient,
+    ConicGradient,
+    RepeatingConicGradient,
     Image,
 }
 
@@ -1260,6 +1810,34 @@ pub struct LayoutFlexShrink(pub FloatValue);
 impl_float_value!(LayoutFlexGrow);
 impl_float_value!(LayoutFlexShrink);
 
+/// Represents a `flex-basis` attribute - default: `Auto`
+///
+/// The size of a flex item along the main axis before the free space
+/// of the parent is distributed (via `flex-grow` / `flex-shrink`)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayoutFlexBasis {
+    /// Use the `width` / `height` (if set), otherwise start from zero, so that
+    /// the free space gets distributed equally between the items
+    Auto,
+    /// Size of the content of the item
+    Content,
+    /// Fixed size, i.e. `flex-basis: 200px`
+    Exact(PixelValue),
+}
+
+impl Default for LayoutFlexBasis {
+    fn default() -> Self {
+        LayoutFlexBasis::Auto
+    }
+}
+
+/// Represents an `order` attribute - default: `0`
+///
+/// Flex items are laid out in ascending order, items with the
+/// same order keep the order in which they appear in the DOM
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutOrder(pub i32);
+
 /// Represents a `flex-direction` attribute - default: `Column`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LayoutDirection {
@@ -1288,6 +1866,17 @@ impl LayoutDirection {
     pub fn is_reverse(&self) -> bool {
         *self == LayoutDirection::RowReverse || *self == LayoutDirection::ColumnReverse
     }
+
+    /// Rows start at the right edge in right-to-left text, so the main axis of a `row` is
+    /// laid out like a `row-reverse` (and vice versa) - columns are not affected
+    pub fn to_physical(&self, direction: StyleTextDirection) -> Self {
+        use self::LayoutDirection::*;
+        match (*self, direction) {
+            (Row, StyleTextDirection::Rtl) => RowReverse,
+            (RowReverse, StyleTextDirection::Rtl) => Row,
+            (other, _) => other,
+        }
+    }
 }
 
 /// Represents a `line-height` attribute
@@ -1302,9 +1891,210 @@ pub struct StyleLetterSpacing(pub PixelValue);
 /// Represents a `word-spacing` attribute
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StyleWordSpacing(pub PixelValue);
+/// Represents a `content` attribute, the text of a `::before` or `::after`
+/// pseudo-element (an empty string doesn't generate a pseudo-element)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleContent(pub String);
+/// Represents an `opacity` attribute: the node and all of its children are
+/// composited as one layer, which is then drawn with the given alpha
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleOpacity(pub PercentageValue);
 
 impl_percentage_value!(StyleTabWidth);
 impl_percentage_value!(StyleLineHeight);
+impl_percentage_value!(StyleOpacity);
+
+/// Represents a `tab-stops` attribute, i.e. `tab-stops: 200px dots, 300px`: the
+/// positions that a tab character advances the caret to - default: no tab stops
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleTabStops(pub Vec<StyleTabStop>);
+
+/// One tab stop of a `tab-stops` attribute
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleTabStop {
+    /// Horizontal distance from the start of the line
+    pub position: PixelValue,
+    /// Characters that the space in front of the tab stop is filled with, if any
+    pub leader: Option<StyleTabLeader>,
+}
+
+/// Characters that the space in front of a tab stop can be filled with
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleTabLeader {
+    /// `.....`
+    Dots,
+    /// `-----`
+    Dashes,
+    /// `_____`
+    Underline,
+}
+
+impl StyleTabLeader {
+    /// The character that is repeated to fill the space in front of a tab stop
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StyleTabLeader::Dots => ".",
+            StyleTabLeader::Dashes => "-",
+            StyleTabLeader::Underline => "_",
+        }
+    }
+}
+
+/// Represents a `visibility` attribute - default: `Visible`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleVisibility {
+    Visible,
+    /// The node keeps its space in the layout, but isn't drawn and can't be hit.
+    /// Unlike `opacity: 0`, children can still be shown via `visibility: visible`.
+    Hidden,
+}
+
+impl Default for StyleVisibility {
+    fn default() -> Self {
+        StyleVisibility::Visible
+    }
+}
+
+/// Represents a `mix-blend-mode` attribute - default: `Normal`
+///
+/// Blends the node (and its children) with the content behind it,
+/// i.e. `multiply` darkens and `screen` brightens the background.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleMixBlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl Default for StyleMixBlendMode {
+    fn default() -> Self {
+        StyleMixBlendMode::Normal
+    }
+}
+
+/// Represents an `object-fit` attribute, how the image of an image node is scaled
+/// to the bounds of the node - default: `Fill`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleObjectFit {
+    /// The image is stretched to the bounds, ignoring its aspect ratio
+    Fill,
+    /// The image is scaled to fit into the bounds, keeping its aspect ratio
+    /// (so it may not cover the whole node)
+    Contain,
+    /// The image is scaled to cover the whole node, keeping its aspect ratio.
+    /// The parts outside of the bounds are clipped.
+    Cover,
+    /// The image isn't scaled, but centered in the bounds
+    None,
+    /// Same as `None` or `Contain`, whichever results in a smaller image
+    ScaleDown,
+}
+
+impl Default for StyleObjectFit {
+    fn default() -> Self {
+        StyleObjectFit::Fill
+    }
+}
+
+/// Represents a `transform` attribute, i.e. `translate(10px, 0px) rotate(45deg)`.
+/// The functions are applied from right to left (as in CSS), an empty list is `transform: none`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleTransform(pub Vec<TransformFunction>);
+
+/// A single function of a `transform` attribute, angles are in degrees (clockwise)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TransformFunction {
+    /// `matrix(a, b, c, d, tx, ty)`
+    Matrix([FloatValue;6]),
+    /// `translate(x, y)`, `translateX(x)`, `translateY(y)`
+    Translate(PixelValue, PixelValue),
+    /// `translateZ(z)`, positive values move the node towards the viewer
+    TranslateZ(PixelValue),
+    /// `scale(x, y)`, `scaleX(x)`, `scaleY(y)`
+    Scale(FloatValue, FloatValue),
+    /// `rotate(angle)`, `rotateZ(angle)`
+    Rotate(FloatValue),
+    /// `rotateX(angle)`, rotation around the horizontal axis
+    RotateX(FloatValue),
+    /// `rotateY(angle)`, rotation around the vertical axis
+    RotateY(FloatValue),
+    /// `skew(x-angle, y-angle)`, `skewX(angle)`, `skewY(angle)`
+    Skew(FloatValue, FloatValue),
+    /// `perspective(distance)`, only affects the node itself (as opposed
+    /// to the `perspective` property, which affects the children)
+    Perspective(PixelValue),
+}
+
+/// Represents a `transform-origin` attribute: The point that the `transform`
+/// scales and rotates around, relative to the top left corner of the node
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleTransformOrigin {
+    pub x: TransformOriginValue,
+    pub y: TransformOriginValue,
+}
+
+impl Default for StyleTransformOrigin {
+    /// Default is the center of the node (`transform-origin: 50% 50%`)
+    fn default() -> Self {
+        Self {
+            x: TransformOriginValue::Percentage(PercentageValue::const_new(50)),
+            y: TransformOriginValue::Percentage(PercentageValue::const_new(50)),
+        }
+    }
+}
+
+/// One coordinate of the `transform-origin`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TransformOriginValue {
+    /// Fixed offset, i.e. `10px`
+    Pixels(PixelValue),
+    /// Percentage of the width or height of the node, i.e. `50%`, `left` or `center`
+    Percentage(PercentageValue),
+}
+
+impl TransformOriginValue {
+    /// Resolves the offset in pixels, given the width or height of the node
+    pub fn to_pixels(&self, size: f32) -> f32 {
+        match self {
+            TransformOriginValue::Pixels(p) => p.to_pixels(),
+            TransformOriginValue::Percentage(p) => p.get() / 100.0 * size,
+        }
+    }
+}
+
+/// Represents a `transform-style` attribute: whether the 3D-transformed children of
+/// a node are rendered in the 3D space of the node or flattened into its plane
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleTransformStyle {
+    /// `flat`
+    Flat,
+    /// `preserve-3d`
+    Preserve3D,
+}
+
+impl Default for StyleTransformStyle {
+    fn default() -> Self {
+        StyleTransformStyle::Flat
+    }
+}
+
+/// Represents a `perspective` attribute: the distance of the viewer to the plane of the node,
+/// applied to the 3D transforms of all children. `None` is `perspective: none`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StylePerspective(pub Option<PixelValue>);
 
 /// Same as the `LayoutDirection`, but without the `-reverse` properties, used in the layout solver,
 /// makes decisions based on horizontal / vertical direction easier to write.
@@ -1323,6 +2113,9 @@ pub enum LayoutPosition {
     Static,
     Relative,
     Absolute,
+    /// Laid out like `Relative`, but sticks to the edges of the scroll container
+    /// given by `top` / `right` / `bottom` / `left` while its parent is visible
+    Sticky,
 }
 
 impl Default for LayoutPosition {
@@ -1376,6 +2169,9 @@ pub enum LayoutAlignItems {
     Start,
     /// Items are positioned at the end of the container
     End,
+    /// Items are positioned so that the baselines of their first lines of text line up
+    /// (only in `flex-direction: row` containers, otherwise same as `Start`)
+    Baseline,
 }
 
 impl Default for LayoutAlignItems {
@@ -1401,6 +2197,194 @@ pub enum LayoutAlignContent {
     SpaceAround,
 }
 
+/// Represents a `display` attribute - default: `Flex`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayoutDisplay {
+    /// Children are laid out along the `flex-direction`
+    Flex,
+    /// Children are placed into the cells of the `grid-template-columns` / `grid-template-rows`
+    Grid,
+    /// The node flows within the lines of its parent, text continues where the
+    /// previous inline sibling left off and wraps at the width of the parent
+    Inline,
+    /// Same as `Inline`, but the node is placed as a single box (like an image),
+    /// its children are laid out inside of it
+    InlineBlock,
+}
+
+impl Default for LayoutDisplay {
+    fn default() -> Self {
+        LayoutDisplay::Flex
+    }
+}
+
+/// Represents a `float` attribute - default: `None`
+///
+/// A floating node is moved to the left or right edge of its parent, the text of the
+/// following siblings wraps around it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayoutFloat {
+    Left,
+    Right,
+    None,
+}
+
+impl Default for LayoutFloat {
+    fn default() -> Self {
+        LayoutFloat::None
+    }
+}
+
+/// Represents a `scroll-snap-type` attribute - default: `none`
+///
+/// Set on a scroll container: after the user stopped scrolling, the container
+/// scrolls to the closest `scroll-snap-align` position of its children.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutScrollSnapType {
+    pub axis: ScrollSnapAxis,
+    pub strictness: ScrollSnapStrictness,
+}
+
+impl Default for LayoutScrollSnapType {
+    fn default() -> Self {
+        LayoutScrollSnapType {
+            axis: ScrollSnapAxis::None,
+            strictness: ScrollSnapStrictness::Proximity,
+        }
+    }
+}
+
+/// Directions in which a scroll container snaps (`x`, `y` or `both`)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScrollSnapAxis {
+    None,
+    X,
+    Y,
+    Both,
+}
+
+impl ScrollSnapAxis {
+
+    pub fn snaps_horizontal(&self) -> bool {
+        *self == ScrollSnapAxis::X || *self == ScrollSnapAxis::Both
+    }
+
+    pub fn snaps_vertical(&self) -> bool {
+        *self == ScrollSnapAxis::Y || *self == ScrollSnapAxis::Both
+    }
+}
+
+/// Whether a scroll container always snaps or only when it is near a snap position
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScrollSnapStrictness {
+    /// Always snaps to the closest snap position, i.e. for carousels and paged lists
+    Mandatory,
+    /// Only snaps if the scroll position is already close to a snap position (default)
+    Proximity,
+}
+
+/// Represents a `scroll-snap-align` attribute - default: `none`
+///
+/// Which edge of a child is aligned to the same edge of the scroll container when
+/// the container snaps. Like in CSS, the first value is for the vertical (block)
+/// axis and the optional second value for the horizontal (inline) axis.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutScrollSnapAlign {
+    pub vertical: ScrollSnapAlignment,
+    pub horizontal: ScrollSnapAlignment,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScrollSnapAlignment {
+    None,
+    Start,
+    Center,
+    End,
+}
+
+impl Default for ScrollSnapAlignment {
+    fn default() -> Self {
+        ScrollSnapAlignment::None
+    }
+}
+
+/// Size of a single column or row of a `display: grid` container
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GridTrackSize {
+    /// Fixed size, i.e. `200px`
+    Fixed(PixelValue),
+    /// Percentage of the inner size of the grid container, i.e. `25%`
+    Percentage(PercentageValue),
+    /// Share of the space that is left over after all other tracks have been sized, i.e. `1fr`
+    Fraction(FloatValue),
+    /// As large as the largest item in the track, takes up leftover space if there are no `fr` tracks
+    Auto,
+}
+
+/// Represents a `grid-template-columns` attribute, i.e. `200px 1fr 1fr`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridTemplateColumns(pub Vec<GridTrackSize>);
+
+/// Represents a `grid-template-rows` attribute, i.e. `auto 1fr`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridTemplateRows(pub Vec<GridTrackSize>);
+
+/// Represents a `grid-gap` attribute: space between the rows and columns of a grid
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridGap {
+    pub row: PixelValue,
+    pub column: PixelValue,
+}
+
+/// Represents a `gap` attribute: shorthand for `row-gap` and `column-gap`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGap {
+    pub row: PixelValue,
+    pub column: PixelValue,
+}
+
+/// Represents a `row-gap` attribute: space between the rows of a grid or
+/// between the children of a `flex-direction: column` container
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutRowGap(pub PixelValue);
+
+/// Represents a `column-gap` attribute: space between the columns of a grid or
+/// between the children of a `flex-direction: row` container
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutColumnGap(pub PixelValue);
+
+/// Start or end of a `grid-column` / `grid-row` placement
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GridLine {
+    /// Placed automatically, in the next free cell
+    Auto,
+    /// Grid line, starting at 1 - negative lines count from the end of the grid (`-1` = last line)
+    Line(isize),
+    /// Spans the given number of tracks, i.e. `span 2`
+    Span(usize),
+}
+
+impl Default for GridLine {
+    fn default() -> Self {
+        GridLine::Auto
+    }
+}
+
+/// Line-based placement of a grid item along one axis, i.e. `1 / 3` or `2 / span 2`
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GridPlacement {
+    pub start: GridLine,
+    pub end: GridLine,
+}
+
+/// Represents a `grid-column` attribute
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridColumn(pub GridPlacement);
+
+/// Represents a `grid-row` attribute
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridRow(pub GridPlacement);
+
 /// Represents a `overflow-x` or `overflow-y` property, see
 /// [`TextOverflowBehaviour`](./struct.TextOverflowBehaviour.html) - default: `Auto`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -1444,12 +2428,19 @@ impl Overflow {
     }
 }
 
-/// Horizontal text alignment enum (left, center, right) - default: `Center`
+/// Horizontal text alignment enum (left, center, right, justify, start, end) - default: `Center`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum StyleTextAlignmentHorz {
     Left,
     Center,
     Right,
+    /// Stretches the spaces between the words so that every line (except for the
+    /// last line of a paragraph) fills the entire width
+    Justify,
+    /// `Left` for left-to-right text, `Right` for right-to-left text
+    Start,
+    /// `Right` for left-to-right text, `Left` for right-to-left text
+    End,
 }
 
 impl Default for StyleTextAlignmentHorz {
@@ -1458,6 +2449,103 @@ impl Default for StyleTextAlignmentHorz {
     }
 }
 
+impl StyleTextAlignmentHorz {
+    /// Resolves the logical `Start` / `End` alignment to `Left` / `Right`,
+    /// depending on the `direction` of the text
+    pub fn to_physical(&self, direction: StyleTextDirection) -> Self {
+        use self::StyleTextAlignmentHorz::*;
+        match (*self, direction) {
+            (Start, StyleTextDirection::Ltr) | (End, StyleTextDirection::Rtl) => Left,
+            (Start, StyleTextDirection::Rtl) | (End, StyleTextDirection::Ltr) => Right,
+            (other, _) => other,
+        }
+    }
+}
+
+/// Represents a `white-space` attribute - default: `Normal`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleWhiteSpace {
+    /// Lines are wrapped at the width of the parent
+    Normal,
+    /// Lines are only broken at explicit line breaks (`\n`)
+    Nowrap,
+}
+
+impl Default for StyleWhiteSpace {
+    fn default() -> Self {
+        StyleWhiteSpace::Normal
+    }
+}
+
+/// Represents a `direction` attribute, the direction that text is written in and that the
+/// logical properties (such as `margin-inline-start`) are resolved against - default: `Ltr`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleTextDirection {
+    /// Left-to-right, i.e. for English
+    Ltr,
+    /// Right-to-left, i.e. for Arabic or Hebrew
+    Rtl,
+}
+
+impl Default for StyleTextDirection {
+    fn default() -> Self {
+        StyleTextDirection::Ltr
+    }
+}
+
+/// Represents a `text-overflow` attribute, how a line that is wider than its
+/// (non-`overflow: visible`) parent is cut off - default: `Clip`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleTextOverflow {
+    /// The line is cut off at the edge of the parent
+    Clip,
+    /// The line is truncated and ends with an ellipsis ("…")
+    Ellipsis,
+}
+
+impl Default for StyleTextOverflow {
+    fn default() -> Self {
+        StyleTextOverflow::Clip
+    }
+}
+
+/// Represents a `text-decoration` attribute, i.e. `underline wavy red` - default: no lines
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleTextDecoration {
+    /// Line below the baseline
+    pub underline: bool,
+    /// Line above the ascender
+    pub overline: bool,
+    /// Line through the middle of the lowercase letters
+    pub line_through: bool,
+    pub style: StyleTextDecorationStyle,
+    /// Color of the lines, uses the text color if set to `None`
+    pub color: Option<ColorU>,
+}
+
+impl StyleTextDecoration {
+    /// Returns whether any line is drawn at all (`text-decoration: none` draws no lines)
+    pub fn has_lines(&self) -> bool {
+        self.underline || self.overline || self.line_through
+    }
+}
+
+/// How the lines of a `text-decoration` are drawn - default: `Solid`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleTextDecorationStyle {
+    Solid,
+    Double,
+    Dotted,
+    Dashed,
+    Wavy,
+}
+
+impl Default for StyleTextDecorationStyle {
+    fn default() -> Self {
+        StyleTextDecorationStyle::Solid
+    }
+}
+
 /// Vertical text alignment enum (top, center, bottom) - default: `Center`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum StyleTextAlignmentVert {
@@ -1504,10 +2592,47 @@ pub struct RectStyle {
     pub word_spacing: Option<StyleWordSpacing>,
     /// `tab-width` property
     pub tab_width: Option<StyleTabWidth>,
+    /// `tab-stops` property
+    pub tab_stops: Option<StyleTabStops>,
+    /// `white-space` property
+    pub white_space: Option<StyleWhiteSpace>,
+    /// `text-overflow` property
+    pub text_overflow: Option<StyleTextOverflow>,
+    /// `text-decoration` property
+    pub text_decoration: Option<StyleTextDecoration>,
+    /// `opacity` property
+    pub opacity: Option<StyleOpacity>,
+    /// `visibility` property
+    pub visibility: Option<StyleVisibility>,
+    /// `mix-blend-mode` property
+    pub mix_blend_mode: Option<StyleMixBlendMode>,
+    /// `object-fit` property
+    pub object_fit: Option<StyleObjectFit>,
+    /// `transform` property
+    pub transform: Option<StyleTransform>,
+    /// `transform-origin` property
+    pub transform_origin: Option<StyleTransformOrigin>,
+    /// `transform-style` property
+    pub transform_style: Option<StyleTransformStyle>,
+    /// `perspective` property
+    pub perspective: Option<StylePerspective>,
+    /// `pointer-events` property
+    pub pointer_events: Option<StylePointerEvents>,
+    /// `user-select` property
+    pub user_select: Option<StyleUserSelect>,
+    /// `outline` property
+    pub outline: Option<StyleOutline>,
+    /// `outline-offset` property
+    pub outline_offset: Option<StyleOutlineOffset>,
+    /// `direction`, which the logical properties of this rectangle are resolved against
+    pub text_direction: Option<StyleTextDirection>,
+    /// `border-inline{-start,-end}`, resolved into `border` once the `text_direction` is known
+    pub border_inline: Option<StyleBorderInline>,
 }
 
 impl_pixel_value!(StyleLetterSpacing);
 impl_pixel_value!(StyleWordSpacing);
+impl_pixel_value!(StyleOutlineOffset);
 
 impl RectStyle {
 
@@ -1594,15 +2719,31 @@ pub struct RectLayout {
 
     pub padding: Option<LayoutPadding>,
     pub margin: Option<LayoutMargin>,
+    /// `padding-inline{-start,-end}`, resolved into `padding` once the direction of the text is known
+    pub padding_inline: Option<LayoutPaddingInline>,
+    /// `margin-inline{-start,-end}`, resolved into `margin` once the direction of the text is known
+    pub margin_inline: Option<LayoutMarginInline>,
     pub overflow: Option<LayoutOverflow>,
 
     pub direction: Option<LayoutDirection>,
     pub wrap: Option<LayoutWrap>,
     pub flex_grow: Option<LayoutFlexGrow>,
     pub flex_shrink: Option<LayoutFlexShrink>,
+    pub flex_basis: Option<LayoutFlexBasis>,
+    pub order: Option<LayoutOrder>,
     pub justify_content: Option<LayoutJustifyContent>,
     pub align_items: Option<LayoutAlignItems>,
     pub align_content: Option<LayoutAlignContent>,
+
+    pub display: Option<LayoutDisplay>,
+    pub grid_gap: Option<LayoutGridGap>,
+    pub row_gap: Option<LayoutRowGap>,
+    pub column_gap: Option<LayoutColumnGap>,
+    pub grid_column: Option<LayoutGridColumn>,
+    pub grid_row: Option<LayoutGridRow>,
+    pub float: Option<LayoutFloat>,
+    pub scroll_snap_type: Option<LayoutScrollSnapType>,
+    pub scroll_snap_align: Option<LayoutScrollSnapAlign>,
 }
 
 impl RectLayout {
@@ -1631,6 +2772,60 @@ impl RectLayout {
         + margin.top.map(|l| l.to_pixels()).unwrap_or(0.0)
     }
 
+    /// Whether the `padding` or the `margin` contain percentages, see `resolve_percentages`
+    pub fn has_percentages(&self) -> bool {
+        let padding = self.padding.unwrap_or_default();
+        let margin = self.margin.unwrap_or_default();
+        [padding.top, padding.bottom, padding.left, padding.right, margin.top, margin.bottom, margin.left, margin.right]
+            .iter()
+            .any(|side| side.map(|value| value.metric == SizeMetric::Percent).unwrap_or(false))
+    }
+
+    /// Converts the percentages of the `padding` and the `margin` into pixels. As in CSS, the
+    /// percentages of all sides (even `top` and `bottom`) refer to the width of the containing block.
+    pub fn resolve_percentages(&mut self, containing_block_width: f32) {
+        fn resolve(side: &mut Option<PixelValue>, containing_block_width: f32) {
+            if let Some(value) = side {
+                *value = value.resolve_percentage(containing_block_width);
+            }
+        }
+        if let Some(padding) = &mut self.padding {
+            for side in &mut [&mut padding.top, &mut padding.bottom, &mut padding.left, &mut padding.right] {
+                resolve(side, containing_block_width);
+            }
+        }
+        if let Some(margin) = &mut self.margin {
+            for side in &mut [&mut margin.top, &mut margin.bottom, &mut margin.left, &mut margin.right] {
+                resolve(side, containing_block_width);
+            }
+        }
+    }
+
+    /// Returns the `row-gap` in pixels (`grid-gap` is only used as a fallback)
+    pub fn get_row_gap(&self) -> f32 {
+        self.row_gap.map(|gap| gap.0)
+            .or(self.grid_gap.map(|gap| gap.row))
+            .map(|gap| gap.to_pixels())
+            .unwrap_or(0.0)
+    }
+
+    /// Returns the `column-gap` in pixels (`grid-gap` is only used as a fallback)
+    pub fn get_column_gap(&self) -> f32 {
+        self.column_gap.map(|gap| gap.0)
+            .or(self.grid_gap.map(|gap| gap.column))
+            .map(|gap| gap.to_pixels())
+            .unwrap_or(0.0)
+    }
+
+    /// Returns the gap between two children of a flex container along its main axis,
+    /// i.e. the `column-gap` for `flex-direction: row`
+    pub fn get_main_axis_gap(&self) -> f32 {
+        match self.direction.unwrap_or_default().get_axis() {
+            LayoutAxis::Horizontal => self.get_column_gap(),
+            LayoutAxis::Vertical => self.get_row_gap(),
+        }
+    }
+
     pub fn is_horizontal_overflow_visible(&self) -> bool {
         self.overflow.unwrap_or_default().is_horizontal_overflow_visible()
     }
@@ -1638,6 +2833,26 @@ impl RectLayout {
     pub fn is_vertical_overflow_visible(&self) -> bool {
         self.overflow.unwrap_or_default().is_vertical_overflow_visible()
     }
+
+    /// Whether the node is placed in the inline flow of its parent (`display: inline` or
+    /// `display: inline-block`). `position: absolute` nodes are never inline.
+    pub fn is_inline_level(&self) -> bool {
+        let is_inline_display = match self.display {
+            Some(LayoutDisplay::Inline) | Some(LayoutDisplay::InlineBlock) => true,
+            _ => false,
+        };
+        is_inline_display && self.position != Some(LayoutPosition::Absolute)
+    }
+
+    /// Whether the node has `float: left` or `float: right`. Like in a browser,
+    /// `position: absolute` nodes never float.
+    pub fn is_floating(&self) -> bool {
+        let is_float = match self.float {
+            Some(LayoutFloat::Left) | Some(LayoutFloat::Right) => true,
+            _ => false,
+        };
+        is_float && self.position != Some(LayoutPosition::Absolute)
+    }
 }
 
 impl_pixel_value!(LayoutWidth);
@@ -1646,6 +2861,8 @@ impl_pixel_value!(LayoutMinHeight);
 impl_pixel_value!(LayoutMinWidth);
 impl_pixel_value!(LayoutMaxWidth);
 impl_pixel_value!(LayoutMaxHeight);
+impl_pixel_value!(LayoutRowGap);
+impl_pixel_value!(LayoutColumnGap);
 impl_pixel_value!(LayoutTop);
 impl_pixel_value!(LayoutBottom);
 impl_pixel_value!(LayoutRight);
@@ -16,6 +16,12 @@ pub trait HotReloadHandler {
     fn reload_style(&mut self) -> Result<Css, String>;
     /// Returns how quickly the hot-reloader should reload the source format.
     fn get_reload_interval(&self) -> Duration;
+    /// Called every `get_reload_interval()` before the style is reloaded: returns whether the
+    /// source has changed since the last `reload_style()`, i.e. if the file was modified.
+    /// Handlers that can't detect changes always reload.
+    fn has_changed(&mut self) -> bool {
+        true
+    }
 }
 
 /// Custom hot-reloader combinator that can be used to merge hot-reloaded styles onto a base style.
@@ -41,16 +47,16 @@ impl HotReloadOverrideHandler {
 impl HotReloadHandler for HotReloadOverrideHandler {
     fn reload_style(&mut self) -> Result<Css, String> {
         let mut css = Css::new();
-        for stylesheet in self.base_style.clone().stylesheets {
-            css.append_stylesheet(stylesheet);
-        }
-        for stylesheet in self.hot_reloader.reload_style()?.stylesheets {
-            css.append_stylesheet(stylesheet);
-        }
+        css.append(self.base_style.clone());
+        css.append(self.hot_reloader.reload_style()?);
         Ok(css)
     }
 
     fn get_reload_interval(&self) -> Duration {
         self.hot_reloader.get_reload_interval()
     }
+
+    fn has_changed(&mut self) -> bool {
+        self.hot_reloader.has_changed()
+    }
 }
@@ -0,0 +1,122 @@
+//! Derive macros for azul, re-exported via `azul::derive` (`--features="derive"`)
+//!
+//! `#[derive(AutoForm)]` implements `azul::derive::AutoForm` for a struct with named
+//! fields, so that the struct can be edited with a `PropertyGrid`. Every field gets one
+//! row, the type of the field has to implement `azul::derive::FormValue`:
+//!
+//! ```rust,ignore
+//! #[derive(AutoForm)]
+//! struct Settings {
+//!     user_name: String,
+//!     #[auto_form(label = "Font size (px)")]
+//!     font_size: f32,
+//!     dark_mode: bool,
+//!     #[auto_form(skip)]
+//!     last_saved: Option<Instant>,
+//! }
+//! ```
+
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate syn;
+#[macro_use]
+extern crate quote;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{
+    Data, DeriveInput, Fields, Field, Lit, Meta, NestedMeta,
+    spanned::Spanned,
+};
+
+#[proc_macro_derive(AutoForm, attributes(auto_form))]
+pub fn derive_auto_form(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match impl_auto_form(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Options of a field, parsed from the `#[auto_form(...)]` attribute
+#[derive(Default)]
+struct FieldOptions {
+    skip: bool,
+    label: Option<String>,
+}
+
+fn impl_auto_form(input: &DeriveInput) -> Result<TokenStream2, syn::Error> {
+
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(named) => &named.named,
+            _ => return Err(syn::Error::new(input.span(), "AutoForm can only be derived for structs with named fields")),
+        },
+        _ => return Err(syn::Error::new(input.span(), "AutoForm can only be derived for structs")),
+    };
+
+    let mut rows = Vec::new();
+
+    for field in fields {
+        let options = parse_field_options(field)?;
+        if options.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field without identifier");
+        let label = options.label.unwrap_or_else(|| label_from_field_name(&ident.to_string()));
+        rows.push(quote! { grid.add_field(#label, &self.#ident); });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::azul::derive::AutoForm for #name #ty_generics #where_clause {
+            fn add_form_fields<T>(&self, grid: &mut ::azul::widgets::property_grid::PropertyGrid<T>) {
+                #(#rows)*
+            }
+        }
+    })
+}
+
+fn parse_field_options(field: &Field) -> Result<FieldOptions, syn::Error> {
+
+    let mut options = FieldOptions::default();
+
+    for attr in &field.attrs {
+        let meta = attr.parse_meta()?;
+        if meta.name() != "auto_form" {
+            continue;
+        }
+        let list = match meta {
+            Meta::List(list) => list,
+            other => return Err(syn::Error::new(other.span(), "expected #[auto_form(skip)] or #[auto_form(label = \"...\")]")),
+        };
+        for nested in list.nested.iter() {
+            match nested {
+                NestedMeta::Meta(Meta::Word(word)) if word == "skip" => {
+                    options.skip = true;
+                },
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.ident == "label" => {
+                    match &nv.lit {
+                        Lit::Str(s) => options.label = Some(s.value()),
+                        other => return Err(syn::Error::new(other.span(), "the label has to be a string")),
+                    }
+                },
+                other => return Err(syn::Error::new(other.span(), "unknown auto_form option, expected `skip` or `label`")),
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// Turns `font_size` into `Font size`
+fn label_from_field_name(name: &str) -> String {
+    let mut label = name.trim_start_matches("r#").replace('_', " ").trim().to_string();
+    if let Some(first) = label.chars().next() {
+        let upper = first.to_uppercase().collect::<String>();
+        label.replace_range(..first.len_utf8(), &upper);
+    }
+    label
+}
@@ -4,12 +4,26 @@ extern crate azul_css;
 use azul_css::Css;
 
 extern crate azul_css_parser;
+#[cfg(target_os = "windows")]
+extern crate winapi;
+#[cfg(target_os = "macos")]
+#[macro_use]
+extern crate objc;
+
+mod theme;
+pub mod platform;
+
+pub use theme::Theme;
 
 /// CSS mimicking the OS-native look - Windows: `styles/native_windows.css`
 #[cfg(target_os="windows")]
 pub const NATIVE_CSS: &str = concat!(
     include_str!("styles/native_windows.css"),
     include_str!("styles/shared/table.css"),
+    include_str!("styles/shared/skeleton.css"),
+    include_str!("styles/shared/router.css"),
+    include_str!("styles/shared/property_grid.css"),
+    include_str!("styles/shared/carousel.css"),
 );
 
 /// CSS mimicking the OS-native look - Linux: `styles/native_linux.css`
@@ -17,6 +31,10 @@ pub const NATIVE_CSS: &str = concat!(
 pub const NATIVE_CSS: &str = concat!(
     include_str!("styles/native_linux.css"),
     include_str!("styles/shared/table.css"),
+    include_str!("styles/shared/skeleton.css"),
+    include_str!("styles/shared/router.css"),
+    include_str!("styles/shared/property_grid.css"),
+    include_str!("styles/shared/carousel.css"),
 );
 
 /// CSS mimicking the OS-native look - Mac: `styles/native_macos.css`
@@ -24,9 +42,22 @@ pub const NATIVE_CSS: &str = concat!(
 pub const NATIVE_CSS: &str = concat!(
     include_str!("styles/native_macos.css"),
     include_str!("styles/shared/table.css"),
+    include_str!("styles/shared/skeleton.css"),
+    include_str!("styles/shared/router.css"),
+    include_str!("styles/shared/property_grid.css"),
+    include_str!("styles/shared/carousel.css"),
 );
 
-/// Returns the native style for the OS
+/// Returns the native style for the OS. The accent color follows the built-in
+/// `--azul-system-accent-color` variable (see `platform::get_system_colors`),
+/// so the widgets are restyled when the user changes it in the settings of the OS.
 pub fn native() -> Css {
     azul_css_parser::new_from_str(NATIVE_CSS).unwrap()
 }
+
+/// Returns the native style for the OS, with the design tokens overridden by the `theme`
+pub fn themed(theme: &Theme) -> Css {
+    let mut css = native();
+    css.append(theme.to_css());
+    css
+}
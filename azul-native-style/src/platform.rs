@@ -0,0 +1,480 @@
+//! Reads the settings of the operating system that the native style depends on (i.e. the
+//! accent color): from the registry on Windows, via the `defaults` and `gsettings` tools on
+//! macOS and Linux (where the settings are stored in the preferences / GSettings databases
+//! of the user). Waiting for changes of the settings (see `watch_system_colors`) uses the OS APIs.
+
+#[cfg(not(target_os = "windows"))]
+use std::process::Command;
+use azul_css::{ColorU, SystemColors};
+
+/// Returns the colors that the user selected in the settings of the operating system.
+/// Runs several OS tools on macOS and Linux, so this takes a few milliseconds.
+pub fn get_system_colors() -> SystemColors {
+
+    let accent = get_accent_color();
+
+    #[cfg(target_os = "windows")] {
+        let system_color = |name: &str| {
+            read_registry_string("Control Panel\\Colors", name).and_then(|value| parse_rgb_string(&value))
+        };
+        SystemColors {
+            accent,
+            highlight: system_color("Hilight"),
+            window_background: system_color("Window"),
+            text: system_color("WindowText"),
+        }
+    }
+
+    #[cfg(target_os = "macos")] {
+        // The key only exists in dark mode
+        let is_dark = run_command("defaults", &["read", "-g", "AppleInterfaceStyle"])
+            .map(|output| parse_macos_dark_mode(&output)).unwrap_or(false);
+        // Doesn't exist if the highlight follows the accent color
+        let highlight = run_command("defaults", &["read", "-g", "AppleHighlightColor"])
+            .and_then(|output| parse_macos_rgb_color(&output));
+        macos_system_colors(accent, highlight, is_dark)
+    }
+
+    #[cfg(target_os = "linux")] {
+        let is_dark = run_command("gsettings", &["get", "org.gnome.desktop.interface", "color-scheme"])
+            .map(|output| parse_gnome_dark_mode(&output)).unwrap_or(false);
+        gnome_system_colors(accent, is_dark)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))] {
+        SystemColors { accent, .. SystemColors::default() }
+    }
+}
+
+/// Returns the accent color that the user selected in the settings of the operating
+/// system, or `None` if it can't be determined (so that the native style keeps its default)
+pub fn get_accent_color() -> Option<ColorU> {
+
+    #[cfg(target_os = "windows")] {
+        read_registry_dword("Software\\Microsoft\\Windows\\DWM", "AccentColor").map(abgr_to_color)
+    }
+
+    #[cfg(target_os = "macos")] {
+        // The key doesn't exist if the default (blue) accent color is selected
+        let output = run_command("defaults", &["read", "-g", "AppleAccentColor"]).unwrap_or_default();
+        macos_accent_color(output.trim())
+    }
+
+    #[cfg(target_os = "linux")] {
+        // GNOME 47+
+        let output = run_command("gsettings", &["get", "org.gnome.desktop.interface", "accent-color"])?;
+        gnome_accent_color(parse_gsettings_string(&output))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))] {
+        None
+    }
+}
+
+/// Blocks the current thread and calls `on_change` whenever the user changes a setting that
+/// `get_system_colors` depends on, until `on_change` returns `false`. Waits for the change
+/// notifications of the OS (registry change notifications on Windows, distributed notifications
+/// on macOS, `gsettings monitor` on Linux) and returns immediately if they aren't available.
+pub fn watch_system_colors<F: FnMut() -> bool>(on_change: F) {
+
+    #[cfg(target_os = "windows")] {
+        watch_registry_keys(&["Software\\Microsoft\\Windows\\DWM", "Control Panel\\Colors"], on_change);
+    }
+
+    #[cfg(target_os = "macos")] {
+        watch_distributed_notifications(&["AppleInterfaceThemeChangedNotification", "AppleColorPreferencesChangedNotification"], on_change);
+    }
+
+    #[cfg(target_os = "linux")] {
+        watch_gsettings("org.gnome.desktop.interface", &["accent-color", "color-scheme"], on_change);
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))] {
+        let _ = on_change;
+    }
+}
+
+/// Runs the command and returns its output, if it succeeded
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn run_command(command: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(command).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Reads a `REG_DWORD` value of the `key` below `HKEY_CURRENT_USER`
+#[cfg(target_os = "windows")]
+fn read_registry_dword(key: &str, value: &str) -> Option<u32> {
+
+    use std::{mem, ptr, ffi::OsStr, os::windows::ffi::OsStrExt};
+    use winapi::{
+        ctypes::c_void,
+        shared::{minwindef::DWORD, winerror::ERROR_SUCCESS},
+        um::winreg::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD},
+    };
+
+    let key = OsStr::new(key).encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    let value = OsStr::new(value).encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    let mut data: DWORD = 0;
+    let mut size = mem::size_of::<DWORD>() as DWORD;
+
+    let result = unsafe {
+        RegGetValueW(HKEY_CURRENT_USER, key.as_ptr(), value.as_ptr(), RRF_RT_REG_DWORD, ptr::null_mut(), &mut data as *mut DWORD as *mut c_void, &mut size)
+    };
+
+    if result as u32 == ERROR_SUCCESS { Some(data) } else { None }
+}
+
+/// Reads a (short) `REG_SZ` value of the `key` below `HKEY_CURRENT_USER`
+#[cfg(target_os = "windows")]
+fn read_registry_string(key: &str, value: &str) -> Option<String> {
+
+    use std::{ptr, ffi::OsStr, os::windows::ffi::OsStrExt};
+    use winapi::{
+        ctypes::c_void,
+        shared::{minwindef::DWORD, winerror::ERROR_SUCCESS},
+        um::winreg::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ},
+    };
+
+    let key = OsStr::new(key).encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    let value = OsStr::new(value).encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    let mut data = [0_u16; 256];
+    let mut size = (data.len() * 2) as DWORD;
+
+    let result = unsafe {
+        RegGetValueW(HKEY_CURRENT_USER, key.as_ptr(), value.as_ptr(), RRF_RT_REG_SZ, ptr::null_mut(), data.as_mut_ptr() as *mut c_void, &mut size)
+    };
+
+    if result as u32 != ERROR_SUCCESS {
+        return None;
+    }
+
+    // The size is in bytes and includes the terminating null
+    let length = (size as usize / 2).saturating_sub(1).min(data.len());
+    Some(String::from_utf16_lossy(&data[..length]))
+}
+
+/// Waits until one of the values of the `keys` (below `HKEY_CURRENT_USER`) changes
+#[cfg(target_os = "windows")]
+fn watch_registry_keys<F: FnMut() -> bool>(keys: &[&str], mut on_change: F) {
+
+    use std::{ptr, ffi::OsStr, os::windows::ffi::OsStrExt};
+    use winapi::{
+        shared::{minwindef::{HKEY, TRUE, FALSE}, winerror::ERROR_SUCCESS},
+        um::{
+            winreg::{RegOpenKeyExW, RegNotifyChangeKeyValue, RegCloseKey, HKEY_CURRENT_USER},
+            winnt::{KEY_NOTIFY, REG_NOTIFY_CHANGE_LAST_SET, HANDLE},
+            synchapi::{CreateEventW, WaitForMultipleObjects},
+            handleapi::CloseHandle,
+            winbase::{INFINITE, WAIT_OBJECT_0},
+        },
+    };
+
+    // The notification only fires once, so it has to be requested again after every change
+    let notify = |key: HKEY, event: HANDLE| unsafe {
+        RegNotifyChangeKeyValue(key, FALSE, REG_NOTIFY_CHANGE_LAST_SET, event, TRUE) as u32 == ERROR_SUCCESS
+    };
+
+    let mut opened_keys = Vec::new();
+    for key_path in keys {
+        let key_path = OsStr::new(key_path).encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+        let mut key: HKEY = ptr::null_mut();
+        if unsafe { RegOpenKeyExW(HKEY_CURRENT_USER, key_path.as_ptr(), 0, KEY_NOTIFY, &mut key) } as u32 != ERROR_SUCCESS {
+            continue;
+        }
+        let event = unsafe { CreateEventW(ptr::null_mut(), FALSE, FALSE, ptr::null()) };
+        if event.is_null() || !notify(key, event) {
+            unsafe { RegCloseKey(key); }
+            continue;
+        }
+        opened_keys.push((key, event));
+    }
+
+    let events = opened_keys.iter().map(|(_, event)| *event).collect::<Vec<HANDLE>>();
+
+    while !events.is_empty() {
+        let result = unsafe { WaitForMultipleObjects(events.len() as u32, events.as_ptr(), FALSE, INFINITE) };
+        let (key, event) = match opened_keys.get(result.wrapping_sub(WAIT_OBJECT_0) as usize) {
+            Some(changed_key) => *changed_key,
+            None => break,
+        };
+        if !notify(key, event) || !on_change() {
+            break;
+        }
+    }
+
+    for (key, event) in opened_keys {
+        unsafe {
+            RegCloseKey(key);
+            CloseHandle(event);
+        }
+    }
+}
+
+/// Waits for the distributed notifications with the given names, on the run loop of the current thread
+#[cfg(target_os = "macos")]
+fn watch_distributed_notifications<F: FnMut() -> bool>(notification_names: &[&str], mut on_change: F) {
+
+    use std::{ptr, ffi::CString, os::raw::c_void};
+    use objc::{declare::ClassDecl, runtime::{Class, Object, Sel}};
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRunLoopGetCurrent() -> *mut c_void;
+        fn CFRunLoopRun();
+        fn CFRunLoopStop(run_loop: *mut c_void);
+    }
+
+    type OnChange<'a> = &'a mut dyn FnMut() -> bool;
+
+    extern "C" fn settings_changed(this: &Object, _: Sel, _notification: *mut Object) {
+        unsafe {
+            let on_change = &mut *(*this.get_ivar::<*mut c_void>("onChange") as *mut OnChange);
+            if !on_change() {
+                CFRunLoopStop(CFRunLoopGetCurrent());
+            }
+        }
+    }
+
+    let observer_class = Class::get("AzulSystemColorsObserver").unwrap_or_else(|| {
+        let mut decl = ClassDecl::new("AzulSystemColorsObserver", class!(NSObject)).unwrap();
+        decl.add_ivar::<*mut c_void>("onChange");
+        unsafe { decl.add_method(sel!(settingsChanged:), settings_changed as extern "C" fn(&Object, Sel, *mut Object)); }
+        decl.register()
+    });
+
+    let mut on_change: OnChange = &mut on_change;
+
+    unsafe {
+        let pool: *mut Object = msg_send![class!(NSAutoreleasePool), new];
+        let observer: *mut Object = msg_send![observer_class, new];
+        (*observer).set_ivar("onChange", &mut on_change as *mut OnChange as *mut c_void);
+
+        let center: *mut Object = msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+        for notification_name in notification_names {
+            let notification_name = CString::new(*notification_name).unwrap();
+            let notification_name: *mut Object = msg_send![class!(NSString), stringWithUTF8String: notification_name.as_ptr()];
+            let () = msg_send![center, addObserver: observer selector: sel!(settingsChanged:) name: notification_name object: ptr::null_mut::<Object>()];
+        }
+
+        // Returns once `settings_changed` stops the run loop
+        CFRunLoopRun();
+
+        let () = msg_send![center, removeObserver: observer];
+        let () = msg_send![observer, release];
+        let () = msg_send![pool, drain];
+    }
+}
+
+/// Waits until one of the `keys` of the GSettings `schema` changes, via `gsettings monitor`
+#[cfg(target_os = "linux")]
+fn watch_gsettings<F: FnMut() -> bool>(schema: &str, keys: &[&str], mut on_change: F) {
+
+    use std::{io::{BufRead, BufReader}, process::Stdio};
+
+    let mut monitor = match Command::new("gsettings").arg("monitor").arg(schema).stdout(Stdio::piped()).spawn() {
+        Ok(monitor) => monitor,
+        Err(_) => return,
+    };
+
+    if let Some(stdout) = monitor.stdout.take() {
+        // Prints one line per change, i.e. "color-scheme: 'prefer-dark'"
+        for line in BufReader::new(stdout).lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            let is_color_change = parse_gsettings_monitor_line(&line).map(|key| keys.contains(&key)).unwrap_or(false);
+            if is_color_change && !on_change() {
+                break;
+            }
+        }
+    }
+
+    let _ = monitor.kill();
+    let _ = monitor.wait();
+}
+
+/// Converts a color of the DWM, which stores colors as `0xAABBGGRR` DWORDs (the alpha is ignored)
+#[cfg(any(target_os = "windows", test))]
+fn abgr_to_color(abgr: u32) -> ColorU {
+    ColorU {
+        r: (abgr & 0xff) as u8,
+        g: ((abgr >> 8) & 0xff) as u8,
+        b: ((abgr >> 16) & 0xff) as u8,
+        a: 255,
+    }
+}
+
+/// Parses a color of `HKCU\Control Panel\Colors`, which are stored as strings, i.e. `"255 255 255"`
+#[cfg(any(target_os = "windows", test))]
+fn parse_rgb_string(value: &str) -> Option<ColorU> {
+    let mut components = value.split_whitespace().map(|c| c.parse::<u8>().ok());
+    let (r, g, b) = (components.next()??, components.next()??, components.next()??);
+    if components.next().is_some() {
+        return None;
+    }
+    Some(ColorU { r, g, b, a: 255 })
+}
+
+/// Parses the value of the `AppleInterfaceStyle` setting, which is `Dark` in dark mode
+#[cfg(any(target_os = "macos", test))]
+fn parse_macos_dark_mode(output: &str) -> bool {
+    output.trim() == "Dark"
+}
+
+/// Parses the value of the `AppleHighlightColor` setting, i.e. `0.698039 0.843137 1.000000 Blue`
+#[cfg(any(target_os = "macos", test))]
+fn parse_macos_rgb_color(output: &str) -> Option<ColorU> {
+    let mut components = output.split_whitespace().take(3).map(|c| c.parse::<f32>().ok());
+    let mut component = || components.next().and_then(|c| c).map(|c| (c.max(0.0).min(1.0) * 255.0).round() as u8);
+    let (r, g, b) = (component()?, component()?, component()?);
+    Some(ColorU { r, g, b, a: 255 })
+}
+
+/// Maps the value of the `AppleAccentColor` setting to the `controlAccentColor` of macOS
+#[cfg(any(target_os = "macos", test))]
+fn macos_accent_color(setting: &str) -> Option<ColorU> {
+    let (r, g, b) = match setting {
+        "-1" => (140, 140, 140), // Graphite
+        "0" => (224, 56, 62),    // Red
+        "1" => (247, 130, 27),   // Orange
+        "2" => (255, 199, 56),   // Yellow
+        "3" => (98, 186, 70),    // Green
+        "5" => (149, 61, 150),   // Purple
+        "6" => (247, 79, 158),   // Pink
+        _ => (0, 122, 255),      // Blue
+    };
+    Some(ColorU { r, g, b, a: 255 })
+}
+
+/// Colors of the default macOS appearance. The highlight follows the accent
+/// color (at 30% opacity) unless the user selected a highlight color.
+#[cfg(any(target_os = "macos", test))]
+fn macos_system_colors(accent: Option<ColorU>, highlight: Option<ColorU>, is_dark: bool) -> SystemColors {
+    let (window_background, text) = if is_dark {
+        (ColorU { r: 50, g: 50, b: 50, a: 255 }, ColorU { r: 255, g: 255, b: 255, a: 217 })
+    } else {
+        (ColorU { r: 236, g: 236, b: 236, a: 255 }, ColorU { r: 0, g: 0, b: 0, a: 217 })
+    };
+    SystemColors {
+        accent,
+        highlight: highlight.or_else(|| accent.map(|accent| ColorU { a: 77, .. accent })),
+        window_background: Some(window_background),
+        text: Some(text),
+    }
+}
+
+/// Parses the output of `gsettings get` for a string setting, i.e. `'prefer-dark'`
+#[cfg(any(target_os = "linux", test))]
+fn parse_gsettings_string(output: &str) -> &str {
+    output.trim().trim_matches('\'')
+}
+
+/// Parses the value of the GNOME `color-scheme` setting: `'prefer-dark'`, `'prefer-light'` or `'default'`
+#[cfg(any(target_os = "linux", test))]
+fn parse_gnome_dark_mode(output: &str) -> bool {
+    parse_gsettings_string(output) == "prefer-dark"
+}
+
+/// Parses a line of `gsettings monitor`, i.e. `color-scheme: 'prefer-dark'`, into the changed key
+#[cfg(any(target_os = "linux", test))]
+fn parse_gsettings_monitor_line(line: &str) -> Option<&str> {
+    let separator = line.find(':')?;
+    Some(line[..separator].trim())
+}
+
+/// Maps the value of the GNOME `accent-color` setting to the libadwaita accent colors
+#[cfg(any(target_os = "linux", test))]
+fn gnome_accent_color(setting: &str) -> Option<ColorU> {
+    let (r, g, b) = match setting {
+        "blue" => (53, 132, 228),
+        "teal" => (33, 144, 164),
+        "green" => (58, 148, 74),
+        "yellow" => (200, 136, 0),
+        "orange" => (237, 91, 0),
+        "red" => (230, 45, 66),
+        "pink" => (213, 97, 153),
+        "purple" => (145, 65, 172),
+        "slate" => (111, 131, 150),
+        _ => return None,
+    };
+    Some(ColorU { r, g, b, a: 255 })
+}
+
+/// Colors of the default libadwaita style, which draws selections in the accent color, at 30% opacity
+#[cfg(any(target_os = "linux", test))]
+fn gnome_system_colors(accent: Option<ColorU>, is_dark: bool) -> SystemColors {
+    let (window_background, text) = if is_dark {
+        (ColorU { r: 34, g: 34, b: 38, a: 255 }, ColorU { r: 255, g: 255, b: 255, a: 255 })
+    } else {
+        (ColorU { r: 250, g: 250, b: 251, a: 255 }, ColorU { r: 0, g: 0, b: 6, a: 204 })
+    };
+    SystemColors {
+        accent,
+        highlight: accent.map(|accent| ColorU { a: 77, .. accent }),
+        window_background: Some(window_background),
+        text: Some(text),
+    }
+}
+
+#[test]
+fn test_parse_registry_colors() {
+
+    // Default value of `HKCU\Software\Microsoft\Windows\DWM\AccentColor`
+    assert_eq!(abgr_to_color(0xffd7_7800), ColorU { r: 0, g: 120, b: 215, a: 255 });
+
+    // Default value of `HKCU\Control Panel\Colors\Hilight`
+    assert_eq!(parse_rgb_string("0 120 215"), Some(ColorU { r: 0, g: 120, b: 215, a: 255 }));
+    assert_eq!(parse_rgb_string(""), None);
+    assert_eq!(parse_rgb_string("255 255"), None);
+    assert_eq!(parse_rgb_string("255 255 255 255"), None);
+    assert_eq!(parse_rgb_string("256 0 0"), None);
+}
+
+#[test]
+fn test_parse_macos_defaults() {
+
+    // Captured from `defaults read -g AppleInterfaceStyle`
+    assert!(parse_macos_dark_mode("Dark\n"));
+    assert!(!parse_macos_dark_mode(""));
+
+    // Captured from `defaults read -g AppleHighlightColor`
+    let highlight = parse_macos_rgb_color("0.698039 0.843137 1.000000 Blue\n");
+    assert_eq!(highlight, Some(ColorU { r: 178, g: 215, b: 255, a: 255 }));
+    assert_eq!(parse_macos_rgb_color("Blue\n"), None);
+
+    // Captured from `defaults read -g AppleAccentColor`, the key doesn't exist for blue
+    let green = ColorU { r: 98, g: 186, b: 70, a: 255 };
+    assert_eq!(macos_accent_color("3"), Some(green));
+    assert_eq!(macos_accent_color(""), Some(ColorU { r: 0, g: 122, b: 255, a: 255 }));
+
+    let colors = macos_system_colors(Some(green), None, true);
+    assert_eq!(colors.highlight, Some(ColorU { a: 77, .. green }));
+    assert_eq!(colors.window_background, Some(ColorU { r: 50, g: 50, b: 50, a: 255 }));
+    assert_eq!(macos_system_colors(Some(green), highlight, false).highlight, highlight);
+}
+
+#[test]
+fn test_parse_gsettings() {
+
+    // Captured from `gsettings get org.gnome.desktop.interface color-scheme`
+    assert!(parse_gnome_dark_mode("'prefer-dark'\n"));
+    assert!(!parse_gnome_dark_mode("'default'\n"));
+
+    // Captured from `gsettings get org.gnome.desktop.interface accent-color`
+    let teal = ColorU { r: 33, g: 144, b: 164, a: 255 };
+    assert_eq!(gnome_accent_color(parse_gsettings_string("'teal'\n")), Some(teal));
+    assert_eq!(gnome_accent_color(parse_gsettings_string("'magenta'\n")), None);
+
+    // Captured from `gsettings monitor org.gnome.desktop.interface`
+    assert_eq!(parse_gsettings_monitor_line("accent-color: 'teal'"), Some("accent-color"));
+    assert_eq!(parse_gsettings_monitor_line("color-scheme: 'prefer-dark'"), Some("color-scheme"));
+    assert_eq!(parse_gsettings_monitor_line(""), None);
+
+    let colors = gnome_system_colors(Some(teal), false);
+    assert_eq!(colors.highlight, Some(ColorU { a: 77, .. teal }));
+    assert_eq!(colors.text, Some(ColorU { r: 0, g: 0, b: 6, a: 204 }));
+}
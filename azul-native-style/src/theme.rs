@@ -0,0 +1,115 @@
+//! Design tokens (colors, spacing, radius, typography) that the built-in widgets are styled with
+
+use platform;
+use azul_css::{
+    Css, Stylesheet, CssRuleBlock, CssPath, CssPathSelector,
+    CssPathPseudoSelector, CssVariable, ColorU,
+};
+
+/// Overrides for the design tokens of the native style. The widgets of the native style
+/// don't use hardcoded values, but reference the tokens via CSS variables (i.e.
+/// `var(--azul-accent-color)`), so changing a token restyles all widgets at once.
+///
+/// Every token that is `None` keeps the value of the native style. The tokens can also
+/// be overridden in CSS, either globally (`:root { --azul-accent-color: #e91e63; }`)
+/// or for a subtree only (`.sidebar { --azul-spacing-medium: 2px; }`).
+///
+/// ```rust,ignore
+/// let theme = Theme {
+///     accent_color: Some(ColorU { r: 233, g: 30, b: 99, a: 255 }),
+///     border_radius: Some(0.0),
+///     .. Default::default()
+/// };
+///
+/// let css = azul_native_style::themed(&theme);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Theme {
+    /// `--azul-accent-color`: Focused, hovered and checked widgets
+    pub accent_color: Option<ColorU>,
+    /// `--azul-surface-color`: Background of input fields and checkboxes
+    pub surface_color: Option<ColorU>,
+    /// `--azul-text-color`: Default text color
+    pub text_color: Option<ColorU>,
+    /// `--azul-disabled-text-color`: Text color of disabled widgets
+    pub disabled_text_color: Option<ColorU>,
+    /// `--azul-border-color`: Borders of buttons, input fields and checkboxes
+    pub border_color: Option<ColorU>,
+    /// `--azul-spacing-small`: Space between the rows of a property grid (in pixels)
+    pub spacing_small: Option<f32>,
+    /// `--azul-spacing-medium`: Space between skeleton placeholders (in pixels)
+    pub spacing_medium: Option<f32>,
+    /// `--azul-spacing-large`: Space between groups of widgets (in pixels)
+    pub spacing_large: Option<f32>,
+    /// `--azul-border-radius`: Corner radius of buttons (in pixels)
+    pub border_radius: Option<f32>,
+    /// `--azul-font-family`: Default font family
+    pub font_family: Option<String>,
+    /// `--azul-font-size`: Default font size (in pixels)
+    pub font_size: Option<f32>,
+    /// `--azul-control-height`: Height of single-line input fields (in pixels)
+    pub control_height: Option<f32>,
+    /// `--azul-focus-ring-color`: Color of the outline around the focused widget
+    pub focus_ring_color: Option<ColorU>,
+    /// `--azul-focus-ring-width`: Width of the outline around the focused widget (in pixels)
+    pub focus_ring_width: Option<f32>,
+}
+
+impl Theme {
+
+    /// Returns the tokens that can be read from the settings of the operating system
+    /// at runtime (currently only the accent color). Only necessary if the native style
+    /// is used outside of an `App`, which declares the system colors as built-in variables.
+    pub fn from_os() -> Self {
+        Self {
+            accent_color: platform::get_accent_color(),
+            .. Default::default()
+        }
+    }
+
+    /// Returns the tokens that are set as CSS variables (without the leading `--`)
+    pub fn get_variables(&self) -> Vec<CssVariable> {
+
+        fn pixels(px: &f32) -> String {
+            format!("{}px", px)
+        }
+
+        let tokens = [
+            ("azul-accent-color", self.accent_color.as_ref().map(ColorU::to_hex_string)),
+            ("azul-surface-color", self.surface_color.as_ref().map(ColorU::to_hex_string)),
+            ("azul-text-color", self.text_color.as_ref().map(ColorU::to_hex_string)),
+            ("azul-disabled-text-color", self.disabled_text_color.as_ref().map(ColorU::to_hex_string)),
+            ("azul-border-color", self.border_color.as_ref().map(ColorU::to_hex_string)),
+            ("azul-spacing-small", self.spacing_small.as_ref().map(pixels)),
+            ("azul-spacing-medium", self.spacing_medium.as_ref().map(pixels)),
+            ("azul-spacing-large", self.spacing_large.as_ref().map(pixels)),
+            ("azul-border-radius", self.border_radius.as_ref().map(pixels)),
+            ("azul-font-family", self.font_family.clone()),
+            ("azul-font-size", self.font_size.as_ref().map(pixels)),
+            ("azul-control-height", self.control_height.as_ref().map(pixels)),
+            ("azul-focus-ring-color", self.focus_ring_color.as_ref().map(ColorU::to_hex_string)),
+            ("azul-focus-ring-width", self.focus_ring_width.as_ref().map(pixels)),
+        ];
+
+        tokens.iter().filter_map(|(name, value)| Some(CssVariable {
+            name: name.to_string(),
+            value: value.clone()?,
+        })).collect()
+    }
+
+    /// Returns a stylesheet that declares the tokens on the `:root` node. Append
+    /// it after the native style, so that the tokens override the native ones.
+    pub fn to_css(&self) -> Css {
+        let root_rule = CssRuleBlock {
+            path: CssPath { selectors: vec![CssPathSelector::PseudoSelector(CssPathPseudoSelector::Root)] },
+            declarations: Vec::new(),
+            variables: self.get_variables(),
+            important: false,
+        };
+
+        Css {
+            stylesheets: vec![Stylesheet::from(vec![root_rule])],
+            font_faces: Vec::new(),
+        }
+    }
+}
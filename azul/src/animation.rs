@@ -0,0 +1,892 @@
+//! Timelines that sequence and group multiple property animations
+//!
+//! A `Timeline` is built from `TimelineItem`s - either a single `Animation` or a
+//! group of items that run one after another (`TimelineItem::serial`) or at the same
+//! time (`TimelineItem::parallel`). Every item can be delayed, repeated and played
+//! back and forth ("ping-pong"). Once the timeline is added to the `AppState`, it is
+//! advanced every frame together with the timers:
+//!
+//! ```rust,ignore
+//! let fade_in = Animation::new(AnimatedProperty::Opacity("card".into()), 0.0, 1.0, Duration::from_millis(300));
+//! let slide_in = Animation::new(AnimatedProperty::TranslateY("card".into()), 20.0, 0.0, Duration::from_millis(300))
+//!     .with_easing(Easing::EaseOut);
+//! let pulse = Animation::new(AnimatedProperty::Scale("badge".into()), 1.0, 1.2, Duration::from_millis(150));
+//!
+//! let timeline = Timeline::new(TimelineItem::serial(vec![
+//!     TimelineItem::parallel(vec![fade_in.into(), slide_in.into()]),
+//!     TimelineItem::from(pulse).with_repeat(Repeat::Times(2)).with_ping_pong(),
+//! ])).with_completion_callback(on_card_shown);
+//!
+//! app_state.add_timeline(TimelineId::new(), timeline);
+//! ```
+//!
+//! Additionally, nodes can be animated automatically whenever their layout changes
+//! (see `AppConfig::layout_transition`) or when they are added to or removed from the DOM
+//! (see `Dom::with_enter_animation` and `Dom::with_exit_animation`).
+
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use {
+    FastHashMap,
+    callbacks::{UpdateScreen, Redraw, DontRedraw},
+    app_resources::{AppResources, PaintPropertyOverride, PaintTransform},
+    diff::KeyedRectChange,
+};
+
+static TIMELINE_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// ID for uniquely identifying a running timeline
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimelineId(usize);
+
+impl TimelineId {
+    /// Generates a new, unique `TimelineId`.
+    pub fn new() -> Self {
+        TimelineId(TIMELINE_ID_COUNTER.fetch_add(1, Ordering::SeqCst))
+    }
+}
+
+/// Callback that is invoked once a timeline has finished playing
+pub type TimelineCompletionCallback<T> = fn(&mut T, &mut AppResources) -> UpdateScreen;
+
+/// Timing function of an `Animation`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::Linear
+    }
+}
+
+impl Easing {
+    /// Maps the linear progress (0.0 - 1.0) of an animation to the eased progress
+    pub fn apply(&self, t: f32) -> f32 {
+        use self::Easing::*;
+        match self {
+            Linear => t,
+            EaseIn => t * t * t,
+            EaseOut => 1.0 - (1.0 - t).powi(3),
+            EaseInOut => if t < 0.5 {
+                4.0 * t * t * t
+            } else {
+                1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+            },
+        }
+    }
+}
+
+/// The property that an `Animation` changes
+pub enum AnimatedProperty<T> {
+    /// Opacity of all nodes with the given ID (see `Dom::with_id`)
+    Opacity(String),
+    /// Horizontal translation (in pixels) of all nodes with the given ID
+    TranslateX(String),
+    /// Vertical translation (in pixels) of all nodes with the given ID
+    TranslateY(String),
+    /// Uniform scale of all nodes with the given ID
+    Scale(String),
+    /// Rotation (in degrees) of all nodes with the given ID
+    Rotate(String),
+    /// Any value of the application data - the function gets called with the current
+    /// value of the animation on every frame. Since this changes the application data,
+    /// the UI is re-layouted on every frame that the animation is running.
+    Custom(fn(&mut T, f32)),
+}
+
+impl<T> AnimatedProperty<T> {
+
+    /// Applies the current value of the animation, returns whether the application data changed
+    fn apply(&self, value: f32, data: &mut T, app_resources: &mut AppResources) -> bool {
+        use self::AnimatedProperty::*;
+
+        let (dom_id, modify): (&str, fn(&mut PaintPropertyOverride, f32)) = match self {
+            Opacity(id) => (id, |o, v| o.opacity = Some(v)),
+            TranslateX(id) => (id, |o, v| o.transform.get_or_insert_with(PaintTransform::default).translate.0 = v),
+            TranslateY(id) => (id, |o, v| o.transform.get_or_insert_with(PaintTransform::default).translate.1 = v),
+            Scale(id) => (id, |o, v| o.transform.get_or_insert_with(PaintTransform::default).scale = (v, v)),
+            Rotate(id) => (id, |o, v| o.transform.get_or_insert_with(PaintTransform::default).rotate = v),
+            Custom(callback) => {
+                callback(data, value);
+                return true;
+            },
+        };
+
+        let mut paint_override = app_resources.get_paint_property_override(dom_id).cloned().unwrap_or_default();
+        modify(&mut paint_override, value);
+        app_resources.set_paint_property_override(dom_id, paint_override);
+        false
+    }
+
+    /// Removes the animated value from the paint property override, so
+    /// that the nodes are drawn with their styled value again
+    fn reset(&self, app_resources: &mut AppResources) {
+        use self::AnimatedProperty::*;
+        match self {
+            Opacity(id) => modify_paint_override(app_resources, id, |o| o.opacity = None),
+            TranslateX(id) | TranslateY(id) | Scale(id) | Rotate(id) => {
+                modify_paint_override(app_resources, id, |o| o.transform = None);
+            },
+            Custom(_) => { },
+        }
+    }
+}
+
+impl<T> fmt::Debug for AnimatedProperty<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::AnimatedProperty::*;
+        match self {
+            Opacity(id) => write!(f, "Opacity({:?})", id),
+            TranslateX(id) => write!(f, "TranslateX({:?})", id),
+            TranslateY(id) => write!(f, "TranslateY({:?})", id),
+            Scale(id) => write!(f, "Scale({:?})", id),
+            Rotate(id) => write!(f, "Rotate({:?})", id),
+            Custom(callback) => write!(f, "Custom({:x})", *callback as usize),
+        }
+    }
+}
+
+/// Animates a single property from one value to another
+#[derive(Debug)]
+pub struct Animation<T> {
+    pub property: AnimatedProperty<T>,
+    pub from: f32,
+    pub to: f32,
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl<T> Animation<T> {
+
+    /// Creates a new, linear animation
+    pub fn new(property: AnimatedProperty<T>, from: f32, to: f32, duration: Duration) -> Self {
+        Self { property, from, to, duration, easing: Easing::default() }
+    }
+
+    #[inline]
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Returns the value of the animation at the given time (in seconds)
+    fn value_at(&self, time: f32) -> f32 {
+        let duration = duration_to_secs(self.duration);
+        let progress = if duration <= 0.0 { 1.0 } else { (time / duration).max(0.0).min(1.0) };
+        self.from + (self.to - self.from) * self.easing.apply(progress)
+    }
+}
+
+/// How often a `TimelineItem` is played
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Repeat {
+    Times(u32),
+    Forever,
+}
+
+impl Default for Repeat {
+    fn default() -> Self {
+        Repeat::Times(1)
+    }
+}
+
+#[derive(Debug)]
+enum TimelineItemKind<T> {
+    Animation(Animation<T>),
+    Serial(Vec<TimelineItem<T>>),
+    Parallel(Vec<TimelineItem<T>>),
+}
+
+/// A single animation or a group of animations, together with their delay and repetitions
+#[derive(Debug)]
+pub struct TimelineItem<T> {
+    kind: TimelineItemKind<T>,
+    delay: Duration,
+    repeat: Repeat,
+    ping_pong: bool,
+}
+
+impl<T> From<Animation<T>> for TimelineItem<T> {
+    fn from(animation: Animation<T>) -> Self {
+        Self::new(TimelineItemKind::Animation(animation))
+    }
+}
+
+impl<T> TimelineItem<T> {
+
+    fn new(kind: TimelineItemKind<T>) -> Self {
+        Self { kind, delay: Duration::from_millis(0), repeat: Repeat::default(), ping_pong: false }
+    }
+
+    /// Plays the items one after another
+    pub fn serial(items: Vec<TimelineItem<T>>) -> Self {
+        Self::new(TimelineItemKind::Serial(items))
+    }
+
+    /// Plays the items at the same time, the group is finished once the longest item is finished
+    pub fn parallel(items: Vec<TimelineItem<T>>) -> Self {
+        Self::new(TimelineItemKind::Parallel(items))
+    }
+
+    /// Waits for the given duration before the item starts playing (only once, not on every repetition)
+    #[inline]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    #[inline]
+    pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Plays every second repetition backwards
+    #[inline]
+    pub fn with_ping_pong(mut self) -> Self {
+        self.ping_pong = true;
+        self
+    }
+
+    /// Resets all properties animated by this item, see `Timeline::reset_properties`
+    fn reset_properties(&self, app_resources: &mut AppResources) {
+        use self::TimelineItemKind::*;
+        match &self.kind {
+            Animation(animation) => animation.property.reset(app_resources),
+            Serial(items) | Parallel(items) => items.iter().for_each(|item| item.reset_properties(app_resources)),
+        }
+    }
+
+    /// Length of a single repetition in seconds, `None` if the item repeats forever
+    fn iteration_length(&self) -> Option<f32> {
+        use self::TimelineItemKind::*;
+        match &self.kind {
+            Animation(animation) => Some(duration_to_secs(animation.duration)),
+            Serial(items) => items.iter().fold(Some(0.0), |sum, item| Some(sum? + item.total_length()?)),
+            Parallel(items) => items.iter().map(|item| item.total_length())
+                .collect::<Option<Vec<f32>>>()
+                .map(|lengths| lengths.into_iter().fold(0.0, f32::max)),
+        }
+    }
+
+    /// Length of the item including the delay and all repetitions,
+    /// `None` if the item repeats forever
+    fn total_length(&self) -> Option<f32> {
+        let repetitions = match self.repeat {
+            Repeat::Times(count) => count,
+            Repeat::Forever => return None,
+        };
+        self.iteration_length().map(|length| duration_to_secs(self.delay) + length * repetitions as f32)
+    }
+
+    /// Collects the values of all animations at the given time (in seconds, relative
+    /// to the start of the item). Items that haven't started yet are skipped.
+    fn sample<'a>(&'a self, time: f32, values: &mut Vec<(&'a AnimatedProperty<T>, f32)>) {
+        use self::TimelineItemKind::*;
+
+        let time = time - duration_to_secs(self.delay);
+        if time < 0.0 {
+            return;
+        }
+
+        let (iteration, mut local_time, length) = match self.iteration_length() {
+            Some(length) if length > 0.0 => {
+                let iteration = (time / length).floor() as u32;
+                match self.repeat {
+                    Repeat::Times(count) if iteration >= count => (count.saturating_sub(1), length, length),
+                    _ => (iteration, time - iteration as f32 * length, length),
+                }
+            },
+            _ => (0, time, 0.0),
+        };
+
+        if self.ping_pong && iteration % 2 == 1 {
+            local_time = length - local_time;
+        }
+
+        match &self.kind {
+            Animation(animation) => values.push((&animation.property, animation.value_at(local_time))),
+            Parallel(items) => {
+                for item in items {
+                    item.sample(local_time, values);
+                }
+            },
+            Serial(items) => {
+                // Items that haven't played yet are reset to their start value, items
+                // that already played keep their end value - so that the currently playing
+                // item always wins if multiple items animate the same property
+                let mut start_times = Vec::with_capacity(items.len());
+                let mut current_start = Some(0.0);
+                for item in items {
+                    start_times.push(current_start);
+                    current_start = current_start.and_then(|start| item.total_length().map(|l| start + l));
+                }
+
+                let active_index = start_times.iter()
+                    .rposition(|start| start.map(|s| s <= local_time).unwrap_or(false))
+                    .unwrap_or(0);
+
+                for (item, start) in items.iter().zip(start_times.iter()).skip(active_index + 1).rev() {
+                    if start.is_some() {
+                        item.sample(0.0, values);
+                    }
+                }
+
+                for (item, start) in items.iter().zip(start_times.iter()).take(active_index + 1) {
+                    if let Some(start) = start {
+                        item.sample(local_time - start, values);
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// A choreographed sequence of animations, see the module-level documentation
+pub struct Timeline<T> {
+    root: TimelineItem<T>,
+    /// When the timeline started playing, set on the first frame the timeline is run
+    started: Option<Instant>,
+    on_complete: Option<TimelineCompletionCallback<T>>,
+}
+
+impl<T> Timeline<T> {
+
+    pub fn new(root: TimelineItem<T>) -> Self {
+        Self { root, started: None, on_complete: None }
+    }
+
+    /// Calls the `callback` once all animations of the timeline have finished
+    #[inline]
+    pub fn with_completion_callback(mut self, callback: TimelineCompletionCallback<T>) -> Self {
+        self.on_complete = Some(callback);
+        self
+    }
+
+    /// Returns the duration of the entire timeline, `None` if the timeline repeats forever
+    pub fn get_duration(&self) -> Option<Duration> {
+        self.root.total_length().map(secs_to_duration)
+    }
+
+    /// Removes the values that the timeline has applied to the paint properties of the
+    /// nodes (opacity and transforms), so that the nodes are drawn with their styled values
+    /// again - for example after a loading animation was stopped in the middle of a fade.
+    pub fn reset_properties(&self, app_resources: &mut AppResources) {
+        self.root.reset_properties(app_resources);
+    }
+
+    /// Crate-internal: Applies the animation values for the current frame,
+    /// returns whether the UI needs to be redrawn and whether the timeline is finished
+    pub(crate) fn run(&mut self, data: &mut T, app_resources: &mut AppResources) -> (UpdateScreen, bool) {
+
+        let now = Instant::now();
+        let elapsed = duration_to_secs(now - *self.started.get_or_insert(now));
+
+        let mut values = Vec::new();
+        self.root.sample(elapsed, &mut values);
+
+        let mut should_redraw = DontRedraw;
+        for (property, value) in values {
+            if property.apply(value, data, app_resources) {
+                should_redraw = Redraw;
+            }
+        }
+
+        let is_finished = self.root.total_length().map(|length| elapsed >= length).unwrap_or(false);
+
+        if is_finished {
+            if let Some(on_complete) = self.on_complete {
+                if on_complete(data, app_resources) == Redraw {
+                    should_redraw = Redraw;
+                }
+            }
+        }
+
+        (should_redraw, is_finished)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Timeline<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+            "Timeline {{ root: {:?}, started: {:?}, has_completion_callback: {:?} }}",
+            self.root, self.started, self.on_complete.is_some(),
+        )
+    }
+}
+
+/// Settings for the automatic layout transitions: If a node with an ID (see `Dom::with_id`)
+/// changes its position or size between two layouts (the nodes of two layouts are matched
+/// by their key, see `Dom::with_key`), it is animated from the old to the
+/// new rectangle (using the "FLIP" technique: the node is immediately laid out at its new
+/// position, but transformed back to its old position, then the transform is animated away).
+///
+/// The transition only changes the paint transform of the node, not the layout. Since the
+/// transform is applied to the whole node, the content of a resized node is stretched
+/// during the transition. Nodes that are added or removed are not animated.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LayoutTransition {
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl Default for LayoutTransition {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_millis(250),
+            easing: Easing::EaseInOut,
+        }
+    }
+}
+
+/// Crate-internal: Currently running layout transitions, by the key (first ID) of the node
+#[derive(Debug, Default)]
+pub(crate) struct LayoutTransitions {
+    config: Option<LayoutTransition>,
+    running: FastHashMap<String, RunningLayoutTransition>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct RunningLayoutTransition {
+    /// Transform that moves the node from its new rect back to its old rect
+    from: PaintTransform,
+    started: Instant,
+}
+
+impl RunningLayoutTransition {
+
+    /// Returns the transform at the given time and whether the transition is finished
+    fn transform_at(&self, now: Instant, config: &LayoutTransition) -> (PaintTransform, bool) {
+        let duration = duration_to_secs(config.duration);
+        let elapsed = duration_to_secs(now - self.started);
+        let progress = if duration <= 0.0 { 1.0 } else { (elapsed / duration).min(1.0) };
+        // Eased progress from the old to the new rectangle
+        let t = config.easing.apply(progress);
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+
+        let transform = PaintTransform {
+            translate: (lerp(self.from.translate.0, 0.0), lerp(self.from.translate.1, 0.0)),
+            scale: (lerp(self.from.scale.0, 1.0), lerp(self.from.scale.1, 1.0)),
+            rotate: 0.0,
+        };
+
+        (transform, progress >= 1.0)
+    }
+}
+
+impl LayoutTransitions {
+
+    pub(crate) fn new(config: Option<LayoutTransition>) -> Self {
+        Self { config, running: FastHashMap::default() }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    /// Starts a transition for every changed node: the node is transformed back to
+    /// its old rectangle, so that the first frame of the new layout looks exactly like
+    /// the last frame of the old layout. Returns whether any transition was started.
+    pub(crate) fn start(&mut self, changes: Vec<KeyedRectChange>, app_resources: &mut AppResources) -> bool {
+
+        let config = match self.config {
+            Some(s) => s,
+            None => return false,
+        };
+
+        let now = Instant::now();
+        let has_changes = !changes.is_empty();
+
+        for KeyedRectChange { key, old, new } in changes {
+
+            // If the node is still moving, start from where it is currently drawn
+            let current = self.running.get(&key)
+                .map(|transition| transition.transform_at(now, &config).0)
+                .unwrap_or_default();
+
+            let old_center = (old.origin.x + old.size.width / 2.0, old.origin.y + old.size.height / 2.0);
+            let new_center = (new.origin.x + new.size.width / 2.0, new.origin.y + new.size.height / 2.0);
+            let scale = |old: f32, new: f32| if new > 0.0 { old / new } else { 1.0 };
+
+            let from = PaintTransform {
+                translate: (
+                    old_center.0 - new_center.0 + current.translate.0,
+                    old_center.1 - new_center.1 + current.translate.1,
+                ),
+                scale: (
+                    scale(old.size.width, new.size.width) * current.scale.0,
+                    scale(old.size.height, new.size.height) * current.scale.1,
+                ),
+                rotate: 0.0,
+            };
+
+            set_transform_override(app_resources, &key, Some(from));
+            self.running.insert(key, RunningLayoutTransition { from, started: now });
+        }
+
+        has_changes
+    }
+
+    /// Advances all running transitions towards the new layout of the nodes
+    pub(crate) fn run(&mut self, app_resources: &mut AppResources) {
+
+        let config = match self.config {
+            Some(s) => s,
+            None => return,
+        };
+
+        let now = Instant::now();
+        let mut finished_transitions = Vec::new();
+
+        for (key, transition) in self.running.iter() {
+            let (transform, is_finished) = transition.transform_at(now, &config);
+            if is_finished {
+                set_transform_override(app_resources, key, None);
+                finished_transitions.push(key.clone());
+            } else {
+                set_transform_override(app_resources, key, Some(transform));
+            }
+        }
+
+        for key in finished_transitions {
+            self.running.remove(&key);
+        }
+    }
+}
+
+/// Replaces only the transform of the paint property override, keeps the opacity and clip
+fn set_transform_override(app_resources: &mut AppResources, dom_id: &str, transform: Option<PaintTransform>) {
+    modify_paint_override(app_resources, dom_id, |paint_override| paint_override.transform = transform);
+}
+
+/// Modifies the paint property override of the nodes with the given ID,
+/// removes the override if it doesn't change anything anymore
+fn modify_paint_override<F: FnOnce(&mut PaintPropertyOverride)>(app_resources: &mut AppResources, dom_id: &str, modify: F) {
+    let mut paint_override = app_resources.get_paint_property_override(dom_id).cloned().unwrap_or_default();
+    modify(&mut paint_override);
+    if paint_override == PaintPropertyOverride::default() {
+        app_resources.remove_paint_property_override(dom_id);
+    } else {
+        app_resources.set_paint_property_override(dom_id, paint_override);
+    }
+}
+
+/// Animation that is played when a node is added to or removed from the DOM, see
+/// `Dom::with_enter_animation` and `Dom::with_exit_animation`. The animation describes
+/// how the node looks while it's not in the DOM: an entering node is animated from this
+/// state to its normal state, an exiting node from its normal state to this state.
+///
+/// Nodes are matched across frames by their key (see `Dom::with_key`), the animation is
+/// applied via the first ID of the node (see `Dom::with_id`), nodes without an ID are not animated.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PresenceAnimation {
+    pub duration: Duration,
+    pub easing: Easing,
+    /// Opacity of the node while it's not in the DOM
+    pub opacity: f32,
+    /// Offset (in pixels) of the node while it's not in the DOM
+    pub translate: (f32, f32),
+    /// Scale of the node while it's not in the DOM
+    pub scale: f32,
+}
+
+impl PresenceAnimation {
+
+    /// Fades the node in or out
+    pub fn fade(duration: Duration) -> Self {
+        Self {
+            duration,
+            easing: Easing::EaseInOut,
+            opacity: 0.0,
+            translate: (0.0, 0.0),
+            scale: 1.0,
+        }
+    }
+
+    /// Fades the node in or out while sliding it from / to the given offset (in pixels)
+    pub fn slide(x: f32, y: f32, duration: Duration) -> Self {
+        Self { translate: (x, y), .. Self::fade(duration) }
+    }
+
+    #[inline]
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    #[inline]
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Applies the animation to the override, `visibility` goes from
+    /// 0.0 (not in the DOM) to 1.0 (normal state of the node)
+    fn apply(&self, visibility: f32, paint_override: &mut PaintPropertyOverride) {
+        let lerp = |hidden: f32, visible: f32| hidden + (visible - hidden) * visibility;
+        let scale = lerp(self.scale, 1.0);
+        paint_override.opacity = Some(lerp(self.opacity, 1.0));
+        paint_override.transform = Some(PaintTransform {
+            translate: (lerp(self.translate.0, 0.0), lerp(self.translate.1, 0.0)),
+            scale: (scale, scale),
+            rotate: 0.0,
+        });
+    }
+}
+
+impl Hash for PresenceAnimation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.duration.hash(state);
+        self.easing.hash(state);
+        self.opacity.to_bits().hash(state);
+        self.translate.0.to_bits().hash(state);
+        self.translate.1.to_bits().hash(state);
+        self.scale.to_bits().hash(state);
+    }
+}
+
+/// Crate-internal: Currently running enter and exit animations, by the key (first ID) of the node
+#[derive(Debug, Default)]
+pub(crate) struct PresenceAnimations {
+    running: FastHashMap<String, RunningPresenceAnimation>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct RunningPresenceAnimation {
+    animation: PresenceAnimation,
+    is_exit: bool,
+    started: Instant,
+}
+
+impl RunningPresenceAnimation {
+
+    /// Returns the linear progress of the animation (0.0 - 1.0)
+    fn progress(&self, now: Instant) -> f32 {
+        let duration = duration_to_secs(self.animation.duration);
+        if duration <= 0.0 {
+            1.0
+        } else {
+            (duration_to_secs(now - self.started) / duration).min(1.0)
+        }
+    }
+
+    /// Returns the visibility (0.0 = not in the DOM, 1.0 = normal) at the given time
+    fn visibility(&self, now: Instant) -> f32 {
+        let eased = self.animation.easing.apply(self.progress(now));
+        if self.is_exit { 1.0 - eased } else { eased }
+    }
+}
+
+impl PresenceAnimations {
+
+    pub(crate) fn start_enter(&mut self, key: &str, animation: PresenceAnimation, app_resources: &mut AppResources) {
+        self.start(key, animation, false, app_resources);
+    }
+
+    pub(crate) fn start_exit(&mut self, key: &str, animation: PresenceAnimation, app_resources: &mut AppResources) {
+        self.start(key, animation, true, app_resources);
+    }
+
+    fn start(&mut self, key: &str, animation: PresenceAnimation, is_exit: bool, app_resources: &mut AppResources) {
+
+        let now = Instant::now();
+
+        // If the opposite animation is still running (i.e. a node is added again while it's
+        // still exiting), continue from the current progress instead of jumping to the start
+        let already_played = match self.running.get(key) {
+            Some(running) if running.is_exit != is_exit => (1.0 - running.progress(now)) * duration_to_secs(animation.duration),
+            _ => 0.0,
+        };
+
+        let running = RunningPresenceAnimation {
+            animation,
+            is_exit,
+            started: now - secs_to_duration(already_played),
+        };
+
+        let visibility = running.visibility(now);
+        modify_paint_override(app_resources, key, |paint_override| animation.apply(visibility, paint_override));
+        self.running.insert(key.to_string(), running);
+    }
+
+    /// Returns whether the node with the given key is currently playing its exit animation
+    pub(crate) fn is_exiting(&self, key: &str) -> bool {
+        self.running.get(key).map(|running| running.is_exit).unwrap_or(false)
+    }
+
+    /// Stops the animation of the node and resets the node to its normal state
+    /// (a finished exit animation keeps the node hidden until it is stopped)
+    pub(crate) fn stop(&mut self, key: &str, app_resources: &mut AppResources) {
+        self.running.remove(key);
+        reset_presence_override(app_resources, key);
+    }
+
+    /// Advances all running animations, returns `Redraw` if an exit animation has finished,
+    /// since the exiting node has to be removed from the DOM
+    pub(crate) fn run(&mut self, app_resources: &mut AppResources) -> UpdateScreen {
+
+        let now = Instant::now();
+        let mut finished_animations = Vec::new();
+        let mut should_redraw = DontRedraw;
+
+        for (key, running) in self.running.iter() {
+            if running.progress(now) >= 1.0 {
+                if running.is_exit {
+                    should_redraw = Redraw;
+                } else {
+                    reset_presence_override(app_resources, key);
+                }
+                finished_animations.push(key.clone());
+            } else {
+                let visibility = running.visibility(now);
+                modify_paint_override(app_resources, key, |paint_override| running.animation.apply(visibility, paint_override));
+            }
+        }
+
+        for key in finished_animations {
+            self.running.remove(&key);
+        }
+
+        should_redraw
+    }
+}
+
+/// Removes the opacity and transform that were set by a `PresenceAnimation`
+fn reset_presence_override(app_resources: &mut AppResources, dom_id: &str) {
+    modify_paint_override(app_resources, dom_id, |paint_override| {
+        paint_override.opacity = None;
+        paint_override.transform = None;
+    });
+}
+
+fn duration_to_secs(duration: Duration) -> f32 {
+    duration.as_secs() as f32 + duration.subsec_nanos() as f32 / 1_000_000_000.0
+}
+
+fn secs_to_duration(secs: f32) -> Duration {
+    Duration::new(secs.trunc() as u64, (secs.fract() * 1_000_000_000.0) as u32)
+}
+
+#[cfg(test)]
+fn sample_values<T>(item: &TimelineItem<T>, time: f32) -> Vec<f32> {
+    let mut values = Vec::new();
+    item.sample(time, &mut values);
+    values.into_iter().map(|(_, value)| value).collect()
+}
+
+#[test]
+fn test_timeline_serial_and_parallel() {
+
+    let opacity = |from, to| TimelineItem::<()>::from(Animation::new(
+        AnimatedProperty::Opacity("a".into()), from, to, Duration::from_secs(1)
+    ));
+
+    let serial = TimelineItem::serial(vec![opacity(0.0, 1.0), opacity(1.0, 0.5)]);
+    assert_eq!(serial.total_length(), Some(2.0));
+    // The second animation hasn't started yet, so it's reset to its start value first
+    assert_eq!(sample_values(&serial, 0.5), vec![1.0, 0.5]);
+    assert_eq!(sample_values(&serial, 1.5), vec![1.0, 0.75]);
+    assert_eq!(sample_values(&serial, 5.0), vec![1.0, 0.5]);
+
+    let parallel = TimelineItem::parallel(vec![opacity(0.0, 1.0), opacity(0.0, 1.0).with_delay(Duration::from_secs(1))]);
+    assert_eq!(parallel.total_length(), Some(2.0));
+    assert_eq!(sample_values(&parallel, 0.5), vec![0.5]);
+    assert_eq!(sample_values(&parallel, 1.5), vec![1.0, 0.5]);
+}
+
+#[test]
+fn test_timeline_repeat_and_ping_pong() {
+
+    let item = TimelineItem::<()>::from(Animation::new(
+        AnimatedProperty::Rotate("a".into()), 0.0, 100.0, Duration::from_secs(1)
+    ));
+
+    let repeated = item.with_repeat(Repeat::Times(3)).with_ping_pong();
+    assert_eq!(repeated.total_length(), Some(3.0));
+    assert_eq!(sample_values(&repeated, 0.25), vec![25.0]);
+    assert_eq!(sample_values(&repeated, 1.25), vec![75.0]);
+    assert_eq!(sample_values(&repeated, 2.25), vec![25.0]);
+    // The last repetition is played forwards, so the animation ends at 100
+    assert_eq!(sample_values(&repeated, 10.0), vec![100.0]);
+
+    let forever = TimelineItem::serial(vec![repeated.with_repeat(Repeat::Forever)]);
+    assert_eq!(forever.total_length(), None);
+}
+
+#[test]
+fn test_layout_transition_interpolates_to_identity() {
+
+    let config = LayoutTransition { duration: Duration::from_secs(1), easing: Easing::Linear };
+    let started = Instant::now();
+    let transition = RunningLayoutTransition {
+        from: PaintTransform { translate: (-100.0, 20.0), scale: (2.0, 0.5), rotate: 0.0 },
+        started,
+    };
+
+    let (transform, is_finished) = transition.transform_at(started, &config);
+    assert_eq!(transform, transition.from);
+    assert!(!is_finished);
+
+    let (transform, is_finished) = transition.transform_at(started + Duration::from_millis(500), &config);
+    assert_eq!(transform, PaintTransform { translate: (-50.0, 10.0), scale: (1.5, 0.75), rotate: 0.0 });
+    assert!(!is_finished);
+
+    let (transform, is_finished) = transition.transform_at(started + Duration::from_secs(2), &config);
+    assert_eq!(transform, PaintTransform::default());
+    assert!(is_finished);
+}
+
+#[test]
+fn test_timeline_reset_properties() {
+
+    let mut timeline = Timeline::<()>::new(TimelineItem::parallel(vec![
+        Animation::new(AnimatedProperty::Opacity("a".into()), 1.0, 0.4, Duration::from_secs(1)).into(),
+        Animation::new(AnimatedProperty::Rotate("b".into()), 0.0, 90.0, Duration::from_secs(1)).into(),
+    ]).with_repeat(Repeat::Forever));
+
+    let mut app_resources = AppResources::new();
+    app_resources.set_paint_property_override("b", PaintPropertyOverride { opacity: Some(0.5), .. Default::default() });
+
+    timeline.run(&mut (), &mut app_resources);
+    assert_eq!(app_resources.get_paint_property_override("a").and_then(|o| o.opacity), Some(1.0));
+    assert!(app_resources.get_paint_property_override("b").and_then(|o| o.transform).is_some());
+
+    // Only the animated values are removed, the opacity of "b" wasn't set by the timeline
+    timeline.reset_properties(&mut app_resources);
+    assert!(app_resources.get_paint_property_override("a").is_none());
+    assert_eq!(app_resources.get_paint_property_override("b").cloned(), Some(PaintPropertyOverride { opacity: Some(0.5), .. Default::default() }));
+}
+
+#[test]
+fn test_presence_animation_reverses_from_current_progress() {
+
+    let animation = PresenceAnimation::slide(0.0, 40.0, Duration::from_secs(1)).with_easing(Easing::Linear);
+    let started = Instant::now();
+    let exit = RunningPresenceAnimation { animation, is_exit: true, started };
+
+    let mut paint_override = PaintPropertyOverride::default();
+    animation.apply(exit.visibility(started + Duration::from_millis(250)), &mut paint_override);
+    assert_eq!(paint_override.opacity, Some(0.75));
+    assert_eq!(paint_override.transform.map(|t| t.translate), Some((0.0, 10.0)));
+
+    // Node is added again while exiting: the enter animation starts at the current visibility
+    let mut animations = PresenceAnimations::default();
+    let mut app_resources = AppResources::new();
+    animations.running.insert("a".into(), RunningPresenceAnimation { started: Instant::now() - Duration::from_millis(250), .. exit });
+    animations.start_enter("a", animation, &mut app_resources);
+    assert!(!animations.is_exiting("a"));
+    let opacity = app_resources.get_paint_property_override("a").and_then(|o| o.opacity).unwrap();
+    assert!(opacity > 0.7 && opacity < 0.8);
+
+    animations.stop("a", &mut app_resources);
+    assert!(app_resources.get_paint_property_override("a").is_none());
+}
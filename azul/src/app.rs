@@ -1,7 +1,7 @@
 use std::{
     mem,
     fmt,
-    time::Instant,
+    time::{Instant, Duration},
     collections::BTreeMap,
     sync::{Arc, Mutex, PoisonError},
 };
@@ -10,7 +10,7 @@ use azul_css::HotReloadHandler;
 use glium::{
     SwapBuffersError,
     glutin::{
-        WindowEvent, WindowId as GliumWindowId,
+        WindowEvent, WindowId as GliumWindowId, MouseCursor,
         dpi::{LogicalPosition, LogicalSize}
     },
 };
@@ -20,7 +20,8 @@ use webrender::{
     api::{
         HitTestResult, HitTestFlags, DevicePixel,
         WorldPoint, LayoutSize, LayoutPoint,
-        Epoch, Transaction, ImageData, ImageDescriptor,
+        Epoch, Transaction, ImageData, ImageDescriptor, DisplayListBuilder,
+        LayoutTransform,
     },
 };
 #[cfg(feature = "image_loading")]
@@ -28,16 +29,19 @@ use app_resources::ImageSource;
 #[cfg(feature = "logging")]
 use log::LevelFilter;
 use azul_css::{Css, ColorU};
+#[cfg(all(feature = "native_style", not(test)))]
+use azul_css::SystemColors;
 use {
     FastHashMap,
     error::ClipboardError,
+    rich_text::RichText,
     window::{
-        Window, FakeWindow, ScrollStates,
+        Window, FakeWindow, ScrollStates, SCROLL_SNAP_DELAY,
         WindowCreateError, WindowCreateOptions, RendererType,
     },
     window_state::{WindowSize, DebugState},
     app_resources::TextId,
-    dom::{Dom, ScrollTagId},
+    dom::{Dom, ScrollTagId, MemoCache},
     app_resources::{
         ImageId, FontSource, FontId, ImageReloadError,
         FontReloadError, CssImageId,
@@ -45,10 +49,34 @@ use {
     traits::Layout,
     ui_state::UiState,
     ui_description::UiDescription,
-    async::{Task, Timer, TimerId, TerminateTimer},
-    callbacks::{FocusTarget, UpdateScreen, Redraw, DontRedraw, LayoutInfo},
+    async::{self, Task, Timer, TimerId, TerminateTimer},
+    animation::{Timeline, TimelineId, LayoutTransition, LayoutTransitions, PresenceAnimations},
+    diff::{self, KeyedRects},
+    keyboard_audit::KeyboardAudit,
+    layout_debug,
+    callbacks::{
+        FocusTarget, UpdateScreen, Redraw, DontRedraw, LayoutInfo, LayoutResult,
+        FrameStartCallbackType, AfterLayoutCallbackType, AfterRenderCallbackType,
+        OpenDocumentsCallbackType,
+    },
+    compositor::ContextLost,
+    display_list::{ScrolledNodes, IFrameCache, get_scrollbar_thumb_binding},
+    ui_solver::LayoutCache,
 };
 pub use app_resources::AppResources;
+#[cfg(all(feature = "native_style", not(test)))]
+use app_resources::load_system_colors;
+#[cfg(not(test))]
+use screenshot::Screenshot;
+#[cfg(not(test))]
+use recent_documents::{get_launch_documents, take_opened_documents, install_open_documents_handler};
+#[cfg(not(test))]
+use std::path::PathBuf;
+pub use router::Router;
+#[cfg(feature = "settings")]
+pub use settings::{Settings, SettingsError, SettingsChangeCallback, config_dir};
+#[cfg(feature = "websocket")]
+use websocket::{WebSocket, WebSocketId};
 
 type DeviceIntSize = ::euclid::TypedSize2D<i32, DevicePixel>;
 
@@ -56,6 +84,10 @@ type DeviceIntSize = ::euclid::TypedSize2D<i32, DevicePixel>;
 // (otherwise, "transparent") backgrounds would be painted black.
 const COLOR_WHITE: ColorU = ColorU { r: 255, g: 255, b: 255, a: 0 };
 
+/// Maximum time that `WindowCreateOptions::wait_for_first_frame` keeps a window hidden,
+/// so that a window is still shown if WebRender never finishes a frame
+const MAX_FIRST_FRAME_WAIT: Duration = Duration::from_secs(1);
+
 /// Graphical application that maintains some kind of application state
 pub struct App<T> {
     /// The graphical windows, indexed by their system ID / handle
@@ -73,6 +105,88 @@ pub struct App<T> {
     /// - It's a preparation for the C ABI, in which traits don't exist (for language bindings).
     ///   In the C ABI "traits" are simply structs with function pointers (and void* instead of T)
     layout_callback: fn(&T, layout_info: LayoutInfo<T>) -> Dom<T>,
+    /// Hooks that integrations can register to run code at fixed points in every frame
+    frame_hooks: FrameHooks<T>,
+}
+
+/// App-level hooks, called in the order they were registered
+struct FrameHooks<T> {
+    frame_start: Vec<FrameStartCallbackType<T>>,
+    after_layout: Vec<AfterLayoutCallbackType<T>>,
+    after_render: Vec<AfterRenderCallbackType<T>>,
+    open_documents: Vec<OpenDocumentsCallbackType<T>>,
+}
+
+impl<T> Default for FrameHooks<T> {
+    fn default() -> Self {
+        Self {
+            frame_start: Vec::new(),
+            after_layout: Vec::new(),
+            after_render: Vec::new(),
+            open_documents: Vec::new(),
+        }
+    }
+}
+
+/// Handle to an app that is driven by an external loop, see `App::run_manual`
+#[cfg(not(test))]
+pub struct ManualApp<T> {
+    app: App<T>,
+    frame_caches: FrameCaches<T>,
+}
+
+/// Returned by `ManualApp::tick()`, tells the external loop whether the app is still running
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ControlFlow {
+    /// The app is still running, `tick()` should be called again
+    Continue,
+    /// All windows were closed, call `ManualApp::finish()` to get the data model back
+    Exit,
+}
+
+#[cfg(not(test))]
+impl<T> ManualApp<T> {
+
+    /// Runs a single frame: polls the window events, calls the callbacks, timers
+    /// and tasks and re-layouts / re-renders the windows if necessary.
+    ///
+    /// `events` are processed in addition to the events of azuls own event loop,
+    /// for example events that the external loop received or synthesized for azuls windows.
+    pub fn tick(&mut self, events: Vec<::glium::glutin::Event>) -> Result<ControlFlow, RuntimeError<T>> {
+
+        if self.app.windows.is_empty() {
+            return Ok(ControlFlow::Exit);
+        }
+
+        let frame_result = self.app.run_single_frame(&mut self.frame_caches, events)?;
+
+        if frame_result.should_exit || self.app.windows.is_empty() {
+            Ok(ControlFlow::Exit)
+        } else {
+            Ok(ControlFlow::Continue)
+        }
+    }
+
+    /// Returns the global application state, i.e. to modify the data model between frames
+    pub fn app_state(&mut self) -> &mut AppState<T> {
+        &mut self.app.app_state
+    }
+
+    /// Renders the first window once more and reads the frame back from the GPU, see the
+    /// `screenshot` module. Returns `None` if no window is open or the OpenGL context was lost.
+    pub fn take_screenshot(&mut self) -> Option<Screenshot> {
+        let app = &mut self.app;
+        let window = app.windows.values_mut().next()?;
+        // Make sure that the last display list is part of the frame
+        app.app_state.resources.fake_display.render_api.flush_scene_builder();
+        rerender_single_window(&app.config, window, &mut app.app_state.resources).ok()?;
+        read_last_frame(window, &app.app_state.resources)
+    }
+
+    /// Stops the app and returns the data model
+    pub fn finish(self) -> Result<T, RuntimeError<T>> {
+        self.app.into_data()
+    }
 }
 
 /// Configuration for optional features, such as whether to enable logging or panic hooks
@@ -104,6 +218,11 @@ pub struct AppConfig {
     pub debug_state: DebugState,
     /// Background color for all windows
     pub background_color: ColorU,
+    /// If set, nodes with an ID that change their position or size between two
+    /// layouts are automatically animated from the old to the new rectangle
+    /// (see `LayoutTransition`).
+    /// Default is `None` (disabled).
+    pub layout_transition: Option<LayoutTransition>,
 }
 
 impl Default for AppConfig {
@@ -121,6 +240,7 @@ impl Default for AppConfig {
             renderer_type: RendererType::default(),
             debug_state: DebugState::default(),
             background_color: COLOR_WHITE,
+            layout_transition: None,
         }
     }
 }
@@ -162,6 +282,15 @@ pub struct AppState<T> {
     pub(crate) timers: FastHashMap<TimerId, Timer<T>>,
     /// Currently running tasks (asynchronous functions running each on a different thread)
     pub(crate) tasks: Vec<Task<T>>,
+    /// Currently running animation timelines, advanced every frame together with the timers
+    pub(crate) timelines: FastHashMap<TimelineId, Timeline<T>>,
+    /// Currently running layout transitions (see `AppConfig::layout_transition`)
+    pub(crate) layout_transitions: LayoutTransitions,
+    /// Currently running enter / exit animations (see `Dom::with_enter_animation`)
+    pub(crate) presence_animations: PresenceAnimations,
+    /// Open WebSocket connections, their messages are handled after the timers have run
+    #[cfg(feature = "websocket")]
+    pub(crate) websockets: FastHashMap<WebSocketId, WebSocket<T>>,
 }
 
 /// Same as the [AppState](./struct.AppState.html) but without the
@@ -179,6 +308,8 @@ pub struct AppStateNoData<'a, T> {
     pub(crate) timers: FastHashMap<TimerId, Timer<T>>,
     /// Currently running tasks (asynchronous functions running each on a different thread)
     pub(crate) tasks: Vec<Task<T>>,
+    /// Currently running animation timelines
+    pub(crate) timelines: FastHashMap<TimelineId, Timeline<T>>,
 }
 
 /// Error returned by the `.run()` function
@@ -192,6 +323,8 @@ pub enum RuntimeError<T> {
     MutexPoisonError(PoisonError<T>),
     MutexLockError,
     WindowIndexError,
+    /// The OpenGL context was lost and the renderer or the windows could not be re-created
+    ContextRecoveryError(WindowCreateError),
 }
 
 pub(crate) struct FrameEventInfo {
@@ -199,6 +332,7 @@ pub(crate) struct FrameEventInfo {
     pub(crate) should_hittest: bool,
     pub(crate) cur_cursor_pos: LogicalPosition,
     pub(crate) new_window_size: Option<LogicalSize>,
+    pub(crate) new_window_position: Option<LogicalPosition>,
     pub(crate) new_dpi_factor: Option<f64>,
     pub(crate) is_resize_event: bool,
 }
@@ -210,6 +344,7 @@ impl Default for FrameEventInfo {
             should_hittest: false,
             cur_cursor_pos: LogicalPosition::new(0.0, 0.0),
             new_window_size: None,
+            new_window_position: None,
             new_dpi_factor: None,
             is_resize_event: false,
         }
@@ -237,6 +372,7 @@ impl<T> fmt::Debug for RuntimeError<T> {
             MutexPoisonError(e) => write!(f, "Mutex poisoned (thread panicked unexpectedly): {}", e),
             MutexLockError => write!(f, "Failed to lock application state mutex"),
             WindowIndexError => write!(f, "Invalid window index"),
+            ContextRecoveryError(e) => write!(f, "Failed to recover from OpenGL context loss: {}", e),
         }
     }
 }
@@ -256,7 +392,7 @@ impl<T: Layout> App<T> {
     #[cfg(not(test))]
     #[allow(unused_variables)]
     /// Create a new, empty application. This does not open any windows.
-    pub fn new(initial_data: T, config: AppConfig) -> Result<Self, WindowCreateError> {
+    pub fn new(initial_data: T, mut config: AppConfig) -> Result<Self, WindowCreateError> {
 
         #[cfg(feature = "logging")] {
             if let Some(log_level) = config.enable_logging {
@@ -275,6 +411,8 @@ impl<T: Layout> App<T> {
 
         let mut app_state = AppState::new(initial_data, &config)?;
 
+        config.debug_state.active_renderer = Some(app_state.resources.fake_display.active_renderer);
+
         if let Some(r) = &mut app_state.resources.fake_display.renderer {
             set_webrender_debug_flags(r, &DebugState::default(), &config.debug_state);
         }
@@ -284,17 +422,20 @@ impl<T: Layout> App<T> {
             app_state,
             config,
             layout_callback: T::layout,
+            frame_hooks: FrameHooks::default(),
         })
     }
 }
 
 impl<T> App<T> {
 
-    /// Creates a new window
+    /// Creates a new window. Every window has its own stylesheet and can render its own
+    /// DOM, see `WindowCreateOptions::layout_callback`
     #[cfg(not(test))]
     pub fn create_window(&mut self, options: WindowCreateOptions<T>, css: Css)
     -> Result<Window<T>, WindowCreateError>
     {
+        options.validate()?;
         Window::new(
             &mut self.app_state.resources.fake_display.render_api,
             &mut self.app_state.resources.fake_display.hidden_display.gl_window().context(),
@@ -302,6 +443,7 @@ impl<T> App<T> {
             options,
             css,
             self.config.background_color,
+            self.app_state.resources.fake_display.active_renderer,
         )
     }
 
@@ -310,6 +452,7 @@ impl<T> App<T> {
     pub fn create_hot_reload_window(&mut self, options: WindowCreateOptions<T>, css_loader: Box<dyn HotReloadHandler>)
     -> Result<Window<T>, WindowCreateError>
     {
+        options.validate()?;
         Window::new_hot_reload(
             &mut self.app_state.resources.fake_display.render_api,
             &mut self.app_state.resources.fake_display.hidden_display.gl_window().context(),
@@ -317,6 +460,7 @@ impl<T> App<T> {
             options,
             css_loader,
             self.config.background_color,
+            self.app_state.resources.fake_display.active_renderer,
         )
     }
 
@@ -330,7 +474,11 @@ impl<T> App<T> {
         let fake_window = FakeWindow {
             state: window.state.clone(),
             default_callbacks: DefaultCallbackSystem::new(),
-            read_only_window: window.display.clone(),
+            read_only_window: Some(window.display.clone()),
+            text_search: None,
+            memo_cache: MemoCache::new(),
+            iframe_cache: IFrameCache::new(),
+            layout_cache: LayoutCache::new(),
         };
 
         self.app_state.windows.insert(window_id, fake_window);
@@ -364,8 +512,28 @@ impl<T> App<T> {
 
         // Apps need to have at least one window open
         self.add_window(window);
+        self.open_launch_documents();
         self.run_inner()?;
+        self.into_data()
+    }
+
+    /// Same as `run()`, but instead of blocking until all windows are closed, returns a
+    /// handle that runs exactly one frame every time `ManualApp::tick()` is called.
+    ///
+    /// This is useful if azul can't own the main loop, for example when it is embedded
+    /// in a game engine. The caller is responsible for calling `tick()` regularly
+    /// (i.e. once per frame) and for pacing the frames.
+    #[cfg(not(test))]
+    pub fn run_manual(mut self, window: Window<T>) -> Result<ManualApp<T>, RuntimeError<T>> {
+        self.add_window(window);
+        self.open_launch_documents();
+        let frame_caches = FrameCaches::new(&mut self)?;
+        Ok(ManualApp { app: self, frame_caches })
+    }
 
+    /// Consumes the app and returns the data model, after all windows have been closed
+    #[cfg(not(test))]
+    fn into_data(self) -> Result<T, RuntimeError<T>> {
         // NOTE: This is necessary because otherwise, the Arc::try_unwrap would fail,
         // since one Arc is still owned by the app_state.tasks structure
         //
@@ -376,150 +544,307 @@ impl<T> App<T> {
         unique_arc.into_inner().map_err(|e| e.into())
     }
 
+    /// Calls the `on_open_documents` hooks with the documents that were passed on the command
+    /// line and lets the OS deliver the documents that the running app is asked to open
     #[cfg(not(test))]
-    fn run_inner(&mut self) -> Result<(), RuntimeError<T>> {
+    fn open_launch_documents(&mut self) {
 
-        use std::{thread, time::Duration};
-        use glium::glutin::Event;
+        if self.frame_hooks.open_documents.is_empty() {
+            return;
+        }
 
-        let mut ui_state_cache = {
-            let app_state = &mut self.app_state;
-            let mut ui_state_map = BTreeMap::new();
-            for window_id in self.windows.keys() {
-              ui_state_map.insert(*window_id, UiState::from_app_state(app_state, window_id, self.layout_callback)?);
-            }
-            ui_state_map
-        };
-        let mut ui_description_cache = self.windows.keys().map(|window_id| (*window_id, UiDescription::default())).collect::<BTreeMap<_, _>>();
-        let mut force_redraw_cache = self.windows.keys().map(|window_id| (*window_id, 2)).collect();
-        let mut awakened_tasks = self.windows.keys().map(|window_id| (*window_id, false)).collect();
+        install_open_documents_handler();
+        self.open_documents(&get_launch_documents());
+    }
+
+    /// Calls the `on_open_documents` hooks, returns whether there were any documents to open
+    #[cfg(not(test))]
+    fn open_documents(&mut self, documents: &[PathBuf]) -> bool {
+
+        if documents.is_empty() {
+            return false;
+        }
+
+        for hook in &self.frame_hooks.open_documents {
+            hook(&mut self.app_state, documents);
+        }
+
+        true
+    }
+
+    #[cfg(not(test))]
+    fn run_inner(&mut self) -> Result<(), RuntimeError<T>> {
+
+        use std::thread;
 
-        #[cfg(debug_assertions)]
-        let mut last_style_reload = Instant::now();
-        #[cfg(debug_assertions)]
-        let mut should_print_css_error = true;
+        let mut frame_caches = FrameCaches::new(self)?;
 
         while !self.windows.is_empty() {
 
             let time_start = Instant::now();
 
-            let mut closed_windows = Vec::<GliumWindowId>::new();
-            let mut frame_was_resize = false;
-            let mut events = BTreeMap::new();
+            let frame_result = self.run_single_frame(&mut frame_caches, Vec::new())?;
+
+            if frame_result.should_exit {
+                return Ok(());
+            }
+
+            if !frame_result.was_resize {
+                // Wait until 16ms have passed, but not during a resize event
+                let diff = time_start.elapsed();
+                const FRAME_TIME: Duration = Duration::from_millis(16);
+                if diff < FRAME_TIME {
+                    thread::sleep(FRAME_TIME - diff);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Processes the pending window events (plus the `injected_events`), then
+    /// re-layouts and re-renders all windows if necessary
+    #[cfg(not(test))]
+    fn run_single_frame(&mut self, frame_caches: &mut FrameCaches<T>, injected_events: Vec<::glium::glutin::Event>)
+    -> Result<FrameResult, RuntimeError<T>>
+    {
+        use glium::glutin::Event;
 
-            self.app_state.resources.fake_display.hidden_events_loop.poll_events(|e| match e {
+        profile_scope!("frame");
+
+        for hook in &self.frame_hooks.frame_start {
+            hook(&mut self.app_state);
+        }
+
+        let mut closed_windows = Vec::<GliumWindowId>::new();
+        let mut frame_result = FrameResult { should_exit: false, was_resize: false };
+        let mut events = BTreeMap::new();
+
+        {
+            let mut sort_event = |e: Event| match e {
                 // Filter out all events that are uninteresting or unnecessary
                 Event::WindowEvent { event: WindowEvent::Refresh, .. } => { },
                 Event::WindowEvent { window_id, event } => {
                     events.entry(window_id).or_insert_with(|| Vec::new()).push(event);
                 },
                 _ => { },
-            });
+            };
 
-            let mut single_window_results = Vec::with_capacity(self.windows.len());
+            self.app_state.resources.fake_display.hidden_events_loop.poll_events(&mut sort_event);
+            injected_events.into_iter().for_each(sort_event);
+        }
 
-            for (current_window_id, mut window) in self.windows.iter_mut() {
+        let mut single_window_results = Vec::with_capacity(self.windows.len());
 
-                // Only process the events belong to this window ID...
-                let window_events: Vec<WindowEvent> = events.get(current_window_id).cloned().unwrap_or_default();
-
-                let single_window_result =
-                    hit_test_single_window(
-                        &window_events,
-                        &current_window_id,
-                        &mut window,
-                        &mut self.app_state,
-                        &mut ui_state_cache,
-                        &mut force_redraw_cache,
-                        &mut awakened_tasks,
-                    )?;
-
-                if single_window_result.needs_relayout_resize {
-                    frame_was_resize = true;
-                }
+        for (current_window_id, mut window) in self.windows.iter_mut() {
+
+            // Only process the events belong to this window ID...
+            let window_events: Vec<WindowEvent> = events.get(current_window_id).cloned().unwrap_or_default();
+
+            let single_window_result =
+                hit_test_single_window(
+                    &window_events,
+                    &current_window_id,
+                    &mut window,
+                    &mut self.app_state,
+                    &mut frame_caches.ui_state_cache,
+                    &frame_caches.ui_description_cache,
+                    &mut frame_caches.force_redraw_cache,
+                    &mut frame_caches.awakened_tasks,
+                )?;
+
+            if single_window_result.needs_relayout_resize {
+                frame_result.was_resize = true;
+            }
 
-                if single_window_result.window_should_close {
-                    closed_windows.push(*current_window_id);
+            if single_window_result.window_should_close {
+                closed_windows.push(*current_window_id);
 
-                    // TODO: Currently there is no way to return from the main event loop
-                    // i.e. the windows aren't actually getting closed
-                    // This is a hack, so that windows currently close properly
-                    return Ok(());
+                // TODO: Currently there is no way to return from the main event loop
+                // i.e. the windows aren't actually getting closed
+                // This is a hack, so that windows currently close properly
+                frame_result.should_exit = true;
+                return Ok(frame_result);
+            }
+
+            single_window_results.push(single_window_result);
+        }
+
+        #[cfg(debug_assertions)] {
+            hot_reload_css(
+                &mut self.windows,
+                &mut frame_caches.last_style_reload,
+                &mut frame_caches.should_print_css_error,
+                &mut frame_caches.awakened_tasks,
+            )?;
+        }
+
+        // Documents that the OS asked the running app to open (i.e. from the dock menu on macOS)
+        if self.open_documents(&take_opened_documents()) {
+            for awakened in frame_caches.awakened_tasks.values_mut() {
+                *awakened = true;
+            }
+        }
+
+        // Restyle all windows with the new built-in `--azul-system-*` variables
+        #[cfg(feature = "native_style")] {
+            if let Some(system_colors) = frame_caches.system_colors_watcher.take_changed_colors() {
+                self.app_state.resources.set_system_colors(system_colors);
+                for awakened in frame_caches.awakened_tasks.values_mut() {
+                    *awakened = true;
                 }
+            }
+        }
 
-                single_window_results.push(single_window_result);
+        // Close windows if necessary
+        closed_windows.into_iter().for_each(|closed_window_id| {
+            frame_caches.ui_state_cache.remove(&closed_window_id);
+            frame_caches.ui_description_cache.remove(&closed_window_id);
+            frame_caches.force_redraw_cache.remove(&closed_window_id);
+            if let Some(closed_window) = self.windows.remove(&closed_window_id) {
+                delete_last_frame_texture(&closed_window, &self.app_state.resources);
             }
+        });
 
-            #[cfg(debug_assertions)] {
-                hot_reload_css(
-                    &mut self.windows,
-                    &mut last_style_reload,
-                    &mut should_print_css_error,
-                    &mut awakened_tasks,
+        let should_relayout_all_windows = single_window_results.iter().any(|res| res.should_relayout());
+        let should_rerender_all_windows = single_window_results.iter().any(|res| res.should_rerender());
+
+        let should_redraw_timers = self.app_state.run_all_timers();
+        let should_redraw_tasks = self.app_state.clean_up_finished_tasks();
+        #[cfg(feature = "websocket")]
+        let should_redraw_websockets = self.app_state.run_all_websockets();
+        #[cfg(not(feature = "websocket"))]
+        let should_redraw_websockets = DontRedraw;
+        let should_redraw_timers_or_tasks = [should_redraw_timers, should_redraw_tasks, should_redraw_websockets].into_iter().any(|e| *e == Redraw);
+
+        // Paint property overrides only need a new display list, not a new layout
+        let should_repaint_all_windows = self.app_state.resources.take_paint_property_changes();
+
+        // If there is a relayout necessary, re-layout *all* windows!
+        if should_relayout_all_windows || should_redraw_timers_or_tasks {
+            for (current_window_id, mut window) in self.windows.iter_mut() {
+                relayout_single_window(
+                    self.layout_callback,
+                    &self.frame_hooks.after_layout,
+                    &self.config.debug_state,
+                    &current_window_id,
+                    &mut window,
+                    &mut self.app_state,
+                    &mut frame_caches.ui_state_cache,
+                    &mut frame_caches.ui_description_cache,
+                    &mut frame_caches.force_redraw_cache,
+                    &mut frame_caches.awakened_tasks,
+                )?;
+            }
+        } else if should_repaint_all_windows {
+            for (current_window_id, mut window) in self.windows.iter_mut() {
+                repaint_single_window(
+                    &current_window_id,
+                    &mut window,
+                    &mut self.app_state,
+                    &frame_caches.ui_state_cache,
+                    &frame_caches.ui_description_cache,
                 )?;
             }
+        }
 
-            // Close windows if necessary
-            closed_windows.into_iter().for_each(|closed_window_id| {
-                ui_state_cache.remove(&closed_window_id);
-                ui_description_cache.remove(&closed_window_id);
-                force_redraw_cache.remove(&closed_window_id);
-                self.windows.remove(&closed_window_id);
-            });
-
-            let should_relayout_all_windows = single_window_results.iter().any(|res| res.should_relayout());
-            let should_rerender_all_windows = single_window_results.iter().any(|res| res.should_rerender());
-
-            let should_redraw_timers = self.app_state.run_all_timers();
-            let should_redraw_tasks = self.app_state.clean_up_finished_tasks();
-            let should_redraw_timers_or_tasks = [should_redraw_timers, should_redraw_tasks].into_iter().any(|e| *e == Redraw);
-
-            // If there is a relayout necessary, re-layout *all* windows!
-            if should_relayout_all_windows || should_redraw_timers_or_tasks{
-                for (current_window_id, mut window) in self.windows.iter_mut() {
-                    relayout_single_window(
-                        self.layout_callback,
-                        &current_window_id,
-                        &mut window,
-                        &mut self.app_state,
-                        &mut ui_state_cache,
-                        &mut ui_description_cache,
-                        &mut force_redraw_cache,
-                        &mut awakened_tasks,
-                    )?;
+        // If there is a re-render necessary, re-render *all* windows
+        if should_rerender_all_windows || should_redraw_timers_or_tasks || should_repaint_all_windows {
+            let mut context_lost = false;
+            for window in self.windows.values_mut() {
+                // TODO: For some reason this function has to be called twice in order
+                // to actually update the screen. For some reason the first swap_buffers() has
+                // no effect (winit bug?)
+                let render_result = rerender_single_window(
+                    &self.config,
+                    window,
+                    &mut self.app_state.resources,
+                ).and_then(|_| rerender_single_window(
+                    &self.config,
+                    window,
+                    &mut self.app_state.resources,
+                ));
+
+                if render_result.is_err() {
+                    context_lost = true;
+                    break;
                 }
             }
 
-            // If there is a re-render necessary, re-render *all* windows
-            if should_rerender_all_windows || should_redraw_timers_or_tasks {
-                for window in self.windows.values_mut() {
-                    // TODO: For some reason this function has to be called twice in order
-                    // to actually update the screen. For some reason the first swap_buffers() has
-                    // no effect (winit bug?)
-                    rerender_single_window(
-                        &self.config,
-                        window,
-                        &mut self.app_state.resources,
-                    );
-                    rerender_single_window(
-                        &self.config,
-                        window,
-                        &mut self.app_state.resources,
-                    );
-                }
-                // Automatically remove unused fonts and images from webrender
-                // Tell the font + image GC to start a new frame
-                self.app_state.resources.garbage_collect_fonts_and_images();
+            if context_lost {
+                self.recover_from_context_loss().map_err(RuntimeError::ContextRecoveryError)?;
+
+                // All windows have new IDs now, re-layout + re-render everything on the next frame
+                *frame_caches = FrameCaches::new(self)?;
+                return Ok(frame_result);
             }
 
-            if !frame_was_resize {
-                // Wait until 16ms have passed, but not during a resize event
-                let diff = time_start.elapsed();
-                const FRAME_TIME: Duration = Duration::from_millis(16);
-                if diff < FRAME_TIME {
-                    thread::sleep(FRAME_TIME - diff);
+            // Windows that are still hidden because WebRender hasn't rendered
+            // their first frame yet have to be rendered again on the next frame
+            for (window_id, window) in self.windows.iter() {
+                if window.is_waiting_for_first_frame() {
+                    frame_caches.force_redraw_cache.insert(*window_id, 2);
                 }
             }
+
+            // Automatically remove unused fonts and images from webrender
+            // Tell the font + image GC to start a new frame
+            self.app_state.resources.garbage_collect_fonts_and_images();
+
+            for hook in &self.frame_hooks.after_render {
+                hook(&mut self.app_state);
+            }
+        }
+
+        // IFrames that were stretched while the window was being resized have
+        // to invoke their IFrameCallbacks again once the resizing has ended
+        for (window_id, window) in self.windows.iter() {
+            let has_stale_iframes = self.app_state.windows.get(window_id).map(|w| w.iframe_cache.has_stale_content).unwrap_or(false);
+            if has_stale_iframes && !window.is_iframe_resize_preview_active() {
+                frame_caches.force_redraw_cache.insert(*window_id, 2);
+            }
+        }
+
+        Ok(frame_result)
+    }
+
+    /// Re-creates the renderer and all windows after the OpenGL context was lost.
+    ///
+    /// The windows are re-created with their current state (so they get new window IDs),
+    /// fonts and images are re-uploaded to the new renderer during the next layout.
+    #[cfg(not(test))]
+    fn recover_from_context_loss(&mut self) -> Result<(), WindowCreateError> {
+
+        use compositor::forget_active_gl_textures;
+
+        #[cfg(feature = "logging")] {
+            warn!("OpenGL context lost, re-creating renderer and windows");
+        }
+
+        forget_active_gl_textures();
+        self.app_state.resources.fake_display.recreate_after_context_loss()?;
+        self.app_state.resources.clear_registered_fonts_and_images();
+
+        let old_windows = mem::replace(&mut self.windows, BTreeMap::new());
+
+        for (old_window_id, old_window) in old_windows {
+            let fake_display = &mut self.app_state.resources.fake_display;
+            let window = old_window.recreate_after_context_loss(
+                &mut fake_display.render_api,
+                &fake_display.hidden_display.gl_window().context(),
+                &fake_display.hidden_events_loop,
+                self.config.background_color,
+                fake_display.active_renderer,
+            )?;
+
+            match self.app_state.windows.remove(&old_window_id) {
+                Some(mut fake_window) => {
+                    fake_window.read_only_window = Some(window.display.clone());
+                    self.app_state.windows.insert(window.id, fake_window);
+                    self.windows.insert(window.id, window);
+                },
+                None => self.add_window(window),
+            }
         }
 
         Ok(())
@@ -530,9 +855,43 @@ impl<T> App<T> {
         self.app_state.add_task(task);
     }
 
+    /// Registers a hook that is called at the start of every frame,
+    /// before the window events are processed.
+    pub fn on_frame_start(&mut self, hook: FrameStartCallbackType<T>) {
+        self.frame_hooks.frame_start.push(hook);
+    }
+
+    /// Registers a hook that is called every time a window has been laid out. The
+    /// hook gets the ID of the window (the key into `AppState::windows`) and the solved
+    /// layout, i.e. the final positions of all nodes in the window.
+    pub fn on_after_layout(&mut self, hook: AfterLayoutCallbackType<T>) {
+        self.frame_hooks.after_layout.push(hook);
+    }
+
+    /// Registers a hook that is called after all windows of a frame have been
+    /// rendered (frames where nothing changed aren't rendered).
+    pub fn on_after_render(&mut self, hook: AfterRenderCallbackType<T>) {
+        self.frame_hooks.after_render.push(hook);
+    }
+
+    /// Registers a hook that is called with the documents that the app is asked to open,
+    /// i.e. when the user picks a document registered via
+    /// `recent_documents::add_recent_document` from a jump list or a "recent" menu.
+    ///
+    /// The OS passes these documents as command-line arguments, so the hook is called
+    /// once, before the first frame, if the app was launched with nothing but existing
+    /// files as arguments (see `recent_documents::get_launch_documents`). On macOS, the
+    /// documents that the running app is asked to open (i.e. from Finder or the dock menu)
+    /// are sent via the `application:openFiles:` Apple Event, the hook is called with them
+    /// before the next frame.
+    pub fn on_open_documents(&mut self, hook: OpenDocumentsCallbackType<T>) {
+        self.frame_hooks.open_documents.push(hook);
+    }
+
     /// Toggles debugging flags in webrender, updates `self.config.debug_state`
     #[cfg(not(test))]
-    pub fn toggle_debug_flags(&mut self, new_state: DebugState) {
+    pub fn toggle_debug_flags(&mut self, mut new_state: DebugState) {
+        new_state.active_renderer = self.config.debug_state.active_renderer;
         if let Some(r) = &mut self.app_state.resources.fake_display.renderer {
             set_webrender_debug_flags(r, &self.config.debug_state, &new_state);
         }
@@ -556,12 +915,17 @@ impl<T> AppState<T> {
             resources: AppResources::new(config)?,
             timers: FastHashMap::default(),
             tasks: Vec::new(),
+            timelines: FastHashMap::default(),
+            layout_transitions: LayoutTransitions::new(config.layout_transition),
+            presence_animations: PresenceAnimations::default(),
+            #[cfg(feature = "websocket")]
+            websockets: FastHashMap::default(),
         })
     }
 
     impl_deamon_api!();
 
-    /// Run all currently registered timers
+    /// Run all currently registered timers and animation timelines
     #[must_use]
     fn run_all_timers(&mut self) -> UpdateScreen {
         let mut should_update_screen = DontRedraw;
@@ -585,6 +949,78 @@ impl<T> AppState<T> {
             self.timers.remove(&key);
         }
 
+        let mut finished_timelines = Vec::new();
+
+        for (key, timeline) in self.timelines.iter_mut() {
+            let (should_update, is_finished) = timeline.run(&mut lock, &mut self.resources);
+
+            if should_update == Redraw {
+                should_update_screen = Redraw;
+            }
+
+            if is_finished {
+                finished_timelines.push(*key);
+            }
+        }
+
+        for key in finished_timelines {
+            self.timelines.remove(&key);
+        }
+
+        self.layout_transitions.run(&mut self.resources);
+
+        // Finished exit animations need a relayout, so that the exiting nodes are removed
+        if self.presence_animations.run(&mut self.resources) == Redraw {
+            should_update_screen = Redraw;
+        }
+
+        should_update_screen
+    }
+
+    /// Adds a WebSocket connection, its callback is called on every frame in which
+    /// messages have been received. Closes the existing connection if called with
+    /// the same `WebSocketId`.
+    #[cfg(feature = "websocket")]
+    pub fn add_websocket(&mut self, id: WebSocketId, websocket: WebSocket<T>) {
+        self.websockets.insert(id, websocket);
+    }
+
+    #[cfg(feature = "websocket")]
+    pub fn has_websocket(&self, id: &WebSocketId) -> bool {
+        self.websockets.contains_key(id)
+    }
+
+    /// Closes the WebSocket connection
+    #[cfg(feature = "websocket")]
+    pub fn delete_websocket(&mut self, id: &WebSocketId) {
+        self.websockets.remove(id);
+    }
+
+    /// Hands all received WebSocket messages to the callbacks,
+    /// removes the connections that have been closed
+    #[cfg(feature = "websocket")]
+    #[must_use]
+    fn run_all_websockets(&mut self) -> UpdateScreen {
+        let mut should_update_screen = DontRedraw;
+        let mut lock = self.data.lock().unwrap();
+        let mut closed_websockets = Vec::new();
+
+        for (key, websocket) in self.websockets.iter_mut() {
+            let (should_update, is_closed) = websocket.run(&mut lock, &mut self.resources);
+
+            if should_update == Redraw {
+                should_update_screen = Redraw;
+            }
+
+            if is_closed {
+                closed_websockets.push(*key);
+            }
+        }
+
+        for key in closed_websockets {
+            self.websockets.remove(&key);
+        }
+
         should_update_screen
     }
 
@@ -592,11 +1028,15 @@ impl<T> AppState<T> {
     #[must_use] fn clean_up_finished_tasks(&mut self) -> UpdateScreen {
         let old_count = self.tasks.len();
         let mut timers_to_add = Vec::new();
+        let mut timelines_to_stop = Vec::new();
         self.tasks.retain(|task| {
             if task.is_finished() {
                 if let Some(timer) = task.after_completion_timer {
                     timers_to_add.push((TimerId::new(), timer));
                 }
+                if let Some(timeline_id) = task.after_completion_stop_timeline {
+                    timelines_to_stop.push(timeline_id);
+                }
                 false
             } else {
                 true
@@ -611,6 +1051,12 @@ impl<T> AppState<T> {
             self.add_timer(timer_id, timer);
         }
 
+        for timeline_id in timelines_to_stop {
+            if let Some(timeline) = self.delete_timeline(&timeline_id) {
+                timeline.reset_properties(&mut self.resources);
+            }
+        }
+
         if old_count == new_count && timers_is_empty {
             DontRedraw
         } else {
@@ -637,6 +1083,108 @@ struct SingleWindowContentResult {
     new_focus_target: Option<FocusTarget>,
 }
 
+/// Per-window caches of the main loop that persist between frames
+#[cfg(not(test))]
+struct FrameCaches<T> {
+    ui_state_cache: BTreeMap<GliumWindowId, UiState<T>>,
+    ui_description_cache: BTreeMap<GliumWindowId, UiDescription<T>>,
+    force_redraw_cache: BTreeMap<GliumWindowId, usize>,
+    awakened_tasks: BTreeMap<GliumWindowId, bool>,
+    #[cfg(debug_assertions)]
+    last_style_reload: Instant,
+    #[cfg(debug_assertions)]
+    should_print_css_error: bool,
+    #[cfg(feature = "native_style")]
+    system_colors_watcher: SystemColorsWatcher,
+}
+
+#[cfg(not(test))]
+impl<T> FrameCaches<T> {
+
+    /// Creates the caches for all windows of the app, calls the layout callback once per window
+    fn new(app: &mut App<T>) -> Result<Self, RuntimeError<T>> {
+
+        let mut ui_state_cache = BTreeMap::new();
+        for (window_id, window) in app.windows.iter() {
+            let layout_callback = window.get_layout_callback(app.layout_callback);
+            ui_state_cache.insert(*window_id, UiState::from_app_state(&mut app.app_state, window_id, layout_callback)?);
+        }
+
+        Ok(Self {
+            ui_state_cache,
+            ui_description_cache: app.windows.keys().map(|window_id| (*window_id, UiDescription::default())).collect(),
+            force_redraw_cache: app.windows.keys().map(|window_id| (*window_id, 2)).collect(),
+            awakened_tasks: app.windows.keys().map(|window_id| (*window_id, false)).collect(),
+            #[cfg(debug_assertions)]
+            last_style_reload: Instant::now(),
+            #[cfg(debug_assertions)]
+            should_print_css_error: true,
+            #[cfg(feature = "native_style")]
+            system_colors_watcher: SystemColorsWatcher::new(),
+        })
+    }
+}
+
+/// Loads the system colors on a background thread (since asking the OS takes a few
+/// milliseconds, the app starts with the default colors) and reloads them whenever the
+/// OS notifies the thread that the user changed them, so that the windows can be restyled
+#[cfg(all(feature = "native_style", not(test)))]
+struct SystemColorsWatcher {
+    /// Set by the thread if the colors changed, the thread stops at the
+    /// next change notification once the watcher is dropped
+    changed_colors: Arc<Mutex<Option<SystemColors>>>,
+}
+
+#[cfg(all(feature = "native_style", not(test)))]
+impl SystemColorsWatcher {
+
+    fn new() -> Self {
+
+        use std::thread;
+        use azul_native_style::platform::watch_system_colors;
+
+        let changed_colors = Arc::new(Mutex::new(None));
+        let thread_changed_colors = changed_colors.clone();
+
+        thread::spawn(move || {
+            let mut last_colors = SystemColors::default();
+            let mut reload_colors = || {
+                if Arc::strong_count(&thread_changed_colors) == 1 {
+                    return false;
+                }
+                let new_colors = load_system_colors();
+                if new_colors != last_colors {
+                    last_colors = new_colors;
+                    if let Ok(mut changed) = thread_changed_colors.lock() {
+                        *changed = Some(new_colors);
+                    }
+                }
+                true
+            };
+            if reload_colors() {
+                watch_system_colors(reload_colors);
+            }
+        });
+
+        Self { changed_colors }
+    }
+
+    /// Returns the new system colors if they changed since the last call
+    fn take_changed_colors(&self) -> Option<SystemColors> {
+        self.changed_colors.lock().ok()?.take()
+    }
+}
+
+/// What happened during one iteration of the main loop
+#[cfg(not(test))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct FrameResult {
+    /// A window was closed, the app should stop
+    should_exit: bool,
+    /// A window was resized, the next frame should run without waiting
+    was_resize: bool,
+}
+
 impl SingleWindowContentResult {
 
     pub fn should_relayout(&self) -> bool {
@@ -661,12 +1209,18 @@ fn hit_test_single_window<T>(
     window: &mut Window<T>,
     app_state: &mut AppState<T>,
     ui_state_cache: &mut BTreeMap<GliumWindowId, UiState<T>>,
+    ui_description_cache: &BTreeMap<GliumWindowId, UiDescription<T>>,
     force_redraw_cache: &mut BTreeMap<GliumWindowId, usize>,
     awakened_tasks: &mut BTreeMap<GliumWindowId, bool>,
 ) -> Result<SingleWindowContentResult, RuntimeError<T>> {
 
     use self::RuntimeError::*;
 
+    // While the left mouse button is held down, the time-based `On::LongPress` and
+    // `On::HoldRepeat` events have to be checked every frame, even without new events
+    let held_press_tick = [WindowEvent::Refresh];
+    let events = if events.is_empty() && window.state.internal.held_press.is_some() { &held_press_tick[..] } else { events };
+
     let (mut frame_event_info, window_should_close) = window.state.update_window_state(&events);
     let mut ret = SingleWindowContentResult {
         needs_rerender_hover_active: false,
@@ -681,7 +1235,7 @@ fn hit_test_single_window<T>(
         new_focus_target: None,
     };
 
-    if events.is_empty() && !ret.should_relayout() && !ret.should_rerender() {
+    if events.is_empty() && !ret.should_relayout() && !ret.should_rerender() && !window.scroll_states.has_pending_snaps() {
         // Event was not a resize event, window should **not** close
         ret.window_should_close = window_should_close;
         return Ok(ret);
@@ -699,6 +1253,7 @@ fn hit_test_single_window<T>(
                 window,
                 &window_id,
                 ui_state_cache.get_mut(window_id).ok_or(WindowIndexError)?,
+                ui_description_cache.get(window_id).ok_or(WindowIndexError)?,
                 app_state
             )?;
 
@@ -725,13 +1280,25 @@ fn hit_test_single_window<T>(
 
     ret.hit_test_results = ret.hit_test_results.or_else(|| do_hit_test(window, &app_state.resources));
 
+    // Show the `cursor` of the topmost hovered node that has one, otherwise the default cursor
+    if let Some(hit_test_results) = &ret.hit_test_results {
+        use css::webrender_translate::winit_translate_cursor;
+        let ui_state = ui_state_cache.get(window_id).ok_or(WindowIndexError)?;
+        let cursor = hit_test_results.items.iter().rev()
+            .find_map(|item| ui_state.tag_ids_to_cursors.get(&item.tag.0))
+            .map(|cursor| winit_translate_cursor(*cursor))
+            .unwrap_or(MouseCursor::Default);
+        let fake_window = app_state.windows.get_mut(window_id).ok_or(WindowIndexError)?;
+        fake_window.state.internal.mouse_state.mouse_cursor_type = cursor;
+    }
+
     // Scroll for the scrolled amount for each node that registered a scroll state.
     let should_scroll_render = match &ret.hit_test_results {
         Some(hit_test_results) => update_scroll_state(window, hit_test_results),
         None => false,
     };
 
-    ret.should_scroll_render = should_scroll_render;
+    ret.should_scroll_render = should_scroll_render || update_scroll_snap(window);
 
     if frame_event_info.is_resize_event {
         // This is a hack because during a resize event, winit eats the "awakened"
@@ -741,6 +1308,7 @@ fn hit_test_single_window<T>(
         //
         // This is a reported bug and should be fixed somewhere in July
         *force_redraw_cache.get_mut(window_id).ok_or(WindowIndexError)? = 2;
+        window.last_resize = Some(Instant::now());
     }
 
     // See: https://docs.rs/glutin/0.19.0/glutin/struct.CombinedContext.html#method.resize
@@ -759,8 +1327,21 @@ fn hit_test_single_window<T>(
     // Update the window state that we got from the frame event (updates window dimensions and DPI)
     // Sets frame_event_info.needs redraw if the event was a
     window.update_from_external_window_state(&mut frame_event_info, &app_state.resources.fake_display.hidden_events_loop);
+
+    if frame_event_info.is_resize_event {
+        // Cover the window with the (stretched) last frame until the layout + render for the
+        // new size is done (later in this frame), instead of showing empty white areas.
+        // A lost context is ignored here, since it will be detected during the render.
+        let _ = redraw_last_frame(window, &app_state.resources);
+    }
+
     // Update the window state every frame that was set by the user
-    window.update_from_user_window_state(app_state.windows[&window_id].state.clone());
+    {
+        let fake_window = app_state.windows.get_mut(window_id).ok_or(WindowIndexError)?;
+        window.update_from_user_window_state(&mut fake_window.state);
+        // The cursor should only be warped once, not on every frame
+        fake_window.state.internal.pending_cursor_position = None;
+    }
     // Reset the scroll amount to 0 (for the next frame)
     window.clear_scroll_state();
 
@@ -770,6 +1351,8 @@ fn hit_test_single_window<T>(
 #[cfg(not(test))]
 fn relayout_single_window<T>(
     layout_callback: fn(&T, LayoutInfo<T>) -> Dom<T>,
+    after_layout_hooks: &[AfterLayoutCallbackType<T>],
+    debug_state: &DebugState,
     window_id: &GliumWindowId,
     window: &mut Window<T>,
     app_state: &mut AppState<T>,
@@ -781,33 +1364,129 @@ fn relayout_single_window<T>(
 
     use self::RuntimeError::*;
 
-    // Call the Layout::layout() fn, get the DOM
-    *ui_state_cache.get_mut(window_id).ok_or(WindowIndexError)? =
-        UiState::from_app_state(app_state, window_id, layout_callback)?;
+    // Call the Layout::layout() fn (or the layout callback of the window), get the DOM
+    let layout_callback = window.get_layout_callback(layout_callback);
+    let mut dom = UiState::dom_from_app_state(app_state, window_id, layout_callback)?;
+
+    // Show the error of the last CSS hot-reload on top of the application
+    #[cfg(debug_assertions)] {
+        if let Some(error) = &window.css_reload_error {
+            dom.add_child(css_reload_error_dom(error));
+        }
+    }
+
+    // Show the result of the keyboard audit of the last frame on top of the application
+    if debug_state.keyboard_audit {
+        if let Some(audit) = &window.keyboard_audit {
+            dom.add_child(audit.to_dom());
+        }
+    }
+
+    // Which node of the last frame each node of the new DOM corresponds to (see `Dom::with_key`)
+    let matched_nodes = {
+        let old_dom = &ui_state_cache.get(window_id).ok_or(WindowIndexError)?.dom;
+        diff::match_nodes_by_key(
+            &old_dom.arena.node_layout, &old_dom.arena.node_data, old_dom.root,
+            &dom.arena.node_layout, &dom.arena.node_data, dom.root,
+        )
+    };
+
+    // Start the enter / exit animations before the display list is built, so that the
+    // first frame already shows the nodes in their animated state
+    diff::update_presence_animations(
+        &ui_state_cache.get(window_id).ok_or(WindowIndexError)?.dom,
+        &mut dom,
+        &matched_nodes,
+        &mut window.exiting_nodes,
+        &mut app_state.presence_animations,
+        &mut app_state.resources,
+    );
+
+    let mut ui_state = dom.into_ui_state();
+    ui_state.builtin_css_variables = app_state.resources.get_system_colors().get_variables();
+    *ui_state_cache.get_mut(window_id).ok_or(WindowIndexError)? = ui_state;
 
     // Style the DOM (is_mouse_down is necessary for styling :hover, :active + :focus nodes)
     let is_mouse_down = window.state.internal.mouse_state.mouse_down();
 
-    *ui_description_cache.get_mut(window_id).ok_or(WindowIndexError)? =
-        UiDescription::match_css_to_dom(
-            ui_state_cache.get_mut(window_id).ok_or(WindowIndexError)?,
-            &window.css,
-            &mut window.state.internal.focused_node,
-            &mut window.state.internal.pending_focus_target,
-            &window.state.internal.hovered_nodes,
-            is_mouse_down,
-        );
-
-    let mut fake_window = app_state.windows.get_mut(window_id).ok_or(WindowIndexError)?;
-    update_display_list(
-        &mut app_state.data,
-        &ui_description_cache[window_id],
-        &ui_state_cache[window_id],
-        &mut *window,
-        &mut fake_window,
-        &mut app_state.resources,
+    // Only the nodes whose hover / focus state, classes, etc. changed since the last frame are restyled
+    let ui_description = UiDescription::match_css_to_dom_incremental(
+        ui_description_cache.get(window_id).ok_or(WindowIndexError)?,
+        ui_state_cache.get_mut(window_id).ok_or(WindowIndexError)?,
+        &window.css,
+        window.state.size.get_css_viewport(),
+        &mut window.state.internal.focused_node,
+        &mut window.state.internal.pending_focus_target,
+        &window.state.internal.hovered_nodes,
+        is_mouse_down,
     );
-    *awakened_tasks.get_mut(window_id).ok_or(WindowIndexError)? = false;
+    *ui_description_cache.get_mut(window_id).ok_or(WindowIndexError)? = ui_description;
+
+    let layout_result = {
+        let mut fake_window = app_state.windows.get_mut(window_id).ok_or(WindowIndexError)?;
+        update_display_list(
+            &mut app_state.data,
+            &ui_description_cache[window_id],
+            &ui_state_cache[window_id],
+            &mut *window,
+            &mut fake_window,
+            &mut app_state.resources,
+        )
+    };
+
+    window.update_cursor_confinement_rect(&layout_result.rects);
+
+    let mut has_started_transitions = false;
+
+    if app_state.layout_transitions.is_enabled() {
+        let dom = &ui_state_cache[window_id].dom;
+        let keyed_rects = KeyedRects::new(&dom.arena.node_layout, &dom.arena.node_data, &layout_result.rects);
+        let changes = window.last_keyed_rects.diff(&keyed_rects, &dom.arena.node_data, &matched_nodes);
+        window.last_keyed_rects = keyed_rects;
+        has_started_transitions = app_state.layout_transitions.start(changes, &mut app_state.resources);
+    }
+
+    for hook in after_layout_hooks {
+        hook(app_state, window_id, &layout_result);
+    }
+
+    // The overlay is only absolutely positioned, so it doesn't change the layout of the
+    // audited nodes - the audit converges after one additional frame
+    let mut has_new_keyboard_audit = false;
+
+    if debug_state.layout_debug {
+        if let Err(e) = layout_debug::write_layout_debug(&window.state.title, &ui_description_cache[window_id], &layout_result) {
+            #[cfg(feature = "logging")] {
+                warn!("Could not write {}: {}", layout_debug::LAYOUT_DEBUG_FILE, e);
+            }
+        }
+    }
+
+    if debug_state.keyboard_audit {
+        let audit = KeyboardAudit::new(&ui_state_cache[window_id], &layout_result.rects);
+        if window.keyboard_audit.as_ref() != Some(&audit) {
+            #[cfg(feature = "logging")] {
+                let dom = &ui_state_cache[window_id].dom;
+                let previous = window.keyboard_audit.as_ref().map(|a| &a.unreachable[..]).unwrap_or(&[]);
+                for (node_id, _) in audit.unreachable.iter().filter(|unreachable| !previous.contains(unreachable)) {
+                    warn!("Keyboard audit: {} reacts to mouse clicks, but can't be focused with the keyboard (no TabIndex)", dom.arena.node_data[*node_id]);
+                }
+            }
+            window.keyboard_audit = Some(audit);
+            has_new_keyboard_audit = true;
+        }
+    }
+
+    window.last_layout_result = Some(layout_result);
+
+    // The display list was built before the transitions were known - rebuild it, so that
+    // the moved nodes are drawn at their old position in the first frame of the transition
+    if has_started_transitions {
+        repaint_single_window(window_id, window, app_state, ui_state_cache, ui_description_cache)?;
+    }
+
+    // Lay out the window once more, so that the overlay shows the new audit
+    *awakened_tasks.get_mut(window_id).ok_or(WindowIndexError)? = has_new_keyboard_audit;
 
     if let Some(i) = force_redraw_cache.get_mut(window_id) {
         if *i > 0 { *i -= 1 };
@@ -824,11 +1503,21 @@ fn rerender_single_window<T>(
     config: &AppConfig,
     window: &mut Window<T>,
     resources: &mut AppResources,
-) {
-    render_inner(window, resources, Transaction::new(), config.background_color);
+) -> Result<(), ContextLost> {
+    // With a client-side window shadow, the background is drawn in the display list
+    let background_color = if window.state.size.shadow_margin > 0.0 {
+        ColorU::TRANSPARENT
+    } else {
+        window.state.background_color.unwrap_or(config.background_color)
+    };
+    render_inner(window, resources, Transaction::new(), background_color)
 }
 
-/// Returns if there was an error with the CSS reloading, necessary so that the error message is only printed once
+/// Reloads the style of the windows whose style source has changed since the last reload.
+///
+/// If the new style can't be parsed, the window keeps its old style and the error is
+/// displayed on top of the window (see `css_reload_error_dom`) until the source is fixed.
+/// `should_print_error` is used so that the error message is only printed once.
 #[cfg(debug_assertions)]
 fn hot_reload_css<T>(
     windows: &mut BTreeMap<GliumWindowId, Window<T>>,
@@ -852,14 +1541,21 @@ fn hot_reload_css<T>(
             continue;
         }
 
+        *last_style_reload = Instant::now();
+
+        // Only re-parse (and re-layout) if the source was actually modified
+        if !hot_reloader.has_changed() {
+            continue;
+        }
+
         match hot_reloader.reload_style() {
             Ok(mut new_css) => {
                 new_css.sort_by_specificity();
                 window.css = new_css;
+                window.css_reload_error = None;
                 if !(*should_print_error) {
                     println!("--- OK: CSS parsed without errors, continuing hot-reload.");
                 }
-                *last_style_reload = Instant::now();
                 // window.events_loop.create_proxy().wakeup().unwrap_or(());
                 *awakened_tasks.get_mut(window_id).ok_or(WindowIndexError)? = true;
 
@@ -870,6 +1566,15 @@ fn hot_reload_css<T>(
                     println!("{}", why);
                 }
                 *should_print_error = false;
+
+                if window.css_reload_error.as_ref() != Some(&why) {
+                    // The old style is kept, only the style of the error message is added
+                    if window.css_reload_error.is_none() {
+                        window.css.append(css_reload_error_style());
+                    }
+                    window.css_reload_error = Some(why);
+                    *awakened_tasks.get_mut(window_id).ok_or(WindowIndexError)? = true;
+                }
             },
         };
     }
@@ -877,6 +1582,51 @@ fn hot_reload_css<T>(
     Ok(())
 }
 
+/// Class of the node that displays the error of a failed CSS hot-reload
+#[cfg(debug_assertions)]
+const CSS_RELOAD_ERROR_CLASS: &str = "__azul-css-reload-error";
+
+/// Returns the node that displays the CSS hot-reload `error`, appended to the root of the DOM
+#[cfg(debug_assertions)]
+fn css_reload_error_dom<T>(error: &str) -> Dom<T> {
+    Dom::label(error.to_string()).with_class(CSS_RELOAD_ERROR_CLASS)
+}
+
+/// Style of the `css_reload_error_dom`: a red box in the top left corner of the window.
+/// Built by hand, since it has to work without the CSS parser (and while the user style is broken).
+/// The rule is `!important`, so that a global rule of the user style can't hide the error.
+#[cfg(debug_assertions)]
+fn css_reload_error_style() -> Css {
+
+    use azul_css::{
+        Stylesheet, CssRuleBlock, CssPath, CssPathSelector, CssDeclaration, CssProperty,
+        LayoutPosition, LayoutTop, LayoutLeft, LayoutPadding, StyleBackground, StyleTextColor,
+        StyleFontSize, StyleFontFamily, FontId, StyleTextAlignmentHorz, PixelValue,
+    };
+
+    let declarations = vec![
+        CssProperty::Position(LayoutPosition::Absolute),
+        CssProperty::Top(LayoutTop::px(0.0)),
+        CssProperty::Left(LayoutLeft::px(0.0)),
+        CssProperty::Padding(LayoutPadding::all(PixelValue::px(10.0))),
+        CssProperty::Background(StyleBackground::Color(ColorU { r: 180, g: 30, b: 30, a: 230 })),
+        CssProperty::TextColor(StyleTextColor(ColorU { r: 255, g: 255, b: 255, a: 255 })),
+        CssProperty::FontSize(StyleFontSize::px(14.0)),
+        CssProperty::FontFamily(StyleFontFamily { fonts: vec![FontId("monospace".into())] }),
+        CssProperty::TextAlign(StyleTextAlignmentHorz::Left),
+    ];
+
+    Css {
+        stylesheets: vec![Stylesheet::from(vec![CssRuleBlock {
+            path: CssPath { selectors: vec![CssPathSelector::Class(CSS_RELOAD_ERROR_CLASS.into())] },
+            declarations: declarations.into_iter().map(CssDeclaration::Static).collect(),
+            variables: Vec::new(),
+            important: true,
+        }])],
+        font_faces: Vec::new(),
+    }
+}
+
 /// Returns the currently hit-tested results, in back-to-front order
 #[cfg(not(test))]
 fn do_hit_test<T>(window: &Window<T>, app_resources: &AppResources) -> Option<HitTestResult> {
@@ -923,6 +1673,7 @@ fn call_callbacks<T>(
     window: &mut Window<T>,
     window_id: &GliumWindowId,
     ui_state: &UiState<T>,
+    ui_description: &UiDescription<T>,
     app_state: &mut AppState<T>)
 -> Result<CallCallbackReturn, RuntimeError<T>> {
 
@@ -932,6 +1683,8 @@ fn call_callbacks<T>(
         self::RuntimeError::*,
     };
 
+    profile_scope!("callbacks");
+
     let mut should_update_screen = DontRedraw;
 
     let hit_test_items = hit_test_results.map(|h| h.items.clone()).unwrap_or_default();
@@ -948,6 +1701,7 @@ fn call_callbacks<T>(
 
     let mut default_timers = FastHashMap::default();
     let mut default_tasks = Vec::new();
+    let mut default_timelines = FastHashMap::default();
 
     // Run all default callbacks - **before** the user-defined callbacks are run!
     {
@@ -962,7 +1716,10 @@ fn call_callbacks<T>(
                     window_id,
                     hit_dom_node: *node_id,
                     ui_state,
+                    ui_description,
                     hit_test_items: &hit_test_items,
+                    layout_rects: window.last_layout_result.as_ref().map(|layout_result| &layout_result.rects),
+                    window_size: &window.state.size,
                     cursor_relative_to_item: hit_item.as_ref().map(|hi| (hi.point_relative_to_item.x, hi.point_relative_to_item.y)),
                     cursor_in_viewport: hit_item.as_ref().map(|hi| (hi.point_in_viewport.x, hi.point_in_viewport.y)),
                 };
@@ -972,6 +1729,7 @@ fn call_callbacks<T>(
                     resources: &mut app_state.resources,
                     timers: FastHashMap::default(),
                     tasks: Vec::new(),
+                    timelines: FastHashMap::default(),
                 };
 
                 if app_state.windows[window_id].default_callbacks.run_callback(
@@ -985,6 +1743,7 @@ fn call_callbacks<T>(
 
                 default_timers.extend(app_state_no_data.timers.into_iter());
                 default_tasks.extend(app_state_no_data.tasks.into_iter());
+                default_timelines.extend(app_state_no_data.timelines.into_iter());
 
                 // Overwrite the focus from the callback info
                 if let Some(new_focus) = callback_info.focus {
@@ -994,15 +1753,19 @@ fn call_callbacks<T>(
         }
     }
 
-    // If the default callbacks have started timers or tasks, add them to the main app state
+    // If the default callbacks have started timers, tasks or timelines, add them to the main app state
     for (timer_id, timer) in default_timers {
-        app_state.add_timer(timer_id, timer);
+        async::insert_timer(&mut app_state.timers, timer_id, timer);
     }
 
     for task in default_tasks {
         app_state.add_task(task);
     }
 
+    for (timeline_id, timeline) in default_timelines {
+        app_state.add_timeline(timeline_id, timeline);
+    }
+
     for (node_id, callback_results) in callbacks_filter_list.nodes_with_callbacks.iter() {
         let hit_item = &callback_results.hit_test_item;
         for callback in callback_results.normal_callbacks.values() {
@@ -1012,7 +1775,10 @@ fn call_callbacks<T>(
                 window_id,
                 hit_dom_node: *node_id,
                 ui_state: &ui_state,
+                ui_description,
                 hit_test_items: &hit_test_items,
+                layout_rects: window.last_layout_result.as_ref().map(|layout_result| &layout_result.rects),
+                window_size: &window.state.size,
                 cursor_relative_to_item: hit_item.as_ref().map(|hi| (hi.point_relative_to_item.x, hi.point_relative_to_item.y)),
                 cursor_in_viewport: hit_item.as_ref().map(|hi| (hi.point_in_viewport.x, hi.point_in_viewport.y)),
             };
@@ -1049,20 +1815,81 @@ fn update_display_list<T>(
     window: &mut Window<T>,
     fake_window: &mut FakeWindow<T>,
     app_resources: &mut AppResources,
-) {
+) -> LayoutResult {
     use display_list::DisplayList;
 
     let display_list = DisplayList::new_from_ui_description(ui_description, ui_state);
 
     // NOTE: layout_result contains all words, text information, etc.
     // - very important for selection!
-    let (builder, scrolled_nodes, _layout_result) = display_list.into_display_list_builder(
+    let (builder, scrolled_nodes, layout_result) = display_list.into_display_list_builder(
         app_data,
         window,
         fake_window,
         app_resources,
     );
 
+    send_display_list(builder, scrolled_nodes, window, app_resources);
+
+    window.update_min_dimensions_from_layout(layout_result.min_content_size);
+
+    layout_result
+}
+
+/// Rebuilds the display list from the last layout of the window, without restyling
+/// or re-layouting the DOM - used if only the paint property overrides changed
+#[cfg(not(test))]
+fn repaint_single_window<T>(
+    window_id: &GliumWindowId,
+    window: &mut Window<T>,
+    app_state: &mut AppState<T>,
+    ui_state_cache: &BTreeMap<GliumWindowId, UiState<T>>,
+    ui_description_cache: &BTreeMap<GliumWindowId, UiDescription<T>>,
+) -> Result<(), RuntimeError<T>> {
+
+    use display_list::DisplayList;
+    use self::RuntimeError::*;
+
+    let layout_result = match window.last_layout_result.take() {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+
+    let display_list = DisplayList::new_from_ui_description(
+        ui_description_cache.get(window_id).ok_or(WindowIndexError)?,
+        ui_state_cache.get(window_id).ok_or(WindowIndexError)?,
+    );
+
+    // Images and fonts have to be marked as used, otherwise they'd be garbage-collected
+    app_state.resources.add_fonts_and_images(&display_list, window.state.size.hidpi_factor as f32);
+
+    let (builder, scrolled_nodes) = {
+        let fake_window = app_state.windows.get_mut(window_id).ok_or(WindowIndexError)?;
+        display_list.build_display_list_builder(
+            &mut app_state.data,
+            window,
+            fake_window,
+            &mut app_state.resources,
+            &layout_result,
+        )
+    };
+
+    send_display_list(builder, scrolled_nodes, window, &mut app_state.resources);
+    window.last_layout_result = Some(layout_result);
+
+    Ok(())
+}
+
+/// Finalizes the display list and sends it to webrender
+#[cfg(not(test))]
+fn send_display_list<T>(
+    builder: DisplayListBuilder,
+    scrolled_nodes: ScrolledNodes,
+    window: &mut Window<T>,
+    app_resources: &mut AppResources,
+) {
+    profile_scope!("send_display_list");
+
     // NOTE: Display list has to be rebuilt every frame, otherwise, the epochs get out of sync
     let display_list_builder = builder.finalize().2;
     window.internal.last_scrolled_nodes = scrolled_nodes;
@@ -1084,16 +1911,29 @@ fn update_display_list<T>(
 /// Scroll all nodes in the ScrollStates to their correct position and insert
 /// the positions into the transaction
 ///
-/// NOTE: scroll_states has to be mutable, since every key has a "visited" field, to
-/// indicate whether it was used during the current frame or not.
-fn scroll_all_nodes(scroll_states: &mut ScrollStates, txn: &mut Transaction) {
+/// NOTE: This doesn't mark the scroll states as used - only the scroll frames
+/// in the display list do that, otherwise the states would never be removed.
+fn scroll_all_nodes(scroll_states: &ScrollStates, txn: &mut Transaction) {
     use webrender::api::ScrollClamping;
-    for (key, value) in scroll_states.0.iter_mut() {
-        let (x, y) = value.get();
+    for (key, value) in scroll_states.0.iter() {
+        let (x, y) = value.scroll_amount();
         txn.scroll_node_with_id(LayoutPoint::new(x, y), *key, ScrollClamping::ToContentBounds);
     }
 }
 
+/// Moves the thumbs of all scrollbars to the current scroll positions,
+/// so that the display list doesn't have to be rebuilt while scrolling
+fn update_scrollbar_thumbs(scroll_states: &ScrollStates, txn: &mut Transaction) {
+    use webrender::api::DynamicProperties;
+    let mut transforms = Vec::with_capacity(scroll_states.0.len() * 2);
+    for (scroll_id, state) in scroll_states.0.iter() {
+        let (x, y) = state.get_thumb_offset();
+        transforms.push(get_scrollbar_thumb_binding(scroll_id, false).with(LayoutTransform::create_translation(x, 0.0, 0.0)));
+        transforms.push(get_scrollbar_thumb_binding(scroll_id, true).with(LayoutTransform::create_translation(0.0, y, 0.0)));
+    }
+    txn.update_dynamic_properties(DynamicProperties { transforms, floats: Vec::new() });
+}
+
 /// Returns the (logical_size, physical_size) as LayoutSizes, which can then be passed to webrender
 fn convert_window_size(size: &WindowSize) -> (LayoutSize, DeviceIntSize) {
     let logical_size = LayoutSize::new(
@@ -1105,6 +1945,17 @@ fn convert_window_size(size: &WindowSize) -> (LayoutSize, DeviceIntSize) {
     (logical_size, physical_size)
 }
 
+/// Returns the size (in device pixels) that the frame is rendered at - the `framebuffer_size`
+/// scaled by the ratio between the rendering and the actual DPI factor of the window
+#[cfg(not(test))]
+fn get_render_size(size: &WindowSize, framebuffer_size: DeviceIntSize) -> DeviceIntSize {
+    let scale = size.get_render_hidpi_factor() / size.hidpi_factor;
+    DeviceIntSize::new(
+        (framebuffer_size.width as f64 * scale).round() as i32,
+        (framebuffer_size.height as f64 * scale).round() as i32,
+    )
+}
+
 /// Special rendering function that skips building a layout and only does
 /// hit-testing and rendering - called on pure scroll events, since it's
 /// significantly less CPU-intensive to just render the last display list instead of
@@ -1139,6 +1990,9 @@ fn update_scroll_state<T>(
         if scroll_states.0.contains_key(&scroll_id) {
             // TODO: make scroll speed configurable (system setting?)
             scroll_states.scroll_node(&scroll_id, scroll_x as f32, scroll_y as f32);
+            if scroll_node.snap.is_some() {
+                scroll_states.defer_snap(&scroll_id);
+            }
             should_scroll_render = true;
         }
     }
@@ -1146,6 +2000,36 @@ fn update_scroll_state<T>(
     should_scroll_render
 }
 
+/// Snaps the nodes with a `scroll-snap-type` once the user stopped scrolling them
+/// and animates their scroll amount towards the snap position
+fn update_scroll_snap<T>(window: &mut Window<T>) -> bool {
+
+    let scrolled_nodes = &window.internal.last_scrolled_nodes;
+    let scroll_states = &mut window.scroll_states;
+
+    let mut should_scroll_render = false;
+
+    for scroll_node in scrolled_nodes.overflowing_nodes.values() {
+
+        let snap = match &scroll_node.snap {
+            Some(s) => s,
+            None => continue,
+        };
+
+        if let Some(scroll_state) = scroll_states.0.get_mut(&scroll_node.parent_external_scroll_id) {
+            if scroll_state.take_finished_scroll(SCROLL_SNAP_DELAY) {
+                let target = snap.get_target(scroll_state.scroll_amount(), scroll_node.parent_rect.bounds.size);
+                scroll_state.set_snap_target(target);
+            }
+            if scroll_state.step_snap_animation() {
+                should_scroll_render = true;
+            }
+        }
+    }
+
+    should_scroll_render
+}
+
 fn clean_up_unused_opengl_textures(pipeline_info: PipelineInfo) {
 
     use compositor::ACTIVE_GL_TEXTURES;
@@ -1198,41 +2082,58 @@ fn increase_epoch(old: Epoch) -> Epoch {
 //
 // NOTE: For some reason, webrender allows rendering to a framebuffer with a
 // negative width / height, although that doesn't make sense
+//
+// Returns `Err(ContextLost)` if the OpenGL context was lost during rendering,
+// the caller has to re-create the renderer and the windows in that case.
 #[cfg(not(test))]
 fn render_inner<T>(
     window: &mut Window<T>,
     app_resources: &mut AppResources,
     mut txn: Transaction,
     background_color: ColorU,
-) {
+) -> Result<(), ContextLost> {
 
     use window::get_gl_context;
     use glium::glutin::ContextTrait;
+    use compositor::is_context_lost;
     use webrender::api::{DeviceIntRect, DeviceIntPoint};
     use azul_css::ColorF;
 
+    profile_scope!("render");
+
     let (_, framebuffer_size) = convert_window_size(&window.state.size);
 
     // Especially during minimization / maximization of a window, it can happen that the window
     // width or height is zero. In that case, no rendering is necessary (doing so would crash
     // the application, since glTexImage2D may never have a 0 as the width or height.
     if framebuffer_size.width == 0 || framebuffer_size.height == 0 {
-        return;
+        return Ok(());
     }
 
     window.internal.epoch = increase_epoch(window.internal.epoch);
 
+    // Size of the texture that WebRender renders into - larger than the
+    // framebuffer if the frame is downscaled (see `HidpiRoundingPolicy::IntegerScale`)
+    let render_size = get_render_size(&window.state.size, framebuffer_size);
+
     txn.set_window_parameters(
-        framebuffer_size.clone(),
-        DeviceIntRect::new(DeviceIntPoint::new(0, 0), framebuffer_size),
-        window.state.size.hidpi_factor as f32
+        render_size.clone(),
+        DeviceIntRect::new(DeviceIntPoint::new(0, 0), render_size),
+        window.state.size.get_render_hidpi_factor() as f32
     );
     txn.set_root_pipeline(window.internal.pipeline_id);
-    scroll_all_nodes(&mut window.scroll_states, &mut txn);
+    scroll_all_nodes(&window.scroll_states, &mut txn);
+    update_scrollbar_thumbs(&window.scroll_states, &mut txn);
     txn.generate_frame();
 
     app_resources.fake_display.render_api.send_transaction(window.internal.document_id, txn);
 
+    // Block until the scene of the display list is built, so that the
+    // first frame is more likely to already contain the content
+    if window.is_waiting_for_first_frame() {
+        app_resources.fake_display.render_api.flush_scene_builder();
+    }
+
     // Update WR texture cache
     app_resources.fake_display.renderer.as_mut().unwrap().update();
 
@@ -1251,7 +2152,11 @@ fn render_inner<T>(
         // The context **must** be made current before calling `.bind_framebuffer()`,
         // otherwise EGL will panic with EGL_BAD_MATCH. The current context has to be the
         // hidden_display context, otherwise this will segfault on Windows.
-        app_resources.fake_display.hidden_display.gl_window().make_current().unwrap();
+        check_make_current(app_resources.fake_display.hidden_display.gl_window().make_current())?;
+
+        if is_context_lost(&*gl_context) {
+            return Err(ContextLost);
+        }
 
         let mut current_program = [0_i32];
         gl_context.get_integer_v(gl::CURRENT_PROGRAM, &mut current_program);
@@ -1264,16 +2169,18 @@ fn render_inner<T>(
         let textures = gl_context.gen_textures(1);
 
         gl_context.bind_texture(gl::TEXTURE_2D, textures[0]);
-        gl_context.tex_image_2d(gl::TEXTURE_2D, 0, gl::RGB as i32, framebuffer_size.width, framebuffer_size.height, 0, gl::RGB, gl::UNSIGNED_BYTE, None);
+        gl_context.tex_image_2d(gl::TEXTURE_2D, 0, gl::RGB as i32, render_size.width, render_size.height, 0, gl::RGB, gl::UNSIGNED_BYTE, None);
 
-        gl_context.tex_parameter_i(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
-        gl_context.tex_parameter_i(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+        // Only use linear filtering if the texture has to be downscaled, otherwise it'd blur the frame
+        let texture_filter = if render_size == framebuffer_size { gl::NEAREST } else { gl::LINEAR };
+        gl_context.tex_parameter_i(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, texture_filter as i32);
+        gl_context.tex_parameter_i(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, texture_filter as i32);
         gl_context.tex_parameter_i(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
         gl_context.tex_parameter_i(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
 
         let depthbuffers = gl_context.gen_renderbuffers(1);
         gl_context.bind_renderbuffer(gl::RENDERBUFFER, depthbuffers[0]);
-        gl_context.renderbuffer_storage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT, framebuffer_size.width, framebuffer_size.height);
+        gl_context.renderbuffer_storage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT, render_size.width, render_size.height);
         gl_context.framebuffer_renderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, depthbuffers[0]);
 
         // Set "textures[0]" as the color attachement #0
@@ -1293,21 +2200,33 @@ fn render_inner<T>(
         gl_context.disable(gl::MULTISAMPLE);
         gl_context.disable(gl::POLYGON_SMOOTH);
 
-        app_resources.fake_display.renderer.as_mut().unwrap().render(framebuffer_size).unwrap();
+        if let Err(errors) = app_resources.fake_display.renderer.as_mut().unwrap().render(render_size) {
+            if is_context_lost(&*gl_context) {
+                return Err(ContextLost);
+            }
+            panic!("Rendering failed: {:?}", errors);
+        }
 
         gl_context.delete_framebuffers(&framebuffers);
         gl_context.delete_renderbuffers(&depthbuffers);
 
         // FBOs can't be shared between windows, but textures can.
         // In order to draw on the windows backbuffer, first make the window current, then draw to FB 0
-        window.display.gl_window().make_current().unwrap();
+        check_make_current(window.display.gl_window().make_current())?;
         draw_texture_to_screen(&*gl_context, textures[0], framebuffer_size);
-        window.display.swap_buffers().unwrap();
+        match window.display.swap_buffers() {
+            Ok(()) => { },
+            Err(SwapBuffersError::ContextLost) => return Err(ContextLost),
+            Err(e) => panic!("Could not swap buffers: {}", e),
+        }
 
-        app_resources.fake_display.hidden_display.gl_window().make_current().unwrap();
+        check_make_current(app_resources.fake_display.hidden_display.gl_window().make_current())?;
 
-        // Only delete the texture here...
-        gl_context.delete_textures(&textures);
+        // Keep the texture of this frame (so that it can be stretched over the window
+        // during a resize), only delete the texture of the frame before
+        if let Some(previous_frame_texture) = mem::replace(&mut window.last_frame_texture, Some(textures[0])) {
+            gl_context.delete_textures(&[previous_frame_texture]);
+        }
 
         gl_context.bind_framebuffer(gl::FRAMEBUFFER, 0);
         gl_context.bind_texture(gl::TEXTURE_2D, 0);
@@ -1317,11 +2236,113 @@ fn render_inner<T>(
 
     // The initial setup can lead to flickering during startup, by default
     // the window is hidden until the first frame has been rendered.
-    if window.create_options.state.is_visible && window.state.is_visible {
+    let is_first_frame_ready = !window.is_waiting_for_first_frame() ||
+        app_resources.fake_display.renderer.as_ref().unwrap().current_epoch(window.internal.pipeline_id).is_some() ||
+        window.created.elapsed() > MAX_FIRST_FRAME_WAIT;
+
+    if window.create_options.state.is_visible && window.state.is_visible && is_first_frame_ready {
         window.display.gl_window().window().show();
         window.state.is_visible = true;
         window.create_options.state.is_visible = false;
     }
+
+    Ok(())
+}
+
+#[cfg(not(test))]
+fn check_make_current(result: Result<(), ::glium::glutin::ContextError>) -> Result<(), ContextLost> {
+    use glium::glutin::ContextError;
+    match result {
+        Ok(()) => Ok(()),
+        Err(ContextError::ContextLost) => Err(ContextLost),
+        Err(e) => panic!("Could not make OpenGL context current: {}", e),
+    }
+}
+
+/// Draws the texture of the last rendered frame onto the window, stretched to the current
+/// window size, without re-running the layout or WebRender.
+#[cfg(not(test))]
+fn redraw_last_frame<T>(window: &Window<T>, app_resources: &AppResources) -> Result<(), ContextLost> {
+
+    use window::get_gl_context;
+    use glium::glutin::ContextTrait;
+
+    let last_frame_texture = match window.last_frame_texture {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+
+    let (_, framebuffer_size) = convert_window_size(&window.state.size);
+    if framebuffer_size.width == 0 || framebuffer_size.height == 0 {
+        return Ok(());
+    }
+
+    let gl_context = get_gl_context(&app_resources.fake_display.hidden_display).unwrap();
+
+    unsafe {
+        check_make_current(window.display.gl_window().make_current())?;
+
+        // The texture is scaled, so use linear filtering to avoid jagged edges
+        gl_context.bind_texture(gl::TEXTURE_2D, last_frame_texture);
+        gl_context.tex_parameter_i(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        gl_context.tex_parameter_i(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+
+        draw_texture_to_screen(&*gl_context, last_frame_texture, framebuffer_size);
+        match window.display.swap_buffers() {
+            Ok(()) => { },
+            Err(SwapBuffersError::ContextLost) => return Err(ContextLost),
+            Err(e) => panic!("Could not swap buffers: {}", e),
+        }
+
+        check_make_current(app_resources.fake_display.hidden_display.gl_window().make_current())?;
+    }
+
+    Ok(())
+}
+
+/// Reads the texture of the last rendered frame of the window back from the GPU
+#[cfg(not(test))]
+fn read_last_frame<T>(window: &Window<T>, app_resources: &AppResources) -> Option<Screenshot> {
+
+    use window::get_gl_context;
+    use glium::glutin::ContextTrait;
+
+    let last_frame_texture = window.last_frame_texture?;
+    let (_, framebuffer_size) = convert_window_size(&window.state.size);
+    let render_size = get_render_size(&window.state.size, framebuffer_size);
+    let gl_context = get_gl_context(&app_resources.fake_display.hidden_display).unwrap();
+
+    let pixels = unsafe {
+        check_make_current(app_resources.fake_display.hidden_display.gl_window().make_current()).ok()?;
+
+        let framebuffers = gl_context.gen_framebuffers(1);
+        gl_context.bind_framebuffer(gl::FRAMEBUFFER, framebuffers[0]);
+        gl_context.framebuffer_texture_2d(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, last_frame_texture, 0);
+        gl_context.read_buffer(gl::COLOR_ATTACHMENT0);
+
+        let pixels = gl_context.read_pixels(0, 0, render_size.width, render_size.height, gl::RGBA, gl::UNSIGNED_BYTE);
+
+        gl_context.bind_framebuffer(gl::FRAMEBUFFER, 0);
+        gl_context.delete_framebuffers(&framebuffers);
+        pixels
+    };
+
+    Some(Screenshot::from_gl_pixels(render_size.width as u32, render_size.height as u32, pixels))
+}
+
+/// Deletes the texture of the last frame of a window that is about to be closed
+#[cfg(not(test))]
+fn delete_last_frame_texture<T>(window: &Window<T>, app_resources: &AppResources) {
+
+    use window::get_gl_context;
+    use glium::glutin::ContextTrait;
+
+    if let Some(last_frame_texture) = window.last_frame_texture {
+        let gl_context = get_gl_context(&app_resources.fake_display.hidden_display).unwrap();
+        if unsafe { app_resources.fake_display.hidden_display.gl_window().make_current() }.is_ok() {
+            gl_context.delete_textures(&[last_frame_texture]);
+        }
+    }
 }
 
 /// When called with glDrawArrays(0, 3), generates a simple triangle that
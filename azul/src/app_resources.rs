@@ -1,14 +1,16 @@
 use std::{
-    fmt,
+    fmt, mem,
+    ops::RangeInclusive,
     path::PathBuf,
     io::Error as IoError,
     sync::atomic::{AtomicUsize, Ordering},
 };
 use webrender::api::{
-    FontKey, FontInstanceKey, ImageKey, AddImage,
+    FontKey, FontInstanceKey, ImageKey, AddImage, GlyphInstance,
     ResourceUpdate, AddFont, AddFontInstance, RenderApi,
 };
 use app_units::Au;
+use azul_css::{CssFontFace, ConicGradient, SystemColors, StyleFontSize};
 use clipboard2::{Clipboard, ClipboardError, SystemClipboard};
 use {
     FastHashMap, FastHashSet,
@@ -16,7 +18,10 @@ use {
     app::AppConfig,
     display_list::DisplayList,
     text_layout::Words,
+    rich_text::{RichText, RichTextClipboard},
 };
+#[cfg(feature = "net")]
+use net;
 pub use webrender::api::{ImageFormat as RawImageFormat, ImageData, ImageDescriptor};
 #[cfg(feature = "image_loading")]
 pub use image::{ImageError, DynamicImage, GenericImageView};
@@ -43,6 +48,9 @@ pub struct AppResources {
     css_ids_to_font_ids: FastHashMap<CssFontId, FontId>,
     /// Stores where the images were loaded from
     image_sources: FastHashMap<ImageId, ImageSource>,
+    /// The variants of the images that were added as `ImageSource::Variants`: each variant
+    /// is stored in `image_sources` under its own `ImageId`, together with its scale
+    image_variants: FastHashMap<ImageId, Vec<(u32, ImageId)>>,
     /// Stores where the fonts were loaded from
     font_sources: FastHashMap<FontId, FontSource>,
     /// All image keys currently active in the RenderApi
@@ -61,10 +69,45 @@ pub struct AppResources {
     /// the string of the file path where the font was loaded from, so no huge memory pressure).
     /// The reason for this agressive strategy is that the
     last_frame_font_keys: FastHashMap<ImmediateFontId, FastHashSet<Au>>,
+    /// Images that `conic-gradient()` backgrounds were rasterized into (since WebRender
+    /// can't draw conic gradients), keyed by the gradient and the size of the image
+    conic_gradient_images: FastHashMap<(ConicGradient, u32, u32), ImageId>,
+    /// Rasterized conic gradients that were displayed in the last frame, the images
+    /// of all other gradients are deleted during the garbage collection
+    last_frame_conic_gradients: FastHashSet<(ConicGradient, u32, u32)>,
     /// Stores long texts across frames
     text_cache: TextCache,
     /// Keyboard clipboard storage and retrieval functionality
     clipboard: SystemClipboard,
+    /// Clipboard for formatted text, created by the first `set_clipboard_rich_text` call
+    rich_text_clipboard: Option<RichTextClipboard>,
+    /// Paint property overrides, keyed by the ID of the DOM nodes they apply to
+    paint_property_overrides: FastHashMap<String, PaintPropertyOverride>,
+    /// Whether the paint property overrides changed since the last display list was built
+    paint_properties_changed: bool,
+    /// Colors selected in the settings of the OS, declared as built-in CSS variables
+    system_colors: SystemColors,
+    /// Glyphs that WebRender has rasterized (because they were drawn or preloaded), per font instance
+    rasterized_glyphs: FastHashMap<FontInstanceKey, FastHashSet<u32>>,
+    /// Characters whose glyphs are rasterized ahead of time, see `preload_glyphs`
+    glyph_preloads: FastHashMap<(FontId, Au), Vec<RangeInclusive<char>>>,
+    /// Glyph cache statistics of the frame that is currently being drawn
+    current_glyph_cache_stats: GlyphCacheStats,
+    /// Glyph cache statistics of the last frame, see `get_glyph_cache_stats`
+    glyph_cache_stats: GlyphCacheStats,
+}
+
+/// How many glyphs a frame drew and how many of them had to be rasterized
+/// during the frame, see `AppResources::get_glyph_cache_stats`
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GlyphCacheStats {
+    /// Number of glyphs that were drawn
+    pub glyphs_drawn: usize,
+    /// Glyphs that were drawn for the first time (in their font and size), so that
+    /// WebRender had to rasterize them before the frame could be shown
+    pub cache_misses: usize,
+    /// Glyphs that were rasterized ahead of time, see `AppResources::preload_glyphs`
+    pub glyphs_preloaded: usize,
 }
 
 static TEXT_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
@@ -121,6 +164,30 @@ pub enum ImageSource {
     Raw(RawImage),
     /// The image is loaded from a file
     File(PathBuf),
+    /// The image is downloaded from an URL (see `net::load_image`),
+    /// it is drawn once the download has finished
+    #[cfg(feature = "net")]
+    Url(String),
+    /// The same image, exported for different display densities (i.e. `icon.png` and
+    /// `icon@2x.png`). When the image is drawn, the variant that best matches the HiDPI
+    /// factor of the window is used, the other variants are never loaded.
+    Variants(Vec<ImageVariant>),
+}
+
+/// One resolution of an image that is added as `ImageSource::Variants`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageVariant {
+    /// Display density that the image was exported for, i.e. `2` for an "@2x" image.
+    /// The image is drawn at `1 / scale` of its size in pixels.
+    pub scale: u32,
+    /// Where the image of this resolution is loaded from
+    pub source: ImageSource,
+}
+
+impl ImageVariant {
+    pub fn new(scale: u32, source: ImageSource) -> Self {
+        Self { scale, source }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -131,6 +198,10 @@ pub enum FontSource {
     File(PathBuf),
     /// The font is a system built-in font
     System(String),
+    /// The font is downloaded from an URL (see `net::load_font`),
+    /// it is used once the download has finished
+    #[cfg(feature = "net")]
+    Url(String),
 }
 
 #[derive(Debug)]
@@ -140,6 +211,11 @@ pub enum ImageReloadError {
     DecodingError(ImageError),
     #[cfg(not(feature = "image_loading"))]
     DecodingModuleNotActive,
+    /// The image from this URL hasn't been downloaded yet
+    #[cfg(feature = "net")]
+    NotDownloaded(String),
+    /// The image is an `ImageSource::Variants` without any variants
+    NoVariants,
 }
 
 impl Clone for ImageReloadError {
@@ -151,6 +227,9 @@ impl Clone for ImageReloadError {
             DecodingError(e) => DecodingError(e.clone()),
             #[cfg(not(feature = "image_loading"))]
             DecodingModuleNotActive => DecodingModuleNotActive,
+            #[cfg(feature = "net")]
+            NotDownloaded(url) => NotDownloaded(url.clone()),
+            NoVariants => NoVariants,
         }
     }
 }
@@ -164,6 +243,9 @@ impl fmt::Display for ImageReloadError {
             DecodingError(err) => write!(f, "Image decoding error: \"{}\"", err),
             #[cfg(not(feature = "image_loading"))]
             DecodingModuleNotActive => write!(f, "Found decoded image, but crate was not compiled with --features=\"image_loading\""),
+            #[cfg(feature = "net")]
+            NotDownloaded(url) => write!(f, "Image \"{}\" has not been downloaded yet", url),
+            NoVariants => write!(f, "Image has no resolution variants"),
         }
     }
 }
@@ -172,6 +254,9 @@ impl fmt::Display for ImageReloadError {
 pub enum FontReloadError {
     Io(IoError, PathBuf),
     FontNotFound(String),
+    /// The font from this URL hasn't been downloaded yet
+    #[cfg(feature = "net")]
+    NotDownloaded(String),
 }
 
 impl Clone for FontReloadError {
@@ -180,14 +265,23 @@ impl Clone for FontReloadError {
         match self {
             Io(err, path) => Io(IoError::new(err.kind(), "Io Error"), path.clone()),
             FontNotFound(id) => FontNotFound(id.clone()),
+            #[cfg(feature = "net")]
+            NotDownloaded(url) => NotDownloaded(url.clone()),
         }
     }
 }
 
-impl_display!(FontReloadError, {
-    Io(err, path_buf) => format!("Could not load \"{}\" - IO error: {}", path_buf.as_path().to_string_lossy(), err),
-    FontNotFound(id) => format!("Could not locate system font: \"{}\" found", id),
-});
+impl fmt::Display for FontReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::FontReloadError::*;
+        match &self {
+            Io(err, path_buf) => write!(f, "Could not load \"{}\" - IO error: {}", path_buf.as_path().to_string_lossy(), err),
+            FontNotFound(id) => write!(f, "Could not locate system font: \"{}\" found", id),
+            #[cfg(feature = "net")]
+            NotDownloaded(url) => write!(f, "Font \"{}\" has not been downloaded yet", url),
+        }
+    }
+}
 
 impl ImageSource {
 
@@ -230,6 +324,24 @@ impl ImageSource {
                     Err(ImageReloadError::DecodingModuleNotActive)
                 }
             },
+            #[cfg(feature = "net")]
+            Url(url) => {
+                #[cfg(feature = "image_loading")] {
+                    use std::fs;
+                    let file_path = net::cached_path(url).ok_or_else(|| ImageReloadError::NotDownloaded(url.clone()))?;
+                    let bytes = fs::read(&file_path).map_err(|e| ImageReloadError::Io(e, file_path.clone()))?;
+                    decode_image_data(bytes).map_err(|e| ImageReloadError::DecodingError(e))
+                }
+                #[cfg(not(feature = "image_loading"))] {
+                    Err(ImageReloadError::DecodingModuleNotActive)
+                }
+            },
+            // Only used if the bytes of the image are requested directly, the display
+            // list loads the variant for the HiDPI factor of the window instead
+            Variants(variants) => {
+                let largest_variant = variants.iter().max_by_key(|variant| variant.scale).ok_or(ImageReloadError::NoVariants)?;
+                largest_variant.source.get_bytes()
+            },
         }
     }
 }
@@ -249,6 +361,13 @@ impl FontSource {
                 .map(|f| (f, 0))
             },
             System(id) => load_system_font(id).ok_or(FontReloadError::FontNotFound(id.clone())),
+            #[cfg(feature = "net")]
+            Url(url) => {
+                let file_path = net::cached_path(url).ok_or_else(|| FontReloadError::NotDownloaded(url.clone()))?;
+                fs::read(&file_path)
+                .map_err(|e| FontReloadError::Io(e, file_path.clone()))
+                .map(|f| (f, 0))
+            },
         }
     }
 }
@@ -330,6 +449,61 @@ impl TextCache {
     }
 }
 
+/// Opacity, transform and clip of a node (and its children), which are applied when
+/// the display list is built. Changing them doesn't restyle or re-layout the DOM,
+/// so they can be animated cheaply, see `AppResources::set_paint_property_override()`.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct PaintPropertyOverride {
+    /// Opacity from 0.0 (invisible) to 1.0 (opaque). The node and its children are composited
+    /// as one group, so overlapping children don't shine through each other.
+    pub opacity: Option<f32>,
+    /// Transform of the node, applied around the center of the node
+    pub transform: Option<PaintTransform>,
+    /// Clips the node to a rectangle, relative to the top left corner of the node
+    pub clip: Option<PaintClip>,
+}
+
+/// 2D transform of a `PaintPropertyOverride`, the node is first scaled,
+/// then rotated and then translated
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PaintTransform {
+    /// Translation in pixels
+    pub translate: (f32, f32),
+    /// Scale factor, `(1.0, 1.0)` leaves the size of the node unchanged
+    pub scale: (f32, f32),
+    /// Clockwise rotation in degrees
+    pub rotate: f32,
+}
+
+impl Default for PaintTransform {
+    fn default() -> Self {
+        Self { translate: (0.0, 0.0), scale: (1.0, 1.0), rotate: 0.0 }
+    }
+}
+
+impl PaintTransform {
+    pub fn translate(x: f32, y: f32) -> Self {
+        Self { translate: (x, y), .. Default::default() }
+    }
+
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self { scale: (x, y), .. Default::default() }
+    }
+
+    pub fn rotate(degrees: f32) -> Self {
+        Self { rotate: degrees, .. Default::default() }
+    }
+}
+
+/// Clip rectangle of a `PaintPropertyOverride`, in pixels
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct PaintClip {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
 /// Used only for debugging, so that the AppResource garbage
 /// collection tests can run without a real RenderApi
 #[cfg(test)]
@@ -381,15 +555,37 @@ impl AppResources {
             css_ids_to_image_ids: FastHashMap::default(),
             font_sources: FastHashMap::default(),
             image_sources: FastHashMap::default(),
+            image_variants: FastHashMap::default(),
             currently_registered_fonts: FastHashMap::default(),
             currently_registered_images: FastHashMap::default(),
             last_frame_font_keys: FastHashMap::default(),
             last_frame_image_keys: FastHashSet::default(),
+            conic_gradient_images: FastHashMap::default(),
+            last_frame_conic_gradients: FastHashSet::default(),
             text_cache: TextCache::default(),
             clipboard: SystemClipboard::new().unwrap(),
+            rich_text_clipboard: None,
+            paint_property_overrides: FastHashMap::default(),
+            paint_properties_changed: false,
+            // Loaded by the `SystemColorsWatcher` of the main loop, off the startup path
+            system_colors: SystemColors::default(),
+            rasterized_glyphs: FastHashMap::default(),
+            glyph_preloads: FastHashMap::default(),
+            current_glyph_cache_stats: GlyphCacheStats::default(),
+            glyph_cache_stats: GlyphCacheStats::default(),
         })
     }
 
+    /// Forgets all fonts and images that are registered in the RenderApi, so that they
+    /// get re-uploaded on the next layout - necessary after the renderer was re-created
+    pub(crate) fn clear_registered_fonts_and_images(&mut self) {
+        self.currently_registered_fonts.clear();
+        self.currently_registered_images.clear();
+        self.last_frame_font_keys.clear();
+        self.last_frame_image_keys.clear();
+        self.rasterized_glyphs.clear();
+    }
+
     pub(crate) fn get_render_api(&self) -> &impl FontImageApi {
         #[cfg(not(test))] {
             &self.fake_display.render_api
@@ -426,6 +622,16 @@ impl AppResources {
     /// you have to enable them as features in the Cargo.toml file.
     #[cfg(feature = "image_loading")]
     pub fn add_image(&mut self, image_id: ImageId, image_source: ImageSource) {
+        self.delete_image_variants(&image_id);
+        if let ImageSource::Variants(variants) = &image_source {
+            let mut variant_ids = variants.iter().map(|variant| {
+                let variant_id = ImageId::new();
+                self.image_sources.insert(variant_id, variant.source.clone());
+                (variant.scale, variant_id)
+            }).collect::<Vec<_>>();
+            variant_ids.sort_by_key(|(scale, _)| *scale);
+            self.image_variants.insert(image_id, variant_ids);
+        }
         self.image_sources.insert(image_id, image_source);
     }
 
@@ -441,9 +647,43 @@ impl AppResources {
     }
 
     pub fn delete_image(&mut self, image_id: &ImageId) {
+        self.delete_image_variants(image_id);
         self.image_sources.remove(image_id);
     }
 
+    fn delete_image_variants(&mut self, image_id: &ImageId) {
+        for (_, variant_id) in self.image_variants.remove(image_id).unwrap_or_default() {
+            self.image_sources.remove(&variant_id);
+        }
+    }
+
+    /// Returns the image that is drawn for the `image_id` in a window with the given HiDPI
+    /// factor and the scale of that image: the smallest variant that is at least as dense as
+    /// the display, or the largest variant if the display is denser than all variants.
+    /// Images without variants are returned unchanged (with a scale of 1).
+    pub(crate) fn get_image_variant(&self, image_id: &ImageId, hidpi_factor: f32) -> (ImageId, f32) {
+        let variants = match self.image_variants.get(image_id) {
+            Some(variants) => variants,
+            None => return (*image_id, 1.0),
+        };
+        // Tolerate rounding errors of the HiDPI factor, i.e. 2.0000002
+        const EPSILON: f32 = 0.01;
+        variants.iter()
+            .find(|(scale, _)| *scale as f32 >= hidpi_factor - EPSILON)
+            .or_else(|| variants.last())
+            .map(|(scale, variant_id)| (*variant_id, (*scale).max(1) as f32))
+            .unwrap_or((*image_id, 1.0))
+    }
+
+    /// Returns the size of the image in logical pixels: the size of the variant that is drawn
+    /// in a window with the given HiDPI factor (see `get_image_variant`), divided by its scale.
+    /// Returns `None` if the image isn't loaded yet.
+    pub(crate) fn get_image_size(&self, image_id: &ImageId, hidpi_factor: f32) -> Option<(f32, f32)> {
+        let (variant_id, scale) = self.get_image_variant(image_id, hidpi_factor);
+        let size = self.get_image_info(&variant_id)?.descriptor.size;
+        Some((size.width as f32 / scale, size.height as f32 / scale))
+    }
+
     pub fn add_css_image_id<S: Into<String>>(&mut self, css_id: S) -> ImageId {
         *self.css_ids_to_image_ids.entry(css_id.into()).or_insert_with(|| ImageId::new())
     }
@@ -501,6 +741,25 @@ impl AppResources {
         self.font_sources.remove(id);
     }
 
+    /// Registers the fonts of `@font-face` rules under their `font-family` name. If the source
+    /// of an already registered font changed (i.e. after the CSS was hot-reloaded), the font
+    /// gets a new `FontId`, so that the old font is removed by the font GC once it isn't used anymore.
+    pub(crate) fn add_css_font_faces(&mut self, font_faces: &[CssFontFace]) {
+        for font_face in font_faces {
+            let font_source = FontSource::File(font_face.source.clone());
+            let mut font_id = self.add_css_font_id(font_face.font_family.clone());
+            match self.font_sources.get(&font_id) {
+                Some(existing_source) if *existing_source == font_source => continue,
+                Some(_) => {
+                    font_id = FontId::new();
+                    self.css_ids_to_font_ids.insert(font_face.font_family.clone(), font_id);
+                },
+                None => { },
+            }
+            self.add_font(font_id, font_source);
+        }
+    }
+
     // -- TextId cache
 
     /// Adds a string to the internal text cache, but only store it as a string,
@@ -535,15 +794,164 @@ impl AppResources {
         self.clipboard.set_string_contents(contents.into())
     }
 
+    /// Copies formatted text to the system clipboard: the plain text together with the HTML
+    /// and RTF versions of the text (see `RichText::to_html` / `RichText::to_rtf`), so that
+    /// the formatting is kept when the text is pasted into a word processor. If the formatted
+    /// versions can't be placed on the clipboard (i.e. on Wayland without an X server),
+    /// only the plain text is copied.
+    pub fn set_clipboard_rich_text(&mut self, contents: &RichText) -> Result<(), ClipboardError> {
+
+        let rich_text_clipboard = match self.rich_text_clipboard.take() {
+            Some(rich_text_clipboard) => Ok(rich_text_clipboard),
+            None => RichTextClipboard::new(),
+        };
+
+        let result = rich_text_clipboard.and_then(|mut rich_text_clipboard| {
+            let result = rich_text_clipboard.set_contents(contents);
+            self.rich_text_clipboard = Some(rich_text_clipboard);
+            result
+        });
+
+        result.or_else(|_| self.clipboard.set_string_contents(contents.to_plain_text()))
+    }
+
+    // -- Paint property overrides
+
+    /// Overrides the opacity, transform and clip of all nodes with the given ID
+    /// (see `Dom::with_id`). Only the display list is rebuilt in the next frame, the
+    /// DOM isn't restyled or re-layouted - so this can be called from a timer to
+    /// animate nodes without having to return `Redraw`.
+    pub fn set_paint_property_override<S: Into<String>>(&mut self, dom_id: S, paint_override: PaintPropertyOverride) {
+        let previous = self.paint_property_overrides.insert(dom_id.into(), paint_override);
+        if previous != Some(paint_override) {
+            self.paint_properties_changed = true;
+        }
+    }
+
+    pub fn get_paint_property_override(&self, dom_id: &str) -> Option<&PaintPropertyOverride> {
+        self.paint_property_overrides.get(dom_id)
+    }
+
+    /// Removes the paint property override of the nodes with the given ID
+    pub fn remove_paint_property_override(&mut self, dom_id: &str) -> Option<PaintPropertyOverride> {
+        let removed = self.paint_property_overrides.remove(dom_id);
+        if removed.is_some() {
+            self.paint_properties_changed = true;
+        }
+        removed
+    }
+
+    pub(crate) fn get_paint_property_overrides(&self) -> &FastHashMap<String, PaintPropertyOverride> {
+        &self.paint_property_overrides
+    }
+
+    /// Returns whether the paint property overrides changed since the last call
+    pub(crate) fn take_paint_property_changes(&mut self) -> bool {
+        mem::replace(&mut self.paint_properties_changed, false)
+    }
+
+    // -- System colors
+
+    /// Returns the colors that the user selected in the settings of the OS. They are loaded in the
+    /// background once the app runs (all colors are undefined until then), updated when the user
+    /// changes them and are also visible to the CSS as built-in variables
+    /// (i.e. `var(--azul-system-accent-color)`), see `SystemColors::get_variables`.
+    pub fn get_system_colors(&self) -> &SystemColors {
+        &self.system_colors
+    }
+
+    pub(crate) fn set_system_colors(&mut self, system_colors: SystemColors) {
+        self.system_colors = system_colors;
+    }
+
+    // -- Glyph cache
+
+    /// Rasterizes the glyphs of the characters in the `char_range` ahead of time, so that
+    /// an app that shows a large range of characters for the first time (i.e. an emoji picker
+    /// or CJK text) doesn't stutter while WebRender rasterizes them.
+    ///
+    /// Glyphs are rasterized per font size, so the range has to be preloaded for every size
+    /// that it is displayed at. The glyphs are rasterized while the next frame is drawn,
+    /// whether the font is used in the DOM or not.
+    pub fn preload_glyphs(&mut self, font_id: &FontId, font_size: StyleFontSize, char_range: RangeInclusive<char>) {
+        use ui_solver::font_size_to_au;
+        self.glyph_preloads.entry((*font_id, font_size_to_au(font_size))).or_insert_with(Vec::new).push(char_range);
+    }
+
+    /// Returns how many glyphs the last frame drew and how many of them weren't
+    /// rasterized yet. Cache misses cause a hitch on the frame where the glyphs are
+    /// shown for the first time, see `preload_glyphs`.
+    ///
+    /// Note that WebRender can evict glyphs that weren't drawn for a while, which isn't
+    /// counted as a cache miss when they are drawn again.
+    pub fn get_glyph_cache_stats(&self) -> GlyphCacheStats {
+        self.glyph_cache_stats
+    }
+
+    /// Counts the glyphs that are drawn in the current frame for the `GlyphCacheStats`
+    pub(crate) fn record_drawn_glyphs(&mut self, font_instance_key: FontInstanceKey, glyphs: &[GlyphInstance]) {
+        let rasterized_glyphs = self.rasterized_glyphs.entry(font_instance_key).or_insert_with(FastHashSet::default);
+        let cache_misses = glyphs.iter().filter(|glyph| rasterized_glyphs.insert(glyph.index)).count();
+        self.current_glyph_cache_stats.glyphs_drawn += glyphs.len();
+        self.current_glyph_cache_stats.cache_misses += cache_misses;
+    }
+
+    /// Returns the glyphs requested by `preload_glyphs` that aren't rasterized yet (per font
+    /// instance) and marks them as rasterized. The display list has to draw them, so that
+    /// WebRender actually rasterizes them. Must be called after `add_fonts_and_images`.
+    pub(crate) fn take_glyphs_to_preload(&mut self) -> Vec<(FontInstanceKey, Vec<u32>)> {
+
+        use text_shaping::get_glyph_indices_hb;
+
+        let mut glyphs_to_preload = Vec::new();
+
+        for ((font_id, font_size), char_ranges) in mem::replace(&mut self.glyph_preloads, FastHashMap::default()) {
+
+            let loaded_font = self.currently_registered_fonts.get(&ImmediateFontId::Resolved(font_id))
+                .and_then(|font| Some((font, *font.font_instances.get(&font_size)?)));
+            let (loaded_font, font_instance_key) = match loaded_font {
+                Some(s) => s,
+                None => {
+                    #[cfg(feature = "logging")] {
+                        warn!("Can't preload the glyphs of {:?}: the font isn't loaded", font_id);
+                    }
+                    continue;
+                },
+            };
+            let text = char_ranges.into_iter().flatten().collect::<String>();
+            let glyph_indices = get_glyph_indices_hb(
+                &loaded_font.font_bytes,
+                loaded_font.font_index as u32,
+                font_size.to_f32_px(),
+                &text,
+            );
+
+            let rasterized_glyphs = self.rasterized_glyphs.entry(font_instance_key).or_insert_with(FastHashSet::default);
+            let new_glyphs = glyph_indices.into_iter().filter(|index| rasterized_glyphs.insert(*index)).collect::<Vec<_>>();
+            if new_glyphs.is_empty() {
+                continue;
+            }
+
+            self.current_glyph_cache_stats.glyphs_preloaded += new_glyphs.len();
+            glyphs_to_preload.push((font_instance_key, new_glyphs));
+        }
+
+        glyphs_to_preload
+    }
+
     pub(crate) fn get_loaded_font(&self, font_id: &ImmediateFontId) -> Option<&LoadedFont> {
         self.currently_registered_fonts.get(font_id)
     }
 
     /// Scans the DisplayList for new images and fonts. After this call, the RenderApi is
     /// guaranteed to know about all FontKeys and FontInstanceKey
-    pub(crate) fn add_fonts_and_images<T>(&mut self, display_list: &DisplayList<T>) {
-        let font_keys = scan_ui_description_for_font_keys(&self, display_list);
-        let image_keys = scan_ui_description_for_image_keys(&self, display_list);
+    pub(crate) fn add_fonts_and_images<T>(&mut self, display_list: &DisplayList<T>, hidpi_factor: f32) {
+        let mut font_keys = scan_ui_description_for_font_keys(&self, display_list);
+        // Fonts that glyphs are preloaded for have to be loaded, even if the DOM doesn't use them
+        for (font_id, font_size) in self.glyph_preloads.keys() {
+            font_keys.entry(ImmediateFontId::Resolved(*font_id)).or_insert_with(FastHashSet::default).insert(*font_size);
+        }
+        let image_keys = scan_ui_description_for_image_keys(&self, display_list, hidpi_factor);
 
         self.last_frame_font_keys.extend(font_keys.clone().into_iter());
         self.last_frame_image_keys.extend(image_keys.clone().into_iter());
@@ -554,6 +962,35 @@ impl AppResources {
         add_resources(self, add_font_resource_updates, add_image_resource_updates);
     }
 
+    /// Returns the image that a `conic-gradient()` of the given size (in physical pixels) is drawn
+    /// with - the gradient is only rasterized again if it or its size changed.
+    pub(crate) fn get_conic_gradient_image(&mut self, gradient: &ConicGradient, width: u32, height: u32) -> ImageId {
+        let key = (gradient.clone(), width, height);
+        let image_id = match self.conic_gradient_images.get(&key) {
+            Some(image_id) => *image_id,
+            None => {
+                let image_id = ImageId::new();
+                self.add_image(image_id, ImageSource::Raw(rasterize_conic_gradient(gradient, width, height)));
+                self.conic_gradient_images.insert(key.clone(), image_id);
+                image_id
+            },
+        };
+        self.last_frame_conic_gradients.insert(key);
+        self.add_image_for_current_frame(image_id);
+        image_id
+    }
+
+    /// Same as `add_fonts_and_images`, but for a single image that isn't
+    /// referenced by the DOM, such as the background image of a window
+    pub(crate) fn add_image_for_current_frame(&mut self, image_id: ImageId) {
+        let mut image_keys = FastHashSet::default();
+        image_keys.insert(image_id);
+        self.last_frame_image_keys.insert(image_id);
+
+        let add_image_resource_updates = build_add_image_resource_updates(self, &image_keys);
+        add_resources(self, Vec::new(), add_image_resource_updates);
+    }
+
     /// To be called at the end of a frame (after the UI has rendered):
     /// Deletes all FontKeys and FontImageKeys that weren't used in
     /// the last frame, to save on memory. If the font needs to be recreated, it
@@ -565,8 +1002,25 @@ impl AppResources {
 
         delete_resources(self, delete_font_resource_updates, delete_image_resource_updates);
 
+        // Rasterized gradients can't be reloaded from a source, so they're deleted entirely
+        let mut unused_gradient_images = Vec::new();
+        let last_frame_conic_gradients = &self.last_frame_conic_gradients;
+        self.conic_gradient_images.retain(|gradient, image_id| {
+            let is_used = last_frame_conic_gradients.contains(gradient);
+            if !is_used {
+                unused_gradient_images.push(*image_id);
+            }
+            is_used
+        });
+        for image_id in unused_gradient_images {
+            self.delete_image(&image_id);
+        }
+
         self.last_frame_font_keys.clear();
         self.last_frame_image_keys.clear();
+        self.last_frame_conic_gradients.clear();
+
+        self.glyph_cache_stats = mem::replace(&mut self.current_glyph_cache_stats, GlyphCacheStats::default());
     }
 }
 
@@ -615,7 +1069,8 @@ fn scan_ui_description_for_font_keys<'a, T>(
 /// Scans the display list for all image keys
 fn scan_ui_description_for_image_keys<'a, T>(
     app_resources: &AppResources,
-    display_list: &DisplayList<'a, T>
+    display_list: &DisplayList<'a, T>,
+    hidpi_factor: f32,
 ) -> FastHashSet<ImageId> {
 
     use dom::NodeType::*;
@@ -633,7 +1088,9 @@ fn scan_ui_description_for_image_keys<'a, T>(
                 Some(*image_id)
             }
         }
-    }).collect()
+    })
+    .map(|image_id| app_resources.get_image_variant(&image_id, hidpi_factor).0)
+    .collect()
 }
 
 // Debug, PartialEq, Eq, PartialOrd, Ord
@@ -784,6 +1241,8 @@ fn build_add_font_resource_updates(
 
                 let (font_bytes, font_index) = match font_source.get_bytes() {
                     Ok(o) => o,
+                    #[cfg(feature = "net")]
+                    Err(FontReloadError::NotDownloaded(_)) => continue,
                     Err(e) => {
                         #[cfg(feature = "logging")] {
                             warn!("Could not load font with ID: {:?} - error: {}", im_font_id, e);
@@ -827,6 +1286,9 @@ fn build_add_image_resource_updates(
     .filter_map(|image_id| {
         let (data, descriptor) = match app_resources.image_sources.get(image_id)?.get_bytes() {
             Ok(o) => o,
+            // The image is added once its download has finished
+            #[cfg(feature = "net")]
+            Err(ImageReloadError::NotDownloaded(_)) => return None,
             Err(e) => {
                 #[cfg(feature = "logging")] {
                     warn!("Could not load image with ID: {:?} - error: {}", image_id, e);
@@ -928,8 +1390,17 @@ fn delete_resources(
     for (font_id, delete_font_msg) in delete_font_resources {
         use self::DeleteFontMsg::*;
         match delete_font_msg {
-            Font(_) => { app_resources.currently_registered_fonts.remove(&font_id); },
-            Instance(_, size) => { app_resources.currently_registered_fonts.get_mut(&font_id).unwrap().delete_font_instance(&size); },
+            Font(_) => {
+                if let Some(loaded_font) = app_resources.currently_registered_fonts.remove(&font_id) {
+                    for font_instance_key in loaded_font.font_instances.values() {
+                        app_resources.rasterized_glyphs.remove(font_instance_key);
+                    }
+                }
+            },
+            Instance(font_instance_key, size) => {
+                app_resources.rasterized_glyphs.remove(&font_instance_key);
+                app_resources.currently_registered_fonts.get_mut(&font_id).unwrap().delete_font_instance(&size);
+            },
         }
     }
 }
@@ -979,6 +1450,13 @@ fn load_system_font(id: &str) -> Option<(Vec<u8>, i32)> {
     system_fonts::get(&font_builder.build())
 }
 
+/// Reads the colors that the user selected in the settings of the OS. Querying the
+/// OS is implemented by the native style, without it all colors are undefined.
+#[cfg(all(feature = "native_style", not(test)))]
+pub(crate) fn load_system_colors() -> SystemColors {
+    ::azul_native_style::platform::get_system_colors()
+}
+
 /// Return the native fonts
 #[cfg(target_os = "linux")]
 enum LinuxNativeFontType { SansSerif, Monospace }
@@ -1154,6 +1632,36 @@ fn is_image_opaque(format: RawImageFormat, bytes: &[u8]) -> bool {
     }
 }
 
+/// Rasterizes a conic gradient into a (premultiplied) BGRA8 image
+fn rasterize_conic_gradient(gradient: &ConicGradient, width: u32, height: u32) -> RawImage {
+
+    let center_x = gradient.center.0.get() / 100.0 * width as f32;
+    let center_y = gradient.center.1.get() / 100.0 * height as f32;
+    let start_angle = gradient.angle.get();
+
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            // 0deg points to the top, the angle increases clockwise
+            let angle = dx.atan2(-dy).to_degrees() - start_angle;
+            let position = ((angle % 360.0) + 360.0) % 360.0 / 360.0 * 100.0;
+            let color = gradient.get_color_at(position);
+            pixels.extend_from_slice(&[color.b, color.g, color.r, color.a]);
+        }
+    }
+
+    premultiply(pixels.as_mut_slice());
+
+    RawImage {
+        pixels,
+        image_dimensions: (width, height),
+        data_format: RawImageFormat::BGRA8,
+    }
+}
+
 // From webrender/wrench
 // These are slow. Gecko's gfx/2d/Swizzle.cpp has better versions
 fn premultiply(data: &mut [u8]) {
@@ -1172,6 +1680,28 @@ fn test_premultiply() {
     assert_eq!(color, [127, 0, 0, 127]);
 }
 
+#[test]
+fn test_conic_gradient_image_cache() {
+
+    use azul_css::{ExtendMode, FloatValue, PercentageValue};
+    use prelude::*;
+
+    let mut app_resources = AppResources::new(&AppConfig::default()).unwrap();
+    let gradient = ConicGradient {
+        angle: FloatValue::new(0.0),
+        center: (PercentageValue::new(50.0), PercentageValue::new(50.0)),
+        extend_mode: ExtendMode::Clamp,
+        stops: Vec::new(),
+    };
+
+    // The same gradient on a hidpi screen is rasterized again, at the larger size
+    let image = app_resources.get_conic_gradient_image(&gradient, 100, 50);
+    assert_eq!(app_resources.get_conic_gradient_image(&gradient, 100, 50), image);
+    let hidpi_image = app_resources.get_conic_gradient_image(&gradient, 200, 100);
+    assert!(hidpi_image != image);
+    assert_eq!(rasterize_conic_gradient(&gradient, 200, 100).image_dimensions, (200, 100));
+}
+
 #[test]
 fn test_font_gc() {
 
@@ -1190,6 +1720,7 @@ fn test_font_gc() {
     let mut pending_focus_target = None;
     let is_mouse_down = false;
     let hovered_nodes = BTreeMap::new();
+    let viewport = CssViewport::default();
     let css = css::from_str(r#"
         #one { font-family: Helvetica; }
         #two { font-family: Arial; }
@@ -1201,14 +1732,14 @@ fn test_font_gc() {
         <p id="two">Hello</p>
         <p id="three">Hello</p>
     "#).into_ui_state();
-    let ui_description_frame_1 = UiDescription::match_css_to_dom(&mut ui_state_frame_1, &css, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down);
+    let ui_description_frame_1 = UiDescription::match_css_to_dom(&mut ui_state_frame_1, &css, viewport, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down);
     let display_list_frame_1 = DisplayList::new_from_ui_description(&ui_description_frame_1, &ui_state_frame_1);
 
 
     let mut ui_state_frame_2: UiState<Mock> = Dom::mock_from_xml(r#"
         <p>Hello</p>
     "#).into_ui_state();
-    let ui_description_frame_2 = UiDescription::match_css_to_dom(&mut ui_state_frame_2, &css, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down);
+    let ui_description_frame_2 = UiDescription::match_css_to_dom(&mut ui_state_frame_2, &css, viewport, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down);
     let display_list_frame_2 = DisplayList::new_from_ui_description(&ui_description_frame_2, &ui_state_frame_2);
 
 
@@ -1217,7 +1748,7 @@ fn test_font_gc() {
         <p id="two">Hello</p>
         <p id="three">Hello</p>
     "#).into_ui_state();
-    let ui_description_frame_3 = UiDescription::match_css_to_dom(&mut ui_state_frame_3, &css, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down);
+    let ui_description_frame_3 = UiDescription::match_css_to_dom(&mut ui_state_frame_3, &css, viewport, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down);
     let display_list_frame_3 = DisplayList::new_from_ui_description(&ui_description_frame_3, &ui_state_frame_3);
 
 
@@ -1239,9 +1770,9 @@ fn test_font_gc() {
         set
     }
 
-    assert_eq!(scan_ui_description_for_image_keys(&app_resources, &display_list_frame_1), FastHashSet::default());
-    assert_eq!(scan_ui_description_for_image_keys(&app_resources, &display_list_frame_2), FastHashSet::default());
-    assert_eq!(scan_ui_description_for_image_keys(&app_resources, &display_list_frame_3), FastHashSet::default());
+    assert_eq!(scan_ui_description_for_image_keys(&app_resources, &display_list_frame_1, 1.0), FastHashSet::default());
+    assert_eq!(scan_ui_description_for_image_keys(&app_resources, &display_list_frame_2, 1.0), FastHashSet::default());
+    assert_eq!(scan_ui_description_for_image_keys(&app_resources, &display_list_frame_3, 1.0), FastHashSet::default());
 
     assert_eq!(scan_ui_description_for_font_keys(&app_resources, &display_list_frame_1), build_map(vec![
         (ImmediateFontId::Unresolved("Arial".to_string()), build_set(vec![px_to_au(10.0)])),
@@ -1259,7 +1790,7 @@ fn test_font_gc() {
 
 
 
-    app_resources.add_fonts_and_images(&display_list_frame_1);
+    app_resources.add_fonts_and_images(&display_list_frame_1, 1.0);
     assert_eq!(app_resources.currently_registered_fonts.len(), 3);
     assert_eq!(app_resources.last_frame_font_keys.len(), 3);
 
@@ -1268,25 +1799,81 @@ fn test_font_gc() {
     assert_eq!(app_resources.currently_registered_fonts.len(), 3); // fails
 
     // Assert that fonts don't get double-inserted, still the same font sources as previously
-    app_resources.add_fonts_and_images(&display_list_frame_3);
+    app_resources.add_fonts_and_images(&display_list_frame_3, 1.0);
     app_resources.garbage_collect_fonts_and_images();
     assert_eq!(app_resources.currently_registered_fonts.len(), 3);
 
     // Assert that no new fonts get added on subsequent frames
-    app_resources.add_fonts_and_images(&display_list_frame_3);
-    app_resources.add_fonts_and_images(&display_list_frame_3);
-    app_resources.add_fonts_and_images(&display_list_frame_3);
-    app_resources.add_fonts_and_images(&display_list_frame_3);
-    app_resources.add_fonts_and_images(&display_list_frame_3);
+    app_resources.add_fonts_and_images(&display_list_frame_3, 1.0);
+    app_resources.add_fonts_and_images(&display_list_frame_3, 1.0);
+    app_resources.add_fonts_and_images(&display_list_frame_3, 1.0);
+    app_resources.add_fonts_and_images(&display_list_frame_3, 1.0);
+    app_resources.add_fonts_and_images(&display_list_frame_3, 1.0);
     app_resources.garbage_collect_fonts_and_images();
     assert_eq!(app_resources.currently_registered_fonts.len(), 3);
 
     // If the DOM changes, the fonts should get deleted, the only font still present is "sans-serif"
-    app_resources.add_fonts_and_images(&display_list_frame_2);
+    app_resources.add_fonts_and_images(&display_list_frame_2, 1.0);
     app_resources.garbage_collect_fonts_and_images();
     assert_eq!(app_resources.currently_registered_fonts.len(), 1);
 
-    app_resources.add_fonts_and_images(&display_list_frame_1);
+    app_resources.add_fonts_and_images(&display_list_frame_1, 1.0);
     app_resources.garbage_collect_fonts_and_images();
     assert_eq!(app_resources.currently_registered_fonts.len(), 3);
 }
+
+#[test]
+#[cfg(feature = "image_loading")]
+fn test_image_variants() {
+
+    use prelude::*;
+
+    let mut app_resources = AppResources::new(&AppConfig::default()).unwrap();
+    let image_id = ImageId::new();
+    app_resources.add_image(image_id, ImageSource::Variants(vec![
+        ImageVariant::new(2, ImageSource::File("icon@2x.png".into())),
+        ImageVariant::new(1, ImageSource::File("icon.png".into())),
+        ImageVariant::new(3, ImageSource::File("icon@3x.png".into())),
+    ]));
+
+    let variant_source = |hidpi_factor: f32| {
+        let (variant_id, scale) = app_resources.get_image_variant(&image_id, hidpi_factor);
+        (app_resources.image_sources[&variant_id].clone(), scale as u32)
+    };
+
+    assert_eq!(variant_source(1.0), (ImageSource::File("icon.png".into()), 1));
+    assert_eq!(variant_source(1.5), (ImageSource::File("icon@2x.png".into()), 2));
+    assert_eq!(variant_source(2.0000002), (ImageSource::File("icon@2x.png".into()), 2));
+    assert_eq!(variant_source(4.0), (ImageSource::File("icon@3x.png".into()), 3));
+
+    // Images without variants are drawn as they are
+    let other_image_id = ImageId::new();
+    assert_eq!(app_resources.get_image_variant(&other_image_id, 2.0).0, other_image_id);
+
+    // Deleting the image deletes the sources of its variants
+    app_resources.delete_image(&image_id);
+    assert!(app_resources.image_sources.is_empty());
+}
+
+#[test]
+fn test_glyph_cache_stats() {
+
+    use webrender::api::LayoutPoint;
+
+    let mut app_resources = AppResources::new(&AppConfig::default()).unwrap();
+    let font_instance_key = FontInstanceKey::new(IdNamespace(0), 0);
+    let glyphs = |indices: &[u32]| indices.iter().map(|index| GlyphInstance { index: *index, point: LayoutPoint::zero() }).collect::<Vec<_>>();
+
+    // "abca": the second "a" is already rasterized
+    app_resources.record_drawn_glyphs(font_instance_key, &glyphs(&[1, 2, 3, 1]));
+    app_resources.garbage_collect_fonts_and_images();
+    assert_eq!(app_resources.get_glyph_cache_stats(), GlyphCacheStats { glyphs_drawn: 4, cache_misses: 3, glyphs_preloaded: 0 });
+
+    app_resources.record_drawn_glyphs(font_instance_key, &glyphs(&[1, 4]));
+    app_resources.garbage_collect_fonts_and_images();
+    assert_eq!(app_resources.get_glyph_cache_stats(), GlyphCacheStats { glyphs_drawn: 2, cache_misses: 1, glyphs_preloaded: 0 });
+
+    // Frames that don't draw any text
+    app_resources.garbage_collect_fonts_and_images();
+    assert_eq!(app_resources.get_glyph_cache_stats(), GlyphCacheStats::default());
+}
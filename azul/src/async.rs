@@ -6,8 +6,10 @@ use std::{
     hash::{Hash, Hasher},
 };
 use {
+    FastHashMap,
     callbacks::{UpdateScreen, DontRedraw, TimerCallback, TimerCallbackType},
     app_resources::AppResources,
+    animation::TimelineId,
 };
 
 /// Should a timer terminate or not - used to remove active timers
@@ -37,6 +39,19 @@ impl TimerId {
     }
 }
 
+/// Limits how often the callback of a timer runs if the timer is started again and again
+/// in quick succession (i.e. on every keystroke), see `AppState::debounce` and `AppState::throttle`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimit {
+    /// The callback runs once, after the `delay` of the timer has passed. Starting the
+    /// timer again (with the same `TimerId`) before that replaces it, so the delay restarts.
+    Debounce,
+    /// The callback runs immediately, but at most once per `interval` of the timer.
+    /// If the timer is started again during the interval, `pending` is set and the
+    /// callback runs once more at the end of the interval.
+    Throttle { pending: bool },
+}
+
 /// A `Timer` is a function that is run on every frame.
 ///
 /// There are often a lot of visual tasks such as animations or fetching the
@@ -63,6 +78,8 @@ pub struct Timer<T> {
     pub timeout: Option<Duration>,
     /// Callback to be called for this timer
     pub callback: TimerCallback<T>,
+    /// Whether the timer is debounced or throttled (default: `None`)
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl<T> Timer<T> {
@@ -76,6 +93,7 @@ impl<T> Timer<T> {
             interval: None,
             timeout: None,
             callback: TimerCallback(callback),
+            rate_limit: None,
         }
     }
 
@@ -103,6 +121,13 @@ impl<T> Timer<T> {
         self
     }
 
+    /// Debounces or throttles the timer, see `RateLimit`
+    #[inline]
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
     /// Crate-internal: Invokes the timer if the timer and
     /// the `self.timeout` allow it to
     pub(crate) fn invoke_callback_with_data(
@@ -111,7 +136,16 @@ impl<T> Timer<T> {
         app_resources: &mut AppResources)
     -> (UpdateScreen, TerminateTimer)
     {
-        let instant_now = Instant::now();
+        self.invoke_callback_at(Instant::now(), data, app_resources)
+    }
+
+    fn invoke_callback_at(
+        &mut self,
+        instant_now: Instant,
+        data: &mut T,
+        app_resources: &mut AppResources)
+    -> (UpdateScreen, TerminateTimer)
+    {
         let delay = self.delay.unwrap_or_else(|| Duration::from_millis(0));
 
         // Check if the timers timeout is reached
@@ -121,6 +155,36 @@ impl<T> Timer<T> {
             }
         }
 
+        if instant_now < self.created + delay {
+            return (DontRedraw, TerminateTimer::Continue);
+        }
+
+        match self.rate_limit {
+            Some(RateLimit::Debounce) => {
+                let (should_update, _) = (self.callback.0)(data, app_resources);
+                return (should_update, TerminateTimer::Terminate);
+            },
+            Some(RateLimit::Throttle { pending }) => {
+                let interval = self.interval.unwrap_or_else(|| Duration::from_millis(0));
+                match self.last_run {
+                    Some(last_run) if instant_now - last_run < interval => {
+                        return (DontRedraw, TerminateTimer::Continue);
+                    },
+                    // The interval is over and the timer wasn't started again
+                    Some(_) if !pending => {
+                        return (DontRedraw, TerminateTimer::Terminate);
+                    },
+                    _ => {
+                        let (should_update, _) = (self.callback.0)(data, app_resources);
+                        self.last_run = Some(instant_now);
+                        self.rate_limit = Some(RateLimit::Throttle { pending: false });
+                        return (should_update, TerminateTimer::Continue);
+                    },
+                }
+            },
+            None => { },
+        }
+
         if let Some(interval) = self.interval {
             let last_run = match self.last_run {
                 Some(s) => s,
@@ -139,6 +203,18 @@ impl<T> Timer<T> {
     }
 }
 
+/// Inserts the timer, like `add_timer` - except if the timer is throttled and a throttled
+/// timer with the same ID is already running: then the running timer is only marked as pending
+pub(crate) fn insert_timer<T>(timers: &mut FastHashMap<TimerId, Timer<T>>, id: TimerId, timer: Timer<T>) {
+    if let Some(RateLimit::Throttle { .. }) = timer.rate_limit {
+        if let Some(Timer { rate_limit: Some(RateLimit::Throttle { pending }), .. }) = timers.get_mut(&id) {
+            *pending = true;
+            return;
+        }
+    }
+    timers.insert(id, timer);
+}
+
 // #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)] for Timer<T>
 
 impl<T> fmt::Debug for Timer<T> {
@@ -151,6 +227,7 @@ impl<T> fmt::Debug for Timer<T> {
                 interval: {:?}, \
                 timeout: {:?}, \
                 callback: {:?}, \
+                rate_limit: {:?}, \
             }}",
             self.created,
             self.last_run,
@@ -158,6 +235,7 @@ impl<T> fmt::Debug for Timer<T> {
             self.interval,
             self.timeout,
             self.callback,
+            self.rate_limit,
         )
     }
 }
@@ -176,6 +254,7 @@ impl<T> Hash for Timer<T> {
         self.interval.hash(state);
         self.timeout.hash(state);
         self.callback.hash(state);
+        self.rate_limit.hash(state);
     }
 }
 
@@ -186,7 +265,8 @@ impl<T> PartialEq for Timer<T> {
         self.delay == rhs.delay &&
         self.interval == rhs.interval &&
         self.timeout == rhs.timeout &&
-        self.callback == rhs.callback
+        self.callback == rhs.callback &&
+        self.rate_limit == rhs.rate_limit
     }
 }
 
@@ -213,6 +293,8 @@ pub struct Task<T> {
     dropcheck: Weak<()>,
     /// Timer that will run directly after this task is completed.
     pub(crate) after_completion_timer: Option<Timer<T>>,
+    /// Timeline (i.e. a loading animation) that will be stopped after this task is completed.
+    pub(crate) after_completion_stop_timeline: Option<TimelineId>,
 }
 
 impl<T> Task<T> {
@@ -232,6 +314,7 @@ impl<T> Task<T> {
             join_handle: Some(thread_handle),
             dropcheck: thread_weak,
             after_completion_timer: None,
+            after_completion_stop_timeline: None,
         }
     }
 
@@ -244,6 +327,17 @@ impl<T> Task<T> {
         self
     }
 
+    /// Stops the animation timeline after the task has finished - useful
+    /// for loading animations that should play while the task is running.
+    ///
+    /// The properties that the timeline animated are reset (see `Timeline::reset_properties`),
+    /// so the nodes don't stay at the value of the frame the loading animation was stopped at.
+    #[inline]
+    pub fn then_stop_timeline(mut self, timeline_id: TimelineId) -> Self {
+        self.after_completion_stop_timeline = Some(timeline_id);
+        self
+    }
+
     /// Returns true if the task has been finished, false otherwise
     pub(crate) fn is_finished(&self) -> bool {
         self.dropcheck.upgrade().is_none()
@@ -349,3 +443,59 @@ impl<T> Drop for Thread<T> {
         }
     }
 }
+
+#[cfg(test)]
+fn count_runs(runs: &mut usize, _: &mut AppResources) -> (UpdateScreen, TerminateTimer) {
+    *runs += 1;
+    (DontRedraw, TerminateTimer::Continue)
+}
+
+#[test]
+fn test_debounced_timer() {
+
+    use app::AppConfig;
+
+    let mut app_resources = AppResources::new(&AppConfig::default()).unwrap();
+    let mut runs = 0;
+    let mut timer = Timer::new(count_runs).with_delay(Duration::from_millis(300)).with_rate_limit(RateLimit::Debounce);
+    let start = timer.created;
+
+    assert_eq!(timer.invoke_callback_at(start + Duration::from_millis(100), &mut runs, &mut app_resources).1, TerminateTimer::Continue);
+    assert_eq!(runs, 0);
+
+    // Runs once, even though the callback wants to continue
+    assert_eq!(timer.invoke_callback_at(start + Duration::from_millis(300), &mut runs, &mut app_resources).1, TerminateTimer::Terminate);
+    assert_eq!(runs, 1);
+}
+
+#[test]
+fn test_throttled_timer() {
+
+    use app::AppConfig;
+
+    let mut app_resources = AppResources::new(&AppConfig::default()).unwrap();
+    let mut runs = 0;
+    let interval = Duration::from_millis(100);
+    let throttled = Timer::new(count_runs).with_interval(interval).with_rate_limit(RateLimit::Throttle { pending: false });
+    let id = TimerId::new();
+    let mut timers = FastHashMap::default();
+    let start = throttled.created;
+
+    // The first event runs the callback immediately
+    insert_timer(&mut timers, id, throttled);
+    timers.get_mut(&id).unwrap().invoke_callback_at(start, &mut runs, &mut app_resources);
+    assert_eq!(runs, 1);
+
+    // Two more events during the interval: one run at the end of the interval
+    insert_timer(&mut timers, id, throttled);
+    insert_timer(&mut timers, id, throttled);
+    let timer = timers.get_mut(&id).unwrap();
+    assert_eq!(timer.invoke_callback_at(start + Duration::from_millis(50), &mut runs, &mut app_resources).1, TerminateTimer::Continue);
+    assert_eq!(runs, 1);
+    assert_eq!(timer.invoke_callback_at(start + Duration::from_millis(100), &mut runs, &mut app_resources).1, TerminateTimer::Continue);
+    assert_eq!(runs, 2);
+
+    // No more events: the timer stops after the next interval
+    assert_eq!(timer.invoke_callback_at(start + Duration::from_millis(200), &mut runs, &mut app_resources).1, TerminateTimer::Terminate);
+    assert_eq!(runs, 2);
+}
@@ -1,11 +1,14 @@
 use std::{
     fmt,
+    any::Any,
     rc::Rc,
+    cell::RefCell,
     hash::{Hash, Hasher},
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
     sync::atomic::{AtomicUsize, Ordering},
 };
-use azul_css::CssPath;
+use azul_css::{CssPath, RectStyle, RectLayout};
 #[cfg(feature = "css_parser")]
 use azul_css_parser::CssPathParseError;
 use webrender::api::{HitTestItem, LayoutRect};
@@ -15,15 +18,18 @@ use {
     dom::{Dom, NodeType, NodeData},
     app::AppStateNoData,
     ui_state::UiState,
-    id_tree::{NodeId, Node, NodeHierarchy},
+    ui_description::UiDescription,
+    id_tree::{NodeId, Node, NodeHierarchy, NodeDataContainer, Children, Descendants},
     app_resources::AppResources,
     window::FakeWindow,
+    window_state::WindowSize,
 };
 pub use stack_checked_pointer::StackCheckedPointer;
 pub use glium::texture::Texture2d;
 pub use glium::framebuffer::SimpleFrameBuffer;
 pub use glium::glutin::WindowId as GliumWindowId;
-pub use glium::glutin::dpi::{LogicalSize, PhysicalSize};
+pub use glium::glutin::dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
+pub use ui_solver::{LayoutResult, PositionedRectangle};
 
 pub type DefaultCallbackType<T, U> = fn(&mut U, &mut AppStateNoData<T>, &mut CallbackInfo<T>) -> UpdateScreen;
 pub type DefaultCallbackTypeUnchecked<T> = fn(&StackCheckedPointer<T>, &mut AppStateNoData<T>, &mut CallbackInfo<T>) -> UpdateScreen;
@@ -88,9 +94,57 @@ pub type TimerCallbackType<T> = fn(&mut T, app_resources: &mut AppResources) ->
 pub struct TimerCallback<T>(pub TimerCallbackType<T>);
 impl_callback!(TimerCallback<T>);
 
+/// Function that builds the DOM of a window from the application data. By default, all
+/// windows use `Layout::layout`, see `WindowCreateOptions::layout_callback`
+pub type LayoutCallbackType<T> = fn(&T, LayoutInfo<T>) -> Dom<T>;
+pub struct LayoutCallback<T>(pub LayoutCallbackType<T>);
+impl_callback!(LayoutCallback<T>);
+
+pub type DynCallbackType = fn(&mut dyn Any, &mut AppResources, &mut DynCallbackInfo) -> UpdateScreen;
+/// Callback that doesn't depend on the application data model, used by `DynDom` nodes.
+/// The first argument is the state of the node that the callback is attached to
+/// (see `DynDom::with_state`), or `()` if the node has no state.
+pub struct DynCallback(pub DynCallbackType);
+
+impl fmt::Debug for DynCallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DynCallback @ 0x{:x}", self.0 as usize)
+    }
+}
+
+impl Clone for DynCallback {
+    fn clone(&self) -> Self {
+        DynCallback(self.0)
+    }
+}
+
+impl Copy for DynCallback { }
+
+impl PartialEq for DynCallback {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.0 as usize == rhs.0 as usize
+    }
+}
+
+impl Eq for DynCallback { }
+
+/// Hook that runs at the start of every frame, before any events are processed,
+/// see `App::on_frame_start`
+pub type FrameStartCallbackType<T> = fn(&mut AppState<T>);
+/// Hook that runs after a window has been laid out, with the solved layout
+/// of that window, see `App::on_after_layout`
+pub type AfterLayoutCallbackType<T> = fn(&mut AppState<T>, &GliumWindowId, &LayoutResult);
+/// Hook that runs after all windows have been rendered, see `App::on_after_render`
+pub type AfterRenderCallbackType<T> = fn(&mut AppState<T>);
+/// Hook that is called with the documents that the app was asked to open
+/// (i.e. from a list of recent documents), see `App::on_open_documents`
+pub type OpenDocumentsCallbackType<T> = fn(&mut AppState<T>, &[PathBuf]);
+
 /// Wrapper for storing, inserting and registering default callbacks
 pub(crate) struct DefaultCallbackSystem<T> {
     callbacks: BTreeMap<DefaultCallbackId, (StackCheckedPointer<T>, DefaultCallback<T>)>,
+    /// Callbacks of `DynDom` nodes, together with the state of the node
+    dyn_callbacks: BTreeMap<DefaultCallbackId, (Rc<RefCell<dyn Any>>, DynCallback)>,
 }
 
 impl<T> DefaultCallbackSystem<T> {
@@ -99,6 +153,7 @@ impl<T> DefaultCallbackSystem<T> {
     pub(crate) fn new() -> Self {
         Self {
             callbacks: BTreeMap::new(),
+            dyn_callbacks: BTreeMap::new(),
         }
     }
 
@@ -107,6 +162,19 @@ impl<T> DefaultCallbackSystem<T> {
         self.callbacks.insert(id, (ptr, func));
     }
 
+    /// Registers a new type-erased callback, see `FakeWindow::add_dyn_callback`
+    pub fn add_dyn_callback(&mut self, id: DefaultCallbackId, state: Rc<RefCell<dyn Any>>, func: DynCallback) {
+        self.dyn_callbacks.insert(id, (state, func));
+    }
+
+    /// Removes all callbacks that aren't in `used_ids` anymore (i.e. the node
+    /// that registered the callback isn't in the DOM anymore), dropping the
+    /// state of the `DynDom` nodes together with their callbacks
+    pub(crate) fn retain_callbacks(&mut self, used_ids: &BTreeSet<DefaultCallbackId>) {
+        self.callbacks.retain(|id, _| used_ids.contains(id));
+        self.dyn_callbacks.retain(|id, _| used_ids.contains(id));
+    }
+
     /// Invokes a certain default callback and returns its result
     ///
     /// NOTE: `app_data` is required so we know that we don't
@@ -124,6 +192,19 @@ impl<T> DefaultCallbackSystem<T> {
     {
         if let Some((callback_ptr, callback_fn)) = self.callbacks.get(callback_id) {
             (callback_fn.0)(callback_ptr, app_state_no_data, window_event)
+        } else if let Some((state, callback_fn)) = self.dyn_callbacks.get(callback_id) {
+            let mut dyn_info = DynCallbackInfo {
+                focus: None,
+                window_id: window_event.window_id,
+                hit_dom_node: window_event.hit_dom_node,
+                cursor_relative_to_item: window_event.cursor_relative_to_item,
+                cursor_in_viewport: window_event.cursor_in_viewport,
+            };
+            let result = (callback_fn.0)(&mut *state.borrow_mut(), app_state_no_data.resources, &mut dyn_info);
+            if dyn_info.focus.is_some() {
+                window_event.focus = dyn_info.focus;
+            }
+            result
         } else {
             #[cfg(feature = "logging")] {
                 warn!("Calling default callback with invalid ID {:?}", callback_id);
@@ -137,6 +218,7 @@ impl<T> Clone for DefaultCallbackSystem<T> {
     fn clone(&self) -> Self {
         Self {
             callbacks: self.callbacks.clone(),
+            dyn_callbacks: self.dyn_callbacks.clone(),
         }
     }
 }
@@ -163,8 +245,30 @@ pub struct CallbackInfo<'a, T: 'a> {
     pub hit_dom_node: NodeId,
     /// UiState containing the necessary data for testing what
     pub(crate) ui_state: &'a UiState<T>,
+    /// Styled nodes of the `ui_state`, for querying the computed styles
+    pub(crate) ui_description: &'a UiDescription<T>,
     /// What items are currently being hit
     pub(crate) hit_test_items: &'a [HitTestItem],
+    /// Rectangles of the last layout of the window (`None` before the first layout)
+    pub(crate) layout_rects: Option<&'a NodeDataContainer<PositionedRectangle>>,
+    /// Size of the window, for converting the `layout_rects` to physical pixels
+    pub(crate) window_size: &'a WindowSize,
+    /// The (x, y) position of the mouse cursor, **relative to top left of the element that was hit**.
+    pub cursor_relative_to_item: Option<(f32, f32)>,
+    /// The (x, y) position of the mouse cursor, **relative to top left of the window**.
+    pub cursor_in_viewport: Option<(f32, f32)>,
+}
+
+/// Same as the `CallbackInfo`, but for callbacks that don't know the type of the
+/// application data model (see `DynCallback`)
+#[derive(Debug, Clone)]
+pub struct DynCallbackInfo<'a> {
+    /// Focus for the next frame, see `CallbackInfo::focus`
+    pub focus: Option<FocusTarget>,
+    /// The ID of the window that the event was clicked on
+    pub window_id: &'a GliumWindowId,
+    /// The ID of the node that was hit
+    pub hit_dom_node: NodeId,
     /// The (x, y) position of the mouse cursor, **relative to top left of the element that was hit**.
     pub cursor_relative_to_item: Option<(f32, f32)>,
     /// The (x, y) position of the mouse cursor, **relative to top left of the window**.
@@ -178,7 +282,10 @@ impl<'a, T: 'a> Clone for CallbackInfo<'a, T> {
             window_id: self.window_id,
             hit_dom_node: self.hit_dom_node,
             ui_state: self.ui_state,
+            ui_description: self.ui_description,
             hit_test_items: self.hit_test_items,
+            layout_rects: self.layout_rects,
+            window_size: self.window_size,
             cursor_relative_to_item: self.cursor_relative_to_item,
             cursor_in_viewport: self.cursor_in_viewport,
         }
@@ -192,7 +299,10 @@ impl<'a, T: 'a> fmt::Debug for CallbackInfo<'a, T> {
             window_id: {:?}, \
             hit_dom_node: {:?}, \
             ui_state: {:?}, \
+            ui_description: {:?}, \
             hit_test_items: {:?}, \
+            layout_rects: {:?}, \
+            window_size: {:?}, \
             cursor_relative_to_item: {:?}, \
             cursor_in_viewport: {:?}, \
         }}",
@@ -200,13 +310,62 @@ impl<'a, T: 'a> fmt::Debug for CallbackInfo<'a, T> {
             self.window_id,
             self.hit_dom_node,
             self.ui_state,
+            self.ui_description,
             self.hit_test_items,
+            self.layout_rects,
+            self.window_size,
             self.cursor_relative_to_item,
             self.cursor_in_viewport,
         )
     }
 }
 
+/// Style of a node after the cascade: the stylesheet rules, the inline styles, the inherited
+/// properties and the dynamic CSS overrides are applied. Properties that are set neither by
+/// the node nor by its parents are `None`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ComputedStyle {
+    /// Style properties (colors, fonts, borders, ...)
+    pub style: RectStyle,
+    /// Layout properties (size, padding, margin, flex, ...)
+    pub layout: RectLayout,
+}
+
+/// Position and size of a laid-out node, relative to the top left corner of the window,
+/// see `CallbackInfo::get_node_rect`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NodeRect {
+    /// Top left corner in CSS pixels, i.e. for absolutely positioning a popup next to the node
+    pub logical_origin: LogicalPosition,
+    /// Size in CSS pixels, including the padding
+    pub logical_size: LogicalSize,
+    /// Top left corner in physical pixels, i.e. for custom OpenGL drawing
+    pub physical_origin: PhysicalPosition,
+    /// Size in physical pixels, including the padding
+    pub physical_size: PhysicalSize,
+}
+
+impl NodeRect {
+    pub(crate) fn new(bounds: LayoutRect, window_size: &WindowSize) -> Self {
+        // With `scale_to_fit`, the UI is scaled and centered in the window
+        // (and moved inside of the margin for the window shadow)
+        let (scale, offset) = window_size.get_content_transform().unwrap_or((1.0, LogicalPosition::new(0.0, 0.0)));
+        let hidpi_factor = window_size.hidpi_factor;
+        Self {
+            logical_origin: LogicalPosition::new(bounds.origin.x as f64, bounds.origin.y as f64),
+            logical_size: LogicalSize::new(bounds.size.width as f64, bounds.size.height as f64),
+            physical_origin: PhysicalPosition::new(
+                (bounds.origin.x as f64 * scale + offset.x) * hidpi_factor,
+                (bounds.origin.y as f64 * scale + offset.y) * hidpi_factor,
+            ),
+            physical_size: PhysicalSize::new(
+                bounds.size.width as f64 * scale * hidpi_factor,
+                bounds.size.height as f64 * scale * hidpi_factor,
+            ),
+        }
+    }
+}
+
 /// Information about the bounds of a laid-out div rectangle.
 ///
 /// Necessary when invoking `IFrameCallbacks` and `GlTextureCallbacks`, so
@@ -377,6 +536,48 @@ impl<'a, T: 'a> CallbackInfo<'a, T> {
         self.ui_state.dom.arena.node_data.internal.get(node_id.index())
     }
 
+    /// Returns the DOM of the window (as it was rendered in the last frame),
+    /// for queries that aren't covered by the functions on `CallbackInfo`
+    pub fn get_dom<'b>(&'b self) -> &'b Dom<T> {
+        &self.ui_state.dom
+    }
+
+    /// Returns the final style of a node, as it was rendered in the last frame,
+    /// i.e. for widgets that have to adapt to their resolved colors or sizes
+    pub fn get_computed_style(&self, node_id: NodeId) -> Option<ComputedStyle> {
+        self.ui_description.get_computed_style(node_id)
+    }
+
+    /// Returns the position and size of a node in the last layout of the window, i.e. to
+    /// position a popup next to its anchor. `None` if the window hasn't been laid out yet.
+    /// Note that the scroll positions of the parent nodes are not taken into account.
+    pub fn get_node_rect(&self, node_id: NodeId) -> Option<NodeRect> {
+        let rect = self.layout_rects?.get(node_id)?;
+        Some(NodeRect::new(rect.bounds, self.window_size))
+    }
+
+    /// Returns the position and size of the node that the event was dispatched to, see `get_node_rect`
+    pub fn target_rect(&self) -> Option<NodeRect> {
+        self.get_node_rect(self.hit_dom_node)
+    }
+
+    /// Iterates over the direct children of the given node
+    pub fn children<'b>(&'b self, node_id: NodeId) -> Children<'b> {
+        self.ui_state.dom.children(node_id)
+    }
+
+    /// Iterates over the given node and all of its descendants, in tree order
+    pub fn descendants<'b>(&'b self, node_id: NodeId) -> Descendants<'b> {
+        self.ui_state.dom.descendants(node_id)
+    }
+
+    /// Returns all descendants of the given node for which the `predicate` returns true,
+    /// i.e. `info.find_descendants(info.hit_dom_node, |node| node.tab_index.is_some())`
+    /// for all focusable nodes below the node that was hit
+    pub fn find_descendants<F: Fn(&NodeData<T>) -> bool>(&self, node_id: NodeId, predicate: F) -> Vec<NodeId> {
+        self.ui_state.dom.find_descendants(node_id, predicate)
+    }
+
     /// Returns the index of the target NodeId (the target that received the event)
     /// in the targets parent or None if the target is the root node
     pub fn target_index_in_parent(&self) -> Option<usize> {
@@ -486,4 +687,58 @@ impl<'a, T: 'a> CallbackInfo<'a, T> {
     pub fn clear_focus(&mut self) {
         self.focus = Some(FocusTarget::NoFocus);
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_node_rect_hidpi_and_scale_to_fit() {
+
+    use webrender::api::{LayoutPoint, LayoutSize};
+
+    let bounds = LayoutRect::new(LayoutPoint::new(10.0, 20.0), LayoutSize::new(100.0, 50.0));
+
+    let window_size = WindowSize { hidpi_factor: 2.0, winit_hidpi_factor: 2.0, .. WindowSize::default() };
+    let rect = NodeRect::new(bounds, &window_size);
+    assert_eq!(rect.logical_origin, LogicalPosition::new(10.0, 20.0));
+    assert_eq!(rect.physical_origin, PhysicalPosition::new(20.0, 40.0));
+    assert_eq!(rect.physical_size, PhysicalSize::new(200.0, 100.0));
+
+    // A 400x300 window showing a 200x100 design: scaled by 2, centered vertically
+    let window_size = WindowSize {
+        dimensions: LogicalSize::new(400.0, 300.0),
+        scale_to_fit: Some(LogicalSize::new(200.0, 100.0)),
+        .. WindowSize::default()
+    };
+    let rect = NodeRect::new(bounds, &window_size);
+    assert_eq!(rect.logical_size, LogicalSize::new(100.0, 50.0));
+    assert_eq!(rect.physical_origin, PhysicalPosition::new(20.0, 90.0));
+    assert_eq!(rect.physical_size, PhysicalSize::new(200.0, 100.0));
+
+    // A window with a 20px shadow margin: the UI starts inside of the margin
+    let window_size = WindowSize { shadow_margin: 20.0, .. WindowSize::default() };
+    let rect = NodeRect::new(bounds, &window_size);
+    assert_eq!(rect.physical_origin, PhysicalPosition::new(30.0, 40.0));
+    assert_eq!(window_size.get_layout_size().width, window_size.dimensions.width - 40.0);
+}
+
+#[test]
+fn test_retain_callbacks_drops_dyn_state() {
+
+    fn on_click(_: &mut dyn Any, _: &mut AppResources, _: &mut DynCallbackInfo) -> UpdateScreen { DontRedraw }
+
+    let mut callbacks = DefaultCallbackSystem::<()>::new();
+    let kept_state: Rc<RefCell<dyn Any>> = Rc::new(RefCell::new(0_usize));
+    let dropped_state: Rc<RefCell<dyn Any>> = Rc::new(RefCell::new(0_usize));
+
+    let kept_id = get_new_unique_default_callback_id();
+    let dropped_id = get_new_unique_default_callback_id();
+    callbacks.add_dyn_callback(kept_id, kept_state.clone(), DynCallback(on_click));
+    callbacks.add_dyn_callback(dropped_id, dropped_state.clone(), DynCallback(on_click));
+    assert_eq!(Rc::strong_count(&dropped_state), 2);
+
+    let used_ids = [kept_id].iter().cloned().collect();
+    callbacks.retain_callbacks(&used_ids);
+
+    assert_eq!(Rc::strong_count(&kept_state), 2);
+    assert_eq!(Rc::strong_count(&dropped_state), 1);
+    assert!(callbacks.dyn_callbacks.contains_key(&kept_id));
+    assert!(!callbacks.dyn_callbacks.contains_key(&dropped_id));
+}
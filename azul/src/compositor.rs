@@ -1,4 +1,8 @@
-use std::sync::{Mutex, atomic::{Ordering, AtomicUsize}};
+use std::{
+    mem,
+    sync::{Mutex, atomic::{Ordering, AtomicUsize}},
+};
+use gleam::gl::{Gl, GLenum};
 use webrender::{
     ExternalImageHandler, ExternalImage, ExternalImageSource,
     api::{ExternalImageId, TexelRect, DevicePixel, Epoch, ImageRendering},
@@ -47,6 +51,31 @@ pub(crate) struct ActiveTexture {
 unsafe impl Send for ActiveTexture { }
 unsafe impl Sync for ActiveTexture { }
 
+/// `GL_CONTEXT_LOST` (OpenGL 4.5 / `KHR_robustness`), not exported by gleam
+const GL_CONTEXT_LOST: GLenum = 0x0507;
+
+/// Returned from rendering if the OpenGL context was lost (driver reset, suspend / resume,
+/// remote desktop session attached, etc.). All OpenGL objects of the context are gone,
+/// so the renderer and the window contexts have to be re-created.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct ContextLost;
+
+/// Checks the OpenGL error flag for a lost context
+pub(crate) fn is_context_lost(gl_context: &Gl) -> bool {
+    gl_context.get_error() == GL_CONTEXT_LOST
+}
+
+/// Removes all active textures **without** deleting them: After a context loss, the
+/// texture IDs are invalid, deleting them would call into the dead context.
+pub(crate) fn forget_active_gl_textures() {
+    let mut active_textures_lock = ACTIVE_GL_TEXTURES.lock().unwrap();
+    for (_, textures) in active_textures_lock.drain() {
+        for (_, texture) in textures {
+            mem::forget(texture);
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct Compositor { }
 
@@ -11,28 +11,98 @@
 //! | `color`                                            |              |             |            |                  |
 //! | `font-size`                                        |              |             |            |                  |
 //! | `font-family`                                      |              |             |            |                  |
-//! | `text-align`                                       |              |             |            |                  |
+//! | `text-align` (incl. `justify`, `start`, `end`)     |              |             |            |                  |
 //! | `letter-spacing`                                   |              |             |            |                  |
 //! | `line-height`                                      |              |             |            |                  |
 //! | `word-spacing`                                     |              |             |            |                  |
 //! | `tab-width`                                        |              |             |            |                  |
+//! | `tab-stops` (`200px dots, 300px`, `none`)          |              |             |            |                  |
+//! | `white-space` (`normal`, `nowrap`)                 |              |             |            |                  |
+//! | `text-overflow` (`clip`, `ellipsis`)               |              |             |            |                  |
+//! | `text-decoration` (`underline wavy red`, `none`)   |              |             |            |                  |
 //! | `cursor`                                           |              |             |            |                  |
+//! | `pointer-events` (`auto`, `none`)                  |              |             |            |                  |
+//! | `user-select` (`auto`, `none`, `text`, `all`)      |              |             |            |                  |
+//! | `direction` (`ltr`, `rtl`)                         |              |             |            |                  |
+//! | `opacity`                                          |              |             |            |                  |
+//! | `visibility` (`visible`, `hidden`)                 |              |             |            |                  |
+//! | `mix-blend-mode` (`multiply`, `screen`, ...)       |              |             |            |                  |
+//! | `object-fit` (`fill`, `contain`, `cover`, ...)     |              |             |            |                  |
+//! | `outline`, `outline-offset`                        |              |             |            |                  |
+//! | `perspective`                                      |              |             |            |                  |
+//! | `transform`, `transform-origin`, `transform-style` |              |             |            |                  |
 //! | `width`, `min-width`, `max-width`                  |              |             |            |                  |
 //! | `height`, `min-height`, `max-height`               |              |             |            |                  |
-//! | `position`                                         |              |             |            |                  |
+//! | `position` (incl. `sticky`)                        |              |             |            |                  |
 //! | `top`, `right`, `left`, `bottom`                   |              |             |            |                  |
 //! | `flex-wrap`                                        |              |             |            |                  |
 //! | `flex-direction`                                   |              |             |            |                  |
 //! | `flex-grow`                                        |              |             |            |                  |
 //! | `flex-shrink`                                      |              |             |            |                  |
+//! | `flex-basis` (`auto`, `content`, `200px`)          |              |             |            |                  |
+//! | `order`                                            |              |             |            |                  |
 //! | `justify-content`                                  |              |             |            |                  |
-//! | `align-items`                                      |              |             |            |                  |
+//! | `align-items` (incl. `baseline`)                   |              |             |            |                  |
 //! | `align-content`                                    |              |             |            |                  |
+//! | `display` (`flex`, `grid`, `inline[-block]`)       |              |             |            |                  |
+//! | `grid-template-columns`, `grid-template-rows`      |              |             |            |                  |
+//! | `grid-gap`, `grid-column`, `grid-row`              |              |             |            |                  |
+//! | `gap`, `row-gap`, `column-gap` (flex and grid)     |              |             |            |                  |
+//! | `float` (`left`, `right`)                          |              |             |            |                  |
 //! | `overflow`, `overflow-x`, `overflow-y`             |              |             |            |                  |
 //! | `padding`, `-top`, `-left`, `-right`, `-bottom`    |              |             |            |                  |
 //! | `margin`,  `-top`, `-left`, `-right`, `-bottom`    |              |             |            |                  |
 //! | `border`,  `-top`, `-left`, `-right`, `-bottom`    |              |             |            |                  |
+//! | `padding-inline`, `-block`, `-start`, `-end`       |              |             |            |                  |
+//! | `margin-inline`, `-block`, `-start`, `-end`        |              |             |            |                  |
+//! | `border-inline`, `-block`, `-start`, `-end`        |              |             |            |                  |
 //! | `box-shadow`, `-top`, `-left`, `-right`, `-bottom` |              |             |            |                  |
+//!
+//! # Scrollbars
+//!
+//! Nodes with `overflow: auto` or `overflow: scroll` show scrollbars on top of their content,
+//! which can be styled with the `::scrollbar` (`width`, `padding`), `::track` and `::thumb`
+//! (`background-color`, `border-radius`) pseudo-elements:
+//!
+//! ```css
+//! .list::scrollbar { width: 8px; padding: 1px; }
+//! .list::track { background-color: transparent; }
+//! .list::thumb { background-color: #00000055; border-radius: 3px; }
+//! .list:hover::thumb { background-color: #00000099; }
+//! ```
+//!
+//! # Right-to-left layout
+//!
+//! `direction: rtl` is inherited, so it is usually set on the root node. Rows are laid out
+//! from the right edge (`flex-direction: row` behaves like `row-reverse`), the words of each
+//! line are placed from right to left, `text-align: start` / `end` and the `-inline-start` /
+//! `-inline-end` properties resolve to the right / left side.
+//!
+//! # Theming
+//!
+//! The widgets of the native style reference design tokens via CSS variables, which can be
+//! overridden for the whole window or for a subtree (or via a `native_style::Theme`, see `themed()`):
+//!
+//! ```css
+//! :root { --azul-accent-color: #e91e63; --azul-border-radius: 0px; }
+//! .sidebar { --azul-font-size: 12px; }
+//! ```
+//!
+//! Tokens: `--azul-accent-color`, `--azul-surface-color`, `--azul-text-color`,
+//! `--azul-disabled-text-color`, `--azul-border-color`, `--azul-spacing-small`,
+//! `--azul-spacing-medium`, `--azul-spacing-large`, `--azul-border-radius`,
+//! `--azul-font-family`, `--azul-font-size`, `--azul-control-height`,
+//! `--azul-focus-ring-color` and `--azul-focus-ring-width`.
+//!
+//! # System colors
+//!
+//! The colors selected in the settings of the operating system (Windows, macOS and GNOME) are
+//! declared as built-in variables for every window: `--azul-system-accent-color`,
+//! `--azul-system-highlight-color`, `--azul-system-window-background-color` and
+//! `--azul-system-text-color`. Colors that can't be determined are left undefined, so always
+//! specify a fallback (`var(--azul-system-text-color, black)`). The windows are restyled when
+//! the user changes the colors, the native style uses the system accent color as its
+//! `--azul-accent-color`. See `AppResources::get_system_colors` for reading the colors in Rust.
 
 #[cfg(debug_assertions)]
 use std::time::Duration;
@@ -63,6 +133,13 @@ pub fn native() -> Css {
     azul_native_style::native()
 }
 
+/// Returns the native style with the design tokens overridden by the `theme`.
+/// Convenience wrapper for `azul-native-style::themed`.
+#[cfg(feature = "native_style")]
+pub fn themed(theme: &native_style::Theme) -> Css {
+    azul_native_style::themed(theme)
+}
+
 /// Parses CSS stylesheet from a string. Convenience wrapper for `azul-css-parser::new_from_str`.
 #[cfg(feature = "css_parser")]
 pub fn from_str(input: &str) -> Result<Css, CssParseError> {
@@ -79,6 +156,10 @@ pub fn override_native(input: &str) -> Result<Css, CssParseError> {
 
 /// Allows dynamic reloading of a CSS file during an applications runtime, useful for
 /// changing the look & feel while the application is running.
+///
+/// The file is checked every `reload_interval`, but only re-parsed if it was modified.
+/// If it contains errors, the window keeps the last working style and displays the
+/// error in its top left corner until the file is fixed.
 #[cfg(all(debug_assertions, feature = "css_parser"))]
 pub fn hot_reload<P: Into<PathBuf>>(file_path: P, reload_interval: Duration) -> Box<dyn HotReloadHandler> {
     Box::new(azul_css_parser::HotReloader::new(file_path).with_reload_interval(reload_interval))
@@ -257,6 +338,31 @@ pub(crate) mod webrender_translate {
         }
     }
 
+    use webrender::api::MixBlendMode as WrMixBlendMode;
+    use azul_css::StyleMixBlendMode as CssMixBlendMode;
+
+    #[inline(always)]
+    pub fn wr_translate_mix_blend_mode(input: CssMixBlendMode) -> WrMixBlendMode {
+        match input {
+            CssMixBlendMode::Normal => WrMixBlendMode::Normal,
+            CssMixBlendMode::Multiply => WrMixBlendMode::Multiply,
+            CssMixBlendMode::Screen => WrMixBlendMode::Screen,
+            CssMixBlendMode::Overlay => WrMixBlendMode::Overlay,
+            CssMixBlendMode::Darken => WrMixBlendMode::Darken,
+            CssMixBlendMode::Lighten => WrMixBlendMode::Lighten,
+            CssMixBlendMode::ColorDodge => WrMixBlendMode::ColorDodge,
+            CssMixBlendMode::ColorBurn => WrMixBlendMode::ColorBurn,
+            CssMixBlendMode::HardLight => WrMixBlendMode::HardLight,
+            CssMixBlendMode::SoftLight => WrMixBlendMode::SoftLight,
+            CssMixBlendMode::Difference => WrMixBlendMode::Difference,
+            CssMixBlendMode::Exclusion => WrMixBlendMode::Exclusion,
+            CssMixBlendMode::Hue => WrMixBlendMode::Hue,
+            CssMixBlendMode::Saturation => WrMixBlendMode::Saturation,
+            CssMixBlendMode::Color => WrMixBlendMode::Color,
+            CssMixBlendMode::Luminosity => WrMixBlendMode::Luminosity,
+        }
+    }
+
     use azul_css::StyleCursor as CssCursor;
     use glium::glutin::MouseCursor as WinitCursor;
 
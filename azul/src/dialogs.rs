@@ -1,3 +1,4 @@
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
 pub use tinyfiledialogs::{MessageBoxIcon, DefaultColorValue};
 
 /// Ok or cancel result, returned from the `msg_box_ok_cancel` function
@@ -133,6 +134,148 @@ pub fn color_picker_dialog(title: &str, default_value: Option<ColorValue>)
     ::tinyfiledialogs::color_chooser_dialog(title, default)
 }
 
+/// Group of file extensions with a description, i.e. `FileFilter::new("Images", &["png", "jpg"])`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileFilter {
+    /// Description of the file type, i.e. "Images"
+    pub description: String,
+    /// File extensions without the leading dot, i.e. `"png"`
+    pub extensions: Vec<String>,
+}
+
+impl FileFilter {
+    pub fn new<S: Into<String>>(description: S, extensions: &[&str]) -> Self {
+        Self {
+            description: description.into(),
+            extensions: extensions.iter().map(|extension| extension.trim_start_matches('.').to_string()).collect(),
+        }
+    }
+}
+
+/// Builder for the native file and folder dialogs, returns the selected paths as `PathBuf`s:
+///
+/// ```rust,no_run
+/// # use azul::dialogs::{FileDialog, FileFilter};
+/// let images = FileDialog::new()
+///     .title("Import images")
+///     .default_path("/home/user/Pictures")
+///     .filter(FileFilter::new("Images", &["png", "jpg", "jpeg"]))
+///     .filter(FileFilter::new("Vector graphics", &["svg"]))
+///     .open_files();
+/// ```
+///
+/// **NOTE**: The dialogs are shown via tinyfiledialogs, which can only show one filter
+/// group: only the files of the **first** filter group can be selected, the other
+/// groups are kept (see `get_filters`), but not shown.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct FileDialog {
+    title: Option<String>,
+    default_path: Option<PathBuf>,
+    filters: Vec<FileFilter>,
+}
+
+impl FileDialog {
+
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// File or directory that the dialog starts in
+    #[inline]
+    pub fn default_path<P: Into<PathBuf>>(mut self, default_path: P) -> Self {
+        self.default_path = Some(default_path.into());
+        self
+    }
+
+    /// Adds a filter group, the groups are shown in the order in which they were added.
+    /// Note that the current dialogs only show the first group, see `FileDialog`.
+    #[inline]
+    pub fn filter(mut self, filter: FileFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Returns all filter groups, in the order in which they were added
+    #[inline]
+    pub fn get_filters(&self) -> &[FileFilter] {
+        &self.filters
+    }
+
+    /// Selects a single file, returns `None` if the user canceled the dialog
+    pub fn open_file(&self) -> Option<PathBuf> {
+        let patterns = self.get_filter_patterns();
+        let patterns = patterns.iter().map(|p| p.as_str()).collect::<Vec<_>>();
+        let filter = self.get_shown_filter().map(|filter| (&patterns[..], filter.description.as_str()));
+        ::tinyfiledialogs::open_file_dialog(self.get_title("Open File"), &self.get_default_path(), filter)
+            .map(PathBuf::from)
+    }
+
+    /// Selects one or more files, returns `None` if the user canceled the dialog
+    pub fn open_files(&self) -> Option<Vec<PathBuf>> {
+        let patterns = self.get_filter_patterns();
+        let patterns = patterns.iter().map(|p| p.as_str()).collect::<Vec<_>>();
+        let filter = self.get_shown_filter().map(|filter| (&patterns[..], filter.description.as_str()));
+        ::tinyfiledialogs::open_file_dialog_multi(self.get_title("Open Files"), &self.get_default_path(), filter)
+            .map(|paths| paths.into_iter().map(PathBuf::from).collect())
+    }
+
+    /// Selects a directory, returns `None` if the user canceled the dialog. The filters are ignored.
+    pub fn pick_folder(&self) -> Option<PathBuf> {
+        ::tinyfiledialogs::select_folder_dialog(self.get_title("Open Folder"), &self.get_default_path())
+            .map(PathBuf::from)
+    }
+
+    /// Selects the path to save a file to, returns `None` if the user canceled the dialog
+    pub fn save_file(&self) -> Option<PathBuf> {
+        let patterns = self.get_filter_patterns();
+        let patterns = patterns.iter().map(|p| p.as_str()).collect::<Vec<_>>();
+        let path = match self.get_shown_filter() {
+            Some(filter) => ::tinyfiledialogs::save_file_dialog_with_filter(self.get_title("Save File"), &self.get_default_path(), &patterns, &filter.description),
+            None => ::tinyfiledialogs::save_file_dialog(self.get_title("Save File"), &self.get_default_path()),
+        };
+        path.map(PathBuf::from)
+    }
+
+    /// Returns the filter group that the dialogs show (tinyfiledialogs only supports one group)
+    fn get_shown_filter(&self) -> Option<&FileFilter> {
+        self.filters.first()
+    }
+
+    /// Returns the "*.extension" patterns of the shown filter group
+    fn get_filter_patterns(&self) -> Vec<String> {
+        self.get_shown_filter().iter()
+            .flat_map(|filter| filter.extensions.iter().map(|extension| format!("*.{}", extension)))
+            .collect()
+    }
+
+    fn get_title<'a>(&'a self, default: &'a str) -> &'a str {
+        self.title.as_ref().map(|title| title.as_str()).unwrap_or(default)
+    }
+
+    /// The dialogs only open a directory if the path ends with a path separator
+    fn get_default_path(&self) -> String {
+        match &self.default_path {
+            Some(path) => get_dialog_path(path, path.is_dir()),
+            None => String::new(),
+        }
+    }
+}
+
+fn get_dialog_path(path: &Path, is_dir: bool) -> String {
+    let mut path_string = path.to_string_lossy().into_owned();
+    if is_dir && !path_string.ends_with(MAIN_SEPARATOR) {
+        path_string.push(MAIN_SEPARATOR);
+    }
+    path_string
+}
+
 /// Open a single file, returns `None` if the user canceled the dialog.
 ///
 /// Filters are the file extensions, i.e. `Some(&["doc", "docx"])` to only allow
@@ -162,7 +305,7 @@ pub fn open_multiple_files_dialog(default_path: Option<&str>, filter_list: Optio
 {
     let filter_list = filter_list.map(|f| (f, ""));
     let path = default_path.unwrap_or("");
-    ::tinyfiledialogs::open_file_dialog_multi("Open Folder", path, filter_list)
+    ::tinyfiledialogs::open_file_dialog_multi("Open Files", path, filter_list)
 }
 
 /// Opens a save file dialog, returns `None` if the user canceled the dialog
@@ -179,3 +322,22 @@ pub fn save_file_dialog(default_path: Option<&str>)
 // - Page setup dialog
 // - Print dialog
 // - Print property dialog
+
+#[test]
+fn test_file_dialog_filters() {
+    assert!(FileDialog::new().get_filter_patterns().is_empty());
+
+    // Both groups are kept, the dialogs show the first one
+    let dialog = FileDialog::new()
+        .filter(FileFilter::new("PNG images", &["png"]))
+        .filter(FileFilter::new("JPEG images", &[".jpg", "jpeg"]));
+    assert_eq!(dialog.get_filters(), &[
+        FileFilter::new("PNG images", &["png"]),
+        FileFilter::new("JPEG images", &["jpg", "jpeg"]),
+    ]);
+    assert_eq!(dialog.get_filter_patterns(), vec!["*.png"]);
+
+    let separator = MAIN_SEPARATOR.to_string();
+    assert_eq!(get_dialog_path(Path::new("docs"), true), format!("docs{}", separator));
+    assert_eq!(get_dialog_path(Path::new("docs"), false), "docs");
+}
@@ -2,9 +2,14 @@
 #![allow(dead_code)]
 
 use std::{collections::BTreeMap, marker::PhantomData};
+use webrender::api::{LayoutRect, LayoutPoint};
 use {
-    id_tree::{NodeId, NodeHierarchy},
-    dom::{Dom, NodeData},
+    FastHashMap,
+    id_tree::{NodeId, NodeHierarchy, NodeDataContainer},
+    dom::{Dom, NodeData, HitTestBehavior},
+    ui_solver::PositionedRectangle,
+    animation::PresenceAnimations,
+    app_resources::AppResources,
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -144,4 +149,427 @@ pub(crate) fn diff_dom_tree<T>(old: &Dom<T>, new:Dom<T>) -> DomDiff {
     // depth -> parents (in order) -> [leaf children]
 
     DomDiff::default()
-}
\ No newline at end of file
+}
+
+/// For every node of the `new` DOM, returns the node of the `old` DOM that it corresponds to
+/// (`None` for added nodes), so that per-node state can be carried over to the next frame.
+///
+/// Starting at the roots, the children of two matched nodes are matched by their key
+/// (see `Dom::with_key`), children without a key by their index among the unkeyed siblings.
+/// Inserting a keyed item at the top of a list therefore doesn't shift the following items.
+/// Keys that occur more than once among the old siblings are ambiguous and never matched,
+/// if a key occurs more than once among the new siblings, only the first node is matched.
+pub(crate) fn match_nodes_by_key<T>(
+    old_hierarchy: &NodeHierarchy,
+    old_data: &NodeDataContainer<NodeData<T>>,
+    old_root: NodeId,
+    new_hierarchy: &NodeHierarchy,
+    new_data: &NodeDataContainer<NodeData<T>>,
+    new_root: NodeId,
+) -> NodeDataContainer<Option<NodeId>> {
+
+    let mut matched = NodeDataContainer::new(vec![None; new_hierarchy.len()]);
+
+    if old_hierarchy.len() == 0 || new_hierarchy.len() == 0 {
+        return matched;
+    }
+
+    matched[new_root] = Some(old_root);
+
+    // Parents are visited before their children
+    for new_parent in new_root.descendants(new_hierarchy) {
+
+        let old_parent = match matched[new_parent] {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let mut old_keyed = FastHashMap::default();
+        let mut duplicate_keys = Vec::new();
+        let mut old_unkeyed = Vec::new();
+
+        for old_child in old_parent.children(old_hierarchy) {
+            match old_data[old_child].key {
+                Some(key) => if old_keyed.insert(key, old_child).is_some() { duplicate_keys.push(key); },
+                None => old_unkeyed.push(old_child),
+            }
+        }
+
+        for key in duplicate_keys {
+            old_keyed.remove(&key);
+        }
+
+        let mut old_unkeyed = old_unkeyed.into_iter();
+
+        for new_child in new_parent.children(new_hierarchy) {
+            matched[new_child] = match new_data[new_child].key {
+                Some(key) => old_keyed.remove(&key),
+                None => old_unkeyed.next(),
+            };
+        }
+    }
+
+    matched
+}
+
+/// Returns the node of the `new` DOM for every node of the `old` DOM,
+/// i.e. the inverse of `match_nodes_by_key` (`None` for removed nodes)
+fn invert_matched_nodes(matched_nodes: &NodeDataContainer<Option<NodeId>>, old_len: usize) -> NodeDataContainer<Option<NodeId>> {
+    let mut old_to_new = NodeDataContainer::new(vec![None; old_len]);
+    for new_node in matched_nodes.linear_iter() {
+        if let Some(old_node) = matched_nodes[new_node] {
+            old_to_new[old_node] = Some(new_node);
+        }
+    }
+    old_to_new
+}
+
+/// Returns the ID that the paint property overrides of the node are stored under (see
+/// `AppResources::set_paint_property_override`), i.e. the first ID of the node. Only nodes
+/// with an ID can be animated by the layout transitions and the presence animations.
+fn get_override_key<T>(node_data: &NodeData<T>) -> Option<&str> {
+    node_data.ids.first().map(|id| id.as_str())
+}
+
+/// Solved rectangles of all nodes of a frame, so that the rectangle of a node can be
+/// compared with the rectangle of the same node in the next frame. The nodes of two
+/// frames are matched with `match_nodes_by_key`, so the items of a reordered list
+/// need a key (see `Dom::with_key`) to be recognized at their new position.
+///
+/// The rectangles are relative to the nearest ancestor with an ID (i.e. the nearest ancestor
+/// that is animated itself), so that a moved parent doesn't count as a movement of all its children.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct KeyedRects {
+    rects: NodeDataContainer<LayoutRect>,
+}
+
+impl Default for KeyedRects {
+    fn default() -> Self {
+        Self { rects: NodeDataContainer::new(Vec::new()) }
+    }
+}
+
+/// A node with an ID that changed its size or position between two frames
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct KeyedRectChange {
+    /// ID of the node, see `get_override_key`
+    pub(crate) key: String,
+    pub(crate) old: LayoutRect,
+    pub(crate) new: LayoutRect,
+}
+
+impl KeyedRects {
+
+    pub(crate) fn new<T>(
+        node_hierarchy: &NodeHierarchy,
+        node_data: &NodeDataContainer<NodeData<T>>,
+        rects: &NodeDataContainer<PositionedRectangle>,
+    ) -> Self {
+        Self {
+            rects: rects.transform(|positioned_rect, node_id| {
+                let mut rect = positioned_rect.bounds;
+                let mut parent = node_hierarchy[node_id].parent;
+                while let Some(parent_id) = parent {
+                    if get_override_key(&node_data[parent_id]).is_some() {
+                        let parent_origin = rects[parent_id].bounds.origin;
+                        rect.origin = LayoutPoint::new(rect.origin.x - parent_origin.x, rect.origin.y - parent_origin.y);
+                        break;
+                    }
+                    parent = node_hierarchy[parent_id].parent;
+                }
+                rect
+            }),
+        }
+    }
+
+    /// Returns all nodes with an ID that exist in both frames, but whose rectangle changed.
+    /// `matched_nodes` maps the nodes of the `new` frame to the nodes of this frame (see
+    /// `match_nodes_by_key`). Added or removed nodes are not part of the result, neither
+    /// are nodes whose ID isn't unique (they can't be animated independently).
+    pub(crate) fn diff<T>(
+        &self,
+        new: &Self,
+        new_data: &NodeDataContainer<NodeData<T>>,
+        matched_nodes: &NodeDataContainer<Option<NodeId>>,
+    ) -> Vec<KeyedRectChange> {
+
+        let mut key_counts = FastHashMap::default();
+        for node_data in new_data.iter() {
+            if let Some(key) = get_override_key(node_data) {
+                *key_counts.entry(key).or_insert(0_usize) += 1;
+            }
+        }
+
+        matched_nodes.linear_iter().filter_map(|new_node| {
+            let old_node = matched_nodes[new_node]?;
+            let key = get_override_key(&new_data[new_node]).filter(|key| key_counts[key] == 1)?;
+            let old_rect = *self.rects.get(old_node)?;
+            let new_rect = *new.rects.get(new_node)?;
+            if old_rect == new_rect {
+                None
+            } else {
+                Some(KeyedRectChange { key: key.to_string(), old: old_rect, new: new_rect })
+            }
+        }).collect()
+    }
+}
+
+/// A node that was removed from the DOM, but is kept (without any callbacks) until its
+/// exit animation has finished
+pub(crate) struct RetainedNode<T> {
+    /// ID of the node, see `get_override_key`
+    key: String,
+    /// Root of the retained subtree in the DOM of the last frame
+    node_id: NodeId,
+    /// Nearest ancestor (in the DOM of the last frame) that wasn't removed, the
+    /// subtree is re-inserted below the node that the ancestor is matched to
+    anchor: NodeId,
+    /// Indices of the children on the way from the anchor down to the node
+    path: Vec<usize>,
+    dom: Dom<T>,
+}
+
+/// Compares the nodes of the last frame with the nodes of the new DOM (matched by
+/// `match_nodes_by_key`), starts the enter animations of all added nodes and the exit
+/// animations of all removed nodes. Removed nodes are re-inserted into the `new` DOM
+/// until their exit animation has finished.
+pub(crate) fn update_presence_animations<T>(
+    old: &Dom<T>,
+    new: &mut Dom<T>,
+    matched_nodes: &NodeDataContainer<Option<NodeId>>,
+    retained: &mut Vec<RetainedNode<T>>,
+    animations: &mut PresenceAnimations,
+    app_resources: &mut AppResources,
+) {
+    let old_hierarchy = &old.arena.node_layout;
+    let old_to_new = invert_matched_nodes(matched_nodes, old.arena.len());
+
+    let was_retained = retained.iter().map(|node| node.node_id).collect::<Vec<NodeId>>();
+
+    // Drop all nodes that were added again (i.e. a node of the new DOM is
+    // matched to the retained node) or whose exit animation has finished
+    retained.retain(|node| {
+        if old_to_new[node.node_id].is_some() {
+            false
+        } else if animations.is_exiting(&node.key) {
+            true
+        } else {
+            animations.stop(&node.key, app_resources);
+            false
+        }
+    });
+
+    for new_node in matched_nodes.linear_iter() {
+
+        let node_data = &new.arena.node_data[new_node];
+
+        let key = match get_override_key(node_data) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        // Added nodes aren't matched to any node of the last frame,
+        // nodes that are added again while exiting are matched to their retained node
+        let is_exiting = match matched_nodes[new_node] {
+            None => false,
+            Some(old_node) if was_retained.contains(&old_node) => true,
+            Some(_) => continue,
+        };
+
+        match node_data.enter_animation {
+            Some(animation) => animations.start_enter(key, animation, app_resources),
+            None => if is_exiting { animations.stop(key, app_resources); },
+        }
+    }
+
+    let is_removed_with_exit_animation = |node_id: NodeId| {
+        let node_data = &old.arena.node_data[node_id];
+        old_to_new[node_id].is_none() && node_data.exit_animation.is_some() && get_override_key(node_data).is_some()
+    };
+
+    for old_node in old_hierarchy.linear_iter() {
+
+        if old_to_new[old_node].is_some() {
+            continue;
+        }
+
+        // The retained nodes of the last frame are still exiting
+        if old_node.ancestors(old_hierarchy).any(|ancestor| was_retained.contains(&ancestor)) {
+            continue;
+        }
+
+        let node_data = &old.arena.node_data[old_node];
+        let (key, animation) = match (get_override_key(node_data), node_data.exit_animation) {
+            (Some(key), Some(animation)) => (key, animation),
+            _ => continue,
+        };
+
+        // Nodes inside of a removed subtree are animated together with the subtree
+        if old_node.ancestors(old_hierarchy).skip(1).any(&is_removed_with_exit_animation) {
+            continue;
+        }
+
+        let mut path = Vec::new();
+        let mut anchor = None;
+        let mut current = old_node;
+        while let Some(parent) = old_hierarchy[current].parent {
+            path.push(old_hierarchy.get_index_in_parent(current));
+            if old_to_new[parent].is_some() {
+                anchor = Some(parent);
+                break;
+            }
+            current = parent;
+        }
+        path.reverse();
+
+        // The root node can't be retained
+        let anchor = match anchor {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let mut dom = old.get_subtree(old_node);
+        for node_data in dom.arena.node_data.iter_mut() {
+            node_data.callbacks.clear();
+            node_data.default_callback_ids.clear();
+            node_data.tab_index = None;
+            node_data.hit_test = HitTestBehavior::None;
+        }
+
+        animations.start_exit(key, animation, app_resources);
+        retained.push(RetainedNode { key: key.to_string(), node_id: old_node, anchor, path, dom });
+    }
+
+    // Re-insert the retained nodes at their old position (if the anchor still exists). The node
+    // IDs are updated to the new DOM, which is the DOM of the last frame in the next frame.
+    let mut still_retained = Vec::new();
+
+    for mut node in retained.drain(..) {
+
+        let anchor = match old_to_new[node.anchor] {
+            Some(s) => s,
+            None => {
+                animations.stop(&node.key, app_resources);
+                continue;
+            },
+        };
+
+        let mut parent = anchor;
+        let (index_in_parent, path_to_parent) = node.path.split_last().unwrap();
+        for index in path_to_parent {
+            match parent.children(&new.arena.node_layout).nth(*index) {
+                Some(child) => parent = child,
+                None => break,
+            }
+        }
+
+        if let Some(node_id) = new.insert_child(parent, *index_in_parent, node.dom.get_subtree(node.dom.root)) {
+            node.node_id = node_id;
+            node.anchor = anchor;
+            still_retained.push(node);
+        }
+    }
+
+    *retained = still_retained;
+}
+
+#[test]
+fn test_match_nodes_by_key() {
+
+    struct DataModel;
+
+    // 0: root, 1: key 1, 2: label, 3: key 2, 4: unkeyed label
+    let old: Dom<DataModel> = Dom::div()
+        .with_child(Dom::div().with_key(1).with_child(Dom::label("a")))
+        .with_child(Dom::div().with_key(2))
+        .with_child(Dom::label("b"));
+
+    // 0: root, 1: key 3, 2: key 2, 3: key 1, 4: label, 5: unkeyed label
+    let new: Dom<DataModel> = Dom::div()
+        .with_child(Dom::div().with_key(3))
+        .with_child(Dom::div().with_key(2))
+        .with_child(Dom::div().with_key(1).with_child(Dom::label("a")))
+        .with_child(Dom::label("b"));
+
+    let matched = match_nodes_by_key(
+        &old.arena.node_layout, &old.arena.node_data, old.root,
+        &new.arena.node_layout, &new.arena.node_data, new.root,
+    );
+
+    let expected = [Some(0), None, Some(3), Some(1), Some(2), Some(4)];
+    assert_eq!(matched.internal, expected.iter().map(|id| id.map(NodeId::new)).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_keyed_rects_diff() {
+
+    use webrender::api::LayoutSize;
+
+    struct DataModel;
+
+    let rect = |y| LayoutRect::new(LayoutPoint::new(0.0, y), LayoutSize::new(100.0, 50.0));
+
+    // The two items swap places, the root doesn't have an ID and isn't animated
+    let old: Dom<DataModel> = Dom::div()
+        .with_child(Dom::div().with_id("a").with_key(1))
+        .with_child(Dom::div().with_id("b").with_key(2));
+    let new: Dom<DataModel> = Dom::div()
+        .with_child(Dom::div().with_id("b").with_key(2))
+        .with_child(Dom::div().with_id("a").with_key(1));
+
+    let old_rects = KeyedRects { rects: NodeDataContainer::new(vec![rect(0.0), rect(0.0), rect(50.0)]) };
+    let new_rects = KeyedRects { rects: NodeDataContainer::new(vec![rect(50.0), rect(0.0), rect(50.0)]) };
+
+    let matched = match_nodes_by_key(
+        &old.arena.node_layout, &old.arena.node_data, old.root,
+        &new.arena.node_layout, &new.arena.node_data, new.root,
+    );
+
+    assert_eq!(old_rects.diff(&new_rects, &new.arena.node_data, &matched), vec![
+        KeyedRectChange { key: "b".into(), old: rect(50.0), new: rect(0.0) },
+        KeyedRectChange { key: "a".into(), old: rect(0.0), new: rect(50.0) },
+    ]);
+}
+
+#[test]
+fn test_presence_animations_match_nodes_by_key() {
+
+    use std::time::Duration;
+    use animation::PresenceAnimation;
+
+    struct DataModel;
+
+    let fade = PresenceAnimation::fade(Duration::from_secs(10));
+    let item = |id: &'static str, key| Dom::<DataModel>::div().with_id(id).with_key(key)
+        .with_enter_animation(fade).with_exit_animation(fade);
+    let match_nodes = |old: &Dom<DataModel>, new: &Dom<DataModel>| match_nodes_by_key(
+        &old.arena.node_layout, &old.arena.node_data, old.root,
+        &new.arena.node_layout, &new.arena.node_data, new.root,
+    );
+
+    let mut retained = Vec::new();
+    let mut animations = PresenceAnimations::default();
+    let mut app_resources = AppResources::new();
+
+    // Frame 1: "a" is removed, it is kept at its old position until it has faded out
+    let old = Dom::div().with_child(item("a", 1)).with_child(item("b", 2));
+    let mut new = Dom::div().with_child(item("b", 2));
+    let matched = match_nodes(&old, &new);
+    update_presence_animations(&old, &mut new, &matched, &mut retained, &mut animations, &mut app_resources);
+
+    assert_eq!(retained.len(), 1);
+    assert!(animations.is_exiting("a"));
+    assert!(!animations.is_exiting("b"));
+    let first_child = new.arena.node_layout[new.root].first_child.unwrap();
+    assert_eq!(new.arena.node_data[first_child].ids.first().map(|id| id.as_str()), Some("a"));
+
+    // Frame 2: "a" is added again while exiting, so it is matched to the retained node
+    let old = new;
+    let mut new = Dom::div().with_child(item("a", 1)).with_child(item("b", 2));
+    let matched = match_nodes(&old, &new);
+    update_presence_animations(&old, &mut new, &matched, &mut retained, &mut animations, &mut app_resources);
+
+    assert!(retained.is_empty());
+    assert!(!animations.is_exiting("a"));
+    assert_eq!(new.arena.len(), 3);
+}
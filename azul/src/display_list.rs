@@ -2,10 +2,13 @@
 
 use std::{
     fmt,
+    mem,
+    cmp::Ordering,
     sync::{Arc, Mutex},
     collections::BTreeMap,
 };
-use euclid::{TypedRect, TypedSize2D};
+use euclid::{TypedRect, TypedSize2D, SideOffsets2D};
+use glium::glutin::dpi::{LogicalSize, LogicalPosition};
 use webrender::api::{
     LayoutPixel, DisplayListBuilder, PrimitiveInfo, GradientStop,
     ColorF, PipelineId, Epoch, ImageData, ImageDescriptor,
@@ -14,34 +17,48 @@ use webrender::api::{
     ComplexClipRegion, LayoutPrimitiveInfo, ExternalImageId,
     ExternalImageData, ImageFormat, ExternalImageType, TextureTarget,
     ImageRendering, AlphaType, FontInstanceFlags, FontRenderMode,
+    LayoutTransform, LayoutVector3D, PropertyBinding, TransformStyle,
+    MixBlendMode, GlyphRasterSpace, FilterOp, StickyOffsetBounds, LayoutVector2D, GlyphInstance,
+    ScrollSensitivity, LineOrientation, LineStyle, PropertyBindingKey,
 };
 use azul_css::{
-    Css, LayoutPosition,CssProperty, LayoutOverflow,
+    Css, CssViewport, LayoutPosition,CssProperty, LayoutOverflow, Overflow,
     StyleBorderRadius, LayoutMargin, LayoutPadding, BoxShadowClipMode,
     StyleTextColor, StyleBackground, StyleBoxShadow,
     StyleBackgroundSize, StyleBackgroundRepeat, StyleBorder, BoxShadowPreDisplayItem,
-    RectStyle, RectLayout, ColorU as StyleColorU, DynamicCssPropertyDefault,
+    RectStyle, RectLayout, ColorU as StyleColorU, DynamicCssPropertyDefault, LayoutCalc, GridTrackSize,
+    StyleTransform, StyleTransformOrigin, StyleTransformStyle,
+    StyleTextDecoration, StyleTextDecorationStyle, StylePointerEvents, StyleVisibility,
+    StyleMixBlendMode, StyleObjectFit, StyleOutline, StyleOutlineOffset, PixelValue, LayoutRowGap, LayoutColumnGap,
+    LayoutPaddingInline, LayoutMarginInline, StyleBorderInline, ScrollbarInfo,
+    ScrollSnapAlignment, ScrollSnapStrictness, CssPropertyType,
 };
 use {
     FastHashMap,
     app_resources::AppResources,
-    callbacks::{IFrameCallback, GlTextureCallback, HidpiAdjustedBounds, StackCheckedPointer},
+    callbacks::{IFrameCallback, GlTextureCallback, HidpiAdjustedBounds, StackCheckedPointer, ComputedStyle},
     ui_state::UiState,
-    ui_description::{UiDescription, StyledNode},
+    ui_description::{UiDescription, StyledNode, ScrollbarStyledNode},
     id_tree::{NodeDataContainer, NodeId, NodeHierarchy},
     dom::{
-        NodeData, ScrollTagId, DomHash, DomString, new_scroll_tag_id,
+        NodeData, ScrollTagId, DomHash, DomString, HitTestBehavior, new_scroll_tag_id,
         NodeType::{self, Div, Text, Image, GlTexture, IFrame, Label},
     },
-    ui_solver::{do_the_layout, LayoutResult, PositionedRectangle},
-    app_resources::ImageId,
+    ui_solver::{do_the_layout, LayoutResult, LayoutCacheKey, PositionedRectangle},
+    text_layout::{Words, ScaledWords, LayoutedGlyphs},
+    app_resources::{ImageId, PaintPropertyOverride, PaintTransform},
     compositor::new_opengl_texture_id,
-    window::{Window, FakeWindow, ScrollStates},
+    window::{Window, FakeWindow, ScrollStates, TextSearch, TextSearchMatch, WindowShadow},
     callbacks::LayoutInfo,
-    window_state::WindowSize,
+    window_state::{WindowSize, HidpiRoundingPolicy},
 };
 
 const DEFAULT_FONT_COLOR: StyleTextColor = StyleTextColor(StyleColorU { r: 0, b: 0, g: 0, a: 255 });
+const TRANSPARENT_BG: StyleColorU = StyleColorU { r: 0, g: 0, b: 0, a: 0 };
+const SEARCH_HIGHLIGHT_COLOR: StyleColorU = StyleColorU { r: 255, g: 235, b: 59, a: 160 };
+const SEARCH_ACTIVE_HIGHLIGHT_COLOR: StyleColorU = StyleColorU { r: 255, g: 152, b: 0, a: 200 };
+/// Minimum length of a scrollbar thumb, so that the thumb stays visible for very long content
+const MIN_SCROLLBAR_THUMB_LENGTH: f32 = 20.0;
 
 pub(crate) struct DisplayList<'a, T: 'a> {
     pub(crate) ui_descr: &'a UiDescription<T>,
@@ -70,12 +87,30 @@ pub(crate) struct DisplayRectangle<'a> {
     pub(crate) style: RectStyle,
     /// The layout properties of the node, parsed
     pub(crate) layout: RectLayout,
+    /// `calc()` expressions of the dimension properties, evaluated during the layout
+    pub(crate) layout_calc: Vec<LayoutCalc>,
+    /// `grid-template-columns` of a `display: grid` node (empty = one implicit column)
+    pub(crate) grid_template_columns: Vec<GridTrackSize>,
+    /// `grid-template-rows` of a `display: grid` node (empty = implicit rows only)
+    pub(crate) grid_template_rows: Vec<GridTrackSize>,
+    /// Style of the scrollbars from the `::scrollbar`, `::track` and `::thumb`
+    /// pseudo-elements, `None` uses the default scrollbar style
+    pub(crate) scrollbar: Option<ScrollbarInfo>,
 }
 
 impl<'a> DisplayRectangle<'a> {
     #[inline]
     pub fn new(tag: Option<u64>, styled_node: &'a StyledNode) -> Self {
-        Self { tag, styled_node, style: RectStyle::default(), layout: RectLayout::default() }
+        Self {
+            tag,
+            styled_node,
+            style: RectStyle::default(),
+            layout: RectLayout::default(),
+            layout_calc: Vec::new(),
+            grid_template_columns: Vec::new(),
+            grid_template_rows: Vec::new(),
+            scrollbar: None,
+        }
     }
 }
 
@@ -93,6 +128,7 @@ impl<'a, T: 'a> DisplayList<'a, T> {
             let tag = ui_state.node_ids_to_tag_ids.get(&node_id).map(|tag| *tag);
             let mut rect = DisplayRectangle::new(tag, style);
             populate_css_properties(&mut rect, node_id, &ui_description.dynamic_css_overrides);
+            rect.scrollbar = ui_description.scrollbar_styles.get(&node_id).map(get_scrollbar_info);
             rect
         });
 
@@ -111,10 +147,6 @@ impl<'a, T: 'a> DisplayList<'a, T> {
         app_resources: &mut AppResources
     ) -> (DisplayListBuilder, ScrolledNodes, LayoutResult) {
 
-        use glium::glutin::dpi::LogicalSize;
-
-        let mut resource_updates = Vec::<ResourceUpdate>::new();
-
         let arena = &self.ui_descr.ui_descr_arena;
         let node_hierarchy = &arena.node_layout;
         let node_data = &arena.node_data;
@@ -133,21 +165,65 @@ impl<'a, T: 'a> DisplayList<'a, T> {
         //      - Insert the new font keys and image keys into the render API
         //      - Scan all IFrameCallbacks, generate the DomID for each callback
         //      - Repeat while number_of_iframe_callbacks != 0
-        app_resources.add_fonts_and_images(&self);
-
-        let window_size = window.state.size.get_reverse_logical_size();
-        let layout_result = do_the_layout(
+        //
+        // Fonts from `@font-face` rules need a FontId before the DOM can be scanned for font keys
+        app_resources.add_css_font_faces(&window.css.font_faces);
+        app_resources.add_fonts_and_images(&self, window.state.size.hidpi_factor as f32);
+
+        let window_size = window.state.size.get_layout_size();
+        let rect_size = LayoutSize::new(window_size.width as f32, window_size.height as f32);
+        let rect_offset = LayoutPoint::new(0.0, 0.0);
+        let hidpi_factor = window.state.size.hidpi_factor as f32;
+        let solve_min_content_size = window.state.size.min_dimensions_from_layout && window.state.size.scale_to_fit.is_none();
+        let layout_cache_key = LayoutCacheKey::new(self.ui_descr.layout_generation, node_data, &self.rectangles, &*app_resources, hidpi_factor, rect_size, rect_offset, solve_min_content_size);
+        let layout_result = fake_window.layout_cache.get_or_layout(layout_cache_key, || do_the_layout(
             node_hierarchy,
             node_data,
             &self.rectangles,
             &*app_resources,
-            LayoutSize::new(window_size.width as f32, window_size.height as f32),
-            LayoutPoint::new(0.0, 0.0),
-        );
+            hidpi_factor,
+            rect_size,
+            rect_offset,
+            solve_min_content_size,
+        ));
 
         // TODO: After the layout has been done, call all IFrameCallbacks and get and insert
         // their font keys / image keys
 
+        let (builder, scrollable_nodes) = self.build_display_list_builder(
+            app_data_access,
+            window,
+            fake_window,
+            app_resources,
+            &layout_result,
+        );
+
+        // The IFrames are laid out while the display list is built, so the
+        // unused layouts can only be dropped once the display list is done
+        fake_window.layout_cache.end_frame();
+
+        (builder, scrollable_nodes, layout_result)
+    }
+
+    /// Builds the display list from an already existing layout - this is used when only
+    /// the paint property overrides changed, so that the DOM doesn't need to be re-layouted
+    pub(crate) fn build_display_list_builder(
+        &self,
+        app_data_access: &mut Arc<Mutex<T>>,
+        window: &mut Window<T>,
+        fake_window: &mut FakeWindow<T>,
+        app_resources: &mut AppResources,
+        layout_result: &LayoutResult,
+    ) -> (DisplayListBuilder, ScrolledNodes) {
+
+        profile_scope!("display_list");
+
+        let arena = &self.ui_descr.ui_descr_arena;
+        let node_hierarchy = &arena.node_layout;
+        let node_data = &arena.node_data;
+        let window_size = window.state.size.get_layout_size();
+        let mut resource_updates = Vec::<ResourceUpdate>::new();
+
         let mut scrollable_nodes = get_nodes_that_need_scroll_clip(
             node_hierarchy, &self.rectangles, node_data, &layout_result.rects,
             &layout_result.node_depths, window.internal.pipeline_id
@@ -156,11 +232,50 @@ impl<'a, T: 'a> DisplayList<'a, T> {
         // Make sure unused scroll states are garbage collected.
         window.scroll_states.remove_unused_scroll_states();
 
+        // Search matches are re-collected while the text is pushed into the display list
+        if let Some(text_search) = &mut fake_window.text_search {
+            text_search.matches.clear();
+        }
+
+        fake_window.iframe_cache.begin_frame(
+            window.create_options.iframe_resize_preview.is_some(),
+            window.is_iframe_resize_preview_active(),
+        );
+
         let LogicalSize { width, height } = window.state.size.dimensions;
         let mut builder = DisplayListBuilder::with_capacity(window.internal.pipeline_id, TypedSize2D::new(width as f32, height as f32), self.rectangles.len());
 
         let rects_in_rendering_order = determine_rendering_order(node_hierarchy, &self.rectangles, &layout_result.rects);
 
+        // Preloaded glyphs are pushed first, so that the UI is drawn on top of them
+        push_glyph_preloads(&mut builder, app_resources);
+
+        // Undecorated windows with a client-side shadow: The framebuffer is cleared with a
+        // transparent color, the shadow is drawn into the margin around the window rect and
+        // everything else (including the background) is clipped to the rounded window rect
+        let mut window_rect = LayoutRect::new(LayoutPoint::new(0.0, 0.0), LayoutSize::new(width as f32, height as f32));
+        let window_shadow = window.create_options.window_shadow.filter(|_| window.state.size.shadow_margin > 0.0);
+        if let Some(shadow) = window_shadow {
+            let margin = window.state.size.shadow_margin as f32;
+            let content_size = window.state.size.get_content_size();
+            window_rect = LayoutRect::new(LayoutPoint::new(margin, margin), LayoutSize::new(content_size.width as f32, content_size.height as f32));
+            let background_color = window.state.background_color.unwrap_or(window.default_background_color);
+            push_window_shadow(&mut builder, window_rect, &shadow, background_color);
+        }
+
+        // The background image is stretched over the whole window (including
+        // the letterboxing bars in "kiosk mode"), so it has to be pushed first
+        if let Some(background_image) = window.state.background_image {
+            app_resources.add_image_for_current_frame(background_image);
+            push_image(&LayoutPrimitiveInfo::new(window_rect), &mut builder, app_resources, &background_image, window_rect.size);
+        }
+
+        // In "kiosk mode", the whole UI is scaled uniformly to fit the window
+        let content_transform = window.state.size.get_content_transform();
+        if let Some((scale, offset)) = content_transform {
+            push_scale_to_fit_stacking_context(&mut builder, window_size, scale, offset);
+        }
+
         push_rectangles_into_displaylist(
             window.internal.epoch,
             window.state.size,
@@ -173,7 +288,7 @@ impl<'a, T: 'a> DisplayList<'a, T> {
                 node_data,
                 display_rectangle_arena: &self.rectangles,
                 css: &window.css,
-                layout_result: &layout_result,
+                layout_result,
             },
             &mut DisplayListParametersMut {
                 app_data: app_data_access,
@@ -185,10 +300,107 @@ impl<'a, T: 'a> DisplayList<'a, T> {
             },
         );
 
-        (builder, scrollable_nodes, layout_result)
+        if content_transform.is_some() {
+            builder.pop_stacking_context();
+        }
+
+        if window_shadow.is_some() {
+            builder.pop_clip_id();
+        }
+
+        fake_window.iframe_cache.end_frame();
+
+        if let Some(text_search) = &mut fake_window.text_search {
+            let match_count = text_search.matches.len();
+            if text_search.active_match.map(|active| active >= match_count).unwrap_or(false) {
+                text_search.active_match = if match_count == 0 { None } else { Some(0) };
+            }
+            scroll_to_active_search_match(text_search, node_hierarchy, &scrollable_nodes, &mut window.scroll_states);
+        }
+
+        (builder, scrollable_nodes)
     }
 }
 
+/// Draws the glyphs of `AppResources::preload_glyphs` into the top left pixel of the window,
+/// with a practically invisible color, so that WebRender rasterizes them (it only rasterizes
+/// the glyphs of visible text, so the glyphs can't be drawn off-screen)
+fn push_glyph_preloads(builder: &mut DisplayListBuilder, app_resources: &mut AppResources) {
+
+    let top_left_pixel = LayoutRect::new(LayoutPoint::zero(), LayoutSize::new(1.0, 1.0));
+    let color = ColorF::new(0.0, 0.0, 0.0, 1.0 / 255.0);
+
+    for (font_instance_key, glyph_indices) in app_resources.take_glyphs_to_preload() {
+        let glyphs = glyph_indices.into_iter()
+            .map(|index| GlyphInstance { index, point: LayoutPoint::zero() })
+            .collect::<Vec<_>>();
+        builder.push_text(&LayoutPrimitiveInfo::new(top_left_pixel), &glyphs, font_instance_key, color, None);
+    }
+}
+
+/// Pushes the shadow of an undecorated window around the `window_rect` and the background
+/// color of the window, then pushes a clip with the rounded corners of the window (see
+/// `WindowShadow`). The clip has to be popped after the UI has been pushed.
+fn push_window_shadow(
+    builder: &mut DisplayListBuilder,
+    window_rect: LayoutRect,
+    shadow: &WindowShadow,
+    background_color: StyleColorU,
+) {
+    use css::webrender_translate::{wr_translate_color_u, wr_translate_box_shadow_clip_mode};
+
+    let radii = BorderRadius::uniform(shadow.corner_radius);
+    let content_size = builder.content_size();
+
+    builder.push_box_shadow(
+        &LayoutPrimitiveInfo::new(LayoutRect::new(LayoutPoint::zero(), content_size)),
+        window_rect,
+        LayoutVector2D::new(0.0, shadow.offset_y),
+        apply_gamma(wr_translate_color_u(shadow.color).into()),
+        shadow.blur_radius,
+        0.0,
+        radii,
+        wr_translate_box_shadow_clip_mode(BoxShadowClipMode::Outset),
+    );
+
+    let rounded_clip = ComplexClipRegion {
+        rect: window_rect,
+        radii,
+        mode: ClipMode::Clip,
+    };
+    let clip_id = builder.define_clip(window_rect, vec![rounded_clip], None);
+    builder.push_clip_id(clip_id);
+    push_rect(&LayoutPrimitiveInfo::new(window_rect), builder, &background_color);
+}
+
+/// Pushes a stacking context that scales the UI (laid out at `design_size`)
+/// by `scale` and moves it to `offset` (i.e. to center it in the window)
+fn push_scale_to_fit_stacking_context(
+    builder: &mut DisplayListBuilder,
+    design_size: LogicalSize,
+    scale: f64,
+    offset: LogicalPosition,
+) {
+    let scale = scale as f32;
+    let transform = LayoutTransform::create_scale(scale, scale, 1.0)
+        .post_translate(LayoutVector3D::new(offset.x as f32, offset.y as f32, 0.0));
+    let bounds = LayoutRect::new(
+        LayoutPoint::new(0.0, 0.0),
+        LayoutSize::new(design_size.width as f32, design_size.height as f32)
+    );
+
+    builder.push_stacking_context(
+        &LayoutPrimitiveInfo::new(bounds),
+        None,
+        Some(PropertyBinding::Value(transform)),
+        TransformStyle::Flat,
+        None,
+        MixBlendMode::Normal,
+        Vec::new(),
+        GlyphRasterSpace::Screen,
+    );
+}
+
 /// In order to render rectangles in the correct order, we have to group them together:
 /// As long as there are no position:absolute items, items are inserted in a parents-then-child order
 ///
@@ -382,23 +594,118 @@ pub(crate)  struct ScrolledNodes {
 #[derive(Debug, Clone)]
 pub(crate) struct OverflowingScrollNode {
     pub(crate) parent_rect: PositionedRectangle,
+    /// Rectangle of the scrollable content, including the `parent_rect`. In a direction
+    /// that can't be scrolled, the content rect has the same size as the `parent_rect`.
     pub(crate) child_rect: LayoutRect,
     pub(crate) parent_external_scroll_id: ExternalScrollId,
     pub(crate) parent_dom_hash: DomHash,
     pub(crate) scroll_tag_id: ScrollTagId,
+    /// Set if the node has a `scroll-snap-type`
+    pub(crate) snap: Option<ScrollSnap>,
 }
 
-/// Returns all node IDs where the children overflow the parent, together with the
+/// How far the scroll position can be from a snap position for `scroll-snap-type: proximity`
+/// to still snap, as a fraction of the size of the scroll frame
+const SCROLL_SNAP_PROXIMITY: f32 = 0.3;
+
+/// Scroll amounts that a node with `scroll-snap-type` snaps to, one for
+/// every child with a `scroll-snap-align` in a direction that snaps
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ScrollSnap {
+    pub(crate) strictness: ScrollSnapStrictness,
+    pub(crate) positions_x: Vec<f32>,
+    pub(crate) positions_y: Vec<f32>,
+}
+
+impl ScrollSnap {
+
+    /// Returns the scroll amount to snap to from the `current` scroll amount, or
+    /// `None` if no snap position is close enough (only for `proximity`)
+    pub(crate) fn get_target(&self, current: (f32, f32), frame_size: LayoutSize) -> Option<(f32, f32)> {
+
+        let strictness = self.strictness;
+        let closest = |positions: &[f32], current: f32, frame_size: f32| -> Option<f32> {
+            let closest = positions.iter().cloned().min_by(|a, b| {
+                (a - current).abs().partial_cmp(&(b - current).abs()).unwrap_or(Ordering::Equal)
+            })?;
+            match strictness {
+                ScrollSnapStrictness::Proximity if (closest - current).abs() > frame_size * SCROLL_SNAP_PROXIMITY => None,
+                _ => Some(closest),
+            }
+        };
+
+        let target_x = closest(&self.positions_x, current.0, frame_size.width);
+        let target_y = closest(&self.positions_y, current.1, frame_size.height);
+
+        if target_x.is_none() && target_y.is_none() {
+            None
+        } else {
+            Some((target_x.unwrap_or(current.0), target_y.unwrap_or(current.1)))
+        }
+    }
+}
+
+/// Returns the snap positions of the children of a node with a `scroll-snap-type`.
+/// The positions are scroll amounts, clamped to the `overflow` of the scroll frame.
+fn get_scroll_snap<'a>(
+    parent: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    display_list_rects: &NodeDataContainer<DisplayRectangle<'a>>,
+    layouted_rects: &NodeDataContainer<PositionedRectangle>,
+    overflow_x: f32,
+    overflow_y: f32,
+) -> Option<ScrollSnap> {
+
+    fn snap_position(alignment: ScrollSnapAlignment, child_start: f32, child_size: f32, frame_start: f32, frame_size: f32) -> Option<f32> {
+        match alignment {
+            ScrollSnapAlignment::None => None,
+            ScrollSnapAlignment::Start => Some(child_start - frame_start),
+            ScrollSnapAlignment::Center => Some((child_start + child_size / 2.0) - (frame_start + frame_size / 2.0)),
+            ScrollSnapAlignment::End => Some((child_start + child_size) - (frame_start + frame_size)),
+        }
+    }
+
+    let snap_type = display_list_rects[parent].layout.scroll_snap_type?;
+    let frame = layouted_rects[parent].bounds;
+
+    let mut positions_x = Vec::new();
+    let mut positions_y = Vec::new();
+
+    for child in parent.children(node_hierarchy) {
+        let align = match display_list_rects[child].layout.scroll_snap_align {
+            Some(s) => s,
+            None => continue,
+        };
+        let child_bounds = layouted_rects[child].bounds;
+        if snap_type.axis.snaps_horizontal() {
+            if let Some(x) = snap_position(align.horizontal, child_bounds.origin.x, child_bounds.size.width, frame.origin.x, frame.size.width) {
+                positions_x.push(x.max(0.0).min(overflow_x));
+            }
+        }
+        if snap_type.axis.snaps_vertical() {
+            if let Some(y) = snap_position(align.vertical, child_bounds.origin.y, child_bounds.size.height, frame.origin.y, frame.size.height) {
+                positions_y.push(y.max(0.0).min(overflow_y));
+            }
+        }
+    }
+
+    if positions_x.is_empty() && positions_y.is_empty() {
+        None
+    } else {
+        Some(ScrollSnap { strictness: snap_type.strictness, positions_x, positions_y })
+    }
+}
+
+/// Returns all node IDs that scroll their children, together with the
 /// `(parent_rect, child_rect)` - the child rect is the sum of the children.
 ///
-/// TODO: The performance of this function can be theoretically improved:
+/// A node scrolls its children if it has `overflow: scroll` or if it has `overflow: auto`
+/// and the children overflow the node. Since `auto` is the default value for `overflow`,
+/// it has to be set explicitly, otherwise every slightly overflowing node would scroll.
 ///
-/// - Unioning the rectangles is heavier than just looping through the children and
+/// TODO: The performance of this function can be theoretically improved:
+/// unioning the rectangles is heavier than just looping through the children and
 /// summing up their width / height / padding + margin.
-/// - Scroll nodes only need to be inserted if the parent doesn't have `overflow: hidden`
-/// activated
-/// - Overflow for X and Y needs to be tracked seperately (for overflow-x / overflow-y separation),
-/// so there we'd need to track in which direction the inner_rect is overflowing.
 fn get_nodes_that_need_scroll_clip<'a, T: 'a>(
     node_hierarchy: &NodeHierarchy,
     display_list_rects: &NodeDataContainer<DisplayRectangle<'a>>,
@@ -413,24 +720,43 @@ fn get_nodes_that_need_scroll_clip<'a, T: 'a>(
 
     for (_, parent) in parents {
 
-        let mut children_sum_rect = None;
+        let overflow = display_list_rects[*parent].layout.overflow.unwrap_or_default();
+        let scrolls_horizontal = overflow_scrolls(overflow.horizontal);
+        let scrolls_vertical = overflow_scrolls(overflow.vertical);
 
-        for child in parent.children(&node_hierarchy) {
-            let old = children_sum_rect.unwrap_or(LayoutRect::zero());
-            children_sum_rect = Some(old.union(&layouted_rects[child].bounds));
+        if scrolls_horizontal.is_none() && scrolls_vertical.is_none() {
+            continue;
         }
 
+        let children_sum_rect = parent.children(&node_hierarchy).fold(None, |sum: Option<LayoutRect>, child| {
+            let child_bounds = layouted_rects[child].bounds;
+            Some(sum.map(|sum| sum.union(&child_bounds)).unwrap_or(child_bounds))
+        });
+
         let children_sum_rect = match children_sum_rect {
             None => continue,
             Some(sum) => sum,
         };
 
         let parent_rect = layouted_rects.get(*parent).unwrap();
+        let bounds = parent_rect.bounds;
+        let content_rect = bounds.union(&children_sum_rect);
+
+        let is_overflowing_horizontal = content_rect.min_x() < bounds.min_x() || content_rect.max_x() > bounds.max_x();
+        let is_overflowing_vertical = content_rect.min_y() < bounds.min_y() || content_rect.max_y() > bounds.max_y();
 
-        if children_sum_rect.contains_rect(&parent_rect.bounds) {
+        let scrolls_horizontal = scrolls_horizontal == Some(Overflow::Scroll) || (scrolls_horizontal.is_some() && is_overflowing_horizontal);
+        let scrolls_vertical = scrolls_vertical == Some(Overflow::Scroll) || (scrolls_vertical.is_some() && is_overflowing_vertical);
+
+        if !scrolls_horizontal && !scrolls_vertical {
             continue;
         }
 
+        // The content can only be scrolled in the directions that have `overflow: auto / scroll`
+        let (min_x, max_x) = if scrolls_horizontal { (content_rect.min_x(), content_rect.max_x()) } else { (bounds.min_x(), bounds.max_x()) };
+        let (min_y, max_y) = if scrolls_vertical { (content_rect.min_y(), content_rect.max_y()) } else { (bounds.min_y(), bounds.max_y()) };
+        let children_sum_rect = LayoutRect::new(LayoutPoint::new(min_x, min_y), LayoutSize::new(max_x - min_x, max_y - min_y));
+
         let parent_dom_hash = dom_rects[*parent].calculate_node_data_hash();
 
         // Create an external scroll id. This id is required to preserve its
@@ -443,6 +769,15 @@ fn get_nodes_that_need_scroll_clip<'a, T: 'a>(
             None => new_scroll_tag_id(),
         };
 
+        let snap = get_scroll_snap(
+            *parent,
+            node_hierarchy,
+            display_list_rects,
+            layouted_rects,
+            (children_sum_rect.size.width - bounds.size.width).max(0.0),
+            (children_sum_rect.size.height - bounds.size.height).max(0.0),
+        );
+
         tags_to_node_ids.insert(scroll_tag_id, *parent);
         nodes.insert(*parent, OverflowingScrollNode {
             parent_rect: parent_rect.clone(),
@@ -450,12 +785,21 @@ fn get_nodes_that_need_scroll_clip<'a, T: 'a>(
             parent_external_scroll_id,
             parent_dom_hash,
             scroll_tag_id,
+            snap,
         });
     }
 
     ScrolledNodes { overflowing_nodes: nodes, tags_to_node_ids }
 }
 
+/// Returns the `overflow` if it is explicitly set to `auto` or `scroll`
+fn overflow_scrolls(overflow: Option<Overflow>) -> Option<Overflow> {
+    match overflow {
+        Some(Overflow::Auto) | Some(Overflow::Scroll) => overflow,
+        _ => None,
+    }
+}
+
 fn node_needs_to_clip_children(layout: &RectLayout) -> bool {
     let overflow = layout.overflow.unwrap_or_default();
     !overflow.is_horizontal_overflow_visible() ||
@@ -492,6 +836,84 @@ fn test_overflow_parsing() {
     assert_eq!(node_needs_to_clip_children(&layout3), true);
 }
 
+#[test]
+fn test_scroll_snap_target() {
+
+    let snap = ScrollSnap {
+        strictness: ScrollSnapStrictness::Mandatory,
+        positions_x: vec![0.0, 200.0, 400.0],
+        positions_y: Vec::new(),
+    };
+    let frame_size = LayoutSize::new(200.0, 100.0);
+
+    assert_eq!(snap.get_target((290.0, 0.0), frame_size), Some((200.0, 0.0)));
+    assert_eq!(snap.get_target((310.0, 0.0), frame_size), Some((400.0, 0.0)));
+
+    // 90px is too far away from 0px and 200px (more than 30% of 200px)
+    let snap = ScrollSnap { strictness: ScrollSnapStrictness::Proximity, .. snap };
+    assert_eq!(snap.get_target((90.0, 0.0), frame_size), None);
+    assert_eq!(snap.get_target((50.0, 0.0), frame_size), Some((0.0, 0.0)));
+}
+
+#[test]
+fn test_css_transform() {
+
+    use azul_css::{TransformFunction, TransformOriginValue, FloatValue, PixelValue, PercentageValue};
+
+    fn assert_transform(transform: LayoutTransform, expected: [f32;6]) {
+        let actual = [transform.m11, transform.m12, transform.m21, transform.m22, transform.m41, transform.m42];
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 0.001, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    let bounds = LayoutRect::new(LayoutPoint::new(100.0, 0.0), LayoutSize::new(100.0, 100.0));
+
+    // Rotating by 90 degrees around the center moves the top left corner to the top right corner
+    let rotate = StyleTransform(vec![TransformFunction::Rotate(FloatValue::new(90.0))]);
+    assert_transform(get_css_transform(&rotate, &StyleTransformOrigin::default(), &bounds), [0.0, 1.0, -1.0, 0.0, 200.0, -100.0]);
+
+    let top_left = StyleTransformOrigin {
+        x: TransformOriginValue::Percentage(PercentageValue::new(0.0)),
+        y: TransformOriginValue::Percentage(PercentageValue::new(0.0)),
+    };
+    let translate_scale = StyleTransform(vec![
+        TransformFunction::Translate(PixelValue::px(10.0), PixelValue::px(0.0)),
+        TransformFunction::Scale(FloatValue::new(2.0), FloatValue::new(2.0)),
+    ]);
+    assert_transform(get_css_transform(&translate_scale, &top_left, &bounds), [2.0, 0.0, 0.0, 2.0, -90.0, 0.0]);
+
+    // An IFrame that was laid out at 100x100 and grew to 200x50 (while moving to x = 50)
+    let new_bounds = LayoutRect::new(LayoutPoint::new(50.0, 0.0), LayoutSize::new(200.0, 50.0));
+    assert_transform(get_iframe_preview_transform(&bounds, &new_bounds), [2.0, 0.0, 0.0, 0.5, -150.0, 0.0]);
+}
+
+#[test]
+fn test_css_transform_3d() {
+
+    use azul_css::{TransformFunction, FloatValue, PixelValue};
+
+    fn assert_approx_eq(actual: [f32;4], expected: [f32;4]) {
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 0.001, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    let bounds = LayoutRect::new(LayoutPoint::new(100.0, 0.0), LayoutSize::new(100.0, 100.0));
+
+    // Flipping a card around its vertical center axis mirrors it horizontally and moves it to the back
+    let flip = StyleTransform(vec![
+        TransformFunction::RotateY(FloatValue::new(180.0)),
+        TransformFunction::TranslateZ(PixelValue::px(50.0)),
+    ]);
+    let t = get_css_transform(&flip, &StyleTransformOrigin::default(), &bounds);
+    assert_approx_eq([t.m11, t.m33, t.m41, t.m43], [-1.0, -1.0, 300.0, -50.0]);
+
+    // The vanishing point of the perspective is the center of the node
+    let p = get_css_perspective(500.0, &bounds);
+    assert_approx_eq([p.m31, p.m32, p.m34, p.m44], [-0.3, -0.1, -0.002, 1.0]);
+}
+
 fn push_rectangles_into_displaylist<'a, 'b, 'c, 'd, 'e, 'f, T>(
     epoch: Epoch,
     window_size: WindowSize,
@@ -501,7 +923,9 @@ fn push_rectangles_into_displaylist<'a, 'b, 'c, 'd, 'e, 'f, T>(
     referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
     referenced_mutable_content: &mut DisplayListParametersMut<'f, T>)
 {
-    let mut clip_stack = Vec::new();
+    // Stacking contexts (for an `opacity`, `transform`, `perspective` or `mix-blend-mode`), sticky frames and
+    // `overflow: hidden` clips that are currently pushed, ordered from the root to the innermost node
+    let mut node_contexts = Vec::new();
 
     for content_group in content_grouped_rectangles.groups {
         let rectangle = DisplayListRectParams {
@@ -511,15 +935,36 @@ fn push_rectangles_into_displaylist<'a, 'b, 'c, 'd, 'e, 'f, T>(
             window_size,
         };
 
+        // Absolutely positioned nodes are drawn in their own content group after the
+        // rest of the DOM, so the opacity and transform of their parents have to be applied again
+        let mut parents = content_group.root.node_id.ancestors(referenced_content.node_hierarchy).skip(1).collect::<Vec<_>>();
+        parents.reverse();
+
+        // An absolutely positioned node is only clipped by the `overflow: hidden` of its
+        // containing block (the nearest positioned parent) and the parents of the containing block.
+        // NOTE: The scroll frames of the parents are not pushed again, so absolutely positioned
+        // nodes don't scroll together with their parents.
+        let containing_block_index = parents.iter().rposition(|parent| {
+            referenced_content.display_rectangle_arena[*parent].layout.position.unwrap_or_default() != LayoutPosition::Static
+        });
+
+        for (parent_index, parent) in parents.into_iter().enumerate() {
+            push_node_contexts(parent, referenced_content, referenced_mutable_content.builder, referenced_mutable_content.app_resources.get_paint_property_overrides(), &mut node_contexts);
+            if containing_block_index.map(|index| parent_index <= index).unwrap_or(false) {
+                push_node_overflow_clip(parent, referenced_content, referenced_mutable_content.builder, &mut node_contexts);
+            }
+        }
+        push_node_contexts(content_group.root.node_id, referenced_content, referenced_mutable_content.builder, referenced_mutable_content.app_resources.get_paint_property_overrides(), &mut node_contexts);
+
         // Push the root of the node
         push_rectangles_into_displaylist_inner(
-            content_group.root,
             scrollable_nodes,
             &rectangle,
             referenced_content,
             referenced_mutable_content,
-            &mut clip_stack
         );
+        push_node_outline(content_group.root.node_id, &window_size, referenced_content, &mut node_contexts);
+        push_node_overflow_frame(content_group.root.node_id, scrollable_nodes, scroll_states, referenced_content, referenced_mutable_content.builder, &mut node_contexts);
 
         for item in content_group.node_ids {
 
@@ -530,25 +975,652 @@ fn push_rectangles_into_displaylist<'a, 'b, 'c, 'd, 'e, 'f, T>(
                 window_size,
             };
 
+            // The nodes are in depth-first order, so a stacking context (or sticky frame, clip) is
+            // finished once a node is reached that isn't a child of the stacking context node
+            pop_node_contexts(Some(item.node_id), referenced_content.node_hierarchy, referenced_mutable_content.builder, &mut node_contexts);
+            push_node_contexts(item.node_id, referenced_content, referenced_mutable_content.builder, referenced_mutable_content.app_resources.get_paint_property_overrides(), &mut node_contexts);
+
             push_rectangles_into_displaylist_inner(
-                item,
                 scrollable_nodes,
                 &rectangle,
                 referenced_content,
                 referenced_mutable_content,
-                &mut clip_stack
             );
+
+            push_node_outline(item.node_id, &window_size, referenced_content, &mut node_contexts);
+
+            // The clip / scroll frame only applies to the children, not to the node itself
+            push_node_overflow_frame(item.node_id, scrollable_nodes, scroll_states, referenced_content, referenced_mutable_content.builder, &mut node_contexts);
         }
+
+        pop_node_contexts(None, referenced_content.node_hierarchy, referenced_mutable_content.builder, &mut node_contexts);
+    }
+}
+
+/// What has been pushed onto the display list for a node and all of its children
+#[derive(Debug, Copy, Clone)]
+enum NodeContext {
+    StackingContext,
+    StickyFrame,
+    OverflowClip,
+    /// The scrollbars are pushed once the scroll frame is popped,
+    /// so that they are drawn on top of the (scrolled) children
+    ScrollFrame(Scrollbars),
+    /// The outline is pushed once the node and all of its children are drawn,
+    /// so that it is drawn on top of the children (outside of the clip of the node)
+    Outline(OutlineItem),
+}
+
+/// Outline of a node, see `push_outline`
+#[derive(Debug, Copy, Clone)]
+struct OutlineItem {
+    bounds: LayoutRect,
+    outline: StyleOutline,
+    outline_offset: Option<StyleOutlineOffset>,
+    border_radius: Option<StyleBorderRadius>,
+}
+
+/// Horizontal and vertical scrollbar of a scroll frame
+#[derive(Debug, Default, Copy, Clone)]
+struct Scrollbars {
+    horizontal: Option<ScrollbarItem>,
+    vertical: Option<ScrollbarItem>,
+}
+
+/// Track and thumb of a single scrollbar
+#[derive(Debug, Copy, Clone)]
+struct ScrollbarItem {
+    track: LayoutRect,
+    track_color: Option<StyleColorU>,
+    track_radius: Option<StyleBorderRadius>,
+    /// Bounds of the thumb at the start of the track, the thumb is moved by the `thumb_transform`
+    thumb: LayoutRect,
+    thumb_color: Option<StyleColorU>,
+    thumb_radius: Option<StyleBorderRadius>,
+    thumb_transform: PropertyBindingKey<LayoutTransform>,
+    /// Initial offset of the thumb for the current scroll position
+    thumb_offset: LayoutVector2D,
+}
+
+/// Pushes the sticky frame and the stacking context of the node (if necessary).
+/// The stacking context is pushed inside of the sticky frame, so that the
+/// transform of a sticky node is applied relative to its sticky position.
+fn push_node_contexts<'a,'b,'c,'d,'e, T>(
+    node_id: NodeId,
+    referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
+    builder: &mut DisplayListBuilder,
+    paint_property_overrides: &FastHashMap<String, PaintPropertyOverride>,
+    node_contexts: &mut Vec<(NodeId, NodeContext)>,
+) {
+    push_node_sticky_frame(node_id, referenced_content, builder, node_contexts);
+    push_node_stacking_context(node_id, referenced_content, builder, paint_property_overrides, node_contexts);
+}
+
+/// If the node has an `outline`, remembers the outline so that it is pushed after the
+/// children of the node have been drawn. Hidden nodes don't draw their outline.
+fn push_node_outline<'a,'b,'c,'d,'e, T>(
+    node_id: NodeId,
+    window_size: &WindowSize,
+    referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
+    node_contexts: &mut Vec<(NodeId, NodeContext)>,
+) {
+    let style = &referenced_content.display_rectangle_arena[node_id].style;
+
+    let outline = match style.outline {
+        Some(outline) => outline,
+        None => return,
+    };
+
+    if get_node_display_items(style, referenced_content.node_data[node_id].hit_test) != NodeDisplayItems::All {
+        return;
     }
+
+    node_contexts.push((node_id, NodeContext::Outline(OutlineItem {
+        bounds: snap_to_device_pixels(referenced_content.layout_result.rects[node_id].bounds, window_size),
+        outline,
+        outline_offset: style.outline_offset,
+        border_radius: style.border_radius,
+    })));
+}
+
+/// If the node has `position: sticky`, pushes a sticky frame for the node and all of its
+/// children. While scrolling, WebRender moves the sticky frame so that the node keeps the
+/// distance given by `top` / `right` / `bottom` / `left` to the edges of the scroll frame.
+/// The node never leaves the bounds of its parent, i.e. a table header only sticks
+/// to the top while the rest of the table is visible.
+fn push_node_sticky_frame<'a,'b,'c,'d,'e, T>(
+    node_id: NodeId,
+    referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
+    builder: &mut DisplayListBuilder,
+    node_contexts: &mut Vec<(NodeId, NodeContext)>,
+) {
+    let layout = &referenced_content.display_rectangle_arena[node_id].layout;
+
+    if layout.position != Some(LayoutPosition::Sticky) {
+        return;
+    }
+
+    let margins = SideOffsets2D::new(
+        layout.top.map(|top| top.0.to_pixels()),
+        layout.right.map(|right| right.0.to_pixels()),
+        layout.bottom.map(|bottom| bottom.0.to_pixels()),
+        layout.left.map(|left| left.0.to_pixels()),
+    );
+
+    // Without any offsets, `position: sticky` behaves like `position: relative`
+    if margins.top.is_none() && margins.right.is_none() && margins.bottom.is_none() && margins.left.is_none() {
+        return;
+    }
+
+    let bounds = referenced_content.layout_result.rects[node_id].bounds;
+    let parent_bounds = referenced_content.node_hierarchy[node_id].parent
+        .map(|parent_id| referenced_content.layout_result.rects[parent_id].bounds)
+        .unwrap_or(bounds);
+
+    // How far the node can be moved in each direction without leaving its parent
+    let vertical_offset_bounds = StickyOffsetBounds::new(
+        parent_bounds.min_y() - bounds.min_y(),
+        parent_bounds.max_y() - bounds.max_y(),
+    );
+    let horizontal_offset_bounds = StickyOffsetBounds::new(
+        parent_bounds.min_x() - bounds.min_x(),
+        parent_bounds.max_x() - bounds.max_x(),
+    );
+
+    let sticky_frame_id = builder.define_sticky_frame(
+        bounds,
+        margins,
+        vertical_offset_bounds,
+        horizontal_offset_bounds,
+        LayoutVector2D::zero(),
+    );
+
+    builder.push_clip_id(sticky_frame_id);
+    node_contexts.push((node_id, NodeContext::StickyFrame));
+}
+
+/// If the node has an `opacity` below 1.0, a `transform` or a `mix-blend-mode`, pushes a
+/// stacking context for the node and all of its children. With an `opacity`, the subtree is drawn
+/// into one layer, which is then blended with the given opacity (instead of blending every
+/// primitive of the node individually), the same goes for the `mix-blend-mode`. A `transform` is applied without re-layouting
+/// the DOM, so it doesn't affect the position of the surrounding nodes.
+///
+/// The paint property override of the node (if any) is pushed inside of this stacking context.
+fn push_node_stacking_context<'a,'b,'c,'d,'e, T>(
+    node_id: NodeId,
+    referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
+    builder: &mut DisplayListBuilder,
+    paint_property_overrides: &FastHashMap<String, PaintPropertyOverride>,
+    node_contexts: &mut Vec<(NodeId, NodeContext)>,
+) {
+    use css::webrender_translate::wr_translate_mix_blend_mode;
+
+    let style = &referenced_content.display_rectangle_arena[node_id].style;
+
+    let opacity = style.opacity
+        .map(|opacity| opacity.0.get().max(0.0).min(1.0))
+        .and_then(|opacity| if opacity < 1.0 { Some(opacity) } else { None });
+
+    let bounds = &referenced_content.layout_result.rects[node_id].bounds;
+
+    let transform = style.transform.as_ref()
+        .and_then(|transform| if transform.0.is_empty() { None } else { Some(transform) })
+        .map(|transform| get_css_transform(transform, &style.transform_origin.unwrap_or_default(), bounds));
+
+    // The perspective only affects the children, the node itself lies in the z = 0 plane
+    let perspective = style.perspective
+        .and_then(|perspective| perspective.0)
+        .map(|distance| get_css_perspective(distance.to_pixels(), bounds));
+
+    let preserve_3d = style.transform_style == Some(StyleTransformStyle::Preserve3D);
+
+    let mix_blend_mode = style.mix_blend_mode.unwrap_or_default();
+
+    if opacity.is_some() || transform.is_some() || perspective.is_some() || preserve_3d || mix_blend_mode != StyleMixBlendMode::Normal {
+
+        let filters = match opacity {
+            Some(opacity) => vec![FilterOp::Opacity(PropertyBinding::Value(opacity), opacity)],
+            None => Vec::new(),
+        };
+
+        // The transform is already in the coordinate space of the window,
+        // so the stacking context has to start at the origin of the window
+        builder.push_stacking_context(
+            &LayoutPrimitiveInfo::new(LayoutRect::new(LayoutPoint::zero(), builder.content_size())),
+            None,
+            transform.map(PropertyBinding::Value),
+            if preserve_3d { TransformStyle::Preserve3D } else { TransformStyle::Flat },
+            perspective,
+            wr_translate_mix_blend_mode(mix_blend_mode),
+            filters,
+            GlyphRasterSpace::Screen,
+        );
+
+        node_contexts.push((node_id, NodeContext::StackingContext));
+    }
+
+    let paint_override = referenced_content.node_data[node_id].ids.iter()
+        .filter_map(|id| paint_property_overrides.get(id.as_str()))
+        .next();
+
+    if let Some(paint_override) = paint_override {
+        push_paint_property_override(node_id, bounds, paint_override, builder, node_contexts);
+    }
+}
+
+/// Pushes the scroll frame of the node if the node scrolls its children,
+/// otherwise the `overflow: hidden` clip (if necessary)
+fn push_node_overflow_frame<'a,'b,'c,'d,'e, T>(
+    node_id: NodeId,
+    scrollable_nodes: &ScrolledNodes,
+    scroll_states: &mut ScrollStates,
+    referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
+    builder: &mut DisplayListBuilder,
+    node_contexts: &mut Vec<(NodeId, NodeContext)>,
+) {
+    let scroll_node = match scrollable_nodes.overflowing_nodes.get(&node_id) {
+        Some(s) => s,
+        None => {
+            push_node_overflow_clip(node_id, referenced_content, builder, node_contexts);
+            return;
+        },
+    };
+
+    let bounds = scroll_node.parent_rect.bounds;
+    let content_rect = LayoutRect::new(bounds.origin, LayoutSize::new(
+        scroll_node.child_rect.max_x() - bounds.min_x(),
+        scroll_node.child_rect.max_y() - bounds.min_y(),
+    ));
+
+    // The scroll state outlives the display list, so that the scroll position
+    // is kept when the DOM is re-layouted (as long as the node doesn't change)
+    scroll_states.update_scroll_frame(
+        scroll_node.parent_external_scroll_id,
+        (content_rect.size.width - bounds.size.width).max(0.0),
+        (content_rect.size.height - bounds.size.height).max(0.0),
+    );
+
+    let rect = &referenced_content.display_rectangle_arena[node_id];
+    let rounded_clip = get_clip_region(bounds, rect);
+    let scrollbars = get_scrollbars(scroll_node, content_rect, rect, scroll_states);
+
+    let scroll_frame_id = builder.define_scroll_frame(
+        Some(scroll_node.parent_external_scroll_id),
+        content_rect,
+        bounds,
+        rounded_clip,
+        None,
+        ScrollSensitivity::ScriptAndInputEvents,
+    );
+
+    builder.push_clip_id(scroll_frame_id);
+    node_contexts.push((node_id, NodeContext::ScrollFrame(scrollbars)));
+}
+
+/// Returns the key of the transform that moves the thumb of the horizontal or vertical
+/// scrollbar of a scroll frame, see `ScrollState::get_thumb_offset`
+pub(crate) fn get_scrollbar_thumb_binding(scroll_id: &ExternalScrollId, vertical: bool) -> PropertyBindingKey<LayoutTransform> {
+    PropertyBindingKey::new(scroll_id.0.wrapping_mul(2).wrapping_add(vertical as u64))
+}
+
+/// Lays out the scrollbars of a scroll frame: a vertical scrollbar at the right edge if the
+/// content overflows vertically (or the node has `overflow-y: scroll`) and a horizontal one at
+/// the bottom edge. The scrollbars are drawn on top of the content, they don't take up any space.
+///
+/// The padding of the `::scrollbar` is given for the vertical scrollbar, the horizontal
+/// scrollbar uses the top / bottom padding at its left / right end and vice versa.
+fn get_scrollbars(
+    scroll_node: &OverflowingScrollNode,
+    content_rect: LayoutRect,
+    rect: &DisplayRectangle,
+    scroll_states: &mut ScrollStates,
+) -> Scrollbars {
+
+    let default_scrollbar;
+    let scrollbar_info = match &rect.scrollbar {
+        Some(s) => s,
+        None => { default_scrollbar = ScrollbarInfo::default(); &default_scrollbar },
+    };
+
+    let bounds = scroll_node.parent_rect.bounds;
+    let width = scrollbar_info.width.0.to_pixels();
+    let overflow = rect.layout.overflow.unwrap_or_default();
+
+    let show_horizontal = width > 0.0 && (content_rect.size.width > bounds.size.width || overflow.horizontal == Some(Overflow::Scroll));
+    let show_vertical = width > 0.0 && (content_rect.size.height > bounds.size.height || overflow.vertical == Some(Overflow::Scroll));
+
+    if !show_horizontal && !show_vertical {
+        return Scrollbars::default();
+    }
+
+    let to_pixels = |p: Option<PixelValue>| p.map(|p| p.to_pixels()).unwrap_or(0.0);
+    let padding = &scrollbar_info.padding;
+    let (cross_start, cross_end) = (to_pixels(padding.left), to_pixels(padding.right));
+    let (main_start, main_end) = (to_pixels(padding.top), to_pixels(padding.bottom));
+
+    // Returns the thumb at the start of the track and how far it can be moved
+    let get_thumb = |track: LayoutRect, vertical: bool| {
+        let (track_length, frame_length, content_length) = if vertical {
+            (track.size.height, bounds.size.height, content_rect.size.height)
+        } else {
+            (track.size.width, bounds.size.width, content_rect.size.width)
+        };
+        let track_length = (track_length - main_start - main_end).max(0.0);
+        let thumb_length = (track_length * frame_length / content_length.max(frame_length)).max(MIN_SCROLLBAR_THUMB_LENGTH.min(track_length));
+        let thumb_width = (width - cross_start - cross_end).max(0.0);
+        let thumb = if vertical {
+            LayoutRect::new(LayoutPoint::new(track.min_x() + cross_start, track.min_y() + main_start), LayoutSize::new(thumb_width, thumb_length))
+        } else {
+            LayoutRect::new(LayoutPoint::new(track.min_x() + main_start, track.min_y() + cross_start), LayoutSize::new(thumb_length, thumb_width))
+        };
+        (thumb, track_length - thumb_length)
+    };
+
+    // The corner where both scrollbars meet is left empty
+    let horizontal = if show_horizontal {
+        let corner = if show_vertical { width } else { 0.0 };
+        let track = LayoutRect::new(LayoutPoint::new(bounds.min_x(), bounds.max_y() - width), LayoutSize::new(bounds.size.width - corner, width));
+        let (thumb, travel) = get_thumb(track, false);
+        Some((track, thumb, travel))
+    } else {
+        None
+    };
+
+    let vertical = if show_vertical {
+        let corner = if show_horizontal { width } else { 0.0 };
+        let track = LayoutRect::new(LayoutPoint::new(bounds.max_x() - width, bounds.min_y()), LayoutSize::new(width, bounds.size.height - corner));
+        let (thumb, travel) = get_thumb(track, true);
+        Some((track, thumb, travel))
+    } else {
+        None
+    };
+
+    let scroll_id = scroll_node.parent_external_scroll_id;
+    scroll_states.set_thumb_travel(
+        scroll_id,
+        horizontal.map(|(_, _, travel)| travel).unwrap_or(0.0),
+        vertical.map(|(_, _, travel)| travel).unwrap_or(0.0),
+    );
+    let (offset_x, offset_y) = scroll_states.0.get(&scroll_id).map(|state| state.get_thumb_offset()).unwrap_or((0.0, 0.0));
+
+    let background_color = |style: &RectStyle| match &style.background {
+        Some(StyleBackground::Color(color)) => Some(*color),
+        _ => None,
+    };
+
+    let scrollbar_item = |(track, thumb, _): (LayoutRect, LayoutRect, f32), vertical: bool| ScrollbarItem {
+        track,
+        track_color: background_color(&scrollbar_info.track),
+        track_radius: scrollbar_info.track.border_radius,
+        thumb,
+        thumb_color: background_color(&scrollbar_info.thumb),
+        thumb_radius: scrollbar_info.thumb.border_radius,
+        thumb_transform: get_scrollbar_thumb_binding(&scroll_id, vertical),
+        thumb_offset: if vertical { LayoutVector2D::new(0.0, offset_y) } else { LayoutVector2D::new(offset_x, 0.0) },
+    };
+
+    Scrollbars {
+        horizontal: horizontal.map(|h| scrollbar_item(h, false)),
+        vertical: vertical.map(|v| scrollbar_item(v, true)),
+    }
+}
+
+/// Pushes the tracks and thumbs of the scrollbars. The thumb is pushed in a stacking context
+/// with a bound transform, so that it can be moved while scrolling without rebuilding the
+/// display list. Only background colors are drawn, other backgrounds are ignored.
+fn push_scrollbars(scrollbars: &Scrollbars, builder: &mut DisplayListBuilder) {
+
+    fn push_scrollbar_part(builder: &mut DisplayListBuilder, bounds: LayoutRect, color: Option<StyleColorU>, radius: Option<StyleBorderRadius>) {
+        use css::webrender_translate::wr_translate_border_radius;
+
+        let color = match color {
+            Some(color) => color,
+            None => return,
+        };
+
+        match radius {
+            Some(radius) => {
+                let rounded_clip = ComplexClipRegion {
+                    rect: bounds,
+                    radii: wr_translate_border_radius(radius.0).into(),
+                    mode: ClipMode::Clip,
+                };
+                let clip_id = builder.define_clip(bounds, vec![rounded_clip], None);
+                builder.push_clip_id(clip_id);
+                push_rect(&LayoutPrimitiveInfo::new(bounds), builder, &color);
+                builder.pop_clip_id();
+            },
+            None => push_rect(&LayoutPrimitiveInfo::new(bounds), builder, &color),
+        }
+    }
+
+    for scrollbar in scrollbars.horizontal.iter().chain(scrollbars.vertical.iter()) {
+
+        push_scrollbar_part(builder, scrollbar.track, scrollbar.track_color, scrollbar.track_radius);
+
+        let initial_transform = LayoutTransform::create_translation(scrollbar.thumb_offset.x, scrollbar.thumb_offset.y, 0.0);
+        let content_size = builder.content_size();
+        builder.push_stacking_context(
+            &LayoutPrimitiveInfo::new(LayoutRect::new(LayoutPoint::zero(), content_size)),
+            None,
+            Some(PropertyBinding::Binding(scrollbar.thumb_transform, initial_transform)),
+            TransformStyle::Flat,
+            None,
+            MixBlendMode::Normal,
+            Vec::new(),
+            GlyphRasterSpace::Screen,
+        );
+        push_scrollbar_part(builder, scrollbar.thumb, scrollbar.thumb_color, scrollbar.thumb_radius);
+        builder.pop_stacking_context();
+    }
+}
+
+/// If the node has `overflow: hidden`, pushes a clip for all children of the node, so that
+/// the children can't be drawn outside of the node (including the rounded corners of a
+/// `border-radius`). With only `overflow-x: hidden` or `overflow-y: hidden`, the children
+/// are only clipped in that direction.
+fn push_node_overflow_clip<'a,'b,'c,'d,'e, T>(
+    node_id: NodeId,
+    referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
+    builder: &mut DisplayListBuilder,
+    node_contexts: &mut Vec<(NodeId, NodeContext)>,
+) {
+    if referenced_content.node_hierarchy[node_id].first_child.is_none() {
+        return;
+    }
+
+    let rect = &referenced_content.display_rectangle_arena[node_id];
+    let overflow = rect.layout.overflow.unwrap_or_default();
+    let clip_horizontal = overflow.horizontal == Some(Overflow::Hidden);
+    let clip_vertical = overflow.vertical == Some(Overflow::Hidden);
+
+    if !clip_horizontal && !clip_vertical {
+        return;
+    }
+
+    let bounds = referenced_content.layout_result.rects[node_id].bounds;
+
+    let clip_id = if clip_horizontal && clip_vertical {
+        let rounded_clip = get_clip_region(bounds, rect).into_iter().collect();
+        builder.define_clip(bounds, rounded_clip, None)
+    } else {
+        // In the direction that isn't clipped, the clip extends to the edges of the window
+        let window = LayoutRect::new(LayoutPoint::zero(), builder.content_size());
+        let (min_x, max_x) = if clip_horizontal {
+            (bounds.min_x(), bounds.max_x())
+        } else {
+            (bounds.min_x().min(window.min_x()), bounds.max_x().max(window.max_x()))
+        };
+        let (min_y, max_y) = if clip_vertical {
+            (bounds.min_y(), bounds.max_y())
+        } else {
+            (bounds.min_y().min(window.min_y()), bounds.max_y().max(window.max_y()))
+        };
+        let clip_rect = LayoutRect::new(LayoutPoint::new(min_x, min_y), LayoutSize::new(max_x - min_x, max_y - min_y));
+        builder.define_clip(clip_rect, Vec::new(), None)
+    };
+
+    builder.push_clip_id(clip_id);
+    node_contexts.push((node_id, NodeContext::OverflowClip));
+}
+
+/// Pops all stacking contexts, sticky frames and clips of nodes that `node_id` is
+/// not a child of (all stacking contexts, sticky frames and clips if `node_id` is `None`)
+fn pop_node_contexts(
+    node_id: Option<NodeId>,
+    node_hierarchy: &NodeHierarchy,
+    builder: &mut DisplayListBuilder,
+    node_contexts: &mut Vec<(NodeId, NodeContext)>,
+) {
+    while let Some((context_node_id, context)) = node_contexts.last().cloned() {
+        let is_child = node_id.map(|node_id| {
+            node_id.ancestors(node_hierarchy).any(|ancestor| ancestor == context_node_id)
+        }).unwrap_or(false);
+
+        if is_child {
+            break;
+        }
+
+        match context {
+            NodeContext::StackingContext => builder.pop_stacking_context(),
+            NodeContext::StickyFrame | NodeContext::OverflowClip => builder.pop_clip_id(),
+            NodeContext::ScrollFrame(scrollbars) => {
+                builder.pop_clip_id();
+                push_scrollbars(&scrollbars, builder);
+            },
+            NodeContext::Outline(item) => {
+                push_outline(&item.bounds, builder, &item.outline, &item.outline_offset, &item.border_radius);
+            },
+        }
+        node_contexts.pop();
+    }
+}
+
+/// 4x4 matrix, `m[row][column]`, transforms column vectors: `p' = m * p`
+type Matrix4 = [[f32;4];4];
+
+fn multiply_matrix4(m: &Matrix4, n: &Matrix4) -> Matrix4 {
+    let mut result = [[0.0;4];4];
+    for (row, result_row) in result.iter_mut().enumerate() {
+        for (column, value) in result_row.iter_mut().enumerate() {
+            *value = (0..4).map(|i| m[row][i] * n[i][column]).sum();
+        }
+    }
+    result
+}
+
+fn translation_matrix4(x: f32, y: f32, z: f32) -> Matrix4 {
+    [
+        [1.0, 0.0, 0.0, x],
+        [0.0, 1.0, 0.0, y],
+        [0.0, 0.0, 1.0, z],
+        [0.0, 0.0, 0.0, 1.0],
+    ]
+}
+
+/// Perspective projection with the viewer at `distance` pixels in front of the z = 0 plane
+fn perspective_matrix4(distance: f32) -> Matrix4 {
+    let w = if distance > 0.0 { -1.0 / distance } else { 0.0 };
+    [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, w,   1.0],
+    ]
+}
+
+/// WebRender transforms row vectors (`p' = p * m`), so the matrix has to be transposed
+fn matrix4_to_layout_transform(m: &Matrix4) -> LayoutTransform {
+    LayoutTransform::row_major(
+        m[0][0], m[1][0], m[2][0], m[3][0],
+        m[0][1], m[1][1], m[2][1], m[3][1],
+        m[0][2], m[1][2], m[2][2], m[3][2],
+        m[0][3], m[1][3], m[2][3], m[3][3],
+    )
+}
+
+/// Converts the CSS `transform` of a node into a transform in the coordinate space of the
+/// window. The functions are applied around the `transform-origin`, from right to left.
+fn get_css_transform(transform: &StyleTransform, origin: &StyleTransformOrigin, bounds: &LayoutRect) -> LayoutTransform {
+
+    use azul_css::TransformFunction::*;
+
+    let origin_x = bounds.origin.x + origin.x.to_pixels(bounds.size.width);
+    let origin_y = bounds.origin.y + origin.y.to_pixels(bounds.size.height);
+
+    let mut matrix = translation_matrix4(origin_x, origin_y, 0.0);
+
+    for function in &transform.0 {
+        let function_matrix = match function {
+            Matrix(m) => [
+                [m[0].get(), m[2].get(), 0.0, m[4].get()],
+                [m[1].get(), m[3].get(), 0.0, m[5].get()],
+                [0.0,        0.0,        1.0, 0.0],
+                [0.0,        0.0,        0.0, 1.0],
+            ],
+            Translate(x, y) => translation_matrix4(x.to_pixels(), y.to_pixels(), 0.0),
+            TranslateZ(z) => translation_matrix4(0.0, 0.0, z.to_pixels()),
+            Scale(x, y) => [
+                [x.get(), 0.0,     0.0, 0.0],
+                [0.0,     y.get(), 0.0, 0.0],
+                [0.0,     0.0,     1.0, 0.0],
+                [0.0,     0.0,     0.0, 1.0],
+            ],
+            Rotate(angle) => {
+                let (sin, cos) = angle.get().to_radians().sin_cos();
+                [
+                    [cos, -sin, 0.0, 0.0],
+                    [sin, cos,  0.0, 0.0],
+                    [0.0, 0.0,  1.0, 0.0],
+                    [0.0, 0.0,  0.0, 1.0],
+                ]
+            },
+            RotateX(angle) => {
+                let (sin, cos) = angle.get().to_radians().sin_cos();
+                [
+                    [1.0, 0.0, 0.0,  0.0],
+                    [0.0, cos, -sin, 0.0],
+                    [0.0, sin, cos,  0.0],
+                    [0.0, 0.0, 0.0,  1.0],
+                ]
+            },
+            RotateY(angle) => {
+                let (sin, cos) = angle.get().to_radians().sin_cos();
+                [
+                    [cos,  0.0, sin, 0.0],
+                    [0.0,  1.0, 0.0, 0.0],
+                    [-sin, 0.0, cos, 0.0],
+                    [0.0,  0.0, 0.0, 1.0],
+                ]
+            },
+            Skew(x, y) => [
+                [1.0,                       x.get().to_radians().tan(), 0.0, 0.0],
+                [y.get().to_radians().tan(), 1.0,                       0.0, 0.0],
+                [0.0,                       0.0,                        1.0, 0.0],
+                [0.0,                       0.0,                        0.0, 1.0],
+            ],
+            Perspective(distance) => perspective_matrix4(distance.to_pixels()),
+        };
+        matrix = multiply_matrix4(&matrix, &function_matrix);
+    }
+
+    matrix4_to_layout_transform(&multiply_matrix4(&matrix, &translation_matrix4(-origin_x, -origin_y, 0.0)))
+}
+
+/// Converts the CSS `perspective` of a node into the perspective that WebRender applies to
+/// the children of the stacking context. The vanishing point is the center of the node.
+fn get_css_perspective(distance: f32, bounds: &LayoutRect) -> LayoutTransform {
+    let center_x = bounds.origin.x + bounds.size.width / 2.0;
+    let center_y = bounds.origin.y + bounds.size.height / 2.0;
+    let matrix = multiply_matrix4(&translation_matrix4(center_x, center_y, 0.0), &perspective_matrix4(distance));
+    matrix4_to_layout_transform(&multiply_matrix4(&matrix, &translation_matrix4(-center_x, -center_y, 0.0)))
 }
 
 fn push_rectangles_into_displaylist_inner<'a,'b,'c,'d,'e,'f, T>(
-    item: RenderableNodeId,
     scrollable_nodes: &mut ScrolledNodes,
     rectangle: &DisplayListRectParams<'a, T>,
     referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
     referenced_mutable_content: &mut DisplayListParametersMut<'f, T>,
-    clip_stack: &mut Vec<NodeId>,
 ) {
     displaylist_handle_rect(
         scrollable_nodes,
@@ -556,27 +1628,69 @@ fn push_rectangles_into_displaylist_inner<'a,'b,'c,'d,'e,'f, T>(
         referenced_content,
         referenced_mutable_content
     );
-/*
-
-    // NOTE: table demo has problems with clipping
-
-    if item.clip_children {
-        if let Some(last_child) = referenced_content.node_hierarchy[rectangle.rect_idx].last_child {
-            let styled_node = &referenced_content.display_rectangle_arena[rectangle.rect_idx];
-            let solved_rect = &referenced_content.layout_result.rects[rectangle.rect_idx];
-            let clip = get_clip_region(solved_rect.bounds, &styled_node)
-                .unwrap_or(ComplexClipRegion::new(solved_rect.bounds, BorderRadius::zero(), ClipMode::Clip));
-            let clip_id = referenced_mutable_content.builder.define_clip(solved_rect.bounds, vec![clip], /* image_mask: */ None);
-            referenced_mutable_content.builder.push_clip_id(clip_id);
-            clip_stack.push(last_child);
-        }
+}
+
+/// Pushes the stacking context (for the `opacity` and `transform`) and the clip of a
+/// paint property override, which then apply to the node and all of its children
+fn push_paint_property_override(
+    node_id: NodeId,
+    bounds: &LayoutRect,
+    paint_override: &PaintPropertyOverride,
+    builder: &mut DisplayListBuilder,
+    node_contexts: &mut Vec<(NodeId, NodeContext)>,
+) {
+    if paint_override.opacity.is_some() || paint_override.transform.is_some() {
+        let filters = match paint_override.opacity {
+            Some(opacity) => vec![FilterOp::Opacity(PropertyBinding::Value(opacity), opacity)],
+            None => Vec::new(),
+        };
+        // The transform is already relative to the node, so the stacking context
+        // has to start at the origin of the window
+        builder.push_stacking_context(
+            &LayoutPrimitiveInfo::new(LayoutRect::new(LayoutPoint::zero(), builder.content_size())),
+            None,
+            paint_override.transform.map(|t| PropertyBinding::Value(get_paint_transform(&t, bounds))),
+            TransformStyle::Flat,
+            None,
+            MixBlendMode::Normal,
+            filters,
+            GlyphRasterSpace::Screen,
+        );
+        node_contexts.push((node_id, NodeContext::StackingContext));
     }
 
-    if clip_stack.last().cloned() == Some(rectangle.rect_idx) {
-        referenced_mutable_content.builder.pop_clip_id();
-        clip_stack.pop();
+    if let Some(clip) = paint_override.clip {
+        let clip_rect = LayoutRect::new(
+            LayoutPoint::new(bounds.origin.x + clip.x, bounds.origin.y + clip.y),
+            LayoutSize::new(clip.width, clip.height),
+        );
+        let clip_id = builder.define_clip(clip_rect, Vec::new(), None);
+        builder.push_clip_id(clip_id);
+        node_contexts.push((node_id, NodeContext::OverflowClip));
     }
-*/
+}
+
+/// Converts the `PaintTransform` (scale, rotate, translate around the center
+/// of the node) into a transform in the coordinate space of the window
+fn get_paint_transform(transform: &PaintTransform, bounds: &LayoutRect) -> LayoutTransform {
+
+    let center_x = bounds.origin.x + bounds.size.width / 2.0;
+    let center_y = bounds.origin.y + bounds.size.height / 2.0;
+    let (sin, cos) = transform.rotate.to_radians().sin_cos();
+    let (scale_x, scale_y) = transform.scale;
+    let (translate_x, translate_y) = transform.translate;
+
+    let m11 = cos * scale_x;
+    let m12 = sin * scale_x;
+    let m21 = -sin * scale_y;
+    let m22 = cos * scale_y;
+
+    LayoutTransform::row_major_2d(
+        m11, m12,
+        m21, m22,
+        translate_x + center_x - (m11 * center_x + m21 * center_y),
+        translate_y + center_y - (m12 * center_x + m22 * center_y),
+    )
 }
 
 /// Parameters that apply to a single rectangle / div node
@@ -588,6 +1702,25 @@ pub(crate) struct DisplayListRectParams<'a, T: 'a> {
     window_size: WindowSize,
 }
 
+/// Rounds the edges of the `rect` to whole device pixels if the window
+/// uses the `HidpiRoundingPolicy::SnapToDevicePixels`
+fn snap_to_device_pixels(rect: LayoutRect, window_size: &WindowSize) -> LayoutRect {
+
+    if window_size.hidpi_rounding != HidpiRoundingPolicy::SnapToDevicePixels {
+        return rect;
+    }
+
+    let hidpi_factor = window_size.hidpi_factor as f32;
+    let snap = |value: f32| (value * hidpi_factor).round() / hidpi_factor;
+
+    let x = snap(rect.origin.x);
+    let y = snap(rect.origin.y);
+    let width = snap(rect.origin.x + rect.size.width) - x;
+    let height = snap(rect.origin.y + rect.size.height) - y;
+
+    LayoutRect::new(LayoutPoint::new(x, y), LayoutSize::new(width, height))
+}
+
 fn get_clip_region<'a>(bounds: LayoutRect, rect: &DisplayRectangle<'a>) -> Option<ComplexClipRegion> {
     use css::webrender_translate::wr_translate_border_radius;
     rect.style.border_radius.and_then(|border_radius| {
@@ -599,6 +1732,91 @@ fn get_clip_region<'a>(bounds: LayoutRect, rect: &DisplayRectangle<'a>) -> Optio
     })
 }
 
+/// What is pushed onto the display list for a single node (not including its children)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum NodeDisplayItems {
+    /// `visibility: hidden`: The node keeps its space in the layout, but nothing is
+    /// drawn and the node isn't hit-tested. The children inherit the visibility, but
+    /// are pushed separately (and can override it with `visibility: visible`)
+    Nothing,
+    /// `HitTestBehavior::HitTestOnly`: Only a transparent rectangle for the hit-testing
+    HitTestOnly,
+    /// The background, border, content and outline of the node
+    All,
+}
+
+fn get_node_display_items(style: &RectStyle, hit_test: HitTestBehavior) -> NodeDisplayItems {
+    if style.visibility == Some(StyleVisibility::Hidden) {
+        NodeDisplayItems::Nothing
+    } else if hit_test == HitTestBehavior::HitTestOnly {
+        NodeDisplayItems::HitTestOnly
+    } else {
+        NodeDisplayItems::All
+    }
+}
+
+#[test]
+fn test_get_node_display_items() {
+
+    let visible = RectStyle { visibility: Some(StyleVisibility::Visible), .. RectStyle::default() };
+    let hidden = RectStyle { visibility: Some(StyleVisibility::Hidden), .. RectStyle::default() };
+
+    assert_eq!(get_node_display_items(&RectStyle::default(), HitTestBehavior::Auto), NodeDisplayItems::All);
+    assert_eq!(get_node_display_items(&visible, HitTestBehavior::Auto), NodeDisplayItems::All);
+    assert_eq!(get_node_display_items(&visible, HitTestBehavior::None), NodeDisplayItems::All);
+    assert_eq!(get_node_display_items(&visible, HitTestBehavior::HitTestOnly), NodeDisplayItems::HitTestOnly);
+
+    // Hidden nodes aren't even pushed for the hit-testing
+    assert_eq!(get_node_display_items(&hidden, HitTestBehavior::Auto), NodeDisplayItems::Nothing);
+    assert_eq!(get_node_display_items(&hidden, HitTestBehavior::HitTestOnly), NodeDisplayItems::Nothing);
+}
+
+/// Returns the tag that the items of the node are pushed with, nodes without
+/// a tag are ignored by the hit-testing. The `scroll_tag` is only used if the
+/// node doesn't have a tag of its own (i.e. it has no callbacks, but scrolls its children).
+fn get_hit_test_tag(
+    hit_test: HitTestBehavior,
+    pointer_events: Option<StylePointerEvents>,
+    tag: Option<u64>,
+    scroll_tag: Option<u64>,
+) -> Option<u64> {
+
+    let is_hit_testable = match hit_test {
+        HitTestBehavior::Auto => pointer_events != Some(StylePointerEvents::None),
+        HitTestBehavior::None => false,
+        HitTestBehavior::HitTestOnly => true,
+    };
+
+    if is_hit_testable { tag.or(scroll_tag) } else { None }
+}
+
+#[test]
+fn test_get_hit_test_tag() {
+
+    use dom::HitTestBehavior::{Auto, HitTestOnly};
+
+    let none = Some(StylePointerEvents::None);
+    let auto = Some(StylePointerEvents::Auto);
+
+    // Nodes with callbacks are hit-tested, unless `pointer-events: none` is set
+    assert_eq!(get_hit_test_tag(Auto, None, Some(1), None), Some(1));
+    assert_eq!(get_hit_test_tag(Auto, auto, Some(1), None), Some(1));
+    assert_eq!(get_hit_test_tag(Auto, none, Some(1), None), None);
+
+    // Scroll frames use the scroll tag, the tag of the node takes precedence
+    assert_eq!(get_hit_test_tag(Auto, None, None, Some(2)), Some(2));
+    assert_eq!(get_hit_test_tag(Auto, None, Some(1), Some(2)), Some(1));
+    assert_eq!(get_hit_test_tag(Auto, none, None, Some(2)), None);
+
+    // Nodes without any tag are never hit-tested
+    assert_eq!(get_hit_test_tag(Auto, None, None, None), None);
+    assert_eq!(get_hit_test_tag(HitTestOnly, None, None, None), None);
+
+    // The hit-test behavior of the node overrides the `pointer-events`
+    assert_eq!(get_hit_test_tag(HitTestBehavior::None, auto, Some(1), Some(2)), None);
+    assert_eq!(get_hit_test_tag(HitTestOnly, none, Some(1), None), Some(1));
+}
+
 /// Push a single rectangle into the display list builder
 #[inline]
 fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
@@ -618,19 +1836,34 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
     } = rectangle;
 
     let rect = &display_rectangle_arena[*rect_idx];
-    let bounds = layout_result.rects[*rect_idx].bounds;
+    let hit_test = node_data[*rect_idx].hit_test;
+    let display_items = get_node_display_items(&rect.style, hit_test);
+
+    if display_items == NodeDisplayItems::Nothing {
+        return;
+    }
+
+    let bounds = snap_to_device_pixels(layout_result.rects[*rect_idx].bounds, window_size);
 
     let info = LayoutPrimitiveInfo {
         rect: bounds,
         clip_rect: bounds,
         is_backface_visible: false,
-        tag: rect.tag.map(|tag| (tag, 0)).or({
-            scrollable_nodes.overflowing_nodes
-            .get(&rect_idx)
-            .map(|scrolled| (scrolled.scroll_tag_id.0, 0))
-        }),
+        tag: get_hit_test_tag(
+            hit_test,
+            rect.style.pointer_events,
+            rect.tag,
+            scrollable_nodes.overflowing_nodes.get(&rect_idx).map(|scrolled| scrolled.scroll_tag_id.0),
+        ).map(|tag| (tag, 0)),
     };
 
+    if display_items == NodeDisplayItems::HitTestOnly {
+        if info.tag.is_some() {
+            push_rect(&info, referenced_mutable_content.builder, &TRANSPARENT_BG);
+        }
+        return;
+    }
+
     let clip_region_id = get_clip_region(bounds, &rect).map(|clip|
         referenced_mutable_content.builder.define_clip(bounds, vec![clip], None)
     );
@@ -657,10 +1890,10 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
             bg,
             &rect.style.background_size,
             &rect.style.background_repeat,
-            &referenced_mutable_content.app_resources,
+            referenced_mutable_content.app_resources,
+            rectangle.window_size.hidpi_factor as f32,
         );
     } else if info.tag.is_some() {
-        const TRANSPARENT_BG: StyleColorU = StyleColorU { r: 0, g: 0, b: 0, a: 0 };
         push_rect(
             &info,
             referenced_mutable_content.builder,
@@ -691,15 +1924,25 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
                 rect_idx,
                 &rect.style,
                 &rect.layout,
+                referenced_mutable_content.app_resources,
+                referenced_mutable_content.fake_window.text_search.as_mut(),
+            )
+        },
+        Image(image_id) => {
+            let hidpi_factor = rectangle.window_size.hidpi_factor as f32;
+            let image_rect = referenced_mutable_content.app_resources.get_image_size(image_id, hidpi_factor)
+                .map(|image_size| get_object_fit_rect(info.rect, image_size, rect.style.object_fit.unwrap_or_default()))
+                .unwrap_or(info.rect);
+            let (image_id, _) = referenced_mutable_content.app_resources.get_image_variant(image_id, hidpi_factor);
+            // The clip rect stays at the bounds of the node, so that `object-fit: cover` is clipped
+            push_image(
+                &LayoutPrimitiveInfo { rect: image_rect, .. info },
+                referenced_mutable_content.builder,
+                referenced_mutable_content.app_resources,
+                &image_id,
+                image_rect.size,
             )
         },
-        Image(image_id) => push_image(
-            &info,
-            referenced_mutable_content.builder,
-            referenced_mutable_content.app_resources,
-            image_id,
-            LayoutSize::new(info.rect.size.width, info.rect.size.height)
-        ),
         GlTexture(callback) => push_opengl_texture(callback, &info, rectangle, referenced_content, referenced_mutable_content),
         IFrame(callback) => push_iframe(callback, &info, scrollable_nodes, rectangle, referenced_content, referenced_mutable_content),
     };
@@ -715,6 +1958,8 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
     if clip_region_id.is_some() {
         referenced_mutable_content.builder.pop_clip_id();
     }
+
+    // The outline is pushed after the children, see `push_node_outline`
 }
 
 fn push_opengl_texture<'a,'b,'c,'d,'e,'f, T>(
@@ -796,6 +2041,71 @@ fn push_opengl_texture<'a,'b,'c,'d,'e,'f, T>(
     );
 }
 
+/// Last solved content of the IFrames of a window, so that it can be reused (scaled to the
+/// new bounds) while the window is being resized, see `WindowCreateOptions::iframe_resize_preview`
+pub(crate) struct IFrameCache<T> {
+    /// Whether the content of the IFrames is stored at all
+    is_enabled: bool,
+    /// Whether the IFrames should reuse their last content instead of invoking their callbacks
+    is_resizing: bool,
+    /// Whether an IFrame of the last display list was drawn from the cache,
+    /// i.e. whether the IFrameCallbacks have to be invoked once the resizing ends
+    pub(crate) has_stale_content: bool,
+    /// Index of the next IFrame in the display list that is currently built
+    next_index: usize,
+    /// IFrames of the last display list, indexed by the order in which they were pushed
+    iframes: BTreeMap<usize, CachedIFrame<T>>,
+    /// Address of the `IFrameCallback` and styled content of the IFrames of the last display list
+    /// (even if `is_enabled` is false), so that the next display list only has to restyle the nodes
+    /// that changed and can reuse the layout of the IFrame if none of them did
+    ui_descriptions: BTreeMap<usize, (usize, UiDescription<T>)>,
+}
+
+/// Content and layout of a single IFrame, see `IFrameCache`
+struct CachedIFrame<T> {
+    /// Address of the `IFrameCallback`, so that the content is
+    /// not reused for a different IFrame if the DOM changed
+    callback: usize,
+    bounds: LayoutRect,
+    ui_state: UiState<T>,
+    ui_description: UiDescription<T>,
+    layout_result: LayoutResult,
+}
+
+impl<T> IFrameCache<T> {
+
+    pub(crate) fn new() -> Self {
+        Self {
+            is_enabled: false,
+            is_resizing: false,
+            has_stale_content: false,
+            next_index: 0,
+            iframes: BTreeMap::new(),
+            ui_descriptions: BTreeMap::new(),
+        }
+    }
+
+    fn begin_frame(&mut self, is_enabled: bool, is_resizing: bool) {
+        self.is_enabled = is_enabled;
+        self.is_resizing = is_enabled && is_resizing;
+        self.has_stale_content = false;
+        self.next_index = 0;
+    }
+
+    /// Drops the IFrames that weren't part of the last display list
+    fn end_frame(&mut self) {
+        let next_index = self.next_index;
+        self.iframes = mem::replace(&mut self.iframes, BTreeMap::new())
+            .into_iter()
+            .filter(|(index, _)| *index < next_index)
+            .collect();
+        self.ui_descriptions = mem::replace(&mut self.ui_descriptions, BTreeMap::new())
+            .into_iter()
+            .filter(|(index, _)| *index < next_index)
+            .collect();
+    }
+}
+
 fn push_iframe<'a,'b,'c,'d,'e,'f, T>(
     (iframe_callback, iframe_pointer): &(IFrameCallback<T>, StackCheckedPointer<T>),
     info: &LayoutPrimitiveInfo,
@@ -804,61 +2114,102 @@ fn push_iframe<'a,'b,'c,'d,'e,'f, T>(
     referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
     referenced_mutable_content: &mut DisplayListParametersMut<'f, T>,
 ) {
-    let bounds = HidpiAdjustedBounds::from_bounds(
-        info.rect,
-        rectangle.window_size.hidpi_factor,
-        rectangle.window_size.hidpi_factor
-    );
-
-    let new_dom = {
-        // Make sure that the app data is locked before invoking the callback
-        let _lock = referenced_mutable_content.app_data.lock().unwrap();
-
-        let window_info = LayoutInfo {
-            window: referenced_mutable_content.fake_window,
-            resources: &referenced_mutable_content.app_resources,
-        };
-
-        (iframe_callback.0)(&iframe_pointer, window_info, bounds)
+    let iframe_index = referenced_mutable_content.fake_window.iframe_cache.next_index;
+    referenced_mutable_content.fake_window.iframe_cache.next_index += 1;
+
+    let callback_address = iframe_callback.0 as usize;
+
+    // While the window is being resized, the last content of the IFrame is
+    // stretched to the new bounds instead of invoking the IFrameCallback again
+    let cached_iframe = {
+        let iframe_cache = &mut referenced_mutable_content.fake_window.iframe_cache;
+        if iframe_cache.is_resizing {
+            let cached_iframe = iframe_cache.iframes.remove(&iframe_index).filter(|cached| cached.callback == callback_address);
+            iframe_cache.has_stale_content |= cached_iframe.is_some();
+            cached_iframe
+        } else {
+            None
+        }
     };
 
-    // TODO: Right now, no focusing, hovering or :active allowed in iframes!
-    let is_mouse_down = false;
-    let mut focused_node = None;
-    let mut focus_target = None;
-    let hovered_nodes = BTreeMap::new();
-
-    let mut ui_state = new_dom.into_ui_state();
-    let ui_description = UiDescription::<T>::match_css_to_dom(
-        &mut ui_state,
-        &referenced_content.css,
-        &mut focused_node,
-        &mut focus_target,
-        &hovered_nodes,
-        is_mouse_down
-    );
+    let (ui_state, ui_description, cached_layout) = match cached_iframe {
+        Some(cached) => {
+            let preview_transform = get_iframe_preview_transform(&cached.bounds, &info.rect);
+            (cached.ui_state, cached.ui_description, Some((cached.bounds, cached.layout_result, preview_transform)))
+        },
+        None => {
+            let previous = referenced_mutable_content.fake_window.iframe_cache.ui_descriptions.remove(&iframe_index)
+                .filter(|(callback, _)| *callback == callback_address)
+                .map(|(_, ui_description)| ui_description);
+            let (ui_state, ui_description) = invoke_iframe_callback(
+                iframe_callback,
+                iframe_pointer,
+                info,
+                rectangle,
+                referenced_content,
+                referenced_mutable_content,
+                previous.as_ref(),
+            );
+            (ui_state, ui_description, None)
+        },
+    };
 
     let display_list = DisplayList::new_from_ui_description(&ui_description, &ui_state);
-    referenced_mutable_content.app_resources.add_fonts_and_images(&display_list);
+    referenced_mutable_content.app_resources.add_fonts_and_images(&display_list, rectangle.window_size.hidpi_factor as f32);
 
     let arena = &ui_description.ui_descr_arena;
     let node_hierarchy = &arena.node_layout;
     let node_data = &arena.node_data;
 
-    // Insert the DOM into the solver so we can solve the layout of the rectangles
-    let rect_size = LayoutSize::new(
-        info.rect.size.width / rectangle.window_size.hidpi_factor as f32 * rectangle.window_size.winit_hidpi_factor as f32,
-        info.rect.size.height / rectangle.window_size.hidpi_factor as f32 * rectangle.window_size.winit_hidpi_factor as f32,
-    );
-    let rect_origin = LayoutPoint::new(info.rect.origin.x, info.rect.origin.y);
-    let layout_result = do_the_layout(
-        &node_hierarchy,
-        &node_data,
-        &display_list.rectangles,
-        &*referenced_mutable_content.app_resources,
-        rect_size,
-        rect_origin,
-    );
+    let (bounds, layout_result, preview_transform) = match cached_layout {
+        Some((bounds, layout_result, preview_transform)) => (bounds, layout_result, Some(preview_transform)),
+        None => {
+            // Insert the DOM into the solver so we can solve the layout of the rectangles
+            let rect_size = LayoutSize::new(
+                info.rect.size.width / rectangle.window_size.hidpi_factor as f32 * rectangle.window_size.winit_hidpi_factor as f32,
+                info.rect.size.height / rectangle.window_size.hidpi_factor as f32 * rectangle.window_size.winit_hidpi_factor as f32,
+            );
+            let rect_origin = LayoutPoint::new(info.rect.origin.x, info.rect.origin.y);
+            let layout_cache_key = LayoutCacheKey::new(
+                ui_description.layout_generation,
+                &node_data,
+                &display_list.rectangles,
+                &*referenced_mutable_content.app_resources,
+                rectangle.window_size.hidpi_factor as f32,
+                rect_size,
+                rect_origin,
+                false,
+            );
+            let app_resources = &*referenced_mutable_content.app_resources;
+            let layout_result = referenced_mutable_content.fake_window.layout_cache.get_or_layout(layout_cache_key, || do_the_layout(
+                &node_hierarchy,
+                &node_data,
+                &display_list.rectangles,
+                app_resources,
+                rectangle.window_size.hidpi_factor as f32,
+                rect_size,
+                rect_origin,
+                false,
+            ));
+            (info.rect, layout_result, None)
+        },
+    };
+
+    // The transform is already in the coordinate space of the window,
+    // so the stacking context has to start at the origin of the window
+    if let Some(preview_transform) = preview_transform {
+        let content_size = referenced_mutable_content.builder.content_size();
+        referenced_mutable_content.builder.push_stacking_context(
+            &LayoutPrimitiveInfo::new(LayoutRect::new(LayoutPoint::zero(), content_size)),
+            None,
+            Some(PropertyBinding::Value(preview_transform)),
+            TransformStyle::Flat,
+            None,
+            MixBlendMode::Normal,
+            Vec::new(),
+            GlyphRasterSpace::Screen,
+        );
+    }
 
     let mut scrollable_nodes = get_nodes_that_need_scroll_clip(
         node_hierarchy, &display_list.rectangles, node_data, &layout_result.rects,
@@ -888,8 +2239,109 @@ fn push_iframe<'a,'b,'c,'d,'e,'f, T>(
         referenced_mutable_content
     );
 
+    if preview_transform.is_some() {
+        referenced_mutable_content.builder.pop_stacking_context();
+    }
+
     parent_scrollable_nodes.overflowing_nodes.extend(scrollable_nodes.overflowing_nodes.into_iter());
     parent_scrollable_nodes.tags_to_node_ids.extend(scrollable_nodes.tags_to_node_ids.into_iter());
+
+    let iframe_cache = &mut referenced_mutable_content.fake_window.iframe_cache;
+    if iframe_cache.is_enabled {
+        iframe_cache.iframes.insert(iframe_index, CachedIFrame {
+            callback: callback_address,
+            bounds,
+            ui_state,
+            ui_description: ui_description.clone(),
+            layout_result,
+        });
+    }
+    iframe_cache.ui_descriptions.insert(iframe_index, (callback_address, ui_description));
+}
+
+/// Invokes the `IFrameCallback` and styles the returned DOM, only restyling
+/// the nodes that changed since the `previous` content of the IFrame
+fn invoke_iframe_callback<'a,'b,'c,'d,'e,'f, T>(
+    iframe_callback: &IFrameCallback<T>,
+    iframe_pointer: &StackCheckedPointer<T>,
+    info: &LayoutPrimitiveInfo,
+    rectangle: &DisplayListRectParams<'a, T>,
+    referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
+    referenced_mutable_content: &mut DisplayListParametersMut<'f, T>,
+    previous: Option<&UiDescription<T>>,
+) -> (UiState<T>, UiDescription<T>) {
+
+    let bounds = HidpiAdjustedBounds::from_bounds(
+        info.rect,
+        rectangle.window_size.hidpi_factor,
+        rectangle.window_size.hidpi_factor
+    );
+
+    let new_dom = {
+        // Make sure that the app data is locked before invoking the callback
+        let _lock = referenced_mutable_content.app_data.lock().unwrap();
+
+        let window_info = LayoutInfo {
+            window: referenced_mutable_content.fake_window,
+            resources: &referenced_mutable_content.app_resources,
+        };
+
+        (iframe_callback.0)(&iframe_pointer, window_info, bounds)
+    };
+
+    // TODO: Right now, no focusing, hovering or :active allowed in iframes!
+    let is_mouse_down = false;
+    let mut focused_node = None;
+    let mut focus_target = None;
+    let hovered_nodes = BTreeMap::new();
+
+    // @media queries inside of the iframe are evaluated against the iframe bounds
+    let viewport = CssViewport {
+        width: info.rect.size.width,
+        height: info.rect.size.height,
+        hidpi_factor: rectangle.window_size.hidpi_factor as f32,
+    };
+
+    let mut ui_state = new_dom.into_ui_state();
+    ui_state.builtin_css_variables = referenced_mutable_content.app_resources.get_system_colors().get_variables();
+    let ui_description = match previous {
+        Some(previous) => UiDescription::<T>::match_css_to_dom_incremental(
+            previous,
+            &mut ui_state,
+            &referenced_content.css,
+            viewport,
+            &mut focused_node,
+            &mut focus_target,
+            &hovered_nodes,
+            is_mouse_down
+        ),
+        None => UiDescription::<T>::match_css_to_dom(
+            &mut ui_state,
+            &referenced_content.css,
+            viewport,
+            &mut focused_node,
+            &mut focus_target,
+            &hovered_nodes,
+            is_mouse_down
+        ),
+    };
+
+    (ui_state, ui_description)
+}
+
+/// Transform that stretches the content of an IFrame that was laid out at
+/// `old_bounds` to the `new_bounds`, in the coordinate space of the window
+fn get_iframe_preview_transform(old_bounds: &LayoutRect, new_bounds: &LayoutRect) -> LayoutTransform {
+
+    let scale_x = if old_bounds.size.width > 0.0 { new_bounds.size.width / old_bounds.size.width } else { 1.0 };
+    let scale_y = if old_bounds.size.height > 0.0 { new_bounds.size.height / old_bounds.size.height } else { 1.0 };
+
+    LayoutTransform::row_major_2d(
+        scale_x, 0.0,
+        0.0, scale_y,
+        new_bounds.origin.x - old_bounds.origin.x * scale_x,
+        new_bounds.origin.y - old_bounds.origin.y * scale_y,
+    )
 }
 
 /// Since the display list can take a lot of parameters, we don't want to
@@ -947,6 +2399,8 @@ fn push_text(
     node_id: &NodeId,
     rect_style: &RectStyle,
     rect_layout: &RectLayout,
+    app_resources: &mut AppResources,
+    text_search: Option<&mut TextSearch>,
 ) {
     use text_layout::get_layouted_glyphs;
     use css::webrender_translate::wr_translate_color_u;
@@ -962,6 +2416,9 @@ fn push_text(
         None => return,
     };
 
+    // Percentage paddings are only known after the layout
+    let rect_layout = &RectLayout { padding: Some(layout_result.rects[*node_id].padding), .. *rect_layout };
+
     let (horz_alignment, vert_alignment) = determine_text_alignment(rect_style, rect_layout);
 
     let rect_padding_top = rect_layout.padding.unwrap_or_default().top.map(|top| top.to_pixels()).unwrap_or(0.0);
@@ -1023,6 +2480,12 @@ fn push_text(
         builder.push_clip_id(clip_id);
     }
 
+    if let (Some(text_search), Some(words)) = (text_search, layout_result.word_cache.get(node_id)) {
+        push_search_highlights(builder, text_search, *node_id, words, scaled_words, &layouted_glyphs);
+    }
+
+    app_resources.record_drawn_glyphs(*font_instance_key, &layouted_glyphs.glyphs);
+
     builder.push_text(
         &info,
         &layouted_glyphs.glyphs,
@@ -1034,11 +2497,158 @@ fn push_text(
         })
     );
 
+    if let Some(text_decoration) = rect_style.text_decoration.filter(|td| td.has_lines()) {
+        push_text_decoration(builder, &text_decoration, scaled_words, &layouted_glyphs, font_color.into());
+    }
+
     if text_bounds.is_some() {
         builder.pop_clip_id();
     }
 }
 
+/// Pushes the lines of a `text-decoration` (in front of the text), positioned
+/// relative to the baseline and the ascender / descender of the font
+fn push_text_decoration(
+    builder: &mut DisplayListBuilder,
+    text_decoration: &StyleTextDecoration,
+    scaled_words: &ScaledWords,
+    layouted_glyphs: &LayoutedGlyphs,
+    font_color: ColorF,
+) {
+    use text_layout::get_line_extents;
+    use css::webrender_translate::wr_translate_color_u;
+
+    let color = match text_decoration.color {
+        Some(c) => wr_translate_color_u(c).into(),
+        None => font_color,
+    };
+
+    let thickness = (scaled_words.font_size_px / 14.0).max(1.0);
+
+    // Offsets of the top edge of the lines, relative to the baseline
+    let mut line_offsets = Vec::new();
+    if text_decoration.underline {
+        line_offsets.push((scaled_words.descender_px.abs() * 0.4).max(thickness));
+    }
+    if text_decoration.overline {
+        line_offsets.push(-scaled_words.ascender_px);
+    }
+    if text_decoration.line_through {
+        line_offsets.push(-scaled_words.ascender_px * 0.35 - thickness / 2.0);
+    }
+
+    // Wavy lines need some vertical space for the amplitude of the wave
+    let (line_style, line_height) = match text_decoration.style {
+        StyleTextDecorationStyle::Solid |
+        StyleTextDecorationStyle::Double => (LineStyle::Solid, thickness),
+        StyleTextDecorationStyle::Dotted => (LineStyle::Dotted, thickness),
+        StyleTextDecorationStyle::Dashed => (LineStyle::Dashed, thickness),
+        StyleTextDecorationStyle::Wavy => (LineStyle::Wavy, thickness * 3.0),
+    };
+
+    let mut push_line = |x_start: f32, x_end: f32, y: f32| {
+        let info = LayoutPrimitiveInfo::new(LayoutRect::new(
+            LayoutPoint::new(x_start, y),
+            LayoutSize::new(x_end - x_start, line_height),
+        ));
+        builder.push_line(&info, thickness, LineOrientation::Horizontal, &color, line_style);
+    };
+
+    for line in get_line_extents(layouted_glyphs, scaled_words) {
+        for offset in &line_offsets {
+            let y = line.baseline + offset - (line_height - thickness) / 2.0;
+            push_line(line.x_start, line.x_end, y);
+            if text_decoration.style == StyleTextDecorationStyle::Double {
+                push_line(line.x_start, line.x_end, y + thickness * 2.0);
+            }
+        }
+    }
+}
+
+/// Pushes the highlight rectangles for all search matches in a text node (behind the text)
+/// and records the matches in the `text_search`
+fn push_search_highlights(
+    builder: &mut DisplayListBuilder,
+    text_search: &mut TextSearch,
+    node_id: NodeId,
+    words: &Words,
+    scaled_words: &ScaledWords,
+    layouted_glyphs: &LayoutedGlyphs,
+) {
+    use text_layout::search_layouted_glyphs;
+
+    let matches = search_layouted_glyphs(
+        words,
+        scaled_words,
+        layouted_glyphs,
+        &text_search.query,
+        text_search.case_sensitive
+    );
+
+    for rects in matches {
+        let color = if text_search.active_match == Some(text_search.matches.len()) {
+            SEARCH_ACTIVE_HIGHLIGHT_COLOR
+        } else {
+            SEARCH_HIGHLIGHT_COLOR
+        };
+
+        for rect in &rects {
+            push_rect(&LayoutPrimitiveInfo::new(*rect), builder, &color);
+        }
+
+        text_search.matches.push(TextSearchMatch { node_id, rects });
+    }
+}
+
+/// If the active search match was changed, scrolls the closest scrollable
+/// parent of the match so that the match is visible
+fn scroll_to_active_search_match(
+    text_search: &mut TextSearch,
+    node_hierarchy: &NodeHierarchy,
+    scrolled_nodes: &ScrolledNodes,
+    scroll_states: &mut ScrollStates,
+) {
+    if !text_search.scroll_to_active_match {
+        return;
+    }
+
+    text_search.scroll_to_active_match = false;
+
+    let active_match = match text_search.get_active_match() {
+        Some(s) => s,
+        None => return,
+    };
+
+    let match_bounds = match active_match.rects.iter().skip(1).fold(active_match.rects.first().cloned(), |bounds, rect| {
+        bounds.map(|b| b.union(rect))
+    }) {
+        Some(s) => s,
+        None => return,
+    };
+
+    let mut current_node = node_hierarchy.get(active_match.node_id).map(|_| active_match.node_id);
+
+    while let Some(node_id) = current_node {
+        if let Some(scroll_node) = scrolled_nodes.overflowing_nodes.get(&node_id) {
+            let frame = scroll_node.parent_rect.bounds;
+            let target = LayoutRect::new(
+                LayoutPoint::new(match_bounds.origin.x - frame.origin.x, match_bounds.origin.y - frame.origin.y),
+                match_bounds.size,
+            );
+            scroll_states.scroll_into_view(
+                scroll_node.parent_external_scroll_id,
+                (scroll_node.child_rect.size.width - frame.size.width).max(0.0),
+                (scroll_node.child_rect.size.height - frame.size.height).max(0.0),
+                frame.size.width,
+                frame.size.height,
+                target,
+            );
+            return;
+        }
+        current_node = node_hierarchy[node_id].parent;
+    }
+}
+
 enum ShouldPushShadow {
     OneShadow,
     TwoShadows,
@@ -1061,13 +2671,28 @@ fn push_box_shadow(
 
     // Box-shadow can be applied to each corner separately. This means, in practice
     // that we simply overlay multiple shadows with shifted clipping rectangles
-    let StyleBoxShadow { top, left, bottom, right } = match &style.box_shadow {
+    let StyleBoxShadow { top, left, bottom, right, additional_shadows } = match &style.box_shadow {
         Some(s) => s,
         None => return,
     };
 
     let border_radius = style.border_radius.unwrap_or(StyleBorderRadius::zero());
 
+    // The first shadow of a comma-separated list has to end up on top,
+    // so the remaining shadows are pushed first, in reverse order
+    if let Some(additional_shadows) = additional_shadows {
+        for shadow in additional_shadows.iter().rev() {
+            push_box_shadow_inner(
+                builder,
+                &Some(*shadow),
+                border_radius,
+                bounds,
+                get_clip_rect(shadow, bounds),
+                shadow_type
+            );
+        }
+    }
+
     let what_shadow_to_push = match [top, left, bottom, right].iter().filter(|x| x.is_some()).count() {
         1 => OneShadow,
         2 => TwoShadows,
@@ -1144,6 +2769,22 @@ fn push_box_shadow(
     }
 }
 
+// Apply a gamma of 2.2 to the original value
+//
+// NOTE: strangely box-shadow is the only thing that needs to be gamma-corrected...
+fn apply_gamma(color: ColorF) -> ColorF {
+
+    const GAMMA: f32 = 2.2;
+    const GAMMA_F: f32 = 1.0 / GAMMA;
+
+    ColorF {
+        r: color.r.powf(GAMMA_F),
+        g: color.g.powf(GAMMA_F),
+        b: color.b.powf(GAMMA_F),
+        a: color.a,
+    }
+}
+
 fn push_box_shadow_inner(
     builder: &mut DisplayListBuilder,
     pre_shadow: &Option<BoxShadowPreDisplayItem>,
@@ -1152,7 +2793,6 @@ fn push_box_shadow_inner(
     clip_rect: LayoutRect,
     shadow_type: BoxShadowClipMode)
 {
-    use webrender::api::LayoutVector2D;
     use css::webrender_translate::{
         wr_translate_color_u, wr_translate_border_radius,
         wr_translate_box_shadow_clip_mode
@@ -1173,22 +2813,6 @@ fn push_box_shadow_inner(
     // prevent shadows that are larger than the full screen
     let clip_rect = clip_rect.intersection(&full_screen_rect).unwrap_or(clip_rect);
 
-    // Apply a gamma of 2.2 to the original value
-    //
-    // NOTE: strangely box-shadow is the only thing that needs to be gamma-corrected...
-    fn apply_gamma(color: ColorF) -> ColorF {
-
-        const GAMMA: f32 = 2.2;
-        const GAMMA_F: f32 = 1.0 / GAMMA;
-
-        ColorF {
-            r: color.r.powf(GAMMA_F),
-            g: color.g.powf(GAMMA_F),
-            b: color.b.powf(GAMMA_F),
-            a: color.a,
-        }
-    }
-
     let info = LayoutPrimitiveInfo::with_clip_rect(LayoutRect::zero(), clip_rect);
     builder.push_box_shadow(
         &info,
@@ -1304,7 +2928,8 @@ fn push_background(
     background: &StyleBackground,
     background_size: &Option<StyleBackgroundSize>,
     background_repeat: &Option<StyleBackgroundRepeat>,
-    app_resources: &AppResources)
+    app_resources: &mut AppResources,
+    hidpi_factor: f32)
 {
     use azul_css::{Shape, StyleBackground::*};
     use css::webrender_translate::{
@@ -1352,14 +2977,27 @@ fn push_background(
 
             builder.push_gradient(&info, gradient, bounds.size, LayoutSize::zero());
         },
+        ConicGradient(gradient) => {
+            // WebRender can't draw conic gradients, so they are rasterized into an image
+            // in physical pixels (the size is limited, larger gradients are stretched)
+            const MAX_CONIC_GRADIENT_SIZE: f32 = 2048.0;
+            let width = (bounds.size.width * hidpi_factor).min(MAX_CONIC_GRADIENT_SIZE).round();
+            let height = (bounds.size.height * hidpi_factor).min(MAX_CONIC_GRADIENT_SIZE).round();
+            if width >= 1.0 && height >= 1.0 {
+                let image_id = app_resources.get_conic_gradient_image(gradient, width as u32, height as u32);
+                push_image(&info, builder, app_resources, &image_id, bounds.size);
+            }
+        },
         Image(style_image_id) => {
             // TODO: background-origin, background-position, background-repeat
             if let Some(image_id) = app_resources.get_css_image_id(&style_image_id.0) {
 
+                // An "@2x" image is drawn at half of its size in pixels
                 let bounds = info.rect;
-                let image_dimensions = app_resources.get_image_info(image_id)
-                    .map(|info| (info.descriptor.size.width, info.descriptor.size.height))
+                let image_dimensions = app_resources.get_image_size(image_id, hidpi_factor)
+                    .map(|(width, height)| (width as i32, height as i32))
                     .unwrap_or((bounds.size.width as i32, bounds.size.height as i32)); // better than crashing...
+                let (image_id, _) = app_resources.get_image_variant(image_id, hidpi_factor);
 
                 let size = match background_size {
                     Some(bg_size) => calculate_background_size(bg_size, &info, &image_dimensions),
@@ -1369,7 +3007,7 @@ fn push_background(
                 let background_repeat = background_repeat.unwrap_or_default();
                 let background_repeat_info = get_background_repeat_info(&info, background_repeat, size);
 
-                push_image(&background_repeat_info, builder, app_resources, image_id, size);
+                push_image(&background_repeat_info, builder, app_resources, &image_id, size);
             }
         },
         Color(c) => {
@@ -1456,6 +3094,53 @@ fn push_image(
     }
 }
 
+/// Returns the rect that an image of `image_size` (width, height) is drawn
+/// at, so that it is scaled to the `bounds` of its node as the `object-fit` says
+fn get_object_fit_rect(bounds: LayoutRect, image_size: (f32, f32), object_fit: StyleObjectFit) -> LayoutRect {
+
+    use azul_css::StyleObjectFit::*;
+
+    let (image_width, image_height) = image_size;
+    if image_width <= 0.0 || image_height <= 0.0 {
+        return bounds;
+    }
+
+    let contain_scale = (bounds.size.width / image_width).min(bounds.size.height / image_height);
+    let scale = match object_fit {
+        Fill => return bounds,
+        Contain => contain_scale,
+        Cover => (bounds.size.width / image_width).max(bounds.size.height / image_height),
+        None => 1.0,
+        ScaleDown => contain_scale.min(1.0),
+    };
+
+    // The scaled image is centered in the bounds
+    let size = LayoutSize::new(image_width * scale, image_height * scale);
+    let origin = LayoutPoint::new(
+        bounds.origin.x + (bounds.size.width - size.width) / 2.0,
+        bounds.origin.y + (bounds.size.height - size.height) / 2.0,
+    );
+
+    LayoutRect::new(origin, size)
+}
+
+#[test]
+fn test_object_fit_rect() {
+
+    let bounds = LayoutRect::new(LayoutPoint::new(10.0, 10.0), LayoutSize::new(200.0, 100.0));
+    let rect = |x, y, w, h| LayoutRect::new(LayoutPoint::new(x, y), LayoutSize::new(w, h));
+
+    assert_eq!(get_object_fit_rect(bounds, (50.0, 50.0), StyleObjectFit::Fill), bounds);
+    // Limited by the height, centered horizontally
+    assert_eq!(get_object_fit_rect(bounds, (50.0, 50.0), StyleObjectFit::Contain), rect(60.0, 10.0, 100.0, 100.0));
+    // Limited by the width, overflows at the top and bottom
+    assert_eq!(get_object_fit_rect(bounds, (50.0, 50.0), StyleObjectFit::Cover), rect(10.0, -40.0, 200.0, 200.0));
+    assert_eq!(get_object_fit_rect(bounds, (50.0, 50.0), StyleObjectFit::None), rect(85.0, 35.0, 50.0, 50.0));
+    // Smaller than the bounds, so it isn't scaled up
+    assert_eq!(get_object_fit_rect(bounds, (50.0, 50.0), StyleObjectFit::ScaleDown), rect(85.0, 35.0, 50.0, 50.0));
+    assert_eq!(get_object_fit_rect(bounds, (400.0, 400.0), StyleObjectFit::ScaleDown), rect(60.0, 10.0, 100.0, 100.0));
+}
+
 #[inline]
 fn push_border(
     info: &PrimitiveInfo<LayoutPixel>,
@@ -1475,6 +3160,54 @@ fn push_border(
     }
 }
 
+/// Pushes the `outline` as a border around the `bounds`, grown by the
+/// `outline-offset`. The outline doesn't take up any space in the layout.
+fn push_outline(
+    bounds: &LayoutRect,
+    builder: &mut DisplayListBuilder,
+    outline: &StyleOutline,
+    outline_offset: &Option<StyleOutlineOffset>,
+    border_radius: &Option<StyleBorderRadius>)
+{
+    use azul_css::BorderStyle;
+
+    let side = outline.0;
+    let width = side.border_width.to_pixels();
+
+    if width <= 0.0 || side.border_style == BorderStyle::None || side.border_style == BorderStyle::Hidden {
+        return;
+    }
+
+    let offset = outline_offset.map(|o| o.0.to_pixels()).unwrap_or(0.0);
+    let spread = offset + width;
+    let outline_bounds = bounds.inflate(spread, spread);
+
+    if outline_bounds.size.width <= 0.0 || outline_bounds.size.height <= 0.0 {
+        return;
+    }
+
+    // Rounded corners of the node stay rounded, so that the outline follows the border
+    let grow = |radius: PixelValue| {
+        let radius = radius.to_pixels();
+        PixelValue::px(if radius > 0.0 { (radius + spread).max(0.0) } else { 0.0 })
+    };
+
+    let outline_radius = border_radius.map(|StyleBorderRadius(mut r)| {
+        for corner in &mut [&mut r.top_left, &mut r.top_right, &mut r.bottom_left, &mut r.bottom_right] {
+            corner.width = grow(corner.width);
+            corner.height = grow(corner.height);
+        }
+        StyleBorderRadius(r)
+    });
+
+    push_border(
+        &LayoutPrimitiveInfo::new(outline_bounds),
+        builder,
+        &StyleBorder::all(side),
+        &outline_radius,
+    );
+}
+
 /// Subtracts the padding from the bounds, returning the new bounds
 ///
 /// Warning: The resulting rectangle may have negative width or height
@@ -1528,9 +3261,153 @@ fn populate_css_properties(
                 } else if let DynamicCssPropertyDefault::Exact(default) = &dynamic_property.default {
                     apply_style_property(rect, default);
                 }
-            }
+            },
+            Unresolved(_) => {
+                // var() references are resolved during the cascade, properties
+                // that couldn't be resolved never end up in the styled node
+            },
+        }
+    }
+
+    // The `-inline-start` / `-inline-end` sides depend on the (possibly inherited) `direction`,
+    // so they can only be resolved after all properties are applied. Where a logical and a
+    // physical property set the same side, the one that comes later in the cascade wins.
+    let text_direction = rect.style.text_direction.unwrap_or_default();
+    if let Some(padding_inline) = rect.layout.padding_inline {
+        let logical_wins = rect.styled_node.is_declared_after(CssPropertyType::PaddingInline, CssPropertyType::Padding);
+        rect.layout.padding = merge_logical_sides(rect.layout.padding, padding_inline.to_physical(text_direction), logical_wins, LayoutPadding::merge);
+    }
+    if let Some(margin_inline) = rect.layout.margin_inline {
+        let logical_wins = rect.styled_node.is_declared_after(CssPropertyType::MarginInline, CssPropertyType::Margin);
+        rect.layout.margin = merge_logical_sides(rect.layout.margin, margin_inline.to_physical(text_direction), logical_wins, LayoutMargin::merge);
+    }
+    if let Some(border_inline) = rect.style.border_inline {
+        let logical_wins = rect.styled_node.is_declared_after(CssPropertyType::BorderInline, CssPropertyType::Border);
+        rect.style.border = merge_logical_sides(rect.style.border, border_inline.to_physical(text_direction), logical_wins, StyleBorder::merge);
+    }
+}
+
+/// Merges the resolved sides of a logical property with the physical property: the sides
+/// that both of them set are taken from the `logical` property if `logical_wins`.
+fn merge_logical_sides<T>(physical: Option<T>, logical: T, logical_wins: bool, merge: fn(&mut Option<T>, &T)) -> Option<T> {
+    if logical_wins {
+        let mut merged = physical;
+        merge(&mut merged, &logical);
+        merged
+    } else {
+        let mut merged = Some(logical);
+        if let Some(physical) = physical {
+            merge(&mut merged, &physical);
+        }
+        merged
+    }
+}
+
+/// Applies the style properties of a node like `DisplayList::new_from_ui_description`,
+/// see `UiDescription::get_computed_style`
+pub(crate) fn get_computed_style<T>(ui_description: &UiDescription<T>, node_id: NodeId) -> Option<ComputedStyle> {
+    let styled_node = ui_description.styled_nodes.get(node_id)?;
+    let mut rect = DisplayRectangle::new(None, styled_node);
+    populate_css_properties(&mut rect, node_id, &ui_description.dynamic_css_overrides);
+    Some(ComputedStyle { style: rect.style, layout: rect.layout })
+}
+
+#[test]
+fn test_get_computed_style() {
+
+    use azul_css::*;
+    use dom::Dom;
+
+    struct DataModel;
+
+    let mut ui_state = Dom::<DataModel>::div().with_class("button")
+        .with_child(Dom::label("Save").with_style(CssProperty::Width(LayoutWidth::px(50.0))))
+        .into_ui_state();
+
+    let red = StyleTextColor(ColorU { r: 255, g: 0, b: 0, a: 255 });
+    let css = Css {
+        stylesheets: vec![Stylesheet {
+            rules: vec![CssRuleBlock {
+                path: CssPath { selectors: vec![CssPathSelector::Class("button".into())] },
+                declarations: vec![CssDeclaration::Static(CssProperty::TextColor(red))],
+                variables: Vec::new(),
+                important: false,
+            }],
+            media_queries: Vec::new(),
+        }],
+        font_faces: Vec::new(),
+    };
+
+    let ui_description = UiDescription::match_css_to_dom(&mut ui_state, &css, CssViewport::default(), &mut None, &mut None, &BTreeMap::new(), false);
+
+    // The color is inherited from the parent, the width is an inline style
+    let label_style = ui_description.get_computed_style(NodeId::new(1)).unwrap();
+    assert_eq!(label_style.style.font_color, Some(red));
+    assert_eq!(label_style.layout.width, Some(LayoutWidth::px(50.0)));
+    assert_eq!(ui_description.get_computed_style(NodeId::new(2)), None);
+}
+
+#[test]
+fn test_logical_and_physical_sides_in_cascade_order() {
+
+    use azul_css::*;
+    use dom::Dom;
+
+    struct DataModel;
+
+    let padding_left = CssProperty::Padding(LayoutPadding { left: Some(PixelValue::px(10.0)), .. Default::default() });
+    let padding_inline_start = CssProperty::PaddingInline(LayoutPaddingInline { start: Some(PixelValue::px(20.0)), end: None });
+
+    let mut ui_state = Dom::<DataModel>::div()
+        .with_child(Dom::div().with_style(padding_left.clone()).with_style(padding_inline_start.clone()))
+        .with_child(Dom::div().with_style(padding_inline_start.clone()).with_style(padding_left.clone()))
+        .into_ui_state();
+
+    let ui_description = UiDescription::match_css_to_dom(&mut ui_state, &Css::default(), CssViewport::default(), &mut None, &mut None, &BTreeMap::new(), false);
+
+    let logical_last = ui_description.get_computed_style(NodeId::new(1)).unwrap().layout.padding.unwrap();
+    assert_eq!(logical_last.left, Some(PixelValue::px(20.0)));
+    assert_eq!(logical_last.right, None);
+
+    let physical_last = ui_description.get_computed_style(NodeId::new(2)).unwrap().layout.padding.unwrap();
+    assert_eq!(physical_last.left, Some(PixelValue::px(10.0)));
+    assert_eq!(physical_last.right, None);
+}
+
+/// Applies the `::scrollbar`, `::track` and `::thumb` styles to the default scrollbar. Only the
+/// `width` and `padding` of the `::scrollbar` and the `background` and `border-radius`
+/// of the track and the thumb are used, since they are the only properties that are drawn.
+fn get_scrollbar_info(scrollbar_styles: &ScrollbarStyledNode) -> ScrollbarInfo {
+
+    fn resolve_pseudo_element(styled_node: &StyledNode) -> (RectStyle, RectLayout) {
+        let mut rect = DisplayRectangle::new(None, styled_node);
+        populate_css_properties(&mut rect, NodeId::new(0), &BTreeMap::new());
+        (rect.style, rect.layout)
+    }
+
+    fn merge_part(part: &mut RectStyle, style: RectStyle) {
+        if style.background.is_some() {
+            part.background = style.background;
         }
+        if style.border_radius.is_some() {
+            part.border_radius = style.border_radius;
+        }
+    }
+
+    let mut scrollbar_info = ScrollbarInfo::default();
+
+    let (_, scrollbar_layout) = resolve_pseudo_element(&scrollbar_styles.scrollbar);
+    if let Some(width) = scrollbar_layout.width {
+        scrollbar_info.width = width;
     }
+    if let Some(padding) = scrollbar_layout.padding {
+        scrollbar_info.padding = padding;
+    }
+
+    merge_part(&mut scrollbar_info.track, resolve_pseudo_element(&scrollbar_styles.track).0);
+    merge_part(&mut scrollbar_info.thumb, resolve_pseudo_element(&scrollbar_styles.thumb).0);
+
+    scrollbar_info
 }
 
 // Assert that the types of two properties matches
@@ -1560,6 +3437,16 @@ fn apply_style_property(rect: &mut DisplayRectangle, property: &CssProperty) {
         TextAlign(ta)       => { rect.style.text_align = Some(*ta);                     },
         BoxShadow(b)        => { StyleBoxShadow::merge(&mut rect.style.box_shadow, b);  },
         LineHeight(lh)      => { rect.style.line_height = Some(*lh);                    },
+        Opacity(o)          => { rect.style.opacity = Some(*o);                         },
+        Visibility(v)       => { rect.style.visibility = Some(*v);                      },
+        MixBlendMode(m)     => { rect.style.mix_blend_mode = Some(*m);                  },
+        ObjectFit(o)        => { rect.style.object_fit = Some(*o);                      },
+        Outline(o)          => { rect.style.outline = Some(*o);                         },
+        OutlineOffset(o)    => { rect.style.outline_offset = Some(*o);                  },
+        Transform(t)        => { rect.style.transform = Some(t.clone());                },
+        TransformOrigin(o)  => { rect.style.transform_origin = Some(*o);                },
+        TransformStyle(t)   => { rect.style.transform_style = Some(*t);                 },
+        Perspective(p)      => { rect.style.perspective = Some(*p);                     },
 
         Width(w)            => { rect.layout.width = Some(*w);                          },
         Height(h)           => { rect.layout.height = Some(*h);                         },
@@ -1576,17 +3463,46 @@ fn apply_style_property(rect: &mut DisplayRectangle, property: &CssProperty) {
 
         Padding(p)          => { LayoutPadding::merge(&mut rect.layout.padding, &p);    },
         Margin(m)           => { LayoutMargin::merge(&mut rect.layout.margin, &m);      },
+        PaddingInline(p)    => { LayoutPaddingInline::merge(&mut rect.layout.padding_inline, &p); },
+        MarginInline(m)     => { LayoutMarginInline::merge(&mut rect.layout.margin_inline, &m); },
+        BorderInline(b)     => { StyleBorderInline::merge(&mut rect.style.border_inline, &b); },
+        TextDirection(d)    => { rect.style.text_direction = Some(*d);                  },
         Overflow(o)         => { LayoutOverflow::merge(&mut rect.layout.overflow, &o);  },
+        ScrollSnapType(s)   => { rect.layout.scroll_snap_type = Some(*s);               },
+        ScrollSnapAlign(s)  => { rect.layout.scroll_snap_align = Some(*s);              },
         WordSpacing(ws)     => { rect.style.word_spacing = Some(*ws);                   },
         TabWidth(tw)        => { rect.style.tab_width = Some(*tw);                      },
+        TabStops(ts)        => { rect.style.tab_stops = Some(ts.clone());               },
+        WhiteSpace(ws)      => { rect.style.white_space = Some(*ws);                    },
+        TextOverflow(to)    => { rect.style.text_overflow = Some(*to);                  },
+        TextDecoration(td)  => { rect.style.text_decoration = Some(*td);                },
 
         FlexGrow(g)         => { rect.layout.flex_grow = Some(*g)                       },
         FlexShrink(s)       => { rect.layout.flex_shrink = Some(*s)                     },
+        FlexBasis(b)        => { rect.layout.flex_basis = Some(*b)                      },
+        Order(o)            => { rect.layout.order = Some(*o)                           },
         FlexWrap(w)         => { rect.layout.wrap = Some(*w);                           },
         FlexDirection(d)    => { rect.layout.direction = Some(*d);                      },
         JustifyContent(j)   => { rect.layout.justify_content = Some(*j);                },
         AlignItems(a)       => { rect.layout.align_items = Some(*a);                    },
         AlignContent(a)     => { rect.layout.align_content = Some(*a);                  },
-        Cursor(_)           => { /* cursor neither affects layout nor styling */        },
+        Display(d)          => { rect.layout.display = Some(*d);                        },
+        GridTemplateColumns(c) => { rect.grid_template_columns = c.0.clone();          },
+        GridTemplateRows(r) => { rect.grid_template_rows = r.0.clone();                 },
+        GridGap(g)          => { rect.layout.grid_gap = Some(*g);                       },
+        Gap(g)              => {
+            rect.layout.row_gap = Some(LayoutRowGap(g.row));
+            rect.layout.column_gap = Some(LayoutColumnGap(g.column));
+        },
+        RowGap(g)           => { rect.layout.row_gap = Some(*g);                        },
+        ColumnGap(g)        => { rect.layout.column_gap = Some(*g);                     },
+        GridColumn(c)       => { rect.layout.grid_column = Some(*c);                    },
+        GridRow(r)          => { rect.layout.grid_row = Some(*r);                       },
+        Float(f)            => { rect.layout.float = Some(*f);                          },
+        Cursor(_)           => { /* set as the OS cursor during hit-testing */          },
+        PointerEvents(pe)   => { rect.style.pointer_events = Some(*pe);                 },
+        UserSelect(us)      => { rect.style.user_select = Some(*us);                    },
+        Content(_)          => { /* only used to generate ::before / ::after nodes */   },
+        Calc(c)             => { rect.layout_calc.push(c.clone());                      },
     }
 }
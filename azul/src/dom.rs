@@ -1,11 +1,13 @@
 use std::{
     fmt,
     path::Path,
+    mem,
     hash::{Hash, Hasher},
     sync::atomic::{AtomicUsize, Ordering},
     cmp::Ordering as CmpOrdering,
-    collections::BTreeMap,
+    collections::{BTreeMap, hash_map::DefaultHasher},
     iter::FromIterator,
+    time::Duration,
 };
 use azul_css::{ NodeTypePath, CssProperty };
 use {
@@ -15,11 +17,17 @@ use {
         Callback, GlTextureCallback, IFrameCallback,
     },
     app_resources::{ImageId, TextId},
+    animation::PresenceAnimation,
     id_tree::{Arena, NodeDataContainer},
     xml::{self, XmlParseError, XmlComponentMap},
+    window::FakeWindow,
 };
 
-pub use id_tree::{NodeHierarchy, Node, NodeId};
+pub use id_tree::{
+    NodeHierarchy, Node, NodeId, NodeEdge,
+    Ancestors, PrecedingSiblings, FollowingSiblings, Children, ReverseChildren,
+    Descendants, Traverse, ReverseTraverse,
+};
 
 static TAG_ID: AtomicUsize = AtomicUsize::new(1);
 
@@ -261,6 +269,15 @@ pub enum On {
     FocusReceived,
     /// Equivalent to `onblur`
     FocusLost,
+    /// The left mouse button has been held down over the element for the given
+    /// duration, without being released or leaving the element. Fires once per press,
+    /// i.e. for opening a context menu on touch screens.
+    LongPress(Duration),
+    /// Fires when the left mouse button is pressed over the element and then, while
+    /// it is held down, repeatedly after an initial delay (like a held key), i.e. for the
+    /// arrows of a spinner. The timing is set via `WindowState::hold_repeat` and
+    /// defaults to the key repeat settings of the operating system.
+    HoldRepeat,
 }
 
 /// Sets the target for what events can reach the callbacks specifically.
@@ -357,6 +374,8 @@ impl From<On> for EventFilter {
             HoveredFileCancelled => EventFilter::Hover(HoverEventFilter::HoveredFileCancelled),
             FocusReceived        => EventFilter::Focus(FocusEventFilter::FocusReceived),        // focus!
             FocusLost            => EventFilter::Focus(FocusEventFilter::FocusLost),            // focus!
+            LongPress(duration)  => EventFilter::Hover(HoverEventFilter::LongPress(duration)),
+            HoldRepeat           => EventFilter::Hover(HoverEventFilter::HoldRepeat),
         }
     }
 }
@@ -382,6 +401,8 @@ pub enum HoverEventFilter {
     HoveredFile,
     DroppedFile,
     HoveredFileCancelled,
+    LongPress(Duration),
+    HoldRepeat,
 }
 
 impl HoverEventFilter {
@@ -406,6 +427,8 @@ impl HoverEventFilter {
             HoveredFile => None,
             DroppedFile => None,
             HoveredFileCancelled => None,
+            LongPress(_) => None,
+            HoldRepeat => None,
         }
     }
 }
@@ -504,6 +527,8 @@ pub struct NodeData<T> {
     pub ids: Vec<DomString>,
     /// `.myclass .otherclass`
     pub classes: Vec<DomString>,
+    /// `data-state="error"`, can be selected via `[data-state="error"]` in the CSS
+    pub attributes: Vec<(DomString, DomString)>,
     /// `On::MouseUp` -> `Callback(my_button_click_handler)`
     pub callbacks: Vec<(EventFilter, Callback<T>)>,
     /// Usually not set by the user directly - `FakeWindow::add_default_callback`
@@ -528,14 +553,44 @@ pub struct NodeData<T> {
     /// }
     /// ```
     pub dynamic_css_overrides: Vec<(DomString, CssProperty)>,
+    /// Inline styles of this node, similar to `style="width: 20px"` in HTML. They take precedence
+    /// over the rules of the stylesheet (except `!important` declarations), so values that are
+    /// computed from the application data (i.e. the width of a bar in a bar chart)
+    /// don't need a dynamic CSS property in the stylesheet.
+    pub inline_styles: Vec<CssProperty>,
     /// Whether this div can be dragged or not, similar to `draggable = "true"` in HTML, .
     ///
     /// **TODO**: Currently doesn't do anything, since the drag & drop implementation is missing, API stub.
     pub is_draggable: bool,
+    /// Whether the selected text of this (text / label) node can be dragged out of the node,
+    /// with the selected text as the drag & drop payload. Text with `user-select: none` can't be dragged.
+    ///
+    /// **TODO**: Currently doesn't do anything, since text selection and drag & drop are missing, API stub.
+    pub is_selection_draggable: bool,
     /// Whether this div can be focused, and if yes, in what default to `None` (not focusable).
     /// Note that without this, there can be no `On::FocusReceived` (equivalent to onfocus),
     /// `On::FocusLost` (equivalent to onblur), etc. events.
     pub tab_index: Option<TabIndex>,
+    /// Whether this node is disabled, similar to `disabled` in HTML. Disabled nodes
+    /// can be styled with the `:disabled` pseudo-selector.
+    pub is_disabled: bool,
+    /// Whether this node is checked (i.e. a ticked checkbox), similar to `checked` in HTML.
+    /// Checked nodes can be styled with the `:checked` pseudo-selector.
+    pub is_checked: bool,
+    /// Whether this node can be hit by the mouse, see `HitTestBehavior`
+    pub hit_test: HitTestBehavior,
+    /// Animation that is played when this node is added to the DOM. Only works
+    /// if the node has an ID, see `Dom::with_enter_animation`.
+    pub enter_animation: Option<PresenceAnimation>,
+    /// Animation that is played when this node is removed from the DOM. Only works
+    /// if the node has an ID, see `Dom::with_exit_animation`.
+    pub exit_animation: Option<PresenceAnimation>,
+    /// Identity of the node among its siblings across frames, see `Dom::with_key`
+    pub key: Option<u64>,
+    /// Set on the root of a subtree that was built via `Dom::memo`: hash of the memo key and the
+    /// props hash. A subtree with the same `memo_hash` as in the last frame is unchanged, so its
+    /// nodes can keep their styles without being compared one by one.
+    pub memo_hash: Option<u64>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
@@ -585,16 +640,45 @@ impl Default for TabIndex {
     }
 }
 
+/// How a node takes part in hit-testing, i.e. whether it can receive mouse events
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum HitTestBehavior {
+    /// The node can be hit, unless `pointer-events: none` is set in the CSS
+    Auto,
+    /// The node can't be hit and doesn't receive any mouse events (including `:hover`),
+    /// regardless of the CSS. Useful for decorative overlays on top of other nodes.
+    None,
+    /// The node can be hit, but doesn't draw anything (no background, border or content).
+    /// Useful for invisible regions, such as a custom area for dragging the window.
+    HitTestOnly,
+}
+
+impl Default for HitTestBehavior {
+    fn default() -> Self {
+        HitTestBehavior::Auto
+    }
+}
+
 impl<T> PartialEq for NodeData<T> {
     fn eq(&self, other: &Self) -> bool {
         self.node_type == other.node_type &&
         self.ids == other.ids &&
         self.classes == other.classes &&
+        self.attributes == other.attributes &&
         self.callbacks == other.callbacks &&
         self.default_callback_ids == other.default_callback_ids &&
         self.dynamic_css_overrides == other.dynamic_css_overrides &&
+        self.inline_styles == other.inline_styles &&
         self.is_draggable == other.is_draggable &&
-        self.tab_index == other.tab_index
+        self.is_selection_draggable == other.is_selection_draggable &&
+        self.tab_index == other.tab_index &&
+        self.is_disabled == other.is_disabled &&
+        self.is_checked == other.is_checked &&
+        self.hit_test == other.hit_test &&
+        self.enter_animation == other.enter_animation &&
+        self.exit_animation == other.exit_animation &&
+        self.key == other.key &&
+        self.memo_hash == other.memo_hash
     }
 }
 
@@ -615,6 +699,9 @@ impl<T> Hash for NodeData<T> {
         for class in &self.classes {
             class.hash(state);
         }
+        for attribute in &self.attributes {
+            attribute.hash(state);
+        }
         for callback in &self.callbacks {
             callback.hash(state);
         }
@@ -624,8 +711,19 @@ impl<T> Hash for NodeData<T> {
         for dynamic_css_override in &self.dynamic_css_overrides {
             dynamic_css_override.hash(state);
         }
+        for inline_style in &self.inline_styles {
+            inline_style.hash(state);
+        }
         self.is_draggable.hash(state);
+        self.is_selection_draggable.hash(state);
         self.tab_index.hash(state);
+        self.is_disabled.hash(state);
+        self.is_checked.hash(state);
+        self.hit_test.hash(state);
+        self.enter_animation.hash(state);
+        self.exit_animation.hash(state);
+        self.key.hash(state);
+        self.memo_hash.hash(state);
     }
 }
 
@@ -635,11 +733,21 @@ impl<T> Clone for NodeData<T> {
             node_type: self.node_type.clone(),
             ids: self.ids.clone(),
             classes: self.classes.clone(),
+            attributes: self.attributes.clone(),
             callbacks: self.callbacks.clone(),
             default_callback_ids: self.default_callback_ids.clone(),
             dynamic_css_overrides: self.dynamic_css_overrides.clone(),
+            inline_styles: self.inline_styles.clone(),
             is_draggable: self.is_draggable.clone(),
+            is_selection_draggable: self.is_selection_draggable,
             tab_index: self.tab_index.clone(),
+            is_disabled: self.is_disabled.clone(),
+            is_checked: self.is_checked.clone(),
+            hit_test: self.hit_test,
+            enter_animation: self.enter_animation,
+            exit_animation: self.exit_animation,
+            key: self.key,
+            memo_hash: self.memo_hash,
         }
     }
 }
@@ -662,6 +770,10 @@ impl<T> fmt::Display for NodeData<T> {
             format!(" class=\"{}\"", self.classes.iter().map(|s| s.as_str().to_string()).collect::<Vec<String>>().join(" "))
         };
 
+        let attribute_string = self.attributes.iter()
+            .map(|(key, value)| format!(" {}=\"{}\"", key.as_str(), value.as_str()))
+            .collect::<String>();
+
         let draggable = if self.is_draggable {
             format!(" draggable=\"true\"")
         } else {
@@ -674,6 +786,9 @@ impl<T> fmt::Display for NodeData<T> {
             String::new()
         };
 
+        let disabled = if self.is_disabled { " disabled" } else { "" };
+        let checked = if self.is_checked { " checked" } else { "" };
+
         let callbacks = if self.callbacks.is_empty() {
             String::new()
         } else {
@@ -692,13 +807,19 @@ impl<T> fmt::Display for NodeData<T> {
             format!(" css-overrides=\"{}\"", self.dynamic_css_overrides.iter().map(|(id, prop)| format!("{}={:?};", id, prop)).collect::<Vec<String>>().join(" "))
         };
 
+        let inline_styles = if self.inline_styles.is_empty() {
+            String::new()
+        } else {
+            format!(" style=\"{}\"", self.inline_styles.iter().map(|prop| format!("{:?};", prop)).collect::<Vec<String>>().join(" "))
+        };
+
         if let Some(content) = text_content {
-            write!(f, "<{}{}{}{}{}{}{}{}>{}</{}>",
-                html_type, id_string, class_string, tabindex, draggable, callbacks, default_callbacks, css_overrides, content, html_type
+            write!(f, "<{}{}{}{}{}{}{}{}{}{}{}{}>{}</{}>",
+                html_type, id_string, class_string, attribute_string, tabindex, draggable, disabled, checked, callbacks, default_callbacks, css_overrides, inline_styles, content, html_type
             )
         } else {
-            write!(f, "<{}{}{}{}{}{}{}{}/>",
-                html_type, id_string, class_string, tabindex, draggable, callbacks, default_callbacks, css_overrides,
+            write!(f, "<{}{}{}{}{}{}{}{}{}{}{}{}/>",
+                html_type, id_string, class_string, attribute_string, tabindex, draggable, disabled, checked, callbacks, default_callbacks, css_overrides, inline_styles,
             )
         }
     }
@@ -711,20 +832,40 @@ impl<T> fmt::Debug for NodeData<T> {
                 \tnode_type: {:?}, \
                 \tids: {:?}, \
                 \tclasses: {:?}, \
+                \tattributes: {:?}, \
                 \tcallbacks: {:?}, \
                 \tdefault_callback_ids: {:?}, \
                 \tdynamic_css_overrides: {:?}, \
+                \tinline_styles: {:?}, \
                 \tis_draggable: {:?}, \
+                \tis_selection_draggable: {:?}, \
                 \ttab_index: {:?}, \
+                \tis_disabled: {:?}, \
+                \tis_checked: {:?}, \
+                \thit_test: {:?}, \
+                \tenter_animation: {:?}, \
+                \texit_animation: {:?}, \
+                \tkey: {:?}, \
+                \tmemo_hash: {:?}, \
             }}",
             self.node_type,
             self.ids,
             self.classes,
+            self.attributes,
             self.callbacks,
             self.default_callback_ids,
             self.dynamic_css_overrides,
+            self.inline_styles,
             self.is_draggable,
+            self.is_selection_draggable,
             self.tab_index,
+            self.is_disabled,
+            self.is_checked,
+            self.hit_test,
+            self.enter_animation,
+            self.exit_animation,
+            self.key,
+            self.memo_hash,
         )
     }
 }
@@ -740,11 +881,21 @@ impl<T> NodeData<T> {
             node_type,
             ids: Vec::new(),
             classes: Vec::new(),
+            attributes: Vec::new(),
             callbacks: Vec::new(),
             default_callback_ids: Vec::new(),
             dynamic_css_overrides: Vec::new(),
+            inline_styles: Vec::new(),
             is_draggable: false,
+            is_selection_draggable: false,
             tab_index: None,
+            is_disabled: false,
+            is_checked: false,
+            hit_test: HitTestBehavior::Auto,
+            enter_animation: None,
+            exit_animation: None,
+            key: None,
+            memo_hash: None,
         }
     }
 
@@ -764,6 +915,12 @@ impl<T> NodeData<T> {
         self.classes.iter().any(|self_class| self_class.equals_str(class))
     }
 
+    /// Returns the value of the attribute with the given name (if the
+    /// attribute is set multiple times, the last value wins)
+    pub fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().rev().find(|(key, _)| key.equals_str(name)).map(|(_, value)| value.as_str())
+    }
+
     pub(crate) fn calculate_node_data_hash(&self) -> DomHash {
 
         // Pick hash algorithm based on features
@@ -860,6 +1017,67 @@ impl<T> Clone for Dom<T> {
     }
 }
 
+/// Subtrees that were built via `Dom::memo`, stored per window. The `memo_hash` of
+/// the root node of each subtree is the hash that the subtree was built with.
+pub(crate) struct MemoCache<T> {
+    /// Hashed key -> subtree, for the subtrees of the last call to `layout()`
+    last_frame: BTreeMap<u64, Dom<T>>,
+    /// Hashed key -> subtree, for the subtrees of the current call to `layout()`
+    current_frame: BTreeMap<u64, Dom<T>>,
+}
+
+impl<T> Clone for MemoCache<T> {
+    fn clone(&self) -> Self {
+        Self {
+            last_frame: self.last_frame.clone(),
+            current_frame: self.current_frame.clone(),
+        }
+    }
+}
+
+impl<T> MemoCache<T> {
+
+    pub(crate) fn new() -> Self {
+        Self {
+            last_frame: BTreeMap::new(),
+            current_frame: BTreeMap::new(),
+        }
+    }
+
+    /// Hashes the key and the props hash of a subtree into its `memo_hash`
+    fn get_memo_hash(key: u64, props_hash: u64) -> u64 {
+        let mut hasher = DefaultHasher::default();
+        (key, props_hash).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Takes the subtree that was built with the same key in the last frame
+    /// out of the cache, if it was built with the same `props_hash`
+    pub(crate) fn take(&mut self, key: u64, props_hash: u64) -> Option<Dom<T>> {
+        let subtree = self.last_frame.remove(&key)?;
+        if subtree.arena.node_data[subtree.root].memo_hash == Some(Self::get_memo_hash(key, props_hash)) {
+            Some(subtree)
+        } else {
+            None
+        }
+    }
+
+    /// Stores the subtree for the next frame and marks its root node with the `memo_hash`.
+    /// The subtree is matched with the last frame by the `key`, unless it already has a key.
+    pub(crate) fn insert(&mut self, key: u64, props_hash: u64, mut subtree: Dom<T>) -> Dom<T> {
+        let root = &mut subtree.arena.node_data[subtree.root];
+        root.memo_hash = Some(Self::get_memo_hash(key, props_hash));
+        root.key = root.key.or(Some(key));
+        self.current_frame.insert(key, subtree.clone());
+        subtree
+    }
+
+    /// Drops all subtrees that weren't used during the last call to `layout()`
+    pub(crate) fn end_frame(&mut self) {
+        self.last_frame = mem::replace(&mut self.current_frame, BTreeMap::new());
+    }
+}
+
 impl<T> PartialEq for Dom<T> {
     fn eq(&self, rhs: &Self) -> bool {
         self.arena == rhs.arena &&
@@ -1028,6 +1246,60 @@ impl<T> Dom<T> {
         Self::new(NodeType::IFrame((callback, ptr)))
     }
 
+    /// Returns the subtree that was built with the same `key` in the last frame if the
+    /// `props_hash` didn't change, otherwise calls `build` to build the subtree again.
+    ///
+    /// The `props_hash` has to be a hash of all data that the subtree is built from,
+    /// the `key` has to be unique in the window (i.e. the name of a panel):
+    ///
+    /// ```rust,ignore
+    /// fn layout(&self, info: LayoutInfo<Self>) -> Dom<Self> {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     self.rows.hash(&mut hasher);
+    ///     Dom::div()
+    ///         .with_child(Dom::memo(info.window, "table", hasher.finish(), |window| render_table(&self.rows, window)))
+    /// }
+    /// ```
+    ///
+    /// Subtrees that aren't used during a frame are removed from the cache. A reused subtree
+    /// keeps the styles of the last frame (unless a node in it is hovered, active or focused)
+    /// and doesn't invalidate the cached layout of the window. Note that the default callbacks
+    /// of a reused subtree are not registered again, so the data that their
+    /// `StackCheckedPointer`s point to must not move while the `props_hash` stays the same.
+    pub fn memo<K: Hash, F: FnOnce(&mut FakeWindow<T>) -> Self>(window: &mut FakeWindow<T>, key: K, props_hash: u64, build: F) -> Self {
+
+        let key = {
+            let mut hasher = DefaultHasher::default();
+            key.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let subtree = match window.memo_cache.take(key, props_hash) {
+            Some(subtree) => subtree,
+            None => build(window),
+        };
+
+        window.memo_cache.insert(key, props_hash, subtree)
+    }
+
+    /// Shorthand for `Dom::memo` if the subtree doesn't need a stable key: the subtree is
+    /// only rebuilt if no subtree with the same `hash` was built during the last frame.
+    /// Like with `Dom::memo`, a reused subtree is not restyled and doesn't invalidate the cached layout.
+    ///
+    /// ```rust,ignore
+    /// let mut hasher = DefaultHasher::new();
+    /// self.rows.hash(&mut hasher);
+    /// let table = Dom::cached(info.window, hasher.finish(), || render_table(&self.rows));
+    /// ```
+    ///
+    /// Since the hash is also the key, a subtree whose hash changes is not replaced, but
+    /// cached as a new subtree (the old one is removed at the end of the frame). Use `Dom::memo`
+    /// if the `build` function needs to register default callbacks on the window.
+    #[inline]
+    pub fn cached<F: FnOnce() -> Self>(window: &mut FakeWindow<T>, hash: u64, build: F) -> Self {
+        Self::memo(window, ("__azul-cached", hash), hash, |_| build())
+    }
+
     /// Parses and loads a DOM from an XML string
     #[inline]
     pub fn from_xml(xml: &str, component_map: &mut XmlComponentMap<T>) -> Result<Self, XmlParseError> {
@@ -1180,6 +1452,13 @@ impl<T> Dom<T> {
         self
     }
 
+    /// Same as `add_attribute`, but easier to use for method chaining in a builder-style pattern
+    #[inline]
+    pub fn with_attribute<S: Into<DomString>, V: Into<DomString>>(mut self, name: S, value: V) -> Self {
+        self.add_attribute(name, value);
+        self
+    }
+
     /// Same as `event`, but easier to use for method chaining in a builder-style pattern
     #[inline]
     pub fn with_callback<O: Into<EventFilter>>(mut self, on: O, callback: Callback<T>) -> Self {
@@ -1199,6 +1478,13 @@ impl<T> Dom<T> {
         self
     }
 
+    /// Adds an inline style to the node, see `NodeData::inline_styles`
+    #[inline]
+    pub fn with_style(mut self, property: CssProperty) -> Self {
+        self.add_style(property);
+        self
+    }
+
     #[inline]
     pub fn with_tab_index(mut self, tab_index: TabIndex) -> Self {
         self.set_tab_index(tab_index);
@@ -1211,6 +1497,64 @@ impl<T> Dom<T> {
         self
     }
 
+    /// Allows dragging the selected text of this node, see `NodeData::is_selection_draggable`
+    #[inline]
+    pub fn with_selection_draggable(mut self, draggable: bool) -> Self {
+        self.set_selection_draggable(draggable);
+        self
+    }
+
+    #[inline]
+    pub fn is_disabled(mut self, disabled: bool) -> Self {
+        self.set_disabled(disabled);
+        self
+    }
+
+    #[inline]
+    pub fn is_checked(mut self, checked: bool) -> Self {
+        self.set_checked(checked);
+        self
+    }
+
+    /// Overrides whether the node can be hit by the mouse, i.e. `HitTestBehavior::None`
+    /// for overlays that shouldn't block the hover state of the nodes below
+    #[inline]
+    pub fn with_hit_test(mut self, hit_test: HitTestBehavior) -> Self {
+        self.set_hit_test(hit_test);
+        self
+    }
+
+    /// Plays the animation when the node is added to the DOM, i.e. when the node can't be
+    /// matched to a node of the previous frame (see `Dom::with_key`). Has no effect if the
+    /// node doesn't have an ID.
+    #[inline]
+    pub fn with_enter_animation(mut self, animation: PresenceAnimation) -> Self {
+        self.set_enter_animation(animation);
+        self
+    }
+
+    /// Plays the animation when the node is removed from the DOM: the node (and its children)
+    /// are kept in the DOM until the animation has finished, but don't receive any events
+    /// anymore. Has no effect if the node doesn't have an ID.
+    #[inline]
+    pub fn with_exit_animation(mut self, animation: PresenceAnimation) -> Self {
+        self.set_exit_animation(animation);
+        self
+    }
+
+    /// Gives the node a stable identity among its siblings: when the DOM of the next frame
+    /// is compared with the last one, children are matched by their key instead of by their
+    /// index, so that inserting an item at the top of a list doesn't restyle all following
+    /// items or move the focus to a different item. The layout transitions and the enter / exit
+    /// animations use the same matching, so a reordered item is animated to its new position
+    /// instead of being treated as removed and added. The key only has to be unique among
+    /// the siblings of the node, i.e. the index of the item in the application data.
+    #[inline]
+    pub fn with_key(mut self, key: u64) -> Self {
+        self.set_key(key);
+        self
+    }
+
     #[inline]
     pub fn add_id<S: Into<DomString>>(&mut self, id: S) {
         self.arena.node_data[self.head].ids.push(id.into());
@@ -1221,6 +1565,13 @@ impl<T> Dom<T> {
         self.arena.node_data[self.head].classes.push(class.into());
     }
 
+    /// Sets an attribute such as `data-state="error"`, which can then
+    /// be styled via attribute selectors (`div[data-state="error"]`)
+    #[inline]
+    pub fn add_attribute<S: Into<DomString>, V: Into<DomString>>(&mut self, name: S, value: V) {
+        self.arena.node_data[self.head].attributes.push((name.into(), value.into()));
+    }
+
     #[inline]
     pub fn add_callback<O: Into<EventFilter>>(&mut self, on: O, callback: Callback<T>) {
         self.arena.node_data[self.head].callbacks.push((on.into(), callback));
@@ -1236,6 +1587,11 @@ impl<T> Dom<T> {
         self.arena.node_data[self.head].dynamic_css_overrides.push((override_id.into(), property));
     }
 
+    #[inline]
+    pub fn add_style(&mut self, property: CssProperty) {
+        self.arena.node_data[self.head].inline_styles.push(property);
+    }
+
     #[inline]
     pub fn set_tab_index(&mut self, tab_index: TabIndex) {
         self.arena.node_data[self.head].tab_index = Some(tab_index);
@@ -1246,6 +1602,162 @@ impl<T> Dom<T> {
         self.arena.node_data[self.head].is_draggable = draggable;
     }
 
+    #[inline]
+    pub fn set_selection_draggable(&mut self, draggable: bool) {
+        self.arena.node_data[self.head].is_selection_draggable = draggable;
+    }
+
+    #[inline]
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.arena.node_data[self.head].is_disabled = disabled;
+    }
+
+    #[inline]
+    pub fn set_checked(&mut self, checked: bool) {
+        self.arena.node_data[self.head].is_checked = checked;
+    }
+
+    #[inline]
+    pub fn set_hit_test(&mut self, hit_test: HitTestBehavior) {
+        self.arena.node_data[self.head].hit_test = hit_test;
+    }
+
+    #[inline]
+    pub fn set_enter_animation(&mut self, animation: PresenceAnimation) {
+        self.arena.node_data[self.head].enter_animation = Some(animation);
+    }
+
+    #[inline]
+    pub fn set_exit_animation(&mut self, animation: PresenceAnimation) {
+        self.arena.node_data[self.head].exit_animation = Some(animation);
+    }
+
+    #[inline]
+    pub fn set_key(&mut self, key: u64) {
+        self.arena.node_data[self.head].key = Some(key);
+    }
+
+    /// Returns the ID of the root node of this DOM
+    #[inline]
+    pub fn get_root(&self) -> NodeId {
+        self.root
+    }
+
+    /// Returns the node hierarchy (DOM tree order), for traversing the DOM via
+    /// the iterators on `NodeId`, i.e. `node_id.descendants(dom.get_node_hierarchy())`
+    #[inline]
+    pub fn get_node_hierarchy(&self) -> &NodeHierarchy {
+        &self.arena.node_layout
+    }
+
+    /// Returns the parent / sibling / child IDs of a node, `None` if the ID is out of range
+    #[inline]
+    pub fn get_node(&self, node_id: NodeId) -> Option<&Node> {
+        self.arena.node_layout.get(node_id)
+    }
+
+    /// Returns the content of a node, `None` if the ID is out of range
+    #[inline]
+    pub fn get_node_data(&self, node_id: NodeId) -> Option<&NodeData<T>> {
+        self.arena.node_data.get(node_id)
+    }
+
+    /// Returns the parent of the node or `None` if the node is the root node
+    #[inline]
+    pub fn parent(&self, node_id: NodeId) -> Option<NodeId> {
+        self.get_node(node_id)?.parent
+    }
+
+    /// Iterates over the direct children of the node
+    #[inline]
+    pub fn children(&self, node_id: NodeId) -> Children {
+        node_id.children(&self.arena.node_layout)
+    }
+
+    /// Iterates over the node and all of its descendants, in tree order
+    #[inline]
+    pub fn descendants(&self, node_id: NodeId) -> Descendants {
+        node_id.descendants(&self.arena.node_layout)
+    }
+
+    /// Returns all descendants of the node (not including the node itself), for which the
+    /// `predicate` returns true, in tree order. For example, all focusable descendants:
+    ///
+    /// ```rust
+    /// # use azul::dom::{Dom, TabIndex};
+    /// # struct MyAppData;
+    /// let dom: Dom<MyAppData> = Dom::div()
+    ///     .with_child(Dom::div().with_tab_index(TabIndex::Auto))
+    ///     .with_child(Dom::div());
+    ///
+    /// let focusable = dom.find_descendants(dom.get_root(), |node| node.tab_index.is_some());
+    /// assert_eq!(focusable.len(), 1);
+    /// ```
+    pub fn find_descendants<F: Fn(&NodeData<T>) -> bool>(&self, node_id: NodeId, predicate: F) -> Vec<NodeId> {
+        self.descendants(node_id)
+            .skip(1)
+            .filter(|id| predicate(&self.arena.node_data[*id]))
+            .collect()
+    }
+
+    /// Copies the node and all of its children into a new DOM
+    pub(crate) fn get_subtree(&self, node_id: NodeId) -> Self {
+        let mut subtree = Dom::new(NodeType::Div);
+        subtree.arena.node_data[subtree.root] = self.arena.node_data[node_id].clone();
+        for child in node_id.children(&self.arena.node_layout) {
+            subtree.add_child(self.get_subtree(child));
+        }
+        subtree
+    }
+
+    /// Inserts the `child` DOM as the `index`-th child of the `parent` node
+    /// (or as the last child, if the parent has less children). Returns the
+    /// ID of the inserted root node, `None` if the `child` DOM is empty.
+    pub(crate) fn insert_child(&mut self, parent: NodeId, index: usize, mut child: Self) -> Option<NodeId> {
+
+        fn shift(node_id: &mut Option<NodeId>, offset: usize) {
+            if let Some(node_id) = node_id.as_mut() {
+                *node_id += offset;
+            }
+        }
+
+        if child.arena.is_empty() {
+            return None;
+        }
+
+        let offset = self.arena.len();
+        let child_root = child.root + offset;
+
+        for node in child.arena.node_layout.internal.iter_mut() {
+            shift(&mut node.parent, offset);
+            shift(&mut node.previous_sibling, offset);
+            shift(&mut node.next_sibling, offset);
+            shift(&mut node.first_child, offset);
+            shift(&mut node.last_child, offset);
+        }
+
+        let next_sibling = parent.children(&self.arena.node_layout).nth(index);
+        self.arena.append_arena(&mut child.arena);
+
+        let hierarchy = &mut self.arena.node_layout;
+        match next_sibling {
+            Some(next_sibling) => {
+                let previous_sibling = hierarchy[next_sibling].previous_sibling;
+                hierarchy[child_root].parent = Some(parent);
+                hierarchy[child_root].previous_sibling = previous_sibling;
+                hierarchy[child_root].next_sibling = Some(next_sibling);
+                hierarchy[next_sibling].previous_sibling = Some(child_root);
+                match previous_sibling {
+                    Some(previous_sibling) => hierarchy[previous_sibling].next_sibling = Some(child_root),
+                    None => hierarchy[parent].first_child = Some(child_root),
+                }
+            },
+            None => hierarchy.append_child(parent, child_root),
+        }
+
+        Some(child_root)
+    }
+
     /// Returns a debug formatted version of the DOM for easier debugging
     pub fn debug_dump(&self) -> String {
         format!("{}", self.arena.print_tree(|t| format!("{}", t)))
@@ -1461,6 +1973,8 @@ impl<T> Dom<T> {
             dom: self,
             dynamic_css_overrides,
             tag_ids_to_hover_active_states: BTreeMap::new(),
+            tag_ids_to_cursors: BTreeMap::new(),
+            builtin_css_variables: Vec::new(),
 
             tab_index_tags,
             draggable_tags,
@@ -1534,6 +2048,34 @@ fn test_dom_sibling_1() {
         ].ids);
 }
 
+#[test]
+fn test_dom_find_descendants() {
+
+    struct TestLayout;
+
+    let dom: Dom<TestLayout> =
+        Dom::div()
+            .with_child(Dom::div().with_id("a").with_tab_index(TabIndex::Auto)
+                .with_child(Dom::div().with_id("a-1").with_tab_index(TabIndex::Auto)))
+            .with_child(Dom::div().with_id("b")
+                .with_child(Dom::div().with_id("b-1").with_tab_index(TabIndex::Auto)));
+
+    let root = dom.get_root();
+    let focusable = dom.find_descendants(root, |node| node.tab_index.is_some());
+    let ids = focusable.iter().map(|id| dom.get_node_data(*id).unwrap().ids.clone()).collect::<Vec<_>>();
+
+    assert_eq!(ids, vec![
+        vec![DomString::Static("a")],
+        vec![DomString::Static("a-1")],
+        vec![DomString::Static("b-1")],
+    ]);
+
+    assert_eq!(dom.children(root).count(), 2);
+    assert_eq!(dom.descendants(root).count(), 5);
+    assert_eq!(dom.parent(focusable[1]), Some(focusable[0]));
+    assert_eq!(dom.parent(root), None);
+}
+
 #[test]
 fn test_dom_from_iter_1() {
 
@@ -1592,3 +2134,34 @@ fn test_zero_size_dom() {
     null_dom.add_class("hello"); // should not panic
     null_dom.add_id("id-hello"); // should not panic
 }
+
+#[test]
+fn test_memo_cache_take_and_insert() {
+
+    struct TestLayout;
+
+    let mut cache = MemoCache::<TestLayout>::new();
+
+    let subtree = cache.insert(1, 10, Dom::div().with_child(Dom::label("hello")));
+    let memo_hash = subtree.arena.node_data[subtree.root].memo_hash;
+    assert!(memo_hash.is_some());
+    assert_eq!(subtree.arena.node_data[subtree.root].key, Some(1));
+
+    // The subtree is only available in the next frame
+    assert!(cache.take(1, 10).is_none());
+    cache.end_frame();
+
+    // Different props hash: the subtree has to be rebuilt
+    assert!(cache.take(1, 11).is_none());
+
+    cache.insert(1, 10, subtree);
+    cache.end_frame();
+
+    let reused = cache.take(1, 10).unwrap();
+    assert_eq!(reused.arena.node_data[reused.root].memo_hash, memo_hash);
+    assert_eq!(reused.arena.len(), 2);
+
+    // Subtrees that weren't used in a frame are dropped
+    cache.end_frame();
+    assert!(cache.take(1, 10).is_none());
+}
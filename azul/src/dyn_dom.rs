@@ -0,0 +1,241 @@
+//! Type-erased version of the `Dom`, for widget libraries that should be compiled
+//! independently of the application data model (or that are loaded at runtime)
+
+use std::{
+    fmt,
+    any::Any,
+    rc::Rc,
+    cell::RefCell,
+};
+use azul_css::CssProperty;
+use {
+    callbacks::DynCallback,
+    dom::{Dom, NodeType, DomString, EventFilter, TabIndex},
+    app_resources::{ImageId, TextId},
+    window::FakeWindow,
+};
+
+/// Same as the `NodeType`, but without the `GlTexture` and `IFrame` nodes,
+/// since their callbacks need access to the application data
+#[derive(Debug, Clone)]
+pub enum DynNodeType {
+    Div,
+    Label(DomString),
+    Text(TextId),
+    Image(ImageId),
+}
+
+impl DynNodeType {
+    fn into_node_type<T>(self) -> NodeType<T> {
+        use self::DynNodeType::*;
+        match self {
+            Div => NodeType::Div,
+            Label(s) => NodeType::Label(s),
+            Text(t) => NodeType::Text(t),
+            Image(i) => NodeType::Image(i),
+        }
+    }
+}
+
+/// DOM that doesn't depend on the type of the application data: the callbacks
+/// of a `DynDom` only get access to the `state` of their node, not to `T`.
+///
+/// A `DynDom` gets converted into a regular `Dom<T>` inside of the `layout()`
+/// function via `DynDom::into_dom(info.window)`.
+#[derive(Clone)]
+pub struct DynDom {
+    pub node_type: DynNodeType,
+    pub ids: Vec<DomString>,
+    pub classes: Vec<DomString>,
+    pub attributes: Vec<(DomString, DomString)>,
+    pub callbacks: Vec<(EventFilter, DynCallback)>,
+    /// State that is passed to the callbacks of this node (as a `&mut Any`),
+    /// the widget has to downcast it to its own state type again.
+    pub state: Option<Rc<RefCell<dyn Any>>>,
+    pub dynamic_css_overrides: Vec<(DomString, CssProperty)>,
+    pub tab_index: Option<TabIndex>,
+    pub is_disabled: bool,
+    pub is_checked: bool,
+    pub children: Vec<DynDom>,
+}
+
+impl fmt::Debug for DynDom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+            "DynDom {{ node_type: {:?}, ids: {:?}, classes: {:?}, attributes: {:?}, \
+             callbacks: {:?}, has_state: {:?}, dynamic_css_overrides: {:?}, \
+             tab_index: {:?}, is_disabled: {:?}, is_checked: {:?}, children: {:?} }}",
+            self.node_type, self.ids, self.classes, self.attributes,
+            self.callbacks, self.state.is_some(), self.dynamic_css_overrides,
+            self.tab_index, self.is_disabled, self.is_checked, self.children,
+        )
+    }
+}
+
+impl DynDom {
+
+    /// Creates an empty `DynDom` with the given node type
+    #[inline]
+    pub fn new(node_type: DynNodeType) -> Self {
+        Self {
+            node_type,
+            ids: Vec::new(),
+            classes: Vec::new(),
+            attributes: Vec::new(),
+            callbacks: Vec::new(),
+            state: None,
+            dynamic_css_overrides: Vec::new(),
+            tab_index: None,
+            is_disabled: false,
+            is_checked: false,
+            children: Vec::new(),
+        }
+    }
+
+    /// Shorthand for `DynDom::new(DynNodeType::Div)`.
+    #[inline]
+    pub fn div() -> Self {
+        Self::new(DynNodeType::Div)
+    }
+
+    /// Shorthand for `DynDom::new(DynNodeType::Label(value.into()))`
+    #[inline]
+    pub fn label<S: Into<DomString>>(value: S) -> Self {
+        Self::new(DynNodeType::Label(value.into()))
+    }
+
+    /// Shorthand for `DynDom::new(DynNodeType::Text(text_id))`
+    #[inline]
+    pub fn text_id(text_id: TextId) -> Self {
+        Self::new(DynNodeType::Text(text_id))
+    }
+
+    /// Shorthand for `DynDom::new(DynNodeType::Image(image_id))`
+    #[inline]
+    pub fn image(image: ImageId) -> Self {
+        Self::new(DynNodeType::Image(image))
+    }
+
+    #[inline]
+    pub fn with_id<S: Into<DomString>>(mut self, id: S) -> Self {
+        self.ids.push(id.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_class<S: Into<DomString>>(mut self, class: S) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_attribute<S: Into<DomString>, V: Into<DomString>>(mut self, name: S, value: V) -> Self {
+        self.attributes.push((name.into(), value.into()));
+        self
+    }
+
+    #[inline]
+    pub fn with_callback<O: Into<EventFilter>>(mut self, on: O, callback: DynCallback) -> Self {
+        self.callbacks.push((on.into(), callback));
+        self
+    }
+
+    /// Sets the state that the callbacks of this node get passed in,
+    /// for example `Rc::new(RefCell::new(CounterState { count: 0 }))`
+    #[inline]
+    pub fn with_state(mut self, state: Rc<RefCell<dyn Any>>) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    #[inline]
+    pub fn with_child(mut self, child: Self) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    #[inline]
+    pub fn with_css_override<S: Into<DomString>>(mut self, id: S, property: CssProperty) -> Self {
+        self.dynamic_css_overrides.push((id.into(), property));
+        self
+    }
+
+    #[inline]
+    pub fn with_tab_index(mut self, tab_index: TabIndex) -> Self {
+        self.tab_index = Some(tab_index);
+        self
+    }
+
+    #[inline]
+    pub fn is_disabled(mut self, disabled: bool) -> Self {
+        self.is_disabled = disabled;
+        self
+    }
+
+    #[inline]
+    pub fn is_checked(mut self, checked: bool) -> Self {
+        self.is_checked = checked;
+        self
+    }
+
+    #[inline]
+    pub fn add_child(&mut self, child: Self) {
+        self.children.push(child);
+    }
+
+    /// Converts the `DynDom` into a regular `Dom<T>`. The callbacks are registered
+    /// as default callbacks on the `window`, so (like default callbacks) this
+    /// has to be called on every frame, usually inside of the `layout()` function.
+    pub fn into_dom<T>(self, window: &mut FakeWindow<T>) -> Dom<T> {
+        self.into_dom_inner(Some(window))
+    }
+
+    /// Converts the `DynDom` into a regular `Dom<T>`, but drops all callbacks. Used
+    /// for places where no window is available, such as rendering XML components.
+    pub fn into_dom_without_callbacks<T>(self) -> Dom<T> {
+        self.into_dom_inner(None)
+    }
+
+    fn into_dom_inner<T>(self, mut window: Option<&mut FakeWindow<T>>) -> Dom<T> {
+
+        let mut dom = Dom::new(self.node_type.into_node_type());
+
+        for id in self.ids {
+            dom.add_id(id);
+        }
+        for class in self.classes {
+            dom.add_class(class);
+        }
+        for (name, value) in self.attributes {
+            dom.add_attribute(name, value);
+        }
+        for (override_id, property) in self.dynamic_css_overrides {
+            dom.add_css_override(override_id, property);
+        }
+        if let Some(tab_index) = self.tab_index {
+            dom.set_tab_index(tab_index);
+        }
+        dom.set_disabled(self.is_disabled);
+        dom.set_checked(self.is_checked);
+
+        if let Some(window) = window.as_mut() {
+            if !self.callbacks.is_empty() {
+                // Nodes without state still share one state between all of their callbacks
+                let state: Rc<RefCell<dyn Any>> = match self.state {
+                    Some(state) => state,
+                    None => Rc::new(RefCell::new(())),
+                };
+                for (on, callback) in self.callbacks {
+                    let callback_id = window.add_dyn_callback(state.clone(), callback);
+                    dom.add_default_callback_id(on, callback_id);
+                }
+            }
+        }
+
+        for child in self.children {
+            dom.add_child(child.into_dom_inner(window.as_mut().map(|w| &mut **w)));
+        }
+
+        dom
+    }
+}
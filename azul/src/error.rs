@@ -1,9 +1,17 @@
 pub use {
     app::RuntimeError,
     app_resources::{ImageReloadError, FontReloadError},
+    recent_documents::RecentDocumentError,
+    screenshot::ScreenshotError,
     widgets::errors::*,
-    window::WindowCreateError,
+    window::{WindowCreateError, WindowCreateOptionsError},
 };
+#[cfg(feature = "settings")]
+pub use app::SettingsError;
+#[cfg(feature = "net")]
+pub use net::NetError;
+#[cfg(feature = "plugins")]
+pub use plugin::PluginError;
 // TODO: re-export the sub-types of ClipboardError!
 pub use clipboard2::ClipboardError;
 
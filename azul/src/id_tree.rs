@@ -182,6 +182,32 @@ impl NodeHierarchy {
         non_leaf_nodes
     }
 
+    /// Inserts a node that isn't part of the hierarchy yet (i.e. one that was just
+    /// created with `Arena::new_node`) as the first child of the `parent`
+    pub(crate) fn prepend_child(&mut self, parent: NodeId, child: NodeId) {
+        let old_first_child = self[parent].first_child;
+        self[child].parent = Some(parent);
+        self[child].next_sibling = old_first_child;
+        match old_first_child {
+            Some(old_first_child) => self[old_first_child].previous_sibling = Some(child),
+            None => self[parent].last_child = Some(child),
+        }
+        self[parent].first_child = Some(child);
+    }
+
+    /// Inserts a node that isn't part of the hierarchy yet (i.e. one that was just
+    /// created with `Arena::new_node`) as the last child of the `parent`
+    pub(crate) fn append_child(&mut self, parent: NodeId, child: NodeId) {
+        let old_last_child = self[parent].last_child;
+        self[child].parent = Some(parent);
+        self[child].previous_sibling = old_last_child;
+        match old_last_child {
+            Some(old_last_child) => self[old_last_child].next_sibling = Some(child),
+            None => self[parent].first_child = Some(child),
+        }
+        self[parent].last_child = Some(child);
+    }
+
     /// Returns the index in the parent node of a certain NodeId
     /// (starts at 0, i.e. the first node has the index of 0).
     pub fn get_index_in_parent(&self, node_id: NodeId) -> usize {
@@ -0,0 +1,171 @@
+//! Debug overlay that shows how well a DOM can be used with the keyboard only,
+//! see `DebugState::keyboard_audit`
+
+use azul_css::{
+    CssProperty, LayoutPosition, LayoutTop, LayoutLeft, LayoutWidth, LayoutHeight, LayoutPadding,
+    StyleOutline, StyleBorderSide, BorderStyle, StyleBackground, StyleTextColor, StyleFontSize,
+    StylePointerEvents, PixelValue, ColorU,
+};
+use webrender::api::LayoutRect;
+use {
+    dom::{Dom, TabIndex, HoverEventFilter},
+    id_tree::{NodeId, NodeHierarchy, NodeDataContainer},
+    ui_state::UiState,
+    ui_solver::PositionedRectangle,
+};
+
+const TAB_STOP_COLOR: ColorU = ColorU { r: 30, g: 110, b: 230, a: 255 };
+const UNREACHABLE_COLOR: ColorU = ColorU { r: 220, g: 30, b: 30, a: 255 };
+
+/// Result of auditing one frame of a window: the nodes that can be focused via the
+/// keyboard (in tab order) and the nodes that can only be used with the mouse
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct KeyboardAudit {
+    /// Focusable nodes and their bounds, in the order that the tab key focuses them
+    pub(crate) tab_order: Vec<(NodeId, LayoutRect)>,
+    /// Nodes that react to mouse clicks, but don't have a `TabIndex`
+    /// (or have `TabIndex::NoKeyboardFocus`)
+    pub(crate) unreachable: Vec<(NodeId, LayoutRect)>,
+}
+
+impl KeyboardAudit {
+
+    pub(crate) fn new<T>(ui_state: &UiState<T>, rects: &NodeDataContainer<PositionedRectangle>) -> Self {
+
+        let node_hierarchy = &ui_state.dom.arena.node_layout;
+        let node_data = &ui_state.dom.arena.node_data;
+        let is_focusable = |node_id: NodeId| match node_data[node_id].tab_index {
+            None | Some(TabIndex::NoKeyboardFocus) => false,
+            Some(_) => true,
+        };
+
+        let tab_order = get_tab_order(ui_state.dom.root, node_hierarchy, &|node_id| node_data[node_id].tab_index)
+            .into_iter()
+            .filter(|node_id| is_focusable(*node_id))
+            .filter_map(|node_id| Some((node_id, rects.get(node_id)?.bounds)))
+            .collect();
+
+        let is_click_filter = |filter: &HoverEventFilter| match filter {
+            HoverEventFilter::MouseDown | HoverEventFilter::LeftMouseDown |
+            HoverEventFilter::MouseUp | HoverEventFilter::LeftMouseUp |
+            HoverEventFilter::LongPress(_) | HoverEventFilter::HoldRepeat => true,
+            _ => false,
+        };
+
+        let clickable_nodes = ui_state.hover_callbacks.iter()
+            .filter(|(_, callbacks)| callbacks.keys().any(is_click_filter))
+            .map(|(node_id, _)| *node_id)
+            .chain(ui_state.hover_default_callbacks.iter()
+                .filter(|(_, callbacks)| callbacks.keys().any(is_click_filter))
+                .map(|(node_id, _)| *node_id));
+
+        let mut unreachable = clickable_nodes
+            .filter(|node_id| !is_focusable(*node_id))
+            .filter_map(|node_id| Some((node_id, rects.get(node_id)?.bounds)))
+            .collect::<Vec<_>>();
+        unreachable.sort_by_key(|(node_id, _)| *node_id);
+        unreachable.dedup_by_key(|(node_id, _)| *node_id);
+
+        Self { tab_order, unreachable }
+    }
+
+    /// Returns the overlay that is drawn on top of the window: every focusable node
+    /// is outlined in blue and labeled with its position in the tab order, every
+    /// node that can't be reached via the keyboard is outlined in red
+    pub(crate) fn to_dom<T>(&self) -> Dom<T> {
+
+        let mut overlay = Dom::div()
+            .with_class(KEYBOARD_AUDIT_CLASS)
+            .with_style(CssProperty::Position(LayoutPosition::Absolute))
+            .with_style(CssProperty::Top(LayoutTop::px(0.0)))
+            .with_style(CssProperty::Left(LayoutLeft::px(0.0)))
+            .with_style(CssProperty::PointerEvents(StylePointerEvents::None));
+
+        for (tab_position, (_, bounds)) in self.tab_order.iter().enumerate() {
+            overlay.add_child(outline(bounds, TAB_STOP_COLOR));
+            overlay.add_child(badge(bounds, (tab_position + 1).to_string(), TAB_STOP_COLOR));
+        }
+
+        for (_, bounds) in &self.unreachable {
+            overlay.add_child(outline(bounds, UNREACHABLE_COLOR));
+            overlay.add_child(badge(bounds, "no TabIndex".to_string(), UNREACHABLE_COLOR));
+        }
+
+        overlay
+    }
+}
+
+/// Class of the root node of the `KeyboardAudit::to_dom` overlay
+pub(crate) const KEYBOARD_AUDIT_CLASS: &str = "__azul-keyboard-audit";
+
+/// Returns all nodes of the DOM in the order that the tab key would focus them: depth-first,
+/// but the children with `TabIndex::OverrideInParent` come before their other siblings
+/// (ordered by their index). Nodes that aren't focusable are not filtered out.
+pub(crate) fn get_tab_order<F>(root: NodeId, node_hierarchy: &NodeHierarchy, tab_index: &F) -> Vec<NodeId>
+    where F: Fn(NodeId) -> Option<TabIndex>
+{
+    let mut tab_order = vec![root];
+
+    let mut children = root.children(node_hierarchy).collect::<Vec<_>>();
+    // Stable sort, so the siblings without an override keep the DOM order
+    children.sort_by_key(|child| match tab_index(*child) {
+        Some(TabIndex::OverrideInParent(index)) => (0, index),
+        _ => (1, 0),
+    });
+
+    for child in children {
+        tab_order.extend(get_tab_order(child, node_hierarchy, tab_index));
+    }
+
+    tab_order
+}
+
+fn positioned<T>(dom: Dom<T>, bounds: &LayoutRect) -> Dom<T> {
+    dom.with_style(CssProperty::Position(LayoutPosition::Absolute))
+       .with_style(CssProperty::Top(LayoutTop::px(bounds.origin.y)))
+       .with_style(CssProperty::Left(LayoutLeft::px(bounds.origin.x)))
+       .with_style(CssProperty::PointerEvents(StylePointerEvents::None))
+}
+
+fn outline<T>(bounds: &LayoutRect, color: ColorU) -> Dom<T> {
+    positioned(Dom::div(), bounds)
+        .with_style(CssProperty::Width(LayoutWidth::px(bounds.size.width)))
+        .with_style(CssProperty::Height(LayoutHeight::px(bounds.size.height)))
+        .with_style(CssProperty::Outline(StyleOutline(StyleBorderSide {
+            border_width: PixelValue::px(2.0),
+            border_style: BorderStyle::Solid,
+            border_color: color,
+        })))
+}
+
+fn badge<T>(bounds: &LayoutRect, text: String, color: ColorU) -> Dom<T> {
+    positioned(Dom::label(text), bounds)
+        .with_style(CssProperty::Padding(LayoutPadding::all(PixelValue::px(2.0))))
+        .with_style(CssProperty::Background(StyleBackground::Color(color)))
+        .with_style(CssProperty::TextColor(StyleTextColor(ColorU { r: 255, g: 255, b: 255, a: 255 })))
+        .with_style(CssProperty::FontSize(StyleFontSize::px(11.0)))
+}
+
+#[test]
+fn test_tab_order() {
+
+    use dom::NodeData;
+
+    struct DataModel;
+
+    // 0
+    // |- 1 (Auto)
+    // |  |- 2 (Auto)
+    // |- 3 (OverrideInParent(5))
+    // |- 4 (OverrideInParent(2))
+    let dom: Dom<DataModel> = Dom::div()
+        .with_child(Dom::div().with_tab_index(TabIndex::Auto)
+            .with_child(Dom::div().with_tab_index(TabIndex::Auto)))
+        .with_child(Dom::div().with_tab_index(TabIndex::OverrideInParent(5)))
+        .with_child(Dom::div().with_tab_index(TabIndex::OverrideInParent(2)));
+
+    let node_data: &NodeDataContainer<NodeData<DataModel>> = &dom.arena.node_data;
+    let tab_order = get_tab_order(dom.root, &dom.arena.node_layout, &|node_id| node_data[node_id].tab_index);
+
+    assert_eq!(tab_order, vec![NodeId::new(0), NodeId::new(4), NodeId::new(3), NodeId::new(1), NodeId::new(2)]);
+}
@@ -0,0 +1,164 @@
+//! Dumps the layout constraints and the solved rectangles of a window to a JSON file,
+//! see `DebugState::layout_debug`
+
+use std::{fs, io};
+use azul_css::{CssDeclaration, CssPropertyType};
+use {
+    id_tree::NodeId,
+    ui_description::UiDescription,
+    ui_solver::{LayoutResult, LayoutTrace},
+};
+
+/// File that the layout is written to, relative to the working directory
+pub(crate) const LAYOUT_DEBUG_FILE: &str = "layout_debug.json";
+
+/// Writes the layout of the window to `LAYOUT_DEBUG_FILE`
+pub(crate) fn write_layout_debug<T>(window_title: &str, ui_description: &UiDescription<T>, layout_result: &LayoutResult)
+-> Result<(), io::Error>
+{
+    fs::write(LAYOUT_DEBUG_FILE, layout_debug_json(window_title, ui_description, layout_result))
+}
+
+/// Returns one JSON object per node: the cascaded CSS properties that affect the layout,
+/// the intermediate sizes of the solver and the final rectangle
+pub(crate) fn layout_debug_json<T>(window_title: &str, ui_description: &UiDescription<T>, layout_result: &LayoutResult) -> String {
+
+    let arena = &ui_description.ui_descr_arena;
+
+    let nodes = arena.node_layout.linear_iter().map(|node_id| {
+
+        let css_constraints = &ui_description.styled_nodes[node_id].css_constraints;
+        let constraints = css_constraints.iter()
+            .filter(|(_, declaration)| declaration.can_trigger_relayout())
+            .map(|(property_type, declaration)| format!("\"{}\": {}", property_type, json_string(&format_declaration(declaration))))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let trace = &layout_result.traces[node_id];
+        let bounds = &layout_result.rects[node_id].bounds;
+        let notes = get_notes(trace, bounds.size.width, bounds.size.height, &|property_type| css_constraints.contains_key(&property_type))
+            .iter()
+            .map(|note| json_string(note))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            "    {{\n      \"node\": {},\n      \"parent\": {},\n      \"html\": {},\n      \"constraints\": {{ {} }},\n      \
+            \"content_size\": [{}, {}],\n      \"min_size\": [{}, {}],\n      \"grown_by\": [{}, {}],\n      \
+            \"rect\": {{ \"x\": {}, \"y\": {}, \"width\": {}, \"height\": {} }},\n      \"notes\": [{}]\n    }}",
+            node_id,
+            arena.node_layout[node_id].parent.map(|parent: NodeId| parent.to_string()).unwrap_or_else(|| "null".to_string()),
+            json_string(&arena.node_data[node_id].to_string()),
+            constraints,
+            trace.content_width.map(json_number).unwrap_or_else(|| "null".to_string()),
+            trace.content_height.map(json_number).unwrap_or_else(|| "null".to_string()),
+            json_number(trace.min_width), json_number(trace.min_height),
+            json_number(trace.width_added), json_number(trace.height_added),
+            json_number(bounds.origin.x), json_number(bounds.origin.y),
+            json_number(bounds.size.width), json_number(bounds.size.height),
+            notes,
+        )
+    }).collect::<Vec<_>>();
+
+    format!(
+        "{{\n  \"window\": {},\n  \"min_content_size\": {},\n  \"nodes\": [\n{}\n  ]\n}}\n",
+        json_string(window_title),
+        layout_result.min_content_size
+            .map(|size| format!("[{}, {}]", json_number(size.width), json_number(size.height)))
+            .unwrap_or_else(|| "null".to_string()),
+        nodes.join(",\n"),
+    )
+}
+
+/// Explains common reasons for a collapsed (0px wide or high) node
+fn get_notes<F>(trace: &LayoutTrace, width: f32, height: f32, has_property: &F) -> Vec<String>
+    where F: Fn(CssPropertyType) -> bool
+{
+    use azul_css::CssPropertyType::*;
+
+    let mut notes = Vec::new();
+
+    for &(axis, size, min_size, added, content, size_property, min_property, max_property) in &[
+        ("width", width, trace.min_width, trace.width_added, trace.content_width, Width, MinWidth, MaxWidth),
+        ("height", height, trace.min_height, trace.height_added, trace.content_height, Height, MinHeight, MaxHeight),
+    ] {
+        if size > 0.0 {
+            continue;
+        }
+
+        if has_property(max_property) {
+            notes.push(format!("The {} is 0px because of max-{}", axis, axis));
+        } else if has_property(size_property) || has_property(min_property) {
+            notes.push(format!("The {} is 0px although {} / min-{} is set - check for a percentage of a 0px parent", axis, axis, axis));
+        } else if min_size <= 0.0 && added <= 0.0 {
+            let content = match content {
+                Some(_) => "the content is empty",
+                None => "the node has no content and no children with a size",
+            };
+            notes.push(format!(
+                "The {} is 0px because no {} / min-{} is set, {} and the node didn't grow \
+                (flex-grow is 0 or the parent has no space left)", axis, axis, axis, content
+            ));
+        }
+    }
+
+    notes
+}
+
+fn format_declaration(declaration: &CssDeclaration) -> String {
+    match declaration {
+        CssDeclaration::Static(property) => format!("{:?}", property),
+        CssDeclaration::Dynamic(dynamic) => format!("[[ {} | {:?} ]]", dynamic.dynamic_id, dynamic.default),
+        CssDeclaration::Unresolved(unresolved) => unresolved.value.clone(),
+    }
+}
+
+fn json_number(number: f32) -> String {
+    if number.is_finite() { number.to_string() } else { "null".to_string() }
+}
+
+fn json_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len() + 2);
+    escaped.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[test]
+fn test_json_string() {
+    assert_eq!(json_string("<p>\"a\\b\"\n</p>"), "\"<p>\\\"a\\\\b\\\"\\n</p>\"");
+    assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+}
+
+#[test]
+fn test_zero_size_notes() {
+
+    let trace = LayoutTrace {
+        content_width: None,
+        content_height: Some(20.0),
+        min_width: 0.0,
+        min_height: 20.0,
+        width_added: 0.0,
+        height_added: 0.0,
+    };
+
+    let notes = get_notes(&trace, 0.0, 20.0, &|_| false);
+    assert_eq!(notes.len(), 1);
+    assert!(notes[0].starts_with("The width is 0px because no width / min-width is set, the node has no content"));
+
+    let notes = get_notes(&trace, 0.0, 20.0, &|property_type| property_type == CssPropertyType::MaxWidth);
+    assert_eq!(notes, vec!["The width is 0px because of max-width".to_string()]);
+
+    assert!(get_notes(&trace, 10.0, 20.0, &|_| false).is_empty());
+}
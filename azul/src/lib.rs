@@ -60,10 +60,10 @@
 //!
 //! There are a few limitations that should be noted:
 //!
-//! - There are no scrollbars yet. Creating scrollable frames can be done by
-//!   [creating an `IFrameCallback`].
-//! - Similarly, there is no clipping of overflowing content yet - clipping only
-//!   works for `IFrameCallback`s.
+//! - Scrollbars are only drawn, the thumb can't be dragged with the mouse yet.
+//!   Large scrollable lists can be created by [creating an `IFrameCallback`].
+//! - Overflowing content is only clipped by nodes with `overflow: hidden`, `auto` or
+//!   `scroll` (including their rounded corners), the default `overflow: visible` doesn't clip.
 //! - There is no support for CSS animations of any kind yet
 //! - Changing dynamic variables will trigger an entire UI relayout and restyling
 //!
@@ -137,12 +137,33 @@
 #[macro_use(warn, error, lazy_static)]
 #[cfg_attr(feature = "svg", macro_use(implement_vertex, uniform))]
 pub extern crate azul_dependencies;
-#[cfg(feature = "serde_serialization")]
+#[cfg(any(feature = "serde_serialization", feature = "settings", feature = "net"))]
 #[cfg_attr(feature = "serde_serialization", macro_use)]
 extern crate serde;
 #[cfg(feature = "serde_serialization")]
 #[cfg_attr(feature = "serde_serialization", macro_use)]
 extern crate serde_derive;
+#[cfg(any(feature = "settings", feature = "net"))]
+extern crate serde_json;
+#[cfg(feature = "net")]
+extern crate reqwest;
+#[cfg(feature = "websocket")]
+extern crate tungstenite;
+#[cfg(feature = "websocket")]
+extern crate url;
+#[cfg(feature = "plugins")]
+extern crate libloading;
+#[cfg(feature = "derive")]
+extern crate azul_derive;
+#[cfg(feature = "profiling")]
+extern crate tracing;
+#[cfg(target_os = "macos")]
+#[macro_use]
+extern crate objc;
+#[cfg(target_os = "windows")]
+extern crate winapi;
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+extern crate x11_dl;
 
 pub(crate) use azul_dependencies::glium as glium;
 pub(crate) use azul_dependencies::gleam as gleam;
@@ -186,6 +207,8 @@ mod macros;
 pub mod app;
 /// Async IO helpers / (`Task` / `Timer` / `Thread`)
 pub mod async;
+/// Animation timelines (`Timeline` / `Animation`), sequencing and grouping property animations
+pub mod animation;
 /// Type definitions for various types of callbacks, as well as focus and scroll handling
 pub mod callbacks;
 /// CSS type definitions / CSS parsing functions
@@ -193,16 +216,39 @@ pub mod callbacks;
 pub mod css;
 /// Bindings to the native file-chooser, color picker, etc. dialogs
 pub mod dialogs;
+/// Automatically generated forms (`#[derive(AutoForm)]`), edited via the `PropertyGrid` widget
+pub mod derive {
+    pub use widgets::property_grid::{AutoForm, FormValue, FormEditor, PropertyGrid};
+    #[cfg(feature = "derive")]
+    pub use azul_derive::AutoForm;
+}
 /// DOM / HTML node handling
 pub mod dom;
+/// Type-erased DOM (`DynDom` / `DynCallback`) that doesn't depend on the application data
+pub mod dyn_dom;
 /// Re-exports of errors
 pub mod error;
+/// Formatted text (`RichText`) and its conversion to HTML / RTF for the clipboard
+pub mod rich_text;
 /// Handles text layout (modularized, can be used as a standalone module)
 pub mod text_layout;
 /// Main `Layout` trait definition + convenience traits for `Arc<Mutex<T>>`
 pub mod traits;
+/// Registering recently opened documents with the OS (jump lists, "Open Recent" menus)
+pub mod recent_documents;
+/// Headless rendering and perceptual diffing of screenshots, for visual regression tests
+pub mod screenshot;
 /// Container for default widgets (`TextInput` / `Button` / `Label`, `TableView`, ...)
 pub mod widgets;
+/// HTTP downloads of images, fonts and JSON with a disk cache
+#[cfg(feature = "net")]
+pub mod net;
+/// WebSocket client, the messages are handled by callbacks on the main thread
+#[cfg(feature = "websocket")]
+pub mod websocket;
+/// Loading widgets, callbacks and XML components from shared libraries at runtime
+#[cfg(feature = "plugins")]
+pub mod plugin;
 /// Window state handling and window-related information
 pub mod window;
 /// XML-based DOM serialization and XML-to-Rust compiler implementation
@@ -231,6 +277,15 @@ mod ui_solver;
 mod style;
 /// DOM diffing
 mod diff;
+/// Debug overlay for finding nodes that can't be used with the keyboard
+mod keyboard_audit;
+/// Debug dump of the layout constraints and solved rectangles
+mod layout_debug;
+/// Navigation stack (`Router`), exported under the "app" module
+mod router;
+/// Persisted settings (`Settings`), exported under the "app" module
+#[cfg(feature = "settings")]
+mod settings;
 /// Checks that two-way bound values are on the stack
 mod stack_checked_pointer;
 /// Window state handling and diffing
@@ -243,8 +298,8 @@ pub mod resources {
     // re-export everything *except* the AppResources (which are exported under the "app" module)
     pub use app_resources::{
         FontId, ImageId, LoadedFont, RawImage, FontReloadError, FontSource, ImageReloadError,
-        ImageSource, RawImageFormat, CssFontId, CssImageId,
-        TextCache, TextId,
+        ImageSource, ImageVariant, RawImageFormat, CssFontId, CssImageId,
+        TextCache, TextId, PaintPropertyOverride, PaintTransform, PaintClip, GlyphCacheStats,
     };
 }
 
@@ -263,37 +318,51 @@ type FastHashSet<T> = ::std::collections::HashSet<T>;
 pub mod prelude {
     #[cfg(feature = "css_parser")]
     pub use azul_css::*;
-    pub use app::{App, AppConfig, AppState, AppResources};
-    pub use async::{Task, TerminateTimer, TimerId, Timer, DropCheck};
+    pub use app::{App, AppConfig, AppState, AppResources, Router};
+    pub use async::{Task, TerminateTimer, TimerId, Timer, RateLimit, DropCheck};
+    pub use animation::{
+        Timeline, TimelineId, TimelineItem, Animation, AnimatedProperty, Easing, Repeat,
+        LayoutTransition, PresenceAnimation,
+    };
     pub use resources::{
-        RawImageFormat, ImageId, FontId, FontSource, ImageSource,
-        TextCache, TextId,
+        RawImageFormat, ImageId, FontId, FontSource, ImageSource, ImageVariant,
+        TextCache, TextId, PaintPropertyOverride, PaintTransform, PaintClip,
     };
     pub use callbacks::{
-        Callback, TimerCallback, IFrameCallback, GlTextureCallback,
+        Callback, TimerCallback, IFrameCallback, GlTextureCallback, LayoutCallback,
         UpdateScreen, Redraw, DontRedraw,
-        CallbackInfo, FocusTarget, LayoutInfo, HidpiAdjustedBounds, Texture,
+        CallbackInfo, FocusTarget, LayoutInfo, HidpiAdjustedBounds, NodeRect, Texture,
+        DynCallback, DynCallbackInfo, ComputedStyle,
     };
     pub use dom::{
-        Dom, DomHash, NodeType, NodeData, On, DomString, TabIndex,
+        Dom, DomHash, NodeType, NodeData, NodeId, On, DomString, TabIndex, HitTestBehavior,
         EventFilter, HoverEventFilter, FocusEventFilter, NotEventFilter, WindowEventFilter,
     };
+    pub use dyn_dom::{DynDom, DynNodeType};
+    pub use rich_text::{RichText, TextSpan};
     pub use traits::{Layout, Modify};
     pub use window::{
-        MonitorIter, Window, WindowCreateOptions,
-        WindowMonitorTarget, RendererType, ReadOnlyWindow
+        MonitorIter, Window, WindowCreateOptions, WindowCreateOptionsBuilder,
+        WindowCreateOptionsError, WindowMonitorTarget, RendererType, ReadOnlyWindow, WindowShadow,
+    };
+    pub use window_state::{
+        WindowState, KeyboardState, MouseState, DebugState,
+        HidpiRoundingPolicy, CursorConfinement, HoldRepeatTiming, keymap, AcceleratorKey,
     };
-    pub use window_state::{WindowState, KeyboardState, MouseState, DebugState, keymap, AcceleratorKey};
     pub use glium::glutin::{
         dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
         VirtualKeyCode, ScanCode, Icon,
     };
     pub use stack_checked_pointer::StackCheckedPointer;
-    pub use text_layout::{TextLayoutOptions, GlyphInstance};
+    pub use text_layout::{TextLayoutOptions, TabStop, TabLeader, GlyphInstance};
     pub use xml::{XmlComponent, XmlComponentMap};
 
     #[cfg(any(feature = "css_parser", feature = "native_style"))]
     pub use css;
     #[cfg(feature = "logging")]
     pub use log::LevelFilter;
+    #[cfg(feature = "websocket")]
+    pub use websocket::{WebSocket, WebSocketId, WebSocketEvent, WebSocketSender};
+    #[cfg(feature = "plugins")]
+    pub use plugin::{PluginManager, PluginRegistry, PluginError, WidgetArguments};
 }
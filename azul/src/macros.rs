@@ -78,6 +78,20 @@ macro_rules! impl_display {
     };
 }
 
+/// Records the rest of the current scope as a `tracing` span with the given name
+/// (only with `--features="profiling"`, compiles to nothing otherwise):
+///
+/// ```rust,ignore
+/// fn do_the_layout() {
+///     profile_scope!("layout");
+///     // ...
+/// }
+/// ```
+macro_rules! profile_scope {($name:expr) => (
+    #[cfg(feature = "profiling")]
+    let _profile_scope = ::tracing::info_span!($name).entered();
+)}
+
 /// Implements `Display, Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Hash`
 /// for a Callback with a `.0` field:
 ///
@@ -360,6 +374,13 @@ impl<T> $struct_name<T> {
     pub fn set_clipboard_string<I: Into<String>>(&mut self, contents: I) -> Result<(), ClipboardError> {
         self.$struct_field.set_clipboard_string(contents)
     }
+
+    /// See [`AppResources::set_clipboard_rich_text`]
+    ///
+    /// [`AppResources::set_clipboard_rich_text`]: ../app_resources/struct.AppResources.html#method.set_clipboard_rich_text
+    pub fn set_clipboard_rich_text(&mut self, contents: &RichText) -> Result<(), ClipboardError> {
+        self.$struct_field.set_clipboard_rich_text(contents)
+    }
 }
 
 )}
@@ -395,6 +416,27 @@ impl<T> $struct_name<T> {
     pub fn delete_timer(&mut self, timer_id: &TimerId) -> Option<Timer<T>> {
         self.$struct_field.delete_timer(timer_id)
     }
+
+    /// See [`AppState::add_timeline`]
+    ///
+    /// [`AppState::add_timeline`]: ../app_state/struct.AppState.html#method.add_timeline
+    pub fn add_timeline(&mut self, timeline_id: TimelineId, timeline: Timeline<T>) {
+        self.$struct_field.add_timeline(timeline_id, timeline)
+    }
+
+    /// See [`AppState::has_timeline`]
+    ///
+    /// [`AppState::has_timeline`]: ../app_state/struct.AppState.html#method.has_timeline
+    pub fn has_timeline(&self, timeline_id: &TimelineId) -> bool {
+        self.$struct_field.has_timeline(timeline_id)
+    }
+
+    /// See [`AppState::delete_timeline`]
+    ///
+    /// [`AppState::delete_timeline`]: ../app_state/struct.AppState.html#method.delete_timeline
+    pub fn delete_timeline(&mut self, timeline_id: &TimelineId) -> Option<Timeline<T>> {
+        self.$struct_field.delete_timeline(timeline_id)
+    }
 }
 
 )}
@@ -419,6 +461,44 @@ macro_rules! impl_deamon_api {() => (
         self.timers.remove(timer_id)
     }
 
+    /// Runs the callback once, after `delay` has passed without `debounce()` being called
+    /// again with the same `TimerId` - for expensive work that reacts to rapid events,
+    /// i.e. a search-as-you-type that should only run once the user stopped typing:
+    ///
+    /// ```rust,ignore
+    /// fn on_text_changed(app_state: &mut AppState<MyApp>, _: &mut CallbackInfo<MyApp>) -> UpdateScreen {
+    ///     app_state.debounce(SEARCH_TIMER_ID, Duration::from_millis(300), run_search);
+    ///     Redraw
+    /// }
+    /// ```
+    pub fn debounce(&mut self, id: TimerId, delay: ::std::time::Duration, callback: ::callbacks::TimerCallbackType<T>) {
+        use async::RateLimit;
+        self.timers.insert(id, Timer::new(callback).with_delay(delay).with_rate_limit(RateLimit::Debounce));
+    }
+
+    /// Runs the callback immediately, but at most once per `interval` for the same
+    /// `TimerId`. Calls during the interval are merged into one run at the end of the
+    /// interval, so the callback always sees the result of the last event.
+    pub fn throttle(&mut self, id: TimerId, interval: ::std::time::Duration, callback: ::callbacks::TimerCallbackType<T>) {
+        use async::{RateLimit, insert_timer};
+        insert_timer(&mut self.timers, id, Timer::new(callback).with_interval(interval).with_rate_limit(RateLimit::Throttle { pending: false }));
+    }
+
+    /// Starts playing an animation timeline. The timeline is removed once it's
+    /// finished. Replaces the existing timeline if called with the same TimelineId.
+    pub fn add_timeline(&mut self, id: TimelineId, timeline: Timeline<T>) {
+        self.timelines.insert(id, timeline);
+    }
+
+    pub fn has_timeline(&self, timeline_id: &TimelineId) -> bool {
+        self.timelines.contains_key(timeline_id)
+    }
+
+    /// Stops the timeline - the animated properties keep their current values
+    pub fn delete_timeline(&mut self, timeline_id: &TimelineId) -> Option<Timeline<T>> {
+        self.timelines.remove(timeline_id)
+    }
+
     /// Custom tasks can be used when the `AppState` isn't `Send`. For example
     /// `SvgCache` isn't thread-safe, since it has to interact with OpenGL, so
     /// it can't be sent to other threads safely.
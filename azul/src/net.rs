@@ -0,0 +1,188 @@
+//! HTTP downloads of images, fonts and JSON data (`--features="net"`)
+//!
+//! Images and fonts are downloaded in a `Task` and stored in a disk cache, so that they
+//! are only downloaded once, even across restarts of the application. An image from an
+//! URL is drawn as soon as its download has finished:
+//!
+//! ```rust,ignore
+//! fn load_avatar(app_state: &mut AppState<MyApp>, _: &mut CallbackInfo<MyApp>) -> UpdateScreen {
+//!     let image_id = app_state.resources.add_css_image_id("avatar");
+//!     net::load_image(app_state, image_id, "https://example.com/avatar.png");
+//!     Redraw
+//! }
+//! ```
+//!
+//! The blocking functions (`get`, `get_cached`, `get_json`) can be used from your own tasks.
+
+use std::{
+    env, process,
+    fs::{self, File},
+    io::{Error as IoError, Read, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex, atomic::{AtomicUsize, Ordering}},
+};
+use serde::de::DeserializeOwned;
+use serde_json::Error as JsonError;
+use {
+    app::AppState,
+    async::{Task, DropCheck},
+    app_resources::{FontId, FontSource},
+};
+#[cfg(feature = "image_loading")]
+use app_resources::{ImageId, ImageSource};
+
+static TEMP_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+pub enum NetError {
+    /// The request couldn't be sent or the response couldn't be read (invalid URL, no connection, ...)
+    Http(reqwest::Error),
+    /// The server responded with an error (status code, URL)
+    Status(u16, String),
+    /// Reading or writing the disk cache failed
+    Io(IoError, PathBuf),
+    /// The response isn't valid JSON or doesn't match the expected type
+    Json(JsonError),
+}
+
+impl_display!(NetError, {
+    Http(e) => format!("HTTP request failed: {}", e),
+    Status(status, url) => format!("Server responded with status {} for \"{}\"", status, url),
+    Io(e, path) => format!("Could not access cached file \"{}\": {}", path.display(), e),
+    Json(e) => format!("Invalid JSON response: {}", e),
+});
+
+/// Downloads the `url`, blocks until the download has finished. Doesn't use the disk cache.
+pub fn get(url: &str) -> Result<Vec<u8>, NetError> {
+
+    let mut response = reqwest::get(url).map_err(NetError::Http)?;
+
+    if !response.status().is_success() {
+        return Err(NetError::Status(response.status().as_u16(), url.to_string()));
+    }
+
+    let mut bytes = Vec::new();
+    response.copy_to(&mut bytes).map_err(NetError::Http)?;
+    Ok(bytes)
+}
+
+/// Same as `get`, but returns the cached file if the `url` has been downloaded before.
+/// Only use this for resources that don't change (images, fonts, ...).
+pub fn get_cached(url: &str) -> Result<Vec<u8>, NetError> {
+
+    if let Some(path) = cached_path(url) {
+        let mut bytes = Vec::new();
+        File::open(&path)
+            .and_then(|mut file| file.read_to_end(&mut bytes))
+            .map_err(|e| NetError::Io(e, path.clone()))?;
+        return Ok(bytes);
+    }
+
+    let bytes = get(url)?;
+    store_in_cache(url, &bytes)?;
+    Ok(bytes)
+}
+
+/// Downloads the `url` and deserializes the response. Doesn't use the disk cache.
+pub fn get_json<D: DeserializeOwned>(url: &str) -> Result<D, NetError> {
+    let bytes = get(url)?;
+    serde_json::from_slice(&bytes).map_err(NetError::Json)
+}
+
+/// Downloads the image in a `Task` and registers it as `image_id` (see `ImageSource::Url`)
+#[cfg(feature = "image_loading")]
+pub fn load_image<T>(app_state: &mut AppState<T>, image_id: ImageId, url: &str) {
+    app_state.add_image(image_id, ImageSource::Url(url.to_string()));
+    if cached_path(url).is_none() {
+        app_state.add_task(Task::new(&Arc::new(Mutex::new(url.to_string())), download_into_cache));
+    }
+}
+
+/// Downloads the font in a `Task` and registers it as `font_id` (see `FontSource::Url`)
+pub fn load_font<T>(app_state: &mut AppState<T>, font_id: FontId, url: &str) {
+    app_state.add_font(font_id, FontSource::Url(url.to_string()));
+    if cached_path(url).is_none() {
+        app_state.add_task(Task::new(&Arc::new(Mutex::new(url.to_string())), download_into_cache));
+    }
+}
+
+#[allow(unused_variables)]
+fn download_into_cache(url: Arc<Mutex<String>>, _: DropCheck) {
+    let url = url.lock().unwrap().clone();
+    if let Err(e) = get_cached(&url) {
+        #[cfg(feature = "logging")] {
+            warn!("Could not download \"{}\": {}", url, e);
+        }
+    }
+}
+
+/// Returns the path of the cached file if the `url` has already been downloaded
+pub fn cached_path(url: &str) -> Option<PathBuf> {
+    let path = cache_dir().join(cache_file_name(url));
+    if path.is_file() { Some(path) } else { None }
+}
+
+/// Deletes all cached files, i.e. to force images to be downloaded again
+pub fn clear_cache() -> Result<(), NetError> {
+    let dir = cache_dir();
+    match fs::remove_dir_all(&dir) {
+        Err(ref e) if e.kind() == ::std::io::ErrorKind::NotFound => Ok(()),
+        other => other.map_err(|e| NetError::Io(e, dir)),
+    }
+}
+
+/// Directory of the disk cache:
+///
+/// - Windows: `%LOCALAPPDATA%\azul\http-cache`
+/// - Mac: `~/Library/Caches/azul/http-cache`
+/// - Linux: `$XDG_CACHE_HOME/azul/http-cache` or `~/.cache/azul/http-cache`
+///
+/// Falls back to the temporary directory if none of these directories can be found.
+pub fn cache_dir() -> PathBuf {
+
+    let non_empty_var = |name: &str| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+
+    let platform_cache_dir = if cfg!(target_os = "windows") {
+        non_empty_var("LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        non_empty_var("HOME").map(|home| home.join("Library").join("Caches"))
+    } else {
+        non_empty_var("XDG_CACHE_HOME").or_else(|| non_empty_var("HOME").map(|home| home.join(".cache")))
+    };
+
+    platform_cache_dir.unwrap_or_else(env::temp_dir).join("azul").join("http-cache")
+}
+
+fn store_in_cache(url: &str, bytes: &[u8]) -> Result<(), NetError> {
+
+    let dir = cache_dir();
+    fs::create_dir_all(&dir).map_err(|e| NetError::Io(e, dir.clone()))?;
+
+    // The main thread might read the file while it is being written,
+    // so the file is written under a unique name first, then renamed
+    let file_name = cache_file_name(url);
+    let path = dir.join(&file_name);
+    let temp_path = dir.join(format!("{}.{}.{}.tmp", file_name, process::id(), TEMP_FILE_ID.fetch_add(1, Ordering::SeqCst)));
+
+    File::create(&temp_path)
+        .and_then(|mut file| file.write_all(bytes))
+        .map_err(|e| NetError::Io(e, temp_path.clone()))?;
+
+    fs::rename(&temp_path, &path).map_err(|e| NetError::Io(e, path))
+}
+
+/// FNV-1a hash of the URL - unlike the `DefaultHasher`, the hash
+/// is guaranteed to stay the same across Rust versions
+fn cache_file_name(url: &str) -> String {
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[test]
+fn test_cache_file_name() {
+    assert_eq!(cache_file_name(""), "cbf29ce484222325");
+    assert_eq!(cache_file_name("a"), "af63dc4c8601ec8c");
+    assert_ne!(cache_file_name("https://example.com/a.png"), cache_file_name("https://example.com/b.png"));
+}
@@ -0,0 +1,223 @@
+//! Loading of widgets, callbacks and XML components from shared libraries
+//! at runtime (`--features="plugins"`)
+//!
+//! A plugin is a `cdylib` / `dylib` that depends on `azul` and exports a
+//! registration function, which adds its components to the `PluginRegistry`:
+//!
+//! ```rust,ignore
+//! #[no_mangle]
+//! pub fn azul_plugin_register(registry: &mut PluginRegistry) {
+//!     registry.register_widget("knob", render_knob);
+//!     registry.register_callback("reset_knob", DynCallback(reset_knob));
+//! }
+//! ```
+//!
+//! The host application then loads the plugin and renders the widgets in its `layout()`:
+//!
+//! ```rust,ignore
+//! let mut plugins = PluginManager::new();
+//! plugins.load("plugins/libknob.so")?;
+//! // ...
+//! let knob = plugins.render_widget("knob", &args, info.window);
+//! ```
+//!
+//! Since the plugins only work on the type-erased `DynDom`, they don't have
+//! to be compiled against the data model of the application. However, Rust has no
+//! stable ABI, so the plugin has to be compiled with the same compiler and the
+//! same version of azul as the host application.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    collections::BTreeMap,
+    io::Error as IoError,
+};
+use libloading::{Library, Symbol};
+use {
+    callbacks::DynCallback,
+    dom::Dom,
+    dyn_dom::DynDom,
+    window::FakeWindow,
+    xml::{
+        XmlComponent, XmlComponentMap, ComponentArguments, FilteredComponentArguments,
+        XmlTextContent, RenderDomError, CompileError,
+    },
+};
+
+/// Name of the function that every plugin has to export, see the module documentation
+pub const PLUGIN_REGISTER_FN: &str = "azul_plugin_register";
+
+/// Signature of the `azul_plugin_register` function
+pub type PluginRegisterFn = fn(&mut PluginRegistry);
+
+/// Arguments of a widget, i.e. `value="0.5"` for a `<knob value="0.5" />`
+pub type WidgetArguments = BTreeMap<String, String>;
+
+/// Function that renders a plugin widget
+pub type WidgetRenderFn = fn(&WidgetArguments) -> DynDom;
+
+#[derive(Debug)]
+pub enum PluginError {
+    /// The shared library could not be loaded (path, error)
+    Load(PathBuf, IoError),
+    /// The library doesn't export an `azul_plugin_register` function (path, error)
+    MissingRegisterFn(PathBuf, IoError),
+}
+
+impl_display!(PluginError, {
+    Load(path, e) => format!("Could not load plugin \"{}\": {}", path.display(), e),
+    MissingRegisterFn(path, e) => format!("Plugin \"{}\" has no {} function: {}", path.display(), PLUGIN_REGISTER_FN, e),
+});
+
+/// Components that plugins have registered, indexed by their name
+#[derive(Default, Clone)]
+pub struct PluginRegistry {
+    widgets: BTreeMap<String, WidgetRenderFn>,
+    callbacks: BTreeMap<String, DynCallback>,
+    /// Widgets that can be used as XML components + their available arguments
+    xml_components: BTreeMap<String, (WidgetRenderFn, ComponentArguments)>,
+}
+
+impl fmt::Debug for PluginRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PluginRegistry {{ widgets: {:?}, callbacks: {:?}, xml_components: {:?} }}",
+            self.widgets.keys().collect::<Vec<_>>(),
+            self.callbacks,
+            self.xml_components.keys().collect::<Vec<_>>(),
+        )
+    }
+}
+
+impl PluginRegistry {
+
+    /// Registers a widget that the host application can render via `PluginManager::render_widget`
+    pub fn register_widget<S: Into<String>>(&mut self, name: S, widget: WidgetRenderFn) {
+        self.widgets.insert(name.into(), widget);
+    }
+
+    /// Registers a callback that the host application can attach to its own nodes
+    pub fn register_callback<S: Into<String>>(&mut self, name: S, callback: DynCallback) {
+        self.callbacks.insert(name.into(), callback);
+    }
+
+    /// Registers a widget as an XML component, so that it can be used as `<name arg="..." />`
+    /// in XML files. Note that XML components can't have callbacks, so the callbacks
+    /// of the rendered `DynDom` are ignored, and that they can't be compiled to Rust code.
+    pub fn register_xml_component<S: Into<String>>(&mut self, name: S, widget: WidgetRenderFn, arguments: ComponentArguments) {
+        self.xml_components.insert(name.into(), (widget, arguments));
+    }
+}
+
+/// Loads plugins and keeps their libraries alive.
+///
+/// The widgets and callbacks of a plugin are function pointers into the shared library,
+/// so the `PluginManager` must outlive all `Dom`s that were rendered from plugin widgets.
+#[derive(Default)]
+pub struct PluginManager {
+    // NOTE: `registry` has to be declared before `libraries`, so that it is dropped first
+    registry: PluginRegistry,
+    libraries: Vec<(PathBuf, Library)>,
+}
+
+impl fmt::Debug for PluginManager {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PluginManager {{ registry: {:?}, libraries: {:?} }}",
+            self.registry,
+            self.libraries.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+        )
+    }
+}
+
+impl PluginManager {
+
+    /// Creates a new `PluginManager` without any plugins
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the shared library at `path` and calls its `azul_plugin_register` function
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), PluginError> {
+
+        let path = path.as_ref().to_path_buf();
+        let library = Library::new(&path).map_err(|e| PluginError::Load(path.clone(), e))?;
+
+        {
+            let symbol = format!("{}\0", PLUGIN_REGISTER_FN);
+            // Safe as long as the plugin follows the documented signature of the register function
+            let register: Symbol<PluginRegisterFn> = unsafe { library.get(symbol.as_bytes()) }
+                .map_err(|e| PluginError::MissingRegisterFn(path.clone(), e))?;
+            (*register)(&mut self.registry);
+        }
+
+        self.libraries.push((path, library));
+        Ok(())
+    }
+
+    /// Returns the paths of all loaded plugins, in the order they were loaded
+    pub fn get_loaded_plugins(&self) -> Vec<&Path> {
+        self.libraries.iter().map(|(path, _)| path.as_path()).collect()
+    }
+
+    pub fn get_registry(&self) -> &PluginRegistry {
+        &self.registry
+    }
+
+    pub fn get_widget(&self, name: &str) -> Option<WidgetRenderFn> {
+        self.registry.widgets.get(name).cloned()
+    }
+
+    pub fn get_callback(&self, name: &str) -> Option<DynCallback> {
+        self.registry.callbacks.get(name).cloned()
+    }
+
+    /// Renders the widget `name` and registers its callbacks on the `window`.
+    /// Returns `None` if no plugin has registered a widget with this name.
+    pub fn render_widget<T>(&self, name: &str, arguments: &WidgetArguments, window: &mut FakeWindow<T>) -> Option<Dom<T>> {
+        let widget = self.get_widget(name)?;
+        Some(widget(arguments).into_dom(window))
+    }
+
+    /// Adds all XML components of the loaded plugins to the `component_map`
+    pub fn register_xml_components<T>(&self, component_map: &mut XmlComponentMap<T>) {
+        for (name, (widget, arguments)) in &self.registry.xml_components {
+            component_map.register_component(name, Box::new(PluginXmlComponent {
+                name: name.clone(),
+                widget: *widget,
+                arguments: arguments.clone(),
+            }), false);
+        }
+    }
+}
+
+/// XML component that renders a plugin widget
+struct PluginXmlComponent {
+    name: String,
+    widget: WidgetRenderFn,
+    arguments: ComponentArguments,
+}
+
+impl<T> XmlComponent<T> for PluginXmlComponent {
+
+    fn get_available_arguments(&self) -> ComponentArguments {
+        self.arguments.clone()
+    }
+
+    fn render_dom(
+        &self,
+        _: &XmlComponentMap<T>,
+        arguments: &FilteredComponentArguments,
+        _: &XmlTextContent,
+    ) -> Result<Dom<T>, RenderDomError> {
+        Ok((self.widget)(arguments).into_dom_without_callbacks())
+    }
+
+    fn compile_to_rust_code(
+        &self,
+        _: &XmlComponentMap<T>,
+        _: &FilteredComponentArguments,
+        _: &XmlTextContent,
+    ) -> Result<String, CompileError> {
+        // The widget only exists in the shared library, which isn't available to the compiled code
+        Err(format!("Plugin component \"{}\" can't be compiled to Rust code, it can only be rendered at runtime", self.name))
+    }
+}
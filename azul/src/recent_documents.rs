@@ -0,0 +1,533 @@
+//! Registering recently opened documents with the operating system, so that they show
+//! up in the "recent items" of the OS (the jump list of the taskbar icon on Windows,
+//! the "Open Recent" menu / dock menu on macOS, the "Recent" section of GTK file choosers)
+//!
+//! When the user picks a document from one of these lists, the OS launches the
+//! application with the path of the document as a command-line argument (on macOS,
+//! the running application receives an Apple Event instead). These paths are
+//! delivered to the hooks registered via `App::on_open_documents`.
+
+use std::{
+    env,
+    cell::RefCell,
+    ffi::OsString,
+    io::Error as IoError,
+    path::{Path, PathBuf},
+};
+
+thread_local! {
+    /// Documents that the running application was asked to open, see `take_opened_documents`
+    static OPENED_DOCUMENTS: RefCell<Vec<PathBuf>> = RefCell::new(Vec::new());
+}
+
+#[derive(Debug)]
+pub enum RecentDocumentError {
+    /// The path doesn't point to an existing file
+    NotAFile(PathBuf),
+    /// The list of recent documents of the OS could not be read or written
+    Io(IoError, PathBuf),
+    /// No place to store the recent documents could be found (i.e. `$HOME` isn't set)
+    NoDataDir,
+}
+
+impl_display!(RecentDocumentError, {
+    NotAFile(path) => format!("Recent document \"{}\" is not a file", path.display()),
+    Io(e, path) => format!("Could not update the recent documents in \"{}\": {}", path.display(), e),
+    NoDataDir => "Could not determine the data directory for the recent documents",
+});
+
+/// Adds the document at `path` to the recently used documents of the OS. If the
+/// document was already registered, it is moved to the top of the list.
+///
+/// On Windows, the document only shows up in the jump list of the application if
+/// the file type of the document is registered to open with the application.
+pub fn add_recent_document<P: AsRef<Path>>(path: P) -> Result<(), RecentDocumentError> {
+
+    let path = path.as_ref();
+
+    if !path.is_file() {
+        return Err(RecentDocumentError::NotAFile(path.to_path_buf()));
+    }
+
+    // Relative paths are useless for the OS, since it doesn't know the working directory of the app
+    let path = path.canonicalize().map_err(|e| RecentDocumentError::Io(e, path.to_path_buf()))?;
+
+    platform::add_recent_document(&path)
+}
+
+/// Returns the documents that the application was launched with. This is how the OS passes
+/// a document to the application when the user opens it from a list of recent documents.
+///
+/// The OS passes nothing but the documents (as paths or `file://` URIs), so if any argument
+/// isn't an existing file, the arguments are the command line of the application itself
+/// (i.e. `--config settings.toml`) and no documents are returned.
+pub fn get_launch_documents() -> Vec<PathBuf> {
+    parse_launch_documents(env::args_os().skip(1), |path| path.is_file())
+}
+
+fn parse_launch_documents<I: Iterator<Item=OsString>, F: Fn(&Path) -> bool>(args: I, is_file: F) -> Vec<PathBuf> {
+
+    let mut documents = Vec::new();
+
+    for arg in args {
+        // Older versions of macOS pass the process serial number to apps launched from the Finder
+        if arg.to_str().map(|arg| arg.starts_with("-psn_")).unwrap_or(false) {
+            continue;
+        }
+        let path = match arg.to_str().and_then(get_file_uri_path) {
+            Some(path) => path,
+            None => PathBuf::from(arg),
+        };
+        if !is_file(&path) {
+            return Vec::new();
+        }
+        documents.push(path);
+    }
+
+    documents
+}
+
+/// Returns the documents that the OS asked the running application to open since the last call.
+/// On macOS, these are the documents of the `application:openFiles:` Apple Event (i.e. a recent
+/// document picked from the dock menu while the application is running). Only works after
+/// `install_open_documents_handler` was called, on the main thread.
+pub(crate) fn take_opened_documents() -> Vec<PathBuf> {
+    OPENED_DOCUMENTS.with(|documents| documents.borrow_mut().drain(..).collect())
+}
+
+/// Makes the OS deliver the documents that the running application is asked to open to
+/// `take_opened_documents`. Has to be called on the main thread, after the first window
+/// was created. Does nothing on Windows and Linux, where a new process is launched instead.
+pub(crate) fn install_open_documents_handler() {
+    platform::install_open_documents_handler();
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+
+    use std::{ffi::OsStr, os::windows::ffi::OsStrExt, path::Path};
+    use super::RecentDocumentError;
+
+    /// `SHARD_PATHW`: the argument of `SHAddToRecentDocs` is a null-terminated UTF-16 path
+    const SHARD_PATHW: u32 = 0x00000003;
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHAddToRecentDocs(flags: u32, path: *const u16);
+    }
+
+    pub(super) fn add_recent_document(path: &Path) -> Result<(), RecentDocumentError> {
+        let wide_path = OsStr::new(path).encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+        unsafe { SHAddToRecentDocs(SHARD_PATHW, wide_path.as_ptr()) };
+        Ok(())
+    }
+
+    pub(super) fn install_open_documents_handler() { }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+
+    use std::{ffi::{CStr, CString}, mem, os::raw::c_char, path::{Path, PathBuf}};
+    use objc::runtime::{self, Object, Sel};
+    use super::{RecentDocumentError, OPENED_DOCUMENTS};
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" { }
+
+    /// `NSApplicationDelegateReplySuccess`
+    const REPLY_SUCCESS: usize = 0;
+
+    pub(super) fn add_recent_document(path: &Path) -> Result<(), RecentDocumentError> {
+
+        let path_str = CString::new(path.to_string_lossy().into_owned())
+            .map_err(|_| RecentDocumentError::NotAFile(path.to_path_buf()))?;
+
+        unsafe {
+            // [[NSDocumentController sharedDocumentController] noteNewRecentDocumentURL:[NSURL fileURLWithPath:path]]
+            let ns_path: *mut Object = msg_send![class!(NSString), stringWithUTF8String: path_str.as_ptr()];
+            let url: *mut Object = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+            let controller: *mut Object = msg_send![class!(NSDocumentController), sharedDocumentController];
+            let () = msg_send![controller, noteNewRecentDocumentURL: url];
+        }
+
+        Ok(())
+    }
+
+    /// `-[NSApplicationDelegate application:openFiles:]`, called with an `NSArray` of `NSString` paths
+    extern "C" fn application_open_files(_this: &Object, _sel: Sel, sender: *mut Object, file_names: *mut Object) {
+        unsafe {
+            let count: usize = msg_send![file_names, count];
+            let paths = (0..count).filter_map(|index| {
+                let file_name: *mut Object = msg_send![file_names, objectAtIndex: index];
+                let file_name: *const c_char = msg_send![file_name, UTF8String];
+                if file_name.is_null() {
+                    None
+                } else {
+                    Some(PathBuf::from(CStr::from_ptr(file_name).to_string_lossy().into_owned()))
+                }
+            }).collect::<Vec<_>>();
+
+            OPENED_DOCUMENTS.with(|documents| documents.borrow_mut().extend(paths));
+            let () = msg_send![sender, replyToOpenOrPrint: REPLY_SUCCESS];
+        }
+    }
+
+    pub(super) fn install_open_documents_handler() {
+        unsafe {
+            // The delegate is created by the windowing library, so the method is added to its class
+            let application: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let delegate: *mut Object = msg_send![application, delegate];
+            if delegate.is_null() {
+                return;
+            }
+            let types = CString::new("v@:@@").unwrap();
+            let imp: extern "C" fn(&Object, Sel, *mut Object, *mut Object) = application_open_files;
+            let class = runtime::object_getClass(delegate) as *mut runtime::Class;
+            runtime::class_addMethod(class, sel!(application:openFiles:), mem::transmute(imp), types.as_ptr());
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+
+    use std::{
+        env,
+        fs::{self, File},
+        io::{ErrorKind, Write},
+        path::{Path, PathBuf},
+        time::{SystemTime, UNIX_EPOCH},
+    };
+    use super::{RecentDocumentError, add_xbel_bookmark, get_file_uri, guess_mime_type, format_xbel_timestamp};
+
+    /// Returns the path of the XBEL file that GTK (`GtkRecentManager`) reads the recent documents from
+    fn get_xbel_path() -> Option<PathBuf> {
+        let non_empty_var = |name: &str| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+        non_empty_var("XDG_DATA_HOME")
+            .or_else(|| non_empty_var("HOME").map(|home| home.join(".local").join("share")))
+            .map(|data_dir| data_dir.join("recently-used.xbel"))
+    }
+
+    pub(super) fn add_recent_document(path: &Path) -> Result<(), RecentDocumentError> {
+
+        let xbel_path = get_xbel_path().ok_or(RecentDocumentError::NoDataDir)?;
+        let io_error = |e| RecentDocumentError::Io(e, xbel_path.clone());
+
+        let xbel = match fs::read_to_string(&xbel_path) {
+            Ok(xbel) => xbel,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(io_error(e)),
+        };
+
+        let exe = env::current_exe().map_err(io_error)?;
+        let app_name = exe.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let exec = format!("'{}' %u", exe.display());
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+
+        let new_xbel = add_xbel_bookmark(&xbel, &get_file_uri(path), guess_mime_type(path), &app_name, &exec, &format_xbel_timestamp(now));
+
+        if let Some(parent) = xbel_path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+
+        // Other applications may read the file at any time, so never leave a half-written file behind
+        let temp_path = xbel_path.with_extension(format!("xbel.{}.tmp", ::std::process::id()));
+        File::create(&temp_path)
+            .and_then(|mut file| file.write_all(new_xbel.as_bytes()).and_then(|_| file.sync_all()))
+            .and_then(|_| fs::rename(&temp_path, &xbel_path))
+            .map_err(io_error)
+    }
+
+    pub(super) fn install_open_documents_handler() { }
+}
+
+const XBEL_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<xbel version=\"1.0\"\n      \
+xmlns:bookmark=\"http://www.freedesktop.org/standards/desktop-bookmarks\"\n      \
+xmlns:mime=\"http://www.freedesktop.org/standards/shared-mime-info\"\n>\n";
+
+const XBEL_FOOTER: &str = "</xbel>";
+
+/// Inserts a bookmark for the document at `uri` into the (possibly empty) XBEL file
+/// `xbel`. If a bookmark for the same URI already exists, it is replaced, so that
+/// the document moves to the top of the recent documents. The replaced bookmark keeps
+/// the time when it was added, its mime type and the entries of the other applications
+/// that opened the document, the entry of this application counts how often it was opened.
+fn add_xbel_bookmark(xbel: &str, uri: &str, mime_type: &str, app_name: &str, exec: &str, timestamp: &str) -> String {
+
+    let href = format!("<bookmark href=\"{}\"", escape_xml_attribute(uri));
+    let app_name = escape_xml_attribute(app_name);
+
+    let mut bookmarks = match (xbel.find("<bookmark "), xbel.rfind(XBEL_FOOTER)) {
+        (Some(start), Some(end)) if start < end => xbel[start..end].trim_end().to_string(),
+        _ => String::new(),
+    };
+
+    let mut added = timestamp.to_string();
+    let mut mime_type = escape_xml_attribute(mime_type);
+    let mut applications = Vec::new();
+
+    if let Some(start) = bookmarks.find(&href) {
+        let end = bookmarks[start..].find("</bookmark>").map(|end| start + end + "</bookmark>".len()).unwrap_or(bookmarks.len());
+        let end = end + bookmarks[end..].len() - bookmarks[end..].trim_start().len();
+        {
+            let old_bookmark = &bookmarks[start..end];
+            if let Some(old_added) = get_xml_attribute(old_bookmark, "added") {
+                added = old_added.to_string();
+            }
+            if let Some(old_mime_type) = old_bookmark.find("<mime:mime-type ").and_then(|mime_start| get_xml_attribute(&old_bookmark[mime_start..], "type")) {
+                mime_type = old_mime_type.to_string();
+            }
+            applications = old_bookmark.match_indices("<bookmark:application ").filter_map(|(app_start, _)| {
+                let app_end = old_bookmark[app_start..].find("/>")?;
+                Some(old_bookmark[app_start..app_start + app_end + "/>".len()].to_string())
+            }).collect::<Vec<_>>();
+        }
+        bookmarks.replace_range(start..end, "");
+    }
+
+    let count = applications.iter()
+        .find(|application| get_xml_attribute(application, "name") == Some(app_name.as_str()))
+        .and_then(|application| get_xml_attribute(application, "count")?.parse::<usize>().ok())
+        .unwrap_or(0) + 1;
+
+    applications.retain(|application| get_xml_attribute(application, "name") != Some(app_name.as_str()));
+    applications.push(format!(
+        "<bookmark:application name=\"{name}\" exec=\"{exec}\" modified=\"{time}\" count=\"{count}\"/>",
+        name = app_name,
+        exec = escape_xml_attribute(exec),
+        time = timestamp,
+        count = count,
+    ));
+
+    let header = match xbel.find("<bookmark ") {
+        Some(start) if xbel.contains(XBEL_FOOTER) => &xbel[..start],
+        _ => XBEL_HEADER,
+    };
+
+    let bookmark = format!(
+        "<bookmark href=\"{uri}\" added=\"{added}\" modified=\"{time}\" visited=\"{time}\">\n    \
+        <info>\n      \
+        <metadata owner=\"http://freedesktop.org\">\n        \
+        <mime:mime-type type=\"{mime_type}\"/>\n        \
+        <bookmark:applications>\n          \
+        {applications}\n        \
+        </bookmark:applications>\n      \
+        </metadata>\n    \
+        </info>\n  \
+        </bookmark>",
+        uri = escape_xml_attribute(uri),
+        added = added,
+        time = timestamp,
+        mime_type = mime_type,
+        applications = applications.join("\n          "),
+    );
+
+    let mut new_xbel = header.trim_end().to_string();
+    new_xbel.push_str("\n  ");
+    new_xbel.push_str(&bookmarks);
+    if !bookmarks.is_empty() {
+        new_xbel.push_str("\n  ");
+    }
+    new_xbel.push_str(&bookmark);
+    new_xbel.push('\n');
+    new_xbel.push_str(XBEL_FOOTER);
+    new_xbel.push('\n');
+    new_xbel
+}
+
+/// Returns the (still escaped) value of the attribute `name` of the first XML element in `element`
+fn get_xml_attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    let element = &element[..element.find('>').unwrap_or(element.len())];
+    let prefix = format!(" {}=\"", name);
+    let start = element.find(&prefix)? + prefix.len();
+    let end = element[start..].find('"')?;
+    Some(&element[start..start + end])
+}
+
+/// Guesses the mime type of the document from its file extension
+fn guess_mime_type(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default().to_lowercase();
+    match extension.as_str() {
+        "txt" | "log" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "rs" => "text/rust",
+        "xml" => "application/xml",
+        "json" => "application/json",
+        "toml" => "application/toml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/vnd.microsoft.icon",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/x-wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Converts an absolute path into a percent-encoded `file://` URI
+fn get_file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Converts a `file://` URI (as passed by Linux desktops for `%u` in the `exec` of a bookmark)
+/// back into a path, returns `None` if `uri` isn't a local `file://` URI
+fn get_file_uri_path(uri: &str) -> Option<PathBuf> {
+
+    // "file:///path" or "file://localhost/path"
+    if !uri.starts_with("file://") {
+        return None;
+    }
+    let path = &uri["file://".len()..];
+    let path = if path.starts_with("localhost/") { &path["localhost".len()..] } else { path };
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut chars = path.bytes();
+    while let Some(byte) = chars.next() {
+        if byte == b'%' {
+            let hex = [chars.next()?, chars.next()?];
+            bytes.push(u8::from_str_radix(::std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+fn escape_xml_attribute(s: &str) -> String {
+    s.replace('&', "&amp;")
+     .replace('<', "&lt;")
+     .replace('>', "&gt;")
+     .replace('"', "&quot;")
+     .replace('\'', "&apos;")
+}
+
+/// Formats seconds since the UNIX epoch as an ISO 8601 UTC timestamp, as used in XBEL files
+fn format_xbel_timestamp(unix_secs: u64) -> String {
+
+    let days = (unix_secs / 86400) as i64;
+    let secs_of_day = unix_secs % 86400;
+
+    // Converts days since 1970-01-01 to a civil date (proleptic gregorian calendar)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, secs_of_day / 3600, (secs_of_day / 60) % 60, secs_of_day % 60,
+    )
+}
+
+#[test]
+fn test_add_xbel_bookmark() {
+
+    assert_eq!(format_xbel_timestamp(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_xbel_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_xbel_timestamp(1_791_979_199), "2026-10-14T11:59:59Z");
+
+    assert_eq!(get_file_uri(Path::new("/home/user/my file#1.txt")), "file:///home/user/my%20file%231.txt");
+
+    let xbel = add_xbel_bookmark("", "file:///a.txt", "text/plain", "app", "'app' %u", "2026-10-14T11:59:59Z");
+    assert!(xbel.starts_with(XBEL_HEADER));
+    assert!(xbel.ends_with("</xbel>\n"));
+    assert!(xbel.contains("exec=\"&apos;app&apos; %u\""));
+    assert!(xbel.contains("<mime:mime-type type=\"text/plain\"/>"));
+
+    let xbel = add_xbel_bookmark(&xbel, "file:///b.txt", "text/plain", "app", "'app' %u", "2026-10-14T12:00:00Z");
+    let xbel = add_xbel_bookmark(&xbel, "file:///a.txt", "text/plain", "app", "'app' %u", "2026-10-14T12:00:01Z");
+
+    // Re-adding a document replaces the old bookmark and moves it to the end (most recent)
+    assert_eq!(xbel.matches("<bookmark href=").count(), 2);
+    assert!(xbel.find("file:///b.txt").unwrap() < xbel.find("file:///a.txt").unwrap());
+    assert!(xbel.contains("<bookmark href=\"file:///a.txt\" added=\"2026-10-14T11:59:59Z\" modified=\"2026-10-14T12:00:01Z\""));
+    assert!(xbel.contains("modified=\"2026-10-14T12:00:01Z\" count=\"2\"/>"));
+    assert_eq!(xbel.matches("</xbel>").count(), 1);
+}
+
+#[test]
+fn test_add_xbel_bookmark_keeps_other_applications() {
+
+    // Written by gedit
+    let xbel = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <xbel version=\"1.0\"\n      \
+        xmlns:bookmark=\"http://www.freedesktop.org/standards/desktop-bookmarks\"\n      \
+        xmlns:mime=\"http://www.freedesktop.org/standards/shared-mime-info\"\n>\n  \
+        <bookmark href=\"file:///notes.md\" added=\"2026-01-01T10:00:00.000000Z\" modified=\"2026-01-01T10:00:00.000000Z\" visited=\"2026-01-01T10:00:00.000000Z\">\n    \
+        <info>\n      \
+        <metadata owner=\"http://freedesktop.org\">\n        \
+        <mime:mime-type type=\"text/markdown\"/>\n        \
+        <bookmark:applications>\n          \
+        <bookmark:application name=\"gedit\" exec=\"&apos;gedit %u&apos;\" modified=\"2026-01-01T10:00:00.000000Z\" count=\"3\"/>\n        \
+        </bookmark:applications>\n      \
+        </metadata>\n    \
+        </info>\n  \
+        </bookmark>\n\
+        </xbel>\n";
+
+    let xbel = add_xbel_bookmark(xbel, "file:///notes.md", "application/octet-stream", "app", "'app' %u", "2026-10-14T12:00:00Z");
+
+    assert_eq!(xbel.matches("<bookmark href=").count(), 1);
+    assert!(xbel.contains("added=\"2026-01-01T10:00:00.000000Z\""));
+    assert!(xbel.contains("<mime:mime-type type=\"text/markdown\"/>"));
+    assert!(xbel.contains("<bookmark:application name=\"gedit\" exec=\"&apos;gedit %u&apos;\" modified=\"2026-01-01T10:00:00.000000Z\" count=\"3\"/>"));
+    assert!(xbel.contains("<bookmark:application name=\"app\" exec=\"&apos;app&apos; %u\" modified=\"2026-10-14T12:00:00Z\" count=\"1\"/>"));
+}
+
+#[test]
+fn test_guess_mime_type() {
+    assert_eq!(guess_mime_type(Path::new("/home/user/notes.MD")), "text/markdown");
+    assert_eq!(guess_mime_type(Path::new("/home/user/photo.jpeg")), "image/jpeg");
+    assert_eq!(guess_mime_type(Path::new("/home/user/data.unknown")), "application/octet-stream");
+    assert_eq!(guess_mime_type(Path::new("/home/user/Makefile")), "application/octet-stream");
+}
+
+#[test]
+fn test_parse_launch_documents() {
+
+    let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>().into_iter();
+    let is_file = |path: &Path| path.extension().is_some();
+
+    assert_eq!(parse_launch_documents(args(&["/a.txt", "/b.toml"]), is_file), vec![PathBuf::from("/a.txt"), PathBuf::from("/b.toml")]);
+    assert_eq!(parse_launch_documents(args(&["file:///my%20file.txt"]), is_file), vec![PathBuf::from("/my file.txt")]);
+    assert_eq!(parse_launch_documents(args(&["-psn_0_12345", "/a.txt"]), is_file), vec![PathBuf::from("/a.txt")]);
+
+    // The command line of the application itself
+    assert!(parse_launch_documents(args(&["--config", "settings.toml"]), is_file).is_empty());
+    assert!(parse_launch_documents(args(&[]), is_file).is_empty());
+
+    assert_eq!(get_file_uri_path("file://localhost/a%23b.txt"), Some(PathBuf::from("/a#b.txt")));
+    assert_eq!(get_file_uri_path("file:///a%2"), None);
+    assert_eq!(get_file_uri_path("http://example.com/a.txt"), None);
+}
@@ -0,0 +1,675 @@
+//! Formatted text (`RichText`), made up of styled `TextSpan`s, which can be
+//! converted to HTML and RTF, so that copied text keeps its formatting when it
+//! is pasted into a word processor (see `AppResources::set_clipboard_rich_text`)
+
+use std::fmt::Write;
+use azul_css::ColorU;
+use clipboard2::ClipboardError;
+
+/// Run of text where all characters have the same formatting
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TextSpan {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    /// Font size in pixels, `None` uses the default font size of the application the text is pasted into
+    pub font_size_px: Option<f32>,
+    /// Text color, `None` uses the default text color of the application the text is pasted into
+    pub color: Option<ColorU>,
+}
+
+impl TextSpan {
+
+    /// Creates an unformatted span
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Self { text: text.into(), .. Default::default() }
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+
+    pub fn with_font_size(mut self, font_size_px: f32) -> Self {
+        self.font_size_px = Some(font_size_px);
+        self
+    }
+
+    pub fn with_color(mut self, color: ColorU) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// Formatted text, i.e. the contents of a rich text editor
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RichText {
+    pub spans: Vec<TextSpan>,
+}
+
+impl RichText {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_span(mut self, span: TextSpan) -> Self {
+        self.spans.push(span);
+        self
+    }
+
+    pub fn add_span(&mut self, span: TextSpan) {
+        self.spans.push(span);
+    }
+
+    /// Returns the text without any formatting
+    pub fn to_plain_text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    /// Returns the text as an HTML fragment, one `<span>` with an inline style per span
+    pub fn to_html(&self) -> String {
+
+        let mut html = String::new();
+
+        for span in &self.spans {
+
+            let mut style = String::new();
+            if span.bold {
+                style.push_str("font-weight:bold;");
+            }
+            if span.italic {
+                style.push_str("font-style:italic;");
+            }
+            match (span.underline, span.strikethrough) {
+                (true, true) => style.push_str("text-decoration:underline line-through;"),
+                (true, false) => style.push_str("text-decoration:underline;"),
+                (false, true) => style.push_str("text-decoration:line-through;"),
+                (false, false) => { },
+            }
+            if let Some(font_size) = span.font_size_px {
+                let _ = write!(style, "font-size:{}px;", font_size);
+            }
+            if let Some(c) = span.color {
+                let _ = write!(style, "color:#{:02x}{:02x}{:02x};", c.r, c.g, c.b);
+            }
+
+            let text = escape_html(&span.text);
+            if style.is_empty() {
+                html.push_str(&text);
+            } else {
+                let _ = write!(html, "<span style=\"{}\">{}</span>", style, text);
+            }
+        }
+
+        html
+    }
+
+    /// Returns the text as an RTF document, one group per span
+    pub fn to_rtf(&self) -> String {
+
+        // Color table, index 0 is the default color
+        let mut colors = Vec::<ColorU>::new();
+        for color in self.spans.iter().filter_map(|span| span.color) {
+            if !colors.contains(&color) {
+                colors.push(color);
+            }
+        }
+
+        let mut rtf = String::from("{\\rtf1\\ansi\\deff0{\\colortbl;");
+        for c in &colors {
+            let _ = write!(rtf, "\\red{}\\green{}\\blue{};", c.r, c.g, c.b);
+        }
+        rtf.push('}');
+
+        for span in &self.spans {
+            rtf.push('{');
+            if span.bold {
+                rtf.push_str("\\b");
+            }
+            if span.italic {
+                rtf.push_str("\\i");
+            }
+            if span.underline {
+                rtf.push_str("\\ul");
+            }
+            if span.strikethrough {
+                rtf.push_str("\\strike");
+            }
+            if let Some(font_size) = span.font_size_px {
+                // RTF font sizes are in half-points, 1px = 0.75pt
+                let _ = write!(rtf, "\\fs{}", (font_size * 1.5).round() as u32);
+            }
+            if let Some(color) = span.color {
+                let index = colors.iter().position(|c| *c == color).unwrap_or(0) + 1;
+                let _ = write!(rtf, "\\cf{}", index);
+            }
+            rtf.push(' ');
+            rtf.push_str(&escape_rtf(&span.text));
+            rtf.push('}');
+        }
+
+        rtf.push('}');
+        rtf
+    }
+}
+
+/// Places formatted text on the system clipboard: the plain text together with the HTML
+/// and the RTF version of the text. Created once and kept by the `AppResources`, since on
+/// X11 a thread has to serve the contents of the clipboard to the applications that paste them.
+pub(crate) struct RichTextClipboard {
+    inner: platform::Clipboard,
+}
+
+impl RichTextClipboard {
+
+    pub(crate) fn new() -> Result<Self, ClipboardError> {
+        Ok(Self { inner: platform::Clipboard::new()? })
+    }
+
+    /// Replaces the contents of the clipboard with the plain text, the HTML and the RTF version of the `text`
+    pub(crate) fn set_contents(&mut self, text: &RichText) -> Result<(), ClipboardError> {
+        self.inner.set_contents(&text.to_plain_text(), &text.to_html(), &text.to_rtf())
+    }
+}
+
+/// Wraps an HTML fragment in the `CF_HTML` clipboard format of Windows: a header with
+/// the byte offsets of the HTML document and of the fragment, followed by the document
+#[cfg(any(target_os = "windows", test))]
+fn get_cf_html(fragment: &str) -> String {
+
+    const HEADER: &str = "Version:0.9\r\nStartHTML:0000000000\r\nEndHTML:0000000000\r\n\
+                          StartFragment:0000000000\r\nEndFragment:0000000000\r\n";
+    const DOCUMENT_START: &str = "<html><body>\r\n<!--StartFragment-->";
+    const DOCUMENT_END: &str = "<!--EndFragment-->\r\n</body></html>";
+
+    let start_html = HEADER.len();
+    let start_fragment = start_html + DOCUMENT_START.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + DOCUMENT_END.len();
+
+    format!(
+        "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n{}{}{}",
+        start_html, end_html, start_fragment, end_fragment, DOCUMENT_START, fragment, DOCUMENT_END
+    )
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+
+    use std::{io::Error as IoError, mem, ptr, thread, time::Duration};
+    use winapi::um::{winbase, winuser};
+    use clipboard2::ClipboardError;
+    use super::get_cf_html;
+
+    /// Other applications may have the clipboard open, so opening it is retried a few times
+    const OPEN_CLIPBOARD_ATTEMPTS: usize = 10;
+
+    /// Windows keeps a copy of the clipboard contents, so nothing has to be kept alive between copies
+    pub(super) struct Clipboard;
+
+    fn last_error() -> ClipboardError {
+        ClipboardError::IoError(IoError::last_os_error())
+    }
+
+    fn register_format(name: &str) -> u32 {
+        let wide_name = name.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+        unsafe { winuser::RegisterClipboardFormatW(wide_name.as_ptr()) }
+    }
+
+    /// Copies the `data` into a global memory object, which is owned by the clipboard afterwards
+    unsafe fn set_clipboard_data<T: Copy>(format: u32, data: &[T]) -> Result<(), ClipboardError> {
+
+        let size = data.len() * mem::size_of::<T>();
+        let memory = winbase::GlobalAlloc(winbase::GMEM_MOVEABLE, size);
+        if memory.is_null() {
+            return Err(last_error());
+        }
+
+        let target = winbase::GlobalLock(memory);
+        if target.is_null() {
+            let error = last_error();
+            winbase::GlobalFree(memory);
+            return Err(error);
+        }
+        ptr::copy_nonoverlapping(data.as_ptr() as *const u8, target as *mut u8, size);
+        winbase::GlobalUnlock(memory);
+
+        if winuser::SetClipboardData(format, memory).is_null() {
+            let error = last_error();
+            winbase::GlobalFree(memory);
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    impl Clipboard {
+
+        pub(super) fn new() -> Result<Self, ClipboardError> {
+            Ok(Clipboard)
+        }
+
+        pub(super) fn set_contents(&mut self, plain_text: &str, html: &str, rtf: &str) -> Result<(), ClipboardError> {
+
+            // All formats are null-terminated, the HTML and the RTF are 8-bit text
+            let wide_text = plain_text.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+            let cf_html = get_cf_html(html).into_bytes().into_iter().chain(Some(0)).collect::<Vec<u8>>();
+            let rtf = rtf.bytes().chain(Some(0)).collect::<Vec<u8>>();
+            let html_format = register_format("HTML Format");
+            let rtf_format = register_format("Rich Text Format");
+
+            unsafe {
+
+                let mut is_open = false;
+                for _ in 0..OPEN_CLIPBOARD_ATTEMPTS {
+                    if winuser::OpenClipboard(ptr::null_mut()) != 0 {
+                        is_open = true;
+                        break;
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+                if !is_open {
+                    return Err(last_error());
+                }
+
+                let result = if winuser::EmptyClipboard() == 0 {
+                    Err(last_error())
+                } else {
+                    set_clipboard_data(winuser::CF_UNICODETEXT, &wide_text[..])
+                        .and_then(|_| set_clipboard_data(html_format, &cf_html[..]))
+                        .and_then(|_| set_clipboard_data(rtf_format, &rtf[..]))
+                };
+
+                winuser::CloseClipboard();
+                result
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+
+    use std::{io::{Error as IoError, ErrorKind}, os::raw::c_void};
+    use objc::runtime::{Object, BOOL, NO};
+    use clipboard2::ClipboardError;
+
+    /// `NSUTF8StringEncoding`
+    const UTF8_STRING_ENCODING: usize = 4;
+
+    /// The pasteboard server keeps the contents, so nothing has to be kept alive between copies
+    pub(super) struct Clipboard;
+
+    /// Returns a retained `NSString`, which has to be released by the caller
+    unsafe fn ns_string(text: &str) -> *mut Object {
+        let string: *mut Object = msg_send![class!(NSString), alloc];
+        msg_send![string, initWithBytes: text.as_ptr() as *const c_void length: text.len() encoding: UTF8_STRING_ENCODING]
+    }
+
+    /// Returns a retained `NSData`, which has to be released by the caller
+    unsafe fn ns_data(bytes: &[u8]) -> *mut Object {
+        let data: *mut Object = msg_send![class!(NSData), alloc];
+        msg_send![data, initWithBytes: bytes.as_ptr() as *const c_void length: bytes.len()]
+    }
+
+    impl Clipboard {
+
+        pub(super) fn new() -> Result<Self, ClipboardError> {
+            Ok(Clipboard)
+        }
+
+        pub(super) fn set_contents(&mut self, plain_text: &str, html: &str, rtf: &str) -> Result<(), ClipboardError> {
+            unsafe {
+                // [NSPasteboard generalPasteboard], with one item that has all three types
+                let pasteboard: *mut Object = msg_send![class!(NSPasteboard), generalPasteboard];
+                let _: isize = msg_send![pasteboard, clearContents];
+
+                let contents = [
+                    (ns_string("public.utf8-plain-text"), ns_string(plain_text)),
+                    (ns_string("public.html"), ns_data(html.as_bytes())),
+                    (ns_string("public.rtf"), ns_data(rtf.as_bytes())),
+                ];
+
+                let mut is_set = true;
+                for (index, &(pasteboard_type, value)) in contents.iter().enumerate() {
+                    let was_set: BOOL = if index == 0 {
+                        msg_send![pasteboard, setString: value forType: pasteboard_type]
+                    } else {
+                        msg_send![pasteboard, setData: value forType: pasteboard_type]
+                    };
+                    is_set &= was_set != NO;
+                    let () = msg_send![value, release];
+                    let () = msg_send![pasteboard_type, release];
+                }
+
+                if is_set {
+                    Ok(())
+                } else {
+                    Err(ClipboardError::IoError(IoError::new(ErrorKind::Other, "Could not write to the pasteboard")))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+
+    use std::{
+        ffi::CString,
+        io::{Error as IoError, ErrorKind},
+        mem,
+        os::raw::{c_int, c_long, c_uchar},
+        ptr,
+        sync::{Arc, Mutex, mpsc::{self, Receiver, Sender}},
+        thread::{self, JoinHandle},
+    };
+    use x11_dl::xlib::{self, Xlib, Display, Window, Atom};
+    use clipboard2::ClipboardError;
+
+    /// Messages to the thread that owns the clipboard, sent as `ClientMessage` events
+    const TAKE_OWNERSHIP: c_long = 0;
+    const QUIT: c_long = 1;
+    const MESSAGE_TYPE: &str = "_AZUL_RICH_TEXT_CLIPBOARD";
+
+    /// Target atoms and the contents that are sent when another application requests them
+    type Contents = Vec<(Atom, Vec<u8>)>;
+    type Reply = Result<Window, &'static str>;
+
+    /// The contents of the X11 clipboard aren't stored by the X server: the application that owns
+    /// the `CLIPBOARD` selection sends them to every application that pastes them. So one thread with
+    /// an invisible window serves the contents of the last copy, until the `Clipboard` is dropped.
+    pub(super) struct Clipboard {
+        xlib: Xlib,
+        /// Connection for sending messages to the thread, which runs its event loop on its own connection
+        display: *mut Display,
+        /// Invisible window of the thread, which owns the clipboard
+        window: Window,
+        message_type: Atom,
+        /// `UTF8_STRING`, `text/plain;charset=utf-8`, `text/html` and `text/rtf`
+        formats: [Atom; 4],
+        contents: Arc<Mutex<Contents>>,
+        replies: Receiver<Reply>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    fn clipboard_error(message: &str) -> ClipboardError {
+        ClipboardError::IoError(IoError::new(ErrorKind::Other, message))
+    }
+
+    fn receive_reply(replies: &Receiver<Reply>) -> Result<Window, ClipboardError> {
+        replies.recv()
+            .unwrap_or(Err("The clipboard thread exited unexpectedly"))
+            .map_err(clipboard_error)
+    }
+
+    unsafe fn intern_atom(xlib: &Xlib, display: *mut Display, name: &str) -> Atom {
+        let name = CString::new(name).unwrap();
+        (xlib.XInternAtom)(display, name.as_ptr(), xlib::False)
+    }
+
+    impl Clipboard {
+
+        pub(super) fn new() -> Result<Self, ClipboardError> {
+
+            let xlib = Xlib::open().map_err(|error| clipboard_error(&error.to_string()))?;
+            let display = unsafe { (xlib.XOpenDisplay)(ptr::null()) };
+            if display.is_null() {
+                return Err(clipboard_error("Could not connect to the X server"));
+            }
+
+            let contents = Arc::new(Mutex::new(Contents::new()));
+            let (sender, replies) = mpsc::channel();
+            let thread_contents = contents.clone();
+            let thread = thread::spawn(move || unsafe { serve_clipboard(&thread_contents, &sender) });
+
+            // The thread replies with the ID of its window once it can receive messages
+            let window = match receive_reply(&replies) {
+                Ok(window) => window,
+                Err(error) => {
+                    unsafe { (xlib.XCloseDisplay)(display) };
+                    return Err(error);
+                },
+            };
+
+            let (message_type, formats) = unsafe {(
+                intern_atom(&xlib, display, MESSAGE_TYPE),
+                [
+                    intern_atom(&xlib, display, "UTF8_STRING"),
+                    intern_atom(&xlib, display, "text/plain;charset=utf-8"),
+                    intern_atom(&xlib, display, "text/html"),
+                    intern_atom(&xlib, display, "text/rtf"),
+                ],
+            )};
+
+            Ok(Self { xlib, display, window, message_type, formats, contents, replies, thread: Some(thread) })
+        }
+
+        pub(super) fn set_contents(&mut self, plain_text: &str, html: &str, rtf: &str) -> Result<(), ClipboardError> {
+
+            *self.contents.lock().unwrap() = vec![
+                (self.formats[0], plain_text.as_bytes().to_vec()),
+                (self.formats[1], plain_text.as_bytes().to_vec()),
+                (self.formats[2], html.as_bytes().to_vec()),
+                (self.formats[3], rtf.as_bytes().to_vec()),
+            ];
+
+            self.send_message(TAKE_OWNERSHIP);
+            receive_reply(&self.replies).map(|_| ())
+        }
+
+        fn send_message(&self, message: c_long) {
+
+            let mut data = xlib::ClientMessageData::new();
+            data.set_long(0, message);
+
+            let mut event = xlib::XEvent::from(xlib::XClientMessageEvent {
+                type_: xlib::ClientMessage,
+                serial: 0,
+                send_event: xlib::True,
+                display: self.display,
+                window: self.window,
+                message_type: self.message_type,
+                format: 32,
+                data,
+            });
+
+            unsafe {
+                (self.xlib.XSendEvent)(self.display, self.window, xlib::False, xlib::NoEventMask, &mut event);
+                (self.xlib.XFlush)(self.display);
+            }
+        }
+    }
+
+    impl Drop for Clipboard {
+        fn drop(&mut self) {
+            self.send_message(QUIT);
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+            unsafe { (self.xlib.XCloseDisplay)(self.display) };
+        }
+    }
+
+    /// Event loop of the thread that owns the clipboard: takes the ownership of the clipboard
+    /// whenever the `Clipboard` sends new contents and answers the requests for the contents.
+    unsafe fn serve_clipboard(contents: &Mutex<Contents>, replies: &Sender<Reply>) {
+
+        let xlib = match Xlib::open() {
+            Ok(xlib) => xlib,
+            Err(_) => { let _ = replies.send(Err("Could not load Xlib")); return; },
+        };
+
+        let display = (xlib.XOpenDisplay)(ptr::null());
+        if display.is_null() {
+            let _ = replies.send(Err("Could not connect to the X server"));
+            return;
+        }
+
+        let window = (xlib.XCreateSimpleWindow)(display, (xlib.XDefaultRootWindow)(display), 0, 0, 1, 1, 0, 0, 0);
+        let clipboard = intern_atom(&xlib, display, "CLIPBOARD");
+        let targets = intern_atom(&xlib, display, "TARGETS");
+        let message_type = intern_atom(&xlib, display, MESSAGE_TYPE);
+        let _ = replies.send(Ok(window));
+
+        let mut event: xlib::XEvent = mem::zeroed();
+
+        loop {
+            (xlib.XNextEvent)(display, &mut event);
+
+            match event.get_type() {
+                xlib::ClientMessage => {
+                    let message = xlib::XClientMessageEvent::from(event);
+                    if message.message_type != message_type {
+                        continue;
+                    }
+                    if message.data.get_long(0) == QUIT {
+                        break;
+                    }
+                    (xlib.XSetSelectionOwner)(display, clipboard, window, xlib::CurrentTime);
+                    let _ = replies.send(if (xlib.XGetSelectionOwner)(display, clipboard) == window {
+                        Ok(window)
+                    } else {
+                        Err("Could not take the ownership of the clipboard")
+                    });
+                },
+                xlib::SelectionRequest => {
+                    let request = xlib::XSelectionRequestEvent::from(event);
+                    if request.owner == window && request.selection == clipboard {
+                        answer_request(&xlib, display, &request, targets, &contents.lock().unwrap());
+                    }
+                },
+                _ => { },
+            }
+        }
+
+        (xlib.XDestroyWindow)(display, window);
+        (xlib.XCloseDisplay)(display);
+    }
+
+    /// Sends the requested format of the contents to the requesting window. The contents
+    /// are sent in one piece, the `INCR` protocol for very large contents isn't supported.
+    unsafe fn answer_request(xlib: &Xlib, display: *mut Display, request: &xlib::XSelectionRequestEvent, targets: Atom, contents: &Contents) {
+
+        // Obsolete clients don't set a property, the target is used as the property instead
+        let property = if request.property == 0 { request.target } else { request.property };
+
+        let is_answered = if request.target == targets {
+            let atoms = Some(targets).into_iter().chain(contents.iter().map(|(atom, _)| *atom)).collect::<Vec<Atom>>();
+            (xlib.XChangeProperty)(display, request.requestor, property, xlib::XA_ATOM, 32, xlib::PropModeReplace, atoms[..].as_ptr() as *const c_uchar, atoms.len() as c_int);
+            true
+        } else if let Some((_, data)) = contents.iter().find(|(atom, _)| *atom == request.target) {
+            (xlib.XChangeProperty)(display, request.requestor, property, request.target, 8, xlib::PropModeReplace, data[..].as_ptr(), data.len() as c_int);
+            true
+        } else {
+            false
+        };
+
+        let mut notify = xlib::XEvent::from(xlib::XSelectionEvent {
+            type_: xlib::SelectionNotify,
+            serial: 0,
+            send_event: xlib::True,
+            display,
+            requestor: request.requestor,
+            selection: request.selection,
+            target: request.target,
+            property: if is_answered { property } else { 0 },
+            time: request.time,
+        });
+        (xlib.XSendEvent)(display, request.requestor, xlib::False, xlib::NoEventMask, &mut notify);
+        (xlib.XFlush)(display);
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("<br>"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_rtf(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => { escaped.push('\\'); escaped.push(c); },
+            '\n' => escaped.push_str("\\par "),
+            '\r' => { },
+            c if c.is_ascii() => escaped.push(c),
+            // Non-ASCII characters are written as signed 16-bit UTF-16 code units,
+            // followed by "?" as the replacement for readers without unicode support
+            c => {
+                let mut buf = [0; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    let _ = write!(escaped, "\\u{}?", *unit as i16);
+                }
+            },
+        }
+    }
+    escaped
+}
+
+#[test]
+fn test_rich_text_to_html() {
+    let text = RichText::new()
+        .with_span(TextSpan::new("Buy "))
+        .with_span(TextSpan::new("milk & eggs").bold().strikethrough())
+        .with_span(TextSpan::new("!").with_color(ColorU { r: 255, g: 0, b: 0, a: 255 }));
+
+    assert_eq!(text.to_plain_text(), "Buy milk & eggs!");
+    assert_eq!(text.to_html(),
+        "Buy <span style=\"font-weight:bold;text-decoration:line-through;\">milk &amp; eggs</span>\
+         <span style=\"color:#ff0000;\">!</span>"
+    );
+}
+
+#[test]
+fn test_rich_text_to_rtf() {
+    let text = RichText::new()
+        .with_span(TextSpan::new("{a}\n").italic().with_font_size(16.0))
+        .with_span(TextSpan::new("ü").with_color(ColorU { r: 0, g: 0, b: 255, a: 255 }));
+
+    assert_eq!(text.to_rtf(),
+        "{\\rtf1\\ansi\\deff0{\\colortbl;\\red0\\green0\\blue255;}\
+         {\\i\\fs24 \\{a\\}\\par }{\\cf1 \\u252?}}"
+    );
+}
+
+#[test]
+fn test_cf_html_offsets() {
+    let cf_html = get_cf_html("<b>ü</b>");
+    let get_offset = |key: &str| -> usize {
+        let start = cf_html.find(key).unwrap() + key.len();
+        cf_html[start..start + 10].parse().unwrap()
+    };
+
+    assert_eq!(&cf_html[get_offset("StartFragment:")..get_offset("EndFragment:")], "<b>ü</b>");
+    assert!(cf_html[get_offset("StartHTML:")..].starts_with("<html>"));
+    assert_eq!(get_offset("EndHTML:"), cf_html.len());
+}
@@ -0,0 +1,174 @@
+//! Navigation stack for applications with multiple screens, see `Router`
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use glium::glutin::VirtualKeyCode;
+use {
+    dom::Dom,
+    animation::PresenceAnimation,
+    window_state::KeyboardState,
+    callbacks::{UpdateScreen, Redraw, DontRedraw},
+};
+
+static ROUTER_ENTRY_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Manages a stack of screens ("routes"), i.e. a list view that opens a detail view.
+/// The `Router` is stored in the application data and renders the topmost route:
+///
+/// ```rust,ignore
+/// enum Route { Inbox, Message(usize) }
+///
+/// struct MyApp { router: Router<Route>, messages: Vec<String> }
+///
+/// impl Layout for MyApp {
+///     fn layout(&self, _: LayoutInfo<Self>) -> Dom<Self> {
+///         self.router.dom(|route| match route {
+///             Route::Inbox => self.inbox(),
+///             Route::Message(id) => Dom::label(self.messages[*id].clone()),
+///         })
+///     }
+/// }
+/// ```
+///
+/// Every entry of the stack is rendered with its own ID, so that pushing or popping
+/// a route plays the enter / exit animations of the router (see `Router::with_transition`).
+/// In order for the entering and exiting screens to overlap during the transition, the
+/// `.__azul-router-screen` nodes should be positioned absolutely (the native style does this).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Router<R> {
+    /// Never empty, the last entry is the current route
+    stack: Vec<RouterEntry<R>>,
+    enter_animation: Option<PresenceAnimation>,
+    exit_animation: Option<PresenceAnimation>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RouterEntry<R> {
+    id: usize,
+    route: R,
+}
+
+impl<R> RouterEntry<R> {
+    fn new(route: R) -> Self {
+        Self { id: ROUTER_ENTRY_ID.fetch_add(1, Ordering::SeqCst), route }
+    }
+}
+
+impl<R> Router<R> {
+
+    /// Creates a new router, showing the `initial` route
+    pub fn new(initial: R) -> Self {
+        Self {
+            stack: vec![RouterEntry::new(initial)],
+            enter_animation: None,
+            exit_animation: None,
+        }
+    }
+
+    /// Plays the `enter` animation when a screen is shown and the `exit` animation when
+    /// a screen is hidden, both when pushing and popping routes
+    #[inline]
+    pub fn with_transition(mut self, enter: PresenceAnimation, exit: PresenceAnimation) -> Self {
+        self.enter_animation = Some(enter);
+        self.exit_animation = Some(exit);
+        self
+    }
+
+    /// Returns the currently visible route
+    pub fn current(&self) -> &R {
+        &self.stack.last().unwrap().route
+    }
+
+    /// Returns all routes, from the first to the currently visible route
+    pub fn routes(&self) -> Vec<&R> {
+        self.stack.iter().map(|entry| &entry.route).collect()
+    }
+
+    /// Returns whether there is a route to go back to
+    #[inline]
+    pub fn can_go_back(&self) -> bool {
+        self.stack.len() > 1
+    }
+
+    /// Shows a new route on top of the current route
+    pub fn push(&mut self, route: R) {
+        self.stack.push(RouterEntry::new(route));
+    }
+
+    /// Goes back to the previous route, returns the removed route.
+    /// Returns `None` (and does nothing) if the current route is the first route.
+    pub fn pop(&mut self) -> Option<R> {
+        if self.can_go_back() {
+            self.stack.pop().map(|entry| entry.route)
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the current route, returns the replaced route
+    pub fn replace(&mut self, route: R) -> R {
+        let old = self.stack.pop().unwrap();
+        self.stack.push(RouterEntry::new(route));
+        old.route
+    }
+
+    /// Goes back to the first route, returns the removed routes
+    pub fn pop_to_root(&mut self) -> Vec<R> {
+        self.stack.drain(1..).map(|entry| entry.route).collect()
+    }
+
+    /// Renders the current route using the `layout` function
+    pub fn dom<T, F: FnOnce(&R) -> Dom<T>>(&self, layout: F) -> Dom<T> {
+
+        let current = self.stack.last().unwrap();
+
+        let mut screen = Dom::div()
+            .with_id(format!("__azul-router-screen-{}", current.id))
+            .with_class("__azul-router-screen")
+            .with_child(layout(&current.route));
+
+        if let Some(enter) = self.enter_animation {
+            screen.set_enter_animation(enter);
+        }
+        if let Some(exit) = self.exit_animation {
+            screen.set_exit_animation(exit);
+        }
+
+        Dom::div().with_class("__azul-router").with_child(screen)
+    }
+
+    /// Goes back to the previous route if a "back" shortcut is pressed (the "back" key of
+    /// the keyboard, `Alt + Left` or `Cmd + [` on macOS). Call this from a callback for
+    /// `On::VirtualKeyDown`, returns `Redraw` if the router went back.
+    pub fn handle_back_shortcut(&mut self, keyboard_state: &KeyboardState) -> UpdateScreen {
+
+        let is_back_shortcut = match keyboard_state.latest_virtual_keycode {
+            Some(VirtualKeyCode::NavigateBackward) | Some(VirtualKeyCode::WebBack) => true,
+            Some(VirtualKeyCode::Left) => keyboard_state.alt_down,
+            Some(VirtualKeyCode::LBracket) => cfg!(target_os = "macos") && keyboard_state.super_down,
+            _ => false,
+        };
+
+        if is_back_shortcut && self.pop().is_some() {
+            Redraw
+        } else {
+            DontRedraw
+        }
+    }
+}
+
+#[test]
+fn test_router_stack() {
+
+    let mut router = Router::new("inbox");
+    assert_eq!(router.pop(), None);
+
+    router.push("message");
+    router.push("reply");
+    assert_eq!(router.replace("forward"), "reply");
+    assert_eq!(router.routes(), vec![&"inbox", &"message", &"forward"]);
+
+    assert_eq!(router.pop(), Some("forward"));
+    assert_eq!(*router.current(), "message");
+    assert_eq!(router.pop_to_root(), vec!["message"]);
+    assert!(!router.can_go_back());
+}
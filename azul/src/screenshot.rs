@@ -0,0 +1,372 @@
+//! Renders DOMs into images without showing a window and compares the images, so that
+//! visual regressions of custom widgets can be caught in a CI.
+//!
+//! ```rust,ignore
+//! let before = screenshot::render(MyDataModel::default(), css::native(), LogicalSize::new(400.0, 300.0))?;
+//! let after = screenshot::render(MyDataModel::default(), my_new_css(), LogicalSize::new(400.0, 300.0))?;
+//!
+//! let diff = screenshot::diff(&before, &after, screenshot::DEFAULT_THRESHOLD);
+//! if !diff.is_identical() {
+//!     diff.diff_image.save_png("diff.png")?;
+//!     panic!("{}", diff);
+//! }
+//! ```
+//!
+//! Note that the screenshots are rendered at the HiDPI factor of the monitor and with the
+//! fonts of the machine that the test runs on, so reference images should be rendered on
+//! the same machine (or the same CI image).
+
+use std::fmt;
+use window::WindowCreateError;
+#[cfg(not(test))]
+use {
+    app::{App, AppConfig},
+    traits::Layout,
+    window::WindowCreateOptions,
+};
+#[cfg(not(test))]
+use azul_css::Css;
+#[cfg(not(test))]
+use glium::glutin::dpi::LogicalSize;
+
+/// Threshold for `diff()` that ignores slight differences in anti-aliasing
+pub const DEFAULT_THRESHOLD: f32 = 0.1;
+
+/// Frames that are run before the screenshot is taken, so that the fonts and
+/// images are loaded and the layout has settled
+#[cfg(not(test))]
+const WARMUP_FRAMES: usize = 3;
+
+/// Side length (in pixels) of the tiles that changed pixels are grouped by - changes in the
+/// same or in neighbouring tiles are reported as one `ChangedRect`
+const CHANGED_RECT_TILE_SIZE: usize = 16;
+
+/// Maximum of `color_delta()` (the delta between black and white)
+const MAX_COLOR_DELTA: f32 = 35215.0;
+
+/// Rendered frame of a window, as RGBA8 pixels (top row first)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Screenshot {
+    pub width: u32,
+    pub height: u32,
+    /// `width * height * 4` bytes
+    pub pixels: Vec<u8>,
+}
+
+impl Screenshot {
+
+    /// Creates a screenshot from pixels as OpenGL reads them, i.e. with the bottom row first
+    pub(crate) fn from_gl_pixels(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        let row_len = width as usize * 4;
+        let pixels = if row_len == 0 {
+            pixels
+        } else {
+            pixels.chunks(row_len).rev().flatten().cloned().collect()
+        };
+        Self { width, height, pixels }
+    }
+
+    /// Returns the RGBA color of the pixel or `None` if the pixel is outside of the screenshot
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<[u8;4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let start = (y as usize * self.width as usize + x as usize) * 4;
+        let pixel = self.pixels.get(start..start + 4)?;
+        Some([pixel[0], pixel[1], pixel[2], pixel[3]])
+    }
+
+    /// Saves the screenshot as a PNG file
+    #[cfg(feature = "image_loading")]
+    pub fn save_png<P: AsRef<::std::path::Path>>(&self, path: P) -> Result<(), ::std::io::Error> {
+        use image; // the crate
+        image::save_buffer(path, &self.pixels, self.width, self.height, image::ColorType::RGBA(8))
+    }
+}
+
+/// Error that occurred while rendering a screenshot
+#[derive(Debug)]
+pub enum ScreenshotError {
+    /// The app or the hidden window could not be created
+    WindowCreate(WindowCreateError),
+    /// Running the app failed (contains the message of the `RuntimeError`)
+    Render(String),
+    /// The window didn't render a frame (i.e. because the OpenGL context was lost)
+    NoFrame,
+}
+
+impl_from!(WindowCreateError, ScreenshotError::WindowCreate);
+
+impl fmt::Display for ScreenshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::ScreenshotError::*;
+        match self {
+            WindowCreate(e) => write!(f, "Could not create window for the screenshot: {}", e),
+            Render(e) => write!(f, "Could not render the screenshot: {}", e),
+            NoFrame => write!(f, "The window did not render a frame"),
+        }
+    }
+}
+
+/// Renders the DOM of `data` with the given stylesheet in a hidden window of the given size
+/// and returns the rendered frame. Has to be called on the main thread, like `App::new()`.
+#[cfg(not(test))]
+pub fn render<T: Layout>(data: T, css: Css, size: LogicalSize) -> Result<Screenshot, ScreenshotError> {
+
+    let config = AppConfig {
+        #[cfg(feature = "logging")]
+        enable_logging: None,
+        .. AppConfig::default()
+    };
+
+    let mut app = App::new(data, config)?;
+
+    let mut options = WindowCreateOptions::default();
+    options.state.size.dimensions = size;
+    options.state.is_visible = false;
+    let window = app.create_window(options, css)?;
+
+    let mut app = app.run_manual(window).map_err(|e| ScreenshotError::Render(e.to_string()))?;
+    for _ in 0..WARMUP_FRAMES {
+        app.tick(Vec::new()).map_err(|e| ScreenshotError::Render(e.to_string()))?;
+    }
+
+    app.take_screenshot().ok_or(ScreenshotError::NoFrame)
+}
+
+/// Area of a `ScreenshotDiff` that contains changed pixels (in pixels, from the top left)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChangedRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Result of comparing two screenshots via `diff()`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScreenshotDiff {
+    /// The first screenshot in faded grayscale, with the changed pixels drawn in red
+    pub diff_image: Screenshot,
+    /// Number of pixels that differ by more than the threshold
+    pub changed_pixels: usize,
+    /// Bounds of the changed areas, sorted from top to bottom
+    pub changed_rects: Vec<ChangedRect>,
+}
+
+impl ScreenshotDiff {
+    /// Returns whether no pixel differs by more than the threshold
+    pub fn is_identical(&self) -> bool {
+        self.changed_pixels == 0
+    }
+}
+
+impl fmt::Display for ScreenshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} pixels changed in {} areas", self.changed_pixels, self.changed_rects.len())?;
+        for rect in &self.changed_rects {
+            write!(f, "\n    {}x{} at ({}, {})", rect.width, rect.height, rect.x, rect.y)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the screenshots pixel by pixel, using the perceived difference of the colors
+/// (in the YIQ color space), so that changes in brightness weigh more than changes in hue.
+///
+/// `threshold` ranges from `0.0` (every change is reported) to `1.0` (only black vs. white is
+/// reported), `DEFAULT_THRESHOLD` ignores most differences in anti-aliasing. If the
+/// screenshots have different sizes, the pixels outside of the smaller one count as changed.
+pub fn diff(before: &Screenshot, after: &Screenshot, threshold: f32) -> ScreenshotDiff {
+
+    let width = before.width.max(after.width);
+    let height = before.height.max(after.height);
+    let max_delta = MAX_COLOR_DELTA * threshold * threshold;
+
+    let mut diff_pixels = Vec::with_capacity(width as usize * height as usize * 4);
+    let mut changed = vec![false; width as usize * height as usize];
+
+    for y in 0..height {
+        for x in 0..width {
+            let is_changed = match (before.get_pixel(x, y), after.get_pixel(x, y)) {
+                (Some(a), Some(b)) => a != b && color_delta(a, b) > max_delta,
+                _ => true,
+            };
+
+            if is_changed {
+                changed[(y * width + x) as usize] = true;
+                diff_pixels.extend_from_slice(&[255, 0, 0, 255]);
+            } else {
+                // Fade the unchanged pixels, so that the red ones stand out
+                let gray = before.get_pixel(x, y).map(|p| 255.0 - (255.0 - yiq(p).0) * 0.1).unwrap_or(255.0).round() as u8;
+                diff_pixels.extend_from_slice(&[gray, gray, gray, 255]);
+            }
+        }
+    }
+
+    ScreenshotDiff {
+        diff_image: Screenshot { width, height, pixels: diff_pixels },
+        changed_pixels: changed.iter().filter(|c| **c).count(),
+        changed_rects: get_changed_rects(&changed, width as usize, height as usize),
+    }
+}
+
+/// Converts the color (blended onto white) to the YIQ color space
+fn yiq(pixel: [u8;4]) -> (f32, f32, f32) {
+    let blend = |c: u8| 255.0 + (c as f32 - 255.0) * (pixel[3] as f32 / 255.0);
+    let (r, g, b) = (blend(pixel[0]), blend(pixel[1]), blend(pixel[2]));
+    (
+        r * 0.298_895 + g * 0.586_622 + b * 0.114_482,
+        r * 0.595_978 - g * 0.274_176 - b * 0.321_802,
+        r * 0.211_470 - g * 0.522_617 + b * 0.311_147,
+    )
+}
+
+/// Perceived difference between two colors, between `0.0` and `MAX_COLOR_DELTA`
+/// (see "Measuring perceived color difference using YIQ NTSC transmission color space")
+fn color_delta(a: [u8;4], b: [u8;4]) -> f32 {
+    let (y1, i1, q1) = yiq(a);
+    let (y2, i2, q2) = yiq(b);
+    0.5053 * (y1 - y2).powi(2) + 0.299 * (i1 - i2).powi(2) + 0.1957 * (q1 - q2).powi(2)
+}
+
+/// Groups the changed pixels by tiles and returns the bounds of the changed pixels
+/// of every group of touching (or diagonally touching) tiles
+fn get_changed_rects(changed: &[bool], width: usize, height: usize) -> Vec<ChangedRect> {
+
+    let tiles_x = (width + CHANGED_RECT_TILE_SIZE - 1) / CHANGED_RECT_TILE_SIZE;
+    let tiles_y = (height + CHANGED_RECT_TILE_SIZE - 1) / CHANGED_RECT_TILE_SIZE;
+
+    // Bounds of the changed pixels in each tile: (min_x, min_y, max_x, max_y)
+    let mut tile_bounds: Vec<Option<(usize, usize, usize, usize)>> = vec![None; tiles_x * tiles_y];
+
+    for y in 0..height {
+        for x in 0..width {
+            if !changed[y * width + x] {
+                continue;
+            }
+            let tile = &mut tile_bounds[(y / CHANGED_RECT_TILE_SIZE) * tiles_x + x / CHANGED_RECT_TILE_SIZE];
+            *tile = Some(match *tile {
+                Some((min_x, min_y, max_x, max_y)) => (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)),
+                None => (x, y, x, y),
+            });
+        }
+    }
+
+    let mut visited = vec![false; tiles_x * tiles_y];
+    let mut changed_rects = Vec::new();
+
+    for start in 0..tile_bounds.len() {
+
+        if visited[start] || tile_bounds[start].is_none() {
+            continue;
+        }
+
+        // Flood-fill the group of changed tiles and merge their bounds
+        let mut bounds = tile_bounds[start].unwrap();
+        let mut stack = vec![start];
+        visited[start] = true;
+
+        while let Some(tile) = stack.pop() {
+
+            let (min_x, min_y, max_x, max_y) = tile_bounds[tile].unwrap();
+            bounds = (bounds.0.min(min_x), bounds.1.min(min_y), bounds.2.max(max_x), bounds.3.max(max_y));
+
+            let (tile_x, tile_y) = (tile % tiles_x, tile / tiles_x);
+            for neighbour_y in tile_y.saturating_sub(1)..(tile_y + 2).min(tiles_y) {
+                for neighbour_x in tile_x.saturating_sub(1)..(tile_x + 2).min(tiles_x) {
+                    let neighbour = neighbour_y * tiles_x + neighbour_x;
+                    if !visited[neighbour] && tile_bounds[neighbour].is_some() {
+                        visited[neighbour] = true;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+
+        let (min_x, min_y, max_x, max_y) = bounds;
+        changed_rects.push(ChangedRect {
+            x: min_x as u32,
+            y: min_y as u32,
+            width: (max_x - min_x + 1) as u32,
+            height: (max_y - min_y + 1) as u32,
+        });
+    }
+
+    changed_rects.sort_by_key(|rect| (rect.y, rect.x));
+    changed_rects
+}
+
+#[cfg(test)]
+fn solid_screenshot(width: u32, height: u32, color: [u8;4]) -> Screenshot {
+    Screenshot {
+        width,
+        height,
+        pixels: (0..width * height).flat_map(|_| color.to_vec()).collect(),
+    }
+}
+
+#[cfg(test)]
+fn set_pixel(screenshot: &mut Screenshot, x: u32, y: u32, color: [u8;4]) {
+    let start = ((y * screenshot.width + x) * 4) as usize;
+    screenshot.pixels[start..start + 4].copy_from_slice(&color);
+}
+
+#[test]
+fn test_diff_identical_screenshots() {
+    let a = solid_screenshot(40, 30, [255, 255, 255, 255]);
+    let diff = diff(&a, &a.clone(), DEFAULT_THRESHOLD);
+    assert!(diff.is_identical());
+    assert_eq!(diff.changed_rects, Vec::new());
+    assert_eq!((diff.diff_image.width, diff.diff_image.height), (40, 30));
+}
+
+#[test]
+fn test_diff_ignores_small_color_changes() {
+    let a = solid_screenshot(10, 10, [255, 255, 255, 255]);
+    let mut b = a.clone();
+    set_pixel(&mut b, 5, 5, [250, 250, 250, 255]);
+
+    assert!(diff(&a, &b, DEFAULT_THRESHOLD).is_identical());
+    assert_eq!(diff(&a, &b, 0.0).changed_pixels, 1);
+}
+
+#[test]
+fn test_diff_changed_rects() {
+    let a = solid_screenshot(100, 100, [255, 255, 255, 255]);
+    let mut b = a.clone();
+
+    // Two touching pixels in neighbouring tiles are one area
+    set_pixel(&mut b, 15, 2, [0, 0, 0, 255]);
+    set_pixel(&mut b, 16, 3, [0, 0, 0, 255]);
+    // Far away from the others
+    set_pixel(&mut b, 80, 90, [255, 0, 0, 255]);
+
+    let diff = diff(&a, &b, DEFAULT_THRESHOLD);
+    assert_eq!(diff.changed_pixels, 3);
+    assert_eq!(diff.changed_rects, vec![
+        ChangedRect { x: 15, y: 2, width: 2, height: 2 },
+        ChangedRect { x: 80, y: 90, width: 1, height: 1 },
+    ]);
+    assert_eq!(diff.diff_image.get_pixel(80, 90), Some([255, 0, 0, 255]));
+    assert_eq!(diff.diff_image.get_pixel(0, 0), Some([255, 255, 255, 255]));
+}
+
+#[test]
+fn test_diff_different_sizes() {
+    let a = solid_screenshot(10, 10, [0, 0, 255, 255]);
+    let b = solid_screenshot(12, 10, [0, 0, 255, 255]);
+
+    let diff = diff(&a, &b, DEFAULT_THRESHOLD);
+    assert_eq!(diff.changed_pixels, 20);
+    assert_eq!(diff.changed_rects, vec![ChangedRect { x: 10, y: 0, width: 2, height: 10 }]);
+}
+
+#[test]
+fn test_screenshot_from_gl_pixels() {
+    // Bottom row first
+    let screenshot = Screenshot::from_gl_pixels(1, 2, vec![1, 1, 1, 1, 2, 2, 2, 2]);
+    assert_eq!(screenshot.get_pixel(0, 0), Some([2, 2, 2, 2]));
+    assert_eq!(screenshot.get_pixel(0, 1), Some([1, 1, 1, 1]));
+    assert_eq!(screenshot.get_pixel(1, 0), None);
+}
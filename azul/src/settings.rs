@@ -0,0 +1,183 @@
+//! Persisted application settings, see `Settings`
+
+use std::{
+    fmt,
+    env,
+    fs::{self, File},
+    io::{Error as IoError, Write},
+    path::{Path, PathBuf},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Error as JsonError;
+
+/// Callback that is called with the old and the new settings whenever the settings change
+pub type SettingsChangeCallback<S> = fn(&S, &S);
+
+/// Settings struct that is stored as JSON in the configuration directory of the platform
+/// (see `config_dir`). The settings are saved automatically on every change:
+///
+/// ```rust,ignore
+/// #[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+/// struct MySettings { dark_mode: bool, recent_files: Vec<PathBuf> }
+///
+/// let mut settings = Settings::<MySettings>::load("my_app")?;
+/// settings.modify(|s| s.dark_mode = true)?;
+/// ```
+///
+/// Files are written atomically (the settings are written to a temporary
+/// file, which then replaces the old file), so a crash while saving
+/// never leaves a half-written settings file behind.
+pub struct Settings<S> {
+    value: S,
+    path: PathBuf,
+    change_callbacks: Vec<SettingsChangeCallback<S>>,
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    /// No configuration directory could be found (i.e. `$HOME` isn't set)
+    NoConfigDir,
+    /// Reading or writing the settings file failed
+    Io(IoError, PathBuf),
+    /// The settings file isn't valid JSON or doesn't match the settings struct
+    Json(JsonError, PathBuf),
+}
+
+impl_display!(SettingsError, {
+    NoConfigDir => "Could not determine the configuration directory",
+    Io(e, path) => format!("Could not access settings file \"{}\": {}", path.display(), e),
+    Json(e, path) => format!("Invalid settings file \"{}\": {}", path.display(), e),
+});
+
+impl<S> Settings<S> where S: Serialize + DeserializeOwned + Default + Clone + PartialEq {
+
+    /// Loads the settings from `<config dir>/<app_name>/settings.json`,
+    /// uses the default settings if the file doesn't exist yet
+    pub fn load(app_name: &str) -> Result<Self, SettingsError> {
+        let path = config_dir().ok_or(SettingsError::NoConfigDir)?.join(app_name).join("settings.json");
+        Self::load_from_path(path)
+    }
+
+    /// Same as `load`, but loads the settings from a custom file
+    pub fn load_from_path<P: Into<PathBuf>>(path: P) -> Result<Self, SettingsError> {
+
+        let path = path.into();
+
+        let value = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| SettingsError::Json(e, path.clone()))?,
+            Err(ref e) if e.kind() == ::std::io::ErrorKind::NotFound => S::default(),
+            Err(e) => return Err(SettingsError::Io(e, path)),
+        };
+
+        Ok(Self { value, path, change_callbacks: Vec::new() })
+    }
+
+    #[inline]
+    pub fn get(&self) -> &S {
+        &self.value
+    }
+
+    /// Path of the settings file
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Registers a callback that is called after the settings have changed
+    #[inline]
+    pub fn on_change(&mut self, callback: SettingsChangeCallback<S>) {
+        self.change_callbacks.push(callback);
+    }
+
+    /// Modifies the settings. If the settings changed, they are saved and all
+    /// change callbacks are called. Returns whether the settings changed.
+    pub fn modify<F: FnOnce(&mut S)>(&mut self, modify: F) -> Result<bool, SettingsError> {
+
+        let mut new_value = self.value.clone();
+        modify(&mut new_value);
+
+        if new_value == self.value {
+            return Ok(false);
+        }
+
+        let old_value = ::std::mem::replace(&mut self.value, new_value);
+        self.save()?;
+
+        for callback in &self.change_callbacks {
+            callback(&old_value, &self.value);
+        }
+
+        Ok(true)
+    }
+
+    /// Saves the settings, creating the directory of the settings file if necessary
+    pub fn save(&self) -> Result<(), SettingsError> {
+
+        let io_error = |e| SettingsError::Io(e, self.path.clone());
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+
+        let json = serde_json::to_string_pretty(&self.value).map_err(|e| SettingsError::Json(e, self.path.clone()))?;
+
+        // Write to a temporary file in the same directory, then replace the old file
+        // (renaming is atomic if both files are on the same file system)
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+
+        {
+            let mut file = File::create(&temp_path).map_err(io_error)?;
+            file.write_all(json.as_bytes()).map_err(io_error)?;
+            file.sync_all().map_err(io_error)?;
+        }
+
+        fs::rename(&temp_path, &self.path).map_err(io_error)
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for Settings<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+            "Settings {{ value: {:?}, path: {:?}, change_callbacks: {} }}",
+            self.value, self.path, self.change_callbacks.len(),
+        )
+    }
+}
+
+/// Returns the directory where applications should store their configuration:
+///
+/// - Windows: `%APPDATA%`
+/// - Mac: `~/Library/Application Support`
+/// - Linux: `$XDG_CONFIG_HOME` or `~/.config`
+pub fn config_dir() -> Option<PathBuf> {
+
+    let non_empty_var = |name: &str| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+
+    if cfg!(target_os = "windows") {
+        non_empty_var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        non_empty_var("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        non_empty_var("XDG_CONFIG_HOME").or_else(|| non_empty_var("HOME").map(|home| home.join(".config")))
+    }
+}
+
+#[test]
+fn test_settings_save_and_load() {
+
+    let path = env::temp_dir().join(format!("azul_test_settings_{}", ::std::process::id())).join("settings.json");
+
+    let mut settings = Settings::<Vec<String>>::load_from_path(path.clone()).unwrap();
+    assert!(settings.get().is_empty());
+
+    settings.on_change(|old, new| assert_eq!(old.len() + 1, new.len()));
+    assert!(settings.modify(|s| s.push("recent.txt".into())).unwrap());
+    assert!(!settings.modify(|_| { }).unwrap());
+
+    let loaded = Settings::<Vec<String>>::load_from_path(path.clone()).unwrap();
+    assert_eq!(loaded.get(), &vec!["recent.txt".to_string()]);
+
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
@@ -1,19 +1,80 @@
 //! DOM-tree to CSS style tree stying
 
-use std::{fmt, collections::BTreeMap};
+use std::{
+    fmt,
+    rc::Rc,
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicUsize, Ordering},
+    collections::{BTreeMap, hash_map::DefaultHasher},
+};
 use azul_css::{
-    Css, CssContentGroup, CssPath,
-    CssPathSelector, CssPathPseudoSelector, CssNthChildSelector::*,
+    Css, CssContentGroup, CssPath, CssViewport, CssRuleBlock, CssDeclaration, CssProperty,
+    CssPathSelector, CssPathPseudoSelector, CssPseudoElement, NodeTypePath, get_specificity,
 };
 use webrender::api::HitTestItem;
 use {
-    ui_description::{UiDescription, StyledNode},
+    diff,
+    ui_description::{UiDescription, StyledNode, ScrollbarStyledNode},
     dom::NodeData,
     ui_state::UiState,
-    id_tree::{NodeId, NodeHierarchy, NodeDataContainer},
+    id_tree::{Arena, NodeId, NodeHierarchy, NodeDataContainer},
     callbacks::FocusTarget,
 };
 
+static LAYOUT_GENERATION: AtomicUsize = AtomicUsize::new(1);
+
+/// Returns a `layout_generation` that no other `UiDescription` has, see `UiDescription::layout_generation`
+fn new_layout_generation() -> usize {
+    LAYOUT_GENERATION.fetch_add(1, Ordering::SeqCst)
+}
+
+/// Custom properties that are visible to a node. Nodes that don't declare
+/// any variables themselves share the map of their parent.
+type CssVariables = Rc<BTreeMap<String, String>>;
+
+/// Everything about a single node that decides which selectors match it
+/// (apart from its position in the DOM, which is covered by the `NodeHierarchy`)
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+struct SelectorInputs {
+    /// Hash of the node type, ids, classes, attributes, disabled and checked state and inline styles
+    node_hash: u64,
+    is_hovered_over: bool,
+    is_active: bool,
+    is_focused: bool,
+}
+
+impl SelectorInputs {
+    fn new<'a, T>(html_node: &HtmlCascadeInfo<'a, T>) -> Self {
+        let node_data = html_node.node_data;
+        let mut hasher = DefaultHasher::new();
+        node_data.node_type.get_path().hash(&mut hasher);
+        node_data.ids.hash(&mut hasher);
+        node_data.classes.hash(&mut hasher);
+        node_data.attributes.hash(&mut hasher);
+        node_data.is_disabled.hash(&mut hasher);
+        node_data.is_checked.hash(&mut hasher);
+        node_data.inline_styles.hash(&mut hasher);
+        Self {
+            node_hash: hasher.finish(),
+            is_hovered_over: html_node.is_hovered_over,
+            is_active: html_node.is_active,
+            is_focused: html_node.is_focused,
+        }
+    }
+}
+
+/// Inputs and intermediate results of the last cascade, stored in the `UiDescription`,
+/// so that the next frame only has to restyle the nodes whose selector inputs changed
+#[derive(Default, Clone)]
+pub(crate) struct StyleCache {
+    css: Rc<Css>,
+    viewport: CssViewport,
+    node_hierarchy: NodeHierarchy,
+    selector_inputs: NodeDataContainer<SelectorInputs>,
+    css_variables: NodeDataContainer<CssVariables>,
+    builtin_css_variables: CssVariables,
+}
+
 /// Has all the necessary information about the style CSS path
 pub(crate) struct HtmlCascadeInfo<'a, T: 'a> {
     pub node_data: &'a NodeData<T>,
@@ -22,6 +83,9 @@ pub(crate) struct HtmlCascadeInfo<'a, T: 'a> {
     pub is_hovered_over: bool,
     pub is_focused: bool,
     pub is_active: bool,
+    pub is_disabled: bool,
+    pub is_checked: bool,
+    pub is_root: bool,
 }
 
 impl<'a, T: 'a> fmt::Debug for HtmlCascadeInfo<'a, T> {
@@ -33,6 +97,9 @@ impl<'a, T: 'a> fmt::Debug for HtmlCascadeInfo<'a, T> {
             is_hovered_over: {:?}, \
             is_focused: {:?}, \
             is_active: {:?}, \
+            is_disabled: {:?}, \
+            is_checked: {:?}, \
+            is_root: {:?}, \
          }}",
             self.node_data,
             self.index_in_parent,
@@ -40,6 +107,9 @@ impl<'a, T: 'a> fmt::Debug for HtmlCascadeInfo<'a, T> {
             self.is_hovered_over,
             self.is_focused,
             self.is_active,
+            self.is_disabled,
+            self.is_checked,
+            self.is_root,
          )
     }
 }
@@ -69,7 +139,7 @@ pub(crate) fn matches_html_element<'a, T>(
                 // The node has no parent, but the CSS path
                 // still has an extra limitation - only valid if the
                 // next content group is a "*" element
-                return *content_group == [&CssPathSelector::Global];
+                return last_selector_matched && *content_group == [&CssPathSelector::Global];
             },
         };
         let current_selector_matches = selector_group_matches(&content_group, &html_node_tree[cur_node_id]);
@@ -178,13 +248,16 @@ fn construct_html_cascade_tree<'a, T>(
     is_mouse_down: bool
 ) -> NodeDataContainer<HtmlCascadeInfo<'a, T>> {
 
-    let mut nodes = (0..node_hierarchy.len()).map(|_| HtmlCascadeInfo {
+    let mut nodes = (0..node_hierarchy.len()).map(|node_idx| HtmlCascadeInfo {
         node_data: &input[NodeId::new(0)],
         index_in_parent: 0,
         is_last_child: false,
         is_hovered_over: false,
         is_active: false,
         is_focused: false,
+        is_disabled: false,
+        is_checked: false,
+        is_root: node_idx == 0,
     }).collect::<Vec<_>>();
 
     for (_depth, parent_id) in node_depths_sorted {
@@ -200,6 +273,9 @@ fn construct_html_cascade_tree<'a, T>(
             is_hovered_over: is_parent_hovered_over,
             is_active: is_parent_hovered_over && is_mouse_down,
             is_focused: focused_item == Some(*parent_id),
+            is_disabled: input[*parent_id].is_disabled,
+            is_checked: input[*parent_id].is_checked,
+            is_root: node_hierarchy[*parent_id].parent.is_none(),
         };
 
         nodes[parent_id.index()] = parent_html_matcher;
@@ -213,6 +289,9 @@ fn construct_html_cascade_tree<'a, T>(
                 is_hovered_over: is_child_hovered_over,
                 is_active: is_child_hovered_over && is_mouse_down,
                 is_focused: focused_item == Some(child_id),
+                is_disabled: input[child_id].is_disabled,
+                is_checked: input[child_id].is_checked,
+                is_root: false,
             };
 
             nodes[child_id.index()] = child_html_matcher;
@@ -242,7 +321,7 @@ pub enum ActiveHover {
 
 /// Returns all CSS paths that have a `:hover` or `:active` in their path
 /// (since they need to have tags for hit-testing)
-fn collect_hover_groups(css: &Css) -> BTreeMap<CssPath, HoverGroup> {
+fn collect_hover_groups(css: &Css, viewport: CssViewport) -> BTreeMap<CssPath, HoverGroup> {
     use azul_css::{CssPathSelector::*, CssPathPseudoSelector::*};
 
     let hover_rule = PseudoSelector(Hover);
@@ -250,7 +329,7 @@ fn collect_hover_groups(css: &Css) -> BTreeMap<CssPath, HoverGroup> {
 
     // Filter out all :hover and :active rules, since we need to create tags
     // for them after the main CSS styling has been done
-    css.rules().filter_map(|rule_block| {
+    css.rules_for_viewport(viewport).filter_map(|rule_block| {
         let pos = rule_block.path.selectors.iter().position(|x| *x == hover_rule || *x == active_rule)?;
         if rule_block.declarations.is_empty() {
             return None;
@@ -318,6 +397,12 @@ fn selector_group_matches<'a, T>(selectors: &[&CssPathSelector], html_node: &Htm
                     return false;
                 }
             },
+            Attribute(attribute) => {
+                match html_node.node_data.get_attribute(&attribute.name) {
+                    Some(value) => if !attribute.matches(value) { return false; },
+                    None => return false,
+                }
+            },
             PseudoSelector(CssPathPseudoSelector::First) => {
                 // Notice: index_in_parent is 1-indexed
                 if html_node.index_in_parent != 1 { return false; }
@@ -327,13 +412,7 @@ fn selector_group_matches<'a, T>(selectors: &[&CssPathSelector], html_node: &Htm
                 if !html_node.is_last_child { return false; }
             },
             PseudoSelector(CssPathPseudoSelector::NthChild(x)) => {
-                match *x {
-                    Number(value) => if html_node.index_in_parent != value { return false; },
-                    Even => if html_node.index_in_parent % 2 == 0 { return false; },
-                    Odd => if html_node.index_in_parent % 2 == 1 { return false; },
-                    Pattern { repeat, offset } => if html_node.index_in_parent >= offset &&
-                        ((html_node.index_in_parent - offset) % repeat != 0) { return false; },
-                }
+                if !x.matches(html_node.index_in_parent) { return false; }
             },
             PseudoSelector(CssPathPseudoSelector::Hover) => {
                 if !html_node.is_hovered_over { return false; }
@@ -344,6 +423,19 @@ fn selector_group_matches<'a, T>(selectors: &[&CssPathSelector], html_node: &Htm
             PseudoSelector(CssPathPseudoSelector::Focus) => {
                 if !html_node.is_focused { return false; }
             },
+            PseudoSelector(CssPathPseudoSelector::Disabled) => {
+                if !html_node.is_disabled { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::Checked) => {
+                if !html_node.is_checked { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::Root) => {
+                if !html_node.is_root { return false; }
+            },
+            PseudoElement(_) => {
+                // Pseudo-elements select the generated nodes, see `insert_pseudo_elements`
+                return false;
+            },
             DirectChildren | Children => {
                 panic!("Unreachable: DirectChildren or Children in CSS path!");
             },
@@ -353,19 +445,237 @@ fn selector_group_matches<'a, T>(selectors: &[&CssPathSelector], html_node: &Htm
     true
 }
 
+/// Id, class or node type that a selector requires, used to index the rules
+/// and to build the `AncestorFilter` of a node
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum SelectorFeature<'a> {
+    Id(&'a str),
+    Class(&'a str),
+    Type(NodeTypePath),
+}
+
+impl<'a> SelectorFeature<'a> {
+    fn from_selector(selector: &'a CssPathSelector) -> Option<Self> {
+        use self::CssPathSelector::*;
+        match selector {
+            Id(id) => Some(SelectorFeature::Id(id)),
+            Class(class) => Some(SelectorFeature::Class(class)),
+            Type(node_type) => Some(SelectorFeature::Type(*node_type)),
+            _ => None,
+        }
+    }
+}
+
+/// Bloom filter of the ids, classes and node types of all ancestors of a node.
+///
+/// A rule like `.sidebar .item` can only match a node if one of its ancestors has the
+/// `sidebar` class - if the filter doesn't contain the feature, the walk up the ancestors
+/// in `matches_html_element` can be skipped. The filter can return false positives,
+/// but no false negatives, so the remaining rules still have to be matched.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+struct AncestorFilter {
+    bits: [u64; 8],
+}
+
+impl AncestorFilter {
+
+    fn insert(&mut self, feature: SelectorFeature) {
+        let mut hasher = DefaultHasher::new();
+        feature.hash(&mut hasher);
+        let hash = hasher.finish();
+        // Two bits per feature, taken from independent parts of the hash
+        for bit in &[hash & 511, (hash >> 32) & 511] {
+            self.bits[(bit >> 6) as usize] |= 1 << (bit & 63);
+        }
+    }
+
+    fn insert_node<T>(&mut self, node_data: &NodeData<T>) {
+        self.insert(SelectorFeature::Type(node_data.node_type.get_path()));
+        for id in &node_data.ids {
+            self.insert(SelectorFeature::Id(id.as_str()));
+        }
+        for class in &node_data.classes {
+            self.insert(SelectorFeature::Class(class.as_str()));
+        }
+    }
+
+    /// Returns false if at least one of the features in `other` is definitely not in `self`
+    fn may_contain_all(&self, other: &AncestorFilter) -> bool {
+        self.bits.iter().zip(other.bits.iter()).all(|(a, b)| a & b == *b)
+    }
+}
+
+/// Builds the `AncestorFilter` of every node, in one pass from the root downwards
+fn build_ancestor_filters<T>(
+    root: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    node_data: &NodeDataContainer<NodeData<T>>,
+) -> NodeDataContainer<AncestorFilter> {
+
+    let mut ancestor_filters = node_data.transform(|_, _| AncestorFilter::default());
+
+    for node_id in root.descendants(node_hierarchy) {
+        if let Some(parent_id) = node_hierarchy[node_id].parent {
+            let mut ancestor_filter = ancestor_filters[parent_id];
+            ancestor_filter.insert_node(&node_data[parent_id]);
+            ancestor_filters[node_id] = ancestor_filter;
+        }
+    }
+
+    ancestor_filters
+}
+
+/// Rule of the `RuleIndex` + the features that the ancestors of a node need to have
+struct IndexedRule<'a> {
+    rule: &'a CssRuleBlock,
+    ancestor_features: AncestorFilter,
+}
+
+/// The rules of a stylesheet, put into buckets by the rightmost id, class or node type of
+/// their path, so that a node is only matched against the rules that could select it
+/// instead of against every rule of the stylesheet. A rule is only in one bucket
+/// (ids are more selective than classes, classes more than types).
+struct RuleIndex<'a> {
+    rules: Vec<IndexedRule<'a>>,
+    by_id: BTreeMap<&'a str, Vec<usize>>,
+    by_class: BTreeMap<&'a str, Vec<usize>>,
+    by_type: BTreeMap<NodeTypePath, Vec<usize>>,
+    /// Rules without an id, class or type in their rightmost group, i.e. `*` or `:focus`
+    universal: Vec<usize>,
+}
+
+impl<'a> RuleIndex<'a> {
+
+    fn new(css: &'a Css, viewport: CssViewport) -> Self {
+
+        use self::CssPathSelector::*;
+
+        let mut index = RuleIndex {
+            rules: Vec::new(),
+            by_id: BTreeMap::new(),
+            by_class: BTreeMap::new(),
+            by_type: BTreeMap::new(),
+            universal: Vec::new(),
+        };
+
+        // Every stylesheet is sorted by specificity, but a later stylesheet (i.e. the app
+        // stylesheet after the native one) has to override only the less specific rules of
+        // an earlier stylesheet. The sort is stable, so equally specific rules keep their order.
+        let mut rules = css.rules_for_viewport(viewport).collect::<Vec<_>>();
+        rules.sort_by_key(|rule| get_specificity(&rule.path));
+
+        for rule in rules {
+
+            // Empty paths never match. Pseudo-element rules only select the generated
+            // nodes, so they are matched separately in `insert_pseudo_elements`
+            match rule.path.selectors.last() {
+                None | Some(PseudoElement(_)) => continue,
+                Some(_) => { },
+            }
+
+            let mut groups = rule.path.selectors.rsplit(|selector| *selector == Children || *selector == DirectChildren);
+            let rightmost_group = groups.next().unwrap_or(&[]);
+
+            // If a node has less ancestors than the path has groups, the path still matches
+            // if the remaining group is a "*" (see `matches_html_element`), so the groups
+            // to the left of a "*" group can't be required to be in the ancestor filter
+            let mut ancestor_features = AncestorFilter::default();
+            for selector in groups.take_while(|group| *group != &[Global][..]).flat_map(|group| group.iter()) {
+                if let Some(feature) = SelectorFeature::from_selector(selector) {
+                    ancestor_features.insert(feature);
+                }
+            }
+
+            let rule_idx = index.rules.len();
+            index.rules.push(IndexedRule { rule, ancestor_features });
+
+            let features = rightmost_group.iter().filter_map(SelectorFeature::from_selector).collect::<Vec<_>>();
+            let id = features.iter().find_map(|f| match f { SelectorFeature::Id(id) => Some(*id), _ => None });
+            let class = features.iter().find_map(|f| match f { SelectorFeature::Class(class) => Some(*class), _ => None });
+            let node_type = features.iter().find_map(|f| match f { SelectorFeature::Type(node_type) => Some(*node_type), _ => None });
+
+            if let Some(id) = id {
+                index.by_id.entry(id).or_insert_with(Vec::new).push(rule_idx);
+            } else if let Some(class) = class {
+                index.by_class.entry(class).or_insert_with(Vec::new).push(rule_idx);
+            } else if let Some(node_type) = node_type {
+                index.by_type.entry(node_type).or_insert_with(Vec::new).push(rule_idx);
+            } else {
+                index.universal.push(rule_idx);
+            }
+        }
+
+        index
+    }
+
+    /// Returns all rules that match the node, sorted by specificity
+    fn matching_rules<'b, T>(
+        &self,
+        node_id: NodeId,
+        node_hierarchy: &NodeHierarchy,
+        html_tree: &NodeDataContainer<HtmlCascadeInfo<'b, T>>,
+        ancestor_filter: &AncestorFilter,
+    ) -> Vec<&'a CssRuleBlock> {
+
+        let node_data = html_tree[node_id].node_data;
+
+        let mut candidates = self.universal.clone();
+        for id in &node_data.ids {
+            if let Some(rules) = self.by_id.get(id.as_str()) {
+                candidates.extend_from_slice(rules);
+            }
+        }
+        for class in &node_data.classes {
+            if let Some(rules) = self.by_class.get(class.as_str()) {
+                candidates.extend_from_slice(rules);
+            }
+        }
+        if let Some(rules) = self.by_type.get(&node_data.node_type.get_path()) {
+            candidates.extend_from_slice(rules);
+        }
+
+        // Later rules override earlier ones, so the order of the index has to be restored.
+        // A node can also have the same class twice, which would select a rule twice.
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        candidates.into_iter()
+            .map(|rule_idx| &self.rules[rule_idx])
+            .filter(|indexed_rule| ancestor_filter.may_contain_all(&indexed_rule.ancestor_features))
+            .filter(|indexed_rule| matches_html_element(&indexed_rule.rule.path, node_id, node_hierarchy, html_tree))
+            .map(|indexed_rule| indexed_rule.rule)
+            .collect()
+    }
+}
+
 pub(crate) fn match_dom_selectors<T>(
     ui_state: &UiState<T>,
     css: &Css,
+    viewport: CssViewport,
     focused_node: &mut Option<NodeId>,
     pending_focus_target: &mut Option<FocusTarget>,
     hovered_nodes: &BTreeMap<NodeId, HitTestItem>,
     is_mouse_down: bool,
+    previous: Option<&UiDescription<T>>,
 ) -> UiDescription<T> {
 
-    use azul_css::CssDeclaration;
-
     let non_leaf_nodes = ui_state.dom.arena.node_layout.get_parents_sorted_by_depth();
 
+    // Match the nodes of the last frame by their keys (see `Dom::with_key`), so that
+    // the focus and the style of a node survive if its index in the DOM changes
+    let matched_nodes = previous.map(|previous| diff::match_nodes_by_key(
+        &previous.ui_descr_arena.node_layout,
+        &previous.ui_descr_arena.node_data,
+        previous.ui_descr_root,
+        &ui_state.dom.arena.node_layout,
+        &ui_state.dom.arena.node_data,
+        ui_state.dom.root,
+    ));
+
+    if let (Some(matched_nodes), Some(previous_focus)) = (&matched_nodes, *focused_node) {
+        *focused_node = matched_nodes.linear_iter().find(|node_id| matched_nodes[*node_id] == Some(previous_focus));
+    }
+
     let mut html_tree = construct_html_cascade_tree(
         &ui_state.dom.arena.node_data,
         &ui_state.dom.arena.node_layout,
@@ -384,59 +694,482 @@ pub(crate) fn match_dom_selectors<T>(
         &mut html_tree,
     );
 
-    // First, apply all rules normally (no inheritance) of CSS values
-    // This is an O(n^2) operation, but it can be parallelized in the future
-    let mut styled_nodes = ui_state.dom.arena.node_data.transform(|_, node_id| StyledNode {
-        css_constraints: css
-            .rules()
-            .filter(|rule| matches_html_element(&rule.path, node_id, &ui_state.dom.arena.node_layout, &html_tree))
-            .flat_map(|matched_rule| matched_rule.declarations.iter().map(|declaration| (declaration.get_type(), declaration.clone())))
-            .collect(),
+    let node_hierarchy = &ui_state.dom.arena.node_layout;
+    let builtin_css_variables: CssVariables = Rc::new(ui_state.builtin_css_variables.iter()
+        .map(|variable| (variable.name.clone(), variable.value.clone()))
+        .collect());
+
+    // The previous cascade can only be reused if the stylesheet, the built-in variables and the
+    // viewport haven't changed. `::before` and `::after` insert extra nodes into the arena,
+    // so stylesheets with these pseudo-elements always restyle the whole tree.
+    let (css_rc, previous) = match previous {
+        Some(previous) if *previous.style_cache.css == *css => (previous.style_cache.css.clone(), Some(previous)),
+        _ => (Rc::new(css.clone()), None),
+    };
+    let previous = previous.filter(|previous| {
+        previous.style_cache.viewport == viewport &&
+        previous.style_cache.builtin_css_variables == builtin_css_variables &&
+        !has_pseudo_element_rules(css, viewport)
     });
 
-    // Then, inherit all values of the parent to the children, but only if the property is
-    // inheritable and isn't yet set. NOTE: This step can't be parallelized!
-    for (_depth, parent_id) in non_leaf_nodes {
+    let (arena, styled_nodes, css_variables, selector_inputs, changes) = match (previous, &matched_nodes) {
+        (Some(previous), Some(matched_nodes)) => {
+            let (selector_inputs, is_memoized) = get_selector_inputs(&html_tree, node_hierarchy, Some((previous, matched_nodes)));
+            let (arena, styled_nodes, css_variables, is_restyled) = restyle_changed_nodes(ui_state, css, viewport, &html_tree, &selector_inputs, &builtin_css_variables, previous, matched_nodes);
+            (arena, styled_nodes, css_variables, selector_inputs, Some((previous, matched_nodes, is_memoized, is_restyled)))
+        },
+        _ => {
+            let (selector_inputs, _) = get_selector_inputs(&html_tree, node_hierarchy, None);
+            let (arena, styled_nodes, css_variables) = restyle_all_nodes(ui_state, css, viewport, &html_tree, &builtin_css_variables);
+            (arena, styled_nodes, css_variables, selector_inputs, None)
+        },
+    };
 
-        let inherited_rules: Vec<CssDeclaration> = styled_nodes[parent_id].css_constraints.values().filter(|prop| prop.is_inheritable()).cloned().collect();
-        if inherited_rules.is_empty() {
-            continue;
-        }
+    let scrollbar_styles = match_scrollbar_styles(css, viewport, node_hierarchy, &html_tree, &styled_nodes, &css_variables);
 
-        for child_id in parent_id.children(&ui_state.dom.arena.node_layout) {
-            for inherited_rule in &inherited_rules {
-                // Only override the rule if the child already has an inherited rule, don't override it
-                let inherited_rule_type = inherited_rule.get_type();
-                styled_nodes[child_id].css_constraints.entry(inherited_rule_type).or_insert_with(|| inherited_rule.clone());
-            }
-        }
-    }
+    // The layout can only be reused if no node that the layout depends on changed
+    let layout_generation = match changes {
+        Some((previous, matched_nodes, is_memoized, is_restyled)) => {
+            let is_layout_unchanged = !has_layout_changes(ui_state, previous, matched_nodes, &is_memoized, &is_restyled, &styled_nodes, &scrollbar_styles);
+            if is_layout_unchanged { previous.layout_generation } else { new_layout_generation() }
+        },
+        None => new_layout_generation(),
+    };
 
     // In order to hit-test :hover and :active nodes, need to select them
     // first (to insert their TagId later)
     let selected_hover_nodes = match_hover_selectors(
-        collect_hover_groups(css),
-        &ui_state.dom.arena.node_layout,
+        collect_hover_groups(css, viewport),
+        node_hierarchy,
         &html_tree,
     );
 
     UiDescription {
-
-        // NOTE: this clone is necessary, otherwise we wouldn't be able to
-        // update the UiState
-        //
-        // WARNING: The UIState can modify the `arena` with its copy of the Rc !
-        // Be careful about appending things to the arena, since that could modify
-        // the UiDescription without you knowing!
-        //
-        // NOTE: This deep-clones the entire arena, which may be a
-        // performance-sensitive operation!
-
-        ui_descr_arena: ui_state.dom.arena.clone(),
+        ui_descr_arena: arena,
         dynamic_css_overrides: ui_state.dynamic_css_overrides.clone(),
         ui_descr_root: ui_state.dom.root,
         styled_nodes,
         selected_hover_nodes,
+        scrollbar_styles,
+        layout_generation,
+        style_cache: StyleCache {
+            css: css_rc,
+            viewport,
+            node_hierarchy: node_hierarchy.clone(),
+            selector_inputs,
+            css_variables,
+            builtin_css_variables,
+        },
+    }
+}
+
+/// Returns the `SelectorInputs` of all nodes and whether each node is part of a subtree that was
+/// reused via `Dom::memo` (whose root has the same `memo_hash` as the node it is matched with in
+/// the `previous` frame). The nodes of such a subtree are the same as in the previous frame, so
+/// they don't have to be hashed again - only their `:hover`, `:active` and `:focus` state can change.
+fn get_selector_inputs<'a, T>(
+    html_tree: &NodeDataContainer<HtmlCascadeInfo<'a, T>>,
+    node_hierarchy: &NodeHierarchy,
+    previous: Option<(&UiDescription<T>, &NodeDataContainer<Option<NodeId>>)>,
+) -> (NodeDataContainer<SelectorInputs>, Vec<bool>) {
+
+    let (previous, matched_nodes) = match previous {
+        Some(s) => s,
+        None => return (html_tree.transform(|html_node, _| SelectorInputs::new(html_node)), vec![false; html_tree.len()]),
+    };
+
+    // Parents come before their children in the arena
+    let mut is_memoized = vec![false; html_tree.len()];
+    let mut selector_inputs = Vec::with_capacity(html_tree.len());
+
+    for (node_id, html_node) in html_tree.iter().enumerate() {
+
+        let node_id = NodeId::new(node_id);
+        let parent_is_memoized = node_hierarchy[node_id].parent.map(|parent_id| is_memoized[parent_id.index()]).unwrap_or(false);
+        let previous_id = matched_nodes[node_id];
+
+        is_memoized[node_id.index()] = parent_is_memoized || match (html_node.node_data.memo_hash, previous_id) {
+            (Some(memo_hash), Some(previous_id)) => previous.ui_descr_arena.node_data[previous_id].memo_hash == Some(memo_hash),
+            _ => false,
+        };
+
+        selector_inputs.push(match previous_id {
+            Some(previous_id) if is_memoized[node_id.index()] => SelectorInputs {
+                node_hash: previous.style_cache.selector_inputs[previous_id].node_hash,
+                is_hovered_over: html_node.is_hovered_over,
+                is_active: html_node.is_active,
+                is_focused: html_node.is_focused,
+            },
+            _ => SelectorInputs::new(html_node),
+        });
+    }
+
+    (NodeDataContainer::new(selector_inputs), is_memoized)
+}
+
+/// Whether the layout of the `previous` frame can't be reused for the current frame. Only checks
+/// the nodes that could have changed: the styles of the restyled nodes and the content of the
+/// nodes that aren't part of a reused `Dom::memo` subtree. Since the layout is stored by `NodeId`,
+/// every node also has to keep its index.
+fn has_layout_changes<T>(
+    ui_state: &UiState<T>,
+    previous: &UiDescription<T>,
+    matched_nodes: &NodeDataContainer<Option<NodeId>>,
+    is_memoized: &[bool],
+    is_restyled: &[bool],
+    styled_nodes: &NodeDataContainer<StyledNode>,
+    scrollbar_styles: &BTreeMap<NodeId, ScrollbarStyledNode>,
+) -> bool {
+
+    let node_data = &ui_state.dom.arena.node_data;
+    let previous_node_data = &previous.ui_descr_arena.node_data;
+
+    if ui_state.dom.arena.node_layout != previous.ui_descr_arena.node_layout ||
+       ui_state.dynamic_css_overrides != previous.dynamic_css_overrides ||
+       *scrollbar_styles != previous.scrollbar_styles {
+        return true;
+    }
+
+    node_data.linear_iter().any(|node_id| {
+        matched_nodes[node_id] != Some(node_id) ||
+        (is_restyled[node_id.index()] && styled_nodes[node_id] != previous.styled_nodes[node_id]) ||
+        (!is_memoized[node_id.index()] && node_data[node_id].node_type != previous_node_data[node_id].node_type)
+    })
+}
+
+/// Matches all CSS rules against all nodes and cascades the styles from the root downwards
+fn restyle_all_nodes<'a, T>(
+    ui_state: &UiState<T>,
+    css: &Css,
+    viewport: CssViewport,
+    html_tree: &NodeDataContainer<HtmlCascadeInfo<'a, T>>,
+    builtin_css_variables: &CssVariables,
+) -> (Arena<NodeData<T>>, NodeDataContainer<StyledNode>, NodeDataContainer<CssVariables>) {
+
+    let node_hierarchy = &ui_state.dom.arena.node_layout;
+    let rule_index = RuleIndex::new(css, viewport);
+    let ancestor_filters = build_ancestor_filters(ui_state.dom.root, node_hierarchy, &ui_state.dom.arena.node_data);
+
+    // First, apply all rules normally (no inheritance) of CSS values
+    // This can be parallelized in the future
+    let mut matched_rules = ui_state.dom.arena.node_data.transform(|_, node_id| {
+        rule_index.matching_rules(node_id, node_hierarchy, html_tree, &ancestor_filters[node_id])
+    });
+
+    // NOTE: this clone is necessary, otherwise we wouldn't be able to
+    // update the UiState
+    //
+    // WARNING: The UIState can modify the `arena` with its copy of the Rc !
+    // Be careful about appending things to the arena, since that could modify
+    // the UiDescription without you knowing!
+    //
+    // NOTE: This deep-clones the entire arena, which may be a
+    // performance-sensitive operation!
+    let mut arena = ui_state.dom.arena.clone();
+
+    // Nodes of `::before` and `::after` are only added to the UiDescription, not to the UiState
+    insert_pseudo_elements(css, viewport, &ui_state.dom.arena.node_layout, html_tree, &mut arena, &mut matched_rules);
+
+    let mut styled_nodes = arena.node_data.transform(|_, _| StyledNode::default());
+    let mut css_variables = arena.node_data.transform(|_, _| CssVariables::default());
+
+    // Then, inherit the values of the parent to the children. NOTE: This step can't be
+    // parallelized, since the parent has to be fully styled before its children!
+    for node_id in ui_state.dom.root.descendants(&arena.node_layout) {
+        let (styled_node, variables) = {
+            let parent_id = arena.node_layout[node_id].parent;
+            let inherited_variables = parent_id.map(|parent_id| &css_variables[parent_id]).unwrap_or(builtin_css_variables);
+            cascade_node(&matched_rules[node_id], &arena.node_data[node_id].inline_styles, parent_id.map(|parent_id| &styled_nodes[parent_id]), inherited_variables)
+        };
+        styled_nodes[node_id] = styled_node;
+        css_variables[node_id] = variables;
+    }
+
+    (arena, styled_nodes, css_variables)
+}
+
+/// Restyles only the nodes whose `SelectorInputs` changed since the `previous` frame (i.e. because
+/// they are now hovered over or got a new class) or that didn't exist in the previous frame, plus
+/// all of their descendants, since descendant selectors and inherited properties depend on them.
+/// All other nodes keep the style of the node they were matched with (see `match_nodes_by_key`).
+fn restyle_changed_nodes<'a, T>(
+    ui_state: &UiState<T>,
+    css: &Css,
+    viewport: CssViewport,
+    html_tree: &NodeDataContainer<HtmlCascadeInfo<'a, T>>,
+    selector_inputs: &NodeDataContainer<SelectorInputs>,
+    builtin_css_variables: &CssVariables,
+    previous: &UiDescription<T>,
+    matched_nodes: &NodeDataContainer<Option<NodeId>>,
+) -> (Arena<NodeData<T>>, NodeDataContainer<StyledNode>, NodeDataContainer<CssVariables>, Vec<bool>) {
+
+    let node_hierarchy = &ui_state.dom.arena.node_layout;
+    let previous_hierarchy = &previous.style_cache.node_hierarchy;
+    let rule_index = RuleIndex::new(css, viewport);
+    let ancestor_filters = build_ancestor_filters(ui_state.dom.root, node_hierarchy, &ui_state.dom.arena.node_data);
+    let mut styled_nodes = ui_state.dom.arena.node_data.transform(|_, _| StyledNode::default());
+    let mut css_variables = ui_state.dom.arena.node_data.transform(|_, _| CssVariables::default());
+    let mut is_restyled = vec![false; node_hierarchy.len()];
+
+    // `:first`, `:last` and `:nth-child` also depend on the position among the siblings
+    let is_position_dependent = has_structural_pseudo_selectors(css, viewport);
+    let get_position = |node_id: NodeId, hierarchy: &NodeHierarchy| {
+        (hierarchy.get_index_in_parent(node_id), hierarchy[node_id].next_sibling.is_none())
+    };
+
+    // Parents are visited before their children, so `is_restyled` of the parent is final
+    for node_id in ui_state.dom.root.descendants(node_hierarchy) {
+
+        let parent_id = node_hierarchy[node_id].parent;
+        let parent_is_restyled = parent_id.map(|parent_id| is_restyled[parent_id.index()]).unwrap_or(false);
+
+        let unchanged_node = matched_nodes[node_id].filter(|previous_id| {
+            !parent_is_restyled &&
+            selector_inputs[node_id] == previous.style_cache.selector_inputs[*previous_id] &&
+            (!is_position_dependent || get_position(node_id, node_hierarchy) == get_position(*previous_id, previous_hierarchy))
+        });
+
+        if let Some(previous_id) = unchanged_node {
+            styled_nodes[node_id] = previous.styled_nodes[previous_id].clone();
+            css_variables[node_id] = previous.style_cache.css_variables[previous_id].clone();
+            continue;
+        }
+
+        let matched_rules = rule_index.matching_rules(node_id, node_hierarchy, html_tree, &ancestor_filters[node_id]);
+
+        let (styled_node, variables) = {
+            let inherited_variables = parent_id.map(|parent_id| &css_variables[parent_id]).unwrap_or(builtin_css_variables);
+            cascade_node(&matched_rules, &ui_state.dom.arena.node_data[node_id].inline_styles, parent_id.map(|parent_id| &styled_nodes[parent_id]), inherited_variables)
+        };
+        styled_nodes[node_id] = styled_node;
+        css_variables[node_id] = variables;
+        is_restyled[node_id.index()] = true;
+    }
+
+    (ui_state.dom.arena.clone(), styled_nodes, css_variables, is_restyled)
+}
+
+/// Cascades the style of a single node: the declarations of the rules that match the node
+/// (sorted by specificity, then the inline styles of the node, `!important` declarations last),
+/// with their `var()` references resolved, plus the inheritable properties of the parent
+/// that the node doesn't set itself. The root node inherits the built-in variables.
+fn cascade_node(
+    matched_rules: &[&CssRuleBlock],
+    inline_styles: &[CssProperty],
+    parent: Option<&StyledNode>,
+    inherited_variables: &CssVariables,
+) -> (StyledNode, CssVariables) {
+
+    let declarations = |important: bool| matched_rules.iter()
+        .filter(move |rule| rule.important == important)
+        .flat_map(|matched_rule| matched_rule.declarations.iter().cloned());
+
+    let cascaded_declarations = declarations(false)
+        .chain(inline_styles.iter().map(|property| CssDeclaration::Static(property.clone())))
+        .chain(declarations(true))
+        .collect::<Vec<_>>();
+
+    let mut styled_node = StyledNode {
+        cascade_order: cascaded_declarations.iter().enumerate().map(|(position, declaration)| (declaration.get_type(), position)).collect(),
+        css_constraints: cascaded_declarations.into_iter().map(|declaration| (declaration.get_type(), declaration)).collect(),
+    };
+
+    // Custom properties are inherited from the parent node unless the node declares them itself.
+    // Rules are sorted by specificity, so later variable declarations override earlier ones
+    let css_variables = if matched_rules.iter().all(|rule| rule.variables.is_empty()) {
+        inherited_variables.clone()
+    } else {
+        let mut variables = (*inherited_variables).clone();
+        variables.extend(matched_rules.iter().flat_map(|rule| rule.variables.iter().map(|variable| (variable.name.clone(), variable.value.clone()))));
+        Rc::new(variables)
+    };
+
+    // Properties that use `var()` can only be parsed once the variables of the node are known
+    #[cfg(feature = "css_parser")] {
+        resolve_css_variables(&css_variables, &mut styled_node);
+    }
+
+    // Only inherit the property if the node doesn't already set it itself
+    if let Some(parent_node) = parent {
+        for inherited_rule in parent_node.css_constraints.values().filter(|prop| prop.is_inheritable()) {
+            styled_node.css_constraints.entry(inherited_rule.get_type()).or_insert_with(|| inherited_rule.clone());
+        }
+    }
+
+    (styled_node, css_variables)
+}
+
+fn has_pseudo_element_rules(css: &Css, viewport: CssViewport) -> bool {
+    css.rules_for_viewport(viewport).any(|rule| match rule.path.selectors.last() {
+        Some(CssPathSelector::PseudoElement(pseudo_element)) => pseudo_element.generates_node(),
+        _ => false,
+    })
+}
+
+/// Whether any rule uses a selector that depends on the position
+/// of a node among its siblings (`:first`, `:last`, `:nth-child`)
+fn has_structural_pseudo_selectors(css: &Css, viewport: CssViewport) -> bool {
+    css.rules_for_viewport(viewport).any(|rule| rule.path.selectors.iter().any(|selector| match selector {
+        CssPathSelector::PseudoSelector(CssPathPseudoSelector::First) |
+        CssPathSelector::PseudoSelector(CssPathPseudoSelector::Last) |
+        CssPathSelector::PseudoSelector(CssPathPseudoSelector::NthChild(_)) => true,
+        _ => false,
+    }))
+}
+
+/// Returns all rules that select a pseudo-element as
+/// (pseudo-element, path without the pseudo-element, rule)
+fn get_pseudo_element_rules<'a>(css: &'a Css, viewport: CssViewport) -> Vec<(CssPseudoElement, CssPath, &'a CssRuleBlock)> {
+    css.rules_for_viewport(viewport).filter_map(|rule| {
+        let (last_selector, path) = rule.path.selectors.split_last()?;
+        let pseudo_element = match last_selector {
+            CssPathSelector::PseudoElement(pseudo_element) => *pseudo_element,
+            _ => return None,
+        };
+        // "::before" is a shorthand for "*::before"
+        let selectors = if path.is_empty() { vec![CssPathSelector::Global] } else { path.to_vec() };
+        Some((pseudo_element, CssPath { selectors }, rule))
+    }).collect()
+}
+
+/// Cascades the `::scrollbar`, `::track` and `::thumb` rules of every node that is selected by
+/// at least one of them. The scrollbar is styled like a child of the node, so it can use the
+/// custom properties of the node, and `.list:hover::thumb` changes the thumb while the
+/// node is hovered over.
+fn match_scrollbar_styles<'a, T>(
+    css: &Css,
+    viewport: CssViewport,
+    node_hierarchy: &NodeHierarchy,
+    html_tree: &NodeDataContainer<HtmlCascadeInfo<'a, T>>,
+    styled_nodes: &NodeDataContainer<StyledNode>,
+    css_variables: &NodeDataContainer<CssVariables>,
+) -> BTreeMap<NodeId, ScrollbarStyledNode> {
+
+    let scrollbar_rules = get_pseudo_element_rules(css, viewport).into_iter()
+        .filter(|(pseudo_element, _, _)| !pseudo_element.generates_node())
+        .collect::<Vec<_>>();
+
+    let mut scrollbar_styles = BTreeMap::new();
+
+    if scrollbar_rules.is_empty() {
+        return scrollbar_styles;
+    }
+
+    for node_id in html_tree.linear_iter() {
+
+        let cascade_pseudo_element = |pseudo_element: CssPseudoElement| {
+            let rules = scrollbar_rules.iter()
+                .filter(|(p, path, _)| *p == pseudo_element && matches_html_element(path, node_id, node_hierarchy, html_tree))
+                .map(|(_, _, rule)| *rule)
+                .collect::<Vec<&CssRuleBlock>>();
+            if rules.is_empty() {
+                None
+            } else {
+                Some(cascade_node(&rules, &[], Some(&styled_nodes[node_id]), &css_variables[node_id]).0)
+            }
+        };
+
+        let scrollbar = cascade_pseudo_element(CssPseudoElement::Scrollbar);
+        let track = cascade_pseudo_element(CssPseudoElement::Track);
+        let thumb = cascade_pseudo_element(CssPseudoElement::Thumb);
+
+        if scrollbar.is_none() && track.is_none() && thumb.is_none() {
+            continue;
+        }
+
+        scrollbar_styles.insert(node_id, ScrollbarStyledNode {
+            scrollbar: scrollbar.unwrap_or_default(),
+            track: track.unwrap_or_default(),
+            thumb: thumb.unwrap_or_default(),
+        });
+    }
+
+    scrollbar_styles
+}
+
+/// Generates the nodes of the `::before` and `::after` pseudo-elements: for every node that is
+/// selected by a pseudo-element rule with a non-empty `content`, a `Label` node is created and
+/// inserted as the first (`::before`) or last (`::after`) child of that node.
+///
+/// The generated nodes are appended to the end of the `arena`, so that the `NodeId`s of the
+/// `UiState` (used for callbacks and hit-testing) stay valid. They aren't selected by any other
+/// rules, they only get the declarations of their pseudo-element rules (plus the inherited ones).
+fn insert_pseudo_elements<'a, 'b, T>(
+    css: &'a Css,
+    viewport: CssViewport,
+    node_hierarchy: &NodeHierarchy,
+    html_tree: &NodeDataContainer<HtmlCascadeInfo<'b, T>>,
+    arena: &mut Arena<NodeData<T>>,
+    matched_rules: &mut NodeDataContainer<Vec<&'a CssRuleBlock>>,
+) {
+    use dom::{NodeType, DomString};
+
+    let pseudo_element_rules = get_pseudo_element_rules(css, viewport).into_iter()
+        .filter(|(pseudo_element, _, _)| pseudo_element.generates_node())
+        .collect::<Vec<_>>();
+
+    if pseudo_element_rules.is_empty() {
+        return;
+    }
+
+    for node_id in html_tree.linear_iter() {
+        for pseudo_element in &[CssPseudoElement::Before, CssPseudoElement::After] {
+
+            let rules = pseudo_element_rules.iter()
+                .filter(|(p, path, _)| p == pseudo_element && matches_html_element(path, node_id, node_hierarchy, html_tree))
+                .map(|(_, _, rule)| *rule)
+                .collect::<Vec<&CssRuleBlock>>();
+
+            // Rules are sorted by specificity, so the last `content` declaration wins
+            let content = rules.iter().rev()
+                .flat_map(|rule| rule.declarations.iter().rev())
+                .find_map(|declaration| match declaration {
+                    CssDeclaration::Static(CssProperty::Content(content)) => Some(content.0.clone()),
+                    _ => None,
+                });
+
+            let content = match content {
+                Some(content) if !content.is_empty() => content,
+                _ => continue,
+            };
+
+            let pseudo_element_id = arena.new_node(NodeData::new(NodeType::Label(DomString::Heap(content))));
+            if *pseudo_element == CssPseudoElement::Before {
+                arena.node_layout.prepend_child(node_id, pseudo_element_id);
+            } else {
+                arena.node_layout.append_child(node_id, pseudo_element_id);
+            }
+            matched_rules.internal.push(rules);
+        }
+    }
+}
+
+/// Replaces all `CssDeclaration::Unresolved` properties of a node with the parsed value
+/// after substituting the `var()` references. Properties that reference undefined
+/// variables (or that don't parse after substitution) are dropped.
+#[cfg(feature = "css_parser")]
+fn resolve_css_variables(
+    css_variables: &BTreeMap<String, String>,
+    styled_node: &mut StyledNode,
+) {
+    use azul_css_parser::parse_key_value_pair;
+
+    let css_constraints = &mut styled_node.css_constraints;
+
+    let unresolved_properties = css_constraints.values().filter_map(|declaration| match declaration {
+        CssDeclaration::Unresolved(unresolved) => Some(unresolved.clone()),
+        _ => None,
+    }).collect::<Vec<_>>();
+
+    for unresolved in unresolved_properties {
+        let resolved = unresolved
+            .substitute_variables(|name| css_variables.get(name).map(|value| value.as_str()))
+            .and_then(|value| parse_key_value_pair(unresolved.property_type, &value).ok());
+
+        match resolved {
+            Some(property) => { css_constraints.insert(unresolved.property_type, CssDeclaration::Static(property)); },
+            None => { css_constraints.remove(&unresolved.property_type); },
+        }
     }
 }
 
@@ -567,6 +1300,536 @@ fn test_case_issue_93() {
     assert_eq!(matches_html_element(&tab_active_close, NodeId::new(4), &node_hierarchy, &html_node_tree), true);
 }
 
+#[test]
+fn test_disabled_checked_pseudo_selectors() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::*;
+    use prelude::*;
+
+    struct DataModel;
+
+    let dom: Dom<DataModel> = Dom::div()
+        .with_child(Dom::div().with_class("checkbox").is_checked(true))
+        .with_child(Dom::div().with_class("checkbox").is_disabled(true));
+
+    let checked = CssPath { selectors: vec![Class("checkbox".into()), PseudoSelector(CssPathPseudoSelector::Checked)] };
+    let disabled = CssPath { selectors: vec![Class("checkbox".into()), PseudoSelector(CssPathPseudoSelector::Disabled)] };
+
+    let node_hierarchy = &dom.arena.node_layout;
+    let nodes_sorted = node_hierarchy.get_parents_sorted_by_depth();
+    let html_node_tree = construct_html_cascade_tree(
+        &dom.arena.node_data,
+        &node_hierarchy,
+        &nodes_sorted,
+        None,
+        &BTreeMap::new(),
+        false,
+    );
+
+    assert_eq!(matches_html_element(&checked, NodeId::new(1), &node_hierarchy, &html_node_tree), true);
+    assert_eq!(matches_html_element(&checked, NodeId::new(2), &node_hierarchy, &html_node_tree), false);
+    assert_eq!(matches_html_element(&disabled, NodeId::new(1), &node_hierarchy, &html_node_tree), false);
+    assert_eq!(matches_html_element(&disabled, NodeId::new(2), &node_hierarchy, &html_node_tree), true);
+
+    let root = CssPath { selectors: vec![PseudoSelector(CssPathPseudoSelector::Root)] };
+    assert_eq!(matches_html_element(&root, NodeId::new(0), &node_hierarchy, &html_node_tree), true);
+    assert_eq!(matches_html_element(&root, NodeId::new(1), &node_hierarchy, &html_node_tree), false);
+}
+
+#[test]
+fn test_insert_pseudo_elements() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::*;
+    use prelude::*;
+
+    struct DataModel;
+
+    let dom: Dom<DataModel> = Dom::div().with_class("button")
+        .with_child(Dom::label("Save"));
+
+    let content_rule = |selectors, content: &str| CssRuleBlock {
+        path: CssPath { selectors },
+        declarations: vec![CssDeclaration::Static(CssProperty::Content(StyleContent(content.into())))],
+        variables: Vec::new(),
+        important: false,
+    };
+
+    let css = Css {
+        stylesheets: vec![Stylesheet {
+            rules: vec![
+                content_rule(vec![Class("button".into()), PseudoElement(CssPseudoElement::Before)], "<"),
+                content_rule(vec![Class("button".into()), PseudoElement(CssPseudoElement::After)], ">"),
+                // empty content doesn't generate a node
+                content_rule(vec![Type(NodeTypePath::P), PseudoElement(CssPseudoElement::After)], ""),
+            ],
+            media_queries: Vec::new(),
+        }],
+        font_faces: Vec::new(),
+    };
+
+    let node_hierarchy = &dom.arena.node_layout;
+    let nodes_sorted = node_hierarchy.get_parents_sorted_by_depth();
+    let html_node_tree = construct_html_cascade_tree(
+        &dom.arena.node_data,
+        &node_hierarchy,
+        &nodes_sorted,
+        None,
+        &BTreeMap::new(),
+        false,
+    );
+
+    let mut arena = dom.arena.clone();
+    let mut matched_rules = dom.arena.node_data.transform(|_, _| Vec::new());
+    insert_pseudo_elements(&css, CssViewport::default(), &node_hierarchy, &html_node_tree, &mut arena, &mut matched_rules);
+
+    //  0: [div .button]
+    //   |-- 2: [p "<"]
+    //   |-- 1: [p "Save"]
+    //   |-- 3: [p ">"]
+    assert_eq!(arena.len(), 4);
+    assert_eq!(matched_rules.len(), 4);
+    assert_eq!(NodeId::new(0).children(&arena.node_layout).collect::<Vec<_>>(), vec![NodeId::new(2), NodeId::new(1), NodeId::new(3)]);
+    assert_eq!(arena.node_data[NodeId::new(2)].node_type, NodeType::Label(DomString::Static("<")));
+    assert_eq!(arena.node_data[NodeId::new(3)].node_type, NodeType::Label(DomString::Static(">")));
+}
+
+#[test]
+fn test_match_scrollbar_styles() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::*;
+    use prelude::*;
+
+    struct DataModel;
+
+    let dom: Dom<DataModel> = Dom::div().with_class("list")
+        .with_child(Dom::div().with_class("item"));
+
+    let color = |r| CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(ColorU { r, g: 0, b: 0, a: 255 })));
+    let rule = |selectors, declaration| CssRuleBlock {
+        path: CssPath { selectors },
+        declarations: vec![declaration],
+        variables: Vec::new(),
+        important: false,
+    };
+
+    let css = Css {
+        stylesheets: vec![Stylesheet {
+            rules: vec![
+                rule(vec![Class("list".into()), PseudoElement(CssPseudoElement::Thumb)], color(1)),
+                rule(vec![Class("list".into()), PseudoElement(CssPseudoElement::Track)], color(2)),
+                rule(vec![Class("item".into())], color(3)),
+            ],
+            media_queries: Vec::new(),
+        }],
+        font_faces: Vec::new(),
+    };
+
+    // Scrollbar rules don't insert any nodes, so the previous cascade can be reused
+    assert!(!has_pseudo_element_rules(&css, CssViewport::default()));
+
+    let node_hierarchy = &dom.arena.node_layout;
+    let nodes_sorted = node_hierarchy.get_parents_sorted_by_depth();
+    let html_node_tree = construct_html_cascade_tree(
+        &dom.arena.node_data,
+        &node_hierarchy,
+        &nodes_sorted,
+        None,
+        &BTreeMap::new(),
+        false,
+    );
+
+    let styled_nodes = dom.arena.node_data.transform(|_, _| StyledNode::default());
+    let css_variables = dom.arena.node_data.transform(|_, _| CssVariables::default());
+    let scrollbar_styles = match_scrollbar_styles(&css, CssViewport::default(), &node_hierarchy, &html_node_tree, &styled_nodes, &css_variables);
+
+    assert_eq!(scrollbar_styles.len(), 1);
+    let list_scrollbar = &scrollbar_styles[&NodeId::new(0)];
+    assert!(list_scrollbar.scrollbar.css_constraints.is_empty());
+    assert_eq!(list_scrollbar.thumb.css_constraints.get(&CssPropertyType::TextColor), Some(&color(1)));
+    assert_eq!(list_scrollbar.track.css_constraints.get(&CssPropertyType::TextColor), Some(&color(2)));
+}
+
+#[test]
+fn test_incremental_restyle() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::*;
+    use prelude::*;
+
+    struct DataModel;
+
+    let dom: Dom<DataModel> = Dom::div()
+        .with_child(Dom::div().with_class("item").with_child(Dom::label("A")))
+        .with_child(Dom::div().with_class("item"));
+
+    let red = ColorU { r: 255, g: 0, b: 0, a: 255 };
+    let css = Css {
+        stylesheets: vec![Stylesheet {
+            rules: vec![
+                CssRuleBlock {
+                    path: CssPath { selectors: vec![Class("item".into())] },
+                    declarations: vec![CssDeclaration::Static(CssProperty::FontSize(StyleFontSize(PixelValue::px(12.0))))],
+                    variables: Vec::new(),
+                    important: false,
+                },
+                CssRuleBlock {
+                    path: CssPath { selectors: vec![Class("item".into()), PseudoSelector(CssPathPseudoSelector::Hover)] },
+                    declarations: vec![CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(red)))],
+                    variables: Vec::new(),
+                    important: false,
+                },
+            ],
+            media_queries: Vec::new(),
+        }],
+        font_faces: Vec::new(),
+    };
+
+    let ui_state = dom.into_ui_state();
+    let previous = match_dom_selectors(&ui_state, &css, CssViewport::default(), &mut None, &mut None, &BTreeMap::new(), false, None);
+
+    // Hover over the first item
+    let node_hierarchy = &ui_state.dom.arena.node_layout;
+    let mut html_node_tree = construct_html_cascade_tree(
+        &ui_state.dom.arena.node_data,
+        &node_hierarchy,
+        &node_hierarchy.get_parents_sorted_by_depth(),
+        None,
+        &BTreeMap::new(),
+        false,
+    );
+    html_node_tree[NodeId::new(1)].is_hovered_over = true;
+    let selector_inputs = html_node_tree.transform(|html_node, _| SelectorInputs::new(html_node));
+
+    let matched_nodes = ui_state.dom.arena.node_data.transform(|_, node_id| Some(node_id));
+    let (_, restyled_nodes, _, _) = restyle_changed_nodes(&ui_state, &css, CssViewport::default(), &html_node_tree, &selector_inputs, &CssVariables::default(), &previous, &matched_nodes);
+    let (_, styled_nodes, _) = restyle_all_nodes(&ui_state, &css, CssViewport::default(), &html_node_tree, &CssVariables::default());
+
+    assert_eq!(restyled_nodes, styled_nodes);
+    // The label inherits the color of the hovered item, the other item isn't affected
+    assert_eq!(restyled_nodes[NodeId::new(2)].css_constraints.get(&CssPropertyType::TextColor), Some(&CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(red)))));
+    assert_eq!(restyled_nodes[NodeId::new(3)], previous.styled_nodes[NodeId::new(3)]);
+}
+
+#[test]
+fn test_memoized_subtree_skips_restyle_and_layout() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::*;
+    use std::cell::Cell;
+    use prelude::*;
+    use window::FakeWindow;
+
+    struct DataModel;
+
+    let css = Css {
+        stylesheets: vec![Stylesheet {
+            rules: vec![CssRuleBlock {
+                path: CssPath { selectors: vec![Class("row".into())] },
+                declarations: vec![CssDeclaration::Static(CssProperty::Height(LayoutHeight(PixelValue::px(20.0))))],
+                variables: Vec::new(),
+                important: false,
+            }],
+            media_queries: Vec::new(),
+        }],
+        font_faces: Vec::new(),
+    };
+
+    let mut window = FakeWindow::<DataModel>::mock();
+    let build_count = Cell::new(0);
+    let layout = |window: &mut FakeWindow<DataModel>| {
+        let table = Dom::cached(window, 42, || {
+            build_count.set(build_count.get() + 1);
+            (0..100).map(|row| Dom::label(format!("{}", row)).with_class("row")).collect()
+        });
+        window.memo_cache.end_frame();
+        Dom::div().with_child(Dom::label("header")).with_child(table).into_ui_state()
+    };
+
+    let viewport = CssViewport::default();
+    let first_ui_state = layout(&mut window);
+    let first = match_dom_selectors(&first_ui_state, &css, viewport, &mut None, &mut None, &BTreeMap::new(), false, None);
+
+    let ui_state = layout(&mut window);
+    let node_hierarchy = &ui_state.dom.arena.node_layout;
+    let html_node_tree = construct_html_cascade_tree(
+        &ui_state.dom.arena.node_data,
+        &node_hierarchy,
+        &node_hierarchy.get_parents_sorted_by_depth(),
+        None,
+        &BTreeMap::new(),
+        false,
+    );
+    let matched_nodes = ui_state.dom.arena.node_data.transform(|_, node_id| Some(node_id));
+    let (selector_inputs, is_memoized) = get_selector_inputs(&html_node_tree, node_hierarchy, Some((&first, &matched_nodes)));
+    let (_, _, _, is_restyled) = restyle_changed_nodes(&ui_state, &css, viewport, &html_node_tree, &selector_inputs, &CssVariables::default(), &first, &matched_nodes);
+
+    // The second `Dom::cached` call reused the table of the first frame
+    assert_eq!(build_count.get(), 1);
+
+    // The table and its rows reuse the selector inputs of the last frame instead of hashing their
+    // nodes again and aren't restyled, the root and the header are compared like regular nodes
+    assert_eq!(is_memoized[..3], [false, false, true]);
+    assert!(is_memoized[3..].iter().all(|is_memoized| *is_memoized));
+    assert!(is_restyled.iter().all(|is_restyled| !is_restyled));
+
+    // The layout of the last frame can be reused
+    let second = match_dom_selectors(&ui_state, &css, viewport, &mut None, &mut None, &BTreeMap::new(), false, Some(&first));
+    assert_eq!(second.layout_generation, first.layout_generation);
+}
+
+#[test]
+fn test_keyed_nodes_keep_focus() {
+
+    use prelude::*;
+
+    struct DataModel;
+
+    let list = |keys: &[u64]| keys.iter()
+        .map(|key| Dom::div().with_class("item").with_key(*key))
+        .collect::<Dom<DataModel>>()
+        .into_ui_state();
+
+    let css = Css::default();
+    let viewport = CssViewport::default();
+    let previous_ui_state = list(&[1, 2]);
+    let previous = match_dom_selectors(&previous_ui_state, &css, viewport, &mut None, &mut None, &BTreeMap::new(), false, None);
+
+    // An item is inserted at the top: the focus stays on the item with the key 2
+    let mut focused_node = Some(NodeId::new(2));
+    match_dom_selectors(&list(&[0, 1, 2]), &css, viewport, &mut focused_node, &mut None, &BTreeMap::new(), false, Some(&previous));
+    assert_eq!(focused_node, Some(NodeId::new(3)));
+
+    // The focused item is removed: nothing is focused
+    let mut focused_node = Some(NodeId::new(2));
+    match_dom_selectors(&list(&[0, 1]), &css, viewport, &mut focused_node, &mut None, &BTreeMap::new(), false, Some(&previous));
+    assert_eq!(focused_node, None);
+}
+
+#[test]
+fn test_layout_generation() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::*;
+    use prelude::*;
+
+    struct DataModel;
+
+    let dom = |class: &str, text: &str| Dom::<DataModel>::div()
+        .with_child(Dom::label(text.to_string()).with_class(class.to_string()))
+        .into_ui_state();
+
+    let css = Css {
+        stylesheets: vec![Stylesheet {
+            rules: vec![CssRuleBlock {
+                path: CssPath { selectors: vec![Class("wide".into())] },
+                declarations: vec![CssDeclaration::Static(CssProperty::Width(LayoutWidth(PixelValue::px(200.0))))],
+                variables: Vec::new(),
+                important: false,
+            }],
+            media_queries: Vec::new(),
+        }],
+        font_faces: Vec::new(),
+    };
+
+    let viewport = CssViewport::default();
+    let restyle = |ui_state: &UiState<DataModel>, previous: Option<&UiDescription<DataModel>>| {
+        match_dom_selectors(ui_state, &css, viewport, &mut None, &mut None, &BTreeMap::new(), false, previous)
+    };
+
+    let first = restyle(&dom("item", "A"), None);
+
+    // Nothing changed
+    let unchanged = restyle(&dom("item", "A"), Some(&first));
+    assert_eq!(unchanged.layout_generation, first.layout_generation);
+
+    // The label is restyled, but no rule selects the new class, so its style is the same
+    let same_style = restyle(&dom("other", "A"), Some(&unchanged));
+    assert_eq!(same_style.layout_generation, first.layout_generation);
+
+    // The width of the label changes
+    let new_style = restyle(&dom("wide", "A"), Some(&same_style));
+    assert_ne!(new_style.layout_generation, first.layout_generation);
+
+    // The text of the label changes
+    let new_text = restyle(&dom("wide", "B"), Some(&new_style));
+    assert_ne!(new_text.layout_generation, new_style.layout_generation);
+
+    // Without a previous frame, the layout can't be reused
+    let no_previous = restyle(&dom("wide", "B"), None);
+    assert_ne!(no_previous.layout_generation, new_text.layout_generation);
+}
+
+#[test]
+fn test_rule_index() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::*;
+    use prelude::*;
+
+    struct DataModel;
+
+    let dom: Dom<DataModel> = Dom::div().with_class("sidebar")
+        .with_child(Dom::div().with_class("item").with_id("selected").with_child(Dom::label("A")))
+        .with_child(Dom::label("B").with_class("item"));
+
+    let rule = |selectors| CssRuleBlock {
+        path: CssPath { selectors },
+        declarations: Vec::new(),
+        variables: Vec::new(),
+        important: false,
+    };
+
+    let mut css = Css {
+        stylesheets: vec![Stylesheet {
+            rules: vec![
+                rule(vec![Global]),
+                rule(vec![Type(NodeTypePath::P)]),
+                rule(vec![Class("item".into())]),
+                rule(vec![Class("sidebar".into()), Children, Class("item".into())]),
+                rule(vec![Class("toolbar".into()), Children, Class("item".into())]),
+                rule(vec![Class("item".into()), Id("selected".into())]),
+                rule(vec![Id("selected".into()), DirectChildren, Type(NodeTypePath::P)]),
+                rule(vec![Class("toolbar".into()), Children, Global, Children, Class("sidebar".into())]),
+            ],
+            media_queries: Vec::new(),
+        }],
+        font_faces: Vec::new(),
+    };
+
+    css.sort_by_specificity();
+
+    let ui_state = dom.into_ui_state();
+    let node_hierarchy = &ui_state.dom.arena.node_layout;
+    let html_node_tree = construct_html_cascade_tree(
+        &ui_state.dom.arena.node_data,
+        &node_hierarchy,
+        &node_hierarchy.get_parents_sorted_by_depth(),
+        None,
+        &BTreeMap::new(),
+        false,
+    );
+
+    let rule_index = RuleIndex::new(&css, CssViewport::default());
+    let ancestor_filters = build_ancestor_filters(ui_state.dom.root, node_hierarchy, &ui_state.dom.arena.node_data);
+
+    // The index has to select exactly the same rules (in the same order) as matching every rule
+    for node_id in html_node_tree.linear_iter() {
+        let expected = css.rules_for_viewport(CssViewport::default())
+            .filter(|rule| matches_html_element(&rule.path, node_id, node_hierarchy, &html_node_tree))
+            .collect::<Vec<&CssRuleBlock>>();
+        assert_eq!(rule_index.matching_rules(node_id, node_hierarchy, &html_node_tree, &ancestor_filters[node_id]), expected);
+    }
+
+    // ".toolbar .item" is rejected by the ancestor filter, without walking up the DOM
+    let indexed_rule = |selectors: Vec<CssPathSelector>| rule_index.rules.iter().find(|r| r.rule.path.selectors == selectors).unwrap();
+    let toolbar_item = indexed_rule(vec![Class("toolbar".into()), Children, Class("item".into())]);
+    assert!(!ancestor_filters[NodeId::new(1)].may_contain_all(&toolbar_item.ancestor_features));
+    let sidebar_item = indexed_rule(vec![Class("sidebar".into()), Children, Class("item".into())]);
+    assert!(ancestor_filters[NodeId::new(1)].may_contain_all(&sidebar_item.ancestor_features));
+}
+
+#[test]
+fn test_global_selector_above_the_root() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::*;
+    use prelude::*;
+
+    struct DataModel;
+
+    //  0: [div .outer]
+    //   |-- 1: [div .item]
+    let dom: Dom<DataModel> = Dom::div().with_class("outer")
+        .with_child(Dom::div().with_class("item"));
+
+    let path = |selectors| CssPath { selectors };
+
+    let node_hierarchy = &dom.arena.node_layout;
+    let html_node_tree = construct_html_cascade_tree(
+        &dom.arena.node_data,
+        &node_hierarchy,
+        &node_hierarchy.get_parents_sorted_by_depth(),
+        None,
+        &BTreeMap::new(),
+        false,
+    );
+
+    let item = NodeId::new(1);
+
+    // A "*" group that is left over once the root has been reached matches (nothing is above the root)
+    let global_item = path(vec![Global, Children, Class("item".into())]);
+    let global_outer_item = path(vec![Global, Children, Class("outer".into()), Children, Class("item".into())]);
+    assert_eq!(matches_html_element(&global_item, item, &node_hierarchy, &html_node_tree), true);
+    assert_eq!(matches_html_element(&global_outer_item, item, &node_hierarchy, &html_node_tree), true);
+
+    // ... but only if the group before it matched: ".missing" doesn't match the root,
+    // so "* .missing .item" must not match, even though the path ends with a "*"
+    let global_missing_item = path(vec![Global, Children, Class("missing".into()), Children, Class("item".into())]);
+    assert_eq!(matches_html_element(&global_missing_item, item, &node_hierarchy, &html_node_tree), false);
+    assert_eq!(matches_html_element(&path(vec![Class("missing".into()), Children, Class("item".into())]), item, &node_hierarchy, &html_node_tree), false);
+}
+
+#[test]
+fn test_important_declarations() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::*;
+
+    let color = |r| CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(ColorU { r, g: 0, b: 0, a: 255 })));
+    let rule = |selectors, important, declaration| CssRuleBlock {
+        path: CssPath { selectors },
+        declarations: vec![declaration],
+        variables: Vec::new(),
+        important,
+    };
+
+    // Sorted by specificity: ".button { color: 1 !important }", ".button:hover { color: 2 }", "#ok { color: 3 }"
+    let button_important = rule(vec![Class("button".into())], true, color(1));
+    let button_hover = rule(vec![Class("button".into()), PseudoSelector(CssPathPseudoSelector::Hover)], false, color(2));
+    let ok = rule(vec![Id("ok".into())], false, color(3));
+
+    let (styled_node, _) = cascade_node(&[&button_hover, &ok], &[], None, &CssVariables::default());
+    assert_eq!(styled_node.css_constraints.get(&CssPropertyType::TextColor), Some(&color(3)));
+
+    let (styled_node, _) = cascade_node(&[&button_important, &button_hover, &ok], &[], None, &CssVariables::default());
+    assert_eq!(styled_node.css_constraints.get(&CssPropertyType::TextColor), Some(&color(1)));
+
+    // Inline styles override all rules, except for `!important` declarations
+    let inline_color = CssProperty::TextColor(StyleTextColor(ColorU { r: 4, g: 0, b: 0, a: 255 }));
+    let (styled_node, _) = cascade_node(&[&button_hover, &ok], &[inline_color.clone()], None, &CssVariables::default());
+    assert_eq!(styled_node.css_constraints.get(&CssPropertyType::TextColor), Some(&color(4)));
+
+    let (styled_node, _) = cascade_node(&[&button_important, &button_hover, &ok], &[inline_color], None, &CssVariables::default());
+    assert_eq!(styled_node.css_constraints.get(&CssPropertyType::TextColor), Some(&color(1)));
+}
+
+#[test]
+fn test_builtin_css_variables() {
+
+    use azul_css::*;
+
+    let builtin_css_variables: CssVariables = Rc::new(vec![
+        ("azul-system-accent-color".to_string(), "#ff0000ff".to_string()),
+    ].into_iter().collect());
+
+    // The root node inherits the built-in variables, rules can add to them
+    let (_, variables) = cascade_node(&[], &[], None, &builtin_css_variables);
+    assert_eq!(variables, builtin_css_variables);
+
+    let root = CssRuleBlock {
+        path: CssPath { selectors: vec![CssPathSelector::PseudoSelector(CssPathPseudoSelector::Root)] },
+        declarations: Vec::new(),
+        variables: vec![CssVariable { name: "azul-accent-color".into(), value: "var(--azul-system-accent-color)".into() }],
+        important: false,
+    };
+    let (_, variables) = cascade_node(&[&root], &[], None, &builtin_css_variables);
+    assert_eq!(variables.get("azul-system-accent-color").map(|value| value.as_str()), Some("#ff0000ff"));
+    assert_eq!(variables.get("azul-accent-color").map(|value| value.as_str()), Some("var(--azul-system-accent-color)"));
+}
+
 #[test]
 fn test_css_group_iterator() {
 
@@ -2,11 +2,16 @@
 
 use azul_css::{
     StyleTextAlignmentHorz, StyleTextAlignmentVert, ScrollbarInfo,
+    StyleWhiteSpace, StyleTextOverflow, StyleTextDirection,
 };
+/// Characters that the space in front of a `TabStop` can be filled with
+pub use azul_css::StyleTabLeader as TabLeader;
 pub use webrender::api::{
     GlyphInstance, LayoutSize, LayoutRect, LayoutPoint,
 };
 pub use harfbuzz_sys::{hb_glyph_info_t as GlyphInfo, hb_glyph_position_t as GlyphPosition};
+use std::collections::BTreeMap;
+use text_shaping::HbScaledFont;
 
 pub type WordIndex = usize;
 pub type GlyphIndex = usize;
@@ -15,8 +20,7 @@ pub type IndexOfLineBreak = usize;
 pub type RemainingSpaceToRight = f32;
 pub type LineBreaks = Vec<(GlyphIndex, RemainingSpaceToRight)>;
 
-const DEFAULT_LINE_HEIGHT: f32 = 1.0;
-const DEFAULT_WORD_SPACING: f32 = 1.0;
+const DEFAULT_WORD_SPACING: f32 = 0.0;
 const DEFAULT_LETTER_SPACING: f32 = 0.0;
 const DEFAULT_TAB_WIDTH: f32 = 4.0;
 
@@ -81,6 +85,15 @@ pub struct ScaledWords {
     pub space_advance_px: f32,
     /// Glyph index of the space character
     pub space_codepoint: u32,
+    /// Height of the font above the baseline (in pixels)
+    pub ascender_px: f32,
+    /// Depth of the font below the baseline (in pixels, usually negative)
+    pub descender_px: f32,
+    /// Shaped ellipsis ("…", or "..." if the font has no glyph for it),
+    /// inserted at the end of truncated lines for `text-overflow: ellipsis`
+    pub ellipsis: ScaledWord,
+    /// Shaped leader characters, repeated to fill the space in front of a `TabStop`
+    pub tab_leaders: BTreeMap<TabLeader, ScaledWord>,
 }
 
 /// Word that is scaled (to a font / font instance), but not yet positioned
@@ -108,6 +121,11 @@ pub struct WordPositions {
     /// Index of the word at which the line breaks + length of line
     /// (useful for text selection + horizontal centering)
     pub line_breaks: Vec<(WordIndex, LineLength)>,
+    /// Spaces in front of tab stops that have to be filled with leader characters
+    pub leader_fills: Vec<LeaderFill>,
+    /// Whether the line at the same index in `line_breaks` ends with a `\n` or the end of the
+    /// text instead of being wrapped (the last line of a paragraph is never justified)
+    pub hard_line_breaks: Vec<bool>,
     /// Horizontal width of the last line (in pixels), necessary for inline layout later on,
     /// so that the next text run can contine where the last text run left off.
     ///
@@ -142,7 +160,8 @@ pub struct ScrollbarStyle {
 /// Layout options that can impact the flow of word positions
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct TextLayoutOptions {
-    /// Multiplier for the line height, default to 1.0
+    /// Line height as a multiple of the font size (i.e. `1.5` for 150%). If `None`,
+    /// the lines are one space character apart
     pub line_height: Option<f32>,
     /// Additional spacing between glyphs (in pixels)
     pub letter_spacing: Option<f32>,
@@ -151,18 +170,78 @@ pub struct TextLayoutOptions {
     /// How many spaces should a tab character emulate
     /// (multiplying value, i.e. `4.0` = one tab = 4 spaces)?
     pub tab_width: Option<f32>,
+    /// Positions that a tab character advances the caret to. A tab advances to the
+    /// first tab stop right of the caret - behind the last tab stop, tabs
+    /// fall back to the `tab_width`.
+    pub tab_stops: Vec<TabStop>,
+    /// With `direction: rtl`, the words of each line are placed from right to left. The
+    /// glyphs of a single word are already in visual order (HarfBuzz detects the direction
+    /// of the script), mixed left-to-right and right-to-left text isn't reordered.
+    pub direction: Option<StyleTextDirection>,
     /// Maximum width of the text (in pixels) - if the text is set to `overflow:visible`, set this to None.
     pub max_horizontal_width: Option<f32>,
-    /// How many pixels of leading does the first line have? Note that this added onto to the holes,
-    /// so for effects like `:first-letter`, use a hole instead of a leading.
+    /// With `white-space: nowrap`, lines only break at explicit line breaks,
+    /// not at the `max_horizontal_width`
+    pub white_space: Option<StyleWhiteSpace>,
+    /// With `text-overflow: ellipsis`, lines that are wider than the
+    /// `max_horizontal_width` are cut off and end with an ellipsis
+    pub text_overflow: Option<StyleTextOverflow>,
+    /// How many pixels of leading does the first line have? If the first line starts inside
+    /// of a hole, the text starts at the end of the hole instead.
     pub leading: Option<f32>,
     /// This is more important for inline text layout where items can punch "holes"
-    /// into the text flow, for example an image that floats to the right.
-    ///
-    /// TODO: Currently unused!
+    /// into the text flow, for example an image that floats to the right. A line wraps
+    /// around a hole if the bottom of the line is inside of the hole.
     pub holes: Vec<LayoutRect>,
 }
 
+/// Position that a tab character advances the caret to, for aligning
+/// text in columns (for example the accelerators in a menu)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TabStop {
+    /// Horizontal distance from the start of the line (in pixels)
+    pub position: f32,
+    /// Characters that the space in front of the tab stop is filled with, if any
+    pub leader: Option<TabLeader>,
+}
+
+impl TabStop {
+    /// Creates a new tab stop without any leader characters
+    pub fn new(position: f32) -> Self {
+        Self { position, leader: None }
+    }
+
+    /// Fills the space in front of the tab stop with leader characters,
+    /// i.e. `Chapter 1 ........ 12` in a table of contents
+    pub fn with_leader(self, leader: TabLeader) -> Self {
+        Self { leader: Some(leader), .. self }
+    }
+}
+
+/// Returns the leaders that are used by at least one of the `tab_stops`,
+/// which have to be shaped in `words_to_scaled_words`
+pub fn get_tab_leaders(tab_stops: &[TabStop]) -> Vec<TabLeader> {
+    let mut leaders = tab_stops.iter().filter_map(|tab_stop| tab_stop.leader).collect::<Vec<_>>();
+    leaders.sort();
+    leaders.dedup();
+    leaders
+}
+
+/// Space between the end of the text in front of a tab and the `TabStop`
+/// it advanced to, which is filled with the `leader` characters
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LeaderFill {
+    pub leader: TabLeader,
+    /// Index of the line in `WordPositions::line_breaks`
+    pub line_idx: usize,
+    /// Y position of the baseline of the line
+    pub line_y: f32,
+    /// Start of the space (in pixels)
+    pub start_x: f32,
+    /// Position of the tab stop (in pixels)
+    pub end_x: f32,
+}
+
 /// Given the scale of words + the word positions, lays out the words in a
 #[derive(Debug, Clone, PartialEq)]
 pub struct LeftAlignedGlyphs<'a> {
@@ -183,6 +262,14 @@ pub struct LayoutedGlyphs {
     pub glyphs: Vec<GlyphInstance>,
 }
 
+/// Horizontal extent and baseline of one line of `LayoutedGlyphs`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LineExtent {
+    pub x_start: f32,
+    pub x_end: f32,
+    pub baseline: f32,
+}
+
 /// Whether the text overflows the parent rectangle, and if yes, by how many pixels,
 /// necessary for determining if / how to show a scrollbar + aligning / centering text.
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
@@ -376,12 +463,14 @@ pub fn split_text_into_words(text: &str) -> Words {
 }
 
 /// Takes a text broken into semantic items and a font instance and
-/// scales the font accordingly.
+/// scales the font accordingly. Only the given `tab_leaders` are shaped,
+/// see `get_tab_leaders`.
 pub fn words_to_scaled_words(
     words: &Words,
     font_bytes: &[u8],
     font_index: u32,
     font_size_px: f32,
+    tab_leaders: &[TabLeader],
 ) -> ScaledWords {
 
     use text_shaping::{self, HbBuffer, HbFont, HbScaledFont};
@@ -394,6 +483,17 @@ pub fn words_to_scaled_words(
     let hb_shaped_space = text_shaping::shape_word_hb(&hb_space_buffer, &hb_scaled_font);
     let space_advance_px = hb_shaped_space.glyph_positions[0].x_advance as f32 / 128.0; // TODO: Half width for spaces?
     let space_codepoint = hb_shaped_space.glyph_infos[0].codepoint;
+    let (ascender_px, descender_px) = text_shaping::get_font_extents_hb(&hb_scaled_font);
+
+    // Glyph index 0 is the "missing glyph" of the font
+    let mut ellipsis = shape_single_word("\u{2026}", &hb_scaled_font);
+    if ellipsis.glyph_infos.iter().any(|info| info.codepoint == 0) {
+        ellipsis = shape_single_word("...", &hb_scaled_font);
+    }
+
+    let tab_leaders = tab_leaders.iter()
+        .map(|leader| (*leader, shape_single_word(leader.as_str(), &hb_scaled_font)))
+        .collect();
 
     let hb_buffer_entire_paragraph = HbBuffer::from_str(&words.internal_str);
     let hb_shaped_entire_paragraph = text_shaping::shape_word_hb(&hb_buffer_entire_paragraph, &hb_scaled_font);
@@ -452,10 +552,29 @@ pub fn words_to_scaled_words(
         longest_word_width: longest_word_width,
         space_advance_px,
         space_codepoint,
+        ascender_px,
+        descender_px,
+        ellipsis,
+        tab_leaders,
         font_size_px,
     }
 }
 
+/// Shapes a string that doesn't contain any spaces as one word
+fn shape_single_word(text: &str, hb_scaled_font: &HbScaledFont) -> ScaledWord {
+
+    use text_shaping::{self, HbBuffer};
+
+    let hb_buffer = HbBuffer::from_str(text);
+    let hb_shaped_word = text_shaping::shape_word_hb(&hb_buffer, hb_scaled_font);
+
+    ScaledWord {
+        glyph_infos: text_shaping::get_glyph_infos_hb(&hb_shaped_word.glyph_infos),
+        glyph_positions: text_shaping::get_glyph_positions_hb(&hb_shaped_word.glyph_positions),
+        word_width: text_shaping::get_word_visual_width_hb(&hb_shaped_word.glyph_positions),
+    }
+}
+
 /// Positions the words on the screen (does not layout any glyph positions!), necessary for estimating
 /// the intrinsic width + height of the text content.
 pub fn position_words(
@@ -466,15 +585,23 @@ pub fn position_words(
 ) -> WordPositions {
 
     use self::WordType::*;
-    use std::f32;
+    use std::{f32, cmp::Ordering};
 
     let space_advance = scaled_words.space_advance_px;
-    let word_spacing_px = space_advance * text_layout_options.word_spacing.unwrap_or(DEFAULT_WORD_SPACING);
-    let line_height_px = space_advance * text_layout_options.line_height.unwrap_or(DEFAULT_LINE_HEIGHT);
+    let word_spacing_px = get_word_spacing(scaled_words, text_layout_options);
+    let line_height_px = get_line_gap(scaled_words, text_layout_options, font_size_px);
     let tab_width_px = space_advance * text_layout_options.tab_width.unwrap_or(DEFAULT_TAB_WIDTH);
     let letter_spacing_px = text_layout_options.letter_spacing.unwrap_or(DEFAULT_LETTER_SPACING);
 
+    // With white-space: nowrap, the lines can grow infinitely wide
+    let wrap_width = match text_layout_options.white_space {
+        Some(StyleWhiteSpace::Nowrap) => None,
+        _ => text_layout_options.max_horizontal_width,
+    };
+
     let mut line_breaks = Vec::new();
+    let mut hard_line_breaks = Vec::new();
+    let mut leader_fills = Vec::new();
     let mut word_positions = Vec::new();
 
     let mut line_number = 0;
@@ -488,11 +615,12 @@ pub fn position_words(
             font_size_px,
             line_height_px,
             &text_layout_options.holes,
-            text_layout_options.max_horizontal_width,
+            wrap_width,
         );
 
         if let LineCaretIntersection::PushCaretOntoNextLine(_, _) = caret_intersection {
              line_breaks.push((current_word_idx, line_caret_x));
+             hard_line_breaks.push(false);
         }
 
         // Correct and advance the line caret position
@@ -503,13 +631,12 @@ pub fn position_words(
         );
     })}
 
-    advance_caret!(line_caret_x);
-
     if let Some(leading) = text_layout_options.leading {
-        line_caret_x += leading;
-        advance_caret!(line_caret_x);
+        line_caret_x = leading;
     }
 
+    advance_caret!(line_caret_x);
+
     // NOTE: word_idx increases only on words, not on other symbols!
     let mut word_idx = 0;
 
@@ -538,12 +665,13 @@ pub fn position_words(
             font_size_px,
             line_height_px,
             &text_layout_options.holes,
-            text_layout_options.max_horizontal_width,
+            wrap_width,
         );
 
         let mut is_line_break = false;
         if let LineCaretIntersection::PushCaretOntoNextLine(_, _) = caret_intersection {
             line_breaks.push((current_word_idx, line_caret_x));
+            hard_line_breaks.push(false);
             is_line_break = true;
         }
 
@@ -584,6 +712,7 @@ pub fn position_words(
             },
             Return => {
                 line_breaks.push((current_word_idx, line_caret_x));
+                hard_line_breaks.push(true);
                 line_number += 1;
                 let mut new_caret_x = 0.0;
                 advance_caret!(new_caret_x);
@@ -595,9 +724,32 @@ pub fn position_words(
                 line_caret_x = new_caret_x;
             },
             Tab => {
-                let mut new_caret_x = line_caret_x + word_spacing_px + tab_width_px;
+                let next_tab_stop = text_layout_options.tab_stops.iter()
+                    .filter(|tab_stop| tab_stop.position > line_caret_x)
+                    .min_by(|a, b| a.position.partial_cmp(&b.position).unwrap_or(Ordering::Equal));
+
+                let mut new_caret_x = match next_tab_stop {
+                    Some(tab_stop) => tab_stop.position,
+                    None => line_caret_x + word_spacing_px + tab_width_px,
+                };
+
+                let old_line_number = line_number;
+                let old_line_caret_x = line_caret_x;
                 advance_caret!(new_caret_x);
                 line_caret_x = new_caret_x;
+
+                // Only fill the space if the tab didn't wrap the line
+                if let Some(TabStop { leader: Some(leader), .. }) = next_tab_stop {
+                    if line_number == old_line_number {
+                        leader_fills.push(LeaderFill {
+                            leader: *leader,
+                            line_idx: line_breaks.len(),
+                            line_y: get_line_y_position(line_number, font_size_px, line_height_px),
+                            start_x: old_line_caret_x,
+                            end_x: line_caret_x,
+                        });
+                    }
+                }
             },
         }
     }
@@ -608,12 +760,17 @@ pub fn position_words(
             handle_word!();
         }
         line_breaks.push((current_word_idx, line_caret_x));
+        hard_line_breaks.push(true);
     }
 
     let trailing = line_caret_x;
     let number_of_lines = line_number + 1;
     let number_of_words = current_word_idx + 1;
 
+    if text_layout_options.direction == Some(StyleTextDirection::Rtl) {
+        mirror_lines(&mut word_positions, &mut leader_fills, &line_breaks, scaled_words, letter_spacing_px);
+    }
+
     let longest_line_width = line_breaks.iter().map(|(_word_idx, line_length)| *line_length).fold(0.0_f32, f32::max);
     let content_size_y = get_line_y_position(line_number, font_size_px, line_height_px);
     let content_size_x = text_layout_options.max_horizontal_width.unwrap_or(longest_line_width);
@@ -628,6 +785,37 @@ pub fn position_words(
         content_size,
         word_positions,
         line_breaks,
+        hard_line_breaks,
+        leader_fills,
+    }
+}
+
+/// Mirrors the words (and leader fills) of each line within the width of the line,
+/// so that the first word of the line ends up at the right edge
+fn mirror_lines(
+    word_positions: &mut [LayoutPoint],
+    leader_fills: &mut [LeaderFill],
+    line_breaks: &[(WordIndex, LineLength)],
+    scaled_words: &ScaledWords,
+    letter_spacing_px: f32,
+) {
+    let mut line_start_word = 0;
+
+    for (line_idx, (line_end_word, line_length)) in line_breaks.iter().enumerate() {
+        let line = line_start_word..(*line_end_word).min(word_positions.len());
+        line_start_word = *line_end_word;
+
+        for word_idx in line {
+            let scaled_word = &scaled_words.items[word_idx];
+            let word_width = scaled_word.word_width + letter_spacing_px * scaled_word.number_of_clusters().saturating_sub(1) as f32;
+            word_positions[word_idx].x = line_length - (word_positions[word_idx].x + word_width);
+        }
+
+        for leader_fill in leader_fills.iter_mut().filter(|leader_fill| leader_fill.line_idx == line_idx) {
+            let (start_x, end_x) = (line_length - leader_fill.end_x, line_length - leader_fill.start_x);
+            leader_fill.start_x = start_x;
+            leader_fill.end_x = end_x;
+        }
     }
 }
 
@@ -651,7 +839,8 @@ pub fn get_layouted_glyphs_unpositioned(
             .map(|(mut glyph, cluster_info)| {
                 glyph.point.x += word_position.x;
                 glyph.point.y += word_position.y;
-                glyph.point.x += letter_spacing_px * cluster_info.cluster_idx as f32;
+                // NOTE: cluster_idx starts at 1, the first cluster is not moved
+                glyph.point.x += letter_spacing_px * cluster_info.cluster_idx.saturating_sub(1) as f32;
                 glyph
             })
         )
@@ -666,14 +855,213 @@ pub fn get_layouted_glyphs_with_horizonal_alignment(
     alignment_horz: StyleTextAlignmentHorz,
 ) -> (LayoutedGlyphs, LineBreaks) {
     let mut glyphs = get_layouted_glyphs_unpositioned(word_positions, scaled_words);
+    let mut line_breaks = get_char_indices(&word_positions, &scaled_words);
+
+    // Justify before truncating, overflowing lines have no space left to distribute anyway
+    if alignment_horz == StyleTextAlignmentHorz::Justify {
+        justify_text(&mut glyphs.glyphs, &mut line_breaks, word_positions, scaled_words);
+    }
+
+    let text_layout_options = &word_positions.text_layout_options;
+    if let (Some(StyleTextOverflow::Ellipsis), Some(max_width)) = (text_layout_options.text_overflow, text_layout_options.max_horizontal_width) {
+        let letter_spacing_px = text_layout_options.letter_spacing.unwrap_or(0.0);
+        truncate_lines_with_ellipsis(&mut glyphs.glyphs, &mut line_breaks, scaled_words, max_width, letter_spacing_px);
+    }
 
     // Align glyphs horizontal
-    let line_breaks = get_char_indices(&word_positions, &scaled_words);
     align_text_horz(&mut glyphs.glyphs, alignment_horz, &line_breaks);
 
+    // The leaders are appended behind the glyphs of the words,
+    // so that the glyph indices in the line_breaks stay valid
+    let leader_glyphs = get_leader_glyphs(word_positions, scaled_words, alignment_horz, &line_breaks);
+    glyphs.glyphs.extend(leader_glyphs);
+
     (glyphs, line_breaks)
 }
 
+/// Repeats the leader characters in the `leader_fills` of the `word_positions`,
+/// shifted by the same amount as the (already aligned) line they are in
+fn get_leader_glyphs(
+    word_positions: &WordPositions,
+    scaled_words: &ScaledWords,
+    alignment_horz: StyleTextAlignmentHorz,
+    line_breaks: &LineBreaks,
+) -> Vec<GlyphInstance> {
+
+    use text_shaping;
+
+    // Same as in `align_text_horz`
+    let multiply_factor = match alignment_horz {
+        StyleTextAlignmentHorz::Center => 0.5,
+        StyleTextAlignmentHorz::Right | StyleTextAlignmentHorz::End => 1.0,
+        _ => 0.0,
+    };
+
+    let mut leader_glyphs = Vec::new();
+
+    for leader_fill in &word_positions.leader_fills {
+
+        let leader = match scaled_words.tab_leaders.get(&leader_fill.leader) {
+            Some(s) if s.word_width > 0.0 => s,
+            _ => continue,
+        };
+
+        let line_shift_x = line_breaks.get(leader_fill.line_idx)
+            .map(|(_, remaining_space_px)| remaining_space_px * multiply_factor)
+            .unwrap_or(0.0);
+
+        let leader_glyph_instances = text_shaping::get_glyph_instances_hb(&leader.glyph_infos, &leader.glyph_positions);
+        let leader_count = ((leader_fill.end_x - leader_fill.start_x) / leader.word_width).floor().max(0.0) as usize;
+
+        // The leaders end at the tab stop (instead of starting at the text in front of it),
+        // so that the leaders of multiple lines line up
+        for leader_idx in 0..leader_count {
+            let leader_x = leader_fill.end_x - (leader_count - leader_idx) as f32 * leader.word_width + line_shift_x;
+            leader_glyphs.extend(leader_glyph_instances.iter().map(|glyph| GlyphInstance {
+                index: glyph.index,
+                point: LayoutPoint::new(glyph.point.x + leader_x, glyph.point.y + leader_fill.line_y),
+            }));
+        }
+    }
+
+    leader_glyphs
+}
+
+/// Distributes the remaining space of each wrapped line evenly between the words
+/// of that line, so that the last word ends at the right edge of the `content_size`.
+/// Lines that end with a `\n` or the end of the text keep their left alignment.
+fn justify_text(
+    glyphs: &mut [GlyphInstance],
+    line_breaks: &mut LineBreaks,
+    word_positions: &WordPositions,
+    scaled_words: &ScaledWords,
+) {
+    let width = word_positions.content_size.width;
+    let letter_spacing_px = word_positions.text_layout_options.letter_spacing.unwrap_or(0.0);
+    let is_rtl = word_positions.text_layout_options.direction == Some(StyleTextDirection::Rtl);
+
+    // Index of the first glyph of each word, the glyphs are in the same order as the words
+    let mut word_glyph_starts = Vec::with_capacity(scaled_words.items.len() + 1);
+    let mut glyph_count = 0;
+    for scaled_word in &scaled_words.items {
+        word_glyph_starts.push(glyph_count);
+        glyph_count += scaled_word.glyph_infos.len();
+    }
+    word_glyph_starts.push(glyph_count);
+
+    let mut line_start_word = 0;
+
+    for (((line_end_word, _), is_hard_break), (_, remaining_space_px)) in word_positions.line_breaks.iter()
+        .zip(word_positions.hard_line_breaks.iter())
+        .zip(line_breaks.iter_mut())
+    {
+        let line = line_start_word..*line_end_word;
+        line_start_word = *line_end_word;
+
+        if *is_hard_break || line.len() < 2 || line.end > word_positions.word_positions.len() {
+            continue;
+        }
+
+        // The rightmost word decides the width - in right-to-left text, that's the first word
+        let last_word_idx = if is_rtl { line.start } else { line.end - 1 };
+        let last_word = &scaled_words.items[last_word_idx];
+        let last_word_letter_spacing = letter_spacing_px * last_word.number_of_clusters().saturating_sub(1) as f32;
+        let line_width = word_positions.word_positions[last_word_idx].x + last_word.word_width + last_word_letter_spacing;
+        if line_width >= width {
+            continue;
+        }
+
+        let number_of_gaps = line.len() - 1;
+        let space_per_gap = (width - line_width) / number_of_gaps as f32;
+
+        for (word_in_line, word_idx) in line.enumerate() {
+            let gap_idx = if is_rtl { number_of_gaps - word_in_line } else { word_in_line };
+            let word_glyphs = word_glyph_starts[word_idx].min(glyphs.len())..word_glyph_starts[word_idx + 1].min(glyphs.len());
+            for glyph in &mut glyphs[word_glyphs] {
+                glyph.point.x += space_per_gap * gap_idx as f32;
+            }
+        }
+
+        *remaining_space_px = 0.0;
+    }
+}
+
+/// Cuts off the glyphs of each line that is wider than `max_width` and appends the
+/// ellipsis of the `scaled_words` instead, so that the line (including the ellipsis)
+/// fits into the `max_width` again. Updates the `line_breaks` to the new glyph count
+/// and line width.
+///
+/// The `letter_spacing_px` is added in front of the ellipsis and between its characters,
+/// the same way as between the characters of the words.
+fn truncate_lines_with_ellipsis(
+    glyphs: &mut Vec<GlyphInstance>,
+    line_breaks: &mut LineBreaks,
+    scaled_words: &ScaledWords,
+    max_width: f32,
+    letter_spacing_px: f32,
+) {
+    use text_shaping;
+
+    let ellipsis = &scaled_words.ellipsis;
+    let ellipsis_glyphs = text_shaping::get_glyph_instances_hb(&ellipsis.glyph_infos, &ellipsis.glyph_positions)
+        .into_iter()
+        .zip(ellipsis.cluster_iter())
+        .map(|(mut glyph, cluster_info)| {
+            glyph.point.x += letter_spacing_px * cluster_info.cluster_idx.saturating_sub(1) as f32;
+            glyph
+        })
+        .collect::<Vec<_>>();
+    let ellipsis_width = ellipsis.word_width + letter_spacing_px * ellipsis.number_of_clusters().saturating_sub(1) as f32;
+
+    // The glyphs are in the same order as the glyphs of the scaled words
+    let glyph_advances = scaled_words.items.iter()
+        .flat_map(|word| word.glyph_positions.iter())
+        .map(text_shaping::get_glyph_advance_hb)
+        .collect::<Vec<f32>>();
+
+    let right_edge = |glyph_idx: usize, glyph: &GlyphInstance| {
+        glyph.point.x + glyph_advances.get(glyph_idx).cloned().unwrap_or(0.0)
+    };
+
+    let mut truncated_glyphs = Vec::with_capacity(glyphs.len());
+    let mut line_start = 0;
+
+    for (line_end, remaining_space_px) in line_breaks.iter_mut() {
+
+        let line_end_clamped = (*line_end).min(glyphs.len());
+        let line = line_start.min(line_end_clamped)..line_end_clamped;
+        line_start = line_end_clamped;
+
+        let is_overflowing = line.clone().any(|glyph_idx| right_edge(glyph_idx, &glyphs[glyph_idx]) > max_width);
+
+        if is_overflowing {
+            // The letter spacing separates the last glyph that fits from the ellipsis
+            let max_right_edge = max_width - ellipsis_width - letter_spacing_px;
+            let glyphs_that_fit = line.clone()
+                .take_while(|glyph_idx| right_edge(*glyph_idx, &glyphs[*glyph_idx]) <= max_right_edge)
+                .count();
+            let caret_x = match glyphs_that_fit {
+                0 => glyphs[line.start].point.x,
+                n => right_edge(line.start + n - 1, &glyphs[line.start + n - 1]) + letter_spacing_px,
+            };
+            let line_y = glyphs[line.start].point.y;
+
+            truncated_glyphs.extend_from_slice(&glyphs[line.start..(line.start + glyphs_that_fit)]);
+            truncated_glyphs.extend(ellipsis_glyphs.iter().map(|glyph| GlyphInstance {
+                index: glyph.index,
+                point: LayoutPoint::new(glyph.point.x + caret_x, glyph.point.y + line_y),
+            }));
+            *remaining_space_px = max_width - (caret_x + ellipsis_width);
+        } else {
+            truncated_glyphs.extend_from_slice(&glyphs[line]);
+        }
+
+        *line_end = truncated_glyphs.len();
+    }
+
+    *glyphs = truncated_glyphs;
+}
+
 /// Returns the final glyphs and positions them relative to the `rect_offset`,
 /// ready for webrender to display
 pub fn get_layouted_glyphs(
@@ -696,6 +1084,34 @@ pub fn get_layouted_glyphs(
     glyphs
 }
 
+/// Returns the distance from the top of the bounding box to the baseline of the
+/// first line, after the text has been aligned vertically (see `get_layouted_glyphs`).
+///
+/// `line_height_px` is the gap between two lines, see `get_line_gap`.
+pub fn get_first_baseline(
+    word_positions: &WordPositions,
+    alignment_vert: StyleTextAlignmentVert,
+    bounding_size_height_px: f32,
+    line_height_px: f32,
+) -> f32 {
+
+    let first_line_baseline = get_line_y_position(0, word_positions.font_size_px, line_height_px);
+
+    let multiply_factor = match alignment_vert {
+        StyleTextAlignmentVert::Top => 0.0,
+        StyleTextAlignmentVert::Center => 0.5,
+        StyleTextAlignmentVert::Bottom => 1.0,
+    };
+
+    // Same as in `align_text_vert`
+    let vertical_shift = match get_vertical_overflow(word_positions, bounding_size_height_px) {
+        TextOverflow::InBounds(remaining_space_px) => remaining_space_px * multiply_factor,
+        TextOverflow::IsOverflowing(_) => 0.0,
+    };
+
+    first_line_baseline + vertical_shift
+}
+
 /// Given a width, returns the vertical height and width of the text
 pub fn get_positioned_word_bounding_box(word_positions: &WordPositions) -> LayoutSize {
     word_positions.content_size
@@ -745,6 +1161,29 @@ pub fn get_char_indices(word_positions: &WordPositions, scaled_words: &ScaledWor
     }).collect()
 }
 
+/// Vertical distance between the bottom of two adjacent lines, in pixels
+pub fn get_line_stride(scaled_words: &ScaledWords, text_layout_options: &TextLayoutOptions, font_size_px: f32) -> f32 {
+    let line_height_px = get_line_gap(scaled_words, text_layout_options, font_size_px);
+    get_line_y_position(1, font_size_px, line_height_px) - get_line_y_position(0, font_size_px, line_height_px)
+}
+
+/// Vertical gap between the bottom of one line and the top of the next line, in pixels.
+///
+/// `line-height` is a multiple of the font size (`line-height: 1.5` = 150% of the font size),
+/// so the gap can be negative for a `line-height` below 1.0. Without a `line-height`,
+/// the gap is as large as a space character.
+pub fn get_line_gap(scaled_words: &ScaledWords, text_layout_options: &TextLayoutOptions, font_size_px: f32) -> f32 {
+    match text_layout_options.line_height {
+        Some(line_height) => font_size_px * (line_height - 1.0),
+        None => scaled_words.space_advance_px,
+    }
+}
+
+/// Width of a space between two words, in pixels
+pub fn get_word_spacing(scaled_words: &ScaledWords, text_layout_options: &TextLayoutOptions) -> f32 {
+    scaled_words.space_advance_px + text_layout_options.word_spacing.unwrap_or(DEFAULT_WORD_SPACING)
+}
+
 /// For a given line number (**NOTE: 0-indexed!**), calculates the Y
 /// position of the bottom left corner
 pub fn get_line_y_position(line_number: usize, font_size_px: f32, line_height_px: f32) -> f32 {
@@ -781,71 +1220,48 @@ fn caret_intersects_with_holes(
     max_width: Option<f32>,
 ) -> LineCaretIntersection {
 
-    let mut new_line_caret_x = None;
+    let mut caret_x = line_caret_x;
     let mut line_advance = 0;
+    let mut caret_moved = false;
 
     // If the caret is outside of the max_width, move it to the start of a new line
     if let Some(max_width) = max_width {
         if line_caret_x > max_width {
-            new_line_caret_x = Some(0.0);
+            caret_x = 0.0;
             line_advance += 1;
+            caret_moved = true;
         }
     }
 
-    for hole in holes {
-
-        let mut should_move_caret = false;
-        let mut current_line_advance = 0;
-        let mut new_line_number = line_number + current_line_advance;
-        let mut current_caret = LayoutPoint::new(
-            new_line_caret_x.unwrap_or(line_caret_x),
-            get_line_y_position(new_line_number, font_size_px, line_height_px)
-        );
-
-        // NOTE: holes need to be sorted by Y origin (from smallest to largest Y),
-        // and be sorted from left to right
-        while hole.contains(&current_caret) {
-            should_move_caret = true;
-            if let Some(max_width) = max_width {
-                if hole.origin.x + hole.size.width >= max_width {
-                    // Need to break the line here
-                    current_line_advance += 1;
-                    new_line_number = line_number + current_line_advance;
-                    current_caret = LayoutPoint::new(
-                        new_line_caret_x.unwrap_or(line_caret_x),
-                        get_line_y_position(new_line_number, font_size_px, line_height_px)
-                    );
-                } else {
-                    new_line_number = line_number + current_line_advance;
-                    current_caret = LayoutPoint::new(
-                        hole.origin.x + hole.size.width,
-                        get_line_y_position(new_line_number, font_size_px, line_height_px)
-                    );
-                }
-            } else {
-                // No max width, so no need to break the line, move the caret to the right side of the hole
-                new_line_number = line_number + current_line_advance;
-                current_caret = LayoutPoint::new(
-                    hole.origin.x + hole.size.width,
-                    get_line_y_position(new_line_number, font_size_px, line_height_px)
-                );
-            }
-        }
+    // Move the caret to the right of every hole it is in, or onto the next line if the hole
+    // reaches up to the max_width. Since the caret is then checked against all holes again,
+    // the holes don't need to be sorted.
+    loop {
+        let caret = LayoutPoint::new(caret_x, get_line_y_position(line_number + line_advance, font_size_px, line_height_px));
+        let hole = match holes.iter().find(|hole| hole.contains(&caret)) {
+            Some(s) => s,
+            None => break,
+        };
 
-        if should_move_caret {
-            new_line_caret_x = Some(current_caret.x);
-            line_advance += current_line_advance;
+        caret_moved = true;
+        let hole_end_x = hole.origin.x + hole.size.width;
+        match max_width {
+            Some(max_width) if hole_end_x >= max_width => {
+                // Need to break the line here
+                caret_x = 0.0;
+                line_advance += 1;
+            },
+            // Move the caret to the right side of the hole
+            _ => caret_x = hole_end_x,
         }
     }
 
-    if let Some(new_line_caret_x) = new_line_caret_x {
-        if line_advance == 0 {
-            LineCaretIntersection::AdvanceCaretTo(new_line_caret_x)
-        } else {
-            LineCaretIntersection::PushCaretOntoNextLine(line_advance, new_line_caret_x)
-        }
-    } else {
+    if !caret_moved {
         LineCaretIntersection::NoIntersection
+    } else if line_advance == 0 {
+        LineCaretIntersection::AdvanceCaretTo(caret_x)
+    } else {
+        LineCaretIntersection::PushCaretOntoNextLine(line_advance, caret_x)
     }
 }
 
@@ -900,10 +1316,12 @@ pub fn align_text_horz(
     // // i.e. the last line has to end with the last glyph
     // assert!(glyphs.len() - 1 == line_breaks[line_breaks.len() - 1].0);
 
+    // NOTE: Start / End should already be resolved via `to_physical`,
+    // so they are treated like left-to-right text here
     let multiply_factor = match alignment {
-        Left => return,
+        Left | Start | Justify => return, // justified lines are already positioned
         Center => 0.5, // move the line by the half width
-        Right => 1.0, // move the line by the full width
+        Right | End => 1.0, // move the line by the full width
     };
 
     // If we have the characters "ABC\n\nDEF", this will result in:
@@ -982,6 +1400,151 @@ pub fn add_origin(positioned_glyphs: &mut [GlyphInstance], x: f32, y: f32) {
     }
 }
 
+/// Returns the `(start, end)` character ranges of all non-overlapping occurrences
+/// of `needle` in the `words`. The ranges index into the normalized characters of the
+/// text, the same way that `Word::start` and `Word::end` do.
+pub fn find_text_matches(words: &Words, needle: &str, case_sensitive: bool) -> Vec<(usize, usize)> {
+
+    // Only take the first char of the lowercase mapping, so that the
+    // character indices of the haystack don't shift
+    let fold = |c: char| if case_sensitive { c } else { c.to_lowercase().next().unwrap_or(c) };
+
+    let needle = needle.chars().map(fold).collect::<Vec<char>>();
+    let haystack = words.internal_chars.iter().cloned().map(fold).collect::<Vec<char>>();
+
+    let mut matches = Vec::new();
+
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return matches;
+    }
+
+    let mut char_idx = 0;
+    while char_idx + needle.len() <= haystack.len() {
+        if haystack[char_idx..(char_idx + needle.len())] == needle[..] {
+            matches.push((char_idx, char_idx + needle.len()));
+            char_idx += needle.len();
+        } else {
+            char_idx += 1;
+        }
+    }
+
+    matches
+}
+
+/// Searches for the `needle` in a laid-out text and returns the on-screen bounds of each match.
+///
+/// Since a match can span multiple words (and therefore multiple lines), each
+/// match returns one rectangle per word that it touches. The `layouted_glyphs` have
+/// to be the glyphs returned by `get_layouted_glyphs()`, so that the bounds already
+/// include the text alignment and the origin of the text rectangle.
+pub fn search_layouted_glyphs(
+    words: &Words,
+    scaled_words: &ScaledWords,
+    layouted_glyphs: &LayoutedGlyphs,
+    needle: &str,
+    case_sensitive: bool,
+) -> Vec<Vec<LayoutRect>> {
+
+    use text_shaping::get_glyph_instances_hb;
+
+    let font_size_px = scaled_words.font_size_px;
+
+    // (word, scaled word, offset of the first glyph of the word in the layouted_glyphs)
+    let mut glyph_offset = 0;
+    let word_glyphs = words.items.iter()
+        .filter(|w| w.word_type == WordType::Word)
+        .zip(scaled_words.items.iter())
+        .map(|(word, scaled_word)| {
+            let offset = glyph_offset;
+            glyph_offset += scaled_word.glyph_infos.len();
+            (word, scaled_word, offset)
+        })
+        .collect::<Vec<_>>();
+
+    find_text_matches(words, needle, case_sensitive).into_iter().map(|(match_start, match_end)| {
+        word_glyphs.iter().filter_map(|(word, scaled_word, glyph_offset)| {
+
+            let start = match_start.max(word.start);
+            let end = match_end.min(word.end);
+            if start >= end {
+                return None;
+            }
+
+            // Unshaped glyph advances, necessary to get the width of the last glyph
+            let glyph_advances = get_glyph_instances_hb(&scaled_word.glyph_infos, &scaled_word.glyph_positions);
+            let mut min_x = None::<f32>;
+            let mut max_x = None::<f32>;
+            let mut baseline = 0.0;
+
+            // NOTE: Assumes one cluster per character, which is true for most non-ligature text
+            for cluster in scaled_word.cluster_iter() {
+                let char_in_word = cluster.cluster_idx - 1;
+                if char_in_word < start - word.start || char_in_word >= end - word.start {
+                    continue;
+                }
+
+                let glyph = match layouted_glyphs.glyphs.get(glyph_offset + cluster.glyph_idx) {
+                    Some(s) => s,
+                    None => continue,
+                };
+
+                let advance = match glyph_advances.get(cluster.glyph_idx + 1) {
+                    Some(next) => next.point.x - glyph_advances[cluster.glyph_idx].point.x,
+                    None => scaled_word.word_width - glyph_advances[cluster.glyph_idx].point.x,
+                };
+
+                min_x = Some(min_x.unwrap_or(glyph.point.x).min(glyph.point.x));
+                max_x = Some(max_x.unwrap_or(glyph.point.x + advance).max(glyph.point.x + advance));
+                baseline = glyph.point.y;
+            }
+
+            let (min_x, max_x) = (min_x?, max_x?);
+
+            Some(LayoutRect::new(
+                LayoutPoint::new(min_x, baseline - font_size_px),
+                LayoutSize::new(max_x - min_x, font_size_px * 1.2),
+            ))
+        }).collect::<Vec<LayoutRect>>()
+    })
+    .filter(|rects| !rects.is_empty())
+    .collect()
+}
+
+/// Returns the horizontal extent of each line of the layouted glyphs,
+/// i.e. for drawing the lines of a `text-decoration`
+pub fn get_line_extents(layouted_glyphs: &LayoutedGlyphs, scaled_words: &ScaledWords) -> Vec<LineExtent> {
+
+    use std::collections::BTreeMap;
+    use text_shaping::get_glyph_advance_hb;
+
+    // The glyphs may have been reordered / truncated (`text-overflow: ellipsis`),
+    // so the advance of a glyph has to be looked up via its glyph index
+    let glyph_advances = scaled_words.items.iter()
+        .chain(::std::iter::once(&scaled_words.ellipsis))
+        .flat_map(|word| word.glyph_infos.iter().zip(word.glyph_positions.iter()))
+        .map(|(info, position)| (info.codepoint, get_glyph_advance_hb(position)))
+        .collect::<BTreeMap<u32, f32>>();
+
+    // Glyphs with a vertical offset (i.e. accents) still belong to the same line
+    let max_baseline_offset = scaled_words.font_size_px / 2.0;
+
+    let mut lines: Vec<LineExtent> = Vec::new();
+
+    for glyph in &layouted_glyphs.glyphs {
+        let x_end = glyph.point.x + glyph_advances.get(&glyph.index).cloned().unwrap_or(0.0);
+        if let Some(line) = lines.last_mut() {
+            if (line.baseline - glyph.point.y).abs() < max_baseline_offset {
+                line.x_start = line.x_start.min(glyph.point.x);
+                line.x_end = line.x_end.max(x_end);
+                continue;
+            }
+        }
+        lines.push(LineExtent { x_start: glyph.point.x, x_end, baseline: glyph.point.y });
+    }
+
+    lines
+}
+
 #[test]
 fn test_split_words() {
 
@@ -1072,6 +1635,35 @@ fn test_get_line_y_position() {
     assert_eq!(get_line_y_position(2, 20.0, 5.0), 70.0);
 }
 
+#[test]
+fn test_get_first_baseline() {
+
+    // Two lines of 20px text with line-height: 1.5 (10px gap between the lines)
+    let line_height_px = 10.0;
+    let word_positions = WordPositions {
+        font_size_px: 20.0,
+        text_layout_options: TextLayoutOptions { line_height: Some(1.5), .. Default::default() },
+        word_positions: vec![LayoutPoint::new(0.0, 20.0), LayoutPoint::new(0.0, 50.0)],
+        line_breaks: vec![(1, 40.0), (2, 40.0)],
+        leader_fills: Vec::new(),
+        hard_line_breaks: vec![false, true],
+        trailing: 40.0,
+        number_of_words: 2,
+        number_of_lines: 2,
+        content_size: LayoutSize::new(40.0, get_line_y_position(1, 20.0, line_height_px)),
+    };
+
+    // The line gap only goes between the lines, the first line always starts at the top
+    assert_eq!(get_first_baseline(&word_positions, StyleTextAlignmentVert::Top, 100.0, line_height_px), 20.0);
+
+    // The text is 50px high, so it is moved down by half of the remaining 50px
+    assert_eq!(get_first_baseline(&word_positions, StyleTextAlignmentVert::Center, 100.0, line_height_px), 45.0);
+    assert_eq!(get_first_baseline(&word_positions, StyleTextAlignmentVert::Bottom, 100.0, line_height_px), 70.0);
+
+    // Overflowing text isn't aligned
+    assert_eq!(get_first_baseline(&word_positions, StyleTextAlignmentVert::Bottom, 30.0, line_height_px), 20.0);
+}
+
 // Scenario 1:
 //
 // +---------+
@@ -1208,3 +1800,168 @@ fn test_caret_intersects_with_holes_4() {
 
     assert_eq!(result, LineCaretIntersection::NoIntersection);
 }
+
+// Scenario 5:
+//
+// +-----------+----+
+// |  -------->|+   |
+// |+          |    |
+// |           +----+
+// |                |
+// +----------------+
+// rectangle: 100x100 @ 300.0x, 0.0y (floating on the right)
+// max-width: 400px, line-height 1.0 (i.e. no gap between the lines), font-size: 20
+// cursor is at: 350x, 20y
+// expect cursor to advance to 0x, 40y (+= 1 line, left of the hole is free)
+//
+#[test]
+fn test_caret_intersects_with_holes_5() {
+    let line_caret_x = 350.0;
+    let line_number = 0;
+    let font_size_px = 20.0;
+    // line_height_px is the gap between two lines, line-height 1.0 has no gap
+    let line_height_px = 0.0;
+    let max_width = Some(400.0);
+    let holes = vec![LayoutRect::new(LayoutPoint::new(300.0, 0.0), LayoutSize::new(100.0, 100.0))];
+
+    let result = caret_intersects_with_holes(
+        line_caret_x,
+        line_number,
+        font_size_px,
+        line_height_px,
+        &holes,
+        max_width,
+    );
+
+    assert_eq!(result, LineCaretIntersection::PushCaretOntoNextLine(1, 0.0));
+}
+
+#[test]
+fn test_truncate_lines_with_ellipsis_letter_spacing() {
+
+    use std::mem;
+
+    // Word of `count` glyphs (one glyph per cluster) with the given advance in pixels
+    let scaled_word = |count: u32, advance_px: f32| {
+        let glyph_infos = (0..count).map(|i| {
+            let mut info: GlyphInfo = unsafe { mem::zeroed() };
+            info.codepoint = i + 1;
+            info.cluster = i;
+            info
+        }).collect::<Vec<_>>();
+        let glyph_positions = (0..count).map(|_| {
+            let mut position: GlyphPosition = unsafe { mem::zeroed() };
+            position.x_advance = (advance_px * 128.0) as i32;
+            position
+        }).collect::<Vec<_>>();
+        ScaledWord { glyph_infos, glyph_positions, word_width: advance_px * count as f32 }
+    };
+
+    let scaled_words = ScaledWords {
+        font_size_px: 10.0,
+        items: vec![scaled_word(6, 10.0)],
+        longest_word_width: 60.0,
+        space_advance_px: 5.0,
+        space_codepoint: 0,
+        ascender_px: 8.0,
+        descender_px: -2.0,
+        ellipsis: scaled_word(3, 3.0),
+        tab_leaders: BTreeMap::new(),
+    };
+
+    // 6 glyphs with an advance of 10px and a letter-spacing of 2px
+    let letter_spacing_px = 2.0;
+    let mut glyphs = (0..6).map(|i| GlyphInstance {
+        index: i + 1,
+        point: LayoutPoint::new(i as f32 * (10.0 + letter_spacing_px), 0.0),
+    }).collect::<Vec<_>>();
+    let mut line_breaks = vec![(6, 0.0)];
+
+    truncate_lines_with_ellipsis(&mut glyphs, &mut line_breaks, &scaled_words, 47.0, letter_spacing_px);
+
+    // The ellipsis is 3 * 3px + 2 * 2px wide, plus 2px in front of it: only 2 glyphs fit
+    let x_positions = glyphs.iter().map(|glyph| glyph.point.x).collect::<Vec<_>>();
+    assert_eq!(x_positions, vec![0.0, 12.0, 24.0, 29.0, 34.0]);
+    assert_eq!(line_breaks, vec![(5, 10.0)]);
+}
+
+#[test]
+fn test_position_words_tab_stops() {
+
+    use std::mem;
+
+    // Word of one glyph with the given advance in pixels
+    let scaled_word = |codepoint: u32, advance_px: f32| {
+        let mut info: GlyphInfo = unsafe { mem::zeroed() };
+        info.codepoint = codepoint;
+        let mut position: GlyphPosition = unsafe { mem::zeroed() };
+        position.x_advance = (advance_px * 128.0) as i32;
+        ScaledWord { glyph_infos: vec![info], glyph_positions: vec![position], word_width: advance_px }
+    };
+
+    let words = split_text_into_words("a\tb\tc\td");
+
+    let mut tab_leaders = BTreeMap::new();
+    tab_leaders.insert(TabLeader::Dots, scaled_word(99, 4.0));
+
+    let scaled_words = ScaledWords {
+        font_size_px: 10.0,
+        items: (1..5).map(|codepoint| scaled_word(codepoint, 10.0)).collect(),
+        longest_word_width: 10.0,
+        space_advance_px: 5.0,
+        space_codepoint: 0,
+        ascender_px: 8.0,
+        descender_px: -2.0,
+        ellipsis: scaled_word(100, 3.0),
+        tab_leaders,
+    };
+
+    let text_layout_options = TextLayoutOptions {
+        tab_width: Some(4.0),
+        tab_stops: vec![TabStop::new(100.0), TabStop::new(50.0).with_leader(TabLeader::Dots)],
+        .. Default::default()
+    };
+
+    let word_positions = position_words(&words, &scaled_words, &text_layout_options, 10.0);
+
+    // The tabs advance to the next tab stop right of the caret, behind
+    // the last tab stop they advance by the space + the tab width
+    let x_positions = word_positions.word_positions.iter().map(|p| p.x).collect::<Vec<_>>();
+    assert_eq!(x_positions, vec![0.0, 50.0, 100.0, 135.0]);
+
+    // Only the space in front of the first tab stop is filled
+    assert_eq!(word_positions.leader_fills.len(), 1);
+    let leader_fill = word_positions.leader_fills[0];
+    assert_eq!(leader_fill.leader, TabLeader::Dots);
+    assert_eq!((leader_fill.start_x, leader_fill.end_x), (10.0, 50.0));
+
+    // The leaders end at the tab stop: 10 dots with an advance of 4px fit into the 40px
+    let leader_glyphs = get_leader_glyphs(&word_positions, &scaled_words, StyleTextAlignmentHorz::Left, &vec![(4, 0.0)]);
+    assert_eq!(leader_glyphs.len(), 10);
+    assert!(leader_glyphs.iter().all(|glyph| glyph.index == 99));
+    assert_eq!(leader_glyphs.first().map(|glyph| glyph.point.x), Some(10.0));
+    assert_eq!(leader_glyphs.last().map(|glyph| glyph.point.x), Some(46.0));
+}
+
+#[test]
+fn test_get_tab_leaders() {
+    let tab_stops = vec![
+        TabStop::new(300.0).with_leader(TabLeader::Underline),
+        TabStop::new(100.0),
+        TabStop::new(200.0).with_leader(TabLeader::Dots),
+        TabStop::new(400.0).with_leader(TabLeader::Underline),
+    ];
+    // Only the leaders that are used have to be shaped
+    assert_eq!(get_tab_leaders(&tab_stops), vec![TabLeader::Dots, TabLeader::Underline]);
+    assert_eq!(get_tab_leaders(&[]), Vec::new());
+}
+
+#[test]
+fn test_find_text_matches() {
+    let words = split_text_into_words("Hello world, hello\nHELLO");
+    assert_eq!(find_text_matches(&words, "hello", false), vec![(0, 5), (13, 18), (19, 24)]);
+    assert_eq!(find_text_matches(&words, "hello", true), vec![(13, 18)]);
+    assert_eq!(find_text_matches(&words, "o w", false), vec![(4, 7)]);
+    assert_eq!(find_text_matches(&words, "", false), Vec::new());
+    assert_eq!(find_text_matches(&words, "xyz", false), Vec::new());
+}
@@ -1,19 +1,19 @@
 //! Contains functions for laying out single words (uses HarfBuzz for context-aware font shaping).
 //! Right now, words are laid out on a word-per-word basis, no inter-word font shaping is done.
 
-use std::{slice, ptr, u32, ops::Deref, os::raw::{c_char, c_uint}};
+use std::{slice, ptr, u32, mem, ops::Deref, os::raw::{c_char, c_uint}};
 use webrender::api::{LayoutPoint, GlyphInstance as WrGlyphInstance};
 use harfbuzz_sys::{
     hb_blob_create, hb_blob_destroy,
     hb_font_create, hb_font_destroy,
     hb_face_create, hb_face_destroy,
     hb_buffer_create, hb_buffer_destroy,
-    hb_shape, hb_font_set_scale, hb_buffer_add_utf8, hb_ot_font_set_funcs,
+    hb_shape, hb_font_set_scale, hb_buffer_add_utf8, hb_ot_font_set_funcs, hb_font_get_h_extents,
     hb_buffer_get_glyph_infos, hb_buffer_get_glyph_positions,
     hb_buffer_guess_segment_properties, hb_buffer_allocation_successful,
     hb_blob_t, hb_memory_mode_t, hb_buffer_t,
     hb_glyph_position_t, hb_glyph_info_t, hb_font_t, hb_face_t,
-    hb_feature_t, hb_tag_t,
+    hb_feature_t, hb_tag_t, hb_font_extents_t,
     HB_MEMORY_MODE_READONLY,
 };
 
@@ -232,7 +232,32 @@ pub(crate) fn shape_word_hb<'a>(
 }
 
 pub(crate) fn get_word_visual_width_hb(glyph_positions: &[GlyphPosition]) -> f32 {
-    glyph_positions.iter().map(|pos| pos.x_advance as f32 / HB_SCALE_FACTOR).sum()
+    glyph_positions.iter().map(get_glyph_advance_hb).sum()
+}
+
+pub(crate) fn get_glyph_advance_hb(glyph_position: &GlyphPosition) -> f32 {
+    glyph_position.x_advance as f32 / HB_SCALE_FACTOR
+}
+
+/// Returns the (ascender, descender) of the scaled font in pixels,
+/// relative to the baseline (so the descender is usually negative)
+pub(crate) fn get_font_extents_hb(scaled_font: &HbScaledFont) -> (f32, f32) {
+    let mut extents: hb_font_extents_t = unsafe { mem::zeroed() };
+    unsafe { hb_font_get_h_extents(scaled_font.font.hb_font, &mut extents) };
+    (extents.ascender as f32 / HB_SCALE_FACTOR, extents.descender as f32 / HB_SCALE_FACTOR)
+}
+
+/// Returns the indices of the glyphs that the characters of the `text` are drawn
+/// with, without the glyphs that are missing in the font (glyph index 0)
+pub(crate) fn get_glyph_indices_hb(font_bytes: &[u8], font_index: u32, font_size_px: f32, text: &str) -> Vec<u32> {
+    let hb_font = HbFont::from_bytes(font_bytes, font_index);
+    let hb_scaled_font = HbScaledFont::from_font(&hb_font, font_size_px);
+    let hb_buffer = HbBuffer::from_str(text);
+    let hb_shaped_text = shape_word_hb(&hb_buffer, &hb_scaled_font);
+    hb_shaped_text.glyph_infos.iter()
+        .map(|glyph_info| glyph_info.codepoint)
+        .filter(|glyph_index| *glyph_index != 0)
+        .collect()
 }
 
 pub(crate) fn get_glyph_infos_hb(glyph_infos: &[GlyphInfo]) -> Vec<GlyphInfo> {
@@ -2,15 +2,15 @@ use std::{
     fmt,
     collections::BTreeMap,
 };
-use azul_css::{ Css, CssDeclaration, CssProperty, CssPropertyType };
+use azul_css::{ Css, CssViewport, CssDeclaration, CssProperty, CssPropertyType, StyleCursor };
 use webrender::api::HitTestItem;
 use {
     FastHashMap,
     id_tree::{Arena, NodeId, NodeDataContainer},
     dom::{Dom, NodeData, DomString},
     ui_state::UiState,
-    style::HoverGroup,
-    callbacks::FocusTarget,
+    style::{HoverGroup, StyleCache},
+    callbacks::{FocusTarget, ComputedStyle},
 };
 
 pub struct UiDescription<T> {
@@ -25,6 +25,15 @@ pub struct UiDescription<T> {
     /// that have a non-:hover path, for example if we have `#thing:hover`, then all nodes selected by `#thing`
     /// need to get a TagId, otherwise, they can't be hit-tested.
     pub(crate) selected_hover_nodes: BTreeMap<NodeId, HoverGroup>,
+    /// Styles of the `::scrollbar`, `::track` and `::thumb` pseudo-elements, only contains
+    /// the nodes that are selected by at least one scrollbar rule
+    pub(crate) scrollbar_styles: BTreeMap<NodeId, ScrollbarStyledNode>,
+    /// Two `UiDescription`s with the same `layout_generation` have the same layout: it is only
+    /// inherited from the previous frame if none of the nodes changed in a way that affects the
+    /// layout (see `LayoutCacheKey`)
+    pub(crate) layout_generation: usize,
+    /// Results of the cascade, so that the next frame only has to restyle the nodes that changed
+    pub(crate) style_cache: StyleCache,
 }
 
 impl<T> fmt::Debug for UiDescription<T> {
@@ -35,12 +44,14 @@ impl<T> fmt::Debug for UiDescription<T> {
             styled_nodes: {:?},
             dynamic_css_overrides: {:?},
             selected_hover_nodes: {:?},
+            scrollbar_styles: {:?},
         }}",
             self.ui_descr_arena,
             self.ui_descr_root,
             self.styled_nodes,
             self.dynamic_css_overrides,
             self.selected_hover_nodes,
+            self.scrollbar_styles,
         )
     }
 }
@@ -53,6 +64,9 @@ impl<T> Clone for UiDescription<T> {
             styled_nodes: self.styled_nodes.clone(),
             dynamic_css_overrides: self.dynamic_css_overrides.clone(),
             selected_hover_nodes: self.selected_hover_nodes.clone(),
+            scrollbar_styles: self.scrollbar_styles.clone(),
+            layout_generation: self.layout_generation,
+            style_cache: self.style_cache.clone(),
         }
     }
 }
@@ -68,6 +82,7 @@ impl<T> Default for UiDescription<T> {
         Self::match_css_to_dom(
             &mut default_dom.into_ui_state(),
             &Css::default(),
+            CssViewport::default(),
             &mut focused_node,
             &mut focus_target,
             &hovered_nodes,
@@ -79,33 +94,135 @@ impl<T> Default for UiDescription<T> {
 impl<T> UiDescription<T> {
     /// Applies the styles to the nodes calculated from the `layout_screen`
     /// function and calculates the final display list that is submitted to the
-    /// renderer.
+    /// renderer. Stylesheets whose `@media` queries don't match the `viewport`
+    /// are ignored.
     pub fn match_css_to_dom(
         ui_state: &mut UiState<T>,
         style: &Css,
+        viewport: CssViewport,
         focused_node: &mut Option<NodeId>,
         pending_focus_target: &mut Option<FocusTarget>,
         hovered_nodes: &BTreeMap<NodeId, HitTestItem>,
         is_mouse_down: bool,
     ) -> Self
     {
+        Self::match_css_to_dom_inner(ui_state, style, viewport, focused_node, pending_focus_target, hovered_nodes, is_mouse_down, None)
+    }
+
+    /// Same as `match_css_to_dom`, but only restyles the nodes whose classes, ids, attributes or
+    /// `:hover` / `:active` / `:focus` state changed since the `previous` frame (and their children).
+    /// Falls back to restyling all nodes if the CSS, the viewport or the DOM structure changed.
+    pub(crate) fn match_css_to_dom_incremental(
+        previous: &Self,
+        ui_state: &mut UiState<T>,
+        style: &Css,
+        viewport: CssViewport,
+        focused_node: &mut Option<NodeId>,
+        pending_focus_target: &mut Option<FocusTarget>,
+        hovered_nodes: &BTreeMap<NodeId, HitTestItem>,
+        is_mouse_down: bool,
+    ) -> Self
+    {
+        Self::match_css_to_dom_inner(ui_state, style, viewport, focused_node, pending_focus_target, hovered_nodes, is_mouse_down, Some(previous))
+    }
+
+    fn match_css_to_dom_inner(
+        ui_state: &mut UiState<T>,
+        style: &Css,
+        viewport: CssViewport,
+        focused_node: &mut Option<NodeId>,
+        pending_focus_target: &mut Option<FocusTarget>,
+        hovered_nodes: &BTreeMap<NodeId, HitTestItem>,
+        is_mouse_down: bool,
+        previous: Option<&Self>,
+    ) -> Self
+    {
+        profile_scope!("style");
+
         let ui_description = ::style::match_dom_selectors(
             ui_state,
             &style,
+            viewport,
             focused_node,
             pending_focus_target,
             hovered_nodes,
-            is_mouse_down
+            is_mouse_down,
+            previous,
         );
 
         // Important: Create all the tags for the :hover and :active selectors
         ui_state.create_tags_for_hover_nodes(&ui_description.selected_hover_nodes);
+        // ... and for all nodes with a `cursor`, so that the cursor can be set on hover
+        let cursor_nodes = get_cursor_nodes(&ui_description.styled_nodes, &ui_description.dynamic_css_overrides);
+        ui_state.create_tags_for_cursor_nodes(&cursor_nodes);
         ui_description
     }
+
+    /// Returns the final style of a node (with the dynamic CSS overrides
+    /// of this frame applied) or `None` if the node doesn't exist
+    pub fn get_computed_style(&self, node_id: NodeId) -> Option<ComputedStyle> {
+        ::display_list::get_computed_style(self, node_id)
+    }
+}
+
+/// Returns the value of the `cursor` property for every node that has one
+fn get_cursor_nodes(
+    styled_nodes: &NodeDataContainer<StyledNode>,
+    css_overrides: &BTreeMap<NodeId, FastHashMap<DomString, CssProperty>>,
+) -> BTreeMap<NodeId, StyleCursor> {
+
+    use azul_css::DynamicCssPropertyDefault;
+
+    styled_nodes.linear_iter().filter_map(|node_id| {
+        let property = match styled_nodes[node_id].css_constraints.get(&CssPropertyType::Cursor)? {
+            CssDeclaration::Static(property) => property,
+            CssDeclaration::Dynamic(dynamic_property) => {
+                let overridden_property = css_overrides.get(&node_id).and_then(|overrides| {
+                    overrides.get(&DomString::Heap(dynamic_property.dynamic_id.clone()))
+                });
+                match (overridden_property, &dynamic_property.default) {
+                    (Some(property), _) => property,
+                    (None, DynamicCssPropertyDefault::Exact(property)) => property,
+                    (None, DynamicCssPropertyDefault::Auto) => return None,
+                }
+            },
+            CssDeclaration::Unresolved(_) => return None,
+        };
+        match property {
+            CssProperty::Cursor(cursor) => Some((node_id, *cursor)),
+            _ => None,
+        }
+    }).collect()
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Hash, PartialOrd, Eq, Ord)]
 pub(crate) struct StyledNode {
     /// The CSS constraints, after the cascading step
     pub(crate) css_constraints: BTreeMap<CssPropertyType, CssDeclaration>,
+    /// Position of each declaration of `css_constraints` in the cascade. Inherited
+    /// properties aren't declared by the node itself, so they have no position.
+    pub(crate) cascade_order: BTreeMap<CssPropertyType, usize>,
+}
+
+impl StyledNode {
+    /// Whether the node declares the property `a` later in the cascade than the property `b`,
+    /// i.e. whether `a` overrides `b` where the two properties set the same value
+    pub(crate) fn is_declared_after(&self, a: CssPropertyType, b: CssPropertyType) -> bool {
+        match (self.cascade_order.get(&a), self.cascade_order.get(&b)) {
+            (Some(a), Some(b)) => a > b,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+/// Cascaded styles of the scrollbar pseudo-elements of a node
+#[derive(Debug, Default, Clone, PartialEq, Hash, PartialOrd, Eq, Ord)]
+pub(crate) struct ScrollbarStyledNode {
+    /// `::scrollbar`
+    pub(crate) scrollbar: StyledNode,
+    /// `::track`
+    pub(crate) track: StyledNode,
+    /// `::thumb`
+    pub(crate) thumb: StyledNode,
 }
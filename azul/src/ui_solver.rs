@@ -1,20 +1,22 @@
-use std::{f32, collections::BTreeMap};
+use std::{f32, mem, ops::Range, collections::{BTreeMap, BTreeSet}};
 use azul_css::{
     LayoutPosition, LayoutMargin, LayoutPadding,
     RectLayout, StyleFontSize, RectStyle,
-    StyleTextAlignmentHorz, StyleTextAlignmentVert, PixelValue,
+    StyleTextAlignmentHorz, StyleTextAlignmentVert, PixelValue, LayoutCalc,
+    LayoutDisplay, LayoutFloat, GridTrackSize, GridPlacement, LayoutFlexBasis,
 };
 use app_units::Au;
 use {
     id_tree::{NodeId, NodeDataContainer, NodeHierarchy},
     display_list::DisplayRectangle,
     dom::{NodeData, NodeType},
-    app_resources::AppResources,
-    text_layout::{Words, ScaledWords, TextLayoutOptions, WordPositions},
+    app_resources::{AppResources, LoadedFont},
+    text_layout::{Words, ScaledWords, TextLayoutOptions, TabStop, WordPositions},
 };
 use webrender::api::{LayoutRect, LayoutPoint, LayoutSize, FontInstanceKey};
 
 const DEFAULT_FLEX_GROW_FACTOR: f32 = 1.0;
+const DEFAULT_FLEX_SHRINK_FACTOR: f32 = 1.0;
 const DEFAULT_FONT_SIZE: StyleFontSize = StyleFontSize(PixelValue::const_px(10));
 const DEFAULT_FONT_ID: &str = "sans-serif";
 
@@ -194,13 +196,19 @@ struct WidthCalculatedRect {
 impl WidthCalculatedRect {
     /// Get the flex basis in the horizontal direction - vertical axis has to be calculated differently
     pub fn get_flex_basis_horizontal(&self) -> f32 {
-        self.preferred_width.min_needed_space().unwrap_or(0.0)      +
+        self.get_content_size()                                     +
         self.margin.left.map(|px| px.to_pixels()).unwrap_or(0.0)    +
         self.margin.right.map(|px| px.to_pixels()).unwrap_or(0.0)   +
         self.padding.left.map(|px| px.to_pixels()).unwrap_or(0.0)   +
         self.padding.right.map(|px| px.to_pixels()).unwrap_or(0.0)
     }
 
+    /// Minimum width of the content (without padding): Either the minimum width of the node itself
+    /// or - if the node has children - the width that the children have bubbled up to the node
+    pub fn get_content_size(&self) -> f32 {
+        self.preferred_width.min_needed_space().unwrap_or(0.0).max(self.min_inner_size_px)
+    }
+
     /// Get the sum of the horizontal padding amount (`padding.left + padding.right`)
     pub fn get_horizontal_padding(&self) -> f32 {
         self.padding.left.map(|px| px.to_pixels()).unwrap_or(0.0)   +
@@ -228,13 +236,18 @@ struct HeightCalculatedRect {
 impl HeightCalculatedRect {
     /// Get the flex basis in the horizontal direction - vertical axis has to be calculated differently
     pub fn get_flex_basis_vertical(&self) -> f32 {
-        self.preferred_height.min_needed_space().unwrap_or(0.0) +
+        self.get_content_size()                                  +
         self.margin.top.map(|px| px.to_pixels()).unwrap_or(0.0) +
         self.margin.bottom.map(|px| px.to_pixels()).unwrap_or(0.0) +
         self.padding.top.map(|px| px.to_pixels()).unwrap_or(0.0) +
         self.padding.bottom.map(|px| px.to_pixels()).unwrap_or(0.0)
     }
 
+    /// Minimum height of the content (without padding), see `WidthCalculatedRect::get_content_size`
+    pub fn get_content_size(&self) -> f32 {
+        self.preferred_height.min_needed_space().unwrap_or(0.0).max(self.min_inner_size_px)
+    }
+
     /// Get the sum of the horizontal padding amount (`padding.top + padding.bottom`)
     pub fn get_vertical_padding(&self) -> f32 {
         self.padding.top.map(|px| px.to_pixels()).unwrap_or(0.0) +
@@ -250,13 +263,452 @@ impl HeightCalculatedRect {
     }
 }
 
-// `typed_arena!(WidthCalculatedRect, preferred_width, determine_preferred_width, get_horizontal_padding, get_flex_basis_horizontal)`
+/// Columns or rows of a `display: grid` container, together with the items placed into them
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GridAxis {
+    tracks: Vec<GridTrackSize>,
+    /// Space between two adjacent tracks
+    gap: f32,
+    /// (item, index of the first track, number of tracks the item spans)
+    items: Vec<(NodeId, usize, usize)>,
+    /// Size of each track, filled out by the solver
+    track_sizes: Vec<f32>,
+}
+
+impl GridAxis {
+
+    /// Sizes the tracks so that they fit into `available_space`: Fixed and percentage tracks
+    /// are resolved first, `auto` and `fr` tracks are at least as large as the largest item that
+    /// only spans this track. The leftover space is distributed between the `fr` tracks or - if
+    /// there are no `fr` tracks - equally between the `auto` tracks.
+    fn solve_track_sizes<F: Fn(NodeId) -> f32>(&self, available_space: f32, item_size: F) -> Vec<f32> {
+
+        use azul_css::GridTrackSize::*;
+
+        let mut sizes = self.tracks.iter().map(|track| match track {
+            Fixed(px) => px.to_pixels(),
+            Percentage(percent) => available_space * percent.get() / 100.0,
+            Fraction(_) | Auto => 0.0,
+        }).collect::<Vec<f32>>();
+
+        for (node_id, start, span) in &self.items {
+            let is_content_sized = match self.tracks[*start] {
+                Fraction(_) | Auto => true,
+                Fixed(_) | Percentage(_) => false,
+            };
+            if *span == 1 && is_content_sized {
+                sizes[*start] = sizes[*start].max(item_size(*node_id));
+            }
+        }
+
+        let leftover_space = available_space - self.total_gap() - sizes.iter().sum::<f32>();
+        if leftover_space <= 0.0 {
+            return sizes;
+        }
+
+        let fractions: f32 = self.tracks.iter().map(|track| match track {
+            Fraction(fr) => fr.get(),
+            _ => 0.0,
+        }).sum();
+
+        if fractions > 0.0 {
+            // The `fr` tracks share the space that isn't taken up by the other tracks
+            let fraction_space = available_space - self.total_gap() - self.tracks.iter().zip(sizes.iter())
+                .filter_map(|(track, size)| if let Fraction(_) = track { None } else { Some(*size) })
+                .sum::<f32>();
+            for (track, size) in self.tracks.iter().zip(sizes.iter_mut()) {
+                if let Fraction(fr) = track {
+                    *size = size.max(fraction_space * fr.get() / fractions);
+                }
+            }
+        } else {
+            let auto_tracks = self.tracks.iter().filter(|track| **track == Auto).count();
+            for (track, size) in self.tracks.iter().zip(sizes.iter_mut()) {
+                if *track == Auto {
+                    *size += leftover_space / auto_tracks as f32;
+                }
+            }
+        }
+
+        sizes
+    }
+
+    /// Rows for the lines of a `flex-wrap: wrap` row (see `solve_flex_layout_height`):
+    /// Each line is an `auto` track, so the lines are as high as their highest item
+    /// and the items are stretched to the height of their line.
+    fn from_flex_lines(lines: &[Vec<NodeId>], gap: f32) -> Self {
+        GridAxis {
+            tracks: vec![GridTrackSize::Auto; lines.len()],
+            gap,
+            items: lines.iter().enumerate()
+                .flat_map(|(line_idx, line)| line.iter().map(move |item_id| (*item_id, line_idx, 1)))
+                .collect(),
+            track_sizes: Vec::new(),
+        }
+    }
+
+    /// Minimum size of the whole grid (without the padding of the container)
+    fn get_min_size<F: Fn(NodeId) -> f32>(&self, item_size: F) -> f32 {
+        self.solve_track_sizes(0.0, item_size).iter().sum::<f32>() + self.total_gap()
+    }
+
+    fn total_gap(&self) -> f32 {
+        self.gap * self.tracks.len().saturating_sub(1) as f32
+    }
+
+    /// Distance between the start of the first track and the start of the `track`
+    fn get_track_offset(&self, track: usize) -> f32 {
+        self.track_sizes.iter().take(track).sum::<f32>() + self.gap * track as f32
+    }
+
+    /// Size of the area that an item spans, including the gaps between its tracks
+    fn get_area_size(&self, start: usize, span: usize) -> f32 {
+        self.track_sizes.iter().skip(start).take(span).sum::<f32>() + self.gap * span.saturating_sub(1) as f32
+    }
+}
+
+/// Places the children of the `display: grid` node into the cells of the grid: items
+/// with an explicit row and column first, then the items with an explicit row, then all
+/// other items in order, into the next free cell (row by row). Items that are placed outside
+/// of the `grid-template-columns` / `grid-template-rows` create implicit `auto` tracks.
+///
+/// Returns the columns and the rows of the grid, the tracks aren't sized yet.
+fn place_grid_items(
+    node_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    layouts: &NodeDataContainer<RectLayout>,
+    template_columns: &[GridTrackSize],
+    template_rows: &[GridTrackSize],
+) -> (GridAxis, GridAxis) {
+
+    let mut column_tracks = template_columns.to_vec();
+    if column_tracks.is_empty() {
+        column_tracks.push(GridTrackSize::Auto);
+    }
+    let mut row_tracks = template_rows.to_vec();
+
+    // (item, (column start, column span), (row start, row span))
+    let items = node_id
+        .children(node_hierarchy)
+        .filter(|child_id| layouts[*child_id].position != Some(LayoutPosition::Absolute))
+        .map(|child_id| {
+            let layout = &layouts[child_id];
+            let column = resolve_grid_placement(layout.grid_column.map(|c| c.0).unwrap_or_default(), column_tracks.len());
+            let row = resolve_grid_placement(layout.grid_row.map(|r| r.0).unwrap_or_default(), row_tracks.len());
+            (child_id, column, row)
+        })
+        .collect::<Vec<_>>();
+
+    let column_count = items.iter()
+        .map(|(_, (column_start, column_span), _)| column_start.unwrap_or(0) + column_span)
+        .fold(column_tracks.len(), usize::max);
+
+    let mut occupied_cells = BTreeSet::<(usize, usize)>::new();
+    let mut areas = Vec::<(NodeId, (usize, usize), (usize, usize))>::new();
+
+    let is_free = |occupied_cells: &BTreeSet<(usize, usize)>, (column, column_span): (usize, usize), (row, row_span): (usize, usize)| {
+        (row..(row + row_span)).all(|r| (column..(column + column_span)).all(|c| !occupied_cells.contains(&(r, c))))
+    };
+
+    let mut place = |occupied_cells: &mut BTreeSet<(usize, usize)>, node_id, (column, column_span), (row, row_span)| {
+        for r in row..(row + row_span) {
+            for c in column..(column + column_span) {
+                occupied_cells.insert((r, c));
+            }
+        }
+        areas.push((node_id, (column, column_span), (row, row_span)));
+    };
+
+    // 1. Items with an explicit column and row
+    for (child_id, column, row) in &items {
+        if let ((Some(column), column_span), (Some(row), row_span)) = (column, row) {
+            place(&mut occupied_cells, *child_id, (*column, *column_span), (*row, *row_span));
+        }
+    }
+
+    // 2. Items with an explicit row: first free column in that row
+    for (child_id, column, row) in &items {
+        if let ((None, column_span), (Some(row), row_span)) = (column, row) {
+            let column_span = (*column_span).min(column_count);
+            let column = (0..=(column_count - column_span))
+                .find(|c| is_free(&occupied_cells, (*c, column_span), (*row, *row_span)))
+                .unwrap_or(0);
+            place(&mut occupied_cells, *child_id, (column, column_span), (*row, *row_span));
+        }
+    }
+
+    // 3. All other items, in the order of the DOM
+    let mut cursor = (0, 0);
+    for (child_id, column, row) in &items {
+        match (column, row) {
+            ((Some(column), column_span), (None, row_span)) => {
+                let row = (0..)
+                    .find(|r| is_free(&occupied_cells, (*column, *column_span), (*r, *row_span)))
+                    .unwrap_or(0);
+                place(&mut occupied_cells, *child_id, (*column, *column_span), (row, *row_span));
+            },
+            ((None, column_span), (None, row_span)) => {
+                let column_span = (*column_span).min(column_count);
+                while cursor.0 + column_span > column_count || !is_free(&occupied_cells, (cursor.0, column_span), (cursor.1, *row_span)) {
+                    if cursor.0 + column_span >= column_count {
+                        cursor = (0, cursor.1 + 1);
+                    } else {
+                        cursor.0 += 1;
+                    }
+                }
+                place(&mut occupied_cells, *child_id, (cursor.0, column_span), (cursor.1, *row_span));
+                cursor.0 += column_span;
+            },
+            _ => { },
+        }
+    }
+
+    areas.sort_by_key(|(child_id, _, _)| *child_id);
+
+    let row_count = areas.iter()
+        .map(|(_, _, (row, row_span))| row + row_span)
+        .fold(row_tracks.len(), usize::max);
+
+    column_tracks.resize(column_count, GridTrackSize::Auto);
+    row_tracks.resize(row_count, GridTrackSize::Auto);
+
+    let columns = GridAxis {
+        tracks: column_tracks,
+        gap: layouts[node_id].get_column_gap(),
+        items: areas.iter().map(|(child_id, (column, column_span), _)| (*child_id, *column, *column_span)).collect(),
+        track_sizes: Vec::new(),
+    };
+
+    let rows = GridAxis {
+        tracks: row_tracks,
+        gap: layouts[node_id].get_row_gap(),
+        items: areas.iter().map(|(child_id, _, (row, row_span))| (*child_id, *row, *row_span)).collect(),
+        track_sizes: Vec::new(),
+    };
+
+    (columns, rows)
+}
+
+/// Resolves a `grid-column` / `grid-row` to the index of the first track (`None` if the
+/// item should be placed automatically) and the number of tracks that the item spans
+fn resolve_grid_placement(placement: GridPlacement, explicit_track_count: usize) -> (Option<usize>, usize) {
+
+    use azul_css::GridLine::*;
+
+    // Lines start at 1, negative lines count backwards from the last line of the explicit grid
+    let line_index = |line: isize| if line > 0 {
+        (line - 1) as usize
+    } else {
+        (explicit_track_count as isize + 1 + line).max(0) as usize
+    };
+
+    match (placement.start, placement.end) {
+        (Line(start), Line(end)) => {
+            let (start, end) = (line_index(start), line_index(end));
+            let (start, end) = if end < start { (end, start) } else { (start, end) };
+            (Some(start), (end - start).max(1))
+        },
+        (Line(start), Span(span)) => (Some(line_index(start)), span),
+        (Line(start), Auto) => (Some(line_index(start)), 1),
+        (Span(span), Line(end)) => {
+            let end = line_index(end);
+            let start = end.saturating_sub(span);
+            (Some(start), (end - start).max(1))
+        },
+        (Auto, Line(end)) => (Some(line_index(end).saturating_sub(1)), 1),
+        (Span(span), _) | (Auto, Span(span)) => (None, span),
+        (Auto, Auto) => (None, 1),
+    }
+}
+
+/// Places the items of all `display: grid` nodes, returns the columns and rows of each grid
+fn get_grid_axes<'a>(
+    node_hierarchy: &NodeHierarchy,
+    display_rectangles: &NodeDataContainer<DisplayRectangle<'a>>,
+    layout_only_arena: &NodeDataContainer<RectLayout>,
+) -> (BTreeMap<NodeId, GridAxis>, BTreeMap<NodeId, GridAxis>) {
+
+    let mut grid_columns = BTreeMap::new();
+    let mut grid_rows = BTreeMap::new();
+
+    for node_id in display_rectangles.linear_iter() {
+        if layout_only_arena[node_id].display != Some(LayoutDisplay::Grid) || node_hierarchy[node_id].first_child.is_none() {
+            continue;
+        }
+        let display_rect = &display_rectangles[node_id];
+        let (columns, rows) = place_grid_items(
+            node_id,
+            node_hierarchy,
+            layout_only_arena,
+            &display_rect.grid_template_columns,
+            &display_rect.grid_template_rows,
+        );
+        grid_columns.insert(node_id, columns);
+        grid_rows.insert(node_id, rows);
+    }
+
+    (grid_columns, grid_rows)
+}
+
+/// Returns the nodes that have at least one `display: inline` / `inline-block` or floating child.
+/// These nodes lay out all of their children in an inline flow (see `layout_inline_flows`),
+/// the `flex-direction`, `justify-content` and `align-items` of the node are ignored.
+fn get_inline_containers(
+    node_hierarchy: &NodeHierarchy,
+    layout_only_arena: &NodeDataContainer<RectLayout>,
+    grid_columns: &BTreeMap<NodeId, GridAxis>,
+) -> BTreeSet<NodeId> {
+    layout_only_arena.linear_iter()
+        .filter(|node_id| !grid_columns.contains_key(node_id))
+        .filter(|node_id| node_id.children(node_hierarchy).any(|child_id| {
+            layout_only_arena[child_id].is_inline_level() || layout_only_arena[child_id].is_floating()
+        }))
+        .collect()
+}
+
+/// Child that is grown or shrunk along the main axis of its parent, see `resolve_flexible_lengths`
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct FlexItem {
+    node_id: NodeId,
+    /// Size of the item before the free space is distributed, depends on the `flex-basis`
+    flex_base_size: f32,
+    /// The item can't shrink below this size: Either the `min-width` or -
+    /// if the `min-width` isn't set - the size of the content of the item
+    min_size: f32,
+    max_size: f32,
+    flex_grow: f32,
+    flex_shrink: f32,
+    /// Final size of the item, filled out by `resolve_flexible_lengths`
+    target_size: f32,
+    frozen: bool,
+}
+
+impl FlexItem {
+    /// Flex base size, clamped by the min / max size
+    fn get_hypothetical_size(&self) -> f32 {
+        self.flex_base_size.min(self.max_size).max(self.min_size)
+    }
+}
+
+/// Whether the node is a row with `flex-wrap: wrap`. Only rows wrap, since the height of a
+/// column isn't known yet when the widths are solved.
+fn is_wrapping_row(layout: &RectLayout) -> bool {
+    use azul_css::{LayoutAxis, LayoutWrap};
+    layout.wrap == Some(LayoutWrap::Wrap) && layout.direction.unwrap_or_default().get_axis() == LayoutAxis::Horizontal
+}
+
+/// Breaks the items of a `flex-wrap: wrap` container into lines: A line ends before the first
+/// item that doesn't fit into the `available_space` anymore. `item_sizes` are the hypothetical
+/// sizes of the items (including their margins), before they are grown or shrunk.
+///
+/// Every line contains at least one item, even if that item is larger than the `available_space`.
+fn break_into_flex_lines(item_sizes: &[f32], gap: f32, available_space: f32) -> Vec<Range<usize>> {
+
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    let mut line_size = 0.0;
+
+    for (item_idx, item_size) in item_sizes.iter().enumerate() {
+        if item_idx > line_start && line_size + gap + item_size > available_space {
+            lines.push(line_start..item_idx);
+            line_start = item_idx;
+            line_size = 0.0;
+        }
+        line_size += if item_idx > line_start { gap + item_size } else { *item_size };
+    }
+
+    if line_start < item_sizes.len() {
+        lines.push(line_start..item_sizes.len());
+    }
+
+    lines
+}
+
+/// Grows or shrinks the `items`, so that they fill the `available_space` (see the
+/// "Resolving Flexible Lengths" section of the CSS flexbox spec): The free space is
+/// distributed according to the `flex-grow` (or `flex-shrink`, scaled by the base size)
+/// factors. Items that would violate their min / max size are clamped and "frozen",
+/// then the remaining free space is distributed again between the other items.
+fn resolve_flexible_lengths(items: &mut [FlexItem], available_space: f32) {
+
+    let hypothetical_sizes: f32 = items.iter().map(|item| item.get_hypothetical_size()).sum();
+    let is_growing = hypothetical_sizes < available_space;
+
+    // Items that can't flex in this direction keep their hypothetical size
+    for item in items.iter_mut() {
+        let hypothetical_size = item.get_hypothetical_size();
+        let flex_factor = if is_growing { item.flex_grow } else { item.flex_shrink };
+        item.target_size = hypothetical_size;
+        item.frozen = flex_factor <= 0.0 ||
+            (is_growing && item.flex_base_size > hypothetical_size) ||
+            (!is_growing && item.flex_base_size < hypothetical_size);
+    }
+
+    // Every iteration freezes at least one item, so this terminates
+    while items.iter().any(|item| !item.frozen) {
+
+        let used_space: f32 = items.iter().map(|item| if item.frozen { item.target_size } else { item.flex_base_size }).sum();
+        let free_space = available_space - used_space;
+
+        if is_growing {
+            let sum_flex_grow: f32 = items.iter().filter(|item| !item.frozen).map(|item| item.flex_grow).sum();
+            for item in items.iter_mut().filter(|item| !item.frozen) {
+                item.target_size = item.flex_base_size + free_space * (item.flex_grow / sum_flex_grow);
+            }
+        } else {
+            // Larger items shrink faster than smaller items
+            let sum_scaled_flex_shrink: f32 = items.iter().filter(|item| !item.frozen).map(|item| item.flex_shrink * item.flex_base_size).sum();
+            for item in items.iter_mut().filter(|item| !item.frozen) {
+                item.target_size = if sum_scaled_flex_shrink > 0.0 {
+                    item.flex_base_size + free_space * (item.flex_shrink * item.flex_base_size / sum_scaled_flex_shrink)
+                } else {
+                    item.flex_base_size
+                };
+            }
+        }
+
+        // Clamp the items and freeze the ones that violated their min (or max) size
+        let mut total_violation = 0.0;
+        let mut violations = Vec::with_capacity(items.len());
+
+        for (item_index, item) in items.iter_mut().enumerate().filter(|(_, item)| !item.frozen) {
+            let clamped_size = item.target_size.min(item.max_size).max(item.min_size);
+            let violation = clamped_size - item.target_size;
+            total_violation += violation;
+            item.target_size = clamped_size;
+            violations.push((item_index, violation));
+        }
+
+        for (item_index, violation) in violations {
+            items[item_index].frozen = total_violation == 0.0 ||
+                (total_violation > 0.0 && violation > 0.0) ||
+                (total_violation < 0.0 && violation < 0.0);
+        }
+    }
+}
+
+/// Returns the size of the content of a node (i.e. its text) that the node can't shrink below
+/// (see `WidthCalculatedRect::get_content_size`). The content doesn't make a node with a fixed
+/// size larger and can't make a node larger than its `max-width`.
+fn get_content_floor(preferred: WhConstraint, content_size: Option<f32>) -> f32 {
+    use self::WhConstraint::*;
+    let content_size = content_size.unwrap_or(0.0);
+    match preferred {
+        EqualTo(_) => 0.0,
+        Between(_, max) => content_size.min(max),
+        Unconstrained => content_size,
+    }
+}
+
+// `typed_arena!(WidthCalculatedRect, preferred_width, determine_preferred_width, min_width, get_horizontal_padding, get_flex_basis_horizontal)`
 macro_rules! typed_arena {(
     $struct_name:ident,
     $preferred_field:ident,
     $determine_preferred_fn:ident,
+    $min_size_field:ident,
     $get_padding_fn:ident,
     $get_flex_basis:ident,
+    $get_margin_fn:ident,
     $bubble_fn_name:ident,
     $main_axis:ident
 ) => (
@@ -274,13 +726,18 @@ impl NodeDataContainer<$struct_name> {
     fn from_rect_layout_arena(node_data: &NodeDataContainer<RectLayout>, widths: &NodeDataContainer<Option<f32>>) -> Self {
         let new_nodes = node_data.internal.iter().enumerate().map(|(node_id, node_data)|{
             let id = NodeId::new(node_id);
+            // TODO: get the initial width of the rect content
+            let preferred = $determine_preferred_fn(&node_data, widths[id]);
             $struct_name {
-                // TODO: get the initial width of the rect content
-                $preferred_field: $determine_preferred_fn(&node_data, widths[id]),
+                $preferred_field: preferred,
                 margin: node_data.margin.unwrap_or_default(),
                 padding: node_data.padding.unwrap_or_default(),
                 flex_grow_px: 0.0,
-                min_inner_size_px: 0.0,
+                // The content (i.e. the text) is the floor of `get_content_size`, so that a node
+                // without a `min-width` doesn't shrink below its text, even if its `min-width`
+                // or `max-width` would allow it. Parent nodes overwrite this with the size of
+                // their children when the sizes are bubbled up.
+                min_inner_size_px: get_content_floor(preferred, widths[id]),
             }
         }).collect();
         NodeDataContainer { internal: new_nodes }
@@ -293,8 +750,12 @@ impl NodeDataContainer<$struct_name> {
         &mut self,
         node_hierarchy: &NodeHierarchy,
         arena_data: &NodeDataContainer<RectLayout>,
-        non_leaf_nodes: &[(usize, NodeId)])
+        non_leaf_nodes: &[(usize, NodeId)],
+        grid_axes: &BTreeMap<NodeId, GridAxis>,
+        inline_containers: &BTreeSet<NodeId>)
     {
+        use azul_css::LayoutAxis;
+
         // Reverse, since we want to go from the inside out (depth 5 needs to be filled out first)
         //
         // Set the preferred_width of the parent nodes
@@ -303,7 +764,16 @@ impl NodeDataContainer<$struct_name> {
             use self::WhConstraint::*;
 
             // Sum of the direct children's flex-basis = the parents preferred width
-            let children_flex_basis = self.sum_children_flex_basis(*non_leaf_id, node_hierarchy, arena_data);
+            // (for grids: the sum of the minimum track sizes, for inline flows, wrapping rows
+            // and along the cross axis: the largest child, since the children aren't
+            // necessarily placed next to each other in this direction)
+            let is_cross_axis = arena_data[*non_leaf_id].direction.unwrap_or_default().get_axis() != LayoutAxis::$main_axis ||
+                is_wrapping_row(&arena_data[*non_leaf_id]);
+            let children_flex_basis = match grid_axes.get(non_leaf_id) {
+                Some(grid_axis) => grid_axis.get_min_size(|item_id| self[item_id].$get_flex_basis()),
+                None if inline_containers.contains(non_leaf_id) || is_cross_axis => self.max_children_flex_basis(*non_leaf_id, node_hierarchy, arena_data),
+                None => self.sum_children_flex_basis(*non_leaf_id, node_hierarchy, arena_data) + get_total_main_axis_gap(*non_leaf_id, node_hierarchy, arena_data),
+            };
 
             // Calculate the new flex-basis width
             let parent_width_metrics = self[*non_leaf_id];
@@ -316,9 +786,9 @@ impl NodeDataContainer<$struct_name> {
             let child_width = match parent_width_metrics.$preferred_field {
                 Between(min, max) => {
                     if children_flex_basis > (max - parent_padding)  {
-                        max
-                    } else if children_flex_basis < (min + parent_padding) {
-                        min
+                        max - parent_padding
+                    } else if children_flex_basis < (min - parent_padding) {
+                        min - parent_padding
                     } else {
                         children_flex_basis
                     }
@@ -334,15 +804,27 @@ impl NodeDataContainer<$struct_name> {
         // but they aren't flex-growed or flex-shrinked yet
     }
 
+    /// After the sizes have been bubbled to the parents, returns the minimum
+    /// size of the root node (including its padding), i.e. the space the whole DOM needs
+    fn get_min_root_size(&self) -> f32 {
+        let root = &self[NodeId::new(0)];
+        let min_size = root.$preferred_field.min_needed_space().unwrap_or(0.0);
+        min_size.max(root.min_inner_size_px + root.$get_padding_fn())
+    }
+
     /// Go from the root down and flex_grow the children if needed - respects the `width`, `min_width` and `max_width` properties
     /// The layout step doesn't account for the min_width and max_width constraints, so we have to adjust them manually
+    ///
+    /// Returns the lines of the rows with `flex-wrap: wrap` (only when solving the widths).
     fn apply_flex_grow(
         &mut self,
         node_hierarchy: &NodeHierarchy,
         arena_data: &NodeDataContainer<RectLayout>,
         parent_ids_sorted_by_depth: &[(usize, NodeId)],
-        root_width: f32
-    ) {
+        root_width: f32,
+        grid_axes: &mut BTreeMap<NodeId, GridAxis>,
+        inline_containers: &BTreeSet<NodeId>,
+    ) -> BTreeMap<NodeId, Vec<Vec<NodeId>>> {
         use azul_css::LayoutAlignItems;
 
         debug_assert!(self[NodeId::new(0)].flex_grow_px == 0.0);
@@ -365,6 +847,7 @@ impl NodeDataContainer<$struct_name> {
 
         // Keep track of the nearest relative or absolute positioned element
         let mut positioned_node_stack = vec![NodeId::new(0)];
+        let mut flex_lines = BTreeMap::new();
 
         for (_node_depth, parent_id) in parent_ids_sorted_by_depth {
 
@@ -404,9 +887,20 @@ impl NodeDataContainer<$struct_name> {
                 (width_main_axis, width_cross_axis)
             };
 
-            // Only stretch the items, if they have a align-items: stretch!
-            if parent_node.align_items.unwrap_or_default() == LayoutAlignItems::Stretch {
+            if let Some(grid_axis) = grid_axes.get_mut(parent_id) {
+                let parent_inner_width = self[*parent_id].min_inner_size_px + self[*parent_id].flex_grow_px - parent_node.$get_padding_fn();
+                Self::distribute_space_in_grid(parent_id, parent_inner_width, node_hierarchy, arena_data, self, &positioned_node_stack, grid_axis);
+            } else if inline_containers.contains(parent_id) {
+                let parent_inner_width = self[*parent_id].min_inner_size_px + self[*parent_id].flex_grow_px - parent_node.$get_padding_fn();
+                Self::distribute_space_in_inline_flow(parent_id, parent_inner_width, node_hierarchy, arena_data, self, &positioned_node_stack);
+            } else if LayoutAxis::$main_axis == LayoutAxis::Horizontal && is_wrapping_row(parent_node) {
+                let parent_inner_width = self[*parent_id].min_inner_size_px + self[*parent_id].flex_grow_px - parent_node.$get_padding_fn();
+                let lines = Self::distribute_space_in_flex_lines(parent_id, parent_inner_width, node_hierarchy, arena_data, self, &positioned_node_stack);
+                flex_lines.insert(*parent_id, lines);
+            } else if parent_node.align_items.unwrap_or_default() == LayoutAlignItems::Stretch {
+                // Only stretch the items, if they have a align-items: stretch!
                 if parent_node.direction.unwrap_or_default().get_axis() == LayoutAxis::$main_axis {
+                    let width_main_axis = width_main_axis - get_total_main_axis_gap(*parent_id, node_hierarchy, arena_data);
                     Self::distribute_space_along_main_axis(parent_id, width_main_axis, node_hierarchy, arena_data, self, &positioned_node_stack);
                 } else {
                     Self::distribute_space_along_cross_axis(parent_id, width_cross_axis, node_hierarchy, arena_data, self, &positioned_node_stack);
@@ -417,6 +911,8 @@ impl NodeDataContainer<$struct_name> {
                 positioned_node_stack.pop();
             }
         }
+
+        flex_lines
     }
 
     /// Returns the sum of the flex-basis of the current nodes' children
@@ -434,8 +930,23 @@ impl NodeDataContainer<$struct_name> {
             .sum()
     }
 
+    /// Returns the largest flex-basis of the current nodes' children
+    fn max_children_flex_basis(
+        &self,
+        node_id: NodeId,
+        node_hierarchy: &NodeHierarchy,
+        display_arena: &NodeDataContainer<RectLayout>)
+    -> f32
+    {
+        node_id
+            .children(node_hierarchy)
+            .filter(|child_node_id| display_arena[*child_node_id].position != Some(LayoutPosition::Absolute))
+            .map(|child_node_id| self[child_node_id].$get_flex_basis())
+            .fold(0.0, f32::max)
+    }
+
     /// Does the actual width layout, respects the `width`, `min_width` and `max_width`
-    /// properties as well as the `flex_grow` factor. `flex_shrink` currently does nothing.
+    /// properties as well as the `flex-basis`, `flex-grow` and `flex-shrink` factors.
     fn distribute_space_along_main_axis(
         node_id: &NodeId,
         width_to_distribute: f32,
@@ -482,29 +993,23 @@ impl NodeDataContainer<$struct_name> {
         // Now we can be sure that if we write #x { width: 500px; } that it will actually be 500px large
         // and not be influenced by flex in any way.
 
-        // 2. Set all items to their minimum width. Record how much space is gained by doing so.
-        let mut horizontal_space_taken_up_by_variable_items = 0.0;
+        // 2. Collect the variable-width children. `position: absolute` children don't take
+        //    any space away from their siblings, so they are solved immediately.
+        let mut flex_items = Vec::new();
 
-        use FastHashSet;
-
-        let mut variable_width_childs = node_id
+        let variable_width_childs = node_id
             .children(node_hierarchy)
             .filter(|id| !width_calculated_arena[*id].$preferred_field.is_fixed_constraint())
-            .collect::<FastHashSet<NodeId>>();
-
-        let mut absolute_variable_width_nodes = Vec::new();
+            .collect::<Vec<NodeId>>();
 
-        for variable_child_id in &variable_width_childs {
+        for variable_child_id in variable_width_childs {
 
-            if arena_data[*variable_child_id].position.unwrap_or_default() != LayoutPosition::Absolute {
+            let child_layout = &arena_data[variable_child_id];
+            let child_metrics = width_calculated_arena[variable_child_id];
 
-                let min_width = width_calculated_arena[*variable_child_id].$preferred_field.min_needed_space().unwrap_or(0.0);
+            if child_layout.position.unwrap_or_default() != LayoutPosition::Absolute {
 
-                horizontal_space_taken_up_by_variable_items += min_width;
-
-                // so that node.min_inner_size_px + node.flex_grow_px = min_width
-                width_calculated_arena[*variable_child_id].flex_grow_px =
-                    min_width - width_calculated_arena[*variable_child_id].min_inner_size_px;
+                flex_items.push(Self::get_flex_item(variable_child_id, arena_data, width_calculated_arena));
 
             } else {
 
@@ -518,12 +1023,9 @@ impl NodeDataContainer<$struct_name> {
                     relative_parent_node.flex_grow_px + relative_parent_node.min_inner_size_px
                 };
 
-                // By default, absolute positioned elements take the width of their content
-                // let min_inner_width = width_calculated_arena[*variable_child_id].$preferred_field.min_needed_space().unwrap_or(0.0);
-
                 // The absolute positioned node might have a max-width constraint, which has a
                 // higher precedence than `top, bottom, left, right`.
-                let max_space_current_node = match width_calculated_arena[*variable_child_id].$preferred_field {
+                let max_space_current_node = match child_metrics.$preferred_field {
                     WhConstraint::EqualTo(e) => e,
                     WhConstraint::Between(min, max) => {
                         if relative_parent_width > min {
@@ -540,104 +1042,118 @@ impl NodeDataContainer<$struct_name> {
                 };
 
                 // so that node.min_inner_size_px + node.flex_grow_px = max_space_current_node
-                width_calculated_arena[*variable_child_id].flex_grow_px =
-                    max_space_current_node - width_calculated_arena[*variable_child_id].min_inner_size_px;
-
-                absolute_variable_width_nodes.push(*variable_child_id);
+                width_calculated_arena[variable_child_id].flex_grow_px =
+                    max_space_current_node - child_metrics.min_inner_size_px;
             }
-
         }
 
-        // Absolute positioned nodes aren't in the space-to-distribute set
-        for absolute_node in absolute_variable_width_nodes {
-            variable_width_childs.remove(&absolute_node);
+        // 3. Grow or shrink the variable-width children, so that they fill the remaining space
+        resolve_flexible_lengths(&mut flex_items, parent_node_inner_width);
+
+        for flex_item in flex_items {
+            // so that node.min_inner_size_px + node.flex_grow_px = flex_item.target_size
+            width_calculated_arena[flex_item.node_id].flex_grow_px =
+                flex_item.target_size - width_calculated_arena[flex_item.node_id].min_inner_size_px;
         }
+    }
 
-        // This satisfies the `width` and `min_width` constraints. However, we still need to worry about
-        // the `max_width` and unconstrained children.
-        //
-        // By setting the items to their minimum size, we've gained some space that we now need to distribute
-        // according to the flex_grow values
-        parent_node_inner_width -= horizontal_space_taken_up_by_variable_items;
+    /// Returns the flex item for an (in-flow) child, before its free space is distributed.
+    /// Items with a fixed size can't grow or shrink.
+    fn get_flex_item(
+        child_id: NodeId,
+        arena_data: &NodeDataContainer<RectLayout>,
+        width_calculated_arena: &NodeDataContainer<$struct_name>,
+    ) -> FlexItem {
+
+        let child_layout = &arena_data[child_id];
+        let child_metrics = width_calculated_arena[child_id];
+
+        if let WhConstraint::EqualTo(exact) = child_metrics.$preferred_field {
+            return FlexItem {
+                node_id: child_id,
+                flex_base_size: exact,
+                min_size: exact,
+                max_size: exact,
+                flex_grow: 0.0,
+                flex_shrink: 0.0,
+                target_size: 0.0,
+                frozen: false,
+            };
+        }
 
-        let mut total_horizontal_space_available = parent_node_inner_width;
-        let mut max_width_violations = Vec::new();
+        let min_needed_space = child_metrics.$preferred_field.min_needed_space().unwrap_or(0.0);
+        let max_size = child_metrics.$preferred_field.max_available_space().unwrap_or(f32::MAX);
+        let content_size = child_metrics.get_content_size() + child_metrics.$get_padding_fn();
 
-        loop {
+        FlexItem {
+            node_id: child_id,
+            flex_base_size: match child_layout.flex_basis.unwrap_or_default() {
+                LayoutFlexBasis::Auto => min_needed_space,
+                LayoutFlexBasis::Content => content_size,
+                LayoutFlexBasis::Exact(basis) => basis.to_pixels(),
+            },
+            // Without a min-width, the item can't shrink below its content (but
+            // a max-width always wins, same as for the width of the item itself).
+            // An explicit min-width - i.e. `min-width: 0` - replaces the size of the content.
+            min_size: if child_layout.$min_size_field.is_some() {
+                min_needed_space
+            } else {
+                content_size.min(max_size)
+            },
+            max_size,
+            flex_grow: child_layout.flex_grow.map(|grow| grow.0.get()).unwrap_or(DEFAULT_FLEX_GROW_FACTOR).max(0.0),
+            flex_shrink: child_layout.flex_shrink.map(|shrink| shrink.0.get()).unwrap_or(DEFAULT_FLEX_SHRINK_FACTOR).max(0.0),
+            target_size: 0.0,
+            frozen: false,
+        }
+    }
 
-            if total_horizontal_space_available <= 0.0 || variable_width_childs.is_empty() {
-                break;
-            }
+    /// Sizes the children of a row with `flex-wrap: wrap`: The children are broken into lines
+    /// (in the order of their `order` property), then each line is grown or shrunk on its own.
+    ///
+    /// Returns the (in-flow) children of each line. The lines are stacked vertically like the
+    /// rows of a grid, see `GridAxis::from_flex_lines`.
+    fn distribute_space_in_flex_lines(
+        node_id: &NodeId,
+        width_to_distribute: f32,
+        node_hierarchy: &NodeHierarchy,
+        arena_data: &NodeDataContainer<RectLayout>,
+        width_calculated_arena: &mut NodeDataContainer<$struct_name>,
+        positioned_node_stack: &[NodeId],
+    ) -> Vec<Vec<NodeId>> {
 
-            // In order to apply flex-grow correctly, we need the sum of
-            // the flex-grow factors of all the variable-width children
-            //
-            // NOTE: variable_width_childs can change its length, have to recalculate every loop!
-            let children_combined_flex_grow: f32 = variable_width_childs
-                .iter()
-                .map(|child_id|
-                        // Prevent flex-grow and flex-shrink to be less than 1
-                        arena_data[*child_id].flex_grow
-                            .map(|grow| grow.0.get().max(1.0))
-                            .unwrap_or(DEFAULT_FLEX_GROW_FACTOR))
-                .sum();
-
-            // Grow all variable children by the same amount.
-            for variable_child_id in &variable_width_childs {
-
-                let flex_grow = arena_data[*variable_child_id].flex_grow
-                    .and_then(|grow| Some(grow.0.get()))
-                    .unwrap_or(DEFAULT_FLEX_GROW_FACTOR);
-
-                // Do not expand the item on "flex-grow: 0" (prevent division by 0)
-                if flex_grow as usize == 0 {
-                    continue;
-                }
+        // `position: absolute` children aren't in any line, they are sized like in a flex container
+        Self::distribute_space_along_cross_axis(node_id, width_to_distribute, node_hierarchy, arena_data, width_calculated_arena, positioned_node_stack);
 
-                let added_space_for_one_child = total_horizontal_space_available * (flex_grow / children_combined_flex_grow);
+        let mut flex_items = get_children_in_flex_order(*node_id, node_hierarchy, arena_data)
+            .into_iter()
+            .filter(|child_id| arena_data[*child_id].position != Some(LayoutPosition::Absolute))
+            .map(|child_id| Self::get_flex_item(child_id, arena_data, width_calculated_arena))
+            .collect::<Vec<FlexItem>>();
 
-                let current_width_of_child = width_calculated_arena[*variable_child_id].min_inner_size_px +
-                                             width_calculated_arena[*variable_child_id].flex_grow_px;
+        let main_axis_gap = arena_data[*node_id].get_main_axis_gap();
+        let item_margins = flex_items.iter().map(|item| arena_data[item.node_id].$get_margin_fn()).collect::<Vec<f32>>();
+        let item_sizes = flex_items.iter().zip(item_margins.iter())
+            .map(|(item, margin)| item.get_hypothetical_size() + margin)
+            .collect::<Vec<f32>>();
 
-                if let Some(max_width) = width_calculated_arena[*variable_child_id].$preferred_field.max_available_space() {
-                    if (current_width_of_child + added_space_for_one_child) > max_width {
-                        // so that node.min_inner_size_px + node.flex_grow_px = max_width
-                        width_calculated_arena[*variable_child_id].flex_grow_px =
-                            max_width - width_calculated_arena[*variable_child_id].min_inner_size_px;
+        let lines = break_into_flex_lines(&item_sizes, main_axis_gap, width_to_distribute);
 
-                        max_width_violations.push(*variable_child_id);
-                    } else {
-                        // so that node.min_inner_size_px + node.flex_grow_px = added_space_for_one_child
-                        width_calculated_arena[*variable_child_id].flex_grow_px =
-                            added_space_for_one_child - width_calculated_arena[*variable_child_id].min_inner_size_px;
-                    }
-                } else {
-                    // so that node.min_inner_size_px + node.flex_grow_px = added_space_for_one_child
-                    width_calculated_arena[*variable_child_id].flex_grow_px =
-                        added_space_for_one_child - width_calculated_arena[*variable_child_id].min_inner_size_px;
-                }
-            }
+        for line in &lines {
+            let line_margins = item_margins[line.clone()].iter().sum::<f32>();
+            let line_gaps = main_axis_gap * (line.len() - 1) as f32;
+            resolve_flexible_lengths(&mut flex_items[line.clone()], width_to_distribute - line_margins - line_gaps);
+        }
 
-            // If we haven't violated any max_width constraints, then we have
-            // added all remaining widths and thereby successfully solved the layout
-            if max_width_violations.is_empty() {
-                break;
-            } else {
-                // Nodes that were violated can't grow anymore in the next iteration,
-                // so we remove them from the solution and consider them "solved".
-                // Their amount of violation then gets distributed across the remaining
-                // items in the next iteration.
-                for solved_node_id in max_width_violations.drain(..) {
-
-                    // Since the node now gets removed, it doesn't contribute to the pool anymore
-                    total_horizontal_space_available -=
-                        width_calculated_arena[solved_node_id].min_inner_size_px +
-                        width_calculated_arena[solved_node_id].flex_grow_px;
-
-                    variable_width_childs.remove(&solved_node_id);
-                }
-            }
+        for flex_item in &flex_items {
+            // so that node.min_inner_size_px + node.flex_grow_px = flex_item.target_size
+            width_calculated_arena[flex_item.node_id].flex_grow_px =
+                flex_item.target_size - width_calculated_arena[flex_item.node_id].min_inner_size_px;
         }
+
+        lines.into_iter()
+            .map(|line| flex_items[line].iter().map(|item| item.node_id).collect())
+            .collect()
     }
 
     fn distribute_space_along_cross_axis(
@@ -648,6 +1164,8 @@ impl NodeDataContainer<$struct_name> {
         width_calculated_arena: &mut NodeDataContainer<$struct_name>,
         positioned_node_stack: &[NodeId])
     {
+        use azul_css::LayoutAxis;
+
         let parent_node_inner_width = width_to_distribute;
 
         let last_relative_node_width = {
@@ -665,10 +1183,22 @@ impl NodeDataContainer<$struct_name> {
                 last_relative_node_width
             };
 
+            // Children with an `auto` margin on the cross axis are not stretched, so that they can be centered
+            let has_auto_margin = {
+                let child_margin = arena_data[child_id].margin.unwrap_or_default();
+                let (start, end) = match LayoutAxis::$main_axis {
+                    LayoutAxis::Horizontal => (child_margin.left, child_margin.right),
+                    LayoutAxis::Vertical => (child_margin.top, child_margin.bottom),
+                };
+                [start, end].iter().any(|side| side.map(|s| s.is_auto()).unwrap_or(false))
+            };
+
             let preferred_width = {
                 let min_width = width_calculated_arena[child_id].$preferred_field.min_needed_space().unwrap_or(0.0);
                 // In this case we want to overflow if the min width of the cross axis
-                if min_width > parent_node_inner_width {
+                if has_auto_margin {
+                    min_width.max(width_calculated_arena[child_id].min_inner_size_px)
+                } else if min_width > parent_node_inner_width {
                     min_width
                 } else {
                     if let Some(max_width) = width_calculated_arena[child_id].$preferred_field.max_available_space() {
@@ -688,6 +1218,75 @@ impl NodeDataContainer<$struct_name> {
                 preferred_width - width_calculated_arena[child_id].min_inner_size_px;
         }
     }
+
+    /// Sizes the tracks of a `display: grid` node, then stretches each item to fill the tracks
+    /// it spans (unless the item has a fixed width or a max-width that is smaller than its area)
+    fn distribute_space_in_grid(
+        node_id: &NodeId,
+        width_to_distribute: f32,
+        node_hierarchy: &NodeHierarchy,
+        arena_data: &NodeDataContainer<RectLayout>,
+        width_calculated_arena: &mut NodeDataContainer<$struct_name>,
+        positioned_node_stack: &[NodeId],
+        grid_axis: &mut GridAxis)
+    {
+        // `position: absolute` children aren't grid items, they are sized like in a flex container
+        Self::distribute_space_along_cross_axis(node_id, width_to_distribute, node_hierarchy, arena_data, width_calculated_arena, positioned_node_stack);
+
+        grid_axis.track_sizes = grid_axis.solve_track_sizes(width_to_distribute, |item_id| width_calculated_arena[item_id].$get_flex_basis());
+
+        for (item_id, start, span) in &grid_axis.items {
+
+            let area_width = grid_axis.get_area_size(*start, *span) - arena_data[*item_id].$get_margin_fn();
+
+            let item_width = match width_calculated_arena[*item_id].$preferred_field {
+                WhConstraint::EqualTo(exact) => exact,
+                WhConstraint::Between(min, max) => area_width.min(max).max(min),
+                WhConstraint::Unconstrained => area_width,
+            };
+
+            // so that node.min_inner_size_px + node.flex_grow_px = item_width
+            width_calculated_arena[*item_id].flex_grow_px =
+                item_width - width_calculated_arena[*item_id].min_inner_size_px;
+        }
+    }
+
+    /// Sizes the children of a node with inline children: Inline and floating children (and all
+    /// children along the vertical axis) shrink to their content, block children take the whole line.
+    /// The children are positioned later on, in `layout_inline_flows`.
+    fn distribute_space_in_inline_flow(
+        node_id: &NodeId,
+        width_to_distribute: f32,
+        node_hierarchy: &NodeHierarchy,
+        arena_data: &NodeDataContainer<RectLayout>,
+        width_calculated_arena: &mut NodeDataContainer<$struct_name>,
+        positioned_node_stack: &[NodeId])
+    {
+        use azul_css::LayoutAxis;
+
+        // `position: absolute` children aren't in the inline flow, they are sized like in a flex container
+        Self::distribute_space_along_cross_axis(node_id, width_to_distribute, node_hierarchy, arena_data, width_calculated_arena, positioned_node_stack);
+
+        for child_id in node_id.children(node_hierarchy) {
+
+            let is_block = !arena_data[child_id].is_inline_level() && !arena_data[child_id].is_floating();
+            if arena_data[child_id].position == Some(LayoutPosition::Absolute) || (is_block && LayoutAxis::$main_axis == LayoutAxis::Horizontal) {
+                continue;
+            }
+
+            let shrink_to_fit_width = width_calculated_arena[child_id].min_inner_size_px + width_calculated_arena[child_id].$get_padding_fn();
+
+            let item_width = match width_calculated_arena[child_id].$preferred_field {
+                WhConstraint::EqualTo(exact) => exact,
+                WhConstraint::Between(min, max) => shrink_to_fit_width.max(min).min(max),
+                WhConstraint::Unconstrained => shrink_to_fit_width,
+            };
+
+            // so that node.min_inner_size_px + node.flex_grow_px = item_width
+            width_calculated_arena[child_id].flex_grow_px =
+                item_width - width_calculated_arena[child_id].min_inner_size_px;
+        }
+    }
 }
 
 )}
@@ -696,8 +1295,10 @@ typed_arena!(
     WidthCalculatedRect,
     preferred_width,
     determine_preferred_width,
+    min_width,
     get_horizontal_padding,
     get_flex_basis_horizontal,
+    get_horizontal_margin,
     bubble_preferred_widths_to_parents,
     Horizontal
 );
@@ -706,8 +1307,10 @@ typed_arena!(
     HeightCalculatedRect,
     preferred_height,
     determine_preferred_height,
+    min_height,
     get_vertical_padding,
     get_flex_basis_vertical,
+    get_vertical_margin,
     bubble_preferred_heights_to_parents,
     Vertical
 );
@@ -741,11 +1344,39 @@ pub(crate) struct SolvedWidthLayout {
     pub solved_widths: NodeDataContainer<WidthSolvedResult>,
     pub layout_only_arena: NodeDataContainer<RectLayout>,
     pub non_leaf_nodes_sorted_by_depth: Vec<(usize, NodeId)>,
+    /// Minimum width that the root node needs before the flex-grow step
+    pub min_root_width: f32,
+    /// Columns of the `display: grid` nodes, the track sizes are solved
+    pub grid_columns: BTreeMap<NodeId, GridAxis>,
+    /// Rows of the `display: grid` nodes, the track sizes aren't solved yet
+    pub grid_rows: BTreeMap<NodeId, GridAxis>,
+    /// Nodes that lay out their children in an inline flow, see `get_inline_containers`
+    pub inline_containers: BTreeSet<NodeId>,
+    /// (In-flow) children of each line of the rows with `flex-wrap: wrap`
+    pub flex_lines: BTreeMap<NodeId, Vec<Vec<NodeId>>>,
+}
+
+impl SolvedWidthLayout {
+    /// Lines of a row with `flex-wrap: wrap`, `None` if the children of the node aren't wrapped
+    fn get_flex_lines(&self, node_id: &NodeId) -> Option<&Vec<Vec<NodeId>>> {
+        self.flex_lines.get(node_id)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct SolvedHeightLayout {
     pub solved_heights: NodeDataContainer<HeightSolvedResult>,
+    /// Minimum height that the root node needs before the flex-grow step
+    pub min_root_height: f32,
+    /// Rows of the `display: grid` nodes and the lines of the wrapping rows, the track sizes are solved
+    pub grid_rows: BTreeMap<NodeId, GridAxis>,
+}
+
+impl SolvedHeightLayout {
+    /// Only rows wrap - vertically, their lines are positioned like the rows of a grid
+    fn get_flex_lines(&self, _node_id: &NodeId) -> Option<&Vec<Vec<NodeId>>> {
+        None
+    }
 }
 
 /// Returns the solved widths of the items in a BTree form
@@ -753,15 +1384,122 @@ pub(crate) fn solve_flex_layout_width<'a>(
     node_hierarchy: &NodeHierarchy,
     display_rectangles: &NodeDataContainer<DisplayRectangle<'a>>,
     preferred_widths: &NodeDataContainer<Option<f32>>,
-    window_width: f32
+    intrinsic_image_sizes: &BTreeMap<NodeId, LayoutSize>,
+    window_size: LayoutSize
 ) -> SolvedWidthLayout {
-    let layout_only_arena = display_rectangles.transform(|node, _| node.layout);
-    let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&layout_only_arena, preferred_widths);
     let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
-    width_calculated_arena.bubble_preferred_widths_to_parents(node_hierarchy, &layout_only_arena, &non_leaf_nodes_sorted_by_depth);
-    width_calculated_arena.apply_flex_grow(node_hierarchy, &layout_only_arena, &non_leaf_nodes_sorted_by_depth, window_width);
+    // With `direction: rtl`, rows are laid out from the right to the left edge
+    let mut layout_only_arena = display_rectangles.transform(|node, _| RectLayout {
+        direction: node.layout.direction.map(|direction| direction.to_physical(node.style.text_direction.unwrap_or_default())),
+        .. node.layout
+    });
+    let calc_expressions = display_rectangles.linear_iter()
+        .filter(|node_id| !display_rectangles[*node_id].layout_calc.is_empty())
+        .map(|node_id| (node_id, &display_rectangles[node_id].layout_calc[..]))
+        .collect::<BTreeMap<NodeId, &[LayoutCalc]>>();
+    resolve_calc_expressions(node_hierarchy, &non_leaf_nodes_sorted_by_depth, &calc_expressions, &mut layout_only_arena, window_size);
+    apply_intrinsic_image_sizes(&mut layout_only_arena, intrinsic_image_sizes);
+    let (mut grid_columns, grid_rows) = get_grid_axes(node_hierarchy, display_rectangles, &layout_only_arena);
+    let inline_containers = get_inline_containers(node_hierarchy, &layout_only_arena, &grid_columns);
+    let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&layout_only_arena, preferred_widths);
+    width_calculated_arena.bubble_preferred_widths_to_parents(node_hierarchy, &layout_only_arena, &non_leaf_nodes_sorted_by_depth, &grid_columns, &inline_containers);
+    let min_root_width = width_calculated_arena.get_min_root_size();
+    let flex_lines = width_calculated_arena.apply_flex_grow(node_hierarchy, &layout_only_arena, &non_leaf_nodes_sorted_by_depth, window_size.width, &mut grid_columns, &inline_containers);
     let solved_widths = width_calculated_arena.transform(|node, _| node.solved_result());
-    SolvedWidthLayout { solved_widths , layout_only_arena, non_leaf_nodes_sorted_by_depth }
+    SolvedWidthLayout { solved_widths , layout_only_arena, non_leaf_nodes_sorted_by_depth, min_root_width, grid_columns, grid_rows, inline_containers, flex_lines }
+}
+
+/// Evaluates the `calc()` expressions of the dimension properties and writes the results into
+/// the `layout_only_arena` as fixed pixel values, so that the solver doesn't need to know about them.
+///
+/// Percentages are relative to the inner size of the parent or - if the parent has no fixed
+/// size - to the size that the parent itself would be resolved against (the window size for the root).
+/// Percentage paddings and margins are resolved the same way, but always against the width.
+fn resolve_calc_expressions(
+    node_hierarchy: &NodeHierarchy,
+    non_leaf_nodes_sorted_by_depth: &[(usize, NodeId)],
+    calc_expressions: &BTreeMap<NodeId, &[LayoutCalc]>,
+    layout_only_arena: &mut NodeDataContainer<RectLayout>,
+    window_size: LayoutSize,
+) {
+    fn get_inner_size(layout: &RectLayout, outer_size: LayoutSize) -> LayoutSize {
+        LayoutSize::new(
+            layout.width.map(|w| (w.0.to_pixels() - layout.get_horizontal_padding()).max(0.0)).unwrap_or(outer_size.width),
+            layout.height.map(|h| (h.0.to_pixels() - layout.get_vertical_padding()).max(0.0)).unwrap_or(outer_size.height),
+        )
+    }
+
+    if calc_expressions.is_empty() && !layout_only_arena.internal.iter().any(RectLayout::has_percentages) {
+        return;
+    }
+
+    let apply_calc_expressions = |layout: &mut RectLayout, node_id: NodeId, parent_size: LayoutSize| {
+        for calc in calc_expressions.get(&node_id).into_iter().flat_map(|calcs| calcs.iter()) {
+            calc.apply(layout, parent_size.width, parent_size.height);
+        }
+    };
+
+    let root_id = NodeId::new(0);
+    apply_calc_expressions(&mut layout_only_arena[root_id], root_id, window_size);
+    layout_only_arena[root_id].resolve_percentages(window_size.width);
+
+    // Size that the percentages of the children of a node are resolved against
+    let mut percentage_bases = BTreeMap::<NodeId, LayoutSize>::new();
+    percentage_bases.insert(root_id, get_inner_size(&layout_only_arena[root_id], window_size));
+
+    for (_node_depth, parent_id) in non_leaf_nodes_sorted_by_depth {
+        let parent_size = percentage_bases.get(parent_id).cloned().unwrap_or(window_size);
+        for child_id in parent_id.children(node_hierarchy) {
+            apply_calc_expressions(&mut layout_only_arena[child_id], child_id, parent_size);
+            layout_only_arena[child_id].resolve_percentages(parent_size.width);
+            if node_hierarchy[child_id].first_child.is_some() {
+                percentage_bases.insert(child_id, get_inner_size(&layout_only_arena[child_id], parent_size));
+            }
+        }
+    }
+}
+
+/// Gives the image nodes without a CSS `width` and `height` the size of their image (in logical
+/// pixels, see `AppResources::get_image_size`), plus their padding. If only one of them is set,
+/// the other one is derived from the aspect ratio of the image, so that the image isn't distorted.
+fn apply_intrinsic_image_sizes(
+    layout_only_arena: &mut NodeDataContainer<RectLayout>,
+    intrinsic_image_sizes: &BTreeMap<NodeId, LayoutSize>,
+) {
+    use azul_css::{LayoutWidth, LayoutHeight};
+
+    for (node_id, image_size) in intrinsic_image_sizes {
+
+        if image_size.width <= 0.0 || image_size.height <= 0.0 {
+            continue;
+        }
+
+        let layout = &mut layout_only_arena[*node_id];
+        let horizontal_padding = layout.get_horizontal_padding();
+        let vertical_padding = layout.get_vertical_padding();
+        let aspect_ratio = image_size.width / image_size.height;
+
+        match (layout.width, layout.height) {
+            (None, None) => {
+                let mut width = image_size.width + horizontal_padding;
+                if let Some(max_width) = layout.max_width {
+                    width = width.min(max_width.0.to_pixels());
+                }
+                if let Some(min_width) = layout.min_width {
+                    width = width.max(min_width.0.to_pixels());
+                }
+                layout.width = Some(LayoutWidth::px(width));
+                layout.height = Some(LayoutHeight::px((width - horizontal_padding).max(0.0) / aspect_ratio + vertical_padding));
+            },
+            (Some(width), None) => {
+                layout.height = Some(LayoutHeight::px((width.0.to_pixels() - horizontal_padding).max(0.0) / aspect_ratio + vertical_padding));
+            },
+            (None, Some(height)) => {
+                layout.width = Some(LayoutWidth::px((height.0.to_pixels() - vertical_padding).max(0.0) * aspect_ratio + horizontal_padding));
+            },
+            (Some(_), Some(_)) => { },
+        }
+    }
 }
 
 /// Returns the solved height of the items in a BTree form
@@ -772,11 +1510,17 @@ pub(crate) fn solve_flex_layout_height(
     window_height: f32
 ) -> SolvedHeightLayout {
     let SolvedWidthLayout { layout_only_arena, .. } = solved_widths;
+    let mut grid_rows = solved_widths.grid_rows.clone();
+    // The lines of the wrapping rows are stacked like the rows of a grid
+    grid_rows.extend(solved_widths.flex_lines.iter().map(|(node_id, lines)| {
+        (*node_id, GridAxis::from_flex_lines(lines, layout_only_arena[*node_id].get_row_gap()))
+    }));
     let mut height_calculated_arena = NodeDataContainer::<HeightCalculatedRect>::from_rect_layout_arena(&layout_only_arena, preferred_heights);
-    height_calculated_arena.bubble_preferred_heights_to_parents(node_hierarchy, &layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth);
-    height_calculated_arena.apply_flex_grow(node_hierarchy, &layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, window_height);
+    height_calculated_arena.bubble_preferred_heights_to_parents(node_hierarchy, &layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, &grid_rows, &solved_widths.inline_containers);
+    let min_root_height = height_calculated_arena.get_min_root_size();
+    height_calculated_arena.apply_flex_grow(node_hierarchy, &layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, window_height, &mut grid_rows, &solved_widths.inline_containers);
     let solved_heights = height_calculated_arena.transform(|node, _| node.solved_result());
-    SolvedHeightLayout { solved_heights }
+    SolvedHeightLayout { solved_heights, min_root_height, grid_rows }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
@@ -785,6 +1529,32 @@ pub(crate) struct HorizontalSolvedPosition(pub f32);
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub(crate) struct VerticalSolvedPosition(pub f32);
 
+/// Returns the space taken up by the `gap`s between the (in-flow) children
+/// of a flex container along its main axis
+fn get_total_main_axis_gap(
+    node_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    node_data: &NodeDataContainer<RectLayout>,
+) -> f32 {
+    let in_flow_children = node_id
+        .children(node_hierarchy)
+        .filter(|child_id| node_data[*child_id].position != Some(LayoutPosition::Absolute))
+        .count();
+    node_data[node_id].get_main_axis_gap() * in_flow_children.saturating_sub(1) as f32
+}
+
+/// Returns the children of the node, sorted by their `order` property. The
+/// sort is stable, so children with the same `order` keep their DOM order.
+fn get_children_in_flex_order(
+    node_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    node_data: &NodeDataContainer<RectLayout>,
+) -> Vec<NodeId> {
+    let mut children = node_id.children(node_hierarchy).collect::<Vec<NodeId>>();
+    children.sort_by_key(|child_id| node_data[*child_id].order.unwrap_or_default());
+    children
+}
+
 macro_rules! get_position {
 ($fn_name:ident,
  $width_layout:ident,
@@ -793,7 +1563,8 @@ macro_rules! get_position {
  $min_width:ident,
  $left:ident,
  $right:ident,
- $axis:ident
+ $axis:ident,
+ $grid_field:ident
 ) => (
 
 /// Traverses along the DOM and solve for the X or Y position
@@ -855,15 +1626,55 @@ fn $fn_name(
         }
     }
 
+    /// Returns the size of each `auto` margin along the main axis: the free space of the
+    /// flex line is divided equally between the `auto` margins of the (in-flow) children.
+    /// Returns `None` if no child has an `auto` margin.
+    fn get_auto_margin_size(
+        line: &[NodeId],
+        arena_data: &NodeDataContainer<RectLayout>,
+        solved_widths: &$width_layout,
+        parent_inner_width: f32,
+        main_axis_gap: f32,
+    ) -> Option<f32> {
+
+        let in_flow_children = line.iter()
+            .cloned()
+            .filter(|child_id| arena_data[*child_id].position.unwrap_or_default() != LayoutPosition::Absolute)
+            .collect::<Vec<_>>();
+
+        let auto_margin_count = in_flow_children.iter().map(|child_id| {
+            let child_margin = arena_data[*child_id].margin.unwrap_or_default();
+            [child_margin.$left, child_margin.$right].iter()
+                .filter(|side| side.map(|s| s.is_auto()).unwrap_or(false))
+                .count()
+        }).sum::<usize>();
+
+        if auto_margin_count == 0 {
+            return None;
+        }
+
+        let used_width = in_flow_children.iter().map(|child_id| {
+            let child_node = &solved_widths.$solved_widths_field[*child_id];
+            let child_margin = arena_data[*child_id].margin.unwrap_or_default();
+            child_node.$min_width + child_node.space_added
+            + child_margin.$left.map(|x| x.to_pixels()).unwrap_or(0.0)
+            + child_margin.$right.map(|x| x.to_pixels()).unwrap_or(0.0)
+        }).sum::<f32>() + main_axis_gap * in_flow_children.len().saturating_sub(1) as f32;
+
+        Some((parent_inner_width - used_width).max(0.0) / auto_margin_count as f32)
+    }
+
     fn determine_child_x_along_main_axis(
         parent_id: NodeId,
         main_axis_alignment: LayoutJustifyContent,
+        auto_margin_size: Option<f32>,
         arena_data: &NodeDataContainer<RectLayout>,
         arena_solved_data: &mut NodeDataContainer<$height_solved_position>,
         solved_widths: &$width_layout,
         child_id: NodeId,
         parent_x_position: f32,
         parent_inner_width: f32,
+        main_axis_gap: f32,
         sum_x_of_children_so_far: &mut f32,
         positioned_node_stack: &[NodeId],
     ) {
@@ -877,8 +1688,12 @@ fn $fn_name(
         // width: increase X according to the main axis, Y according to the cross_axis
         let child_node = &arena_data[child_id];
         let child_margin = child_node.margin.unwrap_or_default();
-        let child_margin_left = child_margin.$left.map(|x| x.to_pixels()).unwrap_or(0.0);
-        let child_margin_right = child_margin.$right.map(|x| x.to_pixels()).unwrap_or(0.0);
+        let resolve_margin = |margin: Option<PixelValue>| match (margin, auto_margin_size) {
+            (Some(m), Some(auto_margin_size)) if m.is_auto() => auto_margin_size,
+            (m, _) => m.map(|x| x.to_pixels()).unwrap_or(0.0),
+        };
+        let child_margin_left = resolve_margin(child_margin.$left);
+        let child_margin_right = resolve_margin(child_margin.$right);
 
         if child_node.position.unwrap_or_default() == LayoutPosition::Absolute {
             determine_child_x_absolute(
@@ -909,7 +1724,7 @@ fn $fn_name(
             };
 
             arena_solved_data[child_id].0 = x_of_top_left_corner;
-            *sum_x_of_children_so_far += child_margin_right + child_width_with_padding + child_margin_left;
+            *sum_x_of_children_so_far += child_margin_right + child_width_with_padding + child_margin_left + main_axis_gap;
         }
     }
 
@@ -919,10 +1734,13 @@ fn $fn_name(
         child_id: NodeId,
         positioned_node_stack: &[NodeId],
         arena_solved_data: &mut NodeDataContainer<$height_solved_position>,
-        parent_x_position: f32)
+        parent_x_position: f32,
+        parent_inner_width: f32)
     {
         let child_node = &arena_data[child_id];
-        let child_margin_left = child_node.margin.unwrap_or_default().$left.map(|x| x.to_pixels()).unwrap_or(0.0);
+        let child_margin = child_node.margin.unwrap_or_default();
+        let child_margin_left = child_margin.$left.map(|x| x.to_pixels()).unwrap_or(0.0);
+        let child_margin_right = child_margin.$right.map(|x| x.to_pixels()).unwrap_or(0.0);
 
         if child_node.position.unwrap_or_default() == LayoutPosition::Absolute {
             determine_child_x_absolute(
@@ -933,7 +1751,17 @@ fn $fn_name(
                 solved_widths
             );
         } else {
-            arena_solved_data[child_id].0 = parent_x_position + child_margin_left;
+            // `auto` margins push the child away from that side (or center it, if both are `auto`)
+            let free_width = {
+                let child_node = &solved_widths.$solved_widths_field[child_id];
+                (parent_inner_width - (child_node.$min_width + child_node.space_added) - child_margin_left - child_margin_right).max(0.0)
+            };
+            let auto_offset = match (child_margin.$left.map(|m| m.is_auto()), child_margin.$right.map(|m| m.is_auto())) {
+                (Some(true), Some(true)) => free_width / 2.0,
+                (Some(true), _) => free_width,
+                _ => 0.0,
+            };
+            arena_solved_data[child_id].0 = parent_x_position + child_margin_left + auto_offset;
         }
     }
 
@@ -966,55 +1794,103 @@ fn $fn_name(
             parent_node.$min_width + parent_node.space_added - (parent_padding_left + parent_padding_right)
         };
 
-        if parent_direction.get_axis() == LayoutAxis::$axis {
+        if let Some(grid_axis) = solved_widths.$grid_field.get(parent_id) {
+            // Grid: X of parent + offset of the first track of the item
+            for child_id in parent_id.children(node_hierarchy).filter(|ch| {
+                node_data[*ch].position.unwrap_or_default() == LayoutPosition::Absolute
+            }) {
+                determine_child_x_absolute(child_id, &positioned_node_stack, node_data, &mut arena_solved_data, solved_widths);
+            }
+
+            for (child_id, start, _) in &grid_axis.items {
+                let child_margin_left = node_data[*child_id].margin.unwrap_or_default().$left.map(|x| x.to_pixels()).unwrap_or(0.0);
+                arena_solved_data[*child_id].0 = parent_x_position + grid_axis.get_track_offset(*start) + child_margin_left;
+            }
+
+        } else if parent_direction.get_axis() == LayoutAxis::$axis {
             // Along main axis: Take X of parent
-            let main_axis_alignment = node_data[*parent_id].justify_content.unwrap_or_default();
-            let mut sum_x_of_children_so_far = 0.0;
+            let main_axis_gap = parent_node.get_main_axis_gap();
+
+            // Items are placed in the order of their `order` property, not in DOM order.
+            // With `flex-wrap: wrap`, each line is placed on its own.
+            let lines = match solved_widths.get_flex_lines(parent_id) {
+                Some(lines) => {
+                    for child_id in parent_id.children(node_hierarchy).filter(|ch| {
+                        node_data[*ch].position.unwrap_or_default() == LayoutPosition::Absolute
+                    }) {
+                        determine_child_x_absolute(child_id, &positioned_node_stack, node_data, &mut arena_solved_data, solved_widths);
+                    }
+                    lines.clone()
+                },
+                None => vec![get_children_in_flex_order(*parent_id, node_hierarchy, node_data)],
+            };
 
-            if parent_direction.is_reverse() {
-                for child_id in parent_id.reverse_children(node_hierarchy) {
-                    determine_child_x_along_main_axis(
-                        *parent_id,
-                        main_axis_alignment,
-                        &node_data,
-                        &mut arena_solved_data,
-                        solved_widths,
-                        child_id,
-                        parent_x_position,
-                        parent_inner_width,
-                        &mut sum_x_of_children_so_far,
-                        &positioned_node_stack,
-                    );
-                }
-            } else {
-                for child_id in parent_id.children(node_hierarchy) {
-                    determine_child_x_along_main_axis(
-                        *parent_id,
-                        main_axis_alignment,
-                        &node_data,
-                        &mut arena_solved_data,
-                        solved_widths,
-                        child_id,
-                        parent_x_position,
-                        parent_inner_width,
-                        &mut sum_x_of_children_so_far,
-                        &positioned_node_stack,
-                    );
+            for line in lines {
+
+                let auto_margin_size = get_auto_margin_size(&line, node_data, solved_widths, parent_inner_width, main_axis_gap);
+                // `auto` margins take up all the free space, so `justify-content` has no effect
+                let main_axis_alignment = match auto_margin_size {
+                    Some(_) => LayoutJustifyContent::Start,
+                    None => node_data[*parent_id].justify_content.unwrap_or_default(),
+                };
+                let mut sum_x_of_children_so_far = 0.0;
+
+                if parent_direction.is_reverse() {
+                    for child_id in line.iter().rev() {
+                        determine_child_x_along_main_axis(
+                            *parent_id,
+                            main_axis_alignment,
+                            auto_margin_size,
+                            &node_data,
+                            &mut arena_solved_data,
+                            solved_widths,
+                            *child_id,
+                            parent_x_position,
+                            parent_inner_width,
+                            main_axis_gap,
+                            &mut sum_x_of_children_so_far,
+                            &positioned_node_stack,
+                        );
+                    }
+                } else {
+                    for child_id in line.iter() {
+                        determine_child_x_along_main_axis(
+                            *parent_id,
+                            main_axis_alignment,
+                            auto_margin_size,
+                            &node_data,
+                            &mut arena_solved_data,
+                            solved_widths,
+                            *child_id,
+                            parent_x_position,
+                            parent_inner_width,
+                            main_axis_gap,
+                            &mut sum_x_of_children_so_far,
+                            &positioned_node_stack,
+                        );
+                    }
                 }
-            }
 
-            // If the direction is `flex-end`, we can't add the X position during the iteration,
-            // so we have to "add" the diff to the parent_inner_width at the end
-            let should_align_towards_end =
-                (!parent_direction.is_reverse() && main_axis_alignment == LayoutJustifyContent::End) ||
-                (parent_direction.is_reverse() && main_axis_alignment == LayoutJustifyContent::Start);
+                // If the direction is `flex-end`, we can't add the X position during the iteration,
+                // so we have to "add" the diff to the parent_inner_width at the end
+                let should_align_towards_end =
+                    (!parent_direction.is_reverse() && main_axis_alignment == LayoutJustifyContent::End) ||
+                    (parent_direction.is_reverse() && main_axis_alignment == LayoutJustifyContent::Start);
 
-            if should_align_towards_end {
-                let diff = parent_inner_width - sum_x_of_children_so_far;
-                for child_id in parent_id.children(node_hierarchy).filter(|ch| {
+                let in_flow_children = line.iter().cloned().filter(|ch| {
                     node_data[*ch].position.unwrap_or_default() != LayoutPosition::Absolute
-                }) {
-                    arena_solved_data[child_id].0 += diff;
+                }).collect::<Vec<NodeId>>();
+
+                // There is no gap after the last child
+                if !in_flow_children.is_empty() {
+                    sum_x_of_children_so_far -= main_axis_gap;
+                }
+
+                if should_align_towards_end {
+                    let diff = parent_inner_width - sum_x_of_children_so_far;
+                    for child_id in in_flow_children {
+                        arena_solved_data[child_id].0 += diff;
+                    }
                 }
             }
 
@@ -1030,6 +1906,7 @@ fn $fn_name(
                         &positioned_node_stack,
                         &mut arena_solved_data,
                         parent_x_position,
+                        parent_inner_width,
                     );
                 }
             } else {
@@ -1041,6 +1918,7 @@ fn $fn_name(
                         &positioned_node_stack,
                         &mut arena_solved_data,
                         parent_x_position,
+                        parent_inner_width,
                     );
                 }
             }
@@ -1063,7 +1941,7 @@ fn get_x_positions(
     origin: LayoutPoint,
 ) -> NodeDataContainer<HorizontalSolvedPosition>
 {
-    get_position!(get_pos_x, SolvedWidthLayout, HorizontalSolvedPosition, solved_widths, min_width, left, right, Horizontal);
+    get_position!(get_pos_x, SolvedWidthLayout, HorizontalSolvedPosition, solved_widths, min_width, left, right, Horizontal, grid_columns);
     let mut arena = get_pos_x(node_hierarchy, &solved_widths.layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, solved_widths);
 
     // Add the origin on top of the position
@@ -1083,7 +1961,7 @@ fn get_y_positions(
     origin: LayoutPoint
 ) -> NodeDataContainer<VerticalSolvedPosition>
 {
-    get_position!(get_pos_y, SolvedHeightLayout, VerticalSolvedPosition, solved_heights, min_height, top, bottom, Vertical);
+    get_position!(get_pos_y, SolvedHeightLayout, VerticalSolvedPosition, solved_heights, min_height, top, bottom, Vertical, grid_rows);
     let mut arena = get_pos_y(node_hierarchy, &solved_widths.layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, solved_heights);
 
     // Add the origin on top of the position
@@ -1102,11 +1980,12 @@ fn get_content_width<T>(
         node_id: &NodeId,
         node_type: &NodeType<T>,
         app_resources: &AppResources,
+        hidpi_factor: f32,
         positioned_words: &BTreeMap<NodeId, (WordPositions, FontInstanceKey)>,
 ) -> Option<f32> {
     use dom::NodeType::*;
     match node_type {
-        Image(image_id) => app_resources.get_image_info(image_id).map(|info| info.descriptor.size.width as f32),
+        Image(image_id) => app_resources.get_image_size(image_id, hidpi_factor).map(|(width, _)| width),
         Label(_) | Text(_) => positioned_words.get(node_id).map(|pos| pos.0.content_size.width),
         _ => None,
     }
@@ -1116,17 +1995,18 @@ fn get_content_height<T>(
     node_id: &NodeId,
     node_type: &NodeType<T>,
     app_resources: &AppResources,
+    hidpi_factor: f32,
     positioned_words: &BTreeMap<NodeId, (WordPositions, FontInstanceKey)>,
     div_width: f32,
 ) -> Option<PreferredHeight> {
     use dom::NodeType::*;
     match &node_type {
         Image(i) => {
-            let image_size = &app_resources.get_image_info(i)?.descriptor.size;
-            let aspect_ratio = image_size.width as f32 / image_size.height as f32;
-            let preferred_height = div_width * aspect_ratio;
+            let (image_width, image_height) = app_resources.get_image_size(i, hidpi_factor)?;
+            let aspect_ratio = image_width / image_height;
+            let preferred_height = div_width / aspect_ratio;
             Some(PreferredHeight::Image {
-                original_dimensions: (image_size.width as usize, image_size.height as usize),
+                original_dimensions: (image_width as usize, image_height as usize),
                 aspect_ratio,
                 preferred_height,
             })
@@ -1185,6 +2065,8 @@ pub struct PositionedRectangle {
     /// that image can be bigger than the actual rect
     pub content_width: Option<f32>,
     pub content_height: Option<f32>,
+    /// Padding of the rect, with percentages resolved to pixels
+    pub padding: LayoutPadding,
 }
 
 #[derive(Debug, Clone)]
@@ -1194,6 +2076,185 @@ pub struct LayoutResult {
     pub scaled_words: BTreeMap<NodeId, (ScaledWords, FontInstanceKey)>,
     pub positioned_word_cache: BTreeMap<NodeId, (WordPositions, FontInstanceKey)>,
     pub node_depths: Vec<(usize, NodeId)>,
+    /// Smallest size that the DOM can be laid out at without violating the `width`, `min-width`,
+    /// `height` and `min-height` constraints, paddings and margins of its nodes. Text and image
+    /// content is ignored, since text can wrap and images can be scaled.
+    ///
+    /// Only solved if it was requested (see `WindowSize::min_dimensions_from_layout`),
+    /// since it needs an additional pass over the heights of all nodes.
+    pub min_content_size: Option<LayoutSize>,
+    /// How the sizes of the `rects` were derived, see `DebugState::layout_debug`
+    pub(crate) traces: NodeDataContainer<LayoutTrace>,
+}
+
+/// Intermediate results of the layout of one node, dumped by `DebugState::layout_debug`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct LayoutTrace {
+    /// Size of the text or image of the node (`None` if the node has no content)
+    pub content_width: Option<f32>,
+    pub content_height: Option<f32>,
+    /// Size before the flex-grow step: the largest of the `width` / `min-width`,
+    /// the content and the children, including the padding
+    pub min_width: f32,
+    pub min_height: f32,
+    /// Space that the node gained by growing (`flex-grow`) or stretching (`align-items`)
+    pub width_added: f32,
+    pub height_added: f32,
+}
+
+/// Everything that the result of `do_the_layout` depends on
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct LayoutCacheKey {
+    /// Changes whenever a node of the DOM changes in a way that affects the
+    /// layout, see `UiDescription::layout_generation`
+    layout_generation: usize,
+    /// Hash of the texts, fonts and images that the nodes reference, since the
+    /// nodes only store their IDs and the content can change while the ID stays the same
+    resources_hash: u64,
+    /// Bits of the `rect_size` and the `rect_offset` (since `f32` can't be hashed)
+    rect_size: (u32, u32),
+    rect_offset: (u32, u32),
+    /// Whether the `min_content_size` of the layout is solved
+    solve_min_content_size: bool,
+}
+
+impl LayoutCacheKey {
+    pub(crate) fn new<'a, T>(
+        layout_generation: usize,
+        node_data: &NodeDataContainer<NodeData<T>>,
+        display_rects: &NodeDataContainer<DisplayRectangle<'a>>,
+        app_resources: &AppResources,
+        hidpi_factor: f32,
+        rect_size: LayoutSize,
+        rect_offset: LayoutPoint,
+        solve_min_content_size: bool,
+    ) -> Self {
+
+        use std::{hash::{Hash, Hasher}, collections::hash_map::DefaultHasher};
+
+        let mut hasher = DefaultHasher::new();
+
+        for node_id in node_data.linear_iter() {
+            match &node_data[node_id].node_type {
+                NodeType::Label(_) => {
+                    get_font_instance(app_resources, &display_rects[node_id].style).map(|(_, key)| key).hash(&mut hasher);
+                },
+                NodeType::Text(text_id) => {
+                    app_resources.get_text(text_id).hash(&mut hasher);
+                    get_font_instance(app_resources, &display_rects[node_id].style).map(|(_, key)| key).hash(&mut hasher);
+                },
+                NodeType::Image(image_id) => {
+                    app_resources.get_image_size(image_id, hidpi_factor)
+                        .map(|(width, height)| (width.to_bits(), height.to_bits()))
+                        .hash(&mut hasher);
+                },
+                _ => { },
+            }
+        }
+
+        Self {
+            layout_generation,
+            resources_hash: hasher.finish(),
+            rect_size: (rect_size.width.to_bits(), rect_size.height.to_bits()),
+            rect_offset: (rect_offset.x.to_bits(), rect_offset.y.to_bits()),
+            solve_min_content_size,
+        }
+    }
+}
+
+/// Solved layouts of the last frame, stored per window. Since the DOM is rebuilt on
+/// every frame, a frame in which no node changed (see `UiDescription::layout_generation`)
+/// and that has the same size can reuse the last layout instead of running the flexbox solver again.
+pub(crate) struct LayoutCache {
+    layouts: BTreeMap<LayoutCacheKey, LayoutResult>,
+    /// Keys that were used during the current frame
+    used_keys: BTreeSet<LayoutCacheKey>,
+}
+
+impl LayoutCache {
+
+    pub(crate) fn new() -> Self {
+        Self {
+            layouts: BTreeMap::new(),
+            used_keys: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the layout that was solved for the `key` or solves and caches it via `do_layout`
+    pub(crate) fn get_or_layout<F: FnOnce() -> LayoutResult>(&mut self, key: LayoutCacheKey, do_layout: F) -> LayoutResult {
+        self.used_keys.insert(key);
+        self.layouts.entry(key).or_insert_with(do_layout).clone()
+    }
+
+    /// Drops all layouts that weren't used during the current frame
+    pub(crate) fn end_frame(&mut self) {
+        let used_keys = mem::replace(&mut self.used_keys, BTreeSet::new());
+        let layouts = mem::replace(&mut self.layouts, BTreeMap::new());
+        self.layouts = layouts.into_iter().filter(|(key, _)| used_keys.contains(key)).collect();
+    }
+}
+
+#[cfg(test)]
+fn get_testing_layout_cache_key(layout_generation: usize, width: f32) -> LayoutCacheKey {
+    LayoutCacheKey {
+        layout_generation,
+        resources_hash: 0,
+        rect_size: (width.to_bits(), 600.0_f32.to_bits()),
+        rect_offset: (0, 0),
+        solve_min_content_size: false,
+    }
+}
+
+#[cfg(test)]
+fn get_testing_layout_result(width: f32) -> LayoutResult {
+    LayoutResult {
+        rects: NodeDataContainer::new(Vec::new()),
+        word_cache: BTreeMap::new(),
+        scaled_words: BTreeMap::new(),
+        positioned_word_cache: BTreeMap::new(),
+        node_depths: Vec::new(),
+        min_content_size: Some(LayoutSize::new(width, 600.0)),
+        traces: NodeDataContainer::new(Vec::new()),
+    }
+}
+
+#[test]
+fn test_layout_cache_reuses_layout_of_same_generation() {
+
+    let mut cache = LayoutCache::new();
+    let mut layout_count = 0;
+
+    for _ in 0..3 {
+        let layout = cache.get_or_layout(get_testing_layout_cache_key(1, 800.0), || { layout_count += 1; get_testing_layout_result(800.0) });
+        assert_eq!(layout.min_content_size, Some(LayoutSize::new(800.0, 600.0)));
+        cache.end_frame();
+    }
+
+    assert_eq!(layout_count, 1);
+
+    // A new generation or a new size has to be laid out again
+    cache.get_or_layout(get_testing_layout_cache_key(2, 800.0), || { layout_count += 1; get_testing_layout_result(800.0) });
+    cache.get_or_layout(get_testing_layout_cache_key(2, 1024.0), || { layout_count += 1; get_testing_layout_result(1024.0) });
+    assert_eq!(layout_count, 3);
+}
+
+#[test]
+fn test_layout_cache_drops_unused_layouts() {
+
+    let mut cache = LayoutCache::new();
+
+    cache.get_or_layout(get_testing_layout_cache_key(1, 800.0), || get_testing_layout_result(800.0));
+    cache.get_or_layout(get_testing_layout_cache_key(1, 1024.0), || get_testing_layout_result(1024.0));
+    cache.end_frame();
+    assert_eq!(cache.layouts.len(), 2);
+
+    cache.get_or_layout(get_testing_layout_cache_key(1, 1024.0), || get_testing_layout_result(1024.0));
+    cache.end_frame();
+    assert_eq!(cache.layouts.len(), 1);
+    assert!(cache.layouts.contains_key(&get_testing_layout_cache_key(1, 1024.0)));
+
+    cache.end_frame();
+    assert!(cache.layouts.is_empty());
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -1204,6 +2265,470 @@ struct InlineText {
     horizontal_margin: f32,
 }
 
+/// Children of a node with `display: inline` children, positioned in lines. The rectangles
+/// are relative to the upper left corner of the content box (inside of the padding) of the node.
+#[derive(Debug, Clone, PartialEq)]
+struct InlineFlow {
+    /// Inline-block children, images, floats and block children (rect without the margin)
+    boxes: Vec<(NodeId, LayoutRect)>,
+    /// `display: inline` text nodes and block text nodes next to a float: A text run that spans
+    /// multiple lines covers the whole width of the node, a single-line run only covers its own text
+    text_runs: Vec<(NodeId, LayoutRect)>,
+    /// Height of all lines together
+    content_height: f32,
+}
+
+/// Current position in the lines of an `InlineFlow`
+#[derive(Debug, Clone, PartialEq)]
+struct InlineFlowCursor {
+    /// Width of the content box of the node, the lines wrap at this width
+    max_width: f32,
+    /// Horizontal position in the current line
+    x: f32,
+    /// Top of the current line
+    line_top: f32,
+    /// Height of the tallest item in the current line
+    line_height: f32,
+    /// Margin boxes of the `float: left` children that have been placed so far
+    left_floats: Vec<LayoutRect>,
+    /// Margin boxes of the `float: right` children that have been placed so far
+    right_floats: Vec<LayoutRect>,
+}
+
+impl InlineFlowCursor {
+
+    fn new(max_width: f32) -> Self {
+        Self { max_width, x: 0.0, line_top: 0.0, line_height: 0.0, left_floats: Vec::new(), right_floats: Vec::new() }
+    }
+
+    fn break_line(&mut self) {
+        self.line_top += self.line_height;
+        self.line_height = 0.0;
+        self.x = 0.0;
+    }
+
+    /// Starts a new line, unless the current line is empty
+    fn finish_line(&mut self) {
+        if self.x > 0.0 || self.line_height > 0.0 {
+            self.break_line();
+        }
+    }
+
+    /// Returns the left and right edge of the space that isn't covered
+    /// by floats, in the band from `top` to `top + height`
+    fn get_free_space(&self, top: f32, height: f32) -> (f32, f32) {
+        let overlaps = |rect: &&LayoutRect| rect.origin.y < top + height && rect.origin.y + rect.size.height > top;
+        let left = self.left_floats.iter().filter(overlaps).map(|rect| rect.origin.x + rect.size.width).fold(0.0, f32::max);
+        let right = self.right_floats.iter().filter(overlaps).map(|rect| rect.origin.x).fold(self.max_width, f32::min);
+        (left, right)
+    }
+
+    /// Returns the bottom of the first float that ends below `top`, the next
+    /// position where a line could have more space
+    fn get_next_float_bottom(&self, top: f32) -> Option<f32> {
+        self.left_floats.iter().chain(self.right_floats.iter())
+            .map(|rect| rect.origin.y + rect.size.height)
+            .filter(|bottom| *bottom > top)
+            .fold(None, |next: Option<f32>, bottom| Some(next.map_or(bottom, |next| next.min(bottom))))
+    }
+
+    /// Places a box (size including the margin) at the cursor or at the start of the next
+    /// line if the box doesn't fit into the current line. Returns the upper left corner of the box.
+    fn place_box(&mut self, size: LayoutSize) -> LayoutPoint {
+
+        let x = loop {
+            let (left, right) = self.get_free_space(self.line_top, self.line_height.max(size.height));
+            let x = self.x.max(left);
+            if x + size.width <= right {
+                break x;
+            }
+            if x > left {
+                // Doesn't fit into the rest of the line
+                self.break_line();
+                continue;
+            }
+            // The line is empty, but too narrow because of the floats - continue below the next float
+            match self.get_next_float_bottom(self.line_top) {
+                Some(float_bottom) => {
+                    self.line_top = float_bottom.max(self.line_top + self.line_height);
+                    self.line_height = 0.0;
+                },
+                None => break x,
+            }
+        };
+
+        let origin = LayoutPoint::new(x, self.line_top);
+        self.x = x + size.width;
+        self.line_height = self.line_height.max(size.height);
+        origin
+    }
+
+    /// Places a floating box (size including the margin) at the left or right edge of the highest
+    /// position where it fits, but not above the current line or an earlier float. Content that
+    /// is already in the current line isn't moved, so in that case the float starts below the line.
+    fn place_float(&mut self, size: LayoutSize, float: LayoutFloat) -> LayoutPoint {
+
+        let mut top = if self.x > 0.0 { self.line_top + self.line_height } else { self.line_top };
+        top = self.left_floats.iter().chain(self.right_floats.iter()).map(|rect| rect.origin.y).fold(top, f32::max);
+
+        loop {
+            let (left, right) = self.get_free_space(top, size.height);
+            if left + size.width <= right {
+                break;
+            }
+            match self.get_next_float_bottom(top) {
+                Some(float_bottom) => top = float_bottom,
+                None => break,
+            }
+        }
+
+        let (left, right) = self.get_free_space(top, size.height);
+
+        if float == LayoutFloat::Right {
+            let origin = LayoutPoint::new((right - size.width).max(left), top);
+            self.right_floats.push(LayoutRect::new(origin, size));
+            origin
+        } else {
+            let origin = LayoutPoint::new(left, top);
+            self.left_floats.push(LayoutRect::new(origin, size));
+            origin
+        }
+    }
+
+    /// Returns the floats as holes for a text run that starts at `run_top` (see
+    /// `TextLayoutOptions::holes`). The holes are relative to the text run and reach one
+    /// line further down, since a line only wraps if its bottom is inside of a hole.
+    fn get_text_holes(&self, run_top: f32, line_stride: f32) -> Vec<LayoutRect> {
+        self.left_floats.iter().chain(self.right_floats.iter())
+            .filter(|rect| rect.origin.y + rect.size.height > run_top)
+            .map(|rect| LayoutRect::new(
+                LayoutPoint::new(rect.origin.x, rect.origin.y - run_top),
+                LayoutSize::new(rect.size.width, rect.size.height + line_stride),
+            ))
+            .collect()
+    }
+
+    /// Places a block-level box on a line of its own
+    fn place_block(&mut self, size: LayoutSize) -> LayoutPoint {
+        self.finish_line();
+        let origin = LayoutPoint::new(0.0, self.line_top);
+        self.line_height = size.height;
+        self.break_line();
+        origin
+    }
+
+    /// Returns the top of a text run that starts at the cursor: If the current line is taller than
+    /// a line of the text (i.e. because of an image), the text is moved down to the bottom of the line.
+    fn get_text_run_top(&self, line_stride: f32) -> f32 {
+        self.line_top + (self.line_height - line_stride).max(0.0)
+    }
+
+    /// Moves the cursor to the end of a text run that started at the cursor,
+    /// `trailing` is the width of the last line of the text run
+    fn advance_text_run(&mut self, number_of_lines: usize, line_stride: f32, trailing: f32) {
+        let run_top = self.get_text_run_top(line_stride);
+        if number_of_lines > 1 {
+            self.line_top = run_top + line_stride * (number_of_lines - 1) as f32;
+            self.line_height = line_stride;
+        } else {
+            self.line_height = self.line_height.max(run_top - self.line_top + line_stride);
+        }
+        self.x = trailing;
+    }
+
+    /// Height of all lines, floats that reach below the last line are included
+    fn get_content_height(&self) -> f32 {
+        self.left_floats.iter().chain(self.right_floats.iter())
+            .map(|rect| rect.origin.y + rect.size.height)
+            .fold(self.line_top + self.line_height, f32::max)
+    }
+}
+
+/// Lays out the children of all nodes with `display: inline` children in lines: Text nodes with
+/// `display: inline` continue where the previous child left off and wrap at the width of the
+/// parent, all other inline children (images, `display: inline-block`) are placed as one box.
+/// Block children are placed on a line of their own. Floating children are moved to the left or
+/// right edge of the parent, the text of the following children (inline or block) wraps around them.
+///
+/// Overwrites the word positions of the inline text nodes, since they depend on the
+/// position of the previous child.
+fn layout_inline_flows<'a>(
+    node_hierarchy: &NodeHierarchy,
+    display_rects: &NodeDataContainer<DisplayRectangle<'a>>,
+    solved_widths: &SolvedWidthLayout,
+    solved_heights: &SolvedHeightLayout,
+    word_cache: &BTreeMap<NodeId, Words>,
+    scaled_words: &BTreeMap<NodeId, (ScaledWords, FontInstanceKey)>,
+    word_positions: &mut BTreeMap<NodeId, (WordPositions, FontInstanceKey)>,
+) -> BTreeMap<NodeId, InlineFlow> {
+
+    use text_layout::{self, WordType};
+
+    let layouts = &solved_widths.layout_only_arena;
+
+    solved_widths.inline_containers.iter().map(|container_id| {
+
+        let max_width = solved_widths.solved_widths[*container_id].total() - layouts[*container_id].get_horizontal_padding();
+        let mut cursor = InlineFlowCursor::new(max_width);
+        let mut boxes = Vec::new();
+        let mut text_runs = Vec::new();
+
+        for child_id in container_id.children(node_hierarchy) {
+
+            let layout = &layouts[child_id];
+            if layout.position == Some(LayoutPosition::Absolute) {
+                continue;
+            }
+
+            let is_block = !layout.is_inline_level() && !layout.is_floating();
+
+            let text = if layout.display == Some(LayoutDisplay::Inline) || is_block {
+                word_cache.get(&child_id).and_then(|words| Some((words, scaled_words.get(&child_id)?)))
+            } else {
+                None
+            };
+
+            // Block text is only laid out as a text run if it has to wrap around a float
+            if is_block && text.is_some() {
+                cursor.finish_line();
+            }
+            let text = text.filter(|_| !is_block || cursor.get_next_float_bottom(cursor.line_top).is_some());
+
+            if let Some((words, (scaled_words, font_instance_key))) = text {
+
+                let leading = cursor.x;
+                let rect = &display_rects[child_id];
+                let font_size_px = get_font_size(&rect.style).0.to_pixels();
+                let mut text_layout_options = get_text_layout_options(rect, Some(max_width), Some(leading), Vec::new());
+                let line_stride = text_layout::get_line_stride(scaled_words, &text_layout_options, font_size_px);
+                let run_top = cursor.get_text_run_top(line_stride);
+                text_layout_options.holes = cursor.get_text_holes(run_top, line_stride);
+
+                let mut positions = text_layout::position_words(words, scaled_words, &text_layout_options, font_size_px);
+
+                // A trailing space separates the text from the next child
+                let ends_with_space = words.items.last().map(|word| word.word_type == WordType::Space || word.word_type == WordType::Tab).unwrap_or(false);
+                let trailing_space = if ends_with_space { text_layout::get_word_spacing(scaled_words, &text_layout_options) } else { 0.0 };
+                cursor.advance_text_run(positions.number_of_lines, line_stride, positions.trailing + trailing_space);
+                if is_block {
+                    cursor.break_line();
+                }
+
+                let run_rect = if positions.number_of_lines == 1 && !is_block {
+                    // Make the words relative to the start of the text run (a float
+                    // can push the first word to the right of the leading)
+                    let run_start = positions.word_positions.first().map(|word_position| word_position.x).unwrap_or(leading);
+                    for word_position in &mut positions.word_positions {
+                        word_position.x -= run_start;
+                    }
+                    for (_, line_length) in &mut positions.line_breaks {
+                        *line_length -= run_start;
+                    }
+                    positions.trailing -= run_start;
+                    positions.content_size.width = positions.trailing;
+                    LayoutRect::new(LayoutPoint::new(run_start, run_top), positions.content_size)
+                } else {
+                    LayoutRect::new(LayoutPoint::new(0.0, run_top), positions.content_size)
+                };
+
+                text_runs.push((child_id, run_rect));
+                word_positions.insert(child_id, (positions, *font_instance_key));
+
+            } else {
+
+                let margin = layout.margin.unwrap_or_default();
+                let size = LayoutSize::new(solved_widths.solved_widths[child_id].total(), solved_heights.solved_heights[child_id].total());
+                let margin_box_size = LayoutSize::new(size.width + layout.get_horizontal_margin(), size.height + layout.get_vertical_margin());
+
+                let margin_box_origin = if layout.is_floating() {
+                    cursor.place_float(margin_box_size, layout.float.unwrap_or_default())
+                } else if layout.is_inline_level() {
+                    cursor.place_box(margin_box_size)
+                } else {
+                    cursor.place_block(margin_box_size)
+                };
+
+                let origin = LayoutPoint::new(
+                    margin_box_origin.x + margin.left.map(|m| m.to_pixels()).unwrap_or(0.0),
+                    margin_box_origin.y + margin.top.map(|m| m.to_pixels()).unwrap_or(0.0),
+                );
+
+                boxes.push((child_id, LayoutRect::new(origin, size)));
+            }
+        }
+
+        (*container_id, InlineFlow { boxes, text_runs, content_height: cursor.get_content_height() })
+    }).collect()
+}
+
+/// Moves a node and all of its children by `(dx, dy)`
+fn translate_subtree(
+    node_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    dx: f32,
+    dy: f32,
+    x_positions: &mut NodeDataContainer<HorizontalSolvedPosition>,
+    y_positions: &mut NodeDataContainer<VerticalSolvedPosition>,
+) {
+    let mut stack = vec![node_id];
+    while let Some(current_id) = stack.pop() {
+        x_positions[current_id].0 += dx;
+        y_positions[current_id].0 += dy;
+        stack.extend(current_id.children(node_hierarchy));
+    }
+}
+
+/// Grows the node so that its children (after they were moved down by the baseline alignment)
+/// fit into it again. The growth is propagated to the ancestors: In a column, the siblings
+/// that come after the grown node are moved down, then the parent is grown if necessary.
+///
+/// Nodes with a fixed `height` and the root node (which always has the size of the window) don't grow.
+fn grow_to_fit_children(
+    node_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    solved_widths: &SolvedWidthLayout,
+    solved_heights: &mut SolvedHeightLayout,
+    x_positions: &mut NodeDataContainer<HorizontalSolvedPosition>,
+    y_positions: &mut NodeDataContainer<VerticalSolvedPosition>,
+) {
+    use azul_css::LayoutAxis;
+
+    let node_data = &solved_widths.layout_only_arena;
+    let mut current_id = node_id;
+
+    while let Some(parent_id) = node_hierarchy[current_id].parent {
+
+        let layout = &node_data[current_id];
+        if layout.height.is_some() {
+            return;
+        }
+
+        let padding_bottom = layout.padding.unwrap_or_default().bottom.map(|p| p.to_pixels()).unwrap_or(0.0);
+        let content_bottom = y_positions[current_id].0 + solved_heights.solved_heights[current_id].total() - padding_bottom;
+
+        let children_bottom = current_id.children(node_hierarchy)
+            .filter(|child_id| node_data[*child_id].position != Some(LayoutPosition::Absolute))
+            .map(|child_id| {
+                let margin_bottom = node_data[child_id].margin.unwrap_or_default().bottom.map(|p| p.to_pixels()).unwrap_or(0.0);
+                y_positions[child_id].0 + solved_heights.solved_heights[child_id].total() + margin_bottom
+            })
+            .fold(f32::MIN, f32::max);
+
+        let growth = children_bottom - content_bottom;
+        if growth <= 0.0 {
+            return;
+        }
+
+        solved_heights.solved_heights[current_id].space_added += growth;
+
+        let parent_is_column =
+            node_data[parent_id].direction.unwrap_or_default().get_axis() == LayoutAxis::Vertical &&
+            !solved_widths.grid_columns.contains_key(&parent_id) &&
+            !solved_widths.inline_containers.contains(&parent_id);
+
+        if parent_is_column {
+            let following_siblings = get_children_in_flex_order(parent_id, node_hierarchy, node_data)
+                .into_iter()
+                .skip_while(|sibling_id| *sibling_id != current_id)
+                .skip(1)
+                .filter(|sibling_id| node_data[*sibling_id].position != Some(LayoutPosition::Absolute))
+                .collect::<Vec<NodeId>>();
+            for sibling_id in following_siblings {
+                translate_subtree(sibling_id, node_hierarchy, 0.0, growth, x_positions, y_positions);
+            }
+        }
+
+        current_id = parent_id;
+    }
+}
+
+/// Returns the distance from the top of the node to the baseline of its first line of text:
+/// Either the baseline of the text of the node itself or (recursively) the baseline of its
+/// first child that has one. Returns `None` if the node doesn't contain any text.
+fn get_first_baseline<'a>(
+    node_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    display_rects: &NodeDataContainer<DisplayRectangle<'a>>,
+    solved_heights: &SolvedHeightLayout,
+    scaled_words: &BTreeMap<NodeId, (ScaledWords, FontInstanceKey)>,
+    word_positions: &BTreeMap<NodeId, (WordPositions, FontInstanceKey)>,
+    y_positions: &NodeDataContainer<VerticalSolvedPosition>,
+) -> Option<f32> {
+
+    use text_layout;
+
+    let rect = &display_rects[node_id];
+
+    if let Some((word_positions, _)) = word_positions.get(&node_id) {
+        let padding_top = rect.layout.padding.unwrap_or_default().top.map(|p| p.to_pixels()).unwrap_or(0.0);
+        let inner_height = solved_heights.solved_heights[node_id].total() - rect.layout.get_vertical_padding();
+        let (_, vert_alignment) = determine_text_alignment(&rect.style, &rect.layout);
+        let line_height_px = scaled_words.get(&node_id).map(|(scaled_words, _)| {
+            text_layout::get_line_gap(scaled_words, &word_positions.text_layout_options, word_positions.font_size_px)
+        }).unwrap_or(0.0);
+        return Some(padding_top + text_layout::get_first_baseline(word_positions, vert_alignment, inner_height, line_height_px));
+    }
+
+    node_id.children(node_hierarchy)
+        .filter(|child_id| display_rects[*child_id].layout.position.unwrap_or_default() != LayoutPosition::Absolute)
+        .filter_map(|child_id| {
+            let child_baseline = get_first_baseline(child_id, node_hierarchy, display_rects, solved_heights, scaled_words, word_positions, y_positions)?;
+            Some(y_positions[child_id].0 - y_positions[node_id].0 + child_baseline)
+        })
+        .next()
+}
+
+/// Moves the children of `align-items: baseline` rows down, so that the
+/// baselines of their first lines of text are on the same height. Children without
+/// text use their bottom edge as the baseline (like in a browser). The row grows by
+/// the distance the children were moved down, see `grow_to_fit_children`.
+///
+/// Nodes are aligned from the deepest to the most shallow parent, so that the
+/// baseline of a nested row is already final when its parent row is aligned.
+fn align_baselines<'a>(
+    node_hierarchy: &NodeHierarchy,
+    display_rects: &NodeDataContainer<DisplayRectangle<'a>>,
+    solved_widths: &SolvedWidthLayout,
+    solved_heights: &mut SolvedHeightLayout,
+    scaled_words: &BTreeMap<NodeId, (ScaledWords, FontInstanceKey)>,
+    word_positions: &BTreeMap<NodeId, (WordPositions, FontInstanceKey)>,
+    x_positions: &mut NodeDataContainer<HorizontalSolvedPosition>,
+    y_positions: &mut NodeDataContainer<VerticalSolvedPosition>,
+) {
+    use azul_css::{LayoutAlignItems, LayoutAxis};
+
+    for (_node_depth, parent_id) in solved_widths.non_leaf_nodes_sorted_by_depth.iter().rev() {
+
+        let parent_layout = &display_rects[*parent_id].layout;
+
+        if parent_layout.align_items != Some(LayoutAlignItems::Baseline) ||
+           parent_layout.direction.unwrap_or_default().get_axis() != LayoutAxis::Horizontal ||
+           solved_widths.grid_columns.contains_key(parent_id) ||
+           solved_widths.inline_containers.contains(parent_id) ||
+           solved_widths.flex_lines.contains_key(parent_id) {
+            continue;
+        }
+
+        // (child, absolute y position of the baseline of the child)
+        let baselines = parent_id.children(node_hierarchy)
+            .filter(|child_id| display_rects[*child_id].layout.position.unwrap_or_default() != LayoutPosition::Absolute)
+            .map(|child_id| {
+                let baseline = get_first_baseline(child_id, node_hierarchy, display_rects, solved_heights, scaled_words, word_positions, y_positions)
+                    .unwrap_or_else(|| solved_heights.solved_heights[child_id].total());
+                (child_id, y_positions[child_id].0 + baseline)
+            })
+            .collect::<Vec<_>>();
+
+        let max_baseline = baselines.iter().map(|(_, baseline)| *baseline).fold(f32::MIN, f32::max);
+
+        for (child_id, baseline) in baselines {
+            translate_subtree(child_id, node_hierarchy, 0.0, max_baseline - baseline, x_positions, y_positions);
+        }
+
+        grow_to_fit_children(*parent_id, node_hierarchy, solved_widths, solved_heights, x_positions, y_positions);
+    }
+}
+
 /// At this point in time, all font keys, image keys, etc. have
 /// to be already submitted in the RenderApi!
 pub(crate) fn do_the_layout<'a,'b, T>(
@@ -1211,18 +2736,36 @@ pub(crate) fn do_the_layout<'a,'b, T>(
     node_data: &NodeDataContainer<NodeData<T>>,
     display_rects: &NodeDataContainer<DisplayRectangle<'a>>,
     app_resources: &'b AppResources,
+    hidpi_factor: f32,
     rect_size: LayoutSize,
     rect_offset: LayoutPoint,
+    solve_min_content_size: bool,
 ) -> LayoutResult {
 
+    profile_scope!("layout");
+
     // Determine what the width for each div would be if the content size didn't matter
     let widths_content_ignored = solve_flex_layout_width(
         node_hierarchy,
         &display_rects,
         &node_data.transform(|_, _| None),
-        rect_size.width as f32,
+        &BTreeMap::new(),
+        rect_size,
     );
 
+    // The layout without content determines the minimum size of the DOM
+    let min_content_size = if solve_min_content_size {
+        let heights_content_ignored = solve_flex_layout_height(
+            node_hierarchy,
+            &widths_content_ignored,
+            &node_data.transform(|_, _| None),
+            rect_size.height,
+        );
+        Some(LayoutSize::new(widths_content_ignored.min_root_width, heights_content_ignored.min_root_height))
+    } else {
+        None
+    };
+
     // Determine what the "maximum width" for each div is, except for divs where overflow:visible is set
     // I.e. for a div width 800px, with 4 text child nodes, each text node gets a width of 200px
     let max_widths = node_hierarchy
@@ -1251,24 +2794,34 @@ pub(crate) fn do_the_layout<'a,'b, T>(
     // For images that would be the image width / height, for text it would be the text
     // laid out without any width constraints.
     let content_widths = node_data.transform(|node, node_id|
-        get_content_width(&node_id, &node.node_type, app_resources, &word_positions_no_max_width)
+        get_content_width(&node_id, &node.node_type, app_resources, hidpi_factor, &word_positions_no_max_width)
     );
 
+    // Images without a CSS size are as large as the image itself
+    let intrinsic_image_sizes = node_data.linear_iter().filter_map(|node_id| match &node_data[node_id].node_type {
+        NodeType::Image(image_id) => {
+            let (width, height) = app_resources.get_image_size(image_id, hidpi_factor)?;
+            Some((node_id, LayoutSize::new(width, height)))
+        },
+        _ => None,
+    }).collect::<BTreeMap<NodeId, LayoutSize>>();
+
     // Solve the widths again, this time incorporating the maximum widths
     let solved_widths = solve_flex_layout_width(
         node_hierarchy,
         &display_rects,
         &content_widths,
-        rect_size.width as f32,
+        &intrinsic_image_sizes,
+        rect_size,
     );
 
     // Layout all texts again with the resolved width constraints
     let proper_max_widths = solved_widths.solved_widths.linear_iter().map(|node_id| {
-        (node_id, solved_widths.solved_widths[node_id].total() - display_rects[node_id].layout.get_horizontal_padding())
+        (node_id, solved_widths.solved_widths[node_id].total() - solved_widths.layout_only_arena[node_id].get_horizontal_padding())
     }).collect();
 
     // Resolve the word positions relative to each divs upper left corner
-    let word_positions_with_max_width = create_word_positions(
+    let mut word_positions_with_max_width = create_word_positions(
         &word_cache,
         &scaled_words,
         display_rects,
@@ -1284,6 +2837,7 @@ pub(crate) fn do_the_layout<'a,'b, T>(
             &node_id,
             &node.node_type,
             app_resources,
+            hidpi_factor,
             &word_positions_with_max_width,
             div_width
         ).map(|ch| ch.get_content_size())
@@ -1298,29 +2852,98 @@ pub(crate) fn do_the_layout<'a,'b, T>(
         rect_size.height as f32,
     );
 
-    let x_positions = get_x_positions(&solved_widths, node_hierarchy, rect_offset.clone());
-    let y_positions = get_y_positions(&solved_heights, &solved_widths, node_hierarchy, rect_offset);
+    // Break the inline children into lines - this needs the heights of the inline-block children,
+    // so the heights have to be solved again afterwards, with the height of the lines as the content height
+    let inline_flows = layout_inline_flows(
+        node_hierarchy,
+        display_rects,
+        &solved_widths,
+        &solved_heights,
+        &word_cache,
+        &scaled_words,
+        &mut word_positions_with_max_width,
+    );
+
+    let mut content_heights = content_heights;
+    for (container_id, inline_flow) in &inline_flows {
+        content_heights[*container_id] = Some(inline_flow.content_height);
+        for (text_run_id, text_run_rect) in &inline_flow.text_runs {
+            content_heights[*text_run_id] = Some(text_run_rect.size.height);
+        }
+    }
+
+    let mut solved_heights = if inline_flows.is_empty() {
+        solved_heights
+    } else {
+        solve_flex_layout_height(node_hierarchy, &solved_widths, &content_heights, rect_size.height as f32)
+    };
+
+    let mut x_positions = get_x_positions(&solved_widths, node_hierarchy, rect_offset.clone());
+    let mut y_positions = get_y_positions(&solved_heights, &solved_widths, node_hierarchy, rect_offset);
+
+    // Move the inline children to their position in the lines
+    let mut text_run_sizes = BTreeMap::new();
+
+    for (container_id, inline_flow) in &inline_flows {
+
+        let container_padding = solved_widths.layout_only_arena[*container_id].padding.unwrap_or_default();
+        let content_x = x_positions[*container_id].0 + container_padding.left.map(|p| p.to_pixels()).unwrap_or(0.0);
+        let content_y = y_positions[*container_id].0 + container_padding.top.map(|p| p.to_pixels()).unwrap_or(0.0);
+
+        for (child_id, child_rect) in inline_flow.boxes.iter().chain(inline_flow.text_runs.iter()) {
+            let dx = content_x + child_rect.origin.x - x_positions[*child_id].0;
+            let dy = content_y + child_rect.origin.y - y_positions[*child_id].0;
+            translate_subtree(*child_id, node_hierarchy, dx, dy, &mut x_positions, &mut y_positions);
+        }
+
+        for (text_run_id, text_run_rect) in &inline_flow.text_runs {
+            text_run_sizes.insert(*text_run_id, text_run_rect.size);
+        }
+    }
+
+    align_baselines(
+        node_hierarchy,
+        display_rects,
+        &solved_widths,
+        &mut solved_heights,
+        &scaled_words,
+        &word_positions_with_max_width,
+        &mut x_positions,
+        &mut y_positions,
+    );
 
     let layouted_rects = node_data.transform(|_node, node_id| {
         PositionedRectangle {
             bounds: LayoutRect::new(
                 LayoutPoint::new(x_positions[node_id].0, y_positions[node_id].0),
-                LayoutSize::new(
+                text_run_sizes.get(&node_id).cloned().unwrap_or_else(|| LayoutSize::new(
                     solved_widths.solved_widths[node_id].total(),
                     solved_heights.solved_heights[node_id].total(),
-                )
+                ))
             ),
             content_width: Some(proper_max_widths[&node_id]),
             content_height: content_heights[node_id],
+            padding: solved_widths.layout_only_arena[node_id].padding.unwrap_or_default(),
         }
     });
 
+    let traces = node_data.transform(|_node, node_id| LayoutTrace {
+        content_width: content_widths[node_id],
+        content_height: content_heights[node_id],
+        min_width: solved_widths.solved_widths[node_id].min_width,
+        min_height: solved_heights.solved_heights[node_id].min_height,
+        width_added: solved_widths.solved_widths[node_id].space_added,
+        height_added: solved_heights.solved_heights[node_id].space_added,
+    });
+
     LayoutResult {
         rects: layouted_rects,
         word_cache,
         scaled_words,
         positioned_word_cache: word_positions_with_max_width,
         node_depths: solved_widths.non_leaf_nodes_sorted_by_depth,
+        min_content_size,
+        traces,
     }
 }
 
@@ -1349,35 +2972,48 @@ fn create_scaled_words<'a>(
     display_rects: &NodeDataContainer<DisplayRectangle<'a>>,
 ) -> BTreeMap<NodeId, (ScaledWords, FontInstanceKey)> {
 
-    use text_layout::words_to_scaled_words;
-    use app_resources::ImmediateFontId;
+    use text_layout::{words_to_scaled_words, get_tab_leaders};
+
+    profile_scope!("text_shaping");
 
     words.iter().filter_map(|(node_id, words)| {
         let style = &display_rects[*node_id].style;
         let font_size = get_font_size(&style);
-        let font_size_au = font_size_to_au(font_size);
-        let css_font_id = get_font_id(&style);
-        let font_id = match app_resources.get_css_font_id(css_font_id) {
-            Some(s) => ImmediateFontId::Resolved(*s),
-            None => ImmediateFontId::Unresolved(css_font_id.to_string()),
-        };
-
-        let loaded_font = app_resources.get_loaded_font(&font_id)?;
-        let font_instance_key = loaded_font.font_instances.get(&font_size_au)?;
+        let (loaded_font, font_instance_key) = get_font_instance(app_resources, style)?;
 
         let font_bytes = &loaded_font.font_bytes;
         let font_index = loaded_font.font_index as u32;
+        let tab_leaders = get_tab_leaders(&get_tab_stops(style));
 
         let scaled_words = words_to_scaled_words(
             words,
             font_bytes,
             font_index,
             font_size.0.to_pixels(),
+            &tab_leaders,
         );
-        Some((*node_id, (scaled_words, *font_instance_key)))
+        Some((*node_id, (scaled_words, font_instance_key)))
     }).collect()
 }
 
+/// Returns the font that the text of a node with the given style is shaped with,
+/// along with the instance of that font at the font size of the node
+fn get_font_instance<'r>(app_resources: &'r AppResources, style: &RectStyle) -> Option<(&'r LoadedFont, FontInstanceKey)> {
+
+    use app_resources::ImmediateFontId;
+
+    let font_size_au = font_size_to_au(get_font_size(style));
+    let css_font_id = get_font_id(style);
+    let font_id = match app_resources.get_css_font_id(css_font_id) {
+        Some(s) => ImmediateFontId::Resolved(*s),
+        None => ImmediateFontId::Unresolved(css_font_id.to_string()),
+    };
+
+    let loaded_font = app_resources.get_loaded_font(&font_id)?;
+    let font_instance_key = loaded_font.font_instances.get(&font_size_au)?;
+    Some((loaded_font, *font_instance_key))
+}
+
 fn create_word_positions<'a>(
     words: &BTreeMap<NodeId, Words>,
     scaled_words: &BTreeMap<NodeId, (ScaledWords, FontInstanceKey)>,
@@ -1423,16 +3059,33 @@ fn get_text_layout_options(
         letter_spacing: rect.style.letter_spacing.map(|ls| ls.0.to_pixels()),
         word_spacing: rect.style.word_spacing.map(|ws| ws.0.to_pixels()),
         tab_width: rect.style.tab_width.map(|tw| tw.0.get()),
+        tab_stops: get_tab_stops(&rect.style),
+        direction: rect.style.text_direction,
         max_horizontal_width,
+        white_space: rect.style.white_space,
+        text_overflow: rect.style.text_overflow,
         leading,
         holes,
     }
 }
 
+/// Converts the `tab-stops` of a node to the tab stops of the `TextLayoutOptions`
+fn get_tab_stops(style: &RectStyle) -> Vec<TabStop> {
+    style.tab_stops.as_ref().map(|tab_stops| tab_stops.0.iter().map(|tab_stop| TabStop {
+        position: tab_stop.position.to_pixels(),
+        leader: tab_stop.leader,
+    }).collect()).unwrap_or_default()
+}
+
 /// For a given rectangle, determines what text alignment should be used
 pub(crate) fn determine_text_alignment(rect_style: &RectStyle, rect_layout: &RectLayout)
     -> (StyleTextAlignmentHorz, StyleTextAlignmentVert)
 {
+    // Inline text is already positioned in the lines of its parent
+    if rect_layout.display == Some(LayoutDisplay::Inline) && rect_layout.is_inline_level() {
+        return (StyleTextAlignmentHorz::Left, StyleTextAlignmentVert::Top);
+    }
+
     let mut horz_alignment = StyleTextAlignmentHorz::default();
     let mut vert_alignment = StyleTextAlignmentVert::default();
 
@@ -1459,7 +3112,7 @@ pub(crate) fn determine_text_alignment(rect_style: &RectStyle, rect_layout: &Rec
 
     if let Some(text_align) = rect_style.text_align {
         // Horizontal text alignment with higher priority
-        horz_alignment = text_align;
+        horz_alignment = text_align.to_physical(rect_style.text_direction.unwrap_or_default());
     }
 
     (horz_alignment, vert_alignment)
@@ -1468,7 +3121,7 @@ pub(crate) fn determine_text_alignment(rect_style: &RectStyle, rect_layout: &Rec
 #[cfg(test)]
 mod layout_tests {
 
-    use azul_css::RectLayout;
+    use azul_css::{RectLayout, FloatValue};
     use id_tree::{Node, NodeId};
     use super::*;
 
@@ -1690,7 +3343,7 @@ mod layout_tests {
             (2, NodeId::new(2)),
         ]);
 
-        width_filled_out_data.bubble_preferred_widths_to_parents(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth);
+        width_filled_out_data.bubble_preferred_widths_to_parents(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, &BTreeMap::new(), &BTreeSet::new());
 
         // This step shouldn't have touched the flex_grow_px
         for node in &width_filled_out_data.internal {
@@ -1718,6 +3371,9 @@ mod layout_tests {
         assert_eq!(width_filled_out_data[NodeId::new(5)].get_flex_basis_horizontal(), 0.0);
         assert_eq!(width_filled_out_data[NodeId::new(5)].min_inner_size_px, 0.0);
 
+        // The whole DOM needs at least the 40px of padding
+        assert_eq!(width_filled_out_data.get_min_root_size(), 40.0);
+
         // -- Section 3: Test if growing the sizes works
 
         let window_width = 754.0; // pixel
@@ -1730,7 +3386,7 @@ mod layout_tests {
         //    '   '-- 4     -- [] - expecting width to stretch to 80px (half of 160)
         //    '-- 5         -- [] - expecting width to stretch to 554px (754 - 200px max-width of earlier sibling)
 
-        width_filled_out_data.apply_flex_grow(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, window_width);
+        width_filled_out_data.apply_flex_grow(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, window_width, &mut BTreeMap::new(), &BTreeSet::new());
 
         assert_eq!(width_filled_out_data[NodeId::new(0)].solved_result(), WidthSolvedResult {
             min_width: 40.0,
@@ -1757,4 +3413,803 @@ mod layout_tests {
             space_added: window_width - 200.0,
         });
     }
+
+    #[test]
+    fn test_grid_layout() {
+
+        use azul_css::{
+            LayoutWidth, LayoutGridGap, LayoutGridColumn, LayoutGridRow,
+            GridLine, FloatValue,
+        };
+
+        // 2: display: grid; width: 300px; grid-gap: 10px; grid-template-columns: 100px 1fr;
+        let (node_hierarchy, node_data) = get_display_rectangle_arena(&[
+            (2, RectLayout {
+                display: Some(LayoutDisplay::Grid),
+                width: Some(LayoutWidth::px(300.0)),
+                grid_gap: Some(LayoutGridGap { row: PixelValue::px(10.0), column: PixelValue::px(10.0) }),
+                .. Default::default()
+            }),
+            // 3: grid-row: 2;
+            (3, RectLayout {
+                grid_row: Some(LayoutGridRow(GridPlacement { start: GridLine::Line(2), end: GridLine::Auto })),
+                .. Default::default()
+            }),
+            // 4: grid-column: 2;
+            (4, RectLayout {
+                grid_column: Some(LayoutGridColumn(GridPlacement { start: GridLine::Line(2), end: GridLine::Auto })),
+                .. Default::default()
+            }),
+        ]);
+
+        // 1 / -1 spans the whole explicit grid
+        let whole_row = GridPlacement { start: GridLine::Line(1), end: GridLine::Line(-1) };
+        assert_eq!(resolve_grid_placement(whole_row, 2), (Some(0), 2));
+        assert_eq!(resolve_grid_placement(GridPlacement { start: GridLine::Span(2), end: GridLine::Auto }, 2), (None, 2));
+
+        let template_columns = [GridTrackSize::Fixed(PixelValue::px(100.0)), GridTrackSize::Fraction(FloatValue::new(1.0))];
+        let (columns, rows) = place_grid_items(NodeId::new(2), &node_hierarchy, &node_data, &template_columns, &[]);
+
+        // 3 is placed into the second row, 4 into the first free cell of the second column
+        assert_eq!(columns.items, vec![(NodeId::new(3), 0, 1), (NodeId::new(4), 1, 1)]);
+        assert_eq!(rows.items, vec![(NodeId::new(3), 1, 1), (NodeId::new(4), 0, 1)]);
+        assert_eq!(rows.tracks, vec![GridTrackSize::Auto, GridTrackSize::Auto]);
+
+        let mut grid_columns = BTreeMap::new();
+        grid_columns.insert(NodeId::new(2), columns);
+
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+        let preferred_widths = node_data.transform(|_, _| None);
+        let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&node_data, &preferred_widths);
+        width_calculated_arena.bubble_preferred_widths_to_parents(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, &grid_columns, &BTreeSet::new());
+        width_calculated_arena.apply_flex_grow(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, 800.0, &mut grid_columns, &BTreeSet::new());
+
+        // 300px - 100px - 10px gap = 190px for the 1fr column
+        assert_eq!(grid_columns[&NodeId::new(2)].track_sizes, vec![100.0, 190.0]);
+        assert_eq!(width_calculated_arena[NodeId::new(3)].solved_result().total(), 100.0);
+        assert_eq!(width_calculated_arena[NodeId::new(4)].solved_result().total(), 190.0);
+
+        let solved_widths = SolvedWidthLayout {
+            solved_widths: width_calculated_arena.transform(|node, _| node.solved_result()),
+            layout_only_arena: node_data,
+            non_leaf_nodes_sorted_by_depth,
+            min_root_width: 0.0,
+            grid_columns,
+            grid_rows: BTreeMap::new(),
+            inline_containers: BTreeSet::new(),
+            flex_lines: BTreeMap::new(),
+        };
+
+        let x_positions = get_x_positions(&solved_widths, &node_hierarchy, LayoutPoint::zero());
+        assert_eq!(x_positions[NodeId::new(3)].0, 0.0);
+        assert_eq!(x_positions[NodeId::new(4)].0, 110.0);
+    }
+
+    #[test]
+    fn test_inline_flow() {
+
+        use azul_css::{LayoutWidth, LayoutMinWidth};
+
+        // 2: width: 150px;
+        let (node_hierarchy, node_data) = get_display_rectangle_arena(&[
+            (2, RectLayout {
+                width: Some(LayoutWidth::px(150.0)),
+                .. Default::default()
+            }),
+            // 3: display: inline-block; width: 100px;
+            (3, RectLayout {
+                display: Some(LayoutDisplay::InlineBlock),
+                width: Some(LayoutWidth::px(100.0)),
+                .. Default::default()
+            }),
+            // 4: display: inline-block; min-width: 80px;
+            (4, RectLayout {
+                display: Some(LayoutDisplay::InlineBlock),
+                min_width: Some(LayoutMinWidth::px(80.0)),
+                .. Default::default()
+            }),
+        ]);
+
+        let inline_containers = get_inline_containers(&node_hierarchy, &node_data, &BTreeMap::new());
+        assert_eq!(inline_containers.iter().cloned().collect::<Vec<NodeId>>(), vec![NodeId::new(2)]);
+
+        // Inline children shrink to their content instead of being stretched
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+        let preferred_widths = node_data.transform(|_, _| None);
+        let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&node_data, &preferred_widths);
+        width_calculated_arena.bubble_preferred_widths_to_parents(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, &BTreeMap::new(), &inline_containers);
+        width_calculated_arena.apply_flex_grow(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, 800.0, &mut BTreeMap::new(), &inline_containers);
+        assert_eq!(width_calculated_arena[NodeId::new(3)].solved_result().total(), 100.0);
+        assert_eq!(width_calculated_arena[NodeId::new(4)].solved_result().total(), 80.0);
+
+        // The second box doesn't fit into the first line anymore
+        let mut cursor = InlineFlowCursor::new(150.0);
+        assert_eq!(cursor.place_box(LayoutSize::new(100.0, 20.0)), LayoutPoint::new(0.0, 0.0));
+        assert_eq!(cursor.place_box(LayoutSize::new(80.0, 30.0)), LayoutPoint::new(0.0, 20.0));
+
+        // Text after the 30px box sits at the bottom of the line, the
+        // second line of the text starts one line stride below
+        assert_eq!(cursor.get_text_run_top(12.0), 38.0);
+        cursor.advance_text_run(2, 12.0, 40.0);
+        assert_eq!(cursor.place_box(LayoutSize::new(10.0, 10.0)), LayoutPoint::new(40.0, 50.0));
+
+        // Block children are placed on a line of their own
+        assert_eq!(cursor.place_block(LayoutSize::new(150.0, 5.0)), LayoutPoint::new(0.0, 62.0));
+        assert_eq!(cursor.get_content_height(), 67.0);
+    }
+
+    #[test]
+    fn test_inline_flow_floats() {
+
+        let mut cursor = InlineFlowCursor::new(400.0);
+        assert_eq!(cursor.place_float(LayoutSize::new(100.0, 100.0), LayoutFloat::Left), LayoutPoint::new(0.0, 0.0));
+        assert_eq!(cursor.place_float(LayoutSize::new(50.0, 150.0), LayoutFloat::Right), LayoutPoint::new(350.0, 0.0));
+
+        // Inline boxes are placed between the floats
+        assert_eq!(cursor.place_box(LayoutSize::new(200.0, 20.0)), LayoutPoint::new(100.0, 0.0));
+        assert_eq!(cursor.place_box(LayoutSize::new(100.0, 20.0)), LayoutPoint::new(100.0, 20.0));
+
+        // Too wide for the space between the floats, so the box is moved below the left float
+        assert_eq!(cursor.place_box(LayoutSize::new(300.0, 20.0)), LayoutPoint::new(0.0, 100.0));
+
+        // Text below the left float only has to wrap around the right float
+        let holes = cursor.get_text_holes(100.0, 20.0);
+        assert_eq!(holes, vec![LayoutRect::new(LayoutPoint::new(350.0, -100.0), LayoutSize::new(50.0, 170.0))]);
+
+        // A float can't be placed next to content that is already in the line
+        assert_eq!(cursor.place_float(LayoutSize::new(50.0, 50.0), LayoutFloat::Left), LayoutPoint::new(0.0, 120.0));
+        assert_eq!(cursor.get_content_height(), 170.0);
+    }
+
+    #[test]
+    fn test_resolve_calc_expressions() {
+        use azul_css::{
+            CalcExpression::*, CssPropertyType, PercentageValue, FloatValue,
+            LayoutWidth, LayoutHeight, LayoutMaxWidth, LayoutPadding,
+        };
+
+        let calc = |property_type, expression| vec![LayoutCalc { property_type, expression }];
+        let percent = |v| Box::new(Percentage(PercentageValue::new(v)));
+
+        // 1: width: calc(100% - 20px); padding: 0 10px;
+        let node_1 = calc(CssPropertyType::Width, Sub(percent(100.0), Box::new(Length(PixelValue::px(20.0)))));
+        // 2: width: calc(50%);
+        let node_2 = calc(CssPropertyType::Width, Percentage(PercentageValue::new(50.0)));
+        // 3: height: calc(100% / 4); - no parent has a height, so this resolves against the window height
+        let node_3 = calc(CssPropertyType::Height, Div(percent(100.0), Box::new(Number(FloatValue::new(4.0)))));
+        // 5: max-width: calc(50% + 10px);
+        let node_5 = calc(CssPropertyType::MaxWidth, Add(percent(50.0), Box::new(Length(PixelValue::px(10.0)))));
+
+        let (mut node_hierarchy, mut layout_only_arena) = get_display_rectangle_arena(&[
+            (1, RectLayout {
+                padding: Some(LayoutPadding {
+                    left: Some(PixelValue::px(10.0)),
+                    right: Some(PixelValue::px(10.0)),
+                    .. Default::default()
+                }),
+                .. Default::default()
+            }),
+        ]);
+
+        // The testing hierarchy links 5 as the next sibling of 1, even though its parent is 1:
+        // link it as the last child of 1 (after 2), so that it resolves against the width of 1
+        node_hierarchy.internal[1].next_sibling = None;
+        node_hierarchy.internal[1].last_child = Some(NodeId::new(5));
+        node_hierarchy.internal[2].next_sibling = Some(NodeId::new(5));
+
+        let mut calc_expressions = BTreeMap::new();
+        calc_expressions.insert(NodeId::new(1), &node_1[..]);
+        calc_expressions.insert(NodeId::new(2), &node_2[..]);
+        calc_expressions.insert(NodeId::new(3), &node_3[..]);
+        calc_expressions.insert(NodeId::new(5), &node_5[..]);
+
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+        resolve_calc_expressions(
+            &node_hierarchy,
+            &non_leaf_nodes_sorted_by_depth,
+            &calc_expressions,
+            &mut layout_only_arena,
+            LayoutSize::new(800.0, 600.0),
+        );
+
+        assert_eq!(layout_only_arena[NodeId::new(1)].width, Some(LayoutWidth::px(780.0)));
+        assert_eq!(layout_only_arena[NodeId::new(2)].width, Some(LayoutWidth::px(380.0)));
+        assert_eq!(layout_only_arena[NodeId::new(3)].height, Some(LayoutHeight::px(150.0)));
+        assert_eq!(layout_only_arena[NodeId::new(5)].max_width, Some(LayoutMaxWidth::px(390.0)));
+        assert_eq!(layout_only_arena[NodeId::new(4)], RectLayout::default());
+    }
+
+    #[test]
+    fn test_resolve_percentage_paddings_and_margins() {
+        use azul_css::{LayoutWidth, LayoutPadding, LayoutMargin};
+
+        // 1: width: 400px;
+        // 2: padding: 10%; - resolved against the width of 1, even at the top and bottom
+        // 5: margin: 5% auto; - sibling of 1, so it resolves against the window width
+        let (node_hierarchy, mut layout_only_arena) = get_display_rectangle_arena(&[
+            (1, RectLayout {
+                width: Some(LayoutWidth::px(400.0)),
+                .. Default::default()
+            }),
+            (2, RectLayout {
+                padding: Some(LayoutPadding::all(PixelValue::percent(10.0))),
+                .. Default::default()
+            }),
+            (5, RectLayout {
+                margin: Some(LayoutMargin {
+                    top: Some(PixelValue::percent(5.0)),
+                    bottom: Some(PixelValue::percent(5.0)),
+                    left: Some(PixelValue::auto()),
+                    right: Some(PixelValue::auto()),
+                }),
+                .. Default::default()
+            }),
+        ]);
+
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+        resolve_calc_expressions(
+            &node_hierarchy,
+            &non_leaf_nodes_sorted_by_depth,
+            &BTreeMap::new(),
+            &mut layout_only_arena,
+            LayoutSize::new(800.0, 600.0),
+        );
+
+        assert_eq!(layout_only_arena[NodeId::new(2)].padding, Some(LayoutPadding::all(PixelValue::px(40.0))));
+        assert_eq!(layout_only_arena[NodeId::new(5)].margin, Some(LayoutMargin {
+            top: Some(PixelValue::px(40.0)),
+            bottom: Some(PixelValue::px(40.0)),
+            left: Some(PixelValue::auto()),
+            right: Some(PixelValue::auto()),
+        }));
+    }
+
+    #[test]
+    fn test_apply_intrinsic_image_sizes() {
+        use azul_css::{LayoutWidth, LayoutHeight, LayoutMaxWidth, LayoutPadding};
+
+        // 1: no size - 3: width: 100px; - 4: height: 50px; padding: 5px; - 5: max-width: 120px;
+        let (_, mut layout_only_arena) = get_display_rectangle_arena(&[
+            (3, RectLayout { width: Some(LayoutWidth::px(100.0)), .. Default::default() }),
+            (4, RectLayout {
+                height: Some(LayoutHeight::px(50.0)),
+                padding: Some(LayoutPadding::all(PixelValue::px(5.0))),
+                .. Default::default()
+            }),
+            (5, RectLayout { max_width: Some(LayoutMaxWidth::px(120.0)), .. Default::default() }),
+        ]);
+
+        // All images are 200x100 pixels
+        let intrinsic_image_sizes = [1, 3, 4, 5].iter()
+            .map(|id| (NodeId::new(*id), LayoutSize::new(200.0, 100.0)))
+            .collect();
+        apply_intrinsic_image_sizes(&mut layout_only_arena, &intrinsic_image_sizes);
+
+        let size = |id| (layout_only_arena[NodeId::new(id)].width, layout_only_arena[NodeId::new(id)].height);
+        assert_eq!(size(1), (Some(LayoutWidth::px(200.0)), Some(LayoutHeight::px(100.0))));
+        assert_eq!(size(3), (Some(LayoutWidth::px(100.0)), Some(LayoutHeight::px(50.0))));
+        assert_eq!(size(4), (Some(LayoutWidth::px(90.0)), Some(LayoutHeight::px(50.0))));
+        assert_eq!(size(5), (Some(LayoutWidth::px(120.0)), Some(LayoutHeight::px(60.0))));
+        assert_eq!(size(2), (None, None));
+    }
+
+    #[test]
+    fn test_resolve_flexible_lengths() {
+
+        // (flex base size, min size, max size, flex-grow, flex-shrink)
+        let resolve = |items: &[(f32, f32, f32, f32, f32)], available_space: f32| -> Vec<f32> {
+            let mut flex_items = items.iter().enumerate().map(|(index, (flex_base_size, min_size, max_size, flex_grow, flex_shrink))| FlexItem {
+                node_id: NodeId::new(index),
+                flex_base_size: *flex_base_size,
+                min_size: *min_size,
+                max_size: *max_size,
+                flex_grow: *flex_grow,
+                flex_shrink: *flex_shrink,
+                target_size: 0.0,
+                frozen: false,
+            }).collect::<Vec<_>>();
+            resolve_flexible_lengths(&mut flex_items, available_space);
+            flex_items.iter().map(|item| item.target_size).collect()
+        };
+
+        // flex-grow: 1 and flex-grow: 2
+        assert_eq!(resolve(&[(0.0, 0.0, f32::MAX, 1.0, 1.0), (0.0, 0.0, f32::MAX, 2.0, 1.0)], 300.0), vec![100.0, 200.0]);
+        // max-width: 50px on the first item, the rest goes to the second item
+        assert_eq!(resolve(&[(0.0, 0.0, 50.0, 1.0, 1.0), (0.0, 0.0, f32::MAX, 1.0, 1.0)], 300.0), vec![50.0, 250.0]);
+        // The first item can't shrink below its content (min size), so it grows less than the second item
+        assert_eq!(resolve(&[(0.0, 200.0, f32::MAX, 1.0, 1.0), (0.0, 0.0, f32::MAX, 1.0, 1.0)], 300.0), vec![200.0, 100.0]);
+        // Shrinking is scaled by the flex base size: 60px overflow, shrunk 2:1
+        assert_eq!(resolve(&[(200.0, 0.0, f32::MAX, 1.0, 1.0), (100.0, 0.0, f32::MAX, 1.0, 1.0)], 240.0), vec![160.0, 80.0]);
+        // The first item would shrink to 160px, but has a min-width of 180px
+        assert_eq!(resolve(&[(200.0, 180.0, f32::MAX, 1.0, 1.0), (100.0, 0.0, f32::MAX, 1.0, 1.0)], 240.0), vec![180.0, 60.0]);
+        // flex-shrink: 0
+        assert_eq!(resolve(&[(200.0, 0.0, f32::MAX, 1.0, 0.0), (100.0, 0.0, f32::MAX, 1.0, 1.0)], 240.0), vec![200.0, 40.0]);
+        // Items that can't shrink below their content overflow the parent
+        assert_eq!(resolve(&[(200.0, 200.0, f32::MAX, 1.0, 1.0), (100.0, 100.0, f32::MAX, 1.0, 1.0)], 240.0), vec![200.0, 100.0]);
+    }
+
+    /// Returns a DOM for testing flex rows:
+    ///
+    /// ```no_run
+    /// 0
+    /// '- 1
+    /// '  '-- 2
+    /// '  '-- 3
+    /// '- 4
+    /// ```
+    fn get_flex_testing_hierarchy() -> NodeHierarchy {
+        let node = |parent: Option<usize>, previous_sibling: Option<usize>, next_sibling: Option<usize>, children: Option<(usize, usize)>| Node {
+            parent: parent.map(NodeId::new),
+            previous_sibling: previous_sibling.map(NodeId::new),
+            next_sibling: next_sibling.map(NodeId::new),
+            first_child: children.map(|(first, _)| NodeId::new(first)),
+            last_child: children.map(|(_, last)| NodeId::new(last)),
+        };
+        NodeHierarchy {
+            internal: vec![
+                node(None, None, None, Some((1, 4))),
+                node(Some(0), None, Some(4), Some((2, 3))),
+                node(Some(1), None, Some(3), None),
+                node(Some(1), Some(2), None, None),
+                node(Some(0), Some(1), None, None),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_nested_flex_shrink() {
+
+        use azul_css::{LayoutWidth, LayoutMinWidth, LayoutFlexGrow, LayoutDirection, FloatValue};
+
+        let node_hierarchy = get_flex_testing_hierarchy();
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+
+        let solve_widths = |node_data: &NodeDataContainer<RectLayout>, window_width: f32| -> Vec<f32> {
+            let preferred_widths = node_data.transform(|_, _| None);
+            let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(node_data, &preferred_widths);
+            width_calculated_arena.bubble_preferred_widths_to_parents(&node_hierarchy, node_data, &non_leaf_nodes_sorted_by_depth, &BTreeMap::new(), &BTreeSet::new());
+            width_calculated_arena.apply_flex_grow(&node_hierarchy, node_data, &non_leaf_nodes_sorted_by_depth, window_width, &mut BTreeMap::new(), &BTreeSet::new());
+            width_calculated_arena.internal.iter().map(|node| node.solved_result().total()).collect()
+        };
+
+        let row = RectLayout { direction: Some(LayoutDirection::Row), .. Default::default() };
+        let fixed = RectLayout { width: Some(LayoutWidth::px(100.0)), .. Default::default() };
+
+        // 0: flex-direction: row;
+        // 1: flex-direction: row; (contains 2 and 3, which are 100px wide each)
+        let mut node_data = NodeDataContainer { internal: vec![row, row, fixed, fixed, RectLayout::default()] };
+
+        // The content of 1 bubbles up to the root, the root needs at least 200px
+        let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&node_data, &node_data.transform(|_, _| None));
+        width_calculated_arena.bubble_preferred_widths_to_parents(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, &BTreeMap::new(), &BTreeSet::new());
+        assert_eq!(width_calculated_arena.get_min_root_size(), 200.0);
+
+        // 1 doesn't shrink below its content, so 4 only gets the remaining 100px
+        assert_eq!(solve_widths(&node_data, 300.0), vec![300.0, 200.0, 100.0, 100.0, 100.0]);
+
+        // Not enough space: 1 keeps its content size, 4 shrinks to zero
+        assert_eq!(solve_widths(&node_data, 150.0), vec![150.0, 200.0, 100.0, 100.0, 0.0]);
+
+        // min-width: 0 allows 1 to shrink below its content
+        node_data[NodeId::new(1)].min_width = Some(LayoutMinWidth::px(0.0));
+        assert_eq!(solve_widths(&node_data, 300.0)[1], 150.0);
+        node_data[NodeId::new(1)].min_width = None;
+
+        // 4: flex-basis: 50px; flex-grow: 0;
+        node_data[NodeId::new(4)].flex_basis = Some(LayoutFlexBasis::Exact(PixelValue::px(50.0)));
+        node_data[NodeId::new(4)].flex_grow = Some(LayoutFlexGrow(FloatValue::new(0.0)));
+        assert_eq!(solve_widths(&node_data, 300.0)[1..], [250.0, 100.0, 100.0, 50.0]);
+        node_data[NodeId::new(4)].flex_basis = None;
+        node_data[NodeId::new(4)].flex_grow = None;
+
+        // 1: flex-basis: content; - 1 starts at 200px, the free space is distributed equally
+        node_data[NodeId::new(1)].flex_basis = Some(LayoutFlexBasis::Content);
+        assert_eq!(solve_widths(&node_data, 300.0)[1..], [250.0, 100.0, 100.0, 50.0]);
+    }
+
+    #[test]
+    fn test_text_min_content_size() {
+
+        use azul_css::{LayoutMinWidth, LayoutDirection};
+
+        let node_hierarchy = get_flex_testing_hierarchy();
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+
+        // 2 is a text node with a 120px wide label
+        let mut preferred_widths = NodeDataContainer { internal: vec![None; 5] };
+        preferred_widths[NodeId::new(2)] = Some(120.0);
+
+        let solve_widths = |node_data: &NodeDataContainer<RectLayout>| -> Vec<f32> {
+            let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(node_data, &preferred_widths);
+            width_calculated_arena.bubble_preferred_widths_to_parents(&node_hierarchy, node_data, &non_leaf_nodes_sorted_by_depth, &BTreeMap::new(), &BTreeSet::new());
+            width_calculated_arena.apply_flex_grow(&node_hierarchy, node_data, &non_leaf_nodes_sorted_by_depth, 100.0, &mut BTreeMap::new(), &BTreeSet::new());
+            width_calculated_arena.internal.iter().map(|node| node.solved_result().total()).collect()
+        };
+
+        // 0: flex-direction: row;
+        // 1: flex-direction: row; (the "button", contains the label 2)
+        let row = RectLayout { direction: Some(LayoutDirection::Row), .. Default::default() };
+        let mut node_data = NodeDataContainer { internal: vec![row, row, RectLayout::default(), RectLayout::default(), RectLayout::default()] };
+
+        // The button doesn't shrink below its label, 4 gets no space
+        assert_eq!(solve_widths(&node_data), vec![100.0, 120.0, 120.0, 0.0, 0.0]);
+
+        // A smaller min-width on the label doesn't make its text smaller
+        node_data[NodeId::new(2)].min_width = Some(LayoutMinWidth::px(20.0));
+        assert_eq!(solve_widths(&node_data)[1], 120.0);
+        node_data[NodeId::new(2)].min_width = None;
+
+        // min-width: 0 on the button opts out: it shares the space with 4, the label overflows it
+        node_data[NodeId::new(1)].min_width = Some(LayoutMinWidth::px(0.0));
+        assert_eq!(solve_widths(&node_data), vec![100.0, 50.0, 120.0, 0.0, 50.0]);
+    }
+
+    #[test]
+    fn test_flex_order() {
+
+        use azul_css::{LayoutWidth, LayoutOrder, LayoutDirection};
+
+        // 2: flex-direction: row;
+        // 3: width: 100px; order: 1;
+        // 4: width: 100px;
+        let (node_hierarchy, node_data) = get_display_rectangle_arena(&[
+            (2, RectLayout { direction: Some(LayoutDirection::Row), .. Default::default() }),
+            (3, RectLayout { width: Some(LayoutWidth::px(100.0)), order: Some(LayoutOrder(1)), .. Default::default() }),
+            (4, RectLayout { width: Some(LayoutWidth::px(100.0)), .. Default::default() }),
+        ]);
+
+        assert_eq!(get_children_in_flex_order(NodeId::new(2), &node_hierarchy, &node_data), vec![NodeId::new(4), NodeId::new(3)]);
+
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+        let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&node_data, &node_data.transform(|_, _| None));
+        width_calculated_arena.bubble_preferred_widths_to_parents(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, &BTreeMap::new(), &BTreeSet::new());
+        width_calculated_arena.apply_flex_grow(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, 800.0, &mut BTreeMap::new(), &BTreeSet::new());
+
+        let solved_widths = SolvedWidthLayout {
+            solved_widths: width_calculated_arena.transform(|node, _| node.solved_result()),
+            layout_only_arena: node_data,
+            non_leaf_nodes_sorted_by_depth,
+            min_root_width: 0.0,
+            grid_columns: BTreeMap::new(),
+            grid_rows: BTreeMap::new(),
+            inline_containers: BTreeSet::new(),
+            flex_lines: BTreeMap::new(),
+        };
+
+        // 3 comes after 4, even though it comes first in the DOM
+        let x_positions = get_x_positions(&solved_widths, &node_hierarchy, LayoutPoint::zero());
+        assert_eq!(x_positions[NodeId::new(4)].0, 0.0);
+        assert_eq!(x_positions[NodeId::new(3)].0, 100.0);
+    }
+
+    #[test]
+    fn test_flex_gap() {
+
+        use azul_css::{LayoutWidth, LayoutDirection, LayoutGridGap, LayoutColumnGap};
+
+        // 2: flex-direction: row; width: 300px; column-gap: 20px;
+        // 3: -
+        // 4: -
+        let (node_hierarchy, mut node_data) = get_display_rectangle_arena(&[
+            (2, RectLayout {
+                direction: Some(LayoutDirection::Row),
+                width: Some(LayoutWidth::px(300.0)),
+                column_gap: Some(LayoutColumnGap::px(20.0)),
+                .. Default::default()
+            }),
+        ]);
+
+        assert_eq!(get_total_main_axis_gap(NodeId::new(2), &node_hierarchy, &node_data), 20.0);
+
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+        let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&node_data, &node_data.transform(|_, _| None));
+        width_calculated_arena.bubble_preferred_widths_to_parents(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, &BTreeMap::new(), &BTreeSet::new());
+        width_calculated_arena.apply_flex_grow(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, 800.0, &mut BTreeMap::new(), &BTreeSet::new());
+
+        // The gap is taken away from the space that is distributed to the children
+        assert_eq!(width_calculated_arena[NodeId::new(3)].solved_result().total(), 140.0);
+        assert_eq!(width_calculated_arena[NodeId::new(4)].solved_result().total(), 140.0);
+
+        let solved_widths = SolvedWidthLayout {
+            solved_widths: width_calculated_arena.transform(|node, _| node.solved_result()),
+            layout_only_arena: node_data.clone(),
+            non_leaf_nodes_sorted_by_depth,
+            min_root_width: 0.0,
+            grid_columns: BTreeMap::new(),
+            grid_rows: BTreeMap::new(),
+            inline_containers: BTreeSet::new(),
+            flex_lines: BTreeMap::new(),
+        };
+
+        let x_positions = get_x_positions(&solved_widths, &node_hierarchy, LayoutPoint::zero());
+        assert_eq!(x_positions[NodeId::new(3)].0, 0.0);
+        assert_eq!(x_positions[NodeId::new(4)].0, 160.0);
+
+        // `column-gap` takes precedence over `grid-gap`, the row gap isn't used for rows
+        node_data[NodeId::new(2)].grid_gap = Some(LayoutGridGap { row: PixelValue::px(5.0), column: PixelValue::px(50.0) });
+        assert_eq!(node_data[NodeId::new(2)].get_main_axis_gap(), 20.0);
+        node_data[NodeId::new(2)].column_gap = None;
+        assert_eq!(node_data[NodeId::new(2)].get_main_axis_gap(), 50.0);
+    }
+
+    /// Returns a DOM with a flex container (1) that contains `items` leaf nodes:
+    ///
+    /// ```no_run
+    /// 0
+    /// '- 1
+    ///    '-- 2
+    ///    '-- 3
+    ///    '-- ...
+    /// ```
+    fn get_flex_container_hierarchy(items: usize) -> NodeHierarchy {
+        let node = |parent: Option<usize>, previous_sibling: Option<usize>, next_sibling: Option<usize>, children: Option<(usize, usize)>| Node {
+            parent: parent.map(NodeId::new),
+            previous_sibling: previous_sibling.map(NodeId::new),
+            next_sibling: next_sibling.map(NodeId::new),
+            first_child: children.map(|(first, _)| NodeId::new(first)),
+            last_child: children.map(|(_, last)| NodeId::new(last)),
+        };
+        let last_item = items + 1;
+        let mut internal = vec![
+            node(None, None, None, Some((1, 1))),
+            node(Some(0), None, None, Some((2, last_item))),
+        ];
+        internal.extend((2..=last_item).map(|item| node(
+            Some(1),
+            if item > 2 { Some(item - 1) } else { None },
+            if item < last_item { Some(item + 1) } else { None },
+            None,
+        )));
+        NodeHierarchy { internal }
+    }
+
+    /// Solves the sizes of a DOM from `get_flex_container_hierarchy`, inside of a 600px high window
+    fn solve_flex_container(container: RectLayout, items: &[RectLayout], window_width: f32) -> (SolvedWidthLayout, SolvedHeightLayout) {
+
+        use azul_css::LayoutFlexGrow;
+
+        let node_hierarchy = get_flex_container_hierarchy(items.len());
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+
+        // The container doesn't grow vertically, so that it is as high as its content
+        let container = RectLayout { flex_grow: Some(LayoutFlexGrow(FloatValue::new(0.0))), .. container };
+        let mut internal = vec![RectLayout::default(), container];
+        internal.extend_from_slice(items);
+        let node_data = NodeDataContainer { internal };
+
+        let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&node_data, &node_data.transform(|_, _| None));
+        width_calculated_arena.bubble_preferred_widths_to_parents(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, &BTreeMap::new(), &BTreeSet::new());
+        let flex_lines = width_calculated_arena.apply_flex_grow(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, window_width, &mut BTreeMap::new(), &BTreeSet::new());
+
+        let solved_widths = SolvedWidthLayout {
+            solved_widths: width_calculated_arena.transform(|node, _| node.solved_result()),
+            layout_only_arena: node_data.clone(),
+            non_leaf_nodes_sorted_by_depth,
+            min_root_width: 0.0,
+            grid_columns: BTreeMap::new(),
+            grid_rows: BTreeMap::new(),
+            inline_containers: BTreeSet::new(),
+            flex_lines,
+        };
+
+        let solved_heights = solve_flex_layout_height(&node_hierarchy, &solved_widths, &node_data.transform(|_, _| None), 600.0);
+
+        (solved_widths, solved_heights)
+    }
+
+    /// Widths of the items of a DOM from `get_flex_container_hierarchy`
+    fn get_item_widths(solved_widths: &SolvedWidthLayout) -> Vec<f32> {
+        solved_widths.solved_widths.internal[2..].iter().map(|width| width.total()).collect()
+    }
+
+    /// `flex: <grow> 1 <basis>`
+    fn flex_item(grow: f32, basis: f32) -> RectLayout {
+        use azul_css::LayoutFlexGrow;
+        RectLayout {
+            flex_grow: Some(LayoutFlexGrow(FloatValue::new(grow))),
+            flex_basis: Some(LayoutFlexBasis::Exact(PixelValue::px(basis))),
+            .. Default::default()
+        }
+    }
+
+    // The following tests are ported from the examples of the "Flexible Lengths" section of
+    // the CSS flexbox spec and the flexbox tests of the web-platform-tests, without the text
+
+    #[test]
+    fn test_flexbox_grow() {
+
+        use azul_css::LayoutDirection;
+
+        let row = RectLayout { direction: Some(LayoutDirection::Row), .. Default::default() };
+
+        // flex: 1 / 2 / 3 with a flex-basis of 0: the free space is distributed proportionally
+        let (solved_widths, _) = solve_flex_container(row, &[flex_item(1.0, 0.0), flex_item(2.0, 0.0), flex_item(3.0, 0.0)], 600.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![100.0, 200.0, 300.0]);
+
+        // Only the free space is distributed, the flex-basis is kept
+        let (solved_widths, _) = solve_flex_container(row, &[flex_item(1.0, 100.0), flex_item(1.0, 200.0), flex_item(0.0, 50.0)], 600.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![225.0, 325.0, 50.0]);
+
+        // flex-grow: 0 on all items leaves the free space unused
+        let (solved_widths, _) = solve_flex_container(row, &[flex_item(0.0, 100.0), flex_item(0.0, 100.0)], 600.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![100.0, 100.0]);
+    }
+
+    #[test]
+    fn test_flexbox_shrink() {
+
+        use azul_css::{LayoutDirection, LayoutFlexShrink};
+
+        let row = RectLayout { direction: Some(LayoutDirection::Row), .. Default::default() };
+        let shrink = |flex_shrink: f32| RectLayout {
+            flex_shrink: Some(LayoutFlexShrink(FloatValue::new(flex_shrink))),
+            .. flex_item(0.0, 100.0)
+        };
+
+        // 100px overflow, flex-shrink: 1 / 1 / 2
+        let (solved_widths, _) = solve_flex_container(row, &[shrink(1.0), shrink(1.0), shrink(2.0)], 200.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![75.0, 75.0, 50.0]);
+
+        // flex-shrink: 0 keeps the flex-basis, the other items shrink more
+        let (solved_widths, _) = solve_flex_container(row, &[shrink(1.0), shrink(1.0), shrink(0.0)], 200.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![50.0, 50.0, 100.0]);
+
+        // The shrinking is scaled by the flex-basis, larger items shrink faster
+        let (solved_widths, _) = solve_flex_container(row, &[flex_item(0.0, 300.0), flex_item(0.0, 100.0)], 200.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![150.0, 50.0]);
+    }
+
+    #[test]
+    fn test_flexbox_basis() {
+
+        use azul_css::{LayoutDirection, LayoutWidth};
+
+        let row = RectLayout { direction: Some(LayoutDirection::Row), .. Default::default() };
+
+        // flex: 1 1 50px and flex: 1 1 100px in 250px: the remaining 100px are split equally
+        let (solved_widths, _) = solve_flex_container(row, &[flex_item(1.0, 50.0), flex_item(1.0, 100.0)], 250.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![100.0, 150.0]);
+
+        // flex-basis: auto uses the width of the item (which is fixed, so it doesn't flex),
+        // flex-basis: content uses the size of the content, which is zero for an empty item
+        let fixed = RectLayout { width: Some(LayoutWidth::px(100.0)), .. Default::default() };
+        let content = RectLayout { flex_basis: Some(LayoutFlexBasis::Content), .. Default::default() };
+        let (solved_widths, _) = solve_flex_container(row, &[fixed, content, flex_item(1.0, 50.0)], 250.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![100.0, 50.0, 100.0]);
+    }
+
+    #[test]
+    fn test_flexbox_min_max_size() {
+
+        use azul_css::{LayoutDirection, LayoutMinWidth, LayoutMaxWidth};
+
+        let row = RectLayout { direction: Some(LayoutDirection::Row), .. Default::default() };
+
+        // The item with a max-width is frozen, its free space goes to the other items
+        let max_width = RectLayout { max_width: Some(LayoutMaxWidth::px(50.0)), .. flex_item(1.0, 0.0) };
+        let (solved_widths, _) = solve_flex_container(row, &[max_width, flex_item(1.0, 0.0), flex_item(1.0, 0.0)], 300.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![50.0, 125.0, 125.0]);
+
+        // Same for growing items with a min-width
+        let min_width = RectLayout { min_width: Some(LayoutMinWidth::px(150.0)), .. flex_item(1.0, 0.0) };
+        let (solved_widths, _) = solve_flex_container(row, &[max_width, min_width, flex_item(1.0, 0.0)], 300.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![50.0, 150.0, 100.0]);
+
+        // Shrinking: the first item would shrink to 66.67px, but it can't shrink below 90px
+        let min_width = RectLayout { min_width: Some(LayoutMinWidth::px(90.0)), .. flex_item(0.0, 100.0) };
+        let (solved_widths, _) = solve_flex_container(row, &[min_width, flex_item(0.0, 100.0), flex_item(0.0, 100.0)], 200.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![90.0, 55.0, 55.0]);
+    }
+
+    #[test]
+    fn test_flexbox_wrap() {
+
+        use azul_css::{LayoutDirection, LayoutWrap, LayoutHeight, LayoutRowGap, LayoutColumnGap};
+
+        let row_wrap = RectLayout { direction: Some(LayoutDirection::Row), wrap: Some(LayoutWrap::Wrap), .. Default::default() };
+        let item = |grow: f32| RectLayout { height: Some(LayoutHeight::px(20.0)), .. flex_item(grow, 100.0) };
+
+        assert_eq!(break_into_flex_lines(&[100.0, 100.0, 100.0], 0.0, 250.0), vec![0..2, 2..3]);
+        assert_eq!(break_into_flex_lines(&[100.0, 100.0, 100.0], 60.0, 250.0), vec![0..1, 1..2, 2..3]);
+        assert_eq!(break_into_flex_lines(&[300.0, 100.0], 0.0, 250.0), vec![0..1, 1..2]);
+
+        // Three 100px items in 250px: two lines, each line is grown on its own
+        let (solved_widths, solved_heights) = solve_flex_container(row_wrap, &[item(0.0), item(0.0), item(0.0)], 250.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![100.0, 100.0, 100.0]);
+        assert_eq!(solved_widths.flex_lines[&NodeId::new(1)], vec![vec![NodeId::new(2), NodeId::new(3)], vec![NodeId::new(4)]]);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(1)].total(), 40.0);
+
+        let (solved_widths, _) = solve_flex_container(row_wrap, &[item(1.0), item(1.0), item(1.0)], 250.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![125.0, 125.0, 250.0]);
+
+        // The lines are stacked vertically, the second line starts at x = 0 again
+        let gaps = RectLayout {
+            row_gap: Some(LayoutRowGap::px(10.0)),
+            column_gap: Some(LayoutColumnGap::px(10.0)),
+            .. row_wrap
+        };
+        let (solved_widths, solved_heights) = solve_flex_container(gaps, &[item(0.0), item(0.0), item(0.0)], 250.0);
+        let node_hierarchy = get_flex_container_hierarchy(3);
+        let x_positions = get_x_positions(&solved_widths, &node_hierarchy, LayoutPoint::zero());
+        let y_positions = get_y_positions(&solved_heights, &solved_widths, &node_hierarchy, LayoutPoint::zero());
+        assert_eq!(solved_heights.solved_heights[NodeId::new(1)].total(), 50.0);
+        assert_eq!(x_positions[NodeId::new(3)].0, 110.0);
+        assert_eq!((x_positions[NodeId::new(4)].0, y_positions[NodeId::new(4)].0), (0.0, 30.0));
+
+        // Without wrapping, the items shrink to fit into one line
+        let (solved_widths, _) = solve_flex_container(RectLayout { wrap: Some(LayoutWrap::NoWrap), .. row_wrap }, &[item(0.0), item(0.0), item(0.0)], 240.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![80.0, 80.0, 80.0]);
+        assert!(solved_widths.flex_lines.is_empty());
+
+        // A wrapping row only needs to be as wide as its widest item
+        let (solved_widths, _) = solve_flex_container(row_wrap, &[item(0.0), item(0.0)], 80.0);
+        assert_eq!(get_item_widths(&solved_widths), vec![80.0, 80.0]);
+        assert_eq!(solved_widths.flex_lines[&NodeId::new(1)].len(), 2);
+    }
+
+    #[test]
+    fn test_grow_to_fit_baseline_aligned_children() {
+
+        use azul_css::{LayoutHeight, LayoutFlexGrow, LayoutDirection, FloatValue};
+
+        let node_hierarchy = get_flex_testing_hierarchy();
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+
+        // 1: flex-direction: row; flex-grow: 0; (contains 2 and 3)
+        // 2: height: 20px;
+        // 3: height: 30px;
+        // 4: height: 10px; flex-grow: 0;
+        let no_grow = Some(LayoutFlexGrow(FloatValue::new(0.0)));
+        let mut node_data = NodeDataContainer { internal: vec![
+            RectLayout::default(),
+            RectLayout { direction: Some(LayoutDirection::Row), flex_grow: no_grow, .. Default::default() },
+            RectLayout { height: Some(LayoutHeight::px(20.0)), .. Default::default() },
+            RectLayout { height: Some(LayoutHeight::px(30.0)), .. Default::default() },
+            RectLayout { height: Some(LayoutHeight::px(10.0)), flex_grow: no_grow, .. Default::default() },
+        ]};
+
+        let layout = |node_data: &NodeDataContainer<RectLayout>| {
+            let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(node_data, &node_data.transform(|_, _| None));
+            width_calculated_arena.bubble_preferred_widths_to_parents(&node_hierarchy, node_data, &non_leaf_nodes_sorted_by_depth, &BTreeMap::new(), &BTreeSet::new());
+            width_calculated_arena.apply_flex_grow(&node_hierarchy, node_data, &non_leaf_nodes_sorted_by_depth, 800.0, &mut BTreeMap::new(), &BTreeSet::new());
+            let solved_widths = SolvedWidthLayout {
+                solved_widths: width_calculated_arena.transform(|node, _| node.solved_result()),
+                layout_only_arena: node_data.clone(),
+                non_leaf_nodes_sorted_by_depth: non_leaf_nodes_sorted_by_depth.clone(),
+                min_root_width: 0.0,
+                grid_columns: BTreeMap::new(),
+                grid_rows: BTreeMap::new(),
+                inline_containers: BTreeSet::new(),
+                flex_lines: BTreeMap::new(),
+            };
+            let solved_heights = solve_flex_layout_height(&node_hierarchy, &solved_widths, &node_data.transform(|_, _| None), 600.0);
+            let x_positions = get_x_positions(&solved_widths, &node_hierarchy, LayoutPoint::zero());
+            let y_positions = get_y_positions(&solved_heights, &solved_widths, &node_hierarchy, LayoutPoint::zero());
+            (solved_widths, solved_heights, x_positions, y_positions)
+        };
+
+        let (solved_widths, mut solved_heights, mut x_positions, mut y_positions) = layout(&node_data);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(1)].total(), 30.0);
+        assert_eq!(y_positions[NodeId::new(4)].0, 30.0);
+
+        // Aligning the baselines moved 2 down by 25px, so it sticks out of the row by 15px:
+        // The row grows and 4 moves down, the root keeps its size
+        translate_subtree(NodeId::new(2), &node_hierarchy, 0.0, 25.0, &mut x_positions, &mut y_positions);
+        grow_to_fit_children(NodeId::new(1), &node_hierarchy, &solved_widths, &mut solved_heights, &mut x_positions, &mut y_positions);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(1)].total(), 45.0);
+        assert_eq!(y_positions[NodeId::new(4)].0, 45.0);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(0)].total(), 600.0);
+
+        // Nothing sticks out anymore
+        grow_to_fit_children(NodeId::new(1), &node_hierarchy, &solved_widths, &mut solved_heights, &mut x_positions, &mut y_positions);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(1)].total(), 45.0);
+
+        // A row with a fixed height doesn't grow
+        node_data[NodeId::new(1)].height = Some(LayoutHeight::px(30.0));
+        let (solved_widths, mut solved_heights, mut x_positions, mut y_positions) = layout(&node_data);
+        translate_subtree(NodeId::new(2), &node_hierarchy, 0.0, 25.0, &mut x_positions, &mut y_positions);
+        grow_to_fit_children(NodeId::new(1), &node_hierarchy, &solved_widths, &mut solved_heights, &mut x_positions, &mut y_positions);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(1)].total(), 30.0);
+        assert_eq!(y_positions[NodeId::new(4)].0, 30.0);
+    }
 }
@@ -3,7 +3,7 @@ use std::{
     collections::BTreeMap,
 };
 use glium::glutin::WindowId as GliumWindowId;
-use azul_css::CssProperty;
+use azul_css::{CssProperty, CssVariable, StyleCursor};
 use {
     FastHashMap,
     app::RuntimeError,
@@ -25,6 +25,11 @@ pub struct UiState<T> {
     pub dynamic_css_overrides: BTreeMap<NodeId, FastHashMap<DomString, CssProperty>>,
     /// Stores all tags for nodes that need to activate on a `:hover` or `:active` event.
     pub tag_ids_to_hover_active_states: BTreeMap<TagId, (NodeId, HoverGroup)>,
+    /// Tags -> `cursor` of the node, so that the OS cursor can be set while the node is hovered
+    pub tag_ids_to_cursors: BTreeMap<TagId, StyleCursor>,
+    /// Variables that are visible to all nodes without being declared by the stylesheet
+    /// (i.e. the system colors, see `AppResources::get_system_colors`)
+    pub builtin_css_variables: Vec<CssVariable>,
 
     /// Tags -> Focusable nodes
     pub tab_index_tags: BTreeMap<TagId, (NodeId, TabIndex)>,
@@ -61,6 +66,8 @@ impl<T> fmt::Debug for UiState<T> {
                 dom: {:?}, \
                 dynamic_css_overrides: {:?}, \
                 tag_ids_to_hover_active_states: {:?}, \
+                tag_ids_to_cursors: {:?}, \
+                builtin_css_variables: {:?}, \
                 tab_index_tags: {:?}, \
                 draggable_tags: {:?}, \
                 tag_ids_to_node_ids: {:?}, \
@@ -78,6 +85,8 @@ impl<T> fmt::Debug for UiState<T> {
             self.dom,
             self.dynamic_css_overrides,
             self.tag_ids_to_hover_active_states,
+            self.tag_ids_to_cursors,
+            self.builtin_css_variables,
             self.tab_index_tags,
             self.draggable_tags,
             self.tag_ids_to_node_ids,
@@ -102,6 +111,15 @@ impl<T> UiState<T> {
         window_id: &GliumWindowId,
         layout_callback: fn(&T, layout_info: LayoutInfo<T>) -> Dom<T>
     ) -> Result<Self, RuntimeError<T>> {
+        Ok(Self::dom_from_app_state(app_state, window_id, layout_callback)?.into_ui_state())
+    }
+
+    /// Calls the `layout_callback` and returns the DOM, without creating the `UiState` yet
+    pub(crate) fn dom_from_app_state(
+        app_state: &mut AppState<T>,
+        window_id: &GliumWindowId,
+        layout_callback: fn(&T, layout_info: LayoutInfo<T>) -> Dom<T>
+    ) -> Result<Dom<T>, RuntimeError<T>> {
 
         use dom::{Dom, On, NodeType};
         use std::sync::atomic::Ordering;
@@ -115,6 +133,8 @@ impl<T> UiState<T> {
 
         // Only shortly lock the data to get the dom out
         let dom: Dom<T> = {
+            profile_scope!("layout_callback");
+
             #[cfg(test)]{
                 Dom::<T>::new(NodeType::Div)
             }
@@ -125,7 +145,16 @@ impl<T> UiState<T> {
             }
         };
 
-        Ok(dom.into_ui_state())
+        fake_window.memo_cache.end_frame();
+
+        // Drop the callbacks (and the state of the DynDom nodes) of the last frame
+        // that aren't referenced by the new DOM anymore
+        let used_callback_ids = dom.arena.node_data.internal.iter()
+            .flat_map(|node| node.default_callback_ids.iter().map(|(_, id)| *id))
+            .collect();
+        fake_window.default_callbacks.retain_callbacks(&used_callback_ids);
+
+        Ok(dom)
     }
 
     pub(crate) fn create_tags_for_hover_nodes(&mut self, hover_nodes: &BTreeMap<NodeId, HoverGroup>) {
@@ -141,4 +170,18 @@ impl<T> UiState<T> {
             self.tag_ids_to_hover_active_states.insert(hover_tag, (*hover_node_id, *hover_group));
         }
     }
+
+    pub(crate) fn create_tags_for_cursor_nodes(&mut self, cursor_nodes: &BTreeMap<NodeId, StyleCursor>) {
+        use dom::new_tag_id;
+        for (cursor_node_id, cursor) in cursor_nodes {
+            let cursor_tag = match self.node_ids_to_tag_ids.get(cursor_node_id) {
+                Some(tag_id) => *tag_id,
+                None => new_tag_id(),
+            };
+
+            self.node_ids_to_tag_ids.insert(*cursor_node_id, cursor_tag);
+            self.tag_ids_to_node_ids.insert(cursor_tag, *cursor_node_id);
+            self.tag_ids_to_cursors.insert(cursor_tag, *cursor);
+        }
+    }
 }
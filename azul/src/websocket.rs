@@ -0,0 +1,226 @@
+//! WebSocket client for live data (`--features="websocket"`)
+//!
+//! The connection runs on a background thread, the received messages are handed to a
+//! callback on the main thread (right after the timers have run), which can modify the
+//! application data and return `Redraw` to update the UI:
+//!
+//! ```rust,ignore
+//! struct Dashboard { prices: Vec<String>, socket: Option<WebSocketSender> }
+//!
+//! fn on_price(data: &mut Dashboard, _: &mut AppResources, event: WebSocketEvent) -> UpdateScreen {
+//!     match event {
+//!         WebSocketEvent::Text(price) => { data.prices.push(price); Redraw },
+//!         _ => DontRedraw,
+//!     }
+//! }
+//!
+//! let socket = WebSocket::connect("wss://example.com/prices", on_price);
+//! app_state.data.modify(|data| data.socket = Some(socket.sender()));
+//! app_state.add_websocket(WebSocketId::new(), socket);
+//! ```
+
+use std::{
+    fmt,
+    io::ErrorKind,
+    net::TcpStream,
+    thread,
+    time::Duration,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Sender, Receiver, TryRecvError},
+    },
+};
+use tungstenite::{
+    self, Message, Error as WebSocketError,
+    client::AutoStream, stream::Stream,
+};
+use url::Url;
+use {
+    app_resources::AppResources,
+    callbacks::{UpdateScreen, Redraw, DontRedraw},
+};
+
+/// How long the background thread waits for a message before
+/// it checks whether there are new messages to send
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+static MAX_WEBSOCKET_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// ID for uniquely identifying a WebSocket connection
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WebSocketId(usize);
+
+impl WebSocketId {
+    /// Generates a new, unique `WebSocketId`.
+    pub fn new() -> Self {
+        WebSocketId(MAX_WEBSOCKET_ID.fetch_add(1, Ordering::SeqCst))
+    }
+}
+
+/// Callback that is called on the main thread for every event of the connection
+pub type WebSocketCallback<T> = fn(&mut T, &mut AppResources, WebSocketEvent) -> UpdateScreen;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebSocketEvent {
+    /// The connection has been established
+    Connected,
+    /// A text message has been received
+    Text(String),
+    /// A binary message has been received
+    Binary(Vec<u8>),
+    /// The connection has been closed or could not be established (reason).
+    /// This is the last event of the connection, afterwards the `WebSocket` is removed.
+    Disconnected(String),
+}
+
+/// Connection to a WebSocket server, see the module documentation
+pub struct WebSocket<T> {
+    outgoing: Sender<Message>,
+    incoming: Receiver<WebSocketEvent>,
+    callback: WebSocketCallback<T>,
+    /// Set when the `WebSocket` is dropped - the `WebSocketSender`s can
+    /// outlive the `WebSocket`, so the closed channel can't be used for this
+    is_dropped: Arc<AtomicBool>,
+}
+
+/// Handle for sending messages over a `WebSocket`, can be stored in the application data.
+/// The messages are queued until the connection is established.
+#[derive(Debug, Clone)]
+pub struct WebSocketSender {
+    outgoing: Sender<Message>,
+}
+
+impl<T> WebSocket<T> {
+
+    /// Connects to the `url` (`ws://` or `wss://`) on a background thread.
+    /// The `callback` is called once the `WebSocket` has been added to the `AppState`.
+    pub fn connect(url: &str, callback: WebSocketCallback<T>) -> Self {
+
+        let (outgoing, outgoing_receiver) = mpsc::channel();
+        let (incoming_sender, incoming) = mpsc::channel();
+        let is_dropped = Arc::new(AtomicBool::new(false));
+        let is_dropped_clone = is_dropped.clone();
+        let url = url.to_string();
+
+        thread::spawn(move || run_connection(&url, &outgoing_receiver, &incoming_sender, &is_dropped_clone));
+
+        Self { outgoing, incoming, callback, is_dropped }
+    }
+
+    /// Returns a handle for sending messages
+    #[inline]
+    pub fn sender(&self) -> WebSocketSender {
+        WebSocketSender { outgoing: self.outgoing.clone() }
+    }
+
+    /// Crate-internal: Calls the callback for all events that have been received since
+    /// the last frame. Returns whether the screen should redraw and whether the
+    /// connection has been closed.
+    pub(crate) fn run(&mut self, data: &mut T, app_resources: &mut AppResources) -> (UpdateScreen, bool) {
+
+        let mut should_update_screen = DontRedraw;
+
+        loop {
+            let event = match self.incoming.try_recv() {
+                Ok(event) => event,
+                Err(TryRecvError::Empty) => return (should_update_screen, false),
+                Err(TryRecvError::Disconnected) => return (should_update_screen, true),
+            };
+
+            if (self.callback)(data, app_resources, event) == Redraw {
+                should_update_screen = Redraw;
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for WebSocket<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WebSocket {{ callback: 0x{:x} }}", self.callback as usize)
+    }
+}
+
+impl<T> Drop for WebSocket<T> {
+    fn drop(&mut self) {
+        self.is_dropped.store(true, Ordering::SeqCst);
+    }
+}
+
+impl WebSocketSender {
+
+    /// Sends a text message, returns `false` if the connection has been closed
+    pub fn send_text<S: Into<String>>(&self, text: S) -> bool {
+        self.outgoing.send(Message::Text(text.into())).is_ok()
+    }
+
+    /// Sends a binary message, returns `false` if the connection has been closed
+    pub fn send_binary(&self, bytes: Vec<u8>) -> bool {
+        self.outgoing.send(Message::Binary(bytes)).is_ok()
+    }
+}
+
+/// Runs on the background thread until the connection is closed or the `WebSocket` is dropped
+fn run_connection(url: &str, outgoing: &Receiver<Message>, incoming: &Sender<WebSocketEvent>, is_dropped: &AtomicBool) {
+
+    let connection = Url::parse(url)
+        .map_err(|e| format!("Invalid URL \"{}\": {}", url, e))
+        .and_then(|url| tungstenite::connect(url).map_err(|e| e.to_string()));
+
+    let mut socket = match connection {
+        Ok((socket, _)) => socket,
+        Err(e) => {
+            let _ = incoming.send(WebSocketEvent::Disconnected(e));
+            return;
+        },
+    };
+
+    // Reading a message must not block forever, otherwise messages could never be sent
+    if let Err(e) = get_tcp_stream(socket.get_ref()).set_read_timeout(Some(POLL_INTERVAL)) {
+        let _ = incoming.send(WebSocketEvent::Disconnected(e.to_string()));
+        return;
+    }
+
+    if incoming.send(WebSocketEvent::Connected).is_err() {
+        return;
+    }
+
+    loop {
+        // The `WebSocket` has been removed from the `AppState`
+        if is_dropped.load(Ordering::SeqCst) {
+            let _ = socket.close(None);
+            let _ = socket.write_pending();
+            return;
+        }
+
+        while let Ok(message) = outgoing.try_recv() {
+            if let Err(e) = socket.write_message(message) {
+                let _ = incoming.send(WebSocketEvent::Disconnected(e.to_string()));
+                return;
+            }
+        }
+
+        let (event, is_disconnected) = match socket.read_message() {
+            Ok(Message::Text(text)) => (WebSocketEvent::Text(text), false),
+            Ok(Message::Binary(bytes)) => (WebSocketEvent::Binary(bytes), false),
+            // Pings are answered by tungstenite, the answer is sent by `write_pending`
+            Ok(_) => { let _ = socket.write_pending(); continue; },
+            Err(WebSocketError::Io(ref e)) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {
+                let _ = socket.write_pending();
+                continue;
+            },
+            Err(e) => (WebSocketEvent::Disconnected(e.to_string()), true),
+        };
+
+        if incoming.send(event).is_err() || is_disconnected {
+            return;
+        }
+    }
+}
+
+fn get_tcp_stream(stream: &AutoStream) -> &TcpStream {
+    match stream {
+        Stream::Plain(tcp_stream) => tcp_stream,
+        Stream::Tls(tls_stream) => tls_stream.get_ref(),
+    }
+}
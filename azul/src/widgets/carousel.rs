@@ -0,0 +1,349 @@
+//! Carousel: pages horizontally between child DOMs
+//!
+//! The pages can be swiped / dragged with the mouse or selected via the indicator dots
+//! below the pages. Once the user lets go of the page, it snaps to the nearest page. The
+//! snap animation and the autoplay are driven by `CarouselState::tick`, which has to be
+//! called from a `Timer` (without the timer, the carousel jumps between the pages):
+//!
+//! ```rust,ignore
+//! fn layout(&self, info: LayoutInfo<Self>) -> Dom<Self> {
+//!     let pages = self.images.iter().map(|image| Dom::image(*image)).collect();
+//!     Carousel::new().bind(info.window, &self.carousel, self).dom(&self.carousel, pages)
+//! }
+//!
+//! fn tick_carousel(app: &mut MyApp, app_resources: &mut AppResources) -> (UpdateScreen, TerminateTimer) {
+//!     app.carousel.tick(app_resources)
+//! }
+//!
+//! app_state.add_timer(TimerId::new(), Timer::new(tick_carousel).with_interval(Duration::from_millis(16)));
+//! ```
+
+use std::time::{Duration, Instant};
+use azul_css::{CssProperty, LayoutMargin, PixelValue};
+use {
+    callbacks::{UpdateScreen, Redraw, DontRedraw},
+    dom::{Dom, On},
+    window::FakeWindow,
+    callbacks::{CallbackInfo, StackCheckedPointer, DefaultCallback, DefaultCallbackId},
+    app::AppStateNoData,
+    app_resources::{AppResources, PaintTransform},
+    async::TerminateTimer,
+    animation::Easing,
+};
+
+/// Fraction of the page width that a page has to be dragged to switch to the next page
+const SWIPE_THRESHOLD: f32 = 0.2;
+/// Duration of the animation that moves the pages into place after paging or dragging
+const SNAP_DURATION: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Carousel {
+    callbacks: Option<CarouselCallbacks>,
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+struct CarouselCallbacks {
+    drag_start: DefaultCallbackId,
+    drag_move: DefaultCallbackId,
+    drag_end: DefaultCallbackId,
+    dot_click: DefaultCallbackId,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CarouselState {
+    /// ID of the carousel, has to be unique in the window (the pages are moved
+    /// by transforming the node with the ID `"{id}-track"`)
+    pub id: String,
+    /// Index of the page that is currently shown
+    pub current_page: usize,
+    /// Number of pages, has to match the number of pages passed to `Carousel::dom`
+    pub page_count: usize,
+    /// Switches to the next page after the given time without user interaction
+    pub autoplay: Option<Duration>,
+    /// Width of a page, updated whenever the mouse is over the carousel
+    page_width: f32,
+    drag: Option<CarouselDrag>,
+    snap: Option<SnapAnimation>,
+    last_page_change: Instant,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct CarouselDrag {
+    /// X position of the cursor that corresponds to an offset of 0
+    start_x: f32,
+    offset: f32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct SnapAnimation {
+    /// Offset (in pixels) at which the animation started, the animation ends at 0
+    from: f32,
+    started: Instant,
+}
+
+impl CarouselState {
+
+    pub fn new<S: Into<String>>(id: S, page_count: usize) -> Self {
+        Self {
+            id: id.into(),
+            current_page: 0,
+            page_count,
+            autoplay: None,
+            page_width: 0.0,
+            drag: None,
+            snap: None,
+            last_page_change: Instant::now(),
+        }
+    }
+
+    #[inline]
+    pub fn with_autoplay(mut self, interval: Duration) -> Self {
+        self.autoplay = Some(interval);
+        self
+    }
+
+    /// Animates to the given page (clamped to the last page)
+    pub fn go_to_page(&mut self, page: usize, app_resources: &mut AppResources) -> UpdateScreen {
+        let offset = self.get_offset(Instant::now());
+        self.drag = None;
+        self.snap_to_page(page, offset, app_resources)
+    }
+
+    /// Animates to the next page, wraps around to the first page after the last page
+    pub fn next_page(&mut self, app_resources: &mut AppResources) -> UpdateScreen {
+        let next_page = if self.current_page + 1 >= self.page_count { 0 } else { self.current_page + 1 };
+        self.go_to_page(next_page, app_resources)
+    }
+
+    /// Animates to the previous page, wraps around to the last page before the first page
+    pub fn previous_page(&mut self, app_resources: &mut AppResources) -> UpdateScreen {
+        let previous_page = if self.current_page == 0 { self.page_count.saturating_sub(1) } else { self.current_page - 1 };
+        self.go_to_page(previous_page, app_resources)
+    }
+
+    /// Advances the snap animation and the autoplay, call this from a timer on every frame
+    pub fn tick(&mut self, app_resources: &mut AppResources) -> (UpdateScreen, TerminateTimer) {
+
+        let now = Instant::now();
+        let mut update_screen = DontRedraw;
+
+        if let Some(autoplay) = self.autoplay {
+            if self.drag.is_none() && self.page_count > 1 && now.duration_since(self.last_page_change) >= autoplay {
+                update_screen = self.next_page(app_resources);
+            }
+        }
+
+        if let Some(snap) = self.snap {
+            let offset = get_snap_offset(snap.from, now.duration_since(snap.started));
+            self.set_offset(offset.unwrap_or(0.0), app_resources);
+            if offset.is_none() {
+                self.snap = None;
+            }
+        }
+
+        (update_screen, TerminateTimer::Continue)
+    }
+
+    fn track_id(&self) -> String {
+        format!("{}-track", self.id)
+    }
+
+    /// Current horizontal offset of the pages, relative to the position of the current page
+    fn get_offset(&self, now: Instant) -> f32 {
+        match (self.drag, self.snap) {
+            (Some(drag), _) => drag.offset,
+            (None, Some(snap)) => get_snap_offset(snap.from, now.duration_since(snap.started)).unwrap_or(0.0),
+            (None, None) => 0.0,
+        }
+    }
+
+    fn set_offset(&self, offset: f32, app_resources: &mut AppResources) {
+        let track_id = self.track_id();
+        let mut paint_override = app_resources.get_paint_property_override(&track_id).cloned().unwrap_or_default();
+        paint_override.transform = if offset == 0.0 { None } else { Some(PaintTransform::translate(offset, 0.0)) };
+        app_resources.set_paint_property_override(track_id, paint_override);
+    }
+
+    /// Switches to the `page` and starts the snap animation at the `offset` of the old page
+    fn snap_to_page(&mut self, page: usize, offset: f32, app_resources: &mut AppResources) -> UpdateScreen {
+
+        let page = page.min(self.page_count.saturating_sub(1));
+        let page_changed = page != self.current_page;
+
+        // The next layout moves the pages by one page width per page, the animation
+        // starts from where the pages are on the screen right now
+        let from = offset + (page as f32 - self.current_page as f32) * self.page_width;
+        let now = Instant::now();
+
+        self.current_page = page;
+        if page_changed {
+            self.last_page_change = now;
+        }
+        self.snap = if from == 0.0 { None } else { Some(SnapAnimation { from, started: now }) };
+        self.set_offset(from, app_resources);
+
+        if page_changed { Redraw } else { DontRedraw }
+    }
+
+    fn update_page_width<T>(&mut self, event: &CallbackInfo<T>) {
+        if let Some(rect) = event.target_rect() {
+            self.page_width = rect.logical_size.width as f32;
+        }
+    }
+
+    fn on_drag_start<T>(&mut self, _: &mut AppStateNoData<T>, event: &mut CallbackInfo<T>) -> UpdateScreen {
+        self.update_page_width(event);
+        let (cursor_x, _) = event.cursor_in_viewport?;
+        // Grabbing the pages during the snap animation continues from where they are
+        let offset = self.get_offset(Instant::now());
+        self.snap = None;
+        self.drag = Some(CarouselDrag { start_x: cursor_x - offset, offset });
+        DontRedraw
+    }
+
+    fn on_drag_move<T>(&mut self, app_state_no_data: &mut AppStateNoData<T>, event: &mut CallbackInfo<T>) -> UpdateScreen {
+        self.update_page_width(event);
+        let (cursor_x, _) = event.cursor_in_viewport?;
+        let is_first_page = self.current_page == 0;
+        let is_last_page = self.current_page + 1 >= self.page_count;
+        let drag = self.drag.as_mut()?;
+
+        // Dragging past the first or the last page only moves the pages by a third of the distance
+        let offset = cursor_x - drag.start_x;
+        drag.offset = if (is_first_page && offset > 0.0) || (is_last_page && offset < 0.0) { offset / 3.0 } else { offset };
+
+        let offset = drag.offset;
+        self.set_offset(offset, app_state_no_data.resources);
+        DontRedraw
+    }
+
+    fn on_drag_end<T>(&mut self, app_state_no_data: &mut AppStateNoData<T>, _: &mut CallbackInfo<T>) -> UpdateScreen {
+        let drag = self.drag.take()?;
+        let page = get_swipe_target(self.current_page, self.page_count, drag.offset, self.page_width);
+        self.snap_to_page(page, drag.offset, app_state_no_data.resources)
+    }
+
+    fn on_dot_click<T>(&mut self, app_state_no_data: &mut AppStateNoData<T>, event: &mut CallbackInfo<T>) -> UpdateScreen {
+        let page = event.target_index_in_parent()?;
+        self.go_to_page(page, app_state_no_data.resources)
+    }
+
+    fn on_drag_start_private<T>(data: &StackCheckedPointer<T>, app_state_no_data: &mut AppStateNoData<T>, window_event: &mut CallbackInfo<T>) -> UpdateScreen {
+        unsafe { data.invoke_mut(Self::on_drag_start, app_state_no_data, window_event) }
+    }
+
+    fn on_drag_move_private<T>(data: &StackCheckedPointer<T>, app_state_no_data: &mut AppStateNoData<T>, window_event: &mut CallbackInfo<T>) -> UpdateScreen {
+        unsafe { data.invoke_mut(Self::on_drag_move, app_state_no_data, window_event) }
+    }
+
+    fn on_drag_end_private<T>(data: &StackCheckedPointer<T>, app_state_no_data: &mut AppStateNoData<T>, window_event: &mut CallbackInfo<T>) -> UpdateScreen {
+        unsafe { data.invoke_mut(Self::on_drag_end, app_state_no_data, window_event) }
+    }
+
+    fn on_dot_click_private<T>(data: &StackCheckedPointer<T>, app_state_no_data: &mut AppStateNoData<T>, window_event: &mut CallbackInfo<T>) -> UpdateScreen {
+        unsafe { data.invoke_mut(Self::on_dot_click, app_state_no_data, window_event) }
+    }
+}
+
+impl Carousel {
+
+    pub fn new() -> Self {
+        Carousel { callbacks: None }
+    }
+
+    pub fn bind<T>(self, window: &mut FakeWindow<T>, field: &CarouselState, data: &T) -> Self {
+        let ptr = StackCheckedPointer::new(data, field);
+        let callbacks = ptr.map(|ptr| CarouselCallbacks {
+            drag_start: window.add_callback(ptr, DefaultCallback(CarouselState::on_drag_start_private)),
+            drag_move: window.add_callback(ptr, DefaultCallback(CarouselState::on_drag_move_private)),
+            drag_end: window.add_callback(ptr, DefaultCallback(CarouselState::on_drag_end_private)),
+            dot_click: window.add_callback(ptr, DefaultCallback(CarouselState::on_dot_click_private)),
+        });
+
+        Self { callbacks }
+    }
+
+    pub fn dom<T>(&self, state: &CarouselState, pages: Vec<Dom<T>>) -> Dom<T> {
+
+        // The track is moved to the current page via the layout (so that it stays in place
+        // when the carousel is resized), the dragging and the snapping only transform it
+        let track = pages.into_iter()
+            .map(|page| Dom::div().with_class("__azul-native-carousel-page").with_child(page))
+            .collect::<Dom<T>>()
+            .with_id(state.track_id())
+            .with_class("__azul-native-carousel-track")
+            .with_css_override("azul-carousel-offset", CssProperty::Margin(LayoutMargin {
+                left: Some(PixelValue::percent(state.current_page as f32 * -100.0)),
+                .. Default::default()
+            }));
+
+        let mut viewport = Dom::div()
+            .with_class("__azul-native-carousel-viewport")
+            .with_child(track);
+
+        if let Some(callbacks) = self.callbacks {
+            viewport.add_default_callback_id(On::LeftMouseDown, callbacks.drag_start);
+            viewport.add_default_callback_id(On::MouseOver, callbacks.drag_move);
+            viewport.add_default_callback_id(On::LeftMouseUp, callbacks.drag_end);
+            viewport.add_default_callback_id(On::MouseLeave, callbacks.drag_end);
+        }
+
+        let dots = (0..state.page_count).map(|page| {
+            let mut dot = Dom::div().with_class("__azul-native-carousel-dot");
+            if page == state.current_page {
+                dot.add_class("__azul-native-carousel-dot-active");
+            }
+            if let Some(callbacks) = self.callbacks {
+                dot.add_default_callback_id(On::MouseUp, callbacks.dot_click);
+            }
+            dot
+        }).collect::<Dom<T>>().with_class("__azul-native-carousel-dots");
+
+        Dom::div()
+            .with_class("__azul-native-carousel")
+            .with_child(viewport)
+            .with_child(dots)
+    }
+}
+
+/// Returns the page that the carousel snaps to after the pages were dragged by `offset` pixels
+fn get_swipe_target(current_page: usize, page_count: usize, offset: f32, page_width: f32) -> usize {
+    let threshold = page_width * SWIPE_THRESHOLD;
+    if offset < -threshold && current_page + 1 < page_count {
+        current_page + 1
+    } else if offset > threshold && current_page > 0 {
+        current_page - 1
+    } else {
+        current_page
+    }
+}
+
+/// Returns the offset of the snap animation that started at `from` after `elapsed`,
+/// `None` once the animation is finished
+fn get_snap_offset(from: f32, elapsed: Duration) -> Option<f32> {
+    let elapsed = elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 / 1_000_000_000.0;
+    let duration = SNAP_DURATION.as_secs() as f32 + SNAP_DURATION.subsec_nanos() as f32 / 1_000_000_000.0;
+    let progress = elapsed / duration;
+    if progress >= 1.0 {
+        None
+    } else {
+        Some(from * (1.0 - Easing::EaseOut.apply(progress)))
+    }
+}
+
+#[test]
+fn test_carousel_swipe_and_snap() {
+
+    // Dragging less than 20% of the page width snaps back to the current page
+    assert_eq!(get_swipe_target(1, 3, -10.0, 100.0), 1);
+    assert_eq!(get_swipe_target(1, 3, -30.0, 100.0), 2);
+    assert_eq!(get_swipe_target(1, 3, 30.0, 100.0), 0);
+    // Can't swipe past the first or the last page
+    assert_eq!(get_swipe_target(0, 3, 30.0, 100.0), 0);
+    assert_eq!(get_swipe_target(2, 3, -30.0, 100.0), 2);
+
+    assert_eq!(get_snap_offset(100.0, Duration::from_millis(0)), Some(100.0));
+    let halfway = get_snap_offset(100.0, Duration::from_millis(150)).unwrap();
+    assert!(halfway > 0.0 && halfway < 50.0);
+    assert_eq!(get_snap_offset(100.0, SNAP_DURATION), None);
+}
@@ -4,6 +4,9 @@ pub mod button;
 pub mod label;
 pub mod text_input;
 pub mod table_view;
+pub mod property_grid;
+pub mod skeleton;
+pub mod carousel;
 
 pub mod errors {
     #[cfg(all(feature = "svg", feature = "svg_parsing"))]
@@ -0,0 +1,350 @@
+//! Property grid: one editable label / value row per field of a struct
+//! (demonstrates two-way data binding for arbitrary fields)
+//!
+//! The rows can be added manually via `PropertyGrid::add_field` or generated
+//! from a struct via `#[derive(AutoForm)]` (`--features="derive"`):
+//!
+//! ```rust,ignore
+//! #[derive(AutoForm)]
+//! struct Settings {
+//!     user_name: String,
+//!     font_size: f32,
+//!     dark_mode: bool,
+//! }
+//!
+//! fn layout(&self, info: LayoutInfo<Self>) -> Dom<Self> {
+//!     PropertyGrid::new(info.window, self).with_form(&self.settings).dom()
+//! }
+//! ```
+
+use std::cell::RefCell;
+use {
+    callbacks::{UpdateScreen, Redraw, DontRedraw},
+    dom::{Dom, On, EventFilter, FocusEventFilter, TabIndex},
+    window::FakeWindow,
+    prelude::VirtualKeyCode,
+    callbacks::{CallbackInfo, StackCheckedPointer, DefaultCallback},
+    app::AppStateNoData,
+};
+
+thread_local! {
+    /// Raw text of the field that is currently being edited, see `EditBuffer`
+    static EDIT_BUFFER: RefCell<Option<EditBuffer>> = RefCell::new(None);
+}
+
+/// The text of a field is only parsed once the user presses Enter or the editor loses
+/// the focus, so that intermediate states such as "-" or "1." can be typed in
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EditBuffer {
+    /// Address of the edited field
+    field: usize,
+    text: String,
+}
+
+/// Struct that can be edited in a `PropertyGrid`, usually implemented via `#[derive(AutoForm)]`
+pub trait AutoForm {
+    /// Adds one row per (editable) field to the `grid`
+    fn add_form_fields<T>(&self, grid: &mut PropertyGrid<T>);
+}
+
+/// Which kind of editor is used to edit a `FormValue`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FormEditor {
+    /// Single-line text input
+    Text,
+    /// Checkbox with the current checked state
+    Checkbox(bool),
+}
+
+/// Value that can be edited in a `PropertyGrid`
+pub trait FormValue {
+    /// Returns the editor for this value, `FormEditor::Text` by default
+    fn get_editor(&self) -> FormEditor {
+        FormEditor::Text
+    }
+    /// Formats the value for the editor
+    fn to_form_string(&self) -> String;
+    /// Parses the edited text and updates the value. Returns `false` (and leaves
+    /// the value unchanged) if the text isn't a valid value, i.e. "1a" for a number.
+    /// Called once the user presses Enter or the editor loses the focus.
+    fn set_from_form_string(&mut self, text: &str) -> bool;
+}
+
+impl FormValue for String {
+    fn to_form_string(&self) -> String {
+        self.clone()
+    }
+    fn set_from_form_string(&mut self, text: &str) -> bool {
+        *self = text.to_string();
+        true
+    }
+}
+
+impl FormValue for bool {
+    fn get_editor(&self) -> FormEditor {
+        FormEditor::Checkbox(*self)
+    }
+    fn to_form_string(&self) -> String {
+        self.to_string()
+    }
+    fn set_from_form_string(&mut self, text: &str) -> bool {
+        match text.parse() {
+            Ok(value) => { *self = value; true },
+            Err(_) => false,
+        }
+    }
+}
+
+macro_rules! impl_form_value_number {($($number:ty),*) => ($(
+    impl FormValue for $number {
+        fn to_form_string(&self) -> String {
+            self.to_string()
+        }
+        fn set_from_form_string(&mut self, text: &str) -> bool {
+            // Deleting all characters of a number resets it to zero
+            let text = if text.is_empty() { "0" } else { text };
+            match text.parse() {
+                Ok(value) => { *self = value; true },
+                Err(_) => false,
+            }
+        }
+    }
+)*)}
+
+impl_form_value_number!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+/// Builds the rows of the property grid and binds the editors to the fields of `T`
+pub struct PropertyGrid<'a, T: 'a> {
+    window: &'a mut FakeWindow<T>,
+    data: &'a T,
+    rows: Vec<Dom<T>>,
+}
+
+impl<'a, T: 'a> PropertyGrid<'a, T> {
+
+    /// Creates an empty property grid. The edited fields have to be contained in `data`,
+    /// otherwise the rows are displayed, but can't be edited.
+    pub fn new(window: &'a mut FakeWindow<T>, data: &'a T) -> Self {
+        Self { window, data, rows: Vec::new() }
+    }
+
+    /// Adds one row for each field of the `form`, see `AutoForm`
+    pub fn with_form<F: AutoForm>(mut self, form: &F) -> Self {
+        form.add_form_fields(&mut self);
+        self
+    }
+
+    /// Adds a row with a `label` and an editor for the `field`
+    pub fn add_field<U: FormValue>(&mut self, label: &str, field: &U) {
+
+        let ptr = StackCheckedPointer::new(self.data, field);
+
+        let editor = match field.get_editor() {
+            FormEditor::Text => {
+                let mut editor = Dom::div()
+                    .with_class("__azul-native-input-text")
+                    .with_tab_index(TabIndex::Auto)
+                    .with_child(Dom::label(get_editor_text(field)).with_class("__azul-native-input-text-label"));
+                if let Some(ptr) = ptr {
+                    let text_input_callback = self.window.add_callback(ptr, DefaultCallback(on_text_input_private::<T, U>));
+                    let vk_callback = self.window.add_callback(ptr, DefaultCallback(on_virtual_key_down_private::<T, U>));
+                    let focus_lost_callback = self.window.add_callback(ptr, DefaultCallback(on_focus_lost_private::<T, U>));
+                    editor.add_default_callback_id(EventFilter::Focus(FocusEventFilter::TextInput), text_input_callback);
+                    editor.add_default_callback_id(EventFilter::Focus(FocusEventFilter::VirtualKeyDown), vk_callback);
+                    editor.add_default_callback_id(EventFilter::Focus(FocusEventFilter::FocusLost), focus_lost_callback);
+                }
+                editor
+            },
+            FormEditor::Checkbox(checked) => {
+                let mut editor = Dom::div()
+                    .with_class("__azul-native-checkbox")
+                    .with_tab_index(TabIndex::Auto)
+                    .is_checked(checked);
+                if let Some(ptr) = ptr {
+                    let toggle_callback = self.window.add_callback(ptr, DefaultCallback(on_toggle_private::<T, U>));
+                    editor.add_default_callback_id(On::MouseUp, toggle_callback);
+                }
+                editor
+            },
+        };
+
+        self.rows.push(
+            Dom::div()
+            .with_class("__azul-native-property-grid-row")
+            .with_child(Dom::label(label.to_string()).with_class("__azul-native-property-grid-label"))
+            .with_child(editor)
+        );
+    }
+
+    pub fn dom(self) -> Dom<T> {
+        let mut grid = Dom::div().with_class("__azul-native-property-grid");
+        for row in self.rows {
+            grid.add_child(row);
+        }
+        grid
+    }
+}
+
+fn on_text_input_private<T, U: FormValue>(data: &StackCheckedPointer<T>, app_state_no_data: &mut AppStateNoData<T>, window_event: &mut CallbackInfo<T>) -> UpdateScreen {
+    unsafe { data.invoke_mut(on_text_input::<T, U>, app_state_no_data, window_event) }
+}
+
+fn on_virtual_key_down_private<T, U: FormValue>(data: &StackCheckedPointer<T>, app_state_no_data: &mut AppStateNoData<T>, window_event: &mut CallbackInfo<T>) -> UpdateScreen {
+    unsafe { data.invoke_mut(on_virtual_key_down::<T, U>, app_state_no_data, window_event) }
+}
+
+fn on_focus_lost_private<T, U: FormValue>(data: &StackCheckedPointer<T>, app_state_no_data: &mut AppStateNoData<T>, window_event: &mut CallbackInfo<T>) -> UpdateScreen {
+    unsafe { data.invoke_mut(on_focus_lost::<T, U>, app_state_no_data, window_event) }
+}
+
+fn on_toggle_private<T, U: FormValue>(data: &StackCheckedPointer<T>, app_state_no_data: &mut AppStateNoData<T>, window_event: &mut CallbackInfo<T>) -> UpdateScreen {
+    unsafe { data.invoke_mut(on_toggle::<T, U>, app_state_no_data, window_event) }
+}
+
+fn on_text_input<T, U: FormValue>(value: &mut U, app_state_no_data: &mut AppStateNoData<T>, event: &mut CallbackInfo<T>) -> UpdateScreen {
+    let keyboard_state = app_state_no_data.windows[event.window_id].get_keyboard_state();
+    let c = keyboard_state.current_char?;
+    edit_field_text(value, |text| text.push(c));
+    Redraw
+}
+
+fn on_virtual_key_down<T, U: FormValue>(value: &mut U, app_state_no_data: &mut AppStateNoData<T>, event: &mut CallbackInfo<T>) -> UpdateScreen {
+    let keyboard_state = app_state_no_data.windows[event.window_id].get_keyboard_state();
+    match keyboard_state.latest_virtual_keycode {
+        Some(VirtualKeyCode::Back) => {
+            edit_field_text(value, |text| { text.pop(); });
+            Redraw
+        },
+        Some(VirtualKeyCode::Return) => {
+            commit_field_text(value);
+            Redraw
+        },
+        Some(VirtualKeyCode::Escape) => {
+            cancel_field_text(value);
+            Redraw
+        },
+        _ => DontRedraw,
+    }
+}
+
+fn on_focus_lost<T, U: FormValue>(value: &mut U, _: &mut AppStateNoData<T>, _: &mut CallbackInfo<T>) -> UpdateScreen {
+    if commit_field_text(value).is_some() { Redraw } else { DontRedraw }
+}
+
+fn on_toggle<T, U: FormValue>(value: &mut U, _: &mut AppStateNoData<T>, _: &mut CallbackInfo<T>) -> UpdateScreen {
+    match value.get_editor() {
+        FormEditor::Checkbox(checked) => {
+            if value.set_from_form_string(&(!checked).to_string()) { Redraw } else { DontRedraw }
+        },
+        FormEditor::Text => DontRedraw,
+    }
+}
+
+fn get_field_address<U>(field: &U) -> usize {
+    field as *const U as usize
+}
+
+/// Returns the text that the editor of the `field` shows: the edit buffer
+/// while the field is being edited, otherwise the formatted value
+fn get_editor_text<U: FormValue>(field: &U) -> String {
+    let address = get_field_address(field);
+    EDIT_BUFFER.with(|buffer| match &*buffer.borrow() {
+        Some(edit) if edit.field == address => edit.text.clone(),
+        _ => field.to_form_string(),
+    })
+}
+
+/// Modifies the edit buffer of the `field`. If another field (or no field)
+/// was being edited, the buffer starts with the formatted value of the `field`.
+fn edit_field_text<U: FormValue, F: FnOnce(&mut String)>(field: &U, edit: F) {
+    let address = get_field_address(field);
+    EDIT_BUFFER.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        let is_edited = buffer.as_ref().map(|edit| edit.field == address).unwrap_or(false);
+        if !is_edited {
+            *buffer = Some(EditBuffer { field: address, text: field.to_form_string() });
+        }
+        if let Some(buffer) = buffer.as_mut() {
+            edit(&mut buffer.text);
+        }
+    });
+}
+
+/// Ends the editing of the `field` and parses the edit buffer into the `field`.
+/// Returns `None` if the field wasn't being edited, otherwise whether the text was a valid
+/// value - if not, the field keeps its old value (and the editor shows the old value again).
+fn commit_field_text<U: FormValue>(field: &mut U) -> Option<bool> {
+    let text = take_field_text(field)?;
+    Some(field.set_from_form_string(&text))
+}
+
+/// Ends the editing of the `field` without changing the value
+fn cancel_field_text<U: FormValue>(field: &U) {
+    take_field_text(field);
+}
+
+fn take_field_text<U>(field: &U) -> Option<String> {
+    let address = get_field_address(field);
+    EDIT_BUFFER.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        match buffer.take() {
+            Some(edit) if edit.field == address => Some(edit.text),
+            other => { *buffer = other; None },
+        }
+    })
+}
+
+#[test]
+fn test_form_value_from_string() {
+    let mut number = 5_i32;
+    assert!(number.set_from_form_string("-12"));
+    assert_eq!(number, -12);
+    assert!(!number.set_from_form_string("1a"));
+    assert_eq!(number, -12);
+    assert!(number.set_from_form_string(""));
+    assert_eq!(number, 0);
+
+    let mut checked = false;
+    assert_eq!(checked.get_editor(), FormEditor::Checkbox(false));
+    assert!(checked.set_from_form_string("true"));
+    assert!(checked);
+    assert!(!checked.set_from_form_string("yes"));
+
+    let mut text = String::from("a");
+    assert_eq!(text.get_editor(), FormEditor::Text);
+    assert!(text.set_from_form_string("b"));
+    assert_eq!(text, "b");
+}
+
+#[test]
+fn test_edit_buffer_keeps_intermediate_text() {
+    let mut fields = (1.5_f32, -3_i32);
+
+    // "1." and "-" aren't valid numbers, but they have to stay in the editor until the edit is committed
+    edit_field_text(&fields.0, |text| text.clear());
+    edit_field_text(&fields.0, |text| text.push_str("1."));
+    assert_eq!(get_editor_text(&fields.0), "1.");
+    assert_eq!(fields.0, 1.5);
+    edit_field_text(&fields.0, |text| text.push('2'));
+    assert_eq!(commit_field_text(&mut fields.0), Some(true));
+    assert_eq!(fields.0, 1.2);
+    assert_eq!(get_editor_text(&fields.0), "1.2");
+    assert_eq!(commit_field_text(&mut fields.0), None);
+
+    // Invalid text keeps the old value
+    edit_field_text(&fields.1, |text| text.clear());
+    edit_field_text(&fields.1, |text| text.push('-'));
+    assert_eq!(get_editor_text(&fields.1), "-");
+    assert_eq!(commit_field_text(&mut fields.1), Some(false));
+    assert_eq!(fields.1, -3);
+    assert_eq!(get_editor_text(&fields.1), "-3");
+
+    // Editing another field discards the edit buffer of the previous field
+    edit_field_text(&fields.0, |text| text.push('5'));
+    edit_field_text(&fields.1, |text| text.push('4'));
+    assert_eq!(get_editor_text(&fields.0), "1.2");
+    assert_eq!(get_editor_text(&fields.1), "-34");
+    cancel_field_text(&fields.1);
+    assert_eq!(get_editor_text(&fields.1), "-3");
+    assert_eq!(commit_field_text(&mut fields.0), None);
+}
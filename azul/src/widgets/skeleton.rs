@@ -0,0 +1,146 @@
+//! Placeholder blocks ("skeleton screens") for content that is still being loaded
+//!
+//! A `Skeleton` roughly resembles the layout of the content that is loaded, so that the UI
+//! doesn't jump around when the data arrives. While a `Task` is loading the data, all skeletons
+//! shimmer (see `Skeleton::load`):
+//!
+//! ```rust,ignore
+//! fn layout(&self, _: LayoutInfo<Self>) -> Dom<Self> {
+//!     Skeleton::lines(3).dom_or(self.article.as_ref().map(|article| Dom::label(article.clone())))
+//! }
+//!
+//! fn load_article(app_state: &mut AppState<MyApp>, _: &mut CallbackInfo<MyApp>) -> UpdateScreen {
+//!     let task = Task::new(&app_state.data, download_article).then(Timer::new(show_article));
+//!     Skeleton::load(app_state, task);
+//!     Redraw
+//! }
+//! ```
+
+use std::time::Duration;
+use azul_css::{
+    CssProperty, LayoutWidth, LayoutHeight, StyleBorderRadius,
+    BorderRadius, PixelSize, PixelValue,
+};
+use {
+    dom::Dom,
+    app::AppState,
+    async::Task,
+    animation::{Timeline, TimelineId, TimelineItem, Animation, AnimatedProperty, Easing, Repeat},
+};
+
+/// ID of all skeleton blocks, the shimmer animation changes the opacity of all nodes with this ID
+pub const SKELETON_SHIMMER_ID: &str = "__azul-skeleton-shimmer";
+
+/// Placeholder for the content that is being loaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skeleton {
+    /// Blocks, laid out from top to bottom
+    pub blocks: Vec<SkeletonBlock>,
+}
+
+/// A single placeholder block of a `Skeleton`, sizes are in pixels
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkeletonBlock {
+    /// Line of text that fills the available width
+    Line,
+    /// Line of text with a fixed width, i.e. a heading or the last line of a paragraph
+    ShortLine(f32),
+    /// Rectangle with a fixed width and height, i.e. an image
+    Rect(f32, f32),
+    /// Circle with a fixed diameter, i.e. an avatar or an icon
+    Circle(f32),
+    /// Blocks laid out from left to right, i.e. an avatar next to a name
+    Row(Vec<SkeletonBlock>),
+}
+
+impl Skeleton {
+
+    #[inline]
+    pub fn new(blocks: Vec<SkeletonBlock>) -> Self {
+        Self { blocks }
+    }
+
+    /// Placeholder for a paragraph of `count` lines, the last line is shorter
+    pub fn lines(count: usize) -> Self {
+        let mut blocks = vec![SkeletonBlock::Line; count.saturating_sub(1)];
+        if count > 0 {
+            blocks.push(SkeletonBlock::ShortLine(150.0));
+        }
+        Self { blocks }
+    }
+
+    pub fn dom<T>(self) -> Dom<T> {
+        self.blocks.into_iter()
+            .map(SkeletonBlock::dom)
+            .collect::<Dom<T>>()
+            .with_class("__azul-native-skeleton")
+    }
+
+    /// Returns the `content` once it has been loaded, otherwise the skeleton
+    #[inline]
+    pub fn dom_or<T>(self, content: Option<Dom<T>>) -> Dom<T> {
+        content.unwrap_or_else(|| self.dom())
+    }
+
+    /// Starts the `task` and lets all skeletons shimmer until the task has finished
+    pub fn load<T>(app_state: &mut AppState<T>, task: Task<T>) {
+        let timeline_id = TimelineId::new();
+        app_state.add_timeline(timeline_id, Self::shimmer_timeline());
+        app_state.add_task(task.then_stop_timeline(timeline_id));
+    }
+
+    /// Timeline that fades all skeleton blocks in and out forever,
+    /// use this if the data isn't loaded with a `Task`
+    pub fn shimmer_timeline<T>() -> Timeline<T> {
+        let fade = Animation::new(
+            AnimatedProperty::Opacity(SKELETON_SHIMMER_ID.to_string()),
+            1.0, 0.4, Duration::from_millis(800),
+        ).with_easing(Easing::EaseInOut);
+        Timeline::new(TimelineItem::from(fade).with_ping_pong().with_repeat(Repeat::Forever))
+    }
+}
+
+impl SkeletonBlock {
+
+    fn dom<T>(self) -> Dom<T> {
+        use self::SkeletonBlock::*;
+
+        let block = || Dom::div()
+            .with_id(SKELETON_SHIMMER_ID)
+            .with_class("__azul-native-skeleton-block");
+
+        match self {
+            Line => block().with_class("__azul-native-skeleton-line"),
+            ShortLine(width) => block()
+                .with_css_override("azul-skeleton-width", CssProperty::Width(LayoutWidth::px(width))),
+            Rect(width, height) => block()
+                .with_css_override("azul-skeleton-width", CssProperty::Width(LayoutWidth::px(width)))
+                .with_css_override("azul-skeleton-height", CssProperty::Height(LayoutHeight::px(height))),
+            Circle(diameter) => {
+                let radius = PixelValue::px(diameter / 2.0);
+                block()
+                .with_css_override("azul-skeleton-width", CssProperty::Width(LayoutWidth::px(diameter)))
+                .with_css_override("azul-skeleton-height", CssProperty::Height(LayoutHeight::px(diameter)))
+                .with_css_override("azul-skeleton-radius", CssProperty::BorderRadius(
+                    StyleBorderRadius(BorderRadius::uniform(PixelSize::new(radius, radius)))
+                ))
+            },
+            Row(blocks) => blocks.into_iter()
+                .map(SkeletonBlock::dom)
+                .collect::<Dom<T>>()
+                .with_class("__azul-native-skeleton-row"),
+        }
+    }
+}
+
+#[test]
+fn test_skeleton_is_replaced_by_content() {
+
+    struct Mock;
+
+    let content: Dom<Mock> = Skeleton::lines(3).dom_or(Some(Dom::label("Hello")));
+    assert!(content == Dom::label("Hello"));
+
+    let skeleton: Dom<Mock> = Skeleton::lines(3).dom_or(None);
+    assert_eq!(skeleton.len(), 4);
+}
@@ -1,13 +1,16 @@
 use std::{
-    fmt,
+    fmt, mem,
+    any::Any,
     rc::Rc,
+    cell::RefCell,
     marker::PhantomData,
     io::Error as IoError,
     sync::atomic::{AtomicUsize, Ordering},
+    time::{Instant, Duration},
 };
 use webrender::{
     api::{
-        PipelineId, Epoch, DocumentId,
+        PipelineId, Epoch, DocumentId, LayoutRect, LayoutSize,
         RenderApi, ExternalScrollId, RenderNotifier, DeviceIntSize,
     },
     Renderer, RendererOptions, RendererKind, ShaderPrecacheFlags, WrShaders,
@@ -20,11 +23,11 @@ use glium::{
         self, EventsLoop, AvailableMonitorsIter, ContextTrait, CombinedContext, CreationError,
         MonitorId, ContextError, ContextBuilder, WindowId as GliumWindowId,
         Window as GliumWindow, WindowBuilder as GliumWindowBuilder, Icon, Context,
-        dpi::LogicalSize,
+        dpi::{LogicalSize, LogicalPosition},
     },
     backend::{Context as BackendContext, Facade, glutin::DisplayCreationError},
 };
-use gleam::gl::{self, Gl};
+use gleam::gl::{self, Gl, GLuint};
 use azul_css::{Css, ColorU};
 #[cfg(debug_assertions)]
 use azul_css::HotReloadHandler;
@@ -34,9 +37,15 @@ use {
     app::FrameEventInfo,
     callbacks::{
         Callback, DefaultCallbackSystem, StackCheckedPointer,
-        DefaultCallback, DefaultCallbackId, Texture,
+        DefaultCallback, DefaultCallbackId, Texture, DynCallback,
+        LayoutCallback, LayoutCallbackType,
     },
-    display_list::ScrolledNodes,
+    display_list::{ScrolledNodes, IFrameCache},
+    dom::MemoCache,
+    diff::{KeyedRects, RetainedNode},
+    keyboard_audit::KeyboardAudit,
+    id_tree::{NodeId, NodeDataContainer},
+    ui_solver::{PositionedRectangle, LayoutResult, LayoutCache},
 };
 pub use webrender::api::HitTestItem;
 pub use window_state::*;
@@ -59,7 +68,66 @@ pub struct FakeWindow<T> {
     /// the user can create textures and other OpenGL content in the window
     /// but not change any window properties from underneath - this would
     /// lead to mismatch between the
-    pub(crate) read_only_window: Rc<Display>,
+    ///
+    /// Only `None` for the windows of the unit tests, which don't have an OpenGL context.
+    pub(crate) read_only_window: Option<Rc<Display>>,
+    /// Current find-in-page query + the matches found during the last layout, see `search()`
+    pub(crate) text_search: Option<TextSearch>,
+    /// Subtrees of the last frame that were built via `Dom::memo()`
+    pub(crate) memo_cache: MemoCache<T>,
+    /// Content of the IFrames of the last frame, see `WindowCreateOptions::iframe_resize_preview`
+    pub(crate) iframe_cache: IFrameCache<T>,
+    /// Layouts of the last frame, so that an unchanged DOM doesn't have to be laid out again
+    pub(crate) layout_cache: LayoutCache,
+}
+
+/// State of a find-in-page search, created by `FakeWindow::search()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSearch {
+    /// The text that is being searched for
+    pub query: String,
+    /// Whether upper- and lowercase characters have to match exactly (default: false)
+    pub case_sensitive: bool,
+    /// Matches that were found in the last frame, in rendering order
+    pub(crate) matches: Vec<TextSearchMatch>,
+    /// Index into `self.matches` of the currently selected match
+    pub(crate) active_match: Option<usize>,
+    /// Set by `next_search_match()` / `previous_search_match()`, so that the
+    /// active match gets scrolled into view after the next layout
+    pub(crate) scroll_to_active_match: bool,
+}
+
+impl TextSearch {
+
+    /// Creates a new, case-insensitive search for the given query
+    pub fn new(query: &str) -> Self {
+        Self {
+            query: query.to_string(),
+            case_sensitive: false,
+            matches: Vec::new(),
+            active_match: Some(0),
+            scroll_to_active_match: true,
+        }
+    }
+
+    /// Returns the matches that were found during the last layout
+    pub fn get_matches(&self) -> &[TextSearchMatch] {
+        &self.matches
+    }
+
+    /// Returns the currently selected match, if there are any matches
+    pub fn get_active_match(&self) -> Option<&TextSearchMatch> {
+        self.matches.get(self.active_match?)
+    }
+}
+
+/// A single occurrence of the search query in a laid-out text node
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSearchMatch {
+    /// The text or label node that contains the match
+    pub node_id: NodeId,
+    /// Bounds of the matched glyphs - one rectangle per word the match touches
+    pub rects: Vec<LayoutRect>,
 }
 
 impl<T> FakeWindow<T> {
@@ -68,7 +136,21 @@ impl<T> FakeWindow<T> {
     /// custom OpenGL texture during the `.layout()` phase
     pub fn read_only_window(&self) -> ReadOnlyWindow {
         ReadOnlyWindow {
-            inner: self.read_only_window.clone()
+            inner: self.read_only_window.clone().expect("FakeWindow has no OpenGL context"),
+        }
+    }
+
+    /// Creates a window without an OpenGL context, for testing the layout functions
+    #[cfg(test)]
+    pub(crate) fn mock() -> Self {
+        Self {
+            state: WindowState::default(),
+            default_callbacks: DefaultCallbackSystem::new(),
+            read_only_window: None,
+            text_search: None,
+            memo_cache: MemoCache::new(),
+            iframe_cache: IFrameCache::new(),
+            layout_cache: LayoutCache::new(),
         }
     }
 
@@ -102,9 +184,9 @@ impl<T> FakeWindow<T> {
         self.state.get_mouse_state()
     }
 
-    /// Adds a default callback to the window. The default callbacks are
-    /// cleared after every frame, so two-way data binding widgets have to call this
-    /// on every frame they want to insert a default callback.
+    /// Adds a default callback to the window. Callbacks that aren't attached to
+    /// any node of the DOM anymore are removed after every frame, so two-way data
+    /// binding widgets have to call this on every frame they want to insert a default callback.
     ///
     /// Returns an ID by which the callback can be uniquely identified (used for hit-testing)
     #[must_use]
@@ -120,6 +202,86 @@ impl<T> FakeWindow<T> {
         self.default_callbacks.add_callback(default_callback_id, callback_ptr, callback_fn);
         default_callback_id
     }
+
+    /// Same as `add_callback`, but for callbacks that don't know the type of the
+    /// application data: the callback gets the `state` instead of a pointer into `T`.
+    /// Usually called by `DynDom::into_dom`, not by the user directly.
+    #[must_use]
+    pub fn add_dyn_callback(
+        &mut self,
+        state: Rc<RefCell<dyn Any>>,
+        callback_fn: DynCallback
+    ) -> DefaultCallbackId {
+
+        use callbacks::get_new_unique_default_callback_id;
+
+        let default_callback_id = get_new_unique_default_callback_id();
+        self.default_callbacks.add_dyn_callback(default_callback_id, state, callback_fn);
+        default_callback_id
+    }
+
+    /// Starts a find-in-page search: After the next layout, all occurrences of `query`
+    /// in the text nodes of this window are highlighted. Return `Redraw` from the
+    /// callback, otherwise the highlights will only show up on the next relayout.
+    ///
+    /// Searching for an empty string is the same as calling `clear_search()`.
+    pub fn search(&mut self, query: &str) {
+        if query.is_empty() {
+            self.clear_search();
+        } else {
+            self.text_search = Some(TextSearch::new(query));
+        }
+    }
+
+    /// Same as `search()`, but the case of the characters has to match exactly
+    pub fn search_case_sensitive(&mut self, query: &str) {
+        self.search(query);
+        if let Some(search) = &mut self.text_search {
+            search.case_sensitive = true;
+        }
+    }
+
+    /// Removes all search highlights
+    pub fn clear_search(&mut self) {
+        self.text_search = None;
+    }
+
+    /// Returns the current search, including the matches of the last frame
+    pub fn get_search(&self) -> Option<&TextSearch> {
+        self.text_search.as_ref()
+    }
+
+    /// Returns how many matches the current search found in the last frame
+    pub fn get_search_match_count(&self) -> usize {
+        self.text_search.as_ref().map(|s| s.matches.len()).unwrap_or(0)
+    }
+
+    /// Selects the next match (wrapping around at the end) and scrolls it into view.
+    /// Returns the index of the newly selected match.
+    pub fn next_search_match(&mut self) -> Option<usize> {
+        let search = self.text_search.as_mut()?;
+        if search.matches.is_empty() {
+            return None;
+        }
+        let next = search.active_match.map(|m| (m + 1) % search.matches.len()).unwrap_or(0);
+        search.active_match = Some(next);
+        search.scroll_to_active_match = true;
+        Some(next)
+    }
+
+    /// Selects the previous match (wrapping around at the start) and scrolls it into view.
+    /// Returns the index of the newly selected match.
+    pub fn previous_search_match(&mut self) -> Option<usize> {
+        let search = self.text_search.as_mut()?;
+        if search.matches.is_empty() {
+            return None;
+        }
+        let len = search.matches.len();
+        let previous = search.active_match.map(|m| (m + len - 1) % len).unwrap_or(len - 1);
+        search.active_match = Some(previous);
+        search.scroll_to_active_match = true;
+        Some(previous)
+    }
 }
 
 /// Read-only window which can be used to create / draw
@@ -186,33 +348,446 @@ impl<T> fmt::Debug for FakeWindow<T> {
     }
 }
 
-/// Options on how to initially create the window
+/// Options on how to initially create the window.
+///
+/// Use `WindowCreateOptions::builder()` to construct the options, the builder
+/// checks for conflicting options before the window is created:
+///
+/// ```rust,ignore
+/// let options = WindowCreateOptions::builder()
+///     .title("My App")
+///     .size(LogicalSize::new(800.0, 600.0))
+///     .transparent(true)
+///     .background_color(ColorU::TRANSPARENT)
+///     .build()?;
+/// ```
 #[derive(Debug, Clone)]
 pub struct WindowCreateOptions<T> {
     /// State of the window, set the initial title / width / height here.
     pub state: WindowState,
+    /// Whether the window should be created with an alpha channel, so that the desktop
+    /// is visible through the transparent parts of the window. The window is also created
+    /// transparent if the background color isn't fully opaque. Default: false
+    pub is_transparent: bool,
     /// Which monitor should the window be created on?
     pub monitor: WindowMonitorTarget,
     /// Renderer type: Hardware-with-software-fallback, pure software or pure hardware renderer?
     pub renderer_type: RendererType,
     /// Win32 menu callbacks
     pub menu_callbacks: FastHashMap<u16, Callback<T>>,
+    /// Builds the DOM of this window instead of `Layout::layout`, so that i.e. a preferences
+    /// window can render different content than the main window (the stylesheet of the
+    /// window is passed to `App::create_window`). Default: None
+    pub layout_callback: Option<LayoutCallback<T>>,
     /// Sets the window icon (Windows and Linux only). Usually 16x16 px or 32x32px
     pub window_icon: Option<Icon>,
     /// Windows only: Sets the 256x256 taskbar icon during startup
     pub taskbar_icon: Option<Icon>,
+    /// The window is always hidden until its first frame has been drawn. However, WebRender
+    /// builds the frame on a background thread, so on slower machines the first frame can
+    /// still be blank (only the background color). If set, the window is only shown once
+    /// WebRender has rendered the first display list. Default: false
+    pub wait_for_first_frame: bool,
+    /// While the window is being resized, IFrames don't invoke their `IFrameCallback`, but
+    /// stretch their last content to the new bounds (so that expensive IFrames don't make
+    /// the resizing stutter). The callbacks are invoked again once the window hasn't been
+    /// resized for the given duration. Default: None (callbacks are invoked on every frame)
+    pub iframe_resize_preview: Option<Duration>,
+    /// Linux only: Shadow and rounded corners that azul draws around an undecorated window,
+    /// for X11 / Wayland compositors that don't draw server-side decorations. Requires a
+    /// window without decorations, the other platforms already draw a shadow. Default: None
+    pub window_shadow: Option<WindowShadow>,
+}
+
+/// Client-side shadow of an undecorated window, see `WindowCreateOptions::window_shadow`.
+///
+/// The shadow is drawn into a transparent margin around the UI, so the window is larger
+/// than the UI by `get_margin()` on each side (`WindowSize::dimensions` includes the margin).
+/// Clicks on the margin go to the windows below (X11 only - Wayland has no input shapes
+/// in winit, so the margin stays clickable there). Maximized and fullscreen windows
+/// don't have a shadow or rounded corners.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WindowShadow {
+    /// Color of the shadow, usually a semi-transparent black
+    pub color: ColorU,
+    /// Blur radius of the shadow in logical pixels
+    pub blur_radius: f32,
+    /// Vertical offset of the shadow in logical pixels (positive = shadow below the window)
+    pub offset_y: f32,
+    /// Radius of the rounded corners of the window in logical pixels
+    pub corner_radius: f32,
+}
+
+impl Default for WindowShadow {
+    fn default() -> Self {
+        Self {
+            color: ColorU { r: 0, g: 0, b: 0, a: 90 },
+            blur_radius: 16.0,
+            offset_y: 4.0,
+            corner_radius: 8.0,
+        }
+    }
+}
+
+impl WindowShadow {
+    /// Returns the space (in logical pixels) that is reserved
+    /// on each side of the window for the shadow
+    pub fn get_margin(&self) -> f32 {
+        (self.blur_radius.max(0.0) + self.offset_y.abs()).ceil()
+    }
 }
 
 impl<T> Default for WindowCreateOptions<T> {
     fn default() -> Self {
         Self {
             state: WindowState::default(),
+            is_transparent: false,
             monitor: WindowMonitorTarget::default(),
             renderer_type: RendererType::default(),
             menu_callbacks: FastHashMap::default(),
+            layout_callback: None,
             window_icon: None,
             taskbar_icon: None,
+            wait_for_first_frame: false,
+            iframe_resize_preview: None,
+            window_shadow: None,
+        }
+    }
+}
+
+impl<T> WindowCreateOptions<T> {
+
+    /// Returns a builder for the window options, see `WindowCreateOptionsBuilder`
+    #[inline]
+    pub fn builder() -> WindowCreateOptionsBuilder<T> {
+        WindowCreateOptionsBuilder::default()
+    }
+
+    /// Checks the options for conflicts - called by `WindowCreateOptionsBuilder::build`
+    /// and again by `App::create_window` (since all fields are public).
+    pub fn validate(&self) -> Result<(), WindowCreateOptionsError> {
+
+        use self::WindowCreateOptionsError::*;
+
+        let size = &self.state.size;
+
+        if !is_valid_size(size.dimensions) {
+            return Err(InvalidSize(size.dimensions));
+        }
+
+        if let Some(min) = size.min_dimensions {
+            if !is_valid_size(min) {
+                return Err(InvalidSize(min));
+            }
+        }
+
+        if let Some(max) = size.max_dimensions {
+            if !is_valid_size(max) {
+                return Err(InvalidSize(max));
+            }
+        }
+
+        if let (Some(min), Some(max)) = (size.min_dimensions, size.max_dimensions) {
+            if min.width > max.width || min.height > max.height {
+                return Err(MinSizeLargerThanMaxSize { min, max });
+            }
+        }
+
+        let is_smaller_than_min = size.min_dimensions.map(|min| {
+            size.dimensions.width < min.width || size.dimensions.height < min.height
+        }).unwrap_or(false);
+        let is_larger_than_max = size.max_dimensions.map(|max| {
+            size.dimensions.width > max.width || size.dimensions.height > max.height
+        }).unwrap_or(false);
+
+        if is_smaller_than_min || is_larger_than_max {
+            return Err(SizeOutOfBounds(size.dimensions));
+        }
+
+        if self.state.is_fullscreen && self.state.has_decorations {
+            return Err(FullscreenWithDecorations);
+        }
+
+        if self.state.is_fullscreen && self.state.is_maximized {
+            return Err(FullscreenAndMaximized);
+        }
+
+        if self.window_shadow.is_some() && self.state.has_decorations {
+            return Err(WindowShadowWithDecorations);
+        }
+
+        if let Some(background_color) = self.state.background_color {
+            if self.is_transparent && background_color.a == 255 {
+                return Err(TransparentWithOpaqueBackground(background_color));
+            }
         }
+
+        Ok(())
+    }
+}
+
+fn is_valid_size(size: LogicalSize) -> bool {
+    size.width.is_finite() && size.height.is_finite() && size.width > 0.0 && size.height > 0.0
+}
+
+/// Returns the `WindowCreateOptions::window_shadow` if azul has to draw it (Linux only)
+fn get_window_shadow<T>(options: &WindowCreateOptions<T>) -> Option<WindowShadow> {
+    if cfg!(target_os = "linux") && !options.state.has_decorations {
+        options.window_shadow
+    } else {
+        None
+    }
+}
+
+/// Recalculates the margin of the client-side window shadow (see `WindowShadow`)
+/// and sets the input shape of the window, so that clicks on the shadow go
+/// to the windows below. Has to be called whenever the window is resized.
+#[allow(unused_variables)]
+fn update_shadow_margin<T>(state: &mut WindowState, options: &WindowCreateOptions<T>, window: &GliumWindow) {
+
+    let shadow = match options.window_shadow {
+        Some(shadow) => shadow,
+        None => return,
+    };
+
+    state.size.shadow_margin = get_shadow_margin(options, state);
+
+    #[cfg(target_os = "linux")] {
+        let physical_size = match window.get_inner_size() {
+            Some(size) => size.to_physical(window.get_hidpi_factor()),
+            None => return,
+        };
+        let hidpi_factor = state.size.hidpi_factor;
+        linux_set_input_shape(
+            window,
+            (physical_size.width.round() as i32, physical_size.height.round() as i32),
+            (state.size.shadow_margin * hidpi_factor).round() as i32,
+            (shadow.corner_radius as f64 * hidpi_factor).round() as i32,
+        );
+    }
+}
+
+/// Returns the margin around the UI that is reserved for the window shadow,
+/// maximized and fullscreen windows are drawn without a shadow
+fn get_shadow_margin<T>(options: &WindowCreateOptions<T>, state: &WindowState) -> f64 {
+    match get_window_shadow(options) {
+        Some(shadow) if !state.has_decorations && !state.is_maximized && !state.is_fullscreen => shadow.get_margin() as f64,
+        _ => 0.0,
+    }
+}
+
+/// Builder for `WindowCreateOptions`, created by `WindowCreateOptions::builder()`.
+/// Options that aren't set keep the value of `WindowCreateOptions::default()`.
+#[derive(Debug, Clone)]
+pub struct WindowCreateOptionsBuilder<T> {
+    options: WindowCreateOptions<T>,
+    /// `None` if `decorations()` hasn't been called - fullscreen
+    /// windows then automatically get created without decorations
+    has_decorations: Option<bool>,
+}
+
+impl<T> Default for WindowCreateOptionsBuilder<T> {
+    fn default() -> Self {
+        Self {
+            options: WindowCreateOptions::default(),
+            has_decorations: None,
+        }
+    }
+}
+
+impl<T> WindowCreateOptionsBuilder<T> {
+
+    /// Starts from an existing window state (i.e. a state that was saved when the
+    /// application was closed). Overwrites all previously set state options.
+    #[inline]
+    pub fn state(mut self, state: WindowState) -> Self {
+        self.has_decorations = Some(state.has_decorations);
+        self.options.state = state;
+        self
+    }
+
+    #[inline]
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.options.state.title = title.into();
+        self
+    }
+
+    /// Initial inner size of the window
+    #[inline]
+    pub fn size(mut self, size: LogicalSize) -> Self {
+        self.options.state.size.dimensions = size;
+        self
+    }
+
+    #[inline]
+    pub fn min_size(mut self, min_size: LogicalSize) -> Self {
+        self.options.state.size.min_dimensions = Some(min_size);
+        self
+    }
+
+    #[inline]
+    pub fn max_size(mut self, max_size: LogicalSize) -> Self {
+        self.options.state.size.max_dimensions = Some(max_size);
+        self
+    }
+
+    #[inline]
+    pub fn position(mut self, position: LogicalPosition) -> Self {
+        self.options.state.position = Some(position);
+        self
+    }
+
+    #[inline]
+    pub fn maximized(mut self, is_maximized: bool) -> Self {
+        self.options.state.is_maximized = is_maximized;
+        self
+    }
+
+    /// Creates the window fullscreen on the monitor it is created on. Unless
+    /// `decorations(true)` is set explicitly, the window has no decorations.
+    #[inline]
+    pub fn fullscreen(mut self, is_fullscreen: bool) -> Self {
+        self.options.state.is_fullscreen = is_fullscreen;
+        self
+    }
+
+    /// Whether the window has a title bar and borders
+    #[inline]
+    pub fn decorations(mut self, has_decorations: bool) -> Self {
+        self.has_decorations = Some(has_decorations);
+        self
+    }
+
+    #[inline]
+    pub fn visible(mut self, is_visible: bool) -> Self {
+        self.options.state.is_visible = is_visible;
+        self
+    }
+
+    #[inline]
+    pub fn always_on_top(mut self, is_always_on_top: bool) -> Self {
+        self.options.state.is_always_on_top = is_always_on_top;
+        self
+    }
+
+    #[inline]
+    pub fn resizable(mut self, is_resizable: bool) -> Self {
+        self.options.state.is_resizable = is_resizable;
+        self
+    }
+
+    /// See `WindowCreateOptions::wait_for_first_frame`
+    #[inline]
+    pub fn wait_for_first_frame(mut self, wait_for_first_frame: bool) -> Self {
+        self.options.wait_for_first_frame = wait_for_first_frame;
+        self
+    }
+
+    /// See `WindowCreateOptions::iframe_resize_preview`
+    #[inline]
+    pub fn iframe_resize_preview(mut self, iframe_resize_preview: Option<Duration>) -> Self {
+        self.options.iframe_resize_preview = iframe_resize_preview;
+        self
+    }
+
+    /// See `WindowCreateOptions::window_shadow`
+    #[inline]
+    pub fn window_shadow(mut self, window_shadow: WindowShadow) -> Self {
+        self.options.window_shadow = Some(window_shadow);
+        self
+    }
+
+    /// See `WindowCreateOptions::is_transparent`
+    #[inline]
+    pub fn transparent(mut self, is_transparent: bool) -> Self {
+        self.options.is_transparent = is_transparent;
+        self
+    }
+
+    /// Clear color of the window, overrides `AppConfig::background_color`
+    #[inline]
+    pub fn background_color(mut self, background_color: ColorU) -> Self {
+        self.options.state.background_color = Some(background_color);
+        self
+    }
+
+    #[inline]
+    pub fn monitor(mut self, monitor: WindowMonitorTarget) -> Self {
+        self.options.monitor = monitor;
+        self
+    }
+
+    #[inline]
+    pub fn renderer_type(mut self, renderer_type: RendererType) -> Self {
+        self.options.renderer_type = renderer_type;
+        self
+    }
+
+    #[inline]
+    pub fn window_icon(mut self, icon: Icon) -> Self {
+        self.options.window_icon = Some(icon);
+        self
+    }
+
+    #[inline]
+    pub fn taskbar_icon(mut self, icon: Icon) -> Self {
+        self.options.taskbar_icon = Some(icon);
+        self
+    }
+
+    /// Win32 menu callback for the menu item with the given `id`
+    #[inline]
+    pub fn menu_callback(mut self, id: u16, callback: Callback<T>) -> Self {
+        self.options.menu_callbacks.insert(id, callback);
+        self
+    }
+
+    /// See `WindowCreateOptions::layout_callback`
+    #[inline]
+    pub fn layout(mut self, layout_callback: LayoutCallbackType<T>) -> Self {
+        self.options.layout_callback = Some(LayoutCallback(layout_callback));
+        self
+    }
+
+    /// Returns the options or an error if any of the options conflict with each other
+    pub fn build(self) -> Result<WindowCreateOptions<T>, WindowCreateOptionsError> {
+        let mut options = self.options;
+        options.state.has_decorations = self.has_decorations.unwrap_or(!options.state.is_fullscreen);
+        options.validate()?;
+        Ok(options)
+    }
+}
+
+/// Conflicting or invalid `WindowCreateOptions`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WindowCreateOptionsError {
+    /// A window size is zero, negative or not a finite number
+    InvalidSize(LogicalSize),
+    /// The minimum size is larger than the maximum size
+    MinSizeLargerThanMaxSize { min: LogicalSize, max: LogicalSize },
+    /// The initial size is smaller than the minimum or larger than the maximum size
+    SizeOutOfBounds(LogicalSize),
+    /// Fullscreen windows can't have decorations
+    FullscreenWithDecorations,
+    /// A window can't be fullscreen and maximized at the same time
+    FullscreenAndMaximized,
+    /// The window is transparent, but the background color is fully opaque,
+    /// so nothing would ever show through the window
+    TransparentWithOpaqueBackground(ColorU),
+    /// A window shadow is only drawn for windows without decorations
+    WindowShadowWithDecorations,
+}
+
+impl_display! {
+    WindowCreateOptionsError,
+    {
+        InvalidSize(size) => format!("Invalid window size {}x{}: width and height have to be positive", size.width, size.height),
+        MinSizeLargerThanMaxSize { min, max } => format!("Minimum window size {}x{} is larger than the maximum size {}x{}", min.width, min.height, max.width, max.height),
+        SizeOutOfBounds(size) => format!("Window size {}x{} is outside of the minimum / maximum window size", size.width, size.height),
+        FullscreenWithDecorations => "Fullscreen windows can't have decorations, use `decorations(false)`",
+        FullscreenAndMaximized => "A window can't be both fullscreen and maximized",
+        TransparentWithOpaqueBackground(color) => format!("Transparent window has an opaque background color (rgb({}, {}, {})), use a background color with an alpha below 255", color.r, color.g, color.b),
+        WindowShadowWithDecorations => "A window shadow can only be drawn for windows without decorations, use `decorations(false)`",
     }
 }
 
@@ -222,13 +797,19 @@ impl<T> Default for WindowCreateOptions<T> {
 /// However, in some cases a hardware renderer might create problems
 /// or you want to force either a software or hardware renderer.
 ///
-/// If the field `renderer_type` on the `WindowCreateOptions` is not
-/// `RendererType::Default`, the `create_window` method will try to create
-/// a window with the specific renderer type and **crash** if the renderer is
-/// not available for whatever reason.
+/// If the field `renderer_type` on the `AppConfig` is not `RendererType::Default`,
+/// `App::new` will try to create a renderer with the specific renderer type and
+/// return an error if the renderer is not available for whatever reason.
+///
+/// The software renderer uses the software rasterizer of the systems OpenGL
+/// driver (i.e. llvmpipe via Mesa on Linux). On Windows, a software OpenGL
+/// implementation (SwiftShader or Mesa) has to be shipped as an `opengl32.dll`
+/// next to the executable, the builtin GDI renderer only supports OpenGL 1.1.
+///
+/// The renderer that is actually in use can be queried via `DebugState::active_renderer`.
 ///
 /// If you don't know what any of this means, leave it at `Default`.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum RendererType {
     Default,
     Hardware,
@@ -260,6 +841,8 @@ pub enum WindowCreateError {
     Io(::std::io::Error),
     /// WebRender creation error (probably OpenGL missing?)
     Renderer/*(RendererError)*/,
+    /// The `WindowCreateOptions` conflict with each other
+    InvalidOptions(WindowCreateOptionsError),
 }
 
 impl_display! {
@@ -273,6 +856,7 @@ impl_display! {
         Io(e) => format!("{}", e),
         WebGlNotSupported => "WebGl is not supported by WebRender",
         Renderer => "Webrender creation error (probably OpenGL missing?)",
+        InvalidOptions(e) => format!("Invalid window options: {}", e),
     }
 }
 
@@ -282,6 +866,7 @@ impl_from!(IoError, WindowCreateError::Io);
 impl_from!(IncompatibleOpenGl, WindowCreateError::Gl);
 impl_from!(DisplayCreationError, WindowCreateError::DisplayCreateError);
 impl_from!(ContextError, WindowCreateError::Context);
+impl_from!(WindowCreateOptionsError, WindowCreateError::InvalidOptions);
 
 struct Notifier { }
 
@@ -358,6 +943,40 @@ pub struct Window<T> {
     pub(crate) internal: WindowInternal,
     /// States of scrolling animations, updated every frame
     pub(crate) scroll_states: ScrollStates,
+    /// Texture (in the shared OpenGL context) that contains the last rendered frame.
+    /// While the window is being resized, this frame is stretched over the window,
+    /// so that the new area doesn't flash white until the next frame is rendered.
+    pub(crate) last_frame_texture: Option<GLuint>,
+    /// Minimum size that the DOM needed in the last frame (in logical window units), only set
+    /// if `WindowSize::min_dimensions_from_layout` is enabled. Combined with the
+    /// `WindowSize::min_dimensions` to get the minimum size that is set on the OS window.
+    pub(crate) layout_min_dimensions: Option<LogicalSize>,
+    /// Bounds of the node that the cursor is confined to (in layout units), taken from
+    /// the last layout. Only set if the `WindowState::cursor_confinement` is a `Node`.
+    pub(crate) cursor_confinement_rect: Option<LayoutRect>,
+    /// Size and position of the window as they were last handed to the callbacks (via the
+    /// `FakeWindow`). The OS can resize or move the window at any time, so a size or position
+    /// that differs from `self.state` isn't necessarily a change by the user - only a value
+    /// that differs from the one the user has last seen is applied to the platform window.
+    pub(crate) last_user_dimensions: LogicalSize,
+    pub(crate) last_user_position: Option<LogicalPosition>,
+    /// The `AppConfig::background_color`, used if the `WindowState::background_color` isn't set.
+    /// Windows with a `WindowShadow` draw their background in the display list, since the
+    /// framebuffer has to be cleared with a transparent color for the shadow.
+    pub(crate) default_background_color: ColorU,
+    /// Result of the last layout, so that the display list can be rebuilt without
+    /// re-layouting the DOM if only the paint property overrides changed
+    pub(crate) last_layout_result: Option<LayoutResult>,
+    /// Rectangles of all keyed nodes in the last layout, used to detect which nodes
+    /// moved if the `AppConfig::layout_transition` is enabled
+    pub(crate) last_keyed_rects: KeyedRects,
+    /// Nodes that were removed from the DOM, but are still playing their exit animation
+    pub(crate) exiting_nodes: Vec<RetainedNode<T>>,
+    /// When the window was created, so that `WindowCreateOptions::wait_for_first_frame`
+    /// can't keep the window hidden forever
+    pub(crate) created: Instant,
+    /// When the window was last resized, see `WindowCreateOptions::iframe_resize_preview`
+    pub(crate) last_resize: Option<Instant>,
     // The background thread that is running for this window.
     // pub(crate) background_thread: Option<JoinHandle<()>>,
     /// The style applied to the current window
@@ -366,6 +985,13 @@ pub struct Window<T> {
     /// enabled
     #[cfg(debug_assertions)]
     pub(crate) css_loader: Option<Box<dyn HotReloadHandler>>,
+    /// Error of the last hot-reload of the style, displayed on top of the window until the
+    /// style is fixed. Only available with debug_assertions enabled
+    #[cfg(debug_assertions)]
+    pub(crate) css_reload_error: Option<String>,
+    /// Result of the last keyboard audit, drawn on top of the window
+    /// (only if `DebugState::keyboard_audit` is enabled)
+    pub(crate) keyboard_audit: Option<KeyboardAudit>,
     /// Purely a marker, so that `app.run()` can infer the type of `T: Layout`
     /// of the `WindowCreateOptions`, so that we can write:
     ///
@@ -400,16 +1026,87 @@ impl ScrollStates {
     pub(crate) fn scroll_node(&mut self, scroll_id: &ExternalScrollId, scroll_by_x: f32, scroll_by_y: f32) {
         if let Some(entry) = self.0.get_mut(scroll_id) {
             entry.add(scroll_by_x, scroll_by_y);
+            // Scrolling interrupts a running snap animation
+            entry.snap_target = None;
+        }
+    }
+
+    /// Marks a node with a `scroll-snap-type` as scrolled by the user, it
+    /// snaps once the user hasn't scrolled it for `SCROLL_SNAP_DELAY`
+    pub(crate) fn defer_snap(&mut self, scroll_id: &ExternalScrollId) {
+        if let Some(entry) = self.0.get_mut(scroll_id) {
+            entry.last_scrolled = Some(Instant::now());
         }
     }
 
+    /// Whether a node is waiting to snap or currently animating to a snap position,
+    /// in which case the scroll states have to be updated every frame
+    pub(crate) fn has_pending_snaps(&self) -> bool {
+        self.0.values().any(|state| state.last_scrolled.is_some() || state.snap_target.is_some())
+    }
+
     pub(crate) fn ensure_initialized_scroll_state(&mut self, scroll_id: ExternalScrollId, overflow_x: f32, overflow_y: f32) {
         self.0.entry(scroll_id).or_insert_with(|| ScrollState::new(overflow_x, overflow_y));
     }
 
+    /// Called for every scroll frame in the display list: marks the scroll state as used and
+    /// updates how far the node can be scrolled (the content of the node might have changed)
+    pub(crate) fn update_scroll_frame(&mut self, scroll_id: ExternalScrollId, overflow_x: f32, overflow_y: f32) {
+        let entry = self.0.entry(scroll_id).or_insert_with(|| ScrollState::new(overflow_x, overflow_y));
+        entry.overflow_x = overflow_x;
+        entry.overflow_y = overflow_y;
+        entry.used_this_frame = true;
+        // Clamp the current scroll amount to the new overflow
+        entry.add(0.0, 0.0);
+    }
+
+    /// Scrolls the node the minimum amount necessary so that the `target` rectangle
+    /// (relative to the unscrolled origin of the scroll frame) is fully visible.
+    pub(crate) fn scroll_into_view(
+        &mut self,
+        scroll_id: ExternalScrollId,
+        overflow_x: f32,
+        overflow_y: f32,
+        frame_width: f32,
+        frame_height: f32,
+        target: LayoutRect,
+    ) {
+        fn scroll_axis(current: f32, frame_size: f32, target_start: f32, target_end: f32) -> f32 {
+            if target_start < current {
+                target_start
+            } else if target_end > current + frame_size {
+                target_end - frame_size
+            } else {
+                current
+            }
+        }
+
+        self.ensure_initialized_scroll_state(scroll_id, overflow_x, overflow_y);
+
+        if let Some(entry) = self.0.get_mut(&scroll_id) {
+            let (current_x, current_y) = entry.get();
+            let new_x = scroll_axis(current_x, frame_width, target.origin.x, target.max_x());
+            let new_y = scroll_axis(current_y, frame_height, target.origin.y, target.max_y());
+            entry.add(new_x - current_x, new_y - current_y);
+        }
+    }
+
+    /// Called for every scrollbar in the display list: sets how far the thumbs can move
+    /// inside of the tracks, so that they can be moved while scrolling (without
+    /// rebuilding the display list), see `ScrollState::get_thumb_offset`
+    pub(crate) fn set_thumb_travel(&mut self, scroll_id: ExternalScrollId, thumb_travel_x: f32, thumb_travel_y: f32) {
+        if let Some(entry) = self.0.get_mut(&scroll_id) {
+            entry.thumb_travel_x = thumb_travel_x;
+            entry.thumb_travel_y = thumb_travel_y;
+        }
+    }
+
     /// Removes all scroll states that weren't used in the last frame
     pub(crate) fn remove_unused_scroll_states(&mut self) {
         self.0.retain(|_, state| state.used_this_frame);
+        for state in self.0.values_mut() {
+            state.used_this_frame = false;
+        }
     }
 }
 
@@ -420,10 +1117,21 @@ pub struct ScrollState {
     scroll_amount_y: f32,
     overflow_x: f32,
     overflow_y: f32,
+    /// How far the thumb of the horizontal / vertical scrollbar can move inside of
+    /// the track (the length of the track minus the length of the thumb)
+    thumb_travel_x: f32,
+    thumb_travel_y: f32,
+    /// When the user last scrolled the node, only set for nodes with a `scroll-snap-type`
+    last_scrolled: Option<Instant>,
+    /// Scroll amount that the node is currently animating to
+    snap_target: Option<(f32, f32)>,
     /// Was the scroll amount used in this frame?
     used_this_frame: bool,
 }
 
+/// How long the user has to stop scrolling before a node with a `scroll-snap-type` snaps
+pub(crate) const SCROLL_SNAP_DELAY: Duration = Duration::from_millis(150);
+
 impl ScrollState {
 
     fn new(overflow_x: f32, overflow_y: f32) -> Self {
@@ -432,6 +1140,10 @@ impl ScrollState {
             scroll_amount_y: 0.0,
             overflow_x,
             overflow_y,
+            thumb_travel_x: 0.0,
+            thumb_travel_y: 0.0,
+            last_scrolled: None,
+            snap_target: None,
             used_this_frame: true,
         }
     }
@@ -441,10 +1153,67 @@ impl ScrollState {
         (self.scroll_amount_x, self.scroll_amount_y)
     }
 
+    /// Returns the scroll amount without marking the scroll state as used
+    pub(crate) fn scroll_amount(&self) -> (f32, f32) {
+        (self.scroll_amount_x, self.scroll_amount_y)
+    }
+
+    /// Returns how far the thumbs of the horizontal and vertical scrollbar
+    /// are moved from the start of their tracks for the current scroll amount
+    pub(crate) fn get_thumb_offset(&self) -> (f32, f32) {
+        fn thumb_offset(scroll_amount: f32, overflow: f32, thumb_travel: f32) -> f32 {
+            if overflow <= 0.0 { 0.0 } else { scroll_amount / overflow * thumb_travel }
+        }
+        (
+            thumb_offset(self.scroll_amount_x, self.overflow_x, self.thumb_travel_x),
+            thumb_offset(self.scroll_amount_y, self.overflow_y, self.thumb_travel_y),
+        )
+    }
+
     pub fn add(&mut self, x: f32, y: f32) {
         self.scroll_amount_x = self.overflow_x.min(self.scroll_amount_x + x).max(0.0);
         self.scroll_amount_y = self.overflow_y.min(self.scroll_amount_y + y).max(0.0);
     }
+
+    /// Returns true (once) if the user scrolled the node, but hasn't scrolled it for `delay`
+    pub(crate) fn take_finished_scroll(&mut self, delay: Duration) -> bool {
+        match self.last_scrolled {
+            Some(last_scrolled) if last_scrolled.elapsed() >= delay => {
+                self.last_scrolled = None;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    pub(crate) fn set_snap_target(&mut self, snap_target: Option<(f32, f32)>) {
+        self.snap_target = snap_target;
+    }
+
+    /// Moves the scroll amount a step closer to the snap target,
+    /// returns whether the scroll amount changed
+    pub(crate) fn step_snap_animation(&mut self) -> bool {
+
+        // Fraction of the remaining distance that is scrolled every frame
+        const SNAP_STEP: f32 = 0.3;
+        const SNAP_THRESHOLD: f32 = 0.5; // px
+
+        let (target_x, target_y) = match self.snap_target {
+            Some(s) => s,
+            None => return false,
+        };
+
+        let (distance_x, distance_y) = (target_x - self.scroll_amount_x, target_y - self.scroll_amount_y);
+
+        if distance_x.abs() < SNAP_THRESHOLD && distance_y.abs() < SNAP_THRESHOLD {
+            self.add(distance_x, distance_y);
+            self.snap_target = None;
+        } else {
+            self.add(distance_x * SNAP_STEP, distance_y * SNAP_STEP);
+        }
+
+        true
+    }
 }
 
 impl Default for ScrollState {
@@ -454,6 +1223,10 @@ impl Default for ScrollState {
             scroll_amount_y: 0.0,
             overflow_x: 0.0,
             overflow_y: 0.0,
+            thumb_travel_x: 0.0,
+            thumb_travel_y: 0.0,
+            last_scrolled: None,
+            snap_target: None,
             used_this_frame: true,
         }
     }
@@ -480,6 +1253,7 @@ impl<'a, T> Window<T> {
         options: WindowCreateOptions<T>,
         mut css: Css,
         background_color: ColorU,
+        renderer_type: RendererType,
     ) -> Result<Self, WindowCreateError> {
 
         // NOTE: It would be OK to use &RenderApi here, but it's better
@@ -489,21 +1263,21 @@ impl<'a, T> Window<T> {
         // Report this to the winit developers.
         // let events_loop = EventsLoop::new();
 
-        let is_transparent_background = background_color.a != 0;
+        let default_background_color = background_color;
+        let background_color = options.state.background_color.unwrap_or(background_color);
+        // The window shadow is drawn into a transparent margin around the UI
+        let is_transparent_background = options.is_transparent || background_color.a != 255 || get_window_shadow(&options).is_some();
 
         let mut window = GliumWindowBuilder::new()
             .with_title(options.state.title.clone())
             .with_maximized(options.state.is_maximized)
             .with_decorations(options.state.has_decorations)
+            .with_resizable(options.state.is_resizable)
+            .with_always_on_top(options.state.is_always_on_top)
             .with_visibility(false)
             .with_transparency(is_transparent_background)
             .with_multitouch();
 
-        // TODO: Update winit to have:
-        //      .with_always_on_top(options.state.is_always_on_top)
-        //
-        // winit 0.13 -> winit 0.15
-
         // TODO: Add all the extensions for X11 / Mac / Windows,
         // like setting the taskbar icon, setting the titlebar icon, etc.
 
@@ -533,7 +1307,8 @@ impl<'a, T> Window<T> {
         }
 
         // Only create a context with VSync and SRGB if the context creation works
-        let gl_window = create_gl_window(window, &events_loop, Some(shared_context))?;
+        // The context has to be compatible with the (shared) context of the renderer
+        let gl_window = create_gl_window(window, &events_loop, Some(shared_context), renderer_type)?;
 
         // Hide the window until the first draw (prevents flash on startup)
         gl_window.hide();
@@ -589,7 +1364,10 @@ impl<'a, T> Window<T> {
 
         let last_scrolled_nodes = ScrolledNodes::default();
 
-        let window = Window {
+        let last_user_dimensions = state.size.dimensions;
+        let last_user_position = state.position;
+
+        let mut window = Window {
             id: window_id,
             create_options: options,
             state: state,
@@ -597,11 +1375,27 @@ impl<'a, T> Window<T> {
             css,
             #[cfg(debug_assertions)]
             css_loader: None,
+            #[cfg(debug_assertions)]
+            css_reload_error: None,
+            keyboard_audit: None,
             scroll_states: ScrollStates::new(),
+            last_frame_texture: None,
+            layout_min_dimensions: None,
+            cursor_confinement_rect: None,
+            last_user_dimensions,
+            last_user_position,
+            default_background_color,
+            last_layout_result: None,
+            last_keyed_rects: KeyedRects::default(),
+            exiting_nodes: Vec::new(),
+            created: Instant::now(),
+            last_resize: None,
             internal: WindowInternal { epoch, pipeline_id, document_id, last_scrolled_nodes },
             marker: PhantomData,
         };
 
+        update_shadow_margin(&mut window.state, &window.create_options, window.display.gl_window().window());
+
         Ok(window)
     }
 
@@ -615,12 +1409,74 @@ impl<'a, T> Window<T> {
         options: WindowCreateOptions<T>,
         css_loader: Box<dyn HotReloadHandler>,
         background_color: ColorU,
+        renderer_type: RendererType,
     ) -> Result<Self, WindowCreateError>  {
-        let mut window = Window::new(render_api, shared_context, events_loop, options, Css::default(), background_color)?;
+        let mut window = Window::new(render_api, shared_context, events_loop, options, Css::default(), background_color, renderer_type)?;
         window.css_loader = Some(css_loader);
         Ok(window)
     }
 
+    /// Re-creates the platform window and its OpenGL context after the OpenGL context was
+    /// lost, keeping the current window state, the CSS and the scroll positions.
+    ///
+    /// Note that the re-created window has a new window ID.
+    pub(crate) fn recreate_after_context_loss(
+        self,
+        render_api: &mut RenderApi,
+        shared_context: &Context,
+        events_loop: &EventsLoop,
+        background_color: ColorU,
+        renderer_type: RendererType,
+    ) -> Result<Self, WindowCreateError> {
+
+        let options = WindowCreateOptions {
+            state: self.state.clone(),
+            is_transparent: self.create_options.is_transparent,
+            monitor: self.create_options.monitor,
+            renderer_type: self.create_options.renderer_type,
+            menu_callbacks: self.create_options.menu_callbacks,
+            layout_callback: self.create_options.layout_callback,
+            window_icon: self.create_options.window_icon,
+            taskbar_icon: self.create_options.taskbar_icon,
+            wait_for_first_frame: self.create_options.wait_for_first_frame,
+            iframe_resize_preview: self.create_options.iframe_resize_preview,
+            window_shadow: self.create_options.window_shadow,
+        };
+
+        let mut window = Window::new(render_api, shared_context, events_loop, options, self.css, background_color, renderer_type)?;
+        window.scroll_states = self.scroll_states;
+
+        #[cfg(debug_assertions)] {
+            window.css_loader = self.css_loader;
+            window.css_reload_error = self.css_reload_error;
+        }
+
+        Ok(window)
+    }
+
+    /// Whether the window hasn't been shown yet, because WebRender hasn't
+    /// rendered its first frame (see `WindowCreateOptions::wait_for_first_frame`)
+    pub(crate) fn is_waiting_for_first_frame(&self) -> bool {
+        self.create_options.wait_for_first_frame &&
+        self.create_options.state.is_visible &&
+        self.state.is_visible
+    }
+
+    /// Whether IFrames should reuse their last content instead of invoking
+    /// their callbacks (see `WindowCreateOptions::iframe_resize_preview`)
+    pub(crate) fn is_iframe_resize_preview_active(&self) -> bool {
+        match (self.create_options.iframe_resize_preview, self.last_resize) {
+            (Some(delay), Some(last_resize)) => last_resize.elapsed() < delay,
+            _ => false,
+        }
+    }
+
+    /// Returns the `WindowCreateOptions::layout_callback` or the `default` (`Layout::layout`)
+    /// if the window doesn't have its own layout callback
+    pub(crate) fn get_layout_callback(&self, default: LayoutCallbackType<T>) -> LayoutCallbackType<T> {
+        self.create_options.layout_callback.map(|callback| callback.0).unwrap_or(default)
+    }
+
     /// Returns an iterator over all given monitors
     pub fn get_available_monitors() -> MonitorIter {
         MonitorIter {
@@ -636,11 +1492,16 @@ impl<'a, T> Window<T> {
     /// Updates the window state, diff the `self.state` with the `new_state`
     /// and updating the platform window to reflect the changes
     ///
-    /// Note: Currently, setting `mouse_state.position`, `window.size` or
-    /// `window.position` has no effect on the platform window, since they are very
-    /// frequently modified by the user (other properties are always set by the
-    /// application developer)
-    pub(crate) fn update_from_user_window_state(&mut self, new_state: WindowState) {
+    /// All changes that the callbacks made to the `FakeWindow` state since the last frame
+    /// are applied at once, before the next frame is laid out. Afterwards, the size and
+    /// position of the window (which can also be changed by the OS) are written back into
+    /// the `new_state`, so that the callbacks always see the current values.
+    #[allow(unused_variables)]
+    pub(crate) fn update_from_user_window_state(&mut self, new_state: &mut WindowState) {
+
+        // Maximized and fullscreen windows don't have a shadow, so the margin depends on the
+        // new maximized / fullscreen / decorations flags, not on the ones of the last frame
+        let shadow_margin_changed = self.state.size.shadow_margin != get_shadow_margin(&self.create_options, new_state);
 
         let gl_window = self.display.gl_window();
         let window = gl_window.window();
@@ -650,7 +1511,31 @@ impl<'a, T> Window<T> {
 
         if old_state.title != new_state.title {
             window.set_title(&new_state.title);
-            old_state.title = new_state.title;
+            old_state.title = new_state.title.clone();
+        }
+
+        // Only apply the size / position if the user changed it, otherwise
+        // a resize or move by the OS would be reverted
+        if new_state.size.dimensions != self.last_user_dimensions {
+            old_state.size.dimensions = new_state.size.dimensions;
+            window.set_inner_size(old_state.size.get_inner_logical_size());
+        }
+
+        if new_state.position != self.last_user_position {
+            if let Some(position) = new_state.position {
+                window.set_position(position);
+            }
+            old_state.position = new_state.position;
+        }
+
+        if old_state.is_resizable != new_state.is_resizable {
+            window.set_resizable(new_state.is_resizable);
+            old_state.is_resizable = new_state.is_resizable;
+        }
+
+        if old_state.is_always_on_top != new_state.is_always_on_top {
+            window.set_always_on_top(new_state.is_always_on_top);
+            old_state.is_always_on_top = new_state.is_always_on_top;
         }
 
         if old_state.internal.mouse_state.mouse_cursor_type != new_state.internal.mouse_state.mouse_cursor_type {
@@ -686,18 +1571,108 @@ impl<'a, T> Window<T> {
             old_state.is_visible = new_state.is_visible;
         }
 
-        if old_state.size.min_dimensions != new_state.size.min_dimensions {
-            window.set_min_dimensions(new_state.size.min_dimensions.map(Into::into));
+        if old_state.size.min_dimensions != new_state.size.min_dimensions ||
+           old_state.size.min_dimensions_from_layout != new_state.size.min_dimensions_from_layout {
+            if !new_state.size.min_dimensions_from_layout {
+                self.layout_min_dimensions = None;
+            }
+            window.set_min_dimensions(combine_min_dimensions(new_state.size.min_dimensions, self.layout_min_dimensions));
             old_state.size.min_dimensions = new_state.size.min_dimensions;
+            old_state.size.min_dimensions_from_layout = new_state.size.min_dimensions_from_layout;
         }
 
         if old_state.size.max_dimensions != new_state.size.max_dimensions {
             window.set_max_dimensions(new_state.size.max_dimensions.map(Into::into));
             old_state.size.max_dimensions = new_state.size.max_dimensions;
         }
+
+        if old_state.size.scale_to_fit != new_state.size.scale_to_fit {
+            // Only affects the layout / display list, no need to tell the OS about it
+            old_state.size.scale_to_fit = new_state.size.scale_to_fit;
+        }
+
+        // Only affects the display list and the rendering
+        old_state.size.hidpi_rounding = new_state.size.hidpi_rounding;
+
+        // The background is drawn by azul itself, not by the OS
+        old_state.background_color = new_state.background_color;
+        old_state.background_image = new_state.background_image;
+
+        if let Some(cursor_pos) = new_state.internal.pending_cursor_position {
+            set_cursor_position(window, &old_state.size, cursor_pos);
+            old_state.internal.mouse_state.cursor_pos = Some(cursor_pos);
+        }
+
+        if old_state.cursor_confinement != new_state.cursor_confinement {
+            // On X11 and Windows, grabbing the cursor also confines it to the window
+            let should_grab = new_state.cursor_confinement != CursorConfinement::None;
+            if let Err(e) = window.grab_cursor(should_grab) {
+                #[cfg(feature = "logging")] {
+                    warn!("Could not set the cursor confinement: {}", e);
+                }
+            }
+            old_state.cursor_confinement = new_state.cursor_confinement;
+            self.cursor_confinement_rect = None;
+        }
+
+        // Clamp the cursor to the bounds of the confining node
+        if let (Some(rect), Some(cursor_pos)) = (self.cursor_confinement_rect, old_state.internal.mouse_state.cursor_pos) {
+            let clamped_pos = LogicalPosition::new(
+                cursor_pos.x.max(rect.min_x() as f64).min(rect.max_x() as f64),
+                cursor_pos.y.max(rect.min_y() as f64).min(rect.max_y() as f64),
+            );
+            if clamped_pos != cursor_pos {
+                set_cursor_position(window, &old_state.size, clamped_pos);
+                old_state.internal.mouse_state.cursor_pos = Some(clamped_pos);
+            }
+        }
+
+        new_state.size.dimensions = old_state.size.dimensions;
+        new_state.size.hidpi_factor = old_state.size.hidpi_factor;
+        new_state.size.winit_hidpi_factor = old_state.size.winit_hidpi_factor;
+        new_state.position = old_state.position;
+        self.last_user_dimensions = old_state.size.dimensions;
+        self.last_user_position = old_state.position;
+
+        if shadow_margin_changed {
+            update_shadow_margin(old_state, &self.create_options, window);
+        }
+        new_state.size.shadow_margin = old_state.size.shadow_margin;
+    }
+
+    /// Updates the bounds of the node that the cursor is confined to
+    /// from the rectangles of the last layout
+    pub(crate) fn update_cursor_confinement_rect(&mut self, rects: &NodeDataContainer<PositionedRectangle>) {
+        self.cursor_confinement_rect = match self.state.cursor_confinement {
+            CursorConfinement::Node(node_id) => rects.get(node_id).map(|rect| rect.bounds),
+            _ => None,
+        };
+    }
+
+    /// Updates the minimum size of the OS window from the minimum size of the current layout,
+    /// if `WindowSize::min_dimensions_from_layout` is enabled. The OS window is only updated
+    /// if the minimum size changed since the last frame.
+    pub(crate) fn update_min_dimensions_from_layout(&mut self, min_content_size: Option<LayoutSize>) {
+
+        let min_content_size = match min_content_size {
+            Some(s) if self.state.size.min_dimensions_from_layout && self.state.size.scale_to_fit.is_none() => s,
+            _ => return,
+        };
+
+        let window_size = self.state.size.layout_size_to_window_size(LogicalSize::new(
+            min_content_size.width.ceil() as f64,
+            min_content_size.height.ceil() as f64,
+        ));
+
+        if self.layout_min_dimensions == Some(window_size) {
+            return;
+        }
+
+        self.layout_min_dimensions = Some(window_size);
+        let min_dimensions = combine_min_dimensions(self.state.size.min_dimensions, self.layout_min_dimensions);
+        self.display.gl_window().window().set_min_dimensions(min_dimensions);
     }
 
-    #[allow(unused_variables)]
     pub(crate) fn update_from_external_window_state(
         &mut self,
         frame_event_info: &mut FrameEventInfo,
@@ -715,6 +1690,11 @@ impl<'a, T> Window<T> {
 
         if let Some(new_size) = frame_event_info.new_window_size {
             self.state.size.dimensions = new_size;
+            update_shadow_margin(&mut self.state, &self.create_options, self.display.gl_window().window());
+        }
+
+        if let Some(new_position) = frame_event_info.new_window_position {
+            self.state.position = Some(new_position);
         }
 
         if let Some(dpi) = frame_event_info.new_dpi_factor {
@@ -751,36 +1731,125 @@ pub(crate) struct FakeDisplay {
     /// TODO: Not sure if we even need this, the events loop isn't important
     /// for a window that is never shown
     pub(crate) hidden_events_loop: EventsLoop,
+    /// The renderer that was actually created (never `RendererType::Default`)
+    pub(crate) active_renderer: RendererType,
 }
 
 impl FakeDisplay {
 
-    /// Creates a new render + a new display, given a renderer type (software or hardware)
+    /// Creates a new render + a new display, given a renderer type (software or hardware).
+    ///
+    /// With `RendererType::Default`, a hardware renderer is tried first, falling back
+    /// to a software renderer if the hardware context or the renderer can't be created.
     pub(crate) fn new(renderer_type: RendererType)
     -> Result<Self, WindowCreateError>
     {
-        let events_loop = EventsLoop::new();
-        let window = GliumWindowBuilder::new().with_dimensions(LogicalSize::new(10.0, 10.0)).with_visibility(false);
-        let gl_window = create_gl_window(window, &events_loop, None)?;
-        let (dpi_factor, _) = get_hidpi_factor(&gl_window.window(), &events_loop);
-        gl_window.hide();
-
-        let display = Display::with_debug(gl_window, DebugCallbackBehavior::Ignore)?;
-        let gl = get_gl_context(&display)?;
+        use self::RendererType::*;
 
-        // Note: Notifier is fairly useless, since rendering is completely single-threaded, see comments on RenderNotifier impl
-        let notifier = Box::new(Notifier { });
-        let (mut renderer, render_api) = create_renderer(gl.clone(), notifier, renderer_type, dpi_factor)?;
+        let events_loop = EventsLoop::new();
 
-        renderer.set_external_image_handler(Box::new(Compositor::default()));
+        let (display, renderer, render_api, active_renderer) = match renderer_type {
+            Hardware | Software => create_hidden_display(&events_loop, renderer_type)?,
+            Default => match create_hidden_display(&events_loop, Hardware) {
+                Ok(o) => o,
+                Err(e) => {
+                    #[cfg(feature = "logging")] {
+                        warn!("Could not create hardware renderer, falling back to software renderer: {}", e);
+                    }
+                    create_hidden_display(&events_loop, Software)?
+                }
+            },
+        };
 
         Ok(Self {
             render_api,
             renderer: Some(renderer),
             hidden_display: display,
             hidden_events_loop: events_loop,
+            active_renderer,
         })
     }
+
+    /// Re-creates the hidden display + the renderer after the OpenGL context was lost.
+    /// The events loop is kept, since all windows are registered on it.
+    pub(crate) fn recreate_after_context_loss(&mut self) -> Result<(), WindowCreateError> {
+
+        // All OpenGL objects died with the context, so the renderer
+        // can't be de-initialized - deinit() would call into the dead context
+        if let Some(renderer) = self.renderer.take() {
+            mem::forget(renderer);
+        }
+
+        let (display, renderer, render_api, active_renderer) = create_hidden_display(&self.hidden_events_loop, self.active_renderer)?;
+
+        self.hidden_display = display;
+        self.renderer = Some(renderer);
+        self.render_api = render_api;
+        self.active_renderer = active_renderer;
+
+        Ok(())
+    }
+}
+
+/// Creates the hidden display + the renderer for either `RendererType::Hardware`
+/// or `RendererType::Software`, returns which renderer is actually active.
+fn create_hidden_display(events_loop: &EventsLoop, renderer_type: RendererType)
+-> Result<(Display, Renderer, RenderApi, RendererType), WindowCreateError>
+{
+    if renderer_type == RendererType::Software {
+        select_software_gl_driver();
+    }
+
+    let window = GliumWindowBuilder::new().with_dimensions(LogicalSize::new(10.0, 10.0)).with_visibility(false);
+    let gl_window = create_gl_window(window, events_loop, None, renderer_type)?;
+    let (dpi_factor, _) = get_hidpi_factor(&gl_window.window(), events_loop);
+    gl_window.hide();
+
+    let display = Display::with_debug(gl_window, DebugCallbackBehavior::Ignore)?;
+    let gl = get_gl_context(&display)?;
+
+    // Verify what kind of driver we actually got - the driver might hand out a
+    // software rasterizer even if a hardware-accelerated context was requested
+    let gl_renderer_name = gl.get_string(gl::RENDERER);
+    let active_renderer = if is_software_gl_renderer(&gl_renderer_name) {
+        RendererType::Software
+    } else {
+        renderer_type
+    };
+
+    #[cfg(feature = "logging")] {
+        if active_renderer == RendererType::Software {
+            warn!("Using software renderer, OpenGL driver: \"{}\"", gl_renderer_name);
+        }
+    }
+
+    // Note: Notifier is fairly useless, since rendering is completely single-threaded, see comments on RenderNotifier impl
+    let notifier = Box::new(Notifier { });
+    let (mut renderer, render_api) = create_renderer(gl.clone(), notifier, active_renderer, dpi_factor)?;
+
+    renderer.set_external_image_handler(Box::new(Compositor::default()));
+
+    Ok((display, renderer, render_api, active_renderer))
+}
+
+/// Makes the OpenGL driver select its software rasterizer for all
+/// OpenGL contexts that are created after this function is called
+fn select_software_gl_driver() {
+    // Mesa: use llvmpipe (or softpipe) instead of the hardware driver
+    #[cfg(target_os = "linux")] {
+        ::std::env::set_var("LIBGL_ALWAYS_SOFTWARE", "1");
+    }
+    // Mesa for Windows (if shipped as opengl32.dll): prefer llvmpipe over softpipe
+    #[cfg(target_os = "windows")] {
+        ::std::env::set_var("GALLIUM_DRIVER", "llvmpipe");
+    }
+}
+
+/// Returns whether the `GL_RENDERER` string belongs to a known software rasterizer
+fn is_software_gl_renderer(gl_renderer_name: &str) -> bool {
+    const SOFTWARE_RENDERERS: [&str;5] = ["llvmpipe", "softpipe", "swiftshader", "software rasterizer", "gdi generic"];
+    let gl_renderer_name = gl_renderer_name.to_lowercase();
+    SOFTWARE_RENDERERS.iter().any(|name| gl_renderer_name.contains(name))
 }
 
 impl Drop for FakeDisplay {
@@ -818,6 +1887,28 @@ impl Drop for FakeDisplay {
     }
 }
 
+/// Moves the OS cursor to a position in layout units (the same units as `MouseState::cursor_pos`)
+#[allow(unused_variables)]
+fn set_cursor_position(window: &GliumWindow, size: &WindowSize, position: LogicalPosition) {
+    let os_position = LogicalPosition::new(
+        position.x * size.hidpi_factor / size.winit_hidpi_factor,
+        position.y * size.hidpi_factor / size.winit_hidpi_factor,
+    );
+    if let Err(e) = window.set_cursor_position(os_position) {
+        #[cfg(feature = "logging")] {
+            warn!("Could not set the cursor position: {}", e);
+        }
+    }
+}
+
+/// Returns the larger of the explicit minimum window size and the minimum size that the layout needs
+fn combine_min_dimensions(min_dimensions: Option<LogicalSize>, layout_min_dimensions: Option<LogicalSize>) -> Option<LogicalSize> {
+    match (min_dimensions, layout_min_dimensions) {
+        (Some(a), Some(b)) => Some(LogicalSize::new(a.width.max(b.width), a.height.max(b.height))),
+        (a, b) => a.or(b),
+    }
+}
+
 /// Returns the actual hidpi factor and the winit DPI factor for the current window
 #[allow(unused_variables)]
 fn get_hidpi_factor(window: &GliumWindow, events_loop: &EventsLoop) -> (f64, f64) {
@@ -833,19 +1924,25 @@ fn get_hidpi_factor(window: &GliumWindow, events_loop: &EventsLoop) -> (f64, f64
 }
 
 
-fn create_gl_window(window: GliumWindowBuilder, events_loop: &EventsLoop, shared_context: Option<&Context>)
+fn create_gl_window(window: GliumWindowBuilder, events_loop: &EventsLoop, shared_context: Option<&Context>, renderer_type: RendererType)
 -> Result<CombinedContext, WindowCreateError>
 {
+    let hardware_acceleration = match renderer_type {
+        RendererType::Hardware => Some(true),
+        RendererType::Software => Some(false),
+        RendererType::Default => None,
+    };
+
     // The shared_context is reversed: If the shared_context is None, then this window is the root window,
     // so the window should be created with new_shared (so the context can be shared to all other windows).
     //
     // If the shared_context is Some() then the window is not a root window, so it should share the existing
     // context, but not re-share it (so, create it normally via ::new() instead of ::new_shared()).
 
-    CombinedContext::new(window.clone(), create_context_builder(true, true, shared_context),  &events_loop).or_else(|_|
-    CombinedContext::new(window.clone(), create_context_builder(true, false, shared_context), &events_loop)).or_else(|_|
-    CombinedContext::new(window.clone(), create_context_builder(false, true, shared_context), &events_loop)).or_else(|_|
-    CombinedContext::new(window.clone(), create_context_builder(false, false,shared_context), &events_loop))
+    CombinedContext::new(window.clone(), create_context_builder(true, true, hardware_acceleration, shared_context),  &events_loop).or_else(|_|
+    CombinedContext::new(window.clone(), create_context_builder(true, false, hardware_acceleration, shared_context), &events_loop)).or_else(|_|
+    CombinedContext::new(window.clone(), create_context_builder(false, true, hardware_acceleration, shared_context), &events_loop)).or_else(|_|
+    CombinedContext::new(window.clone(), create_context_builder(false, false, hardware_acceleration, shared_context), &events_loop))
     .map_err(|e| WindowCreateError::CreateError(e))
 }
 
@@ -859,9 +1956,13 @@ fn create_gl_window(window: GliumWindowBuilder, events_loop: &EventsLoop, shared
 /// `allow_sharing_context` should only be true for the root window - so that
 /// we can be sure the shared context can't be re-shared by the created window. Only
 /// the root window (via `FakeDisplay`) is allowed to manage the OpenGL context.
+///
+/// `hardware_acceleration` is `Some(false)` for the software renderer, `Some(true)`
+/// if the hardware renderer is forced and `None` if any driver is acceptable.
 fn create_context_builder<'a>(
     vsync: bool,
     srgb: bool,
+    hardware_acceleration: Option<bool>,
     shared_context: Option<&'a Context>,
 ) -> ContextBuilder<'a> {
 
@@ -890,7 +1991,7 @@ fn create_context_builder<'a>(
         builder = builder.with_srgb(true);
     }
 
-    builder
+    builder.with_hardware_acceleration(hardware_acceleration)
 }
 
 // This exists because RendererOptions isn't Clone-able
@@ -936,24 +2037,18 @@ fn create_renderer(
     let opts_native = get_renderer_opts(true, device_pixel_ratio as f32);
     let opts_osmesa = get_renderer_opts(false, device_pixel_ratio as f32);
 
-    let (renderer, sender) = match renderer_type {
-        Hardware => {
-            // force hardware renderer
-            Renderer::new(gl, notifier, opts_native, WR_SHADER_CACHE).unwrap()
-        },
-        Software => {
-            // force software renderer
-            Renderer::new(gl, notifier, opts_osmesa, WR_SHADER_CACHE).unwrap()
-        },
-        Default => {
-            // try hardware first, fall back to software
-            match Renderer::new(gl.clone(), notifier.clone(), opts_native, WR_SHADER_CACHE) {
-                Ok(r) => r,
-                Err(_) => Renderer::new(gl, notifier, opts_osmesa, WR_SHADER_CACHE).unwrap()
-            }
-        }
+    let opts = match renderer_type {
+        Hardware | Default => opts_native,
+        Software => opts_osmesa,
     };
 
+    let (renderer, sender) = Renderer::new(gl, notifier, opts, WR_SHADER_CACHE).map_err(|e| {
+        #[cfg(feature = "logging")] {
+            error!("Could not create {:?} renderer: {:?}", renderer_type, e);
+        }
+        WindowCreateError::Renderer
+    })?;
+
     let api = sender.create_api();
 
     Ok((renderer, api))
@@ -1036,4 +2131,145 @@ fn linux_get_hidpi_factor(monitor: &MonitorId, events_loop: &EventsLoop) -> f64
 
     let options = [winit_hidpi_factor, qt_font_dpi, gsettings_dpi_factor, xft_dpi];
     options.into_iter().filter_map(|x| *x).next().unwrap_or(winit_dpi)
-}
\ No newline at end of file
+}
+
+/// Sets the X11 input shape of the window to the (rounded) rectangle inside of the shadow
+/// margin, so that clicks on the shadow go to the windows below. Does nothing on Wayland
+/// or if libXext can't be loaded (the whole window stays clickable then).
+#[cfg(target_os = "linux")]
+fn linux_set_input_shape(window: &GliumWindow, window_size: (i32, i32), margin: i32, corner_radius: i32) {
+
+    use std::os::raw::{c_char, c_int, c_ulong, c_void};
+    use glium::glutin::os::unix::WindowExt;
+
+    #[repr(C)]
+    struct XRectangle { x: i16, y: i16, width: u16, height: u16 }
+
+    type XShapeCombineRectangles = unsafe extern "C" fn(
+        display: *mut c_void, window: c_ulong, dest_kind: c_int, x_off: c_int, y_off: c_int,
+        rectangles: *const XRectangle, n_rects: c_int, op: c_int, ordering: c_int,
+    );
+
+    extern "C" {
+        fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+
+    const RTLD_LAZY: c_int = 1;
+    const SHAPE_INPUT: c_int = 2;
+    const SHAPE_SET: c_int = 0;
+    const UNSORTED: c_int = 0;
+
+    lazy_static! {
+        // Address of XShapeCombineRectangles, loaded at runtime so that libXext isn't a hard dependency
+        static ref SHAPE_COMBINE_RECTANGLES: Option<usize> = unsafe {
+            let lib = dlopen(b"libXext.so.6\0".as_ptr() as *const c_char, RTLD_LAZY);
+            if lib.is_null() {
+                #[cfg(feature = "logging")] {
+                    warn!("Could not load libXext, the shadow of the window will not be click-through");
+                }
+                None
+            } else {
+                let symbol = dlsym(lib, b"XShapeCombineRectangles\0".as_ptr() as *const c_char);
+                if symbol.is_null() { None } else { Some(symbol as usize) }
+            }
+        };
+    }
+
+    let (display, xlib_window) = match (window.get_xlib_display(), window.get_xlib_window()) {
+        (Some(display), Some(xlib_window)) => (display, xlib_window),
+        _ => return,
+    };
+
+    let shape_combine_rectangles = match *SHAPE_COMBINE_RECTANGLES {
+        Some(address) => unsafe { mem::transmute::<usize, XShapeCombineRectangles>(address) },
+        None => return,
+    };
+
+    // XRectangles only have 16 bits per coordinate, so clamp instead of wrapping around
+    let clamp_position = |v: i32| v.max(i16::min_value() as i32).min(i16::max_value() as i32) as i16;
+    let clamp_size = |v: i32| v.max(0).min(u16::max_value() as i32) as u16;
+
+    let rectangles = get_rounded_input_shape(window_size, margin, corner_radius).into_iter()
+        .map(|(x, y, width, height)| XRectangle {
+            x: clamp_position(x),
+            y: clamp_position(y),
+            width: clamp_size(width),
+            height: clamp_size(height),
+        })
+        .collect::<Vec<_>>();
+
+    unsafe {
+        shape_combine_rectangles(
+            display, xlib_window, SHAPE_INPUT, 0, 0,
+            rectangles.as_ptr(), rectangles.len() as c_int, SHAPE_SET, UNSORTED,
+        );
+    }
+}
+
+/// Approximates the rounded rectangle inside of the shadow margin with
+/// `(x, y, width, height)` rectangles (in physical pixels): one rectangle
+/// per pixel row of the rounded corners and one for the straight middle part
+fn get_rounded_input_shape((width, height): (i32, i32), margin: i32, corner_radius: i32) -> Vec<(i32, i32, i32, i32)> {
+
+    let inner_width = (width - 2 * margin).max(0);
+    let inner_height = (height - 2 * margin).max(0);
+    let radius = corner_radius.min(inner_width / 2).min(inner_height / 2).max(0);
+
+    let mut rectangles = Vec::with_capacity(2 * radius as usize + 1);
+
+    for row in 0..radius {
+        let dy = radius as f32 - row as f32 - 0.5;
+        let inset = (radius as f32 - (radius as f32 * radius as f32 - dy * dy).sqrt()).round() as i32;
+        let row_width = inner_width - 2 * inset;
+        rectangles.push((margin + inset, margin + row, row_width, 1));
+        rectangles.push((margin + inset, margin + inner_height - 1 - row, row_width, 1));
+    }
+
+    rectangles.push((margin, margin + radius, inner_width, inner_height - 2 * radius));
+    rectangles
+}
+
+#[test]
+fn test_window_create_options_builder() {
+
+    let options = WindowCreateOptions::<()>::builder().title("Test").fullscreen(true).build().unwrap();
+    assert_eq!(options.state.title, "Test");
+    assert!(!options.state.has_decorations);
+
+    assert_eq!(
+        WindowCreateOptions::<()>::builder().fullscreen(true).decorations(true).build().err(),
+        Some(WindowCreateOptionsError::FullscreenWithDecorations),
+    );
+    let opaque_white = ColorU { r: 255, g: 255, b: 255, a: 255 };
+    assert_eq!(
+        WindowCreateOptions::<()>::builder().transparent(true).background_color(opaque_white).build().err(),
+        Some(WindowCreateOptionsError::TransparentWithOpaqueBackground(opaque_white)),
+    );
+    assert!(WindowCreateOptions::<()>::builder().transparent(true).background_color(ColorU::TRANSPARENT).build().is_ok());
+    assert_eq!(
+        WindowCreateOptions::<()>::builder().size(LogicalSize::new(100.0, 100.0)).min_size(LogicalSize::new(200.0, 50.0)).build().err(),
+        Some(WindowCreateOptionsError::SizeOutOfBounds(LogicalSize::new(100.0, 100.0))),
+    );
+    assert_eq!(
+        WindowCreateOptions::<()>::builder().window_shadow(WindowShadow::default()).build().err(),
+        Some(WindowCreateOptionsError::WindowShadowWithDecorations),
+    );
+    assert!(WindowCreateOptions::<()>::builder().window_shadow(WindowShadow::default()).decorations(false).build().is_ok());
+}
+
+#[test]
+fn test_rounded_input_shape() {
+    // No rounded corners: only the area inside of the margin
+    assert_eq!(get_rounded_input_shape((100, 80), 10, 0), vec![(10, 10, 80, 60)]);
+    // Maximized window (no margin): the whole window is clickable
+    assert_eq!(get_rounded_input_shape((100, 80), 0, 0), vec![(0, 0, 100, 80)]);
+
+    let shape = get_rounded_input_shape((100, 80), 10, 4);
+    assert_eq!(shape.len(), 9);
+    // The outermost rows are inset the most, the rows next to the middle part not at all
+    assert_eq!(shape[0], (12, 10, 76, 1));
+    assert_eq!(shape[1], (12, 69, 76, 1));
+    assert_eq!(shape[7], (10, 66, 80, 1));
+    assert_eq!(shape[8], (10, 14, 80, 52));
+}
@@ -1,14 +1,16 @@
 use std::{
-    collections::{HashSet, BTreeMap},
+    collections::{HashSet, BTreeMap, BTreeSet},
     path::PathBuf,
+    time::{Duration, Instant},
     fmt,
 };
 use glium::glutin::{
-    Window, WindowEvent, KeyboardInput, ScanCode, ElementState,
+    Window, WindowEvent, KeyboardInput, ScanCode, ElementState, MouseButton,
     MouseCursor, VirtualKeyCode, MouseScrollDelta, AxisId,
     ModifiersState, dpi::{LogicalPosition, LogicalSize},
 };
 use webrender::api::HitTestItem;
+use azul_css::{CssViewport, ColorU};
 use {
     app::FrameEventInfo,
     dom::{EventFilter, NotEventFilter, HoverEventFilter, FocusEventFilter, WindowEventFilter},
@@ -17,6 +19,8 @@ use {
     ui_state::UiState,
     callbacks::FocusTarget,
     app::AppState,
+    window::RendererType,
+    app_resources::ImageId,
 };
 
 const DEFAULT_TITLE: &str = "Azul App";
@@ -57,16 +61,62 @@ pub struct KeyboardState {
     /// Use when the physical location of the key is more important than the key's host GUI semantics,
     /// such as for movement controls in a first-person game (German keyboard: Z key, UK keyboard: Y key, etc.)
     pub current_scancodes: HashSet<ScanCode>,
+    /// Virtual keycodes that went down during this frame (key repeats are ignored)
+    pub pressed_virtual_keycodes: HashSet<VirtualKeyCode>,
+    /// Virtual keycodes that went up during this frame
+    pub released_virtual_keycodes: HashSet<VirtualKeyCode>,
+    /// Scancodes that went down during this frame (key repeats are ignored)
+    pub pressed_scancodes: HashSet<ScanCode>,
+    /// Scancodes that went up during this frame
+    pub released_scancodes: HashSet<ScanCode>,
 }
 
 impl KeyboardState {
 
+    /// Returns whether the key is currently held down
+    pub fn is_key_down(&self, key: VirtualKeyCode) -> bool {
+        self.current_virtual_keycodes.contains(&key)
+    }
+
+    /// Returns whether the key went down during this frame
+    pub fn is_key_pressed(&self, key: VirtualKeyCode) -> bool {
+        self.pressed_virtual_keycodes.contains(&key)
+    }
+
+    /// Returns whether the key went up during this frame
+    pub fn is_key_released(&self, key: VirtualKeyCode) -> bool {
+        self.released_virtual_keycodes.contains(&key)
+    }
+
+    /// Returns whether the physical key is currently held down, independent of the keyboard layout
+    pub fn is_scancode_down(&self, scancode: ScanCode) -> bool {
+        self.current_scancodes.contains(&scancode)
+    }
+
+    /// Returns whether the physical key went down during this frame
+    pub fn is_scancode_pressed(&self, scancode: ScanCode) -> bool {
+        self.pressed_scancodes.contains(&scancode)
+    }
+
+    /// Returns whether the physical key went up during this frame
+    pub fn is_scancode_released(&self, scancode: ScanCode) -> bool {
+        self.released_scancodes.contains(&scancode)
+    }
+
     fn update_from_modifier_state(&mut self, state: ModifiersState) {
         self.shift_down = state.shift;
         self.ctrl_down = state.ctrl;
         self.alt_down = state.alt;
         self.super_down = state.logo;
     }
+
+    /// Forgets which keys were pressed or released, called before the events of a new frame are processed
+    fn clear_frame_state(&mut self) {
+        self.pressed_virtual_keycodes.clear();
+        self.released_virtual_keycodes.clear();
+        self.pressed_scancodes.clear();
+        self.released_scancodes.clear();
+    }
 }
 
 /// Mouse position on the screen
@@ -111,6 +161,119 @@ impl Default for MouseState {
     }
 }
 
+/// Initial delay and repeat interval of `On::HoldRepeat`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HoldRepeatTiming {
+    /// How long the mouse button has to be held down before the first repeat
+    pub initial_delay: Duration,
+    /// Time between two repeats after the initial delay
+    pub interval: Duration,
+}
+
+impl Default for HoldRepeatTiming {
+    /// 500ms initial delay, 33ms interval (30 repeats per second)
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(500),
+            interval: Duration::from_millis(33),
+        }
+    }
+}
+
+lazy_static! {
+    static ref SYSTEM_HOLD_REPEAT_TIMING: HoldRepeatTiming = load_system_hold_repeat_timing();
+}
+
+impl HoldRepeatTiming {
+
+    /// Returns the key repeat settings of the operating system (queried only once), or
+    /// `HoldRepeatTiming::default()` if they can't be read
+    pub fn system() -> Self {
+        *SYSTEM_HOLD_REPEAT_TIMING
+    }
+
+    /// Returns how many times `On::HoldRepeat` should have fired (not counting the
+    /// initial press) after the mouse button has been held down for `held_down`
+    pub(crate) fn repeats_after(&self, held_down: Duration) -> u32 {
+        if held_down < self.initial_delay {
+            return 0;
+        }
+        let since_first_repeat = held_down - self.initial_delay;
+        let interval_ms = duration_to_millis(self.interval).max(1);
+        1 + (duration_to_millis(since_first_repeat) / interval_ms) as u32
+    }
+}
+
+fn duration_to_millis(duration: Duration) -> u64 {
+    duration.as_secs() * 1000 + u64::from(duration.subsec_millis())
+}
+
+#[cfg(test)]
+fn load_system_hold_repeat_timing() -> HoldRepeatTiming {
+    HoldRepeatTiming::default()
+}
+
+// Executes "gsettings get org.gnome.desktop.peripherals.keyboard delay" (and "repeat-interval")
+#[cfg(all(target_os = "linux", not(test)))]
+fn load_system_hold_repeat_timing() -> HoldRepeatTiming {
+    let default = HoldRepeatTiming::default();
+    let initial_delay = read_system_setting("gsettings", &["get", "org.gnome.desktop.peripherals.keyboard", "delay"]);
+    let interval = read_system_setting("gsettings", &["get", "org.gnome.desktop.peripherals.keyboard", "repeat-interval"]);
+    HoldRepeatTiming {
+        initial_delay: initial_delay.map(Duration::from_millis).unwrap_or(default.initial_delay),
+        interval: interval.map(Duration::from_millis).unwrap_or(default.interval),
+    }
+}
+
+// Reads "KeyboardDelay" (0 - 3, in 250ms steps) and "KeyboardSpeed" (0 - 31, from 2.5 to
+// 30 repeats per second) from "HKEY_CURRENT_USER\Control Panel\Keyboard"
+#[cfg(all(target_os = "windows", not(test)))]
+fn load_system_hold_repeat_timing() -> HoldRepeatTiming {
+    let default = HoldRepeatTiming::default();
+    let initial_delay = read_system_setting("reg", &["query", "HKCU\\Control Panel\\Keyboard", "/v", "KeyboardDelay"]);
+    let speed = read_system_setting("reg", &["query", "HKCU\\Control Panel\\Keyboard", "/v", "KeyboardSpeed"]);
+    HoldRepeatTiming {
+        initial_delay: initial_delay.map(|delay| Duration::from_millis((delay.min(3) + 1) * 250)).unwrap_or(default.initial_delay),
+        interval: speed.map(|speed| {
+            let repeats_per_second = 2.5 + speed.min(31) as f64 * (27.5 / 31.0);
+            Duration::from_millis((1000.0 / repeats_per_second) as u64)
+        }).unwrap_or(default.interval),
+    }
+}
+
+// Executes "defaults read -g InitialKeyRepeat" (and "KeyRepeat"), both are in units of 15ms
+#[cfg(all(target_os = "macos", not(test)))]
+fn load_system_hold_repeat_timing() -> HoldRepeatTiming {
+    let default = HoldRepeatTiming::default();
+    let initial_delay = read_system_setting("defaults", &["read", "-g", "InitialKeyRepeat"]);
+    let interval = read_system_setting("defaults", &["read", "-g", "KeyRepeat"]);
+    HoldRepeatTiming {
+        initial_delay: initial_delay.map(|delay| Duration::from_millis(delay * 15)).unwrap_or(default.initial_delay),
+        interval: interval.map(|interval| Duration::from_millis(interval.max(1) * 15)).unwrap_or(default.interval),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos", test)))]
+fn load_system_hold_repeat_timing() -> HoldRepeatTiming {
+    HoldRepeatTiming::default()
+}
+
+#[cfg(all(any(target_os = "linux", target_os = "windows", target_os = "macos"), not(test)))]
+fn read_system_setting(command: &str, args: &[&str]) -> Option<u64> {
+    use std::process::Command;
+    let output = Command::new(command).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_system_setting(&String::from_utf8(output.stdout).ok()?)
+}
+
+/// Parses the last number of a command output, i.e. "uint32 500" (gsettings),
+/// "KeyboardDelay    REG_SZ    1" (reg) or "25" (defaults)
+fn parse_system_setting(output: &str) -> Option<u64> {
+    output.split_whitespace().last()?.trim_matches('\'').parse().ok()
+}
+
 /// Toggles webrender debug flags (will make stuff appear on
 /// the screen that you might not want to - used for debugging purposes)
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -141,6 +304,19 @@ pub struct DebugState {
     pub show_overdraw: bool,
     /// Toggles `webrender::DebugFlags::GPU_CACHE_DBG`
     pub gpu_cache_dbg: bool,
+    /// Not a webrender flag: outlines every node that reacts to mouse clicks, but can't be
+    /// focused with the tab key (red), and numbers the focusable nodes in their tab order
+    /// (blue). The unreachable nodes are also logged as warnings.
+    pub keyboard_audit: bool,
+    /// Not a webrender flag: after every layout, writes the layout of the window to
+    /// `layout_debug.json` (in the working directory) - for every node the CSS properties
+    /// that affect the layout, the content size, the size before and the space added by the
+    /// flex-grow step and the final rectangle, plus hints why a node collapsed to 0px.
+    /// With several windows, the file contains the window that was laid out last.
+    pub layout_debug: bool,
+    /// (Read-only) The renderer that is actually in use - either `Hardware` or `Software`,
+    /// filled out by `App::new`. Changing this field has no effect.
+    pub active_renderer: Option<RendererType>,
 }
 
 impl Default for DebugState {
@@ -159,6 +335,9 @@ impl Default for DebugState {
             new_scene_indicator: false,
             show_overdraw: false,
             gpu_cache_dbg: false,
+            keyboard_audit: false,
+            layout_debug: false,
+            active_renderer: None,
         }
     }
 }
@@ -184,6 +363,50 @@ pub(crate) struct CrateInternalWindowState {
     pub(crate) pending_focus_target: Option<FocusTarget>,
     /// What the last motion was in case a controller was used.
     pub(crate) last_motion: Option<(AxisId, f64)>,
+    /// Position that the mouse cursor should be warped to in the next frame,
+    /// set via `WindowState::set_cursor_position()`
+    pub(crate) pending_cursor_position: Option<LogicalPosition>,
+    /// The left mouse button is held down over nodes with `On::LongPress` or `On::HoldRepeat` callbacks
+    pub(crate) held_press: Option<HeldPress>,
+}
+
+/// Tracks how long the left mouse button has been held down, in order to fire the
+/// time-based `On::LongPress` and `On::HoldRepeat` events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HeldPress {
+    /// Nodes that the button was pressed on and that are still hovered
+    pub(crate) nodes: BTreeSet<NodeId>,
+    /// When the button was pressed
+    pub(crate) started: Instant,
+    /// How long the button was held down when the events were last determined
+    pub(crate) last_checked: Duration,
+    /// How many `On::HoldRepeat` events have fired, not counting the initial press
+    pub(crate) repeats: u32,
+}
+
+impl HeldPress {
+
+    /// Returns the time-based events that are due since the last check
+    /// (the `long_press_durations` are the ones registered on the pressed nodes)
+    /// and advances the press to `held_down`
+    pub(crate) fn advance(&mut self, held_down: Duration, timing: &HoldRepeatTiming, long_press_durations: &BTreeSet<Duration>)
+    -> Vec<HoverEventFilter>
+    {
+        let mut events = long_press_durations.iter()
+            .filter(|duration| **duration > self.last_checked && **duration <= held_down)
+            .map(|duration| HoverEventFilter::LongPress(*duration))
+            .collect::<Vec<_>>();
+
+        // If frames were dropped, only fire one repeat instead of catching up
+        let repeats = timing.repeats_after(held_down);
+        if repeats > self.repeats {
+            events.push(HoverEventFilter::HoldRepeat);
+            self.repeats = repeats;
+        }
+
+        self.last_checked = held_down;
+        events
+    }
 }
 
 impl Default for CrateInternalWindowState {
@@ -197,6 +420,8 @@ impl Default for CrateInternalWindowState {
             previous_window_state: None,
             pending_focus_target: None,
             last_motion: None,
+            pending_cursor_position: None,
+            held_press: None,
         }
     }
 }
@@ -225,6 +450,41 @@ pub struct WindowState {
     pub is_visible: bool,
     /// Is the window always on top?
     pub is_always_on_top: bool,
+    /// Can the user resize the window? Default: true
+    pub is_resizable: bool,
+    /// Color that the window is cleared with before the DOM is drawn, `None` uses
+    /// the `AppConfig::background_color`. Note that whether the window is transparent
+    /// is only decided once, when the window is created. Default: None
+    pub background_color: Option<ColorU>,
+    /// Image that is stretched over the whole window and drawn before the DOM, so
+    /// that no root div is needed to paint the background. Default: None
+    pub background_image: Option<ImageId>,
+    /// Restricts the movement of the mouse cursor, see `CursorConfinement`.
+    /// Default: `CursorConfinement::None`
+    pub cursor_confinement: CursorConfinement,
+    /// Initial delay and interval of `On::HoldRepeat`.
+    /// Default: `HoldRepeatTiming::system()`
+    pub hold_repeat: HoldRepeatTiming,
+}
+
+/// Restricts where the mouse cursor can move while the window is focused,
+/// for example for sliders with infinite drag or for rotating a 3D viewport.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CursorConfinement {
+    /// The cursor can move freely (default)
+    None,
+    /// The cursor can't leave the window
+    Window,
+    /// The cursor can't leave the bounds of the given node, as they were
+    /// calculated in the last layout. Note that the `NodeId` refers to the DOM
+    /// of the current frame, so it has to be updated if the DOM changes.
+    Node(NodeId),
+}
+
+impl Default for CursorConfinement {
+    fn default() -> Self {
+        CursorConfinement::None
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -240,6 +500,47 @@ pub struct WindowSize {
     pub min_dimensions: Option<LogicalSize>,
     /// Maximum dimensions of the window
     pub max_dimensions: Option<LogicalSize>,
+    /// If set, the minimum dimensions of the window are updated every frame to the
+    /// smallest size that the DOM can be laid out at (as determined by the `width`,
+    /// `min-width`, `height` and `min-height` constraints, paddings and margins), so
+    /// that the window can't be shrunk into a broken layout. `min_dimensions` is
+    /// still respected as a lower bound. Has no effect if `scale_to_fit` is set. Default: false
+    pub min_dimensions_from_layout: bool,
+    /// "Kiosk mode": If set, the UI is always laid out at this fixed resolution and
+    /// then uniformly scaled to fit the window, with empty bars on the sides
+    /// (letterboxing) if the aspect ratio of the window doesn't match. Default: None
+    pub scale_to_fit: Option<LogicalSize>,
+    /// How a fractional `hidpi_factor` (i.e. `1.25` or `1.5`) of the monitor that the window
+    /// is currently on should be handled when rendering. Default: `HidpiRoundingPolicy::Fractional`
+    pub hidpi_rounding: HidpiRoundingPolicy,
+    /// (Read-only, set by azul): Space on each side of the window that is reserved for the
+    /// `WindowCreateOptions::window_shadow`, in layout units. Zero if the window has no
+    /// shadow or is maximized / fullscreen. The UI is laid out inside of this margin.
+    pub shadow_margin: f64,
+}
+
+/// Controls how the frame is rendered on monitors with a fractional DPI factor, where
+/// one CSS pixel doesn't map to a whole number of device pixels. Has no effect on
+/// monitors with an integer DPI factor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HidpiRoundingPolicy {
+    /// Render at the fractional DPI factor, edges that don't fall on a device pixel
+    /// are anti-aliased (blurry hairlines)
+    Fractional,
+    /// Render at the fractional DPI factor, but round the edges of all rectangles to
+    /// whole device pixels, so that borders and hairlines stay sharp. Rectangles may
+    /// be up to one device pixel larger or smaller than their layouted size.
+    SnapToDevicePixels,
+    /// Render the frame at the next integer DPI factor (i.e. `2.0` for `1.5`) and then
+    /// downscale it to the window. Keeps the proportions of the UI consistent with
+    /// integer-scaled monitors, at the cost of a larger framebuffer and softer text.
+    IntegerScale,
+}
+
+impl Default for HidpiRoundingPolicy {
+    fn default() -> Self {
+        HidpiRoundingPolicy::Fractional
+    }
 }
 
 impl WindowSize {
@@ -256,6 +557,78 @@ impl WindowSize {
             self.dimensions.height / self.hidpi_factor * self.winit_hidpi_factor,
         )
     }
+
+    /// Returns the size of the window without the `shadow_margin`, in layout units
+    pub fn get_content_size(&self) -> LogicalSize {
+        let window_size = self.get_reverse_logical_size();
+        LogicalSize::new(
+            (window_size.width - 2.0 * self.shadow_margin).max(0.0),
+            (window_size.height - 2.0 * self.shadow_margin).max(0.0),
+        )
+    }
+
+    /// Returns the size that the UI should be laid out at - either the window size
+    /// (without the `shadow_margin`) or, if `scale_to_fit` is set, the fixed design resolution
+    pub fn get_layout_size(&self) -> LogicalSize {
+        self.scale_to_fit.unwrap_or_else(|| self.get_content_size())
+    }
+
+    /// If `scale_to_fit` is set, returns the uniform scale factor and the offset
+    /// (in logical pixels) that centers the scaled UI in the window
+    pub fn get_scale_to_fit_transform(&self) -> Option<(f64, LogicalPosition)> {
+        let design_size = self.scale_to_fit?;
+        if design_size.width <= 0.0 || design_size.height <= 0.0 {
+            return None;
+        }
+        let window_size = self.get_content_size();
+        let scale = (window_size.width / design_size.width).min(window_size.height / design_size.height);
+        let offset = LogicalPosition::new(
+            (window_size.width - design_size.width * scale) / 2.0,
+            (window_size.height - design_size.height * scale) / 2.0,
+        );
+        Some((scale, offset))
+    }
+
+    /// Returns the scale factor and offset of the UI in the window if the UI isn't drawn
+    /// 1:1 at the top left corner - either because of `scale_to_fit` or because the UI
+    /// is moved inside of the `shadow_margin`
+    pub(crate) fn get_content_transform(&self) -> Option<(f64, LogicalPosition)> {
+        let margin = self.shadow_margin;
+        match self.get_scale_to_fit_transform() {
+            Some((scale, offset)) => Some((scale, LogicalPosition::new(offset.x + margin, offset.y + margin))),
+            None if margin > 0.0 => Some((1.0, LogicalPosition::new(margin, margin))),
+            None => None,
+        }
+    }
+
+    /// Converts a size in layout units (i.e. the `min_content_size` of a layout) to the logical
+    /// window size that is necessary to lay out the UI at that size
+    pub(crate) fn layout_size_to_window_size(&self, layout_size: LogicalSize) -> LogicalSize {
+        LogicalSize::new(
+            (layout_size.width + 2.0 * self.shadow_margin) * self.hidpi_factor / self.winit_hidpi_factor,
+            (layout_size.height + 2.0 * self.shadow_margin) * self.hidpi_factor / self.winit_hidpi_factor,
+        )
+    }
+
+    /// Returns the DPI factor that the frame is rendered at, which differs
+    /// from the `hidpi_factor` if the `HidpiRoundingPolicy::IntegerScale` is used
+    pub(crate) fn get_render_hidpi_factor(&self) -> f64 {
+        match self.hidpi_rounding {
+            HidpiRoundingPolicy::Fractional |
+            HidpiRoundingPolicy::SnapToDevicePixels => self.hidpi_factor,
+            HidpiRoundingPolicy::IntegerScale => self.hidpi_factor.ceil().max(1.0),
+        }
+    }
+
+    /// Returns the size and DPI that `@media` queries are evaluated against
+    pub(crate) fn get_css_viewport(&self) -> CssViewport {
+        let layout_size = self.get_layout_size();
+        CssViewport {
+            width: layout_size.width as f32,
+            height: layout_size.height as f32,
+            hidpi_factor: self.hidpi_factor as f32,
+        }
+    }
 }
 
 impl Default for WindowSize {
@@ -266,6 +639,10 @@ impl Default for WindowSize {
             winit_hidpi_factor: 1.0,
             min_dimensions: None,
             max_dimensions: None,
+            min_dimensions_from_layout: false,
+            scale_to_fit: None,
+            hidpi_rounding: HidpiRoundingPolicy::default(),
+            shadow_margin: 0.0,
         }
     }
 }
@@ -282,7 +659,12 @@ impl Default for WindowState {
             has_decorations: true,
             is_visible: true,
             is_always_on_top: false,
+            is_resizable: true,
             debug_state: DebugState::default(),
+            background_color: None,
+            background_image: None,
+            cursor_confinement: CursorConfinement::default(),
+            hold_repeat: HoldRepeatTiming::system(),
         }
     }
 }
@@ -370,6 +752,14 @@ impl WindowState
         self.internal.previous_window_state.as_ref()
     }
 
+    /// Moves the mouse cursor to the given position (in the same coordinate
+    /// space as `MouseState::cursor_pos`) at the start of the next frame.
+    /// Useful for sliders with infinite drag, where the cursor is reset to
+    /// the center of the slider after every movement.
+    pub fn set_cursor_position(&mut self, position: LogicalPosition) {
+        self.internal.pending_cursor_position = Some(position);
+    }
+
     /// Determine which event / which callback(s) should be called and in which order
     ///
     /// This function also updates / mutates the current window state, so that
@@ -381,8 +771,6 @@ impl WindowState
         ui_state: &UiState<T>
     ) -> CallbacksOfHitTest<T>
     {
-        use std::collections::BTreeSet;
-
         // Store the current window state so we can set it in this.previous_window_state later on
         let mut previous_state = Box::new(self.clone());
         previous_state.internal.previous_window_state = None;
@@ -511,6 +899,44 @@ impl WindowState
             insert_callbacks!(current_focused_node, None, focus_callbacks, focus_default_callbacks, current_focus_events, Focus);
         }
 
+        // Fire On::LongPress and On::HoldRepeat on the nodes that the left mouse button is held down on
+        let event_was_left_mouse_down = if let WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } = event { true } else { false };
+        let event_was_left_mouse_release = if let WindowEvent::MouseInput { state: ElementState::Released, button: MouseButton::Left, .. } = event { true } else { false };
+        let mut held_press_events = Vec::<HoverEventFilter>::new();
+
+        if event_was_left_mouse_down {
+            let nodes = new_hit_node_ids.keys()
+                .filter(|node_id| has_held_press_callbacks(ui_state, **node_id))
+                .cloned()
+                .collect::<BTreeSet<_>>();
+            self.internal.held_press = if nodes.is_empty() {
+                None
+            } else {
+                held_press_events.push(HoverEventFilter::HoldRepeat);
+                Some(HeldPress { nodes, started: Instant::now(), last_checked: Duration::from_secs(0), repeats: 0 })
+            };
+        } else if event_was_left_mouse_release || event_was_mouse_leave {
+            self.internal.held_press = None;
+        } else if let Some(mut held_press) = self.internal.held_press.take() {
+            // Moving the cursor off a node cancels the press on that node
+            held_press.nodes.retain(|node_id| new_hit_node_ids.contains_key(node_id));
+            if !held_press.nodes.is_empty() {
+                let long_press_durations = held_press.nodes.iter()
+                    .flat_map(|node_id| get_hover_event_filters(ui_state, *node_id))
+                    .filter_map(|filter| match filter { HoverEventFilter::LongPress(duration) => Some(*duration), _ => None })
+                    .collect::<BTreeSet<_>>();
+                let held_down = held_press.started.elapsed();
+                held_press_events = held_press.advance(held_down, &self.hold_repeat, &long_press_durations);
+                self.internal.held_press = Some(held_press);
+            }
+        }
+
+        if let Some(held_press) = &self.internal.held_press {
+            for node_id in &held_press.nodes {
+                insert_callbacks!(node_id, new_hit_node_ids.get(node_id).cloned(), hover_callbacks, hover_default_callbacks, held_press_events, Hover);
+            }
+        }
+
         // If the last focused node and the current focused node aren't the same,
         // submit a FocusLost for the last node and a FocusReceived for the current one.
         let mut focus_received_lost_events: BTreeMap<NodeId, FocusEventFilter> = BTreeMap::new();
@@ -709,6 +1135,8 @@ impl WindowState
         let mut frame_event_info = FrameEventInfo::default();
         let mut should_window_close = false;
 
+        self.internal.keyboard_state.clear_frame_state();
+
         for event in events {
             if window_should_close(event, &mut frame_event_info) {
                 should_window_close = true;
@@ -782,11 +1210,17 @@ impl WindowState
             WindowEvent::KeyboardInput {
                 input: KeyboardInput { state: ElementState::Pressed, virtual_keycode, scancode, .. }, ..
             } => {
+                let keyboard_state = &mut self.internal.keyboard_state;
                 if let Some(vk) = virtual_keycode {
-                    self.internal.keyboard_state.current_virtual_keycodes.insert(*vk);
-                    self.internal.keyboard_state.latest_virtual_keycode = Some(*vk);
+                    // insert() returns false if the key was already down (key repeat)
+                    if keyboard_state.current_virtual_keycodes.insert(*vk) {
+                        keyboard_state.pressed_virtual_keycodes.insert(*vk);
+                    }
+                    keyboard_state.latest_virtual_keycode = Some(*vk);
+                }
+                if keyboard_state.current_scancodes.insert(*scancode) {
+                    keyboard_state.pressed_scancodes.insert(*scancode);
                 }
-                self.internal.keyboard_state.current_scancodes.insert(*scancode);
             },
             // The char event is sliced inbetween a keydown and a keyup event
             // so the keyup has to clear the character again
@@ -796,17 +1230,24 @@ impl WindowState
             WindowEvent::KeyboardInput {
                 input: KeyboardInput { state: ElementState::Released, virtual_keycode, scancode, .. }, ..
             } => {
+                let keyboard_state = &mut self.internal.keyboard_state;
                 if let Some(vk) = virtual_keycode {
-                    self.internal.keyboard_state.current_virtual_keycodes.remove(vk);
-                    self.internal.keyboard_state.latest_virtual_keycode = None;
+                    if keyboard_state.current_virtual_keycodes.remove(vk) {
+                        keyboard_state.released_virtual_keycodes.insert(*vk);
+                    }
+                    keyboard_state.latest_virtual_keycode = None;
+                }
+                if keyboard_state.current_scancodes.remove(scancode) {
+                    keyboard_state.released_scancodes.insert(*scancode);
                 }
-                self.internal.keyboard_state.current_scancodes.remove(scancode);
             },
             WindowEvent::Focused(false) => {
-                self.internal.keyboard_state.current_char = None;
-                self.internal.keyboard_state.current_virtual_keycodes.clear();
-                self.internal.keyboard_state.latest_virtual_keycode = None;
-                self.internal.keyboard_state.current_scancodes.clear();
+                // The key-up events are lost when the window isn't focused, so all keys are released
+                let keyboard_state = &mut self.internal.keyboard_state;
+                keyboard_state.current_char = None;
+                keyboard_state.released_virtual_keycodes.extend(keyboard_state.current_virtual_keycodes.drain());
+                keyboard_state.latest_virtual_keycode = None;
+                keyboard_state.released_scancodes.extend(keyboard_state.current_scancodes.drain());
             },
             _ => { },
         }
@@ -912,6 +1353,19 @@ fn get_window_events(window_state: &mut WindowState, event: &WindowEvent) -> Has
     events_vec
 }
 
+/// Returns the filters of all (normal and default) hover callbacks of a node
+fn get_hover_event_filters<'a, T>(ui_state: &'a UiState<T>, node_id: NodeId) -> impl Iterator<Item=&'a HoverEventFilter> + 'a {
+    ui_state.hover_callbacks.get(&node_id).into_iter().flat_map(|callbacks| callbacks.keys())
+        .chain(ui_state.hover_default_callbacks.get(&node_id).into_iter().flat_map(|callbacks| callbacks.keys()))
+}
+
+fn has_held_press_callbacks<T>(ui_state: &UiState<T>, node_id: NodeId) -> bool {
+    get_hover_event_filters(ui_state, node_id).any(|filter| match filter {
+        HoverEventFilter::LongPress(_) | HoverEventFilter::HoldRepeat => true,
+        _ => false,
+    })
+}
+
 fn get_hover_events(input: &HashSet<WindowEventFilter>) -> HashSet<HoverEventFilter> {
     input.iter().filter_map(|window_event| window_event.to_hover_event_filter()).collect()
 }
@@ -940,8 +1394,13 @@ pub(crate) fn window_should_close(event: &WindowEvent, frame_event_info: &mut Fr
             frame_event_info.is_resize_event = true;
             frame_event_info.should_redraw_window = true;
         },
+        WindowEvent::Moved(position) => {
+            frame_event_info.new_window_position = Some(*position);
+        },
         WindowEvent::HiDpiFactorChanged(dpi) => {
             frame_event_info.new_dpi_factor = Some(*dpi);
+            // The physical size changed and `@media` queries have to be re-evaluated
+            frame_event_info.is_resize_event = true;
             frame_event_info.should_redraw_window = true;
         },
         WindowEvent::CloseRequested | WindowEvent::Destroyed => {
@@ -956,6 +1415,11 @@ pub(crate) fn window_should_close(event: &WindowEvent, frame_event_info: &mut Fr
         WindowEvent::Touch(_) => {
             frame_event_info.should_hittest = true;
         },
+        // The OS refresh events are filtered out, this is only sent to
+        // check the `On::LongPress` and `On::HoldRepeat` events
+        WindowEvent::Refresh => {
+            frame_event_info.should_hittest = true;
+        },
         _ => { },
     }
 
@@ -1029,4 +1493,41 @@ pub fn keymap<T>(
         })
         .next()
         .and_then(|(_, callback)| (callback)(app_state, event))
-}
\ No newline at end of file
+}
+#[test]
+fn test_parse_system_setting() {
+    assert_eq!(parse_system_setting("uint32 500\n"), Some(500));
+    assert_eq!(parse_system_setting("\r\n    KeyboardDelay    REG_SZ    1\r\n\r\n"), Some(1));
+    assert_eq!(parse_system_setting("25\n"), Some(25));
+    assert_eq!(parse_system_setting(""), None);
+}
+
+#[test]
+fn test_held_press_events() {
+
+    let timing = HoldRepeatTiming {
+        initial_delay: Duration::from_millis(500),
+        interval: Duration::from_millis(100),
+    };
+
+    let mut long_press_durations = BTreeSet::new();
+    long_press_durations.insert(Duration::from_millis(600));
+
+    let mut held_press = HeldPress {
+        nodes: BTreeSet::new(),
+        started: Instant::now(),
+        last_checked: Duration::from_secs(0),
+        repeats: 0,
+    };
+
+    assert!(held_press.advance(Duration::from_millis(400), &timing, &long_press_durations).is_empty());
+    assert_eq!(held_press.advance(Duration::from_millis(500), &timing, &long_press_durations), vec![HoverEventFilter::HoldRepeat]);
+    assert!(held_press.advance(Duration::from_millis(550), &timing, &long_press_durations).is_empty());
+    assert_eq!(
+        held_press.advance(Duration::from_millis(620), &timing, &long_press_durations),
+        vec![HoverEventFilter::LongPress(Duration::from_millis(600)), HoverEventFilter::HoldRepeat],
+    );
+    // Dropped frames only fire one repeat
+    assert_eq!(held_press.advance(Duration::from_millis(1000), &timing, &long_press_durations), vec![HoverEventFilter::HoldRepeat]);
+    assert_eq!(held_press.repeats, 6);
+}
@@ -497,30 +497,23 @@ fn parse_component_arguments(input: &str) -> Result<ComponentArguments, Componen
 
 pub type FilteredComponentArguments = ComponentArguments;
 
-/// Filters the XML attributes of a component given XmlAttributeMap
-fn validate_and_filter_component_args(xml_attributes: &XmlAttributeMap, valid_args: &FilteredComponentArguments)
+/// Filters the XML attributes of a component given XmlAttributeMap: returns the
+/// values of the attributes that are arguments of the component (`valid_args`)
+fn validate_and_filter_component_args(xml_attributes: &XmlAttributeMap, valid_args: &ComponentArguments)
 -> Result<FilteredComponentArguments, RenderDomError> {
 
-    const DEFAULT_ARGS: [&str;5] = ["id", "class", "tabindex", "draggable", "focusable"];
+    const DEFAULT_ARGS: [&str;7] = ["id", "class", "tabindex", "draggable", "focusable", "disabled", "checked"];
 
     let mut map = FilteredComponentArguments::default();
 
     for (xml_attribute_name, xml_attribute_value) in xml_attributes.iter() {
 
-        let arg_value = match valid_args.get(xml_attribute_name) {
-            Some(s) => Some(s),
-            None => {
-                if DEFAULT_ARGS.contains(&xml_attribute_name.as_str()) {
-                    None // no error, but don't insert the attribute name
-                } else {
-                    let keys = valid_args.keys().cloned().collect();
-                    return Err(RenderDomError::UselessFunctionArgument(xml_attribute_name.clone(), xml_attribute_value.clone(), keys));
-                }
-            }
-        };
-
-        if let Some(value) = arg_value {
-            map.insert(xml_attribute_name.clone(), value.clone());
+        if valid_args.contains_key(xml_attribute_name) {
+            map.insert(xml_attribute_name.clone(), xml_attribute_value.clone());
+        } else if !DEFAULT_ARGS.contains(&xml_attribute_name.as_str()) {
+            // The default arguments aren't an error, but they are set by `set_attributes` instead
+            let keys = valid_args.keys().cloned().collect();
+            return Err(RenderDomError::UselessFunctionArgument(xml_attribute_name.clone(), xml_attribute_value.clone(), keys));
         }
     }
 
@@ -717,6 +710,20 @@ fn set_attributes<T>(dom: &mut Dom<T>, xml_attributes: &XmlAttributeMap, filtere
         dom.set_draggable(drag);
     }
 
+    if let Some(disabled) = xml_attributes.get("disabled")
+        .map(|d| format_args_dynamic(d, &filtered_xml_attributes))
+        .and_then(|d| parse_bool(&d))
+    {
+        dom.set_disabled(disabled);
+    }
+
+    if let Some(checked) = xml_attributes.get("checked")
+        .map(|c| format_args_dynamic(c, &filtered_xml_attributes))
+        .and_then(|c| parse_bool(&c))
+    {
+        dom.set_checked(checked);
+    }
+
     if let Some(focusable) = xml_attributes.get("focusable")
         .map(|f| format_args_dynamic(f, &filtered_xml_attributes))
         .and_then(|f| parse_bool(&f))
@@ -869,6 +876,14 @@ fn render_single_dom_node_to_string<T>(dom: &Dom<T>, existing_str: &mut String)
     if head.is_draggable {
         *existing_str += ".is_draggable(true)";
     }
+
+    if head.is_disabled {
+        *existing_str += ".is_disabled(true)";
+    }
+
+    if head.is_checked {
+        *existing_str += ".is_checked(true)";
+    }
 }
 
 #[test]
@@ -1080,6 +1095,25 @@ fn test_parse_component_arguments() {
     );
 }
 
+#[test]
+fn test_validate_and_filter_component_args() {
+
+    let mut valid_args = ComponentArguments::new();
+    valid_args.insert("title".to_string(), "String".to_string());
+
+    let mut xml_attributes = XmlAttributeMap::new();
+    xml_attributes.insert("title".to_string(), "Hello".to_string());
+    xml_attributes.insert("class".to_string(), "header".to_string());
+
+    // The component gets the value of the attribute, not the type of the argument
+    let mut expected = FilteredComponentArguments::new();
+    expected.insert("title".to_string(), "Hello".to_string());
+    assert_eq!(validate_and_filter_component_args(&xml_attributes, &valid_args), Ok(expected));
+
+    xml_attributes.insert("subtitle".to_string(), "World".to_string());
+    assert!(validate_and_filter_component_args(&xml_attributes, &valid_args).is_err());
+}
+
 #[test]
 fn test_xml_get_item() {
 
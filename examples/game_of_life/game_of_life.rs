@@ -195,8 +195,10 @@ fn main() {
         game_is_running: false,
     }, AppConfig::default()).unwrap();
 
-    let mut window_options = WindowCreateOptions::default();
-    window_options.state.title = "Game of Life".into();
+    let window_options = WindowCreateOptions::builder()
+        .title("Game of Life")
+        .build()
+        .unwrap();
 
     let css = css::override_native(CSS).unwrap();
     let window = app.create_window(window_options, css).unwrap();
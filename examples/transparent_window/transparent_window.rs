@@ -13,6 +13,11 @@ impl Layout for MyDataModel {
 
 fn main() {
     let mut app = App::new(MyDataModel { }, AppConfig::default()).unwrap();
-    let window = app.create_window(WindowCreateOptions::default(), css::native()).unwrap();
+    let window_options = WindowCreateOptions::builder()
+        .transparent(true)
+        .background_color(ColorU::TRANSPARENT)
+        .build()
+        .unwrap();
+    let window = app.create_window(window_options, css::native()).unwrap();
     app.run(window).unwrap();
 }
\ No newline at end of file